@@ -1,6 +1,7 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 
 use std::{
+    collections::BTreeMap,
     fmt::{Debug, Display, Formatter},
     hash::Hash,
     ops::Range,
@@ -30,7 +31,6 @@ pub struct GenericBinding {
 }
 
 #[derive(PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Pou {
     pub id: AstId,
     pub name: String,
@@ -55,7 +55,6 @@ pub struct Pou {
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Interface {
     pub id: AstId,
     pub ident: Identifier,
@@ -66,7 +65,6 @@ pub struct Interface {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Identifier {
     pub name: String,
     pub location: SourceLocation,
@@ -74,7 +72,6 @@ pub struct Identifier {
 
 /// The property container as a whole, which contains [`PropertyImplementation`]s
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct PropertyBlock {
     pub ident: Identifier,
     pub implementations: Vec<PropertyImplementation>,
@@ -89,7 +86,6 @@ impl Hash for PropertyBlock {
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct PropertyImplementation {
     pub kind: PropertyKind,
     pub datatype: DataTypeDeclaration,
@@ -322,7 +318,6 @@ impl Pou {
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Implementation {
     pub name: String,
     pub type_name: String,
@@ -458,7 +453,6 @@ impl PouType {
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct ConfigVariable {
     pub reference: AstNode,
     pub data_type: DataTypeDeclaration,
@@ -478,7 +472,6 @@ impl ConfigVariable {
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct CompilationUnit {
     pub global_vars: Vec<VariableBlock>,
     pub var_config: Vec<ConfigVariable>,
@@ -490,6 +483,13 @@ pub struct CompilationUnit {
     pub user_types: Vec<UserTypeDeclaration>,
     pub file: FileMarker,
     pub linkage: LinkageType,
+    /// Comments and pragmas the parser does not interpret, attached to the declarations and
+    /// statements around them
+    pub trivia: AttachedTrivia,
+    /// The doc comments attached to the declarations of this unit
+    pub doc_comments: Vec<DocComment>,
+    /// The `{attribute ...}` pragmas attached to the declarations of this unit
+    pub attributes: Vec<Attribute>,
 }
 
 impl CompilationUnit {
@@ -503,6 +503,9 @@ impl CompilationUnit {
             user_types: Vec::new(),
             file: FileMarker::File(file_name),
             linkage: LinkageType::Internal,
+            trivia: AttachedTrivia::default(),
+            doc_comments: Vec::new(),
+            attributes: Vec::new(),
         }
     }
 
@@ -527,6 +530,194 @@ impl CompilationUnit {
         self.pous.extend(other.pous);
         self.implementations.extend(other.implementations);
         self.user_types.extend(other.user_types);
+        self.doc_comments.extend(other.doc_comments);
+        self.attributes.extend(other.attributes);
+    }
+
+    /// Returns the documentation of the declaration whose name is at the given location
+    pub fn get_doc_comment(&self, location: &SourceLocation) -> Option<&str> {
+        self.doc_comments.iter().find(|it| &it.location == location).map(|it| it.text.as_str())
+    }
+
+    /// Returns the value of the attribute with the given name attached to the declaration whose name
+    /// is at the given location, `Some(None)` if the attribute has no value
+    pub fn get_attribute(&self, location: &SourceLocation, name: &str) -> Option<Option<&str>> {
+        self.attributes
+            .iter()
+            .find(|it| &it.location == location && it.name.eq_ignore_ascii_case(name))
+            .map(|it| it.value.as_deref())
+    }
+}
+
+/// The documentation of a declaration, taken from the `(** ... *)` or `/// ...` comments in front of it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocComment {
+    /// The text without the comment delimiters
+    pub text: String,
+    /// The location of the documented declaration's name
+    pub location: SourceLocation,
+}
+
+/// An `{attribute 'name' := 'value'}` pragma in front of a declaration. Attributes do not change the
+/// generated code, they are read by tools, e.g. the symbol export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attribute {
+    pub name: String,
+    pub value: Option<String>,
+    /// The location of the declaration's name
+    pub location: SourceLocation,
+}
+
+impl Attribute {
+    /// Parses the name and the optional value of an `{attribute 'name'}` or
+    /// `{attribute 'name' := 'value'}` pragma, returns `None` for any other pragma
+    pub fn parse(text: &str) -> Option<(String, Option<String>)> {
+        fn parse_quoted(text: &str) -> Option<(&str, &str)> {
+            text.strip_prefix('\'')?.split_once('\'')
+        }
+
+        let content = text.strip_prefix('{')?.strip_suffix('}')?.trim();
+        let keyword = content.get(.."attribute".len())?;
+        if !keyword.eq_ignore_ascii_case("attribute") {
+            return None;
+        }
+        let (name, rest) = parse_quoted(content["attribute".len()..].trim_start())?;
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return Some((name.to_string(), None));
+        }
+        let (value, rest) = parse_quoted(rest.strip_prefix(":=")?.trim_start())?;
+        rest.trim().is_empty().then(|| (name.to_string(), Some(value.to_string())))
+    }
+}
+
+/// A comment or a pragma which carries no meaning for the compiler. It is kept with its location
+/// so tools like the formatter can reproduce it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    /// The text including its delimiters, e.g. `(* comment *)`
+    pub text: String,
+    pub location: SourceLocation,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriviaKind {
+    /// A `// ...` comment running until the end of the line
+    LineComment,
+    /// A `(* ... *)` or `/* ... */` comment
+    BlockComment,
+    /// A `{ ... }` pragma that is not known to the parser
+    Pragma,
+    /// A `(** ... *)` or `/// ...` comment documenting the declaration following it
+    DocComment,
+}
+
+impl Trivia {
+    pub fn new(text: &str, location: SourceLocation) -> Self {
+        let kind = if Trivia::is_doc_comment(text) {
+            TriviaKind::DocComment
+        } else if text.starts_with("//") {
+            TriviaKind::LineComment
+        } else if text.starts_with('{') {
+            TriviaKind::Pragma
+        } else {
+            TriviaKind::BlockComment
+        };
+        Trivia { kind, text: text.to_string(), location }
+    }
+
+    /// Returns whether the given comment is a doc comment. Like in rustdoc, comments starting with
+    /// more delimiters, e.g. `(*****)` banners or `////` rulers, are no doc comments.
+    pub fn is_doc_comment(text: &str) -> bool {
+        (text.starts_with("(**") && !text.starts_with("(***") && text != "(**)")
+            || (text.starts_with("///") && !text.starts_with("////"))
+    }
+
+    /// Returns the text of a doc comment without its delimiters, the leading `*` decoration of
+    /// its lines and their common indentation
+    pub fn get_doc_text(text: &str) -> String {
+        if let Some(line) = text.strip_prefix("///") {
+            return line.strip_prefix(' ').unwrap_or(line).trim_end().to_string();
+        }
+
+        let content = text.strip_prefix("(**").unwrap_or(text);
+        let content = content.strip_suffix("*)").unwrap_or(content);
+        let mut lines = content.lines().map(str::trim_end).collect::<Vec<_>>();
+        // the lines after the first may be decorated with a leading `*`
+        if lines.iter().skip(1).filter(|it| !it.trim().is_empty()).all(|it| it.trim_start().starts_with('*'))
+        {
+            for line in lines.iter_mut().skip(1) {
+                let trimmed = line.trim_start();
+                let undecorated = trimmed.strip_prefix('*').unwrap_or(trimmed);
+                *line = undecorated.strip_prefix(' ').unwrap_or(undecorated);
+            }
+        }
+        let indentation = lines
+            .iter()
+            .skip(1)
+            .filter(|it| !it.trim().is_empty())
+            .map(|it| it.len() - it.trim_start().len())
+            .min()
+            .unwrap_or_default();
+        if let Some(first) = lines.first_mut() {
+            *first = first.trim_start();
+        }
+        for line in lines.iter_mut().skip(1) {
+            *line = line.get(indentation..).unwrap_or(line.trim_start());
+        }
+        let lines = lines.into_iter().skip_while(|it| it.is_empty()).collect::<Vec<_>>();
+        lines.join("\n").trim_end().to_string()
+    }
+}
+
+/// The trivia of a unit, attached by the parser to the declarations (POUs, `VAR` blocks, variables,
+/// types, ...) and statements they belong to. Nodes are identified by the start offset of their location.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttachedTrivia {
+    /// Trivia on the lines in front of a node
+    pub leading: BTreeMap<usize, Vec<Trivia>>,
+    /// Trivia following a node on the same line
+    pub trailing: BTreeMap<usize, Vec<Trivia>>,
+    /// Trivia behind the last child of a block (e.g. the last variable of a `VAR` block), in front of
+    /// its closing keyword
+    pub closing: BTreeMap<usize, Vec<Trivia>>,
+    /// Trivia behind the last declaration of the unit
+    pub dangling: Vec<Trivia>,
+}
+
+impl AttachedTrivia {
+    /// Returns the trivia on the lines in front of the node at the given location
+    pub fn get_leading(&self, location: &SourceLocation) -> &[Trivia] {
+        Self::get(&self.leading, location)
+    }
+
+    /// Returns the trivia following the node at the given location on the same line
+    pub fn get_trailing(&self, location: &SourceLocation) -> &[Trivia] {
+        Self::get(&self.trailing, location)
+    }
+
+    /// Returns the trivia in front of the closing keyword of the block at the given location
+    pub fn get_closing(&self, location: &SourceLocation) -> &[Trivia] {
+        Self::get(&self.closing, location)
+    }
+
+    fn get<'a>(trivia: &'a BTreeMap<usize, Vec<Trivia>>, location: &SourceLocation) -> &'a [Trivia] {
+        location.to_range().and_then(|it| trivia.get(&it.start)).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns all trivia of the unit, ordered by their location
+    pub fn iter(&self) -> impl Iterator<Item = &Trivia> {
+        let mut trivia = self
+            .leading
+            .values()
+            .chain(self.trailing.values())
+            .chain(self.closing.values())
+            .flatten()
+            .chain(self.dangling.iter())
+            .collect::<Vec<_>>();
+        trivia.sort_by_key(|it| it.location.to_range().map(|it| it.start));
+        trivia.into_iter()
     }
 }
 
@@ -579,11 +770,12 @@ pub enum ArgumentProperty {
 }
 
 #[derive(PartialEq, Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct VariableBlock {
     pub access: AccessModifier,
     pub constant: bool,
     pub retain: bool,
+    /// `PERSISTENT` blocks keep their values across a download, `RETAIN PERSISTENT` blocks are also `retain`
+    pub persistent: bool,
     pub variables: Vec<Variable>,
     pub kind: VariableBlockType,
     pub linkage: LinkageType,
@@ -629,6 +821,7 @@ impl Default for VariableBlock {
             access: AccessModifier::Internal,
             constant: false,
             retain: false,
+            persistent: false,
             variables: vec![],
             kind: VariableBlockType::Local,
             linkage: LinkageType::Internal,
@@ -648,12 +841,14 @@ impl Debug for VariableBlock {
         if self.retain {
             result.field("retain", &self.retain);
         }
+        if self.persistent {
+            result.field("persistent", &self.persistent);
+        }
         result.finish()
     }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Variable {
     pub name: String,
     pub data_type_declaration: DataTypeDeclaration,
@@ -705,7 +900,6 @@ impl Variable {
 }
 
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum DataTypeDeclaration {
     Reference { referenced_type: String, location: SourceLocation },
     Definition { data_type: Box<DataType>, location: SourceLocation, scope: Option<String> },
@@ -804,7 +998,6 @@ impl DataTypeDeclaration {
 }
 
 #[derive(PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct UserTypeDeclaration {
     pub data_type: DataType,
     pub initializer: Option<AstNode>,
@@ -825,7 +1018,6 @@ impl Debug for UserTypeDeclaration {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum DataType {
     StructType {
         name: Option<String>, //maybe None for inline structs
@@ -962,7 +1154,6 @@ fn replace_reference(
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum ReferenceAccess {
     /// `.foo`
     Global(Box<AstNode>),
@@ -987,7 +1178,6 @@ pub enum ReferenceAccess {
 // Another use-case might be markers to exclude internals from validation - this currently happens based on `SourceLocation` with `FileMarker`s,
 // this might be a better alternative
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct MetaData(Box<AstNode>);
 
 impl From<AstNode> for MetaData {
@@ -1011,7 +1201,6 @@ impl MetaData {
 }
 
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct AstNode {
     pub stmt: AstStatement,
     pub id: AstId,
@@ -1026,7 +1215,6 @@ impl Default for AstNode {
 }
 
 #[derive(Debug, Clone, PartialEq, TryInto, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 #[try_into(ref, ref_mut, owned)]
 pub enum AstStatement {
     EmptyStatement(EmptyStatement),
@@ -1768,7 +1956,7 @@ impl Operator {
 
 #[cfg(test)]
 mod tests {
-    use crate::ast::{ArgumentProperty, DeclarationKind, PouType, VariableBlockType};
+    use crate::ast::{ArgumentProperty, Attribute, DeclarationKind, PouType, Trivia, VariableBlockType};
 
     #[test]
     fn display_pou() {
@@ -1798,6 +1986,45 @@ mod tests {
         assert_eq!(VariableBlockType::Global.to_string(), "Global");
         assert_eq!(VariableBlockType::InOut.to_string(), "InOut");
     }
+
+    #[test]
+    fn doc_comments_are_recognized() {
+        assert!(Trivia::is_doc_comment("(** doc *)"));
+        assert!(Trivia::is_doc_comment("/// doc"));
+        assert!(!Trivia::is_doc_comment("(* comment *)"));
+        assert!(!Trivia::is_doc_comment("(**)"));
+        assert!(!Trivia::is_doc_comment("(*****)"));
+        assert!(!Trivia::is_doc_comment("// comment"));
+        assert!(!Trivia::is_doc_comment("//// ruler"));
+    }
+
+    #[test]
+    fn attribute_pragmas_are_parsed() {
+        assert_eq!(
+            Attribute::parse("{attribute 'symbol' := 'read'}"),
+            Some(("symbol".to_string(), Some("read".to_string())))
+        );
+        assert_eq!(Attribute::parse("{ ATTRIBUTE 'hide' }"), Some(("hide".to_string(), None)));
+        assert_eq!(Attribute::parse("{attribute 'symbol':='none'}").unwrap().1.as_deref(), Some("none"));
+        assert_eq!(Attribute::parse("{attribute symbol}"), None);
+        assert_eq!(Attribute::parse("{attribute 'symbol' := 'read' 'x'}"), None);
+        assert_eq!(Attribute::parse("{external}"), None);
+    }
+
+    #[test]
+    fn doc_text_is_stripped_of_delimiters_and_decoration() {
+        assert_eq!(Trivia::get_doc_text("/// a line  "), "a line");
+        assert_eq!(Trivia::get_doc_text("///indented\tby tab"), "indented\tby tab");
+        assert_eq!(Trivia::get_doc_text("(** single line *)"), "single line");
+        assert_eq!(
+            Trivia::get_doc_text("(**\n     * decorated\n     *\n     *   indented\n     *)"),
+            "decorated\n\n  indented"
+        );
+        assert_eq!(
+            Trivia::get_doc_text("(** first\n        second\n          third\n    *)"),
+            "first\nsecond\n  third"
+        );
+    }
 }
 
 pub struct AstFactory {}
@@ -2225,27 +2452,23 @@ pub struct CastStatement {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct MultipliedStatement {
     pub multiplier: u32,
     pub element: Box<AstNode>,
 }
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct ReferenceExpr {
     pub access: ReferenceAccess,
     pub base: Option<Box<AstNode>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct DirectAccess {
     pub access: DirectAccessType,
     pub index: Box<AstNode>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct HardwareAccess {
     pub direction: HardwareAccessType,
     pub access: DirectAccessType,
@@ -2253,7 +2476,6 @@ pub struct HardwareAccess {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct BinaryExpression {
     pub operator: Operator,
     pub left: Box<AstNode>,
@@ -2261,28 +2483,24 @@ pub struct BinaryExpression {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct UnaryExpression {
     pub operator: Operator,
     pub value: Box<AstNode>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct RangeStatement {
     pub start: Box<AstNode>,
     pub end: Box<AstNode>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Assignment {
     pub left: Box<AstNode>,
     pub right: Box<AstNode>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct CallStatement {
     pub operator: Box<AstNode>,
     pub parameters: Option<Box<AstNode>>,
@@ -2290,7 +2508,6 @@ pub struct CallStatement {
 
 /// Represents a conditional jump from current location to a specified label
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct JumpStatement {
     /// The condition based on which the current statement will perform a jump
     pub condition: Box<AstNode>,
@@ -6,7 +6,6 @@ use plc_source::source_location::SourceLocation;
 use crate::ast::AstNode;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct IfStatement {
     pub blocks: Vec<ConditionalBlock>,
     pub else_block: Vec<AstNode>,
@@ -14,7 +13,6 @@ pub struct IfStatement {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct ForLoopStatement {
     pub counter: Box<AstNode>,
     pub start: Box<AstNode>,
@@ -25,7 +23,6 @@ pub struct ForLoopStatement {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 /// used for While and Repeat loops
 pub struct LoopStatement {
     pub condition: Box<AstNode>,
@@ -34,7 +31,6 @@ pub struct LoopStatement {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct CaseStatement {
     pub selector: Box<AstNode>,
     pub case_blocks: Vec<ConditionalBlock>,
@@ -43,7 +39,6 @@ pub struct CaseStatement {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum AstControlStatement {
     If(IfStatement),
     ForLoop(ForLoopStatement),
@@ -53,14 +48,12 @@ pub enum AstControlStatement {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct ConditionalBlock {
     pub condition: Box<AstNode>,
     pub body: Vec<AstNode>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct ReturnStatement {
     /// Indicates that the given condition must evaluate to true in order for the return to take place.
     /// Only used in CFC where the condition may be [`Some`] and [`None`] otherwise.
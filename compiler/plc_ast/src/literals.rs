@@ -17,7 +17,6 @@ macro_rules! impl_getters {
 }
 
 #[derive(Clone, PartialEq, TryInto, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 #[try_into(ref)]
 pub enum AstLiteral {
     /// a null literal used to initialize pointers
@@ -91,7 +90,6 @@ pub struct StringValue {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Array {
     pub elements: Option<Box<AstNode>>, // expression-list
 }
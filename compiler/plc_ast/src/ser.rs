@@ -1,15 +1,87 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     ast::{
-        Allocation, ArgumentProperty, Assignment, AstNode, AstStatement, AutoDerefType, BinaryExpression,
-        CallStatement, CompilationUnit, ConfigVariable, DataType, DataTypeDeclaration, DefaultValue,
-        DirectAccess, EmptyStatement, HardwareAccess, Implementation, Interface, JumpStatement,
-        LabelStatement, MultipliedStatement, Pou, PouType, PropertyBlock, RangeStatement, ReferenceAccess,
-        ReferenceExpr, UnaryExpression, UserTypeDeclaration, Variable, VariableBlock, VariableBlockType,
+        AccessModifier, Allocation, ArgumentProperty, Assignment, AstNode, AstStatement, AttachedTrivia,
+        AutoDerefType, BinaryExpression, CallStatement, CompilationUnit, ConfigVariable, DataType,
+        DataTypeDeclaration, DefaultValue, DirectAccess, DirectAccessType, EmptyStatement, HardwareAccess,
+        HardwareAccessType, Implementation, Interface, JumpStatement, LabelStatement, MultipliedStatement,
+        PolymorphismMode, Pou, PouType, PropertyBlock, PropertyImplementation, PropertyKind, RangeStatement,
+        ReferenceAccess, ReferenceExpr, Trivia, TypeNature, UnaryExpression, UserTypeDeclaration, Variable,
+        VariableBlock, VariableBlockType,
     },
     control_statements::{AstControlStatement, ReturnStatement},
     literals::AstLiteral,
     visitor::{AstVisitor, Walker},
 };
+use plc_source::source_location::SourceLocation;
+
+/// Options of the formatter, configured in the `formatter` section of the `plc.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FormatOptions {
+    /// Number of spaces per indentation level
+    pub indent_width: usize,
+    pub keyword_case: KeywordCase,
+    /// Spelling of keywords which can be written with or without underscores, e.g. `END_IF` and `ENDIF`
+    pub compound_keywords: CompoundKeywords,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent_width: 4,
+            keyword_case: KeywordCase::Upper,
+            compound_keywords: CompoundKeywords::Underscore,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeywordCase {
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompoundKeywords {
+    /// `END_IF`, `VAR_INPUT`, `REF_TO`, ...
+    Underscore,
+    /// `ENDIF`, `VARINPUT`, `REFTO`, ...
+    Joined,
+}
+
+/// Keywords which can also be written without their underscores
+const COMPOUND_KEYWORDS: &[&str] = &[
+    "END_CLASS",
+    "END_INTERFACE",
+    "VAR_INPUT",
+    "VAR_OUTPUT",
+    "END_METHOD",
+    "PROPERTY_GET",
+    "PROPERTY_SET",
+    "END_PROPERTY",
+    "VAR_TEMP",
+    "VAR_GLOBAL",
+    "VAR_IN_OUT",
+    "END_VAR",
+    "END_PROGRAM",
+    "END_FUNCTION",
+    "FUNCTION_BLOCK",
+    "END_FUNCTION_BLOCK",
+    "END_TYPE",
+    "END_STRUCT",
+    "END_ACTION",
+    "END_ACTIONS",
+    "END_IF",
+    "END_FOR",
+    "END_WHILE",
+    "END_REPEAT",
+    "REF_TO",
+    "END_CASE",
+];
 
 pub struct AstSerializer<'a> {
     result: String,
@@ -17,6 +89,31 @@ pub struct AstSerializer<'a> {
     unit: Option<&'a CompilationUnit>,
     user_type_context: Option<&'a UserTypeDeclaration>,
     is_in_paren: bool,
+    /// Set when a whole source file is formatted, see [`AstSerializer::format_source`]
+    source: Option<SourceFormat<'a>>,
+}
+
+struct SourceFormat<'a> {
+    src: &'a str,
+    options: &'a FormatOptions,
+    /// The trivia which was not emitted yet
+    trivia: AttachedTrivia,
+}
+
+/// A declaration on the top level of a file, see [`AstSerializer::format_source`]
+enum TopLevelItem<'a> {
+    Pou(&'a Pou),
+    Actions(Vec<&'a Implementation>),
+    Interface(&'a Interface),
+    UserType(&'a UserTypeDeclaration),
+    Globals(&'a VariableBlock),
+    Config(Vec<&'a ConfigVariable>),
+}
+
+/// A declaration nested in a POU or an interface
+enum Member<'a> {
+    Method(&'a Pou),
+    Property(&'a str, &'a PropertyImplementation),
 }
 
 impl AstSerializer<'_> {
@@ -27,6 +124,7 @@ impl AstSerializer<'_> {
             unit: None,
             user_type_context: None,
             is_in_paren: false,
+            source: None,
         };
         serializer.visit(node);
 
@@ -42,6 +140,7 @@ impl AstSerializer<'_> {
             unit: Some(unit),
             user_type_context: None,
             is_in_paren: false,
+            source: None,
         };
         serializer.visit_compilation_unit(unit);
 
@@ -55,6 +154,7 @@ impl AstSerializer<'_> {
             unit: None,
             user_type_context: None,
             is_in_paren: false,
+            source: None,
         };
 
         let nodes = nodes.iter().filter(|node| !node.is_empty_statement());
@@ -81,6 +181,7 @@ impl AstSerializer<'_> {
             unit: Some(unit),
             user_type_context: None,
             is_in_paren: false,
+            source: None,
         };
         serializer.visit_variable_block(variable_block);
 
@@ -115,6 +216,893 @@ impl AstSerializer<'_> {
     }
 }
 
+impl<'a> AstSerializer<'a> {
+    /// Formats a whole parsed source file: every declaration and statement is put on its own line,
+    /// nested blocks are indented and keywords are spelled as configured. The unit's trivia is
+    /// emitted next to the declarations and statements it is attached to and empty lines between
+    /// them are kept.
+    pub fn format_source(unit: &'a CompilationUnit, src: &'a str, options: &'a FormatOptions) -> String {
+        let mut serializer = AstSerializer {
+            result: String::new(),
+            indent: 0,
+            unit: None,
+            user_type_context: None,
+            is_in_paren: false,
+            source: Some(SourceFormat { src, options, trivia: unit.trivia.clone() }),
+        };
+
+        for item in top_level_items(unit, src) {
+            match item {
+                TopLevelItem::Pou(pou) => serializer.format_pou(pou, unit),
+                TopLevelItem::Actions(actions) => serializer.format_actions(&actions),
+                TopLevelItem::Interface(interface) => serializer.format_interface(interface),
+                TopLevelItem::UserType(user_type) => serializer.format_user_type(user_type),
+                TopLevelItem::Globals(block) => {
+                    let prefixes = if block.linkage.is_external() { &["{external}"][..] } else { &[] };
+                    let start = serializer.item_start(&block.location, prefixes);
+                    serializer.begin_node_at(&block.location, start, 0);
+                    if block.linkage.is_external() {
+                        serializer.result.push_str("{external}");
+                        serializer.start_line(None, 0);
+                    }
+                    serializer.format_var_block_header(block);
+                    serializer.format_var_block_body(block, 0);
+                }
+                TopLevelItem::Config(variables) => serializer.format_config_variables(&variables),
+            }
+        }
+
+        // trivia which could not be placed next to its node is kept at the end of the file
+        let remaining = std::mem::take(&mut serializer.source_mut().trivia);
+        for trivia in remaining.iter() {
+            serializer.push_trivia_line(trivia, 0);
+        }
+        if !serializer.result.is_empty() {
+            serializer.result.push('\n');
+        }
+
+        serializer.result
+    }
+
+    fn source(&self) -> &SourceFormat<'a> {
+        self.source.as_ref().expect("a source is being formatted")
+    }
+
+    fn source_mut(&mut self) -> &mut SourceFormat<'a> {
+        self.source.as_mut().expect("a source is being formatted")
+    }
+
+    /// Returns the keyword in the configured spelling, keywords are kept as they are outside of
+    /// [`AstSerializer::format_source`]
+    fn keyword(&self, keyword: &str) -> String {
+        let Some(source) = &self.source else { return keyword.to_string() };
+        let keyword = match source.options.compound_keywords {
+            CompoundKeywords::Joined if COMPOUND_KEYWORDS.contains(&keyword) => keyword.replace('_', ""),
+            _ => keyword.to_string(),
+        };
+        match source.options.keyword_case {
+            KeywordCase::Upper => keyword.to_uppercase(),
+            KeywordCase::Lower => keyword.to_lowercase(),
+        }
+    }
+
+    fn push_keyword(&mut self, keyword: &str) {
+        let keyword = self.keyword(keyword);
+        self.result.push_str(&keyword);
+    }
+
+    /// Returns the text of the given location in the formatted source
+    fn source_text(&self, location: &SourceLocation) -> Option<&'a str> {
+        let src = self.source.as_ref()?.src;
+        location.to_range().and_then(|range| src.get(range))
+    }
+
+    /// Returns the offset of a top-level item including the given words written in front of the
+    /// node's location, e.g. `TYPE` in front of the name of the first type of a `TYPE` block
+    fn item_start(&self, location: &SourceLocation, prefixes: &[&str]) -> Option<usize> {
+        let src = self.source().src;
+        let mut start = location.to_range()?.start;
+        loop {
+            let before = src[..start].trim_end();
+            let prefix = prefixes.iter().find_map(|prefix| {
+                let offset = before.len().checked_sub(prefix.len())?;
+                let is_word_start =
+                    !before[..offset].ends_with(|it: char| it.is_ascii_alphanumeric() || it == '_');
+                before.get(offset..)?.eq_ignore_ascii_case(prefix).then_some(offset).filter(|_| is_word_start)
+            });
+            match prefix {
+                Some(offset) => start = offset,
+                None => return Some(start),
+            }
+        }
+    }
+
+    /// Starts a new line at the given depth, an empty line in front of `start` in the source is kept
+    fn start_line(&mut self, start: Option<usize>, depth: usize) {
+        let source = self.source();
+        let indent = " ".repeat(source.options.indent_width * depth);
+        let empty_line_before = start.is_some_and(|it| has_empty_line_before(source.src, it));
+        if !self.result.is_empty() {
+            if empty_line_before {
+                self.result.push('\n');
+            }
+            self.result.push('\n');
+        }
+        self.result.push_str(&indent);
+    }
+
+    /// Starts the line of a declaration or statement, preceded by the trivia leading it
+    fn begin_node(&mut self, location: &SourceLocation, depth: usize) {
+        let start = location.to_range().map(|it| it.start);
+        self.begin_node_at(location, start, depth);
+    }
+
+    fn begin_node_at(&mut self, location: &SourceLocation, start: Option<usize>, depth: usize) {
+        let leading = location
+            .to_range()
+            .and_then(|range| self.source_mut().trivia.leading.remove(&range.start))
+            .unwrap_or_default();
+        for trivia in &leading {
+            self.push_trivia_line(trivia, depth);
+        }
+        self.start_line(start, depth);
+    }
+
+    /// Appends the trivia following the node on the same line
+    fn end_node(&mut self, location: &SourceLocation, depth: usize) {
+        let trailing = location
+            .to_range()
+            .and_then(|range| self.source_mut().trivia.trailing.remove(&range.start))
+            .unwrap_or_default();
+        let mut after_line_comment = false;
+        for trivia in &trailing {
+            if after_line_comment {
+                self.start_line(None, depth + 1);
+            } else {
+                self.result.push(' ');
+            }
+            self.result.push_str(trivia_text(trivia));
+            after_line_comment = trivia.text.starts_with("//");
+        }
+    }
+
+    /// Emits the trivia in front of the closing keyword of a block
+    fn push_closing(&mut self, location: &SourceLocation, depth: usize) {
+        let closing = location
+            .to_range()
+            .and_then(|range| self.source_mut().trivia.closing.remove(&range.start))
+            .unwrap_or_default();
+        for trivia in &closing {
+            self.push_trivia_line(trivia, depth);
+        }
+    }
+
+    fn push_trivia_line(&mut self, trivia: &Trivia, depth: usize) {
+        self.start_line(trivia.location.to_range().map(|it| it.start), depth);
+        self.result.push_str(trivia_text(trivia));
+    }
+
+    /// Formats a literal as it was written, returns false if its text is not known
+    fn format_literal(&mut self, literal: &AstLiteral, node: &AstNode) -> bool {
+        match literal {
+            AstLiteral::Bool(value) => self.push_keyword(if *value { "TRUE" } else { "FALSE" }),
+            AstLiteral::Null => self.push_keyword("NULL"),
+            AstLiteral::Array(array) => {
+                self.result.push('[');
+                if let Some(elements) = &array.elements {
+                    elements.walk(self);
+                }
+                self.result.push(']');
+            }
+            _ => match self.source_text(&node.get_location()) {
+                Some(text) => self.result.push_str(text),
+                None => return false,
+            },
+        }
+        true
+    }
+
+    fn format_pou(&mut self, pou: &Pou, unit: &CompilationUnit) {
+        let mut pragmas = vec![];
+        if pou.linkage.is_external() {
+            pragmas.push("{external}");
+        }
+        if pou.is_const {
+            pragmas.push("{constant}");
+        }
+        let start = self.item_start(&pou.location, &pragmas);
+        self.begin_node_at(&pou.location, start, 0);
+        for pragma in pragmas {
+            self.result.push_str(pragma);
+            self.start_line(None, 0);
+        }
+
+        let (keyword, end_keyword) = pou_keywords(&pou.kind);
+        self.push_keyword(keyword);
+        self.format_polymorphism_mode(pou);
+        self.result.push(' ');
+        self.result.push_str(&pou.name);
+        self.format_generics(pou);
+        if let Some(super_class) = &pou.super_class {
+            self.result.push(' ');
+            self.push_keyword("EXTENDS");
+            self.result.push(' ');
+            self.result.push_str(&super_class.name);
+        }
+        if !pou.interfaces.is_empty() {
+            self.result.push(' ');
+            self.push_keyword("IMPLEMENTS");
+            self.result.push(' ');
+            self.result
+                .push_str(&pou.interfaces.iter().map(|it| it.name.as_str()).collect::<Vec<_>>().join(", "));
+        }
+        self.format_return_type(pou);
+        pou.variable_blocks.iter().for_each(|it| self.format_var_block(it, 0));
+
+        // methods and properties are declared in between the variable blocks and the body
+        let methods = unit
+            .pous
+            .iter()
+            .filter(|it| matches!(&it.kind, PouType::Method { parent, property: None, .. } if parent == &pou.name))
+            .map(Member::Method);
+        let mut members = methods.chain(properties(&pou.properties)).collect::<Vec<_>>();
+        members.sort_by_key(Member::start);
+        for member in members {
+            match member {
+                Member::Method(method) => self.format_method(method, find_implementation(unit, method), 0),
+                Member::Property(name, property) => self.format_property(name, property, 0),
+            }
+        }
+
+        if let Some(implementation) = find_implementation(unit, pou) {
+            self.format_statements(&implementation.statements, 1);
+        }
+        self.push_closing(&pou.location, 1);
+        self.start_line(None, 0);
+        self.push_keyword(end_keyword);
+        self.end_node(&pou.location, 0);
+    }
+
+    fn format_method(&mut self, method: &Pou, implementation: Option<&Implementation>, depth: usize) {
+        self.begin_node(&method.location, depth);
+        self.push_keyword("METHOD");
+        let access = implementation.and_then(|it| it.access).filter(|it| *it != AccessModifier::Protected);
+        if let Some(access) = access {
+            self.result.push(' ');
+            self.push_keyword(access_keyword(access));
+        }
+        self.format_polymorphism_mode(method);
+        if implementation.is_some_and(|it| it.overriding) {
+            self.result.push(' ');
+            self.push_keyword("OVERRIDE");
+        }
+        self.result.push(' ');
+        self.result.push_str(method.name.rsplit_once('.').map_or(method.name.as_str(), |(_, name)| name));
+        self.format_generics(method);
+        self.format_return_type(method);
+        method.variable_blocks.iter().for_each(|it| self.format_var_block(it, depth));
+
+        if let Some(implementation) = implementation {
+            self.format_statements(&implementation.statements, depth + 1);
+        }
+        self.push_closing(&method.location, depth + 1);
+        self.start_line(None, depth);
+        self.push_keyword("END_METHOD");
+        self.end_node(&method.location, depth);
+    }
+
+    fn format_property(&mut self, name: &str, property: &PropertyImplementation, depth: usize) {
+        self.begin_node(&property.location, depth);
+        self.push_keyword(match property.kind {
+            PropertyKind::Get => "PROPERTY_GET",
+            PropertyKind::Set => "PROPERTY_SET",
+        });
+        self.result.push(' ');
+        self.result.push_str(name);
+        self.result.push_str(" : ");
+        self.format_data_type_declaration(&property.datatype, depth, None);
+        property.variable_blocks.iter().for_each(|it| self.format_var_block(it, depth));
+        self.format_statements(&property.body, depth + 1);
+        self.push_closing(&property.location, depth + 1);
+        self.start_line(None, depth);
+        self.push_keyword("END_PROPERTY");
+        self.end_node(&property.location, depth);
+    }
+
+    fn format_polymorphism_mode(&mut self, pou: &Pou) {
+        let keyword = match pou.poly_mode {
+            Some(PolymorphismMode::Abstract) => "ABSTRACT",
+            Some(PolymorphismMode::Final) => "FINAL",
+            _ => return,
+        };
+        self.result.push(' ');
+        self.push_keyword(keyword);
+    }
+
+    fn format_generics(&mut self, pou: &Pou) {
+        if pou.generics.is_empty() {
+            return;
+        }
+        let generics = pou
+            .generics
+            .iter()
+            .map(|it| format!("{}: {}", it.name, nature_name(it.nature)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.result.push_str(&format!("<{generics}>"));
+    }
+
+    fn format_return_type(&mut self, pou: &Pou) {
+        if let Some(return_type) = &pou.return_type {
+            self.result.push_str(" : ");
+            self.format_data_type_declaration(return_type, 0, None);
+        }
+    }
+
+    fn format_actions(&mut self, actions: &[&Implementation]) {
+        let Some(first) = actions.first() else { return };
+        let is_standalone = self.source_text(&first.name_location).is_some_and(|it| it.contains('.'));
+        let container = first.type_name.as_str();
+        let prefixes: &[&str] = if is_standalone { &["ACTION"] } else { &["ACTION", container, "ACTIONS"] };
+        let start = self.item_start(&first.name_location, prefixes);
+        self.begin_node_at(&first.name_location, start, 0);
+        if !is_standalone {
+            self.push_keyword("ACTIONS");
+            self.result.push(' ');
+            self.result.push_str(container);
+        }
+
+        for (index, action) in actions.iter().enumerate() {
+            match index {
+                0 if is_standalone => {}
+                0 => self.start_line(None, 0),
+                _ => self.begin_node(&action.name_location, 0),
+            }
+            let name = action.name.rsplit_once('.').map_or(action.name.as_str(), |(_, name)| name);
+            self.push_keyword("ACTION");
+            self.result.push(' ');
+            if is_standalone {
+                self.result.push_str(&format!("{container}.{name}"));
+            } else {
+                self.result.push_str(name);
+            }
+            self.format_statements(&action.statements, 1);
+            self.push_closing(&action.name_location, 1);
+            self.start_line(None, 0);
+            self.push_keyword("END_ACTION");
+            self.end_node(&action.name_location, 0);
+        }
+
+        if !is_standalone {
+            self.start_line(None, 0);
+            self.push_keyword("END_ACTIONS");
+        }
+    }
+
+    fn format_interface(&mut self, interface: &Interface) {
+        self.begin_node(&interface.location, 0);
+        self.push_keyword("INTERFACE");
+        self.result.push(' ');
+        self.result.push_str(&interface.ident.name);
+        if !interface.extensions.is_empty() {
+            self.result.push(' ');
+            self.push_keyword("EXTENDS");
+            self.result.push(' ');
+            self.result.push_str(
+                &interface.extensions.iter().map(|it| it.name.as_str()).collect::<Vec<_>>().join(", "),
+            );
+        }
+
+        let mut members = interface
+            .methods
+            .iter()
+            .map(Member::Method)
+            .chain(properties(&interface.properties))
+            .collect::<Vec<_>>();
+        members.sort_by_key(Member::start);
+        for member in members {
+            match member {
+                // the implementations of interface methods are not kept by the parser
+                Member::Method(method) => self.format_method(method, None, 0),
+                Member::Property(name, property) => self.format_property(name, property, 0),
+            }
+        }
+        self.push_closing(&interface.location, 1);
+        self.start_line(None, 0);
+        self.push_keyword("END_INTERFACE");
+        self.end_node(&interface.location, 0);
+    }
+
+    fn format_user_type(&mut self, user_type: &UserTypeDeclaration) {
+        let is_external = user_type.linkage.is_external();
+        let prefixes: &[&str] = if is_external { &["TYPE", "{external}"] } else { &["TYPE"] };
+        let start = self.item_start(&user_type.location, prefixes);
+        self.begin_node_at(&user_type.location, start, 0);
+        if is_external {
+            self.result.push_str("{external}");
+            self.start_line(None, 0);
+        }
+        self.push_keyword("TYPE");
+        self.result.push(' ');
+        self.result.push_str(user_type.data_type.get_name().unwrap_or_default());
+        self.result.push_str(" : ");
+        self.format_data_type(&user_type.data_type, 0, Some(&user_type.location));
+        if let Some(initializer) = &user_type.initializer {
+            self.result.push_str(" := ");
+            self.visit(initializer);
+        }
+        if !matches!(user_type.data_type, DataType::StructType { .. }) {
+            self.result.push(';');
+        }
+        self.end_node(&user_type.location, 0);
+        self.start_line(None, 0);
+        self.push_keyword("END_TYPE");
+    }
+
+    fn format_config_variables(&mut self, variables: &[&ConfigVariable]) {
+        let Some(first) = variables.first() else { return };
+        let start = self.item_start(&first.location, &["VAR_CONFIG", "VARCONFIG"]);
+        self.begin_node_at(&first.location, start, 0);
+        self.push_keyword("VAR_CONFIG");
+        for (index, variable) in variables.iter().enumerate() {
+            if index == 0 {
+                self.start_line(None, 1);
+            } else {
+                self.begin_node(&variable.location, 1);
+            }
+            self.visit(&variable.reference);
+            self.result.push(' ');
+            self.push_keyword("AT");
+            self.result.push(' ');
+            self.visit(&variable.address);
+            self.result.push_str(" : ");
+            self.format_data_type_declaration(&variable.data_type, 1, None);
+            self.result.push(';');
+            self.end_node(&variable.location, 1);
+        }
+        self.start_line(None, 0);
+        self.push_keyword("END_VAR");
+    }
+
+    fn format_var_block(&mut self, block: &VariableBlock, depth: usize) {
+        self.begin_node(&block.location, depth);
+        self.format_var_block_header(block);
+        self.format_var_block_body(block, depth);
+    }
+
+    fn format_var_block_header(&mut self, block: &VariableBlock) {
+        let (keyword, by_ref) = match block.kind {
+            VariableBlockType::Local => ("VAR", false),
+            VariableBlockType::Temp => ("VAR_TEMP", false),
+            VariableBlockType::Input(property) => ("VAR_INPUT", property == ArgumentProperty::ByRef),
+            VariableBlockType::Output => ("VAR_OUTPUT", false),
+            VariableBlockType::Global => ("VAR_GLOBAL", false),
+            VariableBlockType::InOut => ("VAR_IN_OUT", false),
+            VariableBlockType::External => ("VAR_EXTERNAL", false),
+        };
+        self.push_keyword(keyword);
+        if by_ref {
+            self.result.push_str(" {ref}");
+        }
+        let modifiers =
+            [(block.constant, "CONSTANT"), (block.retain, "RETAIN"), (block.persistent, "PERSISTENT")];
+        for (_, modifier) in modifiers.into_iter().filter(|(is_set, _)| *is_set) {
+            self.result.push(' ');
+            self.push_keyword(modifier);
+        }
+        if block.access != AccessModifier::Protected {
+            self.result.push(' ');
+            self.push_keyword(access_keyword(block.access));
+        }
+    }
+
+    fn format_var_block_body(&mut self, block: &VariableBlock, depth: usize) {
+        block.variables.iter().for_each(|it| self.format_variable(it, depth + 1));
+        self.push_closing(&block.location, depth + 1);
+        self.start_line(None, depth);
+        self.push_keyword("END_VAR");
+        self.end_node(&block.location, depth);
+    }
+
+    fn format_variable(&mut self, variable: &Variable, depth: usize) {
+        self.begin_node(&variable.location, depth);
+        self.result.push_str(&variable.name);
+
+        let mut initializer = variable.initializer.as_ref().filter(|it| !it.is_default_value());
+        let mut data_type = &variable.data_type_declaration;
+        if let DataTypeDeclaration::Definition { data_type: definition, .. } = data_type {
+            if let DataType::PointerType { auto_deref: Some(AutoDerefType::Alias), referenced_type, .. } =
+                definition.as_ref()
+            {
+                // `x AT %IX1.0 : BOOL` and `x AT y : BOOL` are parsed into alias pointers
+                let target = variable.address.as_ref().or(initializer.take());
+                if let Some(target) = target {
+                    self.result.push(' ');
+                    self.push_keyword("AT");
+                    self.result.push(' ');
+                    self.visit(target);
+                }
+                data_type = referenced_type;
+            }
+        }
+        self.result.push_str(" : ");
+        self.format_data_type_declaration(data_type, depth, None);
+        if let Some(initializer) = initializer {
+            self.result.push_str(" := ");
+            self.visit(initializer);
+        }
+        self.result.push(';');
+        self.end_node(&variable.location, depth);
+    }
+
+    fn format_data_type_declaration(
+        &mut self,
+        declaration: &DataTypeDeclaration,
+        depth: usize,
+        closing: Option<&SourceLocation>,
+    ) {
+        match declaration {
+            DataTypeDeclaration::Reference { referenced_type, .. }
+            | DataTypeDeclaration::Aggregate { referenced_type, .. } => self.result.push_str(referenced_type),
+            DataTypeDeclaration::Definition { data_type, .. } => {
+                self.format_data_type(data_type, depth, closing)
+            }
+        }
+    }
+
+    /// Formats a type definition, the members of a struct are indented one level deeper than
+    /// `depth`. Trivia in front of `END_STRUCT` is taken from the block at `closing`.
+    fn format_data_type(&mut self, data_type: &DataType, depth: usize, closing: Option<&SourceLocation>) {
+        match data_type {
+            DataType::StructType { variables, .. } => {
+                self.push_keyword("STRUCT");
+                variables.iter().for_each(|it| self.format_variable(it, depth + 1));
+                if let Some(closing) = closing {
+                    self.push_closing(closing, depth + 1);
+                }
+                self.start_line(None, depth);
+                self.push_keyword("END_STRUCT");
+            }
+            DataType::EnumType { numeric_type, elements, .. } => {
+                if !numeric_type.eq_ignore_ascii_case("DINT") {
+                    self.result.push_str(numeric_type);
+                    self.result.push(' ');
+                }
+                self.result.push('(');
+                self.visit(elements);
+                self.result.push(')');
+            }
+            DataType::SubRangeType { referenced_type, bounds, .. } => {
+                self.result.push_str(referenced_type);
+                if let Some(bounds) = bounds {
+                    self.result.push('(');
+                    self.visit(bounds);
+                    self.result.push(')');
+                }
+            }
+            DataType::ArrayType { bounds, referenced_type, .. } => {
+                self.push_keyword("ARRAY");
+                self.result.push('[');
+                self.visit(bounds);
+                self.result.push_str("] ");
+                self.push_keyword("OF");
+                self.result.push(' ');
+                self.format_data_type_declaration(referenced_type, depth, None);
+            }
+            DataType::PointerType { referenced_type, auto_deref, type_safe, is_function, .. } => {
+                let keyword = match auto_deref {
+                    Some(AutoDerefType::Reference) => Some("REFERENCE TO"),
+                    Some(AutoDerefType::Alias | AutoDerefType::Default) => None,
+                    None if *is_function => Some("__FPOINTER"),
+                    None if *type_safe => Some("REF_TO"),
+                    None => Some("POINTER TO"),
+                };
+                if let Some(keyword) = keyword {
+                    self.push_keyword(keyword);
+                    self.result.push(' ');
+                }
+                self.format_data_type_declaration(referenced_type, depth, None);
+            }
+            DataType::StringType { is_wide, size, .. } => {
+                self.push_keyword(if *is_wide { "WSTRING" } else { "STRING" });
+                if let Some(size) = size {
+                    self.result.push('[');
+                    self.visit(size);
+                    self.result.push(']');
+                }
+            }
+            DataType::VarArgs { referenced_type, sized } => {
+                if *sized {
+                    self.result.push_str("{sized} ");
+                }
+                if let Some(referenced_type) = referenced_type {
+                    self.format_data_type_declaration(referenced_type, depth, None);
+                }
+                self.result.push_str("...");
+            }
+            DataType::GenericType { generic_symbol, .. } => self.result.push_str(generic_symbol),
+        }
+    }
+
+    /// Formats the statements of a body, each on its own line
+    fn format_statements(&mut self, statements: &[AstNode], depth: usize) {
+        for statement in statements.iter().filter(|it| !it.is_empty_statement()) {
+            let location = statement.get_location();
+            self.begin_node(&location, depth);
+            match statement.get_stmt() {
+                AstStatement::ControlStatement(control) => {
+                    self.format_control_statement(control, &location, depth)
+                }
+                _ => {
+                    self.visit(statement);
+                    self.result.push(';');
+                }
+            }
+            self.end_node(&location, depth);
+        }
+    }
+
+    fn format_control_statement(
+        &mut self,
+        statement: &AstControlStatement,
+        location: &SourceLocation,
+        depth: usize,
+    ) {
+        match statement {
+            AstControlStatement::If(stmt) => {
+                for (index, block) in stmt.blocks.iter().enumerate() {
+                    if index > 0 {
+                        self.start_line(None, depth);
+                    }
+                    self.push_keyword(if index == 0 { "IF" } else { "ELSIF" });
+                    self.result.push(' ');
+                    self.visit(&block.condition);
+                    self.result.push(' ');
+                    self.push_keyword("THEN");
+                    self.format_statements(&block.body, depth + 1);
+                }
+                if !stmt.else_block.is_empty() {
+                    self.start_line(None, depth);
+                    self.push_keyword("ELSE");
+                    self.format_statements(&stmt.else_block, depth + 1);
+                }
+                self.push_closing(location, depth + 1);
+                self.start_line(None, depth);
+                self.push_keyword("END_IF");
+            }
+            AstControlStatement::ForLoop(stmt) => {
+                self.push_keyword("FOR");
+                self.result.push(' ');
+                self.visit(&stmt.counter);
+                self.result.push_str(" := ");
+                self.visit(&stmt.start);
+                self.result.push(' ');
+                self.push_keyword("TO");
+                self.result.push(' ');
+                self.visit(&stmt.end);
+                if let Some(step) = &stmt.by_step {
+                    self.result.push(' ');
+                    self.push_keyword("BY");
+                    self.result.push(' ');
+                    self.visit(step);
+                }
+                self.result.push(' ');
+                self.push_keyword("DO");
+                self.format_statements(&stmt.body, depth + 1);
+                self.push_closing(location, depth + 1);
+                self.start_line(None, depth);
+                self.push_keyword("END_FOR");
+            }
+            AstControlStatement::WhileLoop(stmt) => {
+                self.push_keyword("WHILE");
+                self.result.push(' ');
+                self.visit(&stmt.condition);
+                self.result.push(' ');
+                self.push_keyword("DO");
+                self.format_statements(&stmt.body, depth + 1);
+                self.push_closing(location, depth + 1);
+                self.start_line(None, depth);
+                self.push_keyword("END_WHILE");
+            }
+            AstControlStatement::RepeatLoop(stmt) => {
+                self.push_keyword("REPEAT");
+                self.format_statements(&stmt.body, depth + 1);
+                self.push_closing(location, depth + 1);
+                self.start_line(None, depth);
+                self.push_keyword("UNTIL");
+                self.result.push(' ');
+                self.visit(&stmt.condition);
+                self.start_line(None, depth);
+                self.push_keyword("END_REPEAT");
+            }
+            AstControlStatement::Case(stmt) => {
+                self.push_keyword("CASE");
+                self.result.push(' ');
+                self.visit(&stmt.selector);
+                self.result.push(' ');
+                self.push_keyword("OF");
+                for block in &stmt.case_blocks {
+                    let label = block.condition.get_location();
+                    self.begin_node(&label, depth + 1);
+                    self.visit(&block.condition);
+                    self.result.push(':');
+                    self.end_node(&label, depth + 1);
+                    self.format_statements(&block.body, depth + 2);
+                }
+                if !stmt.else_block.is_empty() {
+                    self.start_line(None, depth + 1);
+                    self.push_keyword("ELSE");
+                    self.format_statements(&stmt.else_block, depth + 2);
+                }
+                self.push_closing(location, depth + 1);
+                self.start_line(None, depth);
+                self.push_keyword("END_CASE");
+            }
+        }
+    }
+}
+
+/// Returns the declarations on the top level of the unit ordered by their location, actions
+/// declared in one `ACTIONS` block and the variables of one `VAR_CONFIG` block are grouped
+fn top_level_items<'a>(unit: &'a CompilationUnit, src: &str) -> Vec<TopLevelItem<'a>> {
+    let start = |location: &SourceLocation| location.to_range().map(|it| it.start).unwrap_or_default();
+
+    let pous = unit
+        .pous
+        .iter()
+        .filter(|it| {
+            matches!(it.kind, PouType::Program | PouType::Function | PouType::FunctionBlock | PouType::Class)
+        })
+        .map(|it| (start(&it.location), TopLevelItem::Pou(it)));
+    let actions = unit
+        .implementations
+        .iter()
+        .filter(|it| it.pou_type == PouType::Action)
+        .map(|it| (start(&it.name_location), TopLevelItem::Actions(vec![it])));
+    let interfaces = unit.interfaces.iter().map(|it| (start(&it.location), TopLevelItem::Interface(it)));
+    let user_types = unit.user_types.iter().map(|it| (start(&it.location), TopLevelItem::UserType(it)));
+    let globals = unit.global_vars.iter().map(|it| (start(&it.location), TopLevelItem::Globals(it)));
+    let config = unit.var_config.iter().map(|it| (start(&it.location), TopLevelItem::Config(vec![it])));
+    let mut items = pous
+        .chain(actions)
+        .chain(interfaces)
+        .chain(user_types)
+        .chain(globals)
+        .chain(config)
+        .collect::<Vec<_>>();
+    items.sort_by_key(|(start, _)| *start);
+
+    let is_standalone = |action: &Implementation| {
+        action.name_location.to_range().and_then(|it| src.get(it)).is_some_and(|it| it.contains('.'))
+    };
+    let mut result: Vec<TopLevelItem<'a>> = vec![];
+    for (_, item) in items {
+        match (result.last_mut(), item) {
+            (Some(TopLevelItem::Actions(previous)), TopLevelItem::Actions(actions))
+                if !is_standalone(previous[0])
+                    && !is_standalone(actions[0])
+                    && previous[0].type_name == actions[0].type_name =>
+            {
+                previous.extend(actions)
+            }
+            (Some(TopLevelItem::Config(previous)), TopLevelItem::Config(variables)) => {
+                previous.extend(variables)
+            }
+            (_, item) => result.push(item),
+        }
+    }
+    result
+}
+
+/// Returns the operands of a call to `EXPT` the parser created for `a ** b`
+fn exponentiation_operands<'b>(call: &'b CallStatement, node: &AstNode) -> Option<[&'b AstNode; 2]> {
+    let is_expt = call.operator.get_flat_reference_name().is_some_and(|it| it == "EXPT");
+    // the operator of the desugared call spans the whole expression
+    if !is_expt || call.operator.get_location() != node.get_location() {
+        return None;
+    }
+    match call.parameters.as_deref().map(AstNode::get_stmt) {
+        Some(AstStatement::ExpressionList(operands)) => match operands.as_slice() {
+            [base, exponent] => Some([base, exponent]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the address of a hardware access, e.g. `%IX1.2`
+fn hardware_address(access: &HardwareAccess) -> String {
+    let direction = match access.direction {
+        HardwareAccessType::Input => "I",
+        HardwareAccessType::Output => "Q",
+        HardwareAccessType::Memory => "M",
+        HardwareAccessType::Global => "G",
+    };
+    let size = match access.access {
+        DirectAccessType::Bit => "X",
+        DirectAccessType::Byte => "B",
+        DirectAccessType::Word => "W",
+        DirectAccessType::DWord => "D",
+        DirectAccessType::LWord => "L",
+        DirectAccessType::Template => "*",
+    };
+    let address = access
+        .address
+        .iter()
+        .map(|it| match it.get_stmt() {
+            AstStatement::Literal(AstLiteral::Integer(value)) => value.to_string(),
+            _ => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+    format!("%{direction}{size}{address}")
+}
+
+fn find_implementation<'a>(unit: &'a CompilationUnit, pou: &Pou) -> Option<&'a Implementation> {
+    unit.implementations.iter().find(|it| it.name == pou.name && it.pou_type == pou.kind)
+}
+
+fn properties(properties: &[PropertyBlock]) -> impl Iterator<Item = Member<'_>> {
+    properties
+        .iter()
+        .flat_map(|block| block.implementations.iter().map(|it| Member::Property(&block.ident.name, it)))
+}
+
+impl Member<'_> {
+    fn start(&self) -> Option<usize> {
+        match self {
+            Member::Method(method) => method.location.to_range().map(|it| it.start),
+            Member::Property(_, property) => property.location.to_range().map(|it| it.start),
+        }
+    }
+}
+
+fn access_keyword(access: AccessModifier) -> &'static str {
+    match access {
+        AccessModifier::Private => "PRIVATE",
+        AccessModifier::Public => "PUBLIC",
+        AccessModifier::Protected => "PROTECTED",
+        AccessModifier::Internal => "INTERNAL",
+    }
+}
+
+/// Returns the name of a type nature as written in a generic declaration, e.g. `ANY_NUM`
+fn nature_name(nature: TypeNature) -> &'static str {
+    match nature {
+        TypeNature::Any => "ANY",
+        TypeNature::Derived => "ANY_DERIVED",
+        TypeNature::Elementary => "ANY_ELEMENTARY",
+        TypeNature::Magnitude => "ANY_MAGNITUDE",
+        TypeNature::Num => "ANY_NUM",
+        TypeNature::Real => "ANY_REAL",
+        TypeNature::Int => "ANY_INT",
+        TypeNature::Signed => "ANY_SIGNED",
+        TypeNature::Unsigned => "ANY_UNSIGNED",
+        TypeNature::Duration => "ANY_DURATION",
+        TypeNature::Bit => "ANY_BIT",
+        TypeNature::Chars => "ANY_CHARS",
+        TypeNature::String => "ANY_STRING",
+        TypeNature::Char => "ANY_CHAR",
+        TypeNature::Date => "ANY_DATE",
+        TypeNature::__VLA => "__ANY_VLA",
+    }
+}
+
+fn trivia_text(trivia: &Trivia) -> &str {
+    // a line comment ends with the line, its trailing whitespace is not kept
+    if trivia.text.starts_with("//") {
+        trivia.text.trim_end()
+    } else {
+        &trivia.text
+    }
+}
+
+/// Returns true if the whitespace in front of the given offset contains an empty line
+fn has_empty_line_before(src: &str, offset: usize) -> bool {
+    let Some(before) = src.get(..offset) else { return false };
+    before[before.trim_end().len()..].matches('\n').count() > 1
+}
+
 fn pou_keywords(kind: &PouType) -> (&'static str, &'static str) {
     match kind {
         PouType::Program => ("PROGRAM", "END_PROGRAM"),
@@ -279,8 +1267,11 @@ impl AstVisitor for AstSerializer<'_> {
 
     fn visit_default_value(&mut self, _stmt: &DefaultValue, _node: &AstNode) {}
 
-    fn visit_literal(&mut self, stmt: &AstLiteral, _node: &AstNode) {
+    fn visit_literal(&mut self, stmt: &AstLiteral, node: &AstNode) {
         use crate::literals::AstLiteral;
+        if self.source.is_some() && self.format_literal(stmt, node) {
+            return;
+        }
         match stmt {
             AstLiteral::Integer(value) => self.result.push_str(&value.to_string()),
             AstLiteral::Real(value) => self.result.push_str(value),
@@ -298,6 +1289,12 @@ impl AstVisitor for AstSerializer<'_> {
     }
 
     fn visit_multiplied_statement(&mut self, stmt: &MultipliedStatement, _node: &AstNode) {
+        if self.source.is_some() {
+            self.result.push_str(&format!("{}(", stmt.multiplier));
+            stmt.element.walk(self);
+            self.result.push(')');
+            return;
+        }
         stmt.walk(self)
     }
 
@@ -342,24 +1339,33 @@ impl AstVisitor for AstSerializer<'_> {
         self.result.push_str(stmt);
     }
 
-    fn visit_direct_access(&mut self, stmt: &DirectAccess, _node: &AstNode) {
+    fn visit_direct_access(&mut self, stmt: &DirectAccess, node: &AstNode) {
+        // the bit access `x.1` is kept as it was written rather than as `x.%X1`
+        if let Some(text) = self.source_text(&node.get_location()) {
+            self.result.push_str(text);
+            return;
+        }
         stmt.walk(self)
     }
 
     fn visit_hardware_access(&mut self, stmt: &HardwareAccess, _node: &AstNode) {
+        if self.source.is_some() {
+            self.result.push_str(&hardware_address(stmt));
+            return;
+        }
         stmt.walk(self)
     }
 
     fn visit_binary_expression(&mut self, stmt: &BinaryExpression, _node: &AstNode) {
         stmt.left.walk(self);
         self.result.push(' ');
-        self.result.push_str(&stmt.operator.to_string());
+        self.push_keyword(&stmt.operator.to_string());
         self.result.push(' ');
         stmt.right.walk(self);
     }
 
     fn visit_unary_expression(&mut self, stmt: &UnaryExpression, _node: &AstNode) {
-        let op = stmt.operator.to_string();
+        let op = self.keyword(&stmt.operator.to_string());
         self.result.push_str(&op);
         // Word-based operators (NOT, MINUS as identifier) need a trailing space.
         if op.chars().next().is_some_and(|c| c.is_alphabetic()) {
@@ -371,7 +1377,7 @@ impl AstVisitor for AstSerializer<'_> {
     fn visit_expression_list(&mut self, stmt: &Vec<AstNode>, _node: &AstNode) {
         let len = stmt.iter().filter(|stmt| !stmt.is_empty_statement()).count();
         let stmt = stmt.iter().filter(|stmt| !stmt.is_empty_statement());
-        if self.is_in_paren {
+        if self.is_in_paren || self.source.is_some() {
             for (i, node) in stmt.enumerate() {
                 if i > 0 {
                     self.result.push_str(", ");
@@ -398,10 +1404,20 @@ impl AstVisitor for AstSerializer<'_> {
     }
 
     fn visit_range_statement(&mut self, stmt: &RangeStatement, _node: &AstNode) {
+        if self.source.is_some() {
+            stmt.start.walk(self);
+            self.result.push_str("..");
+            stmt.end.walk(self);
+            return;
+        }
         stmt.walk(self)
     }
 
-    fn visit_vla_range_statement(&mut self, _node: &AstNode) {}
+    fn visit_vla_range_statement(&mut self, _node: &AstNode) {
+        if self.source.is_some() {
+            self.result.push('*');
+        }
+    }
 
     fn visit_assignment(&mut self, stmt: &Assignment, _node: &AstNode) {
         stmt.left.walk(self);
@@ -421,7 +1437,15 @@ impl AstVisitor for AstSerializer<'_> {
         stmt.right.walk(self);
     }
 
-    fn visit_call_statement(&mut self, stmt: &CallStatement, _node: &AstNode) {
+    fn visit_call_statement(&mut self, stmt: &CallStatement, node: &AstNode) {
+        if self.source.is_some() {
+            if let Some([base, exponent]) = exponentiation_operands(stmt, node) {
+                base.walk(self);
+                self.result.push_str(" ** ");
+                exponent.walk(self);
+                return;
+            }
+        }
         stmt.operator.walk(self);
         self.result.push('(');
         self.is_in_paren = true;
@@ -515,10 +1539,18 @@ impl AstVisitor for AstSerializer<'_> {
     }
 
     fn visit_exit_statement(&mut self, _node: &AstNode) {
+        if self.source.is_some() {
+            self.push_keyword("EXIT");
+            return;
+        }
         self.result.push_str("EXIT;");
     }
 
     fn visit_continue_statement(&mut self, _node: &AstNode) {
+        if self.source.is_some() {
+            self.push_keyword("CONTINUE");
+            return;
+        }
         self.result.push_str("CONTINUE;");
     }
 
@@ -530,7 +1562,7 @@ impl AstVisitor for AstSerializer<'_> {
             stmt.walk(self);
             self.result.push_str(" THEN RETURN; END_IF");
         } else {
-            self.result.push_str("RETURN");
+            self.push_keyword("RETURN");
         }
     }
 
@@ -552,12 +1584,15 @@ impl AstVisitor for AstSerializer<'_> {
         self.result.push_str(&format!("alloca {}: {}", stmt.name, stmt.reference_type));
     }
 
-    fn visit_super(&mut self, _stmt: &AstStatement, _node: &AstNode) {
-        self.result.push_str("SUPER");
+    fn visit_super(&mut self, stmt: &AstStatement, _node: &AstNode) {
+        self.push_keyword("SUPER");
+        if self.source.is_some() && matches!(stmt, AstStatement::Super(Some(_))) {
+            self.result.push('^');
+        }
     }
 
     fn visit_this(&mut self, _stmt: &AstStatement, _node: &AstNode) {
-        self.result.push_str("THIS");
+        self.push_keyword("THIS");
     }
 }
 
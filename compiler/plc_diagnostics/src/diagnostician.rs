@@ -1,13 +1,16 @@
+use plc_source::source_location::SourceLocation;
 use rustc_hash::FxHashMap;
 
 use crate::{
     diagnostics::{
         diagnostics_registry::{DiagnosticsConfiguration, DiagnosticsRegistry},
-        Diagnostic, Severity,
+        lints::LintSettings,
+        Diagnostic, Severity, Suggestion,
     },
     reporter::{
         clang::ClangFormatDiagnosticReporter, codespan::CodeSpanDiagnosticReporter,
-        null::NullDiagnosticReporter, DiagnosticReporter, ResolvedDiagnostics, ResolvedLocation,
+        json::JsonDiagnosticReporter, null::NullDiagnosticReporter, sarif::SarifDiagnosticReporter,
+        DiagnosticReporter, ResolvedDiagnostics, ResolvedEdit, ResolvedLocation, ResolvedSuggestion,
     },
 };
 
@@ -17,6 +20,10 @@ pub struct Diagnostician {
     reporter: Box<dyn DiagnosticReporter>,
     assessor: Box<dyn DiagnosticAssessor>,
     filename_fileid_mapping: FxHashMap<String, usize>,
+    /// the settings of the lint rules, the lints are toggled through the assessor like any other diagnostic
+    lint_settings: LintSettings,
+    /// the suggestions of the reported diagnostics, only collected if requested (see `plc fix`)
+    suggestions: Option<Vec<Suggestion>>,
 }
 
 impl Diagnostician {
@@ -33,39 +40,59 @@ impl Diagnostician {
         file_name.and_then(|it| self.filename_fileid_mapping.get(it).cloned())
     }
 
+    fn resolve_location(&self, location: &SourceLocation) -> ResolvedLocation {
+        ResolvedLocation {
+            file_handle: self
+                .get_file_handle(location.get_file_name().or(Some("<internal>")))
+                .unwrap_or(usize::MAX),
+            span: location.get_span().clone(),
+        }
+    }
+
     /// Assess and reports the given diagnostics.
     pub fn handle(&mut self, diagnostics: &[Diagnostic]) -> Severity {
-        let resolved_diagnostics = diagnostics
-            .iter()
-            .fold(vec![], |mut acc, d| {
-                acc.push(d);
-                acc.extend(d.get_sub_diagnostics());
-                acc
-            })
+        let all_diagnostics = diagnostics.iter().fold(vec![], |mut acc, d| {
+            acc.push(d);
+            acc.extend(d.get_sub_diagnostics());
+            acc
+        });
+        let resolved_diagnostics = all_diagnostics
             .iter()
             .map(|d| ResolvedDiagnostics {
                 code: d.get_error_code().to_string(),
                 message: d.get_message().to_string(),
                 severity: self.assess(d),
-                main_location: ResolvedLocation {
-                    file_handle: self
-                        .get_file_handle(d.get_location().get_file_name().or(Some("<internal>")))
-                        .unwrap_or(usize::MAX),
-                    span: d.get_location().get_span().clone(),
-                },
-                additional_locations: d.get_secondary_locations().map(|it| {
-                    it.iter()
-                        .map(|l| ResolvedLocation {
-                            file_handle: self
-                                .get_file_handle(l.get_file_name().or(Some("<internal>")))
-                                .unwrap_or(usize::MAX),
-                            span: l.get_span().clone(),
-                        })
-                        .collect()
-                }),
+                main_location: self.resolve_location(&d.get_location()),
+                additional_locations: d
+                    .get_secondary_locations()
+                    .map(|it| it.iter().map(|l| self.resolve_location(l)).collect()),
+                suggestions: d
+                    .get_suggestions()
+                    .iter()
+                    .map(|it| ResolvedSuggestion {
+                        message: it.message.clone(),
+                        edits: it
+                            .edits
+                            .iter()
+                            .map(|edit| ResolvedEdit {
+                                location: self.resolve_location(&edit.location),
+                                replacement: edit.replacement.clone(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
             })
             .collect::<Vec<_>>();
 
+        if let Some(suggestions) = self.suggestions.as_mut() {
+            let reported = all_diagnostics.iter().zip(&resolved_diagnostics);
+            suggestions.extend(
+                reported
+                    .filter(|(_, resolved)| resolved.severity > Severity::Ignore)
+                    .flat_map(|(d, _)| d.get_suggestions().iter().cloned()),
+            );
+        }
+
         self.report(resolved_diagnostics.as_slice());
 
         resolved_diagnostics.iter().map(|it| it.severity).max().unwrap_or_default()
@@ -77,6 +104,8 @@ impl Diagnostician {
             assessor: Box::<DiagnosticsRegistry>::default(),
             reporter: Box::<NullDiagnosticReporter>::default(),
             filename_fileid_mapping: FxHashMap::default(),
+            lint_settings: LintSettings::default(),
+            suggestions: None,
         }
     }
 
@@ -86,6 +115,8 @@ impl Diagnostician {
             assessor: Box::<DiagnosticsRegistry>::default(),
             reporter: Box::new(CodeSpanDiagnosticReporter::buffered()),
             filename_fileid_mapping: FxHashMap::default(),
+            lint_settings: LintSettings::default(),
+            suggestions: None,
         }
     }
 
@@ -95,6 +126,30 @@ impl Diagnostician {
             reporter: Box::<ClangFormatDiagnosticReporter>::default(),
             assessor: Box::<DiagnosticsRegistry>::default(),
             filename_fileid_mapping: FxHashMap::default(),
+            lint_settings: LintSettings::default(),
+            suggestions: None,
+        }
+    }
+
+    /// Creates a json-diagnostician that reports every diagnostic as a JSON object on its own line
+    pub fn json_diagnostician() -> Diagnostician {
+        Diagnostician {
+            reporter: Box::<JsonDiagnosticReporter>::default(),
+            assessor: Box::<DiagnosticsRegistry>::default(),
+            filename_fileid_mapping: FxHashMap::default(),
+            lint_settings: LintSettings::default(),
+            suggestions: None,
+        }
+    }
+
+    /// Creates a sarif-diagnostician that reports all diagnostics as a single SARIF log
+    pub fn sarif_diagnostician() -> Diagnostician {
+        Diagnostician {
+            reporter: Box::<SarifDiagnosticReporter>::default(),
+            assessor: Box::<DiagnosticsRegistry>::default(),
+            filename_fileid_mapping: FxHashMap::default(),
+            lint_settings: LintSettings::default(),
+            suggestions: None,
         }
     }
 
@@ -105,6 +160,33 @@ impl Diagnostician {
         res
     }
 
+    pub fn with_lint_settings(self, lint_settings: LintSettings) -> Self {
+        let mut res = self;
+        res.lint_settings = lint_settings;
+        res
+    }
+
+    pub fn get_lint_settings(&self) -> &LintSettings {
+        &self.lint_settings
+    }
+
+    /// Collects the suggestions of all diagnostics reported from now on, see [`Self::take_suggestions`]
+    pub fn collect_suggestions(&mut self) {
+        self.suggestions.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the suggestions of the reported diagnostics collected so far
+    pub fn take_suggestions(&mut self) -> Vec<Suggestion> {
+        self.suggestions.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Replaces the reporter, e.g. to collect diagnostics instead of printing them
+    pub fn with_reporter(self, reporter: Box<dyn DiagnosticReporter>) -> Self {
+        let mut res = self;
+        res.reporter = reporter;
+        res
+    }
+
     /// Explain the error with the given code by consulting the diagnostics registry
     pub fn explain(&self, error: &str) -> String {
         self.assessor.explain(error)
@@ -153,6 +235,8 @@ impl Default for Diagnostician {
             reporter: Box::<CodeSpanDiagnosticReporter>::default(),
             assessor: Box::<DiagnosticsRegistry>::default(),
             filename_fileid_mapping: FxHashMap::default(),
+            lint_settings: LintSettings::default(),
+            suggestions: None,
         }
     }
 }
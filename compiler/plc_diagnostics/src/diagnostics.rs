@@ -13,6 +13,7 @@ use plc_source::{
 };
 
 pub mod diagnostics_registry;
+pub mod lints;
 
 pub const INTERNAL_LLVM_ERROR: &str = "internal llvm codegen error";
 
@@ -49,6 +50,29 @@ pub struct DiagnosticsInner {
     pub sub_diagnostics: Vec<Diagnostic>,
     /// If the diagnostic is caused by an error, this field contains the original error
     pub internal_error: Option<anyhow::Error>,
+    /// Edits that fix the reported issue, applied by `plc fix`
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A suggestion how to fix the issue of a diagnostic. The issue is fixed by applying all of its edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Describes the fix, e.g. ``insert `END_IF` ``
+    pub message: String,
+    pub edits: Vec<SuggestedEdit>,
+}
+
+/// Replaces the text at a location, an empty location inserts the replacement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuggestedEdit {
+    pub location: SourceLocation,
+    pub replacement: String,
+}
+
+impl SuggestedEdit {
+    pub fn new(location: impl Into<SourceLocation>, replacement: impl Into<String>) -> Self {
+        SuggestedEdit { location: location.into(), replacement: replacement.into() }
+    }
 }
 
 impl Deref for Diagnostic {
@@ -87,6 +111,7 @@ impl Diagnostic {
             error_code: "E001", //Default error if none specified
             sub_diagnostics: Default::default(),
             internal_error: Default::default(),
+            suggestions: Default::default(),
         };
         Self { inner: Box::new(inner) }
     }
@@ -134,6 +159,25 @@ impl Diagnostic {
         self
     }
 
+    /// Suggests to fix the diagnostic by applying the given edits
+    pub fn with_suggestion(mut self, message: impl Into<String>, edits: Vec<SuggestedEdit>) -> Self {
+        self.suggestions.push(Suggestion { message: message.into(), edits });
+        self
+    }
+
+    /// Suggests to fix the diagnostic by replacing the text at the given location
+    pub fn with_suggested_edit<T>(
+        self,
+        message: impl Into<String>,
+        location: T,
+        replacement: impl Into<String>,
+    ) -> Self
+    where
+        T: Into<SourceLocation>,
+    {
+        self.with_suggestion(message, vec![SuggestedEdit::new(location, replacement)])
+    }
+
     pub fn from_serde_error(error: serde_json::Error, source: &SourceCode) -> Self {
         let factory = SourceLocationFactory::for_source(source);
         let line = error.line();
@@ -192,6 +236,10 @@ impl Diagnostic {
         self.error_code
     }
 
+    pub fn get_suggestions(&self) -> &[Suggestion] {
+        &self.suggestions
+    }
+
     pub fn get_sub_diagnostics(&self) -> Vec<&Diagnostic> {
         let mut diagnostics = vec![];
         self.sub_diagnostics.iter().for_each(|d| {
@@ -493,8 +541,56 @@ impl Diagnostic {
 #[cfg(test)]
 mod tests {
     use codespan_reporting::files::{Location, SimpleFile};
-
-    use crate::{diagnostics::Severity, reporter::clang::ClangFormatDiagnosticReporter};
+    use plc_source::source_location::{CodeSpan, TextLocation};
+
+    use crate::{
+        diagnostics::Severity,
+        reporter::{
+            clang::ClangFormatDiagnosticReporter, codespan::CodeSpanDiagnosticReporter,
+            json::JsonDiagnosticReporter, sarif::SarifDiagnosticReporter, DiagnosticReporter,
+            ResolvedDiagnostics, ResolvedEdit, ResolvedLocation, ResolvedSuggestion,
+        },
+    };
+
+    const SOURCE: &str = "PROGRAM main\n    x := 1;\nEND_PROGRAM";
+
+    fn resolved_diagnostics(file_handle: usize) -> Vec<ResolvedDiagnostics> {
+        let span = |start: usize, end: usize| {
+            CodeSpan::from_text_info(TextLocation::new(0, 0, start), TextLocation::new(0, 0, end))
+        };
+        vec![
+            ResolvedDiagnostics {
+                code: "E048".into(),
+                message: "Could not resolve reference to x".into(),
+                severity: Severity::Error,
+                main_location: ResolvedLocation { file_handle, span: span(17, 18) },
+                additional_locations: Some(vec![ResolvedLocation { file_handle, span: span(8, 12) }]),
+                suggestions: vec![ResolvedSuggestion {
+                    message: "replace with `X`".into(),
+                    edits: vec![ResolvedEdit {
+                        location: ResolvedLocation { file_handle, span: span(17, 18) },
+                        replacement: "X".into(),
+                    }],
+                }],
+            },
+            ResolvedDiagnostics {
+                code: "E002".into(),
+                message: "Internal problem".into(),
+                severity: Severity::Warning,
+                main_location: ResolvedLocation { file_handle: usize::MAX, span: CodeSpan::None },
+                additional_locations: None,
+                suggestions: vec![],
+            },
+            ResolvedDiagnostics {
+                code: "E048".into(),
+                message: "Ignored".into(),
+                severity: Severity::Ignore,
+                main_location: ResolvedLocation { file_handle, span: span(17, 18) },
+                additional_locations: None,
+                suggestions: vec![],
+            },
+        ]
+    }
 
     #[test]
     fn test_build_diagnostic_msg() {
@@ -573,4 +669,112 @@ mod tests {
 
         assert_eq!(res, "error[E001]: This is an error");
     }
+
+    #[test]
+    fn json_reporter_reports_one_object_per_diagnostic() {
+        let mut reporter = JsonDiagnosticReporter::buffered();
+        let file_handle = reporter.register("test.st".into(), SOURCE.into());
+        reporter.report(&resolved_diagnostics(file_handle));
+
+        let buffer = reporter.buffer().unwrap();
+        let lines = buffer.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                concat!(
+                    r#"{"code":"E048","severity":"error","message":"Could not resolve reference to x","#,
+                    r#""primary":{"file":"test.st","offset":{"start":17,"end":18},"start":{"line":2,"column":5},"end":{"line":2,"column":6}},"#,
+                    r#""secondary":[{"file":"test.st","offset":{"start":8,"end":12},"start":{"line":1,"column":9},"end":{"line":1,"column":13}}],"#,
+                    r#""suggestions":[{"message":"replace with `X`","edits":[{"file":"test.st","offset":{"start":17,"end":18},"start":{"line":2,"column":5},"end":{"line":2,"column":6},"replacement":"X"}]}]}"#
+                ),
+                concat!(
+                    r#"{"code":"E002","severity":"warning","message":"Internal problem","#,
+                    r#""primary":{"file":null,"offset":null,"start":null,"end":null},"secondary":[],"suggestions":[]}"#
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn sarif_reporter_reports_a_log_with_rules_from_the_registry() {
+        let mut reporter = SarifDiagnosticReporter::buffered();
+        let file_handle = reporter.register("test.st".into(), SOURCE.into());
+        reporter.report(&resolved_diagnostics(file_handle));
+
+        let log: serde_json::Value = serde_json::from_str(&reporter.buffer().unwrap()).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["id"], "E048");
+        assert_eq!(rules[0]["shortDescription"]["text"], "Unresolved Reference");
+        assert_eq!(rules[1]["id"], "E002");
+        assert_eq!(rules[1]["shortDescription"]["text"], "General IO Error");
+        assert!(rules[1]["help"]["markdown"].as_str().unwrap().starts_with("# General IO Error"));
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "E048");
+        assert_eq!(results[0]["ruleIndex"], 0);
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["message"]["text"], "Could not resolve reference to x");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "test.st");
+        assert_eq!(
+            location["region"],
+            serde_json::json!({ "startLine": 2, "startColumn": 5, "endLine": 2, "endColumn": 6 })
+        );
+        let related = &results[0]["relatedLocations"][0];
+        assert_eq!(related["physicalLocation"]["region"]["startLine"], 1);
+        assert_eq!(related["physicalLocation"]["region"]["startColumn"], 9);
+        let fix = &results[0]["fixes"][0];
+        assert_eq!(fix["description"]["text"], "replace with `X`");
+        let change = &fix["artifactChanges"][0];
+        assert_eq!(change["artifactLocation"]["uri"], "test.st");
+        assert_eq!(change["replacements"][0]["deletedRegion"]["startColumn"], 5);
+        assert_eq!(change["replacements"][0]["insertedContent"]["text"], "X");
+
+        // internal diagnostics have no location
+        assert_eq!(results[1]["ruleIndex"], 1);
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(results[1]["locations"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn clang_reporter_reports_edits_as_fixits() {
+        let mut reporter = ClangFormatDiagnosticReporter::default();
+        let file_handle = reporter.register("test.st".into(), SOURCE.into());
+        let diagnostics = resolved_diagnostics(file_handle);
+        let edit = &diagnostics[0].suggestions[0].edits[0];
+
+        assert_eq!(
+            reporter.build_fixit_msg(&edit.location, &edit.replacement),
+            r#"fix-it:"test.st":{2:5-2:6}:"X""#
+        );
+        let insertion = ResolvedLocation {
+            file_handle,
+            span: CodeSpan::from_text_info(TextLocation::new(0, 0, 25), TextLocation::new(0, 0, 25)),
+        };
+        assert_eq!(
+            reporter.build_fixit_msg(&insertion, "END_IF\n    \"x\""),
+            r#"fix-it:"test.st":{3:1-3:1}:"END_IF\n    \"x\"""#
+        );
+
+        let file = SimpleFile::new("test.st".to_string(), "source".to_string());
+        let start = Location { line_number: 2, column_number: 5 };
+        assert_eq!(
+            reporter.build_suggestion_msg(Some(&file), Some(&start), "replace with `X`"),
+            "test.st:2:5: help: replace with `X`"
+        );
+    }
+
+    #[test]
+    fn rich_reporter_renders_suggestions_as_help() {
+        let mut reporter = CodeSpanDiagnosticReporter::buffered();
+        let file_handle = reporter.register("test.st".into(), SOURCE.into());
+        reporter.report(&resolved_diagnostics(file_handle)[..1]);
+
+        assert!(reporter.buffer().unwrap().contains("  = help: replace with `X`"));
+    }
 }
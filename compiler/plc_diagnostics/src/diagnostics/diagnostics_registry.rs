@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use crate::diagnostician::DiagnosticAssessor;
 
 use super::{
+    lints::LintSettings,
     Diagnostic,
     Severity::{self, Error, Ignore, Info, Warning},
 };
@@ -54,6 +55,11 @@ impl DiagnosticsRegistry {
         }
         self
     }
+
+    /// Returns the markdown description of the given error code, `None` if the code is unknown
+    pub(crate) fn get_description(&self, code: &str) -> Option<&'static str> {
+        self.0.get(code).map(|it| it.description)
+    }
 }
 
 impl DiagnosticAssessor for DiagnosticsRegistry {
@@ -88,6 +94,16 @@ impl DiagnosticAssessor for DiagnosticsRegistry {
 #[serde(transparent)]
 pub struct DiagnosticsConfiguration(FxHashMap<Severity, Vec<String>>);
 
+/// The content of an error configuration file, the severities of the diagnostics and the settings of
+/// the lint rules
+#[derive(Deserialize, Default)]
+pub struct ErrorConfiguration {
+    #[serde(flatten)]
+    pub diagnostics: DiagnosticsConfiguration,
+    #[serde(default)]
+    pub lints: LintSettings,
+}
+
 impl From<&DiagnosticsRegistry> for DiagnosticsConfiguration {
     fn from(registry: &DiagnosticsRegistry) -> Self {
         let mut res = DiagnosticsConfiguration::default();
@@ -250,6 +266,33 @@ lazy_static! {
         E146,   Error,      include_str!("./error_codes/E146.md"),  // Unknown CFC block type
         E147,   Error,      include_str!("./error_codes/E147.md"),  // Undeclared CFC block output
         E148,   Warning,    include_str!("./error_codes/E148.md"),  // Temporal literal overflow or underflow
+        E149,   Error,      include_str!("./error_codes/E149.md"),  // Program cannot be run (plc run)
+        E150,   Error,      include_str!("./error_codes/E150.md"),  // Input cannot be executed (plc repl)
+        E151,   Error,      include_str!("./error_codes/E151.md"),  // Source file is not formatted (plc fmt --check)
+        E152,   Error,      include_str!("./error_codes/E152.md"),  // Source file cannot be formatted safely
+        E153,   Ignore,     include_str!("./error_codes/E153.md"),  // POU or type is unreachable from any entry point
+        E154,   Error,      include_str!("./error_codes/E154.md"),  // Stack usage of a program exceeds the configured limit
+        E155,   Ignore,     include_str!("./error_codes/E155.md"),  // Lint: name does not match the configured naming pattern
+        E156,   Ignore,     include_str!("./error_codes/E156.md"),  // Lint: unused variable
+        E157,   Ignore,     include_str!("./error_codes/E157.md"),  // Lint: unused parameter
+        E158,   Ignore,     include_str!("./error_codes/E158.md"),  // Lint: variable shadows a global or member
+        E159,   Ignore,     include_str!("./error_codes/E159.md"),  // Lint: magic number in body
+        E160,   Warning,    include_str!("./error_codes/E160.md"),  // Return value not assigned on every path
+        E161,   Warning,    include_str!("./error_codes/E161.md"),  // Output is never written
+        E162,   Warning,    include_str!("./error_codes/E162.md"),  // Temporary variable read before it is assigned
+        E163,   Warning,    include_str!("./error_codes/E163.md"),  // Unreachable code
+        E164,   Warning,    include_str!("./error_codes/E164.md"),  // Condition is always TRUE or FALSE
+        E165,   Warning,    include_str!("./error_codes/E165.md"),  // Case label covered by earlier labels
+        E166,   Warning,    include_str!("./error_codes/E166.md"),  // FOR loop is never executed
+        E167,   Warning,    include_str!("./error_codes/E167.md"),  // CASE does not cover all enum variants
+        E168,   Ignore,     include_str!("./error_codes/E168.md"),  // Lint: identifier differs in case from its declaration
+        E169,   Error,      include_str!("./error_codes/E169.md"),  // Invalid value of the symbol attribute
+        E170,   Warning,    include_str!("./error_codes/E170.md"),  // C declaration cannot be declared in ST
+        E171,   Error,      include_str!("./error_codes/E171.md"),  // Online change cannot migrate the instance data
+        E172,   Error,      include_str!("./error_codes/E172.md"),  // Instance mixes retain and persistent members
+        E173,   Error,      include_str!("./error_codes/E173.md"),  // Shared library cannot be found (plc run, plc repl)
+        E174,   Error,      include_str!("./error_codes/E174.md"),  // Variable cannot be watched (plc run --watch)
+        E175,   Error,      include_str!("./error_codes/E175.md"),  // Retain values with the same hash
     );
 }
 
@@ -257,7 +300,11 @@ lazy_static! {
 mod tests {
     use crate::{
         diagnostician::DiagnosticAssessor,
-        diagnostics::{diagnostics_registry::DiagnosticsConfiguration, Diagnostic, Severity},
+        diagnostics::{
+            diagnostics_registry::{DiagnosticsConfiguration, ErrorConfiguration},
+            lints::{LintSettings, NamingKind},
+            Diagnostic, Severity,
+        },
     };
 
     use super::DiagnosticsRegistry;
@@ -287,6 +334,35 @@ mod tests {
         assert_eq!(configuration.get(&Severity::Ignore).unwrap(), &["E010"]);
     }
 
+    #[test]
+    fn deserialize_json_with_lint_settings() {
+        let error_config = r#"{
+            "warning": ["E155", "E159"],
+            "lints": {
+                "naming": { "function_block_instance": "^fb" },
+                "allowed_numbers": [0, 1, 2.5]
+            }
+        }"#;
+
+        let ErrorConfiguration { diagnostics: DiagnosticsConfiguration(configuration), lints } =
+            serde_json::de::from_str(error_config).unwrap();
+        assert_eq!(configuration.get(&Severity::Warning).unwrap(), &["E155", "E159"]);
+        assert_eq!(lints.naming.get(&NamingKind::FunctionBlockInstance).unwrap().as_str(), "^fb");
+        assert_eq!(lints.allowed_numbers, [0.0, 1.0, 2.5]);
+
+        // the lint settings are optional
+        let ErrorConfiguration { lints, .. } = serde_json::de::from_str(r#"{ "error": ["E001"] }"#).unwrap();
+        assert_eq!(lints, LintSettings::default());
+
+        // unknown declaration kinds are rejected
+        let error_config = r#"{ "lints": { "naming": { "unknown": "^x" } } }"#;
+        assert!(serde_json::de::from_str::<ErrorConfiguration>(error_config).is_err());
+
+        // invalid patterns are rejected
+        let error_config = r#"{ "lints": { "naming": { "program": "(" } } }"#;
+        assert!(serde_json::de::from_str::<ErrorConfiguration>(error_config).is_err());
+    }
+
     #[test]
     fn overridden_errors_are_assessed_correctly() {
         let error_config = r#"{
@@ -0,0 +1,128 @@
+//! Settings of the lint rules.
+//!
+//! Lints report code that is valid but does not follow a project's conventions. Like any other
+//! diagnostic they are toggled by giving their codes a severity in the error configuration, their
+//! settings are read from its `lints` section:
+//!
+//! ```json
+//! {
+//!     "warning": ["E155", "E156", "E157", "E158", "E159"],
+//!     "lints": {
+//!         "naming": { "function_block_instance": "^fb[A-Z]", "program": "^PRG_" },
+//!         "allowed_numbers": [0, 1, 100]
+//!     }
+//! }
+//! ```
+//!
+//! The naming patterns are compiled when the settings are read, an invalid pattern is reported like
+//! any other malformed error configuration.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// The settings of the lint rules, see the module documentation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintSettings {
+    /// the regular expressions the names of each kind of declaration have to match (`E155`)
+    pub naming: BTreeMap<NamingKind, NamingPattern>,
+    /// the numbers which may be used in a body without being reported as magic numbers (`E159`)
+    pub allowed_numbers: Vec<f64>,
+}
+
+impl Default for LintSettings {
+    fn default() -> Self {
+        LintSettings { naming: BTreeMap::new(), allowed_numbers: vec![0.0, 1.0] }
+    }
+}
+
+/// A regular expression the names of a kind of declaration have to match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct NamingPattern(Regex);
+
+impl NamingPattern {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(NamingPattern)
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        self.0.is_match(name)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl TryFrom<String> for NamingPattern {
+    type Error = String;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        NamingPattern::new(&pattern).map_err(|err| format!("invalid naming pattern `{pattern}`: {err}"))
+    }
+}
+
+impl From<NamingPattern> for String {
+    fn from(pattern: NamingPattern) -> Self {
+        pattern.as_str().to_string()
+    }
+}
+
+impl PartialEq for NamingPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+/// The kinds of declarations a naming pattern can be configured for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingKind {
+    Program,
+    Function,
+    FunctionBlock,
+    Class,
+    Method,
+    Action,
+    Interface,
+    Struct,
+    Enum,
+    Global,
+    /// variables of `CONSTANT` blocks, takes precedence over the kind of the block
+    Constant,
+    Input,
+    Output,
+    InOut,
+    Local,
+    Temp,
+    /// variables of a function block type, takes precedence over `constant` and the kind of the block
+    FunctionBlockInstance,
+}
+
+impl Display for NamingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            NamingKind::Program => "program",
+            NamingKind::Function => "function",
+            NamingKind::FunctionBlock => "function_block",
+            NamingKind::Class => "class",
+            NamingKind::Method => "method",
+            NamingKind::Action => "action",
+            NamingKind::Interface => "interface",
+            NamingKind::Struct => "struct",
+            NamingKind::Enum => "enum",
+            NamingKind::Global => "global",
+            NamingKind::Constant => "constant",
+            NamingKind::Input => "input",
+            NamingKind::Output => "output",
+            NamingKind::InOut => "in_out",
+            NamingKind::Local => "local",
+            NamingKind::Temp => "temp",
+            NamingKind::FunctionBlockInstance => "function_block_instance",
+        };
+        write!(f, "{kind}")
+    }
+}
@@ -1,10 +1,13 @@
+use codespan_reporting::files::{Files, Location, SimpleFiles};
 use plc_source::source_location::CodeSpan;
 
 use crate::diagnostics::Severity;
 
 pub mod clang;
 pub mod codespan;
+pub mod json;
 pub mod null;
+pub mod sarif;
 
 /// the DiagnosticReporter decides on the format and where to report the diagnostic to.
 /// possible implementations could print to either std-out, std-err or a file, etc.
@@ -31,6 +34,20 @@ impl ResolvedLocation {
     pub(crate) fn is_internal(&self) -> bool {
         self.span == CodeSpan::None
     }
+
+    /// Returns the name of the file this location points into, `None` if the file is unknown
+    pub(crate) fn get_file_name<'a>(&self, files: &'a SimpleFiles<String, String>) -> Option<&'a str> {
+        files.get(self.file_handle).ok().map(|it| it.name().as_str())
+    }
+
+    /// Returns the start and end of this location as line and column numbers,
+    /// `None` if the location has no text range (e.g. internal or diagram locations)
+    pub(crate) fn get_line_range(&self, files: &SimpleFiles<String, String>) -> Option<(Location, Location)> {
+        let range = self.span.to_range()?;
+        let start = files.location(self.file_handle, range.start).ok()?;
+        let end = files.location(self.file_handle, range.end).ok()?;
+        Some((start, end))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -40,4 +57,17 @@ pub struct ResolvedDiagnostics {
     pub severity: Severity,
     pub main_location: ResolvedLocation,
     pub additional_locations: Option<Vec<ResolvedLocation>>,
+    pub suggestions: Vec<ResolvedSuggestion>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedSuggestion {
+    pub message: String,
+    pub edits: Vec<ResolvedEdit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedEdit {
+    pub location: ResolvedLocation,
+    pub replacement: String,
 }
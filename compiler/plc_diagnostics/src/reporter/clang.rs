@@ -2,7 +2,7 @@ use codespan_reporting::files::{Files, Location, SimpleFile, SimpleFiles};
 
 use crate::diagnostics::Severity;
 
-use super::{DiagnosticReporter, ResolvedDiagnostics};
+use super::{DiagnosticReporter, ResolvedDiagnostics, ResolvedLocation};
 
 /// A reporter that reports diagnostics in clang format. Specifically the messages have the following
 /// form `<filename>:<range>: <severity>: <message>`.
@@ -44,6 +44,13 @@ impl DiagnosticReporter for ClangFormatDiagnosticReporter {
             );
 
             eprintln!("{res}");
+
+            for suggestion in &diagnostic.suggestions {
+                eprintln!("{}", self.build_suggestion_msg(file, start.as_ref(), &suggestion.message));
+                for edit in &suggestion.edits {
+                    eprintln!("{}", self.build_fixit_msg(&edit.location, &edit.replacement));
+                }
+            }
         }
     }
     fn register(&mut self, path: String, src: String) -> usize {
@@ -100,4 +107,48 @@ impl ClangFormatDiagnosticReporter {
 
         str
     }
+
+    /// returns the help message of a suggestion in clang format
+    /// file-name:line:column: help: message
+    pub(crate) fn build_suggestion_msg(
+        &self,
+        file: Option<&SimpleFile<String, String>>,
+        start: Option<&Location>,
+        msg: &str,
+    ) -> String {
+        match (file, start) {
+            (Some(f), Some(s)) => format!("{}:{}:{}: help: {msg}", f.name(), s.line_number, s.column_number),
+            (Some(f), None) => format!("{}: help: {msg}", f.name()),
+            _ => format!("help: {msg}"),
+        }
+    }
+
+    /// returns an edit in the parseable fix-it format of clang
+    /// fix-it:"file-name":{start-line:start-column-end-line:end-column}:"replacement"
+    pub(crate) fn build_fixit_msg(&self, location: &ResolvedLocation, replacement: &str) -> String {
+        let file = location.get_file_name(&self.files).unwrap_or_default();
+        let range = location
+            .get_line_range(&self.files)
+            .map(|(s, e)| {
+                format!("{}:{}-{}:{}", s.line_number, s.column_number, e.line_number, e.column_number)
+            })
+            .unwrap_or_default();
+        format!("fix-it:{}:{{{range}}}:{}", escape(file), escape(replacement))
+    }
+}
+
+/// quotes the given text, escaping it like a C string literal
+fn escape(text: &str) -> String {
+    let mut escaped = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
 }
@@ -139,6 +139,8 @@ impl DiagnosticReporter for CodeSpanDiagnosticReporter {
                 }));
             }
 
+            notes.extend(d.suggestions.iter().map(|it| format!("help: {}", it.message)));
+
             let diag = diagnostic_factory
                 .with_labels(labels)
                 .with_notes(notes)
@@ -0,0 +1,194 @@
+use codespan_reporting::files::{Location, SimpleFiles};
+use serde::Serialize;
+
+use crate::diagnostics::Severity;
+
+use super::{DiagnosticReporter, ResolvedDiagnostics, ResolvedLocation};
+
+/// A reporter that reports every diagnostic as a single line JSON object, so the output
+/// can be consumed by editors and CI tools. An object has the following form:
+/// `{"code":"E001","severity":"error","message":"...","primary":{..},"secondary":[..],"suggestions":[..]}`
+pub struct JsonDiagnosticReporter {
+    files: SimpleFiles<String, String>,
+    /// the reported lines are collected here instead of being printed to std-err
+    buffer: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    code: &'a str,
+    severity: Severity,
+    message: &'a str,
+    primary: JsonSpan<'a>,
+    secondary: Vec<JsonSpan<'a>>,
+    suggestions: Vec<JsonSuggestion<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonSuggestion<'a> {
+    message: &'a str,
+    edits: Vec<JsonEdit<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonEdit<'a> {
+    #[serde(flatten)]
+    span: JsonSpan<'a>,
+    replacement: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonSpan<'a> {
+    /// the file the span points into, `None` for internal locations
+    file: Option<&'a str>,
+    /// the byte offsets of the span, `None` if the span has no text range
+    offset: Option<JsonOffset>,
+    start: Option<JsonPosition>,
+    end: Option<JsonPosition>,
+}
+
+#[derive(Serialize)]
+struct JsonOffset {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+}
+
+impl From<Location> for JsonPosition {
+    fn from(location: Location) -> Self {
+        JsonPosition { line: location.line_number, column: location.column_number }
+    }
+}
+
+impl JsonDiagnosticReporter {
+    fn new() -> Self {
+        JsonDiagnosticReporter { files: SimpleFiles::new(), buffer: None }
+    }
+
+    /// Creates a new reporter which stores its output in a buffer
+    pub fn buffered() -> Self {
+        JsonDiagnosticReporter { buffer: Some(vec![]), ..Self::new() }
+    }
+
+    fn to_span<'a>(&'a self, location: &ResolvedLocation) -> JsonSpan<'a> {
+        let (start, end) = location.get_line_range(&self.files).unzip();
+        JsonSpan {
+            file: location.get_file_name(&self.files),
+            offset: location.span.to_range().map(|it| JsonOffset { start: it.start, end: it.end }),
+            start: start.map(Into::into),
+            end: end.map(Into::into),
+        }
+    }
+
+    /// returns the given diagnostic as a single line JSON object
+    pub(crate) fn build_diagnostic_msg(&self, diagnostic: &ResolvedDiagnostics) -> String {
+        let json = JsonDiagnostic {
+            code: &diagnostic.code,
+            severity: diagnostic.severity,
+            message: &diagnostic.message,
+            primary: self.to_span(&diagnostic.main_location),
+            secondary: diagnostic.additional_locations.iter().flatten().map(|it| self.to_span(it)).collect(),
+            suggestions: diagnostic
+                .suggestions
+                .iter()
+                .map(|it| JsonSuggestion {
+                    message: &it.message,
+                    edits: it
+                        .edits
+                        .iter()
+                        .map(|edit| JsonEdit {
+                            span: self.to_span(&edit.location),
+                            replacement: &edit.replacement,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        };
+        serde_json::to_string(&json).expect("Cannot fail")
+    }
+}
+
+impl Default for JsonDiagnosticReporter {
+    fn default() -> Self {
+        JsonDiagnosticReporter::new()
+    }
+}
+
+impl DiagnosticReporter for JsonDiagnosticReporter {
+    fn report(&mut self, diagnostics: &[ResolvedDiagnostics]) {
+        for diagnostic in diagnostics.iter().filter(|it| it.severity > Severity::Ignore) {
+            let res = self.build_diagnostic_msg(diagnostic);
+            match self.buffer.as_mut() {
+                Some(buffer) => buffer.push(res),
+                None => eprintln!("{res}"),
+            }
+        }
+    }
+
+    fn register(&mut self, path: String, src: String) -> usize {
+        self.files.add(path, src)
+    }
+
+    fn buffer(&self) -> Option<String> {
+        self.buffer.as_ref().map(|it| it.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_source::{source_location::SourceLocationFactory, SourceCode};
+
+    use crate::{
+        diagnostician::Diagnostician,
+        diagnostics::{Diagnostic, SuggestedEdit},
+        reporter::DiagnosticReporter,
+    };
+
+    use super::JsonDiagnosticReporter;
+
+    const SRC: &str = "PROGRAM main\n    IF x THEN\nEND_PROGRAM\n";
+
+    fn report(diagnostics: &[Diagnostic]) -> String {
+        let mut diagnostician =
+            Diagnostician::json_diagnostician().with_reporter(Box::new(JsonDiagnosticReporter::buffered()));
+        diagnostician.register_file("main.st".to_string(), SRC.to_string());
+        diagnostician.handle(diagnostics);
+        diagnostician.buffer().unwrap()
+    }
+
+    fn missing_end_if() -> Diagnostic {
+        let factory = SourceLocationFactory::for_source(&SourceCode::new(SRC, "main.st"));
+        let end = SRC.find("END_PROGRAM").unwrap();
+        let if_start = SRC.find("IF").unwrap();
+        Diagnostic::new("Missing expected Token END_IF")
+            .with_error_code("E006")
+            .with_location(factory.create_range(end..end + "END_PROGRAM".len()))
+            .with_secondary_location(factory.create_range(if_start..if_start + 2))
+            .with_suggestion(
+                "insert `END_IF`",
+                vec![SuggestedEdit::new(factory.create_range(end..end), "END_IF\n")],
+            )
+    }
+
+    #[test]
+    fn every_diagnostic_is_reported_as_one_json_line() {
+        let internal = Diagnostic::new("Unresolved reference `x`").with_error_code("E048");
+        let report = report(&[missing_end_if(), internal]);
+
+        for line in report.lines() {
+            assert!(serde_json::from_str::<serde_json::Value>(line).is_ok(), "{line} is not a JSON object");
+        }
+        insta::assert_snapshot!(report);
+    }
+
+    #[test]
+    fn ignored_diagnostics_are_not_reported() {
+        let ignored = Diagnostic::new("`helper` is never called").with_error_code("E153");
+        assert_eq!(report(&[ignored]), "");
+    }
+}
@@ -0,0 +1,299 @@
+use codespan_reporting::files::SimpleFiles;
+use serde_json::{json, Value};
+
+use crate::diagnostics::{diagnostics_registry::DiagnosticsRegistry, Severity};
+
+use super::{DiagnosticReporter, ResolvedDiagnostics, ResolvedLocation, ResolvedSuggestion};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// A reporter that reports diagnostics as a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+/// log. Since a log is a single JSON document, the diagnostics are collected and the log is
+/// written to std-err once the reporter is dropped.
+/// Every reported error code becomes a rule of the log, described by its entry in the [`DiagnosticsRegistry`].
+pub struct SarifDiagnosticReporter {
+    files: SimpleFiles<String, String>,
+    registry: DiagnosticsRegistry,
+    /// the error codes of the reported diagnostics in the order of their first occurrence
+    rules: Vec<String>,
+    results: Vec<Value>,
+    /// whether anything was reported, a log is only written for runs which reported diagnostics
+    has_reported: bool,
+    /// whether the log is kept in a buffer instead of being written to std-err
+    is_buffered: bool,
+}
+
+impl SarifDiagnosticReporter {
+    fn new() -> Self {
+        SarifDiagnosticReporter {
+            files: SimpleFiles::new(),
+            registry: DiagnosticsRegistry::default(),
+            rules: vec![],
+            results: vec![],
+            has_reported: false,
+            is_buffered: false,
+        }
+    }
+
+    /// Creates a new reporter which keeps its log in a buffer
+    pub fn buffered() -> Self {
+        let mut reporter = Self::new();
+        reporter.is_buffered = true;
+        reporter
+    }
+
+    /// returns the index of the rule for the given code, adding the rule if it was not reported yet
+    fn get_rule_index(&mut self, code: &str) -> usize {
+        self.rules.iter().position(|it| it == code).unwrap_or_else(|| {
+            self.rules.push(code.to_string());
+            self.rules.len() - 1
+        })
+    }
+
+    fn to_rule(&self, code: &str) -> Value {
+        let Some(description) = self.registry.get_description(code) else {
+            return json!({ "id": code });
+        };
+
+        // the descriptions start with a `# Title` followed by the explanation
+        let mut paragraphs = description.split("\n\n").map(str::trim).filter(|it| !it.is_empty());
+        let title = paragraphs.next().unwrap_or_default().trim_start_matches('#').trim();
+        let summary = paragraphs.next().unwrap_or(title).replace('\n', " ");
+
+        json!({
+            "id": code,
+            "shortDescription": { "text": title },
+            "fullDescription": { "text": summary },
+            "help": { "text": description, "markdown": description },
+        })
+    }
+
+    fn to_artifact_location(&self, location: &ResolvedLocation) -> Option<Value> {
+        let file = location.get_file_name(&self.files)?;
+        Some(json!({ "uri": file.replace('\\', "/") }))
+    }
+
+    fn to_region(&self, location: &ResolvedLocation) -> Option<Value> {
+        let (start, end) = location.get_line_range(&self.files)?;
+        Some(json!({
+            "startLine": start.line_number,
+            "startColumn": start.column_number,
+            "endLine": end.line_number,
+            "endColumn": end.column_number,
+        }))
+    }
+
+    /// returns the physical location of the given location, `None` for internal locations
+    fn to_location(&self, location: &ResolvedLocation) -> Option<Value> {
+        let mut physical_location = json!({ "artifactLocation": self.to_artifact_location(location)? });
+        if let Some(region) = self.to_region(location) {
+            physical_location["region"] = region;
+        }
+
+        Some(json!({ "physicalLocation": physical_location }))
+    }
+
+    /// returns the suggestion as a fix, `None` if one of its edits does not point into a text
+    fn to_fix(&self, suggestion: &ResolvedSuggestion) -> Option<Value> {
+        let artifact_changes = suggestion
+            .edits
+            .iter()
+            .map(|edit| {
+                Some(json!({
+                    "artifactLocation": self.to_artifact_location(&edit.location)?,
+                    "replacements": [{
+                        "deletedRegion": self.to_region(&edit.location)?,
+                        "insertedContent": { "text": edit.replacement },
+                    }],
+                }))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(json!({
+            "description": { "text": suggestion.message },
+            "artifactChanges": artifact_changes,
+        }))
+    }
+
+    fn add_result(&mut self, diagnostic: &ResolvedDiagnostics) {
+        let level = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info | Severity::Ignore => "note",
+        };
+
+        let related_locations = diagnostic
+            .additional_locations
+            .iter()
+            .flatten()
+            .filter_map(|it| self.to_location(it))
+            .enumerate()
+            .map(|(id, mut location)| {
+                location["id"] = json!(id);
+                location["message"] = json!({ "text": "see also" });
+                location
+            })
+            .collect::<Vec<_>>();
+
+        let mut result = json!({
+            "ruleId": diagnostic.code,
+            "ruleIndex": self.get_rule_index(&diagnostic.code),
+            "level": level,
+            "message": { "text": diagnostic.message },
+            "locations": self.to_location(&diagnostic.main_location).into_iter().collect::<Vec<_>>(),
+            "relatedLocations": related_locations,
+        });
+        let fixes = diagnostic.suggestions.iter().filter_map(|it| self.to_fix(it)).collect::<Vec<_>>();
+        if !fixes.is_empty() {
+            result["fixes"] = json!(fixes);
+        }
+        self.results.push(result);
+    }
+
+    /// returns the SARIF log of all diagnostics reported so far
+    pub(crate) fn build_log(&self) -> String {
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "plc",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/PLC-lang/rusty",
+                        "rules": self.rules.iter().map(|it| self.to_rule(it)).collect::<Vec<_>>(),
+                    }
+                },
+                "results": self.results,
+            }],
+        });
+        serde_json::to_string_pretty(&log).expect("Cannot fail")
+    }
+}
+
+impl Default for SarifDiagnosticReporter {
+    fn default() -> Self {
+        SarifDiagnosticReporter::new()
+    }
+}
+
+impl DiagnosticReporter for SarifDiagnosticReporter {
+    fn report(&mut self, diagnostics: &[ResolvedDiagnostics]) {
+        self.has_reported = true;
+        for diagnostic in diagnostics.iter().filter(|it| it.severity > Severity::Ignore) {
+            self.add_result(diagnostic);
+        }
+    }
+
+    fn register(&mut self, path: String, src: String) -> usize {
+        self.files.add(path, src)
+    }
+
+    fn buffer(&self) -> Option<String> {
+        self.is_buffered.then(|| self.build_log())
+    }
+}
+
+impl Drop for SarifDiagnosticReporter {
+    fn drop(&mut self) {
+        if self.has_reported && !self.is_buffered {
+            eprintln!("{}", self.build_log());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_source::{source_location::SourceLocationFactory, SourceCode};
+    use serde_json::Value;
+
+    use crate::{
+        diagnostician::Diagnostician,
+        diagnostics::{diagnostics_registry::DiagnosticsRegistry, Diagnostic, SuggestedEdit},
+        reporter::DiagnosticReporter,
+    };
+
+    use super::SarifDiagnosticReporter;
+
+    const SRC: &str = "PROGRAM main\n    VAR x : INT; x : INT; END_VAR\n    IF x THEN\nEND_PROGRAM\n";
+
+    fn report(diagnostics: &[Diagnostic]) -> Value {
+        let mut diagnostician =
+            Diagnostician::sarif_diagnostician().with_reporter(Box::new(SarifDiagnosticReporter::buffered()));
+        diagnostician.register_file("main.st".to_string(), SRC.to_string());
+        diagnostician.handle(diagnostics);
+        serde_json::from_str(&diagnostician.buffer().unwrap()).unwrap()
+    }
+
+    fn diagnostics() -> Vec<Diagnostic> {
+        let factory = SourceLocationFactory::for_source(&SourceCode::new(SRC, "main.st"));
+        let range = |text: &str, nth: usize| {
+            let start = SRC.match_indices(text).nth(nth).unwrap().0;
+            factory.create_range(start..start + text.len())
+        };
+        let end = SRC.find("END_PROGRAM").unwrap();
+
+        vec![
+            Diagnostic::new("Missing expected Token END_IF")
+                .with_error_code("E006")
+                .with_location(range("END_PROGRAM", 0))
+                .with_secondary_location(range("IF", 0))
+                .with_suggestion(
+                    "insert `END_IF`",
+                    vec![SuggestedEdit::new(factory.create_range(end..end), "END_IF\n")],
+                ),
+            Diagnostic::new("x: Duplicate symbol.")
+                .with_error_code("E004")
+                .with_location(range("x :", 0))
+                .with_secondary_locations(vec![range("x :", 0), range("x :", 1)]),
+            Diagnostic::new("Missing expected Token END_IF")
+                .with_error_code("E006")
+                .with_location(range("END_PROGRAM", 0)),
+        ]
+    }
+
+    #[test]
+    fn results_reference_their_rules_by_index() {
+        let log = report(&diagnostics());
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let ids = rules.iter().map(|it| it["id"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(ids, ["E006", "E004"]);
+
+        for result in run["results"].as_array().unwrap() {
+            let index = result["ruleIndex"].as_u64().unwrap() as usize;
+            assert_eq!(rules[index]["id"], result["ruleId"]);
+        }
+    }
+
+    #[test]
+    fn rules_are_described_by_the_registry() {
+        let log = report(&diagnostics());
+        let registry = DiagnosticsRegistry::default();
+
+        for rule in log["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap() {
+            let description = registry.get_description(rule["id"].as_str().unwrap()).unwrap();
+            assert_eq!(rule["help"]["text"], description);
+            assert_eq!(rule["help"]["markdown"], description);
+        }
+        insta::assert_snapshot!(
+            serde_json::to_string_pretty(&log["runs"][0]["tool"]["driver"]["rules"][0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn results_contain_related_locations_and_fixes() {
+        let log = report(&diagnostics());
+        insta::assert_snapshot!(serde_json::to_string_pretty(&log["runs"][0]["results"]).unwrap());
+    }
+
+    #[test]
+    fn the_log_has_the_sarif_schema_and_version() {
+        let log = report(&[]);
+        assert_eq!(log["$schema"], "https://json.schemastore.org/sarif-2.1.0.json");
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(log["runs"][0]["tool"]["driver"]["name"], "plc");
+        assert_eq!(log["runs"][0]["results"], serde_json::json!([]));
+    }
+}
@@ -16,6 +16,11 @@ fn main() {
         _ => package_version.to_string(),
     };
     println!("cargo:rustc-env=RUSTY_BUILD_INFO={build_info}");
+
+    // Identifies the sources the compiler was built from, e.g. for the build cache
+    if let Some(hash) = git_output(&["rev-parse", "HEAD"]) {
+        println!("cargo:rustc-env=RUSTY_BUILD_HASH={hash}");
+    }
 }
 
 fn git_output(args: &[&str]) -> Option<String> {
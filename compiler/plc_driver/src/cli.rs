@@ -3,17 +3,25 @@ use anyhow::{bail, Result};
 use clap::{ArgGroup, Parser, Subcommand};
 use encoding_rs::Encoding;
 use log::LevelFilter;
-use plc_diagnostics::diagnostics::{diagnostics_registry::DiagnosticsConfiguration, Diagnostic};
+use plc_diagnostics::diagnostics::{diagnostics_registry::ErrorConfiguration, Diagnostic};
 use plc_header_generator::GenerateLanguage;
 use std::{
     env,
     ffi::OsStr,
     num::ParseIntError,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
+use crate::runner::RunOptions;
+use plc::call_graph::CallGraphFormat;
+use plc::doc::DocFormat;
+use plc::metrics::MetricsFormat;
 use plc::output::{FormatOption, RelocationPreference};
-use plc::{ConfigFormat, DebugLevel, ErrorFormat, Target, Threads, DEFAULT_GOT_LAYOUT_FILE};
+use plc::symbols::SymbolsFormat;
+use plc::{
+    ConfigFormat, DebugLevel, ErrorFormat, Target, Threads, DEFAULT_DATA_LAYOUT_FILE, DEFAULT_GOT_LAYOUT_FILE,
+};
 use plc_util::path::normalize_lexical_path;
 
 pub type ParameterError = clap::Error;
@@ -24,6 +32,23 @@ pub struct PrefixMapArg {
     pub new: PathBuf,
 }
 
+/// An additional report requested with `--emit KIND=PATH`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmitArg {
+    pub kind: EmitKind,
+    pub path: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmitKind {
+    /// Cross-reference report of all variable accesses
+    Xref,
+    /// Call graph of all POUs, as DOT or JSON
+    CallGraph,
+    /// Memory layout of all types and the size of the global data, as text or JSON
+    Layout,
+}
+
 #[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LogLevel {
     Off,
@@ -223,6 +248,14 @@ pub struct CompileParameters {
     )]
     pub constructors_only: bool,
 
+    #[clap(
+        name = "generate-retain-persistence",
+        long,
+        global = true,
+        help = "Generate __retain_save and __retain_restore, keeping the values of retain variables across firmware updates"
+    )]
+    pub generate_retain_persistence: bool,
+
     #[clap(
         name = "hardware-conf",
         long,
@@ -252,6 +285,17 @@ pub struct CompileParameters {
     )]
     pub hwmap_file: Option<Option<String>>,
 
+    #[clap(
+        name = "symbols-file",
+        long,
+        global = true,
+        help = "Emit the symbol table of all global variables and program instances, with the symbol,
+    byte offset and size of every variable, for HMI and SCADA systems accessing them at runtime.
+    Format is detected by extension. Supported formats : json, xml",
+        parse(try_from_str = validate_symbols_file)
+    )]
+    pub symbols_file: Option<String>,
+
     #[clap(
         name = "got-layout-file",
         long,
@@ -266,6 +310,17 @@ pub struct CompileParameters {
     ) ]
     pub got_layout_file: String,
 
+    #[clap(
+        name = "data-layout-file",
+        long,
+        global = true,
+        help = "Save the layout of all types, global variables and program instances to the given file.
+    Compare the files of two builds with `plc online-change diff` to migrate the instance data.",
+        default_value = DEFAULT_DATA_LAYOUT_FILE,
+        requires = "online-change"
+    )]
+    pub data_layout_file: String,
+
     #[clap(
         name = "optimization",
         long,
@@ -380,6 +435,38 @@ pub struct CompileParameters {
     )]
     pub prefix_maps: Vec<PrefixMapArg>,
 
+    #[clap(
+        long = "emit",
+        value_name = "KIND=PATH",
+        global = true,
+        help = "Write an additional report to PATH. Repeat to emit more reports.
+    Supported kinds : xref (JSON report of every read, write and address-of site of
+    globals, program variables and function block members), callgraph (call graph of all
+    POUs, the format is detected by extension: dot, json), layout (offset, size, alignment and
+    padding of the members of every struct, function block, program and array type on the
+    selected target and the size of the global and retain data, as JSON for a .json extension,
+    otherwise as text)",
+        parse(try_from_str = parse_emit)
+    )]
+    pub emit: Vec<EmitArg>,
+
+    #[clap(
+        long = "stack-usage",
+        value_name = "PATH",
+        global = true,
+        help = "Write the worst-case stack usage of every program to PATH, as JSON if PATH ends
+    with .json, as a text table otherwise"
+    )]
+    pub stack_usage: Option<String>,
+
+    #[clap(
+        long = "stack-limit",
+        value_name = "BYTES",
+        global = true,
+        help = "Report an error if a program may use more than BYTES of stack or reaches a recursive call"
+    )]
+    pub stack_limit: Option<u64>,
+
     #[clap(
         long = "debug-compilation-dir",
         value_name = "dir",
@@ -439,6 +526,14 @@ pub struct CompileParameters {
     )]
     pub build_location: Option<String>,
 
+    #[clap(
+        name = "no-build-cache",
+        long,
+        help = "Do not reuse or store intermediate results of previous builds in the build location",
+        global = true
+    )]
+    pub no_build_cache: bool,
+
     #[clap(subcommand)]
     pub commands: Option<SubCommands>,
 }
@@ -505,7 +600,9 @@ pub enum SubCommands {
     /// Generates code for a given project
     ///
     /// Sub-command(s):
-    ///     Header : Generates the Header files
+    ///     Header   : Generates the Header files
+    ///     Opcua    : Generates an OPC UA NodeSet2 XML
+    ///     Bindings : Generates external ST declarations from a C header
     Generate {
         #[clap(
             parse(try_from_str = validate_config)
@@ -515,6 +612,224 @@ pub enum SubCommands {
         #[clap(subcommand)]
         option: GenerateOption,
     },
+
+    /// Runs a PROGRAM through the LLVM JIT without linking an executable
+    ///
+    /// The module constructors are executed once, after which the program is called
+    /// cyclically. Libraries given with `-l`/`-L` are loaded into the process. If a loaded
+    /// library provides a simulated clock (e.g. the stdlib built with the `mock_time`
+    /// feature), the clock is advanced by the cycle time after every cycle.
+    Run {
+        #[clap(
+            name = "run-input-files",
+            help = "Read input from <run-input-files>, may be a glob expression like 'src/**/*' or a sequence of files",
+            required = true,
+            min_values = 1
+        )]
+        input: Vec<String>,
+
+        #[clap(long, help = "The PROGRAM to execute")]
+        program: String,
+
+        #[clap(long, default_value = "1", help = "Number of cycles the program is called")]
+        cycles: u64,
+
+        #[clap(
+            name = "cycle-time",
+            long,
+            default_value = "10ms",
+            help = "Duration of a cycle, e.g. `10ms`, `500us` or `T#1s`",
+            parse(try_from_str = parse_cycle_time)
+        )]
+        cycle_time: Duration,
+
+        #[clap(
+            long,
+            help = "Print the given program variable after every cycle, e.g. `counter` or `fb.ET` (repeatable)"
+        )]
+        watch: Vec<String>,
+
+        #[clap(
+            name = "run-library-path",
+            long = "library-path",
+            short = 'L',
+            help = "Search path for libraries to load"
+        )]
+        library_paths: Vec<String>,
+
+        #[clap(name = "run-library", long = "library", short = 'l', help = "Library name to load")]
+        libraries: Vec<String>,
+
+        #[clap(
+            name = "run-include",
+            long = "include",
+            short = 'i',
+            help = "Include source files for external functions"
+        )]
+        includes: Vec<String>,
+    },
+
+    /// Starts an interactive session evaluating ST through the LLVM JIT
+    ///
+    /// Declarations (TYPE, FUNCTION, VAR, ...) are added to the session, statements are
+    /// executed right away and the values of expressions are printed. Input files, includes
+    /// (`-i`) and libraries (`-l`/`-L`) are loaded before the first prompt.
+    Repl {
+        #[clap(
+            name = "repl-input-files",
+            help = "Load declarations from <repl-input-files>, may be a glob expression like 'src/**/*' or a sequence of files"
+        )]
+        input: Vec<String>,
+
+        #[clap(
+            name = "repl-library-path",
+            long = "library-path",
+            short = 'L',
+            help = "Search path for libraries to load"
+        )]
+        library_paths: Vec<String>,
+
+        #[clap(name = "repl-library", long = "library", short = 'l', help = "Library name to load")]
+        libraries: Vec<String>,
+
+        #[clap(
+            name = "repl-include",
+            long = "include",
+            short = 'i',
+            help = "Include source files for external functions"
+        )]
+        includes: Vec<String>,
+    },
+
+    /// Formats source files in place
+    ///
+    /// Only whitespace and the spelling of keywords are changed, comments and pragmas are kept.
+    /// Without input files, the sources of the build description are formatted using the
+    /// options of its `formatter` section.
+    Fmt {
+        #[clap(
+            name = "fmt-input-files",
+            help = "Format <fmt-input-files>, may be a glob expression like 'src/**/*' or a sequence of files"
+        )]
+        input: Vec<String>,
+
+        #[clap(long, help = "Report unformatted files instead of rewriting them")]
+        check: bool,
+
+        #[clap(
+            name = "build-config",
+            long,
+            help = "Build description providing the sources and formatter options",
+            parse(try_from_str = validate_config)
+        )]
+        build_config: Option<String>,
+    },
+
+    /// Applies the suggested fixes of diagnostics to the source files in place
+    ///
+    /// The sources are checked like with `check`, every reported diagnostic suggesting a fix, e.g. a
+    /// missing `END_IF` or a misspelled POU name, is fixed. Without input files, the sources of the
+    /// build description are fixed.
+    Fix {
+        #[clap(
+            name = "fix-input-files",
+            help = "Fix <fix-input-files>, may be a glob expression like 'src/**/*' or a sequence of files"
+        )]
+        input: Vec<String>,
+
+        #[clap(
+            name = "build-config",
+            long,
+            help = "Build description providing the sources",
+            parse(try_from_str = validate_config)
+        )]
+        build_config: Option<String>,
+    },
+
+    /// Reports code metrics of every POU
+    ///
+    /// The lines of code, statements, cyclomatic complexity, nesting depth and parameters of every
+    /// POU, the number of POUs calling it (fan-in) and called by it (fan-out) and the size of its
+    /// instance data. The report is printed, or written to the file given with `-o`. Without input
+    /// files, the sources of the build description are used.
+    Metrics {
+        #[clap(
+            name = "metrics-input-files",
+            help = "Report <metrics-input-files>, may be a glob expression like 'src/**/*' or a sequence of files"
+        )]
+        input: Vec<String>,
+
+        #[clap(
+            name = "metrics-format",
+            long = "format",
+            arg_enum,
+            default_value = "text",
+            help = "Format of the report"
+        )]
+        format: MetricsFormat,
+
+        #[clap(
+            name = "build-config",
+            long,
+            help = "Build description providing the sources",
+            parse(try_from_str = validate_config)
+        )]
+        build_config: Option<String>,
+    },
+
+    /// Generates the API documentation of the sources from their doc comments
+    ///
+    /// Every POU, method, property, variable and type preceded by a `(** ... *)` or `///` comment is
+    /// documented with its signature, its inheritance and interface relations and links to the types
+    /// it uses. One page is written per POU, interface and type, next to an index page, into the
+    /// directory given with `-o` (`doc` by default). Without input files, the sources of the build
+    /// description are documented.
+    Doc {
+        #[clap(
+            name = "doc-input-files",
+            help = "Document <doc-input-files>, may be a glob expression like 'src/**/*' or a sequence of files"
+        )]
+        input: Vec<String>,
+
+        #[clap(
+            name = "doc-format",
+            long = "format",
+            arg_enum,
+            default_value = "markdown",
+            help = "Format of the generated pages"
+        )]
+        format: DocFormat,
+
+        #[clap(
+            name = "build-config",
+            long,
+            help = "Build description providing the sources",
+            parse(try_from_str = validate_config)
+        )]
+        build_config: Option<String>,
+    },
+
+    /// Compares the data layout files of two builds compiled with `--online-change`
+    ///
+    /// Every variable of the global variables and program instances is reported as unchanged,
+    /// moved, converted, removed or added. The copies a runtime applies to migrate the instance data
+    /// of the old build into the new one are written to the file given with `-o`. Fails if a
+    /// variable changed its type in a way its value cannot be converted.
+    OnlineChange {
+        #[clap(subcommand)]
+        option: OnlineChangeOption,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OnlineChangeOption {
+    /// Compares the data layout of the running build with the one of the build replacing it
+    Diff {
+        #[clap(help = "The data layout file of the running build")]
+        old: String,
+        #[clap(help = "The data layout file of the new build")]
+        new: String,
+    },
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Subcommand)]
@@ -539,7 +854,7 @@ pub enum GenerateOption {
             name = "header-language",
             long,
             arg_enum,
-            help = "The language used to generate the header file. Currently supported language(s) are: C",
+            help = "The language used to generate the header file. Currently supported language(s) are: C, C++, Rust, Python",
             default_value = "c"
         )]
         language: GenerateLanguage,
@@ -551,6 +866,25 @@ pub enum GenerateOption {
         )]
         prefix: Option<String>,
     },
+
+    /// Generates an OPC UA NodeSet2 XML of the types, global variables and program instances
+    Opcua {
+        #[clap(
+            name = "opcua-config",
+            long,
+            help = "Options of the generated model, like the namespace uri.
+    Format is detected by extension.
+    Supported formats : json, toml",
+            parse(try_from_str = validate_config)
+        )]
+        config: Option<String>,
+    },
+
+    /// Generates `{external}` declarations of the functions and types of a C header
+    Bindings {
+        #[clap(name = "from", long, help = "The C header to generate the declarations from")]
+        from: String,
+    },
 }
 
 impl SubCommands {
@@ -558,7 +892,11 @@ impl SubCommands {
         let (SubCommands::Build { build_config, .. }
         | SubCommands::Check { build_config }
         | SubCommands::Config { build_config, .. }
-        | SubCommands::Generate { build_config, .. }) = self
+        | SubCommands::Generate { build_config, .. }
+        | SubCommands::Fmt { build_config, .. }
+        | SubCommands::Fix { build_config, .. }
+        | SubCommands::Metrics { build_config, .. }
+        | SubCommands::Doc { build_config, .. }) = self
         else {
             return None;
         };
@@ -566,6 +904,22 @@ impl SubCommands {
     }
 }
 
+/// Parses a cycle time such as `10ms`, `1s` or `T#250us`. A value without unit is read as milliseconds.
+fn parse_cycle_time(value: &str) -> Result<Duration, String> {
+    let lower = value.trim().to_lowercase();
+    let time = lower.strip_prefix("time#").or_else(|| lower.strip_prefix("t#")).unwrap_or(&lower);
+    let unit_start = time.find(|c: char| !c.is_ascii_digit()).unwrap_or(time.len());
+    let (amount, unit) = time.split_at(unit_start);
+    let amount = amount.parse::<u64>().map_err(|_| format!("Invalid cycle time {value}"))?;
+    match unit {
+        "ns" => Ok(Duration::from_nanos(amount)),
+        "us" => Ok(Duration::from_micros(amount)),
+        "" | "ms" => Ok(Duration::from_millis(amount)),
+        "s" => Ok(Duration::from_secs(amount)),
+        _ => Err(format!("Invalid cycle time {value}, supported units: ns, us, ms, s")),
+    }
+}
+
 fn parse_encoding(encoding: &str) -> Result<&'static Encoding, String> {
     Encoding::for_label(encoding.as_bytes()).ok_or(format!("Unknown encoding {encoding}"))
 }
@@ -578,6 +932,14 @@ fn validate_config(config_name: &str) -> Result<String, String> {
     }
 }
 
+fn validate_symbols_file(name: &str) -> Result<String, String> {
+    if SymbolsFormat::from_file_name(name).is_some() {
+        Ok(name.to_string())
+    } else {
+        Err(format!(r#"Cannot identify format type for {name}, valid extensions : "json", "xml""#))
+    }
+}
+
 fn get_parallel_threads(thread_count: &str) -> Result<Threads, ParseIntError> {
     if thread_count.is_empty() {
         Ok(Threads::Full)
@@ -625,6 +987,28 @@ fn parse_prefix_map(value: &str) -> Result<PrefixMapArg, String> {
     Ok(PrefixMapArg { old: resolve_old_prefix(Path::new(old))?, new: lex_normalize_replacement(new) })
 }
 
+fn parse_emit(value: &str) -> Result<EmitArg, String> {
+    let Some((kind, path)) = value.split_once('=') else {
+        return Err("Expected KIND=PATH".into());
+    };
+    if path.is_empty() {
+        return Err("Expected non-empty PATH in KIND=PATH".into());
+    }
+    let kind = match kind.to_lowercase().as_str() {
+        "xref" => EmitKind::Xref,
+        "callgraph" => EmitKind::CallGraph,
+        "layout" => EmitKind::Layout,
+        _ => {
+            return Err(format!(r#"Unknown report kind {kind}, valid kinds : "xref", "callgraph", "layout""#))
+        }
+    };
+    if kind == EmitKind::CallGraph && CallGraphFormat::from_file_name(path).is_none() {
+        return Err(format!(r#"Cannot identify format type for {path}, valid extensions : "dot", "json""#));
+    }
+
+    Ok(EmitArg { kind, path: path.to_string() })
+}
+
 fn parse_debug_compilation_dir(value: &str) -> Result<PathBuf, String> {
     Ok(lex_normalize_replacement(value))
 }
@@ -706,6 +1090,34 @@ impl CompileParameters {
         self.check_only || matches!(self.commands, Some(SubCommands::Check { .. }))
     }
 
+    /// If set, an interactive session is started instead of compiling the input
+    pub fn is_repl(&self) -> bool {
+        matches!(self.commands, Some(SubCommands::Repl { .. }))
+    }
+
+    /// Returns whether unformatted files are only reported, if the `fmt` subcommand is used
+    pub fn get_fmt_check(&self) -> Option<bool> {
+        let Some(SubCommands::Fmt { check, .. }) = &self.commands else { return None };
+        Some(*check)
+    }
+
+    /// If set, the suggested fixes of the diagnostics are applied to the sources
+    pub fn is_fix(&self) -> bool {
+        matches!(self.commands, Some(SubCommands::Fix { .. }))
+    }
+
+    /// Returns the format of the report, if the `metrics` subcommand is used
+    pub fn get_metrics_format(&self) -> Option<MetricsFormat> {
+        let Some(SubCommands::Metrics { format, .. }) = &self.commands else { return None };
+        Some(*format)
+    }
+
+    /// Returns the format of the pages, if the `doc` subcommand is used
+    pub fn get_doc_format(&self) -> Option<DocFormat> {
+        let Some(SubCommands::Doc { format, .. }) = &self.commands else { return None };
+        Some(*format)
+    }
+
     /// If set, header files will be generated
     pub fn is_header_generator(&self) -> bool {
         self.generate_headers_only
@@ -715,6 +1127,30 @@ impl CompileParameters {
             )
     }
 
+    /// If set, an OPC UA NodeSet will be generated
+    pub fn is_opcua_generator(&self) -> bool {
+        matches!(self.commands, Some(SubCommands::Generate { option: GenerateOption::Opcua { .. }, .. }))
+    }
+
+    /// Returns the C header to generate external declarations from, if the `bindings` generator is used
+    pub fn get_bindings_header(&self) -> Option<&str> {
+        let Some(SubCommands::Generate { option: GenerateOption::Bindings { from }, .. }) = &self.commands
+        else {
+            return None;
+        };
+        Some(from)
+    }
+
+    /// Returns the data layout files of the old and the new build, if the `online-change diff` subcommand is used
+    pub fn get_online_change_diff(&self) -> Option<(&str, &str)> {
+        let Some(SubCommands::OnlineChange { option: OnlineChangeOption::Diff { old, new } }) =
+            &self.commands
+        else {
+            return None;
+        };
+        Some((old, new))
+    }
+
     /// return the selected output format, or the default if none.
     #[cfg(test)]
     pub fn output_format_or_default(&self) -> FormatOption {
@@ -747,6 +1183,13 @@ impl CompileParameters {
         Ok(Some((path, format)))
     }
 
+    /// Returns the location and format of the symbol table requested with `--symbols-file`
+    pub fn symbols_target(&self) -> Option<(&str, SymbolsFormat)> {
+        // the extension has been checked with `validate_symbols_file`
+        let path = self.symbols_file.as_deref()?;
+        SymbolsFormat::from_file_name(path).map(|format| (path, format))
+    }
+
     pub fn got_layout_format(&self) -> ConfigFormat {
         // It is safe to unwrap here, since the provided argument to `--got-online-change` has been checked with `validate_config`
         get_config_format(&self.got_layout_file).unwrap()
@@ -785,6 +1228,57 @@ impl CompileParameters {
         }
     }
 
+    /// Returns the input files, taken from the `run`, `repl`, `fmt`, `fix`, `metrics` or `doc` subcommand if it is used
+    pub fn get_input_files(&self) -> &[String] {
+        match &self.commands {
+            Some(SubCommands::Run { input, .. })
+            | Some(SubCommands::Repl { input, .. })
+            | Some(SubCommands::Fmt { input, .. })
+            | Some(SubCommands::Fix { input, .. })
+            | Some(SubCommands::Metrics { input, .. })
+            | Some(SubCommands::Doc { input, .. }) => input,
+            _ => &self.input,
+        }
+    }
+
+    /// Returns the include files, taken from the `run` or `repl` subcommand if it is used
+    pub fn get_includes(&self) -> &[String] {
+        match &self.commands {
+            Some(SubCommands::Run { includes, .. }) | Some(SubCommands::Repl { includes, .. }) => includes,
+            _ => &self.includes,
+        }
+    }
+
+    /// Returns the library search paths, taken from the `run` or `repl` subcommand if it is used
+    pub fn get_library_paths(&self) -> &[String] {
+        match &self.commands {
+            Some(SubCommands::Run { library_paths, .. }) | Some(SubCommands::Repl { library_paths, .. }) => {
+                library_paths
+            }
+            _ => &self.library_paths,
+        }
+    }
+
+    /// Returns the libraries, taken from the `run` or `repl` subcommand if it is used
+    pub fn get_libraries(&self) -> &[String] {
+        match &self.commands {
+            Some(SubCommands::Run { libraries, .. }) | Some(SubCommands::Repl { libraries, .. }) => libraries,
+            _ => &self.libraries,
+        }
+    }
+
+    pub fn get_run_options(&self) -> Option<RunOptions> {
+        let Some(SubCommands::Run { program, cycles, cycle_time, watch, .. }) = &self.commands else {
+            return None;
+        };
+        Some(RunOptions {
+            program: program.clone(),
+            cycles: *cycles,
+            cycle_time: *cycle_time,
+            watch: watch.clone(),
+        })
+    }
+
     pub fn get_config_options(&self) -> Option<(ConfigOption, ConfigFormat)> {
         let Some(SubCommands::Config { format, option, .. }) = &self.commands else { return None };
         Some((*option, *format))
@@ -814,25 +1308,35 @@ impl CompileParameters {
 
     fn has_config(&self) -> Result<bool, Diagnostic> {
         let res = match &self.commands {
-            None | Some(SubCommands::Explain { .. }) => false,
+            None
+            | Some(SubCommands::Explain { .. })
+            | Some(SubCommands::Run { .. })
+            | Some(SubCommands::Repl { .. })
+            | Some(SubCommands::OnlineChange { .. }) => false,
             Some(SubCommands::Build { .. }) | Some(SubCommands::Check { .. }) => true,
             Some(SubCommands::Config { build_config, .. }) => {
                 let current_dir = env::current_dir()?;
                 build_config.is_some() || super::get_config(&current_dir).exists()
             }
             Some(SubCommands::Generate { build_config, .. }) => build_config.is_some(),
+            Some(SubCommands::Fmt { input, build_config, .. })
+            | Some(SubCommands::Fix { input, build_config })
+            | Some(SubCommands::Metrics { input, build_config, .. })
+            | Some(SubCommands::Doc { input, build_config, .. }) => {
+                build_config.is_some() || input.is_empty()
+            }
         };
         Ok(res)
     }
 
-    pub fn get_error_configuration(&self) -> Result<Option<DiagnosticsConfiguration>> {
+    pub fn get_error_configuration(&self) -> Result<Option<ErrorConfiguration>> {
         let Some(config) = &self.error_config else {
             return Ok(None);
         };
         let config_path: PathBuf = config.into();
         if config_path.exists() {
             let error_config = std::fs::read_to_string(config_path)?;
-            let configuration: DiagnosticsConfiguration = serde_json::de::from_str(&error_config)?;
+            let configuration: ErrorConfiguration = serde_json::de::from_str(&error_config)?;
             Ok(Some(configuration))
         } else {
             bail!("{} does not exist", config_path.to_string_lossy())
@@ -843,16 +1347,20 @@ impl CompileParameters {
 #[cfg(test)]
 mod cli_tests {
     use crate::cli::{ConfigOption, GenerateLanguage, GenerateOption};
+    use crate::runner::RunOptions;
 
-    use super::{CompileParameters, SubCommands};
+    use super::{parse_cycle_time, CompileParameters, EmitArg, EmitKind, SubCommands};
     use clap::ErrorKind;
     use plc::{
+        doc::DocFormat,
+        metrics::MetricsFormat,
         output::{FormatOption, RelocationPreference},
+        symbols::SymbolsFormat,
         ConfigFormat, ErrorFormat, OptimizationLevel,
     };
     use pretty_assertions::assert_eq;
     use std::fmt::Debug;
-    use std::{env, ffi::OsStr, path::PathBuf};
+    use std::{env, ffi::OsStr, path::PathBuf, time::Duration};
 
     #[test]
     fn verify_cli() {
@@ -1296,6 +1804,15 @@ mod cli_tests {
         assert_eq!(parameters.get_output_directory(), None);
     }
 
+    #[test]
+    fn build_cache_can_be_disabled() {
+        let parameters = CompileParameters::parse(vec_of_strings!("build", "--no-build-cache")).unwrap();
+        assert!(parameters.no_build_cache);
+
+        let parameters = CompileParameters::parse(vec_of_strings!("build")).unwrap();
+        assert!(!parameters.no_build_cache);
+    }
+
     #[test]
     fn build_subcommand_lib_location_falls_back_to_global_build_location() {
         let parameters = CompileParameters::parse(vec_of_strings!(
@@ -1376,6 +1893,7 @@ mod cli_tests {
                             assert_eq!(include_stubs, false);
                             assert_eq!(language, GenerateLanguage::C);
                         }
+                        _ => panic!("Unexpected option"),
                     }
                 }
                 _ => panic!("Unexpected command"),
@@ -1403,6 +1921,7 @@ mod cli_tests {
                             assert_eq!(language, GenerateLanguage::Rust);
                             assert_eq!(prefix, Some("myLib".to_string()));
                         }
+                        _ => panic!("Unexpected option"),
                     }
                 }
                 _ => panic!("Unexpected command"),
@@ -1410,6 +1929,240 @@ mod cli_tests {
         }
     }
 
+    #[test]
+    fn generate_opcua_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "generate",
+            "plc.json",
+            "opcua",
+            "--opcua-config",
+            "opcua.toml"
+        ))
+        .unwrap();
+        assert!(parameters.is_opcua_generator());
+        assert!(!parameters.is_header_generator());
+        let Some(SubCommands::Generate { option: GenerateOption::Opcua { config }, .. }) =
+            parameters.commands
+        else {
+            panic!("Unexpected command")
+        };
+        assert_eq!(config.as_deref(), Some("opcua.toml"));
+
+        let error = CompileParameters::parse(vec_of_strings!(
+            "generate",
+            "plc.json",
+            "opcua",
+            "--opcua-config",
+            "opcua.xml"
+        ))
+        .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn generate_bindings_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "generate", "bindings", "--from", "lib.h", "-o", "lib.st"
+        ))
+        .unwrap();
+        assert_eq!(parameters.get_bindings_header(), Some("lib.h"));
+        assert_eq!(parameters.output.as_deref(), Some("lib.st"));
+        assert!(!parameters.is_header_generator());
+        assert!(!parameters.is_opcua_generator());
+
+        let parameters = CompileParameters::parse(vec_of_strings!("generate", "opcua")).unwrap();
+        assert_eq!(parameters.get_bindings_header(), None);
+    }
+
+    #[test]
+    fn online_change_diff_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "online-change",
+            "diff",
+            "old.json",
+            "new.json",
+            "-o",
+            "migration.json"
+        ))
+        .unwrap();
+        assert_eq!(parameters.get_online_change_diff(), Some(("old.json", "new.json")));
+        assert_eq!(parameters.output.as_deref(), Some("migration.json"));
+        assert!(parameters.get_build_configuration().unwrap().is_none());
+
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "input.st",
+            "--online-change",
+            "--data-layout-file",
+            "layout.json"
+        ))
+        .unwrap();
+        assert_eq!(parameters.get_online_change_diff(), None);
+        assert_eq!(parameters.data_layout_file, "layout.json");
+
+        // the data layout is only written for online changes
+        let error =
+            CompileParameters::parse(vec_of_strings!("input.st", "--data-layout-file", "layout.json"))
+                .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn run_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "run",
+            "main.st",
+            "--program",
+            "Main",
+            "--cycles",
+            "100",
+            "--cycle-time",
+            "T#5ms",
+            "--watch",
+            "counter",
+            "--watch",
+            "fb.ET",
+            "-i",
+            "stdlib.st",
+            "-l",
+            "iec61131std"
+        ))
+        .unwrap();
+
+        assert_eq!(parameters.get_input_files(), &["main.st".to_string()]);
+        assert_eq!(parameters.get_includes(), &["stdlib.st".to_string()]);
+        assert_eq!(parameters.get_libraries(), &["iec61131std".to_string()]);
+        assert!(parameters.includes.is_empty());
+        assert_eq!(
+            parameters.get_run_options(),
+            Some(RunOptions {
+                program: "Main".to_string(),
+                cycles: 100,
+                cycle_time: Duration::from_millis(5),
+                watch: vec!["counter".to_string(), "fb.ET".to_string()],
+            })
+        );
+        assert!(!parameters.is_check());
+    }
+
+    #[test]
+    fn run_subcommand_defaults() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("run", "main.st", "--program", "Main")).unwrap();
+        let options = parameters.get_run_options().unwrap();
+        assert_eq!(options.cycles, 1);
+        assert_eq!(options.cycle_time, Duration::from_millis(10));
+        assert!(options.watch.is_empty());
+
+        expect_argument_error(vec_of_strings!("run", "main.st"), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn cycle_time_units() {
+        assert_eq!(parse_cycle_time("250"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_cycle_time("10ms"), Ok(Duration::from_millis(10)));
+        assert_eq!(parse_cycle_time("500us"), Ok(Duration::from_micros(500)));
+        assert_eq!(parse_cycle_time("100ns"), Ok(Duration::from_nanos(100)));
+        assert_eq!(parse_cycle_time("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_cycle_time("T#1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_cycle_time("TIME#20MS"), Ok(Duration::from_millis(20)));
+        assert!(parse_cycle_time("10h").is_err());
+        assert!(parse_cycle_time("ms").is_err());
+    }
+
+    #[test]
+    fn repl_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!("repl")).unwrap();
+        assert!(parameters.is_repl());
+        assert!(parameters.get_input_files().is_empty());
+
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "repl",
+            "lib.st",
+            "-i",
+            "stdlib.st",
+            "-l",
+            "iec61131std"
+        ))
+        .unwrap();
+        assert!(parameters.is_repl());
+        assert_eq!(parameters.get_input_files(), &["lib.st".to_string()]);
+        assert_eq!(parameters.get_includes(), &["stdlib.st".to_string()]);
+        assert_eq!(parameters.get_libraries(), &["iec61131std".to_string()]);
+    }
+
+    #[test]
+    fn fmt_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!("fmt", "a.st", "b.st")).unwrap();
+        assert_eq!(parameters.get_fmt_check(), Some(false));
+        assert_eq!(parameters.get_input_files(), &["a.st".to_string(), "b.st".to_string()]);
+        assert_eq!(parameters.get_build_configuration().unwrap(), None);
+
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("fmt", "--check", "--build-config", "plc.json"))
+                .unwrap();
+        assert_eq!(parameters.get_fmt_check(), Some(true));
+        assert!(parameters.get_input_files().is_empty());
+        assert!(parameters.get_build_configuration().unwrap().is_some_and(|it| it.ends_with("plc.json")));
+
+        let parameters = CompileParameters::parse(vec_of_strings!("a.st")).unwrap();
+        assert_eq!(parameters.get_fmt_check(), None);
+    }
+
+    #[test]
+    fn fix_subcommand() {
+        let parameters = CompileParameters::parse(vec_of_strings!("fix", "a.st", "b.st")).unwrap();
+        assert!(parameters.is_fix());
+        assert_eq!(parameters.get_input_files(), &["a.st".to_string(), "b.st".to_string()]);
+        assert_eq!(parameters.get_build_configuration().unwrap(), None);
+
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("fix", "--build-config", "plc.json")).unwrap();
+        assert!(parameters.is_fix());
+        assert!(parameters.get_input_files().is_empty());
+        assert!(parameters.get_build_configuration().unwrap().is_some_and(|it| it.ends_with("plc.json")));
+
+        let parameters = CompileParameters::parse(vec_of_strings!("a.st")).unwrap();
+        assert!(!parameters.is_fix());
+    }
+
+    #[test]
+    fn metrics_subcommand() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("metrics", "a.st", "--format", "csv")).unwrap();
+        assert_eq!(parameters.get_metrics_format(), Some(MetricsFormat::Csv));
+        assert_eq!(parameters.get_input_files(), &["a.st".to_string()]);
+        assert_eq!(parameters.get_build_configuration().unwrap(), None);
+
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("metrics", "--build-config", "plc.json", "-o", "m.txt"))
+                .unwrap();
+        assert_eq!(parameters.get_metrics_format(), Some(MetricsFormat::Text));
+        assert_eq!(parameters.output.as_deref(), Some("m.txt"));
+        assert!(parameters.get_build_configuration().unwrap().is_some_and(|it| it.ends_with("plc.json")));
+
+        let parameters = CompileParameters::parse(vec_of_strings!("a.st")).unwrap();
+        assert_eq!(parameters.get_metrics_format(), None);
+    }
+
+    #[test]
+    fn doc_subcommand() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("doc", "a.st", "--format", "html", "-o", "api"))
+                .unwrap();
+        assert_eq!(parameters.get_doc_format(), Some(DocFormat::Html));
+        assert_eq!(parameters.get_input_files(), &["a.st".to_string()]);
+        assert_eq!(parameters.output.as_deref(), Some("api"));
+        assert_eq!(parameters.get_build_configuration().unwrap(), None);
+
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("doc", "--build-config", "plc.json")).unwrap();
+        assert_eq!(parameters.get_doc_format(), Some(DocFormat::Markdown));
+        assert!(parameters.get_build_configuration().unwrap().is_some_and(|it| it.ends_with("plc.json")));
+
+        let parameters = CompileParameters::parse(vec_of_strings!("a.st")).unwrap();
+        assert_eq!(parameters.get_doc_format(), None);
+    }
+
     #[test]
     fn include_files_added() {
         let parameters = CompileParameters::parse(vec_of_strings!(
@@ -1453,6 +2206,11 @@ mod cli_tests {
         // set clang as error format
         let params = CompileParameters::parse(vec_of_strings!("input.st", "--error-format=clang")).unwrap();
         assert_eq!(params.error_format, ErrorFormat::Clang);
+        // set json and sarif as error format
+        let params = CompileParameters::parse(vec_of_strings!("input.st", "--error-format=json")).unwrap();
+        assert_eq!(params.error_format, ErrorFormat::Json);
+        let params = CompileParameters::parse(vec_of_strings!("input.st", "--error-format=sarif")).unwrap();
+        assert_eq!(params.error_format, ErrorFormat::Sarif);
         // set invalid error format
         expect_argument_error(vec_of_strings!("input.st", "--error-format=nothing"), ErrorKind::InvalidValue);
     }
@@ -1614,4 +2372,83 @@ mod cli_tests {
             CompileParameters::parse(vec_of_strings!("input.st", "--file-prefix-map", "=/new")).unwrap_err();
         assert_eq!(error.kind(), ErrorKind::ValueValidation);
     }
+
+    #[test]
+    fn emit_reports_are_parsed() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("input.st", "--check", "--emit", "xref=out/xref.json"))
+                .unwrap();
+        assert_eq!(parameters.emit, vec![EmitArg { kind: EmitKind::Xref, path: "out/xref.json".into() }]);
+
+        let error =
+            CompileParameters::parse(vec_of_strings!("input.st", "--emit", "unknown=out.json")).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+
+        let error = CompileParameters::parse(vec_of_strings!("input.st", "--emit", "xref")).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "input.st",
+            "--target",
+            "armv7-none-eabi",
+            "--emit",
+            "layout=out/layout.txt"
+        ))
+        .unwrap();
+        assert_eq!(parameters.emit, vec![EmitArg { kind: EmitKind::Layout, path: "out/layout.txt".into() }]);
+    }
+
+    #[test]
+    fn symbols_file_format_is_detected_by_extension() {
+        let parameters =
+            CompileParameters::parse(vec_of_strings!("input.st", "--symbols-file", "symbols.xml")).unwrap();
+        assert_eq!(parameters.symbols_target(), Some(("symbols.xml", SymbolsFormat::Xml)));
+
+        let parameters = CompileParameters::parse(vec_of_strings!("input.st")).unwrap();
+        assert_eq!(parameters.symbols_target(), None);
+
+        let error = CompileParameters::parse(vec_of_strings!("input.st", "--symbols-file", "symbols.toml"))
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn stack_usage_report_and_limit_are_parsed() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "input.st",
+            "--stack-usage",
+            "stack.json",
+            "--stack-limit",
+            "4096"
+        ))
+        .unwrap();
+        assert_eq!(parameters.stack_usage.as_deref(), Some("stack.json"));
+        assert_eq!(parameters.stack_limit, Some(4096));
+
+        let error = CompileParameters::parse(vec_of_strings!("input.st", "--stack-limit", "4k")).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn call_graph_format_is_detected_by_extension() {
+        let parameters = CompileParameters::parse(vec_of_strings!(
+            "input.st",
+            "--emit",
+            "callgraph=calls.dot",
+            "--emit",
+            "callgraph=calls.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            parameters.emit,
+            vec![
+                EmitArg { kind: EmitKind::CallGraph, path: "calls.dot".into() },
+                EmitArg { kind: EmitKind::CallGraph, path: "calls.json".into() }
+            ]
+        );
+
+        let error = CompileParameters::parse(vec_of_strings!("input.st", "--emit", "callgraph=calls.svg"))
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    }
 }
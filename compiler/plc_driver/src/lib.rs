@@ -10,7 +10,8 @@
 
 use anyhow::{anyhow, Result};
 use pipelines::{
-    participant::CodegenParticipant, AnnotatedProject, BuildPipeline, GeneratedProject, Pipeline,
+    participant::{CodegenParticipant, StackUsageParticipant},
+    AnnotatedProject, BuildPipeline, GeneratedProject, Pipeline,
 };
 use std::{
     ffi::OsStr,
@@ -34,6 +35,7 @@ use source_code::SourceContainer;
 
 pub mod cli;
 pub mod pipelines;
+pub mod repl;
 
 #[cfg(test)]
 mod tests;
@@ -188,6 +190,20 @@ pub fn compile_with_pipeline<T: SourceContainer + Clone + 'static>(
     //register participants
     pipeline.register_default_mut_participants();
     let target = pipeline.compile_parameters.as_ref().and_then(|it| it.target.clone()).unwrap_or_default();
+    let stack_usage = pipeline
+        .compile_parameters
+        .as_ref()
+        .filter(|it| it.stack_usage.is_some() || it.stack_limit.is_some())
+        .map(|it| (it.stack_usage.clone(), it.stack_limit));
+    if let Some((location, limit)) = stack_usage.clone() {
+        // registered before codegen so the frames are taken from the modules before they are optimized
+        pipeline.register_participant(Box::new(StackUsageParticipant::new(
+            location,
+            limit,
+            target.clone(),
+            pipeline.get_compile_options().unwrap().optimization,
+        )));
+    }
     let codegen_participant = CodegenParticipant {
         compile_options: pipeline.get_compile_options().unwrap(),
         link_options: pipeline.get_link_options().unwrap(),
@@ -199,6 +215,9 @@ pub fn compile_with_pipeline<T: SourceContainer + Clone + 'static>(
         got_layout: Default::default(),
         compile_dirs: Default::default(),
         libraries: pipeline.project.get_libraries().to_vec(),
+        // the stack usage is taken from the generated modules, so no unit may be reused
+        cache: pipeline.get_build_cache().filter(|_| stack_usage.is_none()),
+        object_keys: Default::default(),
     };
     pipeline.register_participant(Box::new(codegen_participant));
 
@@ -414,14 +433,16 @@ fn get_project(compile_parameters: &CompileParameters) -> Result<Project<PathBuf
         .unwrap_or_else(|| {
             //Build with parameters
             let name = compile_parameters
-                .input
+                .get_input_files()
                 .first()
                 .and_then(|it| it.get_location())
                 .and_then(|it| it.file_name())
                 .and_then(|it| it.to_str())
                 .unwrap_or(DEFAULT_OUTPUT_NAME);
-            let file_paths: Vec<PathBuf> = compile_parameters.input.iter().map(PathBuf::from).collect();
-            let include_paths: Vec<PathBuf> = compile_parameters.includes.iter().map(PathBuf::from).collect();
+            let file_paths: Vec<PathBuf> =
+                compile_parameters.get_input_files().iter().map(PathBuf::from).collect();
+            let include_paths: Vec<PathBuf> =
+                compile_parameters.get_includes().iter().map(PathBuf::from).collect();
 
             // Validate file paths and include paths independently so a typo in
             // one set doesn't mask errors in the other — the user sees every
@@ -436,8 +457,10 @@ fn get_project(compile_parameters: &CompileParameters) -> Result<Project<PathBuf
                 (Err(file_err), Err(inc_err)) => return Err(anyhow!("{file_err}\n{inc_err}")),
             };
             let project = project
-                .with_library_paths(compile_parameters.library_paths.iter().map(PathBuf::from).collect())
-                .with_libraries(compile_parameters.libraries.clone());
+                .with_library_paths(
+                    compile_parameters.get_library_paths().iter().map(PathBuf::from).collect(),
+                )
+                .with_libraries(compile_parameters.get_libraries().to_vec());
             Ok(project)
         });
     //Override default settings with compile options
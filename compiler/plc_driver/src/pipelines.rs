@@ -5,33 +5,43 @@ use std::{
     fs::{self, File},
     io::Write,
     path::{Path, PathBuf},
-    sync::Mutex,
+    sync::{Arc, Mutex},
 };
 
 use crate::{
-    cli::{self, CompileParameters, ConfigOption, GenerateOption, SubCommands},
-    get_project, CompileOptions, LinkOptions, LinkerScript,
+    cli::{self, CompileParameters, ConfigOption, EmitKind, GenerateOption, SubCommands},
+    get_project,
+    repl::{self, Repl},
+    runner::{self, RunOptions},
+    CompileOptions, LinkOptions, LinkerScript,
 };
 use ast::{
     ast::{pre_process, CompilationUnit, LinkageType},
     provider::IdProvider,
 };
 
+use cache::BuildCache;
 use itertools::Itertools;
 use participant::{PipelineParticipant, PipelineParticipantMut};
 use plc::{
+    call_graph::CallGraphFormat,
     codegen::{CodegenContext, GeneratedModule},
+    doc::{DocFormat, Documentation},
     index::{indexer, FxIndexSet, Index},
     linker::LinkerType,
     lowering::{calls::AggregateTypeLowerer, polymorphism::PolymorphismLowerer, property::PropertyLowerer},
+    metrics::{MetricsFormat, PouMetrics},
+    online_change::DataLayoutFile,
+    opcua::OpcUaOptions,
     output::{FormatOption, RelocationPreference},
-    parser::parse_file,
+    parser::{parse_file, parse_source, report_parse_diagnostics},
     resolver::{
         const_evaluator::UnresolvableConstant, AnnotationMapImpl, AstAnnotations, Dependency, StringLiterals,
         TypeAnnotator,
     },
+    symbols::SymbolsFormat,
     validation::Validator,
-    ConfigFormat, ErrorFormat, OnlineChange, Target, Threads,
+    ConfigFormat, ErrorFormat, OnlineChange, Target, Threads, DEFAULT_MIGRATION_FILE,
 };
 use plc_diagnostics::{
     diagnostician::Diagnostician,
@@ -52,11 +62,12 @@ use project::{
 };
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use source_code::{source_location::SourceLocation, SourceContainer};
+use source_code::{source_location::SourceLocation, SourceCode, SourceContainer, SourceType};
 
 use serde_json;
 use toml;
 
+pub mod cache;
 pub mod participant;
 pub mod property;
 
@@ -113,10 +124,14 @@ impl TryFrom<CompileParameters> for BuildPipeline<PathBuf> {
         let diagnostician = match compile_parameters.error_format {
             ErrorFormat::Rich => Diagnostician::default(),
             ErrorFormat::Clang => Diagnostician::clang_format_diagnostician(),
+            ErrorFormat::Json => Diagnostician::json_diagnostician(),
+            ErrorFormat::Sarif => Diagnostician::sarif_diagnostician(),
             ErrorFormat::None => Diagnostician::null_diagnostician(),
         };
         let diagnostician = if let Some(configuration) = compile_parameters.get_error_configuration()? {
-            diagnostician.with_configuration(configuration)
+            diagnostician
+                .with_configuration(configuration.diagnostics)
+                .with_lint_settings(configuration.lints)
         } else {
             diagnostician
         };
@@ -141,6 +156,11 @@ impl TryFrom<CompileParameters> for BuildPipeline<PathBuf> {
             } else {
                 context
             };
+        let context = if compile_parameters.generate_retain_persistence {
+            context.generate_retain_persistence()
+        } else {
+            context
+        };
 
         let linker = compile_parameters.linker.as_deref().into();
         Ok(BuildPipeline {
@@ -284,6 +304,16 @@ impl<T: SourceContainer> BuildPipeline<T> {
         })
     }
 
+    /// Returns the cache for intermediate build results, if a build location is used, caching was not
+    /// disabled and the running compiler can be identified
+    pub fn get_build_cache(&self) -> Option<BuildCache> {
+        self.compile_parameters
+            .as_ref()
+            .filter(|params| !params.no_build_cache)
+            .and_then(CompileParameters::get_build_location)
+            .and_then(|location| BuildCache::new(&location))
+    }
+
     pub fn get_generate_header_options(&self) -> Option<GenerateHeaderOptions> {
         let header_output_path =
             self.compile_parameters.as_ref().map(|params| params.header_output.clone().unwrap_or_default());
@@ -302,11 +332,88 @@ impl<T: SourceContainer> BuildPipeline<T> {
                     output_path,
                     prefix: prefix.clone().unwrap_or(String::new()),
                 },
+                GenerateOption::Opcua { .. } | GenerateOption::Bindings { .. } => {
+                    GenerateHeaderOptions { output_path, ..Default::default() }
+                }
             },
             _ => GenerateHeaderOptions { output_path, ..Default::default() },
         })
     }
 
+    /// Returns the options of the OPC UA model, read from the file given with `--opcua-config`
+    fn get_opcua_options(&self) -> Result<OpcUaOptions, Diagnostic> {
+        let config = match self.compile_parameters.as_ref().and_then(|it| it.commands.as_ref()) {
+            Some(SubCommands::Generate {
+                option: GenerateOption::Opcua { config: Some(config) }, ..
+            }) => Some(config),
+            _ => None,
+        };
+        let mut options = match config {
+            Some(location) => {
+                let content = fs::read_to_string(location)
+                    .map_err(|it| Diagnostic::new(format!("{location}: {it}")).with_error_code("E002"))?;
+                // the extension has been checked with `validate_config`
+                match cli::get_config_format(location) {
+                    Some(ConfigFormat::TOML) => toml::de::from_str(&content).map_err(|it| it.to_string()),
+                    _ => serde_json::from_str(&content).map_err(|it| it.to_string()),
+                }
+                .map_err(|it| Diagnostic::new(format!("{location}: {it}")).with_error_code("E002"))?
+            }
+            None => OpcUaOptions::default(),
+        };
+        if options.namespace_uri.is_empty() {
+            options.namespace_uri = format!("urn:{}", self.project.get_name());
+        }
+        Ok(options)
+    }
+
+    /// Writes the OPC UA NodeSet2 XML of the project to the output file, `<project>.NodeSet2.xml` by default
+    fn generate_node_set(&self, project: &AnnotatedProject) -> Result<(), Diagnostic> {
+        let options = self.get_opcua_options()?;
+        let node_set = plc::opcua::generate_node_set(&project.index, &options)?;
+        let location = self
+            .compile_parameters
+            .as_ref()
+            .and_then(|it| it.output.clone())
+            .unwrap_or_else(|| format!("{}.NodeSet2.xml", self.project.get_name()));
+        write_report(&location, &node_set)
+    }
+
+    /// Writes the external declarations of the given C header to the output file, `<header>.st` by default
+    fn generate_bindings(&mut self, header: &str) -> Result<(), Diagnostic> {
+        let source = fs::read_to_string(header)
+            .map_err(|it| Diagnostic::new(format!("{header}: {it}")).with_error_code("E002"))?;
+        self.diagnostician.register_file(header.to_string(), source.clone());
+        let (bindings, diagnostics) = plc::bindings::generate_bindings(&SourceCode::new(source, header));
+        self.diagnostician.handle(&diagnostics);
+        let location = self
+            .compile_parameters
+            .as_ref()
+            .and_then(|it| it.output.clone())
+            .unwrap_or_else(|| Path::new(header).with_extension("st").to_string_lossy().into_owned());
+        write_report(&location, &bindings)
+    }
+
+    fn diff_data_layouts(&self, old: &str, new: &str) -> Result<(), Diagnostic> {
+        let read = |location: &str| -> Result<DataLayoutFile, Diagnostic> {
+            fs::read_to_string(location)
+                .map_err(|it| it.to_string())
+                .and_then(|it| serde_json::from_str(&it).map_err(|it| it.to_string()))
+                .map_err(|it| Diagnostic::new(format!("{location}: {it}")).with_error_code("E002"))
+        };
+        let diff = plc::online_change::diff_data_layouts(&read(old)?, &read(new)?)?;
+        print!("{}", plc::online_change::changes_to_text(&diff.changes));
+        let location = self
+            .compile_parameters
+            .as_ref()
+            .and_then(|it| it.output.clone())
+            .unwrap_or_else(|| DEFAULT_MIGRATION_FILE.to_string());
+        let serialized = serde_json::to_string_pretty(&diff.migration).map_err(|it| {
+            Diagnostic::new(it.to_string()).with_internal_error(it.into()).with_error_code("E002")
+        })?;
+        write_report(&location, &serialized)
+    }
+
     fn get_header_output_file(&self) -> Option<String> {
         self.compile_parameters.as_ref().map(|params| params.output.clone().unwrap_or_default())
     }
@@ -365,7 +472,10 @@ impl<T: SourceContainer> BuildPipeline<T> {
                 self.context.provider(),
                 self.context.should_generate_external_constructors(),
             )),
-            Box::new(RetainParticipant::new(self.context.provider())),
+            Box::new(RetainParticipant::new(
+                self.context.provider(),
+                self.context.should_generate_retain_persistence(),
+            )),
             Box::new(AggregateTypeLowerer::new(self.context.provider())),
             Box::new(InheritanceLowerer::new(self.context.provider())),
             Box::new(ArrayLowerer::new(self.context.provider())),
@@ -390,6 +500,256 @@ impl<T: SourceContainer> BuildPipeline<T> {
     fn get_module_name(&self) -> Option<String> {
         self.module_name.clone()
     }
+
+    /// Compiles the project into a single module and executes a program in it through the JIT
+    fn run_program(&mut self, project: AnnotatedProject, options: &RunOptions) -> Result<(), Diagnostic> {
+        for library in self.find_shared_libraries()? {
+            log::debug!("Loading {}", library.to_string_lossy());
+            plc::codegen::load_library_permanently(&library)?;
+        }
+        let compile_options = self.get_compile_options().unwrap_or_default();
+        let context = CodegenContext::create();
+        let module = project
+            .generate_single_module(&context, &compile_options, None)?
+            .ok_or_else(|| Diagnostic::new("Cannot generate module"))?;
+        runner::run_program(&module, &project.index, options, &mut std::io::stdout())
+    }
+
+    /// Completes the metrics with the call graph and the instance sizes of the generated module and
+    /// prints them, or writes them to the output file if one is given
+    fn report_metrics(
+        &mut self,
+        mut metrics: Vec<PouMetrics>,
+        format: MetricsFormat,
+        project: AnnotatedProject,
+    ) -> Result<(), Diagnostic> {
+        let graph = plc::call_graph::collect_call_graph(
+            project.units.iter().map(AnnotatedUnit::get_unit),
+            &project.index,
+            &project.annotations,
+        );
+        plc::metrics::add_call_graph(&mut metrics, &graph);
+
+        let compile_options = self.get_compile_options().unwrap_or_default();
+        let context = CodegenContext::create();
+        let module = project
+            .generate_single_module(&context, &compile_options, None)?
+            .ok_or_else(|| Diagnostic::new("Cannot generate module"))?;
+        for metric in metrics.iter_mut().filter(|it| it.has_instance) {
+            metric.instance_size = module.get_struct_size(&metric.name);
+        }
+
+        let report = plc::metrics::serialize_metrics(&metrics, format)?;
+        match self.compile_parameters.as_ref().and_then(|it| it.output.as_deref()) {
+            Some(location) => write_report(location, &report),
+            None => {
+                print!("{report}");
+                Ok(())
+            }
+        }
+    }
+
+    /// Resolves the documentation with the index and writes its pages into the output directory,
+    /// `doc` if none is given
+    fn generate_documentation(
+        &self,
+        mut documentation: Documentation,
+        format: DocFormat,
+        project: &AnnotatedProject,
+    ) -> Result<(), Diagnostic> {
+        plc::doc::resolve_documentation(&mut documentation, &project.index);
+        let directory = PathBuf::from(
+            self.compile_parameters.as_ref().and_then(|it| it.output.as_deref()).unwrap_or("doc"),
+        );
+        fs::create_dir_all(&directory).map_err(|it| {
+            Diagnostic::new(format!("{}: {it}", directory.to_string_lossy())).with_error_code("E002")
+        })?;
+        let pages = plc::doc::render_documentation(&documentation, self.project.get_name(), format);
+        for (file_name, content) in &pages {
+            write_report(&directory.join(file_name).to_string_lossy(), content)?;
+        }
+        Ok(())
+    }
+
+    /// Starts an interactive session on stdin with the project's sources and includes loaded
+    fn start_repl(&mut self) -> Result<(), Diagnostic> {
+        for library in self.find_shared_libraries()? {
+            log::debug!("Loading {}", library.to_string_lossy());
+            plc::codegen::load_library_permanently(&library)?;
+        }
+        let load = |containers: &[&T]| {
+            containers
+                .iter()
+                .filter_map(|it| self.context.get(it.get_location_str()))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let sources = load(&self.project.get_sources().iter().collect::<Vec<_>>());
+        let includes = load(
+            &self
+                .project
+                .get_includes()
+                .iter()
+                .chain(self.project.get_libraries().iter().flat_map(LibraryInformation::get_includes))
+                .collect::<Vec<_>>(),
+        );
+        let compile_options = self.get_compile_options().unwrap_or_default();
+
+        let pipeline = BuildPipeline {
+            context: std::mem::take(&mut self.context),
+            project: Project::new("repl".to_string()),
+            diagnostician: std::mem::replace(&mut self.diagnostician, Diagnostician::null_diagnostician()),
+            compile_parameters: None,
+            linker: self.linker.clone(),
+            mutable_participants: vec![],
+            participants: vec![],
+            module_name: None,
+        };
+        // all modules of the session are generated in the same context
+        let context = CodegenContext::create();
+        let mut repl = Repl::new(&context, pipeline, compile_options, sources, includes)?;
+        repl::run(&mut repl, std::io::stdin().lock(), &mut std::io::stdout())
+    }
+
+    /// Formats the project's structured text sources. Files are rewritten in place, or only
+    /// reported if they are not formatted when `check` is set.
+    fn format_sources(&mut self, check: bool) -> Result<(), Diagnostic> {
+        let options = self.project.get_format_options();
+        let mut failed = 0;
+        for container in
+            self.project.get_sources().iter().filter(|it| matches!(it.get_type(), SourceType::Text))
+        {
+            let Some(source) = self.context.get(container.get_location_str()) else { continue };
+            let formatted = match plc::formatter::format(source, options, &mut self.diagnostician) {
+                Ok(formatted) => formatted,
+                Err(diagnostic) => {
+                    self.diagnostician.handle(&[diagnostic]);
+                    failed += 1;
+                    continue;
+                }
+            };
+            if formatted == source.source {
+                continue;
+            }
+            if check {
+                let diagnostic = Diagnostic::new(format!("{} is not formatted", source.get_location_str()))
+                    .with_error_code("E151");
+                self.diagnostician.handle(&[diagnostic]);
+                failed += 1;
+            } else if let Some(path) = container.get_location() {
+                log::info!("Formatting {}", path.to_string_lossy());
+                fs::write(path, formatted)?;
+            }
+        }
+
+        if failed > 0 {
+            let verb = if check { "not formatted" } else { "could not be formatted" };
+            return Err(Diagnostic::new(format!("{failed} file(s) {verb}")));
+        }
+        Ok(())
+    }
+
+    /// Applies the suggested fixes of the reported diagnostics to the project's sources in place.
+    /// A suggestion is only applied as a whole, suggestions overlapping an already accepted one are
+    /// skipped and picked up by the next run.
+    fn fix_sources(&mut self) -> Result<(), Diagnostic> {
+        self.diagnostician.collect_suggestions();
+        let result = self.check_sources();
+
+        // file -> accepted edits as (range, replacement)
+        let mut edits: HashMap<&str, Vec<(std::ops::Range<usize>, String)>> = HashMap::new();
+        let mut fixed = 0;
+        let mut suggestions = self.diagnostician.take_suggestions();
+        // the same diagnostic may be reported more than once
+        suggestions.dedup();
+        for suggestion in suggestions {
+            let Some(resolved) = suggestion
+                .edits
+                .iter()
+                .map(|edit| {
+                    Some((
+                        edit.location.get_file_name()?,
+                        edit.location.to_range()?,
+                        edit.replacement.clone(),
+                    ))
+                })
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let overlaps = resolved.iter().any(|(file, range, _)| {
+                edits.get(file).into_iter().flatten().any(|(accepted, _)| {
+                    accepted.start == range.start
+                        || (accepted.start < range.end && range.start < accepted.end)
+                })
+            });
+            if overlaps || resolved.is_empty() {
+                continue;
+            }
+            log::debug!("Applying fix: {}", suggestion.message);
+            fixed += 1;
+            for (file, range, replacement) in resolved {
+                edits.entry(file).or_default().push((range, replacement));
+            }
+        }
+
+        for container in
+            self.project.get_sources().iter().filter(|it| matches!(it.get_type(), SourceType::Text))
+        {
+            let Some(mut file_edits) = edits.remove(container.get_location_str()) else { continue };
+            let Some(source) = self.context.get(container.get_location_str()) else { continue };
+            let Some(path) = container.get_location() else { continue };
+            // apply back to front so the offsets of the remaining edits stay valid
+            file_edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+            let mut text = source.source.clone();
+            for (range, replacement) in file_edits {
+                text.replace_range(range, &replacement);
+            }
+            log::info!("Fixing {}", path.to_string_lossy());
+            fs::write(path, text)?;
+        }
+
+        if fixed > 0 {
+            println!("Applied {fixed} fix(es)");
+            return Ok(());
+        }
+        result
+    }
+
+    /// Parses, indexes, annotates and validates the project, reporting every diagnostic
+    fn check_sources(&mut self) -> Result<(), Diagnostic> {
+        let parsed_project = self.parse()?;
+        let indexed_project = self.index(parsed_project)?;
+        let annotated_project = self.annotate(indexed_project)?;
+        annotated_project.validate(&self.context, &mut self.diagnostician)
+    }
+
+    /// Resolves the `-l` libraries against the `-L` search paths to shared objects the JIT can load
+    fn find_shared_libraries(&self) -> Result<Vec<PathBuf>, Diagnostic> {
+        let Some(link_options) = self.get_link_options() else { return Ok(vec![]) };
+        let search_paths =
+            link_options.library_paths.iter().map(PathBuf::as_path).chain(std::iter::once(Path::new(".")));
+        link_options
+            .libraries
+            .iter()
+            .map(|library| {
+                // `-l:libfoo.so` names the file, `-l/path/to/libfoo.so` the full path
+                let file_name = match library.strip_prefix(':') {
+                    Some(file_name) => file_name.to_string(),
+                    None if library.contains(std::path::MAIN_SEPARATOR) => {
+                        return Ok(PathBuf::from(library));
+                    }
+                    None => format!("{}{library}{}", env::consts::DLL_PREFIX, env::consts::DLL_SUFFIX),
+                };
+                search_paths.clone().map(|it| it.join(&file_name)).find(|it| it.is_file()).ok_or_else(|| {
+                    Diagnostic::new(format!(
+                        "Cannot find shared library {file_name} in the library search paths"
+                    ))
+                    .with_error_code("E173")
+                })
+            })
+            .collect()
+    }
 }
 
 impl<T: SourceContainer> Pipeline for BuildPipeline<T> {
@@ -412,6 +772,36 @@ impl<T: SourceContainer> Pipeline for BuildPipeline<T> {
             return Ok(());
         }
 
+        if self.compile_parameters.as_ref().is_some_and(CompileParameters::is_repl) {
+            return self.start_repl();
+        }
+
+        if let Some(check) = self.compile_parameters.as_ref().and_then(CompileParameters::get_fmt_check) {
+            return self.format_sources(check);
+        }
+
+        if self.compile_parameters.as_ref().is_some_and(CompileParameters::is_fix) {
+            return self.fix_sources();
+        }
+
+        if let Some(header) = self
+            .compile_parameters
+            .as_ref()
+            .and_then(CompileParameters::get_bindings_header)
+            .map(str::to_string)
+        {
+            return self.generate_bindings(&header);
+        }
+
+        if let Some((old, new)) = self
+            .compile_parameters
+            .as_ref()
+            .and_then(CompileParameters::get_online_change_diff)
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+        {
+            return self.diff_data_layouts(&old, &new);
+        }
+
         //If no input files are available in the project, stop the compilation
         if self.project.get_sources().is_empty() && self.project.get_objects().is_empty() {
             return Err(Diagnostic::new("no input files"));
@@ -427,6 +817,18 @@ impl<T: SourceContainer> Pipeline for BuildPipeline<T> {
             return Ok(());
         }
 
+        // The metrics describe the code as it was written, so they are taken before the bodies are lowered
+        let metrics = self
+            .compile_parameters
+            .as_ref()
+            .and_then(CompileParameters::get_metrics_format)
+            .map(|format| (plc::metrics::collect_metrics(&parsed_project.units), format));
+        let documentation = self
+            .compile_parameters
+            .as_ref()
+            .and_then(CompileParameters::get_doc_format)
+            .map(|format| (plc::doc::collect_documentation(&parsed_project.units), format));
+
         // 2. Index
         let indexed_project = self.index(parsed_project)?;
 
@@ -472,6 +874,46 @@ impl<T: SourceContainer> Pipeline for BuildPipeline<T> {
             }
         }
 
+        if let Some((location, format)) = self.compile_parameters.as_ref().and_then(|it| it.symbols_target())
+        {
+            annotated_project.generate_symbols(format, location)?;
+        }
+
+        // Additional reports, like hwmap these only need the annotated project
+        for emit in self.compile_parameters.iter().flat_map(|it| &it.emit) {
+            match emit.kind {
+                EmitKind::Xref => annotated_project.generate_xref(&emit.path)?,
+                EmitKind::CallGraph => annotated_project.generate_call_graph(&emit.path)?,
+                EmitKind::Layout => {
+                    let target = self.compile_parameters.as_ref().and_then(|it| it.target.clone());
+                    annotated_project.generate_layout(&target.unwrap_or_default(), &emit.path)?
+                }
+            }
+        }
+
+        // The data layout is compared with the one of the next build to migrate the instance data
+        if let Some(params) = self.compile_parameters.as_ref().filter(|it| it.online_change) {
+            let target = params.target.clone().unwrap_or_default();
+            annotated_project.generate_data_layout(&target, &params.data_layout_file)?;
+        }
+
+        if let Some((metrics, format)) = metrics {
+            return self.report_metrics(metrics, format, annotated_project);
+        }
+
+        if let Some((documentation, format)) = documentation {
+            return self.generate_documentation(documentation, format, &annotated_project);
+        }
+
+        if self.compile_parameters.as_ref().is_some_and(CompileParameters::is_opcua_generator) {
+            return self.generate_node_set(&annotated_project);
+        }
+
+        // Execute a program through the JIT instead of generating output
+        if let Some(options) = self.compile_parameters.as_ref().and_then(CompileParameters::get_run_options) {
+            return self.run_program(annotated_project, &options);
+        }
+
         // Skip code-gen if it is check
         if self.compile_parameters.as_ref().is_some_and(CompileParameters::is_check) {
             return Ok(());
@@ -487,7 +929,13 @@ impl<T: SourceContainer> Pipeline for BuildPipeline<T> {
     }
 
     fn parse(&mut self) -> Result<ParsedProject, Diagnostic> {
-        let project = ParsedProject::parse(&self.context, &self.project, &mut self.diagnostician)?;
+        let cache = self.get_build_cache();
+        let project = ParsedProject::parse_with_cache(
+            &self.context,
+            &self.project,
+            &mut self.diagnostician,
+            cache.as_ref(),
+        )?;
         Ok(project)
     }
 
@@ -496,7 +944,8 @@ impl<T: SourceContainer> Pipeline for BuildPipeline<T> {
             p.pre_index(&project);
         });
         let project = self.mutable_participants.iter_mut().fold(project, |project, p| p.pre_index(project));
-        let indexed_project = project.index(self.context.provider());
+        let indexed_project =
+            project.index_with_cache(self.context.provider(), self.get_build_cache().as_ref());
         self.participants.iter().for_each(|p| {
             p.post_index(&indexed_project);
         });
@@ -555,7 +1004,17 @@ impl<T: SourceContainer> Pipeline for BuildPipeline<T> {
             let _ = project
                 .units
                 .par_iter()
-                .map(|AnnotatedUnit { unit, dependencies, literals }| {
+                .map(|annotated_unit| {
+                    // a unit is not generated again if a participant could reuse a previous result
+                    let reused = self
+                        .participants
+                        .iter()
+                        .map(|participant| participant.reuse(annotated_unit))
+                        .collect::<Result<Vec<_>, Diagnostic>>()?;
+                    if reused.contains(&true) {
+                        return Ok(());
+                    }
+                    let AnnotatedUnit { unit, dependencies, literals } = annotated_unit;
                     let context = CodegenContext::create();
                     let module = project.generate_module(
                         &context,
@@ -660,6 +1119,28 @@ fn write_header_file(generated_header: Box<dyn GeneratedHeader>) -> Result<(), D
     Ok(())
 }
 
+/// Parses a text source, unless the given cache holds the unit parsed from the same source in a previous build
+fn parse_text(
+    source: &SourceCode,
+    linkage: LinkageType,
+    ctxt: &GlobalContext,
+    diagnostician: &mut Diagnostician,
+    cache: Option<&BuildCache>,
+) -> Result<CompilationUnit, Diagnostic> {
+    let Some(cache) = cache else {
+        return parse_file(source, linkage, ctxt.provider(), diagnostician);
+    };
+    if let Some(unit) = cache.load_unit(source, linkage, ctxt.provider()) {
+        return report_parse_diagnostics(source, (unit, vec![]), diagnostician);
+    }
+    let (unit, diagnostics) = parse_source(source, linkage, ctxt.provider());
+    // units with diagnostics are not cached so the diagnostics are reported again in the next build
+    if diagnostics.is_empty() {
+        cache.store_unit(source, linkage, &unit);
+    }
+    report_parse_diagnostics(source, (unit, diagnostics), diagnostician)
+}
+
 ///Represents a parsed project
 ///For this struct to be built, the project would have been parsed correctly and an AST would have
 ///been generated
@@ -667,6 +1148,10 @@ fn write_header_file(generated_header: Box<dyn GeneratedHeader>) -> Result<(), D
 #[serde(bound(deserialize = "'de: 'static"))]
 pub struct ParsedProject {
     units: Vec<CompilationUnit>,
+    /// Declarations indexed by an earlier build (e.g. the inputs of a `plc repl` session),
+    /// the units are indexed on top of them instead of an index with only the built-ins
+    #[serde(skip)]
+    imports: Option<Arc<Index>>,
 }
 
 impl ParsedProject {
@@ -676,6 +1161,17 @@ impl ParsedProject {
         ctxt: &GlobalContext,
         project: &Project<T>,
         diagnostician: &mut Diagnostician,
+    ) -> Result<Self, Diagnostic> {
+        Self::parse_with_cache(ctxt, project, diagnostician, None)
+    }
+
+    /// Parses a giving project like [`ParsedProject::parse`],
+    /// text sources that did not change since they were stored in the given cache are not parsed again
+    pub fn parse_with_cache<T: SourceContainer + Sync>(
+        ctxt: &GlobalContext,
+        project: &Project<T>,
+        diagnostician: &mut Diagnostician,
+        cache: Option<&BuildCache>,
     ) -> Result<Self, Diagnostic> {
         //TODO in parallel
         //Parse the source files
@@ -687,13 +1183,15 @@ impl ParsedProject {
             .map(|it| {
                 let source = ctxt.get(it.get_location_str()).expect("All sources should've been read");
 
-                let parse_func = match source.get_type() {
-                    source_code::SourceType::Text => parse_file,
-                    source_code::SourceType::Xml => plc_cfc::parse_file,
+                match source.get_type() {
+                    source_code::SourceType::Text => {
+                        parse_text(source, LinkageType::Internal, ctxt, diagnostician, cache)
+                    }
+                    source_code::SourceType::Xml => {
+                        plc_cfc::parse_file(source, LinkageType::Internal, ctxt.provider(), diagnostician)
+                    }
                     source_code::SourceType::Unknown => unreachable!(),
-                };
-
-                parse_func(source, LinkageType::Internal, ctxt.provider(), diagnostician)
+                }
             })
             .collect::<Vec<_>>();
 
@@ -705,7 +1203,7 @@ impl ParsedProject {
             .iter()
             .map(|it| {
                 let source = ctxt.get(it.get_location_str()).expect("All sources should've been read");
-                parse_file(source, LinkageType::Include, ctxt.provider(), diagnostician)
+                parse_text(source, LinkageType::Include, ctxt, diagnostician, cache)
             })
             .collect::<Vec<_>>();
         units.extend(includes);
@@ -717,51 +1215,84 @@ impl ParsedProject {
             .flat_map(LibraryInformation::get_includes)
             .map(|it| {
                 let source = ctxt.get(it.get_location_str()).expect("All sources should've been read");
-                parse_file(source, LinkageType::Include, ctxt.provider(), diagnostician)
+                parse_text(source, LinkageType::Include, ctxt, diagnostician, cache)
             })
             .collect::<Vec<_>>();
         units.extend(lib_includes);
 
         let units = units.into_iter().collect::<Result<Vec<_>, Diagnostic>>()?;
 
-        Ok(ParsedProject { units })
+        Ok(ParsedProject { units, imports: None })
+    }
+
+    /// Creates a project out of units that were parsed elsewhere (e.g. leniently, ignoring syntax errors)
+    pub fn from_units(units: Vec<CompilationUnit>) -> Self {
+        ParsedProject { units, imports: None }
+    }
+
+    /// Indexes the units against the given declarations, they are kept through all following steps
+    pub fn with_imports(mut self, imports: Arc<Index>) -> Self {
+        self.imports = Some(imports);
+        self
+    }
+
+    /// Adds an already parsed unit to the project
+    pub fn with_unit(mut self, unit: CompilationUnit) -> Self {
+        self.units.push(unit);
+        self
     }
 
     /// Creates an index out of a pased project. The index could then be used to query datatypes
     pub fn index(self, id_provider: IdProvider) -> IndexedProject {
+        self.index_with_cache(id_provider, None)
+    }
+
+    /// Creates an index like [`ParsedProject::index`],
+    /// units that were already indexed by a previous build are taken from the given cache
+    pub fn index_with_cache(self, id_provider: IdProvider, cache: Option<&BuildCache>) -> IndexedProject {
+        let index_unit = |mut unit: CompilationUnit| {
+            //Preprocess
+            pre_process(&mut unit, id_provider.clone());
+            //import to index
+            let index = indexer::index(&unit);
+
+            (index, unit)
+        };
         let indexed_units = self
             .units
             .into_par_iter()
-            .map(|mut unit| {
-                //Preprocess
-                pre_process(&mut unit, id_provider.clone());
-                //import to index
-                let index = indexer::index(&unit);
-
-                (index, unit)
+            .map(|unit| match cache {
+                Some(cache) => cache.index_unit(unit, id_provider.clone(), index_unit),
+                None => index_unit(unit),
             })
             .collect::<Vec<_>>();
 
-        let mut global_index = Index::default();
+        let mut global_index = match &self.imports {
+            Some(imports) => Index::with_imports(imports.clone()),
+            None => Index::default(),
+        };
         let mut units = vec![];
         for (index, unit) in indexed_units {
             units.push(unit);
             global_index.import(index);
         }
 
-        // import built-in types like INT, BOOL, etc.
-        for data_type in plc::typesystem::get_builtin_types() {
-            global_index.register_type(data_type);
-        }
+        // imported declarations already contain the built-ins
+        if self.imports.is_none() {
+            // import built-in types like INT, BOOL, etc.
+            for data_type in plc::typesystem::get_builtin_types() {
+                global_index.register_type(data_type);
+            }
 
-        // import builtin functions
-        let builtins = plc::builtins::parse_built_ins(id_provider);
-        global_index.import(indexer::index(&builtins));
+            // import builtin functions
+            let builtins = plc::builtins::parse_built_ins(id_provider);
+            global_index.import(indexer::index(&builtins));
+        }
 
         //TODO: evaluate constants should probably be a participant
         let (index, _unresolvables) = plc::resolver::const_evaluator::evaluate_constants(global_index);
 
-        IndexedProject { project: ParsedProject { units }, index, _unresolvables }
+        IndexedProject { project: ParsedProject { units, imports: self.imports }, index, _unresolvables }
     }
 }
 
@@ -803,7 +1334,13 @@ impl IndexedProject {
 
         let annotations = AstAnnotations::new(all_annotations, id_provider.next_id());
 
-        AnnotatedProject { units: annotated_units, index, annotations, diagnostics: Vec::new() }
+        AnnotatedProject {
+            units: annotated_units,
+            index,
+            annotations,
+            diagnostics: Vec::new(),
+            imports: self.project.imports,
+        }
     }
 }
 
@@ -845,6 +1382,9 @@ pub struct AnnotatedProject {
     /// Diagnostics produced by lowering participants after annotation.
     #[serde(skip)]
     pub diagnostics: Vec<Diagnostic>,
+    /// The declarations the project was indexed against, see [`ParsedProject::with_imports`]
+    #[serde(skip)]
+    pub imports: Option<Arc<Index>>,
 }
 
 impl AnnotatedProject {
@@ -857,7 +1397,8 @@ impl AnnotatedProject {
         let mut severity = diagnostician.handle(&self.diagnostics);
 
         // perform global validation
-        let mut validator = Validator::new(ctxt);
+        let mut validator =
+            Validator::new(ctxt).with_lint_settings(diagnostician.get_lint_settings().clone());
         validator.perform_global_validation(&self.index);
         let diagnostics = validator.diagnostics();
         severity = severity.max(diagnostician.handle(&diagnostics));
@@ -870,6 +1411,14 @@ impl AnnotatedProject {
             let diagnostics = validator.diagnostics();
             severity = severity.max(diagnostician.handle(&diagnostics));
         });
+
+        // reachability depends on all units of the project
+        let units = self.units.iter().map(AnnotatedUnit::get_unit).collect::<Vec<_>>();
+        validator.perform_reachability_validation(&self.annotations, &self.index, &units);
+        validator.perform_definite_assignment_validation(&self.annotations, &self.index, &units);
+        validator.perform_lint_validation(&self.annotations, &self.index, &units);
+        severity = severity.max(diagnostician.handle(&validator.diagnostics()));
+
         if severity == Severity::Error {
             Err(Diagnostic::new("Compilation aborted due to critical errors"))
         } else {
@@ -942,6 +1491,41 @@ impl AnnotatedProject {
         module.map(Some)
     }
 
+    /// Generates a single module containing only the units parsed from `file_names`,
+    /// the symbols of all other units are declared as external
+    pub fn generate_units_module<'ctx>(
+        &self,
+        context: &'ctx CodegenContext,
+        compile_options: &CompileOptions,
+        file_names: &[&str],
+    ) -> Result<Option<GeneratedModule<'ctx>>, Diagnostic> {
+        let got_layout = Mutex::new(HashMap::default());
+        let Some(module) = self
+            .units
+            .iter()
+            .filter(|it| it.unit.file.get_name().is_some_and(|name| file_names.contains(&name)))
+            .map(|AnnotatedUnit { unit, dependencies, literals }| {
+                self.generate_module(
+                    context,
+                    compile_options,
+                    unit,
+                    dependencies,
+                    literals,
+                    &got_layout,
+                    None,
+                )
+            })
+            .reduce(|a, b| {
+                let a = a?;
+                let b = b?;
+                a.merge(b).map_err(Into::into)
+            })
+        else {
+            return Ok(None);
+        };
+        module.map(Some)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn generate_module<'ctx>(
         &self,
@@ -1069,14 +1653,69 @@ impl AnnotatedProject {
     pub fn generate_hw_map(&self, format: ConfigFormat, location: &str) -> Result<(), Diagnostic> {
         let map = plc::hw_map::collect_hw_map(&self.index)?;
         let serialized = plc::hw_map::serialize_hw_map(&map, format)?;
-        // Bad path / permission denied / ENOSPC are user-environment errors, not
-        // internal-compiler bugs — surface them as plain E002 IO errors without
-        // wrapping them as internal errors.
-        File::create(location)
-            .and_then(|mut it| it.write_all(serialized.as_bytes()))
-            .map_err(|it| Diagnostic::new(format!("{location}: {it}")).with_error_code("E002"))?;
-        Ok(())
+        write_report(location, &serialized)
+    }
+
+    /// Writes the symbol table of all global variables and program instances to the given location
+    pub fn generate_symbols(&self, format: SymbolsFormat, location: &str) -> Result<(), Diagnostic> {
+        let symbols =
+            plc::symbols::collect_symbols(&self.index, self.units.iter().map(AnnotatedUnit::get_unit))?;
+        write_report(location, &plc::symbols::serialize_symbols(&symbols, format)?)
+    }
+
+    /// Writes a JSON report of all variable accesses to the given location
+    pub fn generate_xref(&self, location: &str) -> Result<(), Diagnostic> {
+        let xref = plc::xref::collect_cross_references(
+            self.units.iter().map(AnnotatedUnit::get_unit),
+            &self.index,
+            &self.annotations,
+        )?;
+        let serialized = serde_json::to_string_pretty(&xref).map_err(|it| {
+            Diagnostic::new(it.to_string()).with_internal_error(it.into()).with_error_code("E002")
+        })?;
+        write_report(location, &serialized)
     }
+
+    /// Writes the call graph of all POUs to the given location, as DOT or JSON depending on its extension
+    pub fn generate_call_graph(&self, location: &str) -> Result<(), Diagnostic> {
+        let format = CallGraphFormat::from_file_name(location).ok_or_else(|| {
+            Diagnostic::new(format!("Cannot identify call graph format from path: {location}"))
+                .with_error_code("E002")
+        })?;
+        let graph = plc::call_graph::collect_call_graph(
+            self.units.iter().map(AnnotatedUnit::get_unit),
+            &self.index,
+            &self.annotations,
+        );
+        write_report(location, &plc::call_graph::serialize_call_graph(&graph, format)?)
+    }
+
+    /// Writes the memory layout of all types on the given target to the given location, as JSON or text
+    /// depending on its extension
+    pub fn generate_layout(&self, target: &Target, location: &str) -> Result<(), Diagnostic> {
+        let report = plc::layout::collect_layout(&self.index, target)?;
+        let format = plc::layout::LayoutFormat::from_file_name(location);
+        write_report(location, &plc::layout::serialize_layout(&report, format)?)
+    }
+
+    /// Writes the layout of all types, global variables and program instances on the given target to the
+    /// given location, for online changes
+    pub fn generate_data_layout(&self, target: &Target, location: &str) -> Result<(), Diagnostic> {
+        let layout = plc::online_change::collect_data_layout(&self.index, target)?;
+        let serialized = serde_json::to_string_pretty(&layout).map_err(|it| {
+            Diagnostic::new(it.to_string()).with_internal_error(it.into()).with_error_code("E002")
+        })?;
+        write_report(location, &serialized)
+    }
+}
+
+fn write_report(location: &str, content: &str) -> Result<(), Diagnostic> {
+    // Bad path / permission denied / ENOSPC are user-environment errors, not
+    // internal-compiler bugs — surface them as plain E002 IO errors without
+    // wrapping them as internal errors.
+    File::create(location)
+        .and_then(|mut it| it.write_all(content.as_bytes()))
+        .map_err(|it| Diagnostic::new(format!("{location}: {it}")).with_error_code("E002"))
 }
 
 /// Ensures the directores for the various targets have been created
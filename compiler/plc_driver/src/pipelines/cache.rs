@@ -0,0 +1,439 @@
+//!
+//! The build cache keeps intermediate results of previous builds in the build location
+//! so unchanged sources do not have to be parsed, indexed and generated again.
+//!
+//! Every entry is keyed by a hash of its input and of the compiler that produced it:
+//! - parse results by the content of the source
+//! - index fragments by the parsed unit
+//! - objects by the lowered unit, the compile options and the public interface of every unit
+//!   it depends on. Changing the declarations of a unit therefore regenerates its dependents,
+//!   changing only its implementation does not.
+//!
+//! AST ids are not stable between builds, cached units are stored with renumbered ids
+//! which are mapped to ids of the current build when they are loaded.
+//! Entries are never removed, deleting the cache directory is always safe.
+//!
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    hash::{Hash, Hasher},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use ast::{
+    ast::{CompilationUnit, LinkageType},
+    provider::IdProvider,
+};
+use itertools::Itertools;
+use plc::{index::Index, DebugLevel, Target};
+use serde_json::{Map, Value};
+use siphasher::sip::SipHasher13;
+use source_code::{source_location::FileMarker, SourceCode, SourceContainer};
+
+use crate::CompileOptions;
+
+use super::AnnotatedUnit;
+
+/// The directory inside the build location the cache is stored in
+pub const CACHE_DIRECTORY: &str = ".cache";
+
+#[derive(Debug, Clone)]
+pub struct BuildCache {
+    location: PathBuf,
+    fingerprint: u64,
+}
+
+impl BuildCache {
+    /// Creates a cache stored in the given build location, `None` if the running compiler cannot be
+    /// identified
+    pub fn new(build_location: &Path) -> Option<Self> {
+        let fingerprint = compiler_fingerprint()?;
+        Some(BuildCache { location: build_location.join(CACHE_DIRECTORY), fingerprint })
+    }
+
+    /// Returns the unit a previous build parsed from the same source, its ids are taken from the given provider
+    pub fn load_unit(
+        &self,
+        source: &SourceCode,
+        linkage: LinkageType,
+        mut id_provider: IdProvider,
+    ) -> Option<CompilationUnit> {
+        let cached = self.read(&self.unit_entry(source, linkage))?;
+        let mut ids = HashMap::new();
+        let unit = map_ids(cached, &mut |id| *ids.entry(id).or_insert_with(|| id_provider.next_id() as u64));
+        // the locations of a parsed unit only refer to its own source
+        FileMarker::deserialize_with_names(&[source.get_location_str()], || serde_json::from_value(unit).ok())
+    }
+
+    /// Stores the unit parsed from the given source
+    pub fn store_unit(&self, source: &SourceCode, linkage: LinkageType, unit: &CompilationUnit) {
+        match serde_json::to_value(unit) {
+            Ok(unit) => self.write(&self.unit_entry(source, linkage), &unit),
+            Err(err) => log::debug!("Could not cache {:?}: {err}", source.get_location()),
+        }
+    }
+
+    fn unit_entry(&self, source: &SourceCode, linkage: LinkageType) -> PathBuf {
+        let key = self.key(|hasher| {
+            source.get_location().hash(hasher);
+            linkage.hash(hasher);
+            source.source.hash(hasher);
+        });
+        self.entry("units", key)
+    }
+
+    /// Pre-processes and indexes the given unit using `index`, unless a previous build already
+    /// did so for an identical unit. The result of `index` is stored for later builds.
+    pub fn index_unit(
+        &self,
+        unit: CompilationUnit,
+        mut id_provider: IdProvider,
+        index: impl FnOnce(CompilationUnit) -> (Index, CompilationUnit),
+    ) -> (Index, CompilationUnit) {
+        let Ok(parsed) = serde_json::to_value(&unit) else {
+            return index(unit);
+        };
+        let mut ids = CanonicalIds::default();
+        let parsed = ids.canonicalize(parsed);
+        let entry = self.entry("index", self.key(|hasher| parsed.to_string().hash(hasher)));
+
+        if let Some(cached) = self.read(&entry) {
+            // the locations of an indexed unit only refer to its own file
+            let cached = ids.restore(cached, &mut id_provider);
+            let names = unit.file.get_name().into_iter().collect::<Vec<_>>();
+            let result = FileMarker::deserialize_with_names(&names, || serde_json::from_value(cached));
+            if let Ok(result) = result {
+                return result;
+            }
+        }
+
+        let (index, unit) = index(unit);
+        match serde_json::to_value((&index, &unit)) {
+            Ok(result) => self.write(&entry, &ids.canonicalize(result)),
+            Err(err) => log::debug!("Could not cache the index of {:?}: {err}", unit.file.get_name()),
+        }
+        (index, unit)
+    }
+
+    /// Computes the keys of the objects generated from the given units. The key of a unit covers the
+    /// compile options, the unit itself and the public interface of all units it (transitively) depends on.
+    /// Units that share their location with another unit get no key since their objects cannot be told apart.
+    pub fn object_keys(
+        &self,
+        units: &[AnnotatedUnit],
+        options: &CompileOptions,
+        target: &Target,
+    ) -> HashMap<PathBuf, u64> {
+        let Ok(values) = units.iter().map(|it| serde_json::to_value(&it.unit)).collect::<Result<Vec<_>, _>>()
+        else {
+            return HashMap::new();
+        };
+        // declaration locations only end up in the object of a dependent as part of its debug information
+        let keep_locations = !matches!(options.debug_level, DebugLevel::None);
+        let interfaces = values
+            .iter()
+            .map(|it| hash_value(&strip(interface_of(it.clone()), keep_locations)))
+            .collect_vec();
+        let contents = values.into_iter().map(|it| hash_value(&strip(it, true))).collect_vec();
+
+        let mut declarations = HashMap::new();
+        for (idx, unit) in units.iter().enumerate() {
+            for name in declared_names(&unit.unit) {
+                declarations.entry(name.to_lowercase()).or_insert(idx);
+            }
+        }
+        let dependencies = units
+            .iter()
+            .map(|unit| {
+                unit.dependencies
+                    .iter()
+                    .filter_map(|dependency| {
+                        let name = dependency.get_name().to_lowercase();
+                        declarations
+                            .get(&name)
+                            .or_else(|| {
+                                name.split_once('.').and_then(|(container, _)| declarations.get(container))
+                            })
+                            .copied()
+                    })
+                    .collect::<HashSet<_>>()
+            })
+            .collect_vec();
+
+        let options = format!(
+            "{:?}",
+            (
+                &options.root,
+                &options.output_format,
+                &options.optimization,
+                &options.relocation_preference,
+                &options.debug_level,
+                &options.debug_prefix_maps,
+                &options.debug_compilation_dir,
+                &options.constructors_only,
+                &options.build_info,
+                target,
+            )
+        );
+        let locations = units.iter().map(|it| PathBuf::from(&it.unit.file)).collect_vec();
+        let shared_locations = locations.iter().duplicates().cloned().collect::<HashSet<_>>();
+
+        locations
+            .iter()
+            .enumerate()
+            .filter(|(_, location)| !shared_locations.contains(*location))
+            .map(|(idx, location)| {
+                let used_interfaces = dependency_closure(idx, &dependencies)
+                    .into_iter()
+                    .map(|dependency| (&locations[dependency], interfaces[dependency]))
+                    .sorted()
+                    .collect_vec();
+                let key = self.key(|hasher| {
+                    options.hash(hasher);
+                    contents[idx].hash(hasher);
+                    used_interfaces.hash(hasher);
+                });
+                (location.clone(), key)
+            })
+            .collect()
+    }
+
+    /// Copies the object stored for the given key to `output`, returns false if there is no such object
+    pub fn load_object(&self, key: u64, output: &Path) -> bool {
+        let entry = self.entry("objects", key);
+        entry.is_file() && output.parent().is_none_or(|it| fs::create_dir_all(it).is_ok()) && {
+            fs::copy(entry, output)
+                .map_err(|err| log::debug!("Could not reuse a cached object: {err}"))
+                .is_ok()
+        }
+    }
+
+    /// Stores a copy of the given object for later builds
+    pub fn store_object(&self, key: u64, object: &Path) {
+        match fs::read(object) {
+            Ok(content) => self.write_bytes(&self.entry("objects", key), &content),
+            Err(err) => log::debug!("Could not cache {}: {err}", object.to_string_lossy()),
+        }
+    }
+
+    fn key(&self, hash: impl FnOnce(&mut SipHasher13)) -> u64 {
+        let mut hasher = SipHasher13::new_with_keys(0, 0);
+        self.fingerprint.hash(&mut hasher);
+        hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn entry(&self, kind: &str, key: u64) -> PathBuf {
+        self.location.join(kind).join(format!("{key:016x}"))
+    }
+
+    fn read(&self, entry: &Path) -> Option<Value> {
+        let content = fs::read(entry).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    fn write(&self, entry: &Path, value: &Value) {
+        self.write_bytes(entry, value.to_string().as_bytes())
+    }
+
+    /// Writes the entry through a temporary file so concurrent builds never see partial entries.
+    /// The cache is best effort, failing to write it does not fail the build.
+    fn write_bytes(&self, entry: &Path, content: &[u8]) {
+        let Some(directory) = entry.parent() else { return };
+        let result = fs::create_dir_all(directory)
+            .and_then(|_| tempfile::NamedTempFile::new_in(directory))
+            .and_then(|mut file| {
+                file.write_all(content)?;
+                file.persist(entry).map_err(|it| it.error)?;
+                Ok(())
+            });
+        if let Err(err) = result {
+            log::debug!("Could not write cache entry {}: {err}", entry.to_string_lossy());
+        }
+    }
+}
+
+/// Identifies the running compiler by its version, the commit it was built from (see `build.rs`) and
+/// the size and modification time of its executable, entries written by a different compiler are never
+/// used. Builds of the same commit with local changes only differ in their executable, without it the
+/// compiler cannot be identified.
+fn compiler_fingerprint() -> Option<u64> {
+    let executable = std::env::current_exe().and_then(fs::metadata).ok()?;
+    let mut hasher = SipHasher13::new_with_keys(0, 0);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    option_env!("RUSTY_BUILD_HASH").hash(&mut hasher);
+    executable.len().hash(&mut hasher);
+    executable.modified().ok()?.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Renumbers AST ids in the order they appear, so identical units have identical ids in every build
+#[derive(Default)]
+struct CanonicalIds {
+    canonical: HashMap<u64, u64>,
+}
+
+impl CanonicalIds {
+    fn canonicalize(&mut self, value: Value) -> Value {
+        let canonical = &mut self.canonical;
+        map_ids(value, &mut |id| {
+            let next = canonical.len() as u64;
+            *canonical.entry(id).or_insert(next)
+        })
+    }
+
+    /// Maps canonical ids back to the ids they were created from,
+    /// ids that were not canonicalized (e.g. created while indexing) are taken from the provider
+    fn restore(&self, value: Value, id_provider: &mut IdProvider) -> Value {
+        let mut actual =
+            self.canonical.iter().map(|(actual, canonical)| (*canonical, *actual)).collect::<HashMap<_, _>>();
+        map_ids(value, &mut |id| *actual.entry(id).or_insert_with(|| id_provider.next_id() as u64))
+    }
+}
+
+/// Replaces the value of every `id` field
+fn map_ids(value: Value, map: &mut impl FnMut(u64) -> u64) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        Value::Number(id) if name == "id" => {
+                            id.as_u64().map(|id| Value::from(map(id))).unwrap_or(Value::Number(id))
+                        }
+                        value => map_ids(value, map),
+                    };
+                    (name, value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => values.into_iter().map(|it| map_ids(it, map)).collect(),
+        value => value,
+    }
+}
+
+/// Removes all ids and, unless they are kept, all source locations
+fn strip(value: Value, keep_locations: bool) -> Value {
+    match value {
+        Value::Object(fields) if !keep_locations && is_location(&fields) => Value::Null,
+        Value::Object(fields) => fields
+            .into_iter()
+            .filter(|(name, _)| name != "id")
+            .map(|(name, value)| (name, strip(value, keep_locations)))
+            .collect(),
+        Value::Array(values) => values.into_iter().map(|it| strip(it, keep_locations)).collect(),
+        value => value,
+    }
+}
+
+fn is_location(fields: &Map<String, Value>) -> bool {
+    fields.len() == 2 && fields.contains_key("span") && fields.contains_key("file")
+}
+
+/// The fields of a serialized unit other units can see. Everything else, e.g. comments, doc comments
+/// or attributes, does not change the objects generated for the units depending on it.
+const INTERFACE_FIELDS: &[&str] =
+    &["global_vars", "var_config", "pous", "implementations", "interfaces", "user_types", "file", "linkage"];
+
+/// Reduces a serialized unit to what other units can see of it, i.e. its declarations without statements
+fn interface_of(unit: Value) -> Value {
+    let Value::Object(fields) = unit else {
+        return unit;
+    };
+    fields
+        .into_iter()
+        .filter(|(name, _)| INTERFACE_FIELDS.contains(&name.as_str()))
+        .map(|(name, mut value)| {
+            if let Value::Array(implementations) = &mut value {
+                if name == "implementations" {
+                    implementations.iter_mut().filter_map(Value::as_object_mut).for_each(|it| {
+                        it.remove("statements");
+                    });
+                }
+            }
+            (name, value)
+        })
+        .collect()
+}
+
+fn hash_value(value: &Value) -> u64 {
+    let mut hasher = SipHasher13::new_with_keys(0, 0);
+    value.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the names of all symbols declared in the given unit
+fn declared_names(unit: &CompilationUnit) -> impl Iterator<Item = &str> {
+    unit.pous
+        .iter()
+        .map(|it| it.name.as_str())
+        .chain(unit.implementations.iter().map(|it| it.name.as_str()))
+        .chain(unit.interfaces.iter().map(|it| it.ident.name.as_str()))
+        .chain(unit.user_types.iter().filter_map(|it| it.data_type.get_name()))
+        .chain(unit.global_vars.iter().flat_map(|it| it.variables.iter().map(|it| it.name.as_str())))
+}
+
+/// Returns all units the given unit depends on, directly or through other units
+fn dependency_closure(unit: usize, dependencies: &[HashSet<usize>]) -> HashSet<usize> {
+    let mut closure = HashSet::new();
+    let mut pending = vec![unit];
+    while let Some(current) = pending.pop() {
+        pending.extend(dependencies[current].iter().filter(|it| closure.insert(**it)));
+    }
+    closure.remove(&unit);
+    closure
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::provider::IdProvider;
+    use serde_json::json;
+
+    use super::{interface_of, strip, CanonicalIds};
+
+    #[test]
+    fn ids_are_renumbered_in_order_of_appearance_and_restored() {
+        let mut ids = CanonicalIds::default();
+        let unit = json!({ "a": { "id": 17 }, "b": [{ "id": 4 }, { "id": 17 }] });
+        assert_eq!(ids.canonicalize(unit), json!({ "a": { "id": 0 }, "b": [{ "id": 1 }, { "id": 0 }] }));
+
+        // ids the cached value introduced are taken from the provider
+        let cached = json!({ "x": [{ "id": 1 }, { "id": 0 }, { "id": 2 }] });
+        assert_eq!(
+            ids.restore(cached, &mut IdProvider::default()),
+            json!({ "x": [{ "id": 4 }, { "id": 17 }, { "id": 1 }] })
+        );
+    }
+
+    #[test]
+    fn only_declarations_without_ids_and_locations_are_part_of_the_interface() {
+        let location = json!({ "span": "None", "file": "Undefined" });
+        let unit = json!({
+            "pous": [{ "id": 1, "name": "foo", "location": location }],
+            "implementations": [{ "name": "foo", "statements": [{ "id": 2 }] }],
+            "trivia": ["// comment"],
+            "doc_comments": [{ "text": "doc" }],
+            "attributes": [{ "name": "symbol" }],
+            "fields_added_later": [],
+        });
+
+        assert_eq!(
+            strip(interface_of(unit.clone()), false),
+            json!({ "pous": [{ "name": "foo", "location": null }], "implementations": [{ "name": "foo" }] })
+        );
+        assert_eq!(
+            strip(unit, true),
+            json!({
+                "pous": [{ "name": "foo", "location": location }],
+                "implementations": [{ "name": "foo", "statements": [{}] }],
+                "trivia": ["// comment"],
+                "doc_comments": [{ "text": "doc" }],
+                "attributes": [{ "name": "symbol" }],
+                "fields_added_later": [],
+            })
+        );
+    }
+}
@@ -17,7 +17,8 @@ use plc::{
     codegen::GeneratedModule,
     lowering::{calls::AggregateTypeLowerer, polymorphism::PolymorphismLowerer},
     output::FormatOption,
-    ConfigFormat, OnlineChange, Target,
+    stack_usage::{serialize_stack_usage, FrameSize, StackGraph, StackUsageFormat},
+    ConfigFormat, OnlineChange, OptimizationLevel, Target,
 };
 use plc_diagnostics::diagnostics::Diagnostic;
 use plc_lowering::{
@@ -28,7 +29,10 @@ use plc_lowering::{
 use project::{object::Object, project::LibraryInformation};
 use source_code::SourceContainer;
 
-use super::{AnnotatedProject, AnnotatedUnit, GeneratedProject, IndexedProject, ParsedProject};
+use super::{
+    cache::BuildCache, write_report, AnnotatedProject, AnnotatedUnit, GeneratedProject, IndexedProject,
+    ParsedProject,
+};
 
 /// A Build particitpant for different steps in the pipeline
 /// Implementors can decide parse the Ast and project information
@@ -51,6 +55,12 @@ pub trait PipelineParticipant: Sync + Send {
     fn pre_generate(&mut self, _annotated_project: &AnnotatedProject) -> Result<(), Diagnostic> {
         Ok(())
     }
+    /// Implement this to provide the result of generating a unit without generating it,
+    /// e.g. from a previous build. If any participant returns true the unit is not generated
+    /// This is only called when generating multiple modules
+    fn reuse(&self, _unit: &AnnotatedUnit) -> Result<bool, Diagnostic> {
+        Ok(false)
+    }
     /// Implement this to get access to the module generation section of the codegen
     /// This is useful if generating multiple modules to hook into single module generation
     fn generate(&self, _generated_module: &GeneratedModule) -> Result<(), Diagnostic> {
@@ -105,6 +115,9 @@ pub struct CodegenParticipant<T: SourceContainer> {
     pub compile_dirs: HashMap<Target, PathBuf>,
     pub objects: Arc<RwLock<GeneratedProject>>,
     pub libraries: Vec<LibraryInformation<T>>,
+    /// Objects of unchanged units are taken from this cache instead of being generated again
+    pub cache: Option<BuildCache>,
+    pub object_keys: HashMap<PathBuf, u64>,
 }
 
 impl<T: SourceContainer> CodegenParticipant<T> {
@@ -123,6 +136,33 @@ impl<T: SourceContainer> CodegenParticipant<T> {
         }
         Ok(())
     }
+
+    /// Returns the name of the output generated for the unit at the given location,
+    /// relative to the root of the project
+    fn get_output_name(&self, unit_location: &Path) -> Result<String, Diagnostic> {
+        let current_dir = env::current_dir()?;
+        let current_dir = self.compile_options.root.as_deref().unwrap_or(&current_dir);
+        let unit_location =
+            if unit_location.exists() { fs::canonicalize(unit_location)? } else { unit_location.into() };
+        let output_name = if unit_location.starts_with(current_dir) {
+            unit_location.strip_prefix(current_dir).map_err(|it| {
+                Diagnostic::new(format!("Could not strip prefix for {}", current_dir.to_string_lossy()))
+                    .with_internal_error(it.into())
+            })?
+        } else if unit_location.has_root() {
+            let root = unit_location.ancestors().last().expect("Should exist?");
+            unit_location.strip_prefix(root).expect("The root directory should exist")
+        } else {
+            unit_location.as_path()
+        };
+
+        Ok(match self.compile_options.output_format {
+            FormatOption::IR => format!("{}.ll", output_name.to_string_lossy()),
+            FormatOption::Bitcode => format!("{}.bc", output_name.to_string_lossy()),
+            _ => format!("{}.o", output_name.to_string_lossy()),
+        })
+    }
+
     pub fn read_got_layout(location: &str, format: ConfigFormat) -> Result<HashMap<String, u64>, Diagnostic> {
         let path = Path::new(location);
         if !path.is_file() {
@@ -143,7 +183,7 @@ impl<T: SourceContainer> CodegenParticipant<T> {
 }
 
 impl<T: SourceContainer + Send> PipelineParticipant for CodegenParticipant<T> {
-    fn pre_generate(&mut self, _annotated_project: &AnnotatedProject) -> Result<(), Diagnostic> {
+    fn pre_generate(&mut self, annotated_project: &AnnotatedProject) -> Result<(), Diagnostic> {
         self.ensure_compile_dirs()?;
 
         let got_layout =
@@ -153,33 +193,42 @@ impl<T: SourceContainer + Send> PipelineParticipant for CodegenParticipant<T> {
                 HashMap::default()
             };
         self.got_layout = Mutex::new(got_layout);
+
+        // objects are only cached if every unit is generated into its own module
+        let options = &self.compile_options;
+        if let Some(cache) = self.cache.as_ref().filter(|_| {
+            !options.single_module
+                && !matches!(options.output_format, FormatOption::Object)
+                && matches!(options.online_change, OnlineChange::Disabled)
+        }) {
+            self.object_keys = cache.object_keys(&annotated_project.units, options, &self.target);
+        }
         Ok(())
     }
 
-    fn generate(&self, module: &GeneratedModule) -> Result<(), Diagnostic> {
-        let current_dir = env::current_dir()?;
-        let current_dir = self.compile_options.root.as_deref().unwrap_or(&current_dir);
-        let unit_location = module.get_unit_location();
-        let unit_location =
-            if unit_location.exists() { fs::canonicalize(unit_location)? } else { unit_location.into() };
-        let output_name = if unit_location.starts_with(current_dir) {
-            unit_location.strip_prefix(current_dir).map_err(|it| {
-                Diagnostic::new(format!("Could not strip prefix for {}", current_dir.to_string_lossy()))
-                    .with_internal_error(it.into())
-            })?
-        } else if unit_location.has_root() {
-            let root = unit_location.ancestors().last().expect("Should exist?");
-            unit_location.strip_prefix(root).expect("The root directory should exist")
-        } else {
-            unit_location.as_path()
-        };
-
-        let output_name = match self.compile_options.output_format {
-            FormatOption::IR => format!("{}.ll", output_name.to_string_lossy()),
-            FormatOption::Bitcode => format!("{}.bc", output_name.to_string_lossy()),
-            _ => format!("{}.o", output_name.to_string_lossy()),
+    fn reuse(&self, unit: &AnnotatedUnit) -> Result<bool, Diagnostic> {
+        let location = PathBuf::from(&unit.get_unit().file);
+        let (Some(cache), Some(key)) = (&self.cache, self.object_keys.get(&location)) else {
+            return Ok(false);
         };
+        let target = &self.target;
+        let compile_directory = self.compile_dirs.get(target).expect("Required dir");
+        let output = GeneratedModule::get_output_file(
+            Some(compile_directory),
+            &self.get_output_name(&location)?,
+            target,
+        );
+        if !cache.load_object(*key, &output) {
+            return Ok(false);
+        }
+        log::debug!("Reusing the cached object of {}", location.to_string_lossy());
+        let object = Object::from(output).with_target(target);
+        self.objects.write().expect("Failed to aquire read write lock").objects.push(object);
+        Ok(true)
+    }
 
+    fn generate(&self, module: &GeneratedModule) -> Result<(), Diagnostic> {
+        let output_name = self.get_output_name(module.get_unit_location())?;
         let target = &self.target;
         let compile_directory = self.compile_dirs.get(target).expect("Required dir");
         let object = module
@@ -193,6 +242,10 @@ impl<T: SourceContainer + Send> PipelineParticipant for CodegenParticipant<T> {
             )
             .map(Into::into)
             .map(|it: Object| it.with_target(target))?;
+        if let Some((cache, key)) = self.cache.as_ref().zip(self.object_keys.get(module.get_unit_location()))
+        {
+            cache.store_object(*key, object.get_path());
+        }
         self.objects.write().expect("Failed to aquire read write lock").objects.push(object);
         Ok(())
     }
@@ -222,6 +275,71 @@ impl<T: SourceContainer + Send> PipelineParticipant for CodegenParticipant<T> {
     }
 }
 
+/// Reports the worst-case stack usage of every program, see [`plc::stack_usage`]
+pub struct StackUsageParticipant {
+    /// where the report is written, `None` if the stack usage is only checked against the limit
+    location: Option<String>,
+    limit: Option<u64>,
+    target: Target,
+    optimization: OptimizationLevel,
+    graph: Option<StackGraph>,
+    /// the frame sizes of the generated functions by their lowercase name
+    frames: Mutex<Vec<(String, FrameSize)>>,
+}
+
+impl StackUsageParticipant {
+    pub fn new(
+        location: Option<String>,
+        limit: Option<u64>,
+        target: Target,
+        optimization: OptimizationLevel,
+    ) -> Self {
+        Self { location, limit, target, optimization, graph: None, frames: Default::default() }
+    }
+}
+
+impl PipelineParticipant for StackUsageParticipant {
+    fn pre_generate(&mut self, annotated_project: &AnnotatedProject) -> Result<(), Diagnostic> {
+        self.graph = Some(StackGraph::new(
+            annotated_project.units.iter().map(AnnotatedUnit::get_unit),
+            &annotated_project.index,
+            &annotated_project.annotations,
+        ));
+        Ok(())
+    }
+
+    fn generate(&self, generated_module: &GeneratedModule) -> Result<(), Diagnostic> {
+        let frames = generated_module.get_frame_sizes(&self.target, self.optimization)?;
+        self.frames.lock().expect("Failed to aquire lock for frames").extend(frames);
+        Ok(())
+    }
+
+    fn post_generate(&self) -> Result<(), Diagnostic> {
+        let Some(graph) = &self.graph else {
+            return Ok(());
+        };
+        let frames = self.frames.lock().expect("Failed to aquire lock for frames");
+        let usage = graph.analyze(&frames.iter().cloned().collect());
+        if let Some(location) = &self.location {
+            let format = StackUsageFormat::from_file_name(location);
+            write_report(location, &serialize_stack_usage(&usage, format)?)?;
+        }
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+        let mut errors = usage.check_limit(limit);
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            count => Err(Diagnostic::new(format!(
+                "{count} programs may use more than the stack limit of {limit} bytes"
+            ))
+            .with_error_code("E154")
+            .with_sub_diagnostics(errors)),
+        }
+    }
+}
+
 pub struct InitParticipant {
     id_provider: IdProvider,
     generate_externals: bool,
@@ -236,7 +354,7 @@ impl InitParticipant {
 impl PipelineParticipantMut for InitParticipant {
     fn post_annotate(&mut self, annotated_project: AnnotatedProject) -> AnnotatedProject {
         // Create a new init lowerer
-        let AnnotatedProject { units, index, .. } = annotated_project;
+        let AnnotatedProject { units, index, imports, .. } = annotated_project;
         let mut resulting_units = vec![];
         let index = Rc::new(index);
         for unit in units {
@@ -245,7 +363,7 @@ impl PipelineParticipantMut for InitParticipant {
             resulting_units.push(unit);
         }
         // Append new units and constructor to the ast and re-index
-        let project = ParsedProject { units: resulting_units };
+        let project = ParsedProject { units: resulting_units, imports };
         project.index(self.id_provider.clone()).annotate(self.id_provider.clone())
     }
 }
@@ -262,26 +380,28 @@ impl ArrayLowerer {
 
 impl PipelineParticipantMut for ArrayLowerer {
     fn post_annotate(&mut self, annotated_project: AnnotatedProject) -> AnnotatedProject {
-        let AnnotatedProject { mut units, index, .. } = annotated_project;
+        let AnnotatedProject { mut units, index, imports, .. } = annotated_project;
         for unit in &mut units {
             array_lowering::lower_literal_arrays(&mut unit.unit, &index, &mut self.id_provider);
         }
         // Re-index since we modified the AST (new statements, possible new alloca variables)
-        let project =
-            ParsedProject { units: units.into_iter().map(|AnnotatedUnit { unit, .. }| unit).collect() };
+        let project = ParsedProject {
+            units: units.into_iter().map(|AnnotatedUnit { unit, .. }| unit).collect(),
+            imports,
+        };
         project.index(self.id_provider.clone()).annotate(self.id_provider.clone())
     }
 }
 
 impl PipelineParticipantMut for InheritanceLowerer {
     fn pre_index(&mut self, parsed_project: ParsedProject) -> ParsedProject {
-        let ParsedProject { mut units } = parsed_project;
+        let ParsedProject { mut units, imports } = parsed_project;
         units.iter_mut().for_each(|unit| self.visit_unit(unit));
-        ParsedProject { units }
+        ParsedProject { units, imports }
     }
 
     fn post_annotate(&mut self, annotated_project: AnnotatedProject) -> AnnotatedProject {
-        let AnnotatedProject { mut units, index, annotations, diagnostics } = annotated_project;
+        let AnnotatedProject { mut units, index, annotations, diagnostics, imports } = annotated_project;
         self.annotations = Some(Box::new(annotations));
         self.index = Some(index);
         units.iter_mut().for_each(|unit| self.visit_unit(&mut unit.unit));
@@ -290,6 +410,7 @@ impl PipelineParticipantMut for InheritanceLowerer {
         let mut project = IndexedProject {
             project: ParsedProject {
                 units: units.into_iter().map(|AnnotatedUnit { unit, .. }| unit).collect(),
+                imports,
             },
             index,
             _unresolvables: vec![],
@@ -302,7 +423,7 @@ impl PipelineParticipantMut for InheritanceLowerer {
 
 impl PipelineParticipantMut for AggregateTypeLowerer {
     fn post_annotate(&mut self, annotated_project: AnnotatedProject) -> AnnotatedProject {
-        let AnnotatedProject { units, index, annotations, diagnostics } = annotated_project;
+        let AnnotatedProject { units, index, annotations, diagnostics, imports } = annotated_project;
         self.index = Some(index);
         self.annotation = Some(Box::new(annotations));
 
@@ -316,7 +437,7 @@ impl PipelineParticipantMut for AggregateTypeLowerer {
 
         // Re-index from modified units so the index reflects POU signature
         // changes (e.g. aggregate returns converted to VAR_IN_OUT parameters).
-        let project = ParsedProject { units };
+        let project = ParsedProject { units, imports };
         let mut project = project.index(self.id_provider.clone()).annotate(self.id_provider.clone());
         project.diagnostics = diagnostics;
         project
@@ -337,12 +458,12 @@ impl PipelineParticipantMut for PolymorphismLowerer {
     }
 
     fn post_annotate(&mut self, annotated_project: AnnotatedProject) -> AnnotatedProject {
-        let AnnotatedProject { units, index, annotations, diagnostics } = annotated_project;
+        let AnnotatedProject { units, index, annotations, diagnostics, imports } = annotated_project;
         let mut units: Vec<_> = units.into_iter().map(|AnnotatedUnit { unit, .. }| unit).collect();
 
         let new_diagnostics = self.dispatch(index, annotations.annotation_map, &mut units);
         self.stash_diagnostics(new_diagnostics);
-        let project = ParsedProject { units };
+        let project = ParsedProject { units, imports };
 
         // Dispatch lowering may inject new types (e.g. `__FATPOINTER` and itables for interface
         // dispatch) into the compilation units' `user_types`. Re-indexing from the units ensures
@@ -365,6 +486,10 @@ impl PipelineParticipantMut for RetainParticipant {
         // Re-index
         project.index(self.ids.clone())
     }
+
+    fn diagnostics(&mut self) -> Vec<Diagnostic> {
+        self.take_diagnostics()
+    }
 }
 
 /// Transpiles each CFC document post-index via [`plc_cfc::transpile_file`],
@@ -423,36 +548,36 @@ impl PipelineParticipantMut for CfcParticipant {
 
 impl PipelineParticipantMut for ControlStatementParticipant {
     fn pre_index(&mut self, parsed_project: ParsedProject) -> ParsedProject {
-        let ParsedProject { mut units } = parsed_project;
+        let ParsedProject { mut units, imports } = parsed_project;
         self.lower_control_statements(&mut units);
 
-        ParsedProject { units }
+        ParsedProject { units, imports }
     }
 }
 
 impl PipelineParticipantMut for LoopDesugarer {
     fn pre_index(&mut self, parsed_project: ParsedProject) -> ParsedProject {
-        let ParsedProject { mut units } = parsed_project;
+        let ParsedProject { mut units, imports } = parsed_project;
         self.desugar(&mut units);
 
-        ParsedProject { units }
+        ParsedProject { units, imports }
     }
 }
 
 impl PipelineParticipantMut for ReferenceToReturnParticipant {
     fn pre_index(&mut self, parsed_project: ParsedProject) -> ParsedProject {
-        let ParsedProject { mut units } = parsed_project;
+        let ParsedProject { mut units, imports } = parsed_project;
         self.gather_context(&mut units, None);
-        ParsedProject { units }
+        ParsedProject { units, imports }
     }
 
     fn post_annotate(&mut self, annotated_project: AnnotatedProject) -> AnnotatedProject {
-        let AnnotatedProject { units, index, annotations, .. } = annotated_project;
+        let AnnotatedProject { units, index, annotations, imports, .. } = annotated_project;
         let mut units: Vec<_> = units.into_iter().map(|AnnotatedUnit { unit, .. }| unit).collect();
 
         self.lower_reference_to_return(&mut units, index, annotations);
 
-        let project = ParsedProject { units };
+        let project = ParsedProject { units, imports };
 
         project.index(self.ids.clone()).annotate(self.ids.clone())
     }
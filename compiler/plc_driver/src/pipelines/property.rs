@@ -9,17 +9,17 @@ use super::{
 
 impl PipelineParticipantMut for PropertyLowerer {
     fn pre_index(&mut self, parsed_project: ParsedProject) -> ParsedProject {
-        let ParsedProject { mut units } = parsed_project;
+        let ParsedProject { mut units, imports } = parsed_project;
 
         for unit in &mut units {
             self.properties_to_pous(unit);
         }
 
-        ParsedProject { units }
+        ParsedProject { units, imports }
     }
 
     fn post_annotate(&mut self, project: AnnotatedProject) -> AnnotatedProject {
-        let AnnotatedProject { mut units, index, annotations, diagnostics } = project;
+        let AnnotatedProject { mut units, index, annotations, diagnostics, imports } = project;
         self.annotations = Some(annotations);
 
         for AnnotatedUnit { unit, .. } in &mut units.iter_mut() {
@@ -27,7 +27,10 @@ impl PipelineParticipantMut for PropertyLowerer {
         }
 
         let indexed_project = IndexedProject {
-            project: ParsedProject { units: units.into_iter().map(|annotated| annotated.unit).collect() },
+            project: ParsedProject {
+                units: units.into_iter().map(|annotated| annotated.unit).collect(),
+                imports,
+            },
             index,
             _unresolvables: vec![],
         };
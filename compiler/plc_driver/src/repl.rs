@@ -0,0 +1,508 @@
+//! An interactive session compiling and executing ST through the LLVM JIT, see `plc repl`
+//!
+//! Every input becomes its own compilation unit named `<repl-N>`. It is indexed against the declarations
+//! of all accepted inputs, which are kept in the session's index, and only the unit of the current input
+//! is lowered and compiled. Symbols of previous inputs are resolved to the modules they were compiled into.
+//! Statements are wrapped into a parameterless `FUNCTION __repl_N` which is called once. If a statement is
+//! a single expression, its value is stored in a `__repl_N_result` global and printed.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{BufRead, Write},
+    rc::Rc,
+    sync::Arc,
+};
+
+use ast::{
+    ast::{AstFactory, AstNode, AstStatement, CompilationUnit, LinkageType},
+    provider::IdProvider,
+};
+use plc::{
+    codegen::{find_symbol_address, CodegenContext, GeneratedModule},
+    index::{indexer, Index},
+    lexer::{lex_with_ids, Token},
+    parser::{parse_file, parse_source},
+    resolver::AnnotationMap,
+    validation::Validator,
+};
+use plc_diagnostics::{
+    diagnostician::Diagnostician,
+    diagnostics::{Diagnostic, Severity},
+};
+use source_code::{
+    source_location::{FileMarker, SourceLocationFactory},
+    SourceCode, SourceContainer,
+};
+
+use crate::{
+    pipelines::{
+        participant::PipelineParticipantMut, AnnotatedProject, BuildPipeline, ParsedProject, Pipeline,
+    },
+    runner::ValueKind,
+    CompileOptions,
+};
+
+const HELP: &str = "\
+Enter declarations (TYPE, FUNCTION, FUNCTION_BLOCK, PROGRAM, VAR, ...) or statements.
+The value of an expression is printed, e.g. `1 + 2` or `MAX(3, 5)`.
+Input spanning multiple lines is evaluated once all blocks are closed.
+
+Commands:
+  :help    Show this message
+  :decls   Show the declarations of this session
+  :quit    Exit the session";
+
+/// The state of an interactive session
+pub struct Repl<'ctx> {
+    pub(crate) pipeline: BuildPipeline<SourceCode>,
+    compile_options: CompileOptions,
+    /// The context all modules are generated in, it is created once for the whole session
+    context: &'ctx CodegenContext,
+    /// Declarations accepted so far
+    sources: Vec<SourceCode>,
+    /// The built-ins, includes and lowered declarations of all accepted inputs
+    pub(crate) index: Arc<Index>,
+    /// Modules of all evaluated inputs, kept alive since later inputs refer to their symbols
+    modules: Vec<GeneratedModule<'ctx>>,
+    /// Addresses of all functions and globals defined by the modules
+    symbols: HashMap<String, usize>,
+    counter: usize,
+}
+
+/// A complete input of the user
+enum Input {
+    Declaration(String),
+    Statement(String),
+}
+
+/// The declared type and value kind of an expression's result
+type ResultType = (String, ValueKind);
+
+impl<'ctx> Repl<'ctx> {
+    /// Creates a session generating its modules in `context`, with the given declaration files and includes
+    pub fn new(
+        context: &'ctx CodegenContext,
+        pipeline: BuildPipeline<SourceCode>,
+        compile_options: CompileOptions,
+        sources: Vec<SourceCode>,
+        includes: Vec<SourceCode>,
+    ) -> Result<Self, Diagnostic> {
+        let provider = pipeline.context.provider();
+        let index =
+            ParsedProject::from_units(vec![]).index(provider.clone()).annotate(provider.clone()).index;
+        let mut repl = Repl {
+            pipeline,
+            compile_options,
+            context,
+            sources: vec![],
+            index: Arc::new(index),
+            modules: vec![],
+            symbols: HashMap::new(),
+            counter: 0,
+        };
+        // includes are only declared, their diagnostics were reported when they were loaded
+        let includes = includes
+            .iter()
+            .map(|it| {
+                parse_file(
+                    it,
+                    LinkageType::Include,
+                    provider.clone(),
+                    &mut Diagnostician::null_diagnostician(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !includes.is_empty() {
+            let files = includes.iter().map(|it| it.file).collect::<Vec<_>>();
+            let project = repl.lower(includes, None)?;
+            repl.declare(project, &files);
+        }
+        if !sources.is_empty() {
+            let units = sources.iter().map(|it| repl.parse(it)).collect::<Result<Vec<_>, _>>()?;
+            let file_names = sources.iter().map(SourceContainer::get_location_str).collect::<Vec<_>>();
+            repl.compile(units, &file_names, None)?;
+            repl.sources = sources;
+        }
+        Ok(repl)
+    }
+
+    /// Evaluates a complete input, returns the printed value of an expression
+    pub fn eval(&mut self, input: &str) -> Result<Option<String>, Diagnostic> {
+        self.counter += 1;
+        let file_name = format!("<repl-{}>", self.counter);
+        match Input::from(input) {
+            Input::Declaration(declaration) => {
+                let source = SourceCode::new(declaration, &file_name);
+                let unit = self.parse(&source)?;
+                self.compile(vec![unit], &[&file_name], None)?;
+                self.sources.push(source);
+                Ok(None)
+            }
+            Input::Statement(statement) => {
+                let function = format!("__repl_{}", self.counter);
+                let source =
+                    SourceCode::new(format!("FUNCTION {function}\n{statement}\nEND_FUNCTION\n"), &file_name);
+                let unit = self.parse(&source)?;
+                let capture = ResultCapture::new(&function, self.pipeline.context.provider());
+                let result_type = capture.result_type.clone();
+                let module = self.compile(vec![unit], &[&file_name], Some(capture))?;
+                module.call(&function)?;
+                let Some((type_name, kind)) = result_type.take() else {
+                    return Ok(None);
+                };
+
+                let result = format!("{function}_result");
+                let address = module.get_global_address(&result).ok_or_else(|| {
+                    Diagnostic::new(format!("Cannot find the result `{result}`")).with_error_code("E150")
+                })?;
+                // SAFETY: the global was declared with the type the value kind was created for
+                let value = unsafe { kind.read(address) };
+                Ok(Some(format!("{value} : {type_name}")))
+            }
+        }
+    }
+
+    /// Returns the declarations of this session
+    pub fn get_declarations(&self) -> String {
+        self.sources.iter().map(|it| it.source.trim()).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Compiles the units parsed from `file_names` against the declarations of the session, runs their
+    /// constructors and adds their declarations to the session. The result of a statement is captured
+    /// during the same run if `capture` is given.
+    fn compile(
+        &mut self,
+        units: Vec<CompilationUnit>,
+        file_names: &[&str],
+        capture: Option<ResultCapture>,
+    ) -> Result<&GeneratedModule<'ctx>, Diagnostic> {
+        let files = units.iter().map(|it| it.file).collect::<Vec<_>>();
+        let mut project = self.lower(units, capture)?;
+        self.validate(&mut project, file_names)?;
+
+        let module = project
+            .generate_units_module(self.context, &self.compile_options, file_names)?
+            .ok_or_else(|| Diagnostic::new("Cannot generate module").with_error_code("E150"))?;
+
+        for symbol in module.get_declared_symbols() {
+            match self.symbols.get(&symbol) {
+                Some(address) => module.add_symbol_mapping(&symbol, *address),
+                // resolved from the process or a library loaded with `-l`
+                None if find_symbol_address(&symbol).is_some() => {}
+                None => {
+                    return Err(Diagnostic::new(format!(
+                        "Cannot resolve `{symbol}`, is a library missing (`-l`/`-L`)?"
+                    ))
+                    .with_error_code("E150"))
+                }
+            }
+        }
+        module.run_static_constructors();
+        for symbol in module.get_defined_symbols() {
+            if let Some(address) = module.get_symbol_address(&symbol) {
+                self.symbols.insert(symbol, address);
+            }
+        }
+
+        self.declare(project, &files);
+        self.modules.push(module);
+        Ok(self.modules.last().expect("module was just added"))
+    }
+
+    /// Indexes the units against the declarations of the session, annotates and lowers them
+    fn lower(
+        &mut self,
+        units: Vec<CompilationUnit>,
+        capture: Option<ResultCapture>,
+    ) -> Result<AnnotatedProject, Diagnostic> {
+        let project = ParsedProject::from_units(units).with_imports(self.index.clone());
+        let mut participants = self.pipeline.get_default_mut_participants();
+        // the expression is looked up before any lowering changed the statement
+        if let Some(capture) = capture {
+            participants.insert(0, Box::new(capture));
+        }
+        self.pipeline.mutable_participants = participants;
+        let project = self.pipeline.index(project)?;
+        self.pipeline.annotate(project)
+    }
+
+    /// Adds the lowered declarations of the given files to the session,
+    /// units generated while lowering (e.g. for retain variables) are left out
+    fn declare(&mut self, project: AnnotatedProject, files: &[FileMarker]) {
+        let declarations = project
+            .units
+            .iter()
+            .filter(|it| files.contains(&it.get_unit().file))
+            .map(|it| indexer::index(it.get_unit()))
+            .collect::<Vec<_>>();
+        // the project and the participants which lowered it were indexed on top of the session's index,
+        // they are released before the declarations are added to it
+        drop(project);
+        self.pipeline.mutable_participants.clear();
+        match Arc::get_mut(&mut self.index) {
+            Some(index) => declarations.into_iter().for_each(|it| index.import(it)),
+            // something still refers to the session's index, the declarations are added on top of it
+            None => {
+                let mut index = Index::with_imports(self.index.clone());
+                declarations.into_iter().for_each(|it| index.import(it));
+                self.index = Arc::new(index);
+            }
+        }
+    }
+
+    /// Parses a new input and reports its syntax errors
+    fn parse(&mut self, source: &SourceCode) -> Result<CompilationUnit, Diagnostic> {
+        self.pipeline.context.insert(source, None)?;
+        let source = self.pipeline.context.get(source.get_location_str()).expect("source was just added");
+        let provider = self.pipeline.context.provider();
+        parse_file(source, LinkageType::Internal, provider, &mut self.pipeline.diagnostician)
+    }
+
+    /// Reports the diagnostics of the given files, earlier inputs were already validated
+    fn validate(&mut self, project: &mut AnnotatedProject, file_names: &[&str]) -> Result<(), Diagnostic> {
+        let mut validator = Validator::new(&self.pipeline.context);
+        validator.perform_global_validation(&project.index);
+        let mut diagnostics = std::mem::take(&mut project.diagnostics);
+        diagnostics.extend(validator.diagnostics());
+        for unit in &project.units {
+            validator.visit_unit(&project.annotations, &project.index, unit.get_unit());
+            diagnostics.extend(validator.diagnostics());
+        }
+        diagnostics.retain(|it| {
+            it.get_location().get_file_name().is_some_and(|file_name| file_names.contains(&file_name))
+        });
+
+        if self.pipeline.diagnostician.handle(&diagnostics) == Severity::Error {
+            Err(Diagnostic::new("Input rejected due to errors"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Looks up the type of a statement that is a single printable expression right after it was annotated
+/// and assigns its value to a `<function>_result` global of that type
+struct ResultCapture {
+    function: String,
+    ids: IdProvider,
+    /// The declared type and value kind of the result, if the statement is a printable expression
+    result_type: Rc<RefCell<Option<ResultType>>>,
+}
+
+impl ResultCapture {
+    fn new(function: &str, ids: IdProvider) -> Self {
+        ResultCapture { function: function.to_string(), ids, result_type: Rc::new(RefCell::new(None)) }
+    }
+
+    /// Returns the declared type and value kind of the statement in `function` if it is a printable expression
+    fn find_result_type(&self, project: &AnnotatedProject) -> Option<ResultType> {
+        let statement = project
+            .units
+            .iter()
+            .flat_map(|it| &it.get_unit().implementations)
+            .find(|it| it.name == self.function)
+            .and_then(|it| match it.statements.as_slice() {
+                [statement] if is_expression(statement) => Some(statement),
+                _ => None,
+            })?;
+
+        let index = &project.index;
+        let info = project.annotations.get_type_or_void(statement, index).get_type_information();
+        let kind = ValueKind::of(info, index).ok()?;
+        let type_name = match (&kind, index.get_intrinsic_type_information(info)) {
+            (ValueKind::String { length }, _) => format!("STRING[{}]", length - 1),
+            (_, info) => info.get_name().to_string(),
+        };
+        Some((type_name, kind))
+    }
+}
+
+impl PipelineParticipantMut for ResultCapture {
+    fn post_annotate(&mut self, annotated_project: AnnotatedProject) -> AnnotatedProject {
+        let Some((type_name, kind)) = self.find_result_type(&annotated_project) else {
+            return annotated_project;
+        };
+
+        let AnnotatedProject { units, diagnostics, imports, .. } = annotated_project;
+        let mut units = units.into_iter().map(CompilationUnit::from).collect::<Vec<_>>();
+        let result = format!("{}_result", self.function);
+        let unit = units
+            .iter_mut()
+            .find(|it| it.implementations.iter().any(|it| it.name == self.function))
+            .expect("the statement was found in the annotated units");
+
+        // the global is parsed on its own, its location is never reported
+        let source = SourceCode::new(format!("VAR_GLOBAL {result} : {type_name}; END_VAR"), "<repl>");
+        let (declaration, _) = parse_source(&source, LinkageType::Internal, self.ids.clone());
+        unit.global_vars.extend(declaration.global_vars);
+
+        let implementation = unit
+            .implementations
+            .iter_mut()
+            .find(|it| it.name == self.function)
+            .expect("the statement was found in the annotated units");
+        let expression = implementation.statements.pop().expect("the statement is a single expression");
+        let location = expression.get_location();
+        let target = AstFactory::create_member_reference(
+            AstFactory::create_identifier(&result, location, self.ids.next_id()),
+            None,
+            self.ids.next_id(),
+        );
+        implementation.statements.push(AstFactory::create_assignment(target, expression, self.ids.next_id()));
+        self.result_type.replace(Some((type_name, kind)));
+
+        let mut project = ParsedProject::from_units(units);
+        if let Some(imports) = imports {
+            project = project.with_imports(imports);
+        }
+        let mut project = project.index(self.ids.clone()).annotate(self.ids.clone());
+        project.diagnostics = diagnostics;
+        project
+    }
+}
+
+impl From<&str> for Input {
+    fn from(input: &str) -> Self {
+        let session = lex_with_ids(input, IdProvider::default(), SourceLocationFactory::internal(input));
+        match session.token {
+            // variables of the session are globals
+            Token::KeywordVar => {
+                let range = session.range();
+                Input::Declaration(format!("{}VAR_GLOBAL{}", &input[..range.start], &input[range.end..]))
+            }
+            Token::KeywordType
+            | Token::KeywordFunction
+            | Token::KeywordFunctionBlock
+            | Token::KeywordProgram
+            | Token::KeywordClass
+            | Token::KeywordInterface
+            | Token::KeywordActions
+            | Token::KeywordAction
+            | Token::KeywordVarGlobal
+            | Token::KeywordVarConfig => Input::Declaration(input.to_string()),
+            _ => {
+                let statement = input.trim();
+                if statement.ends_with(';') {
+                    Input::Statement(statement.to_string())
+                } else {
+                    Input::Statement(format!("{statement};"))
+                }
+            }
+        }
+    }
+}
+
+fn is_expression(statement: &AstNode) -> bool {
+    matches!(
+        statement.get_stmt(),
+        AstStatement::Literal(_)
+            | AstStatement::ReferenceExpr(_)
+            | AstStatement::Identifier(_)
+            | AstStatement::DirectAccess(_)
+            | AstStatement::BinaryExpression(_)
+            | AstStatement::UnaryExpression(_)
+            | AstStatement::ParenExpression(_)
+            | AstStatement::CallStatement(_)
+    )
+}
+
+/// Returns true if every block opened in `input` (e.g. `IF`, `FUNCTION` or `VAR`) is closed again
+pub fn is_complete(input: &str) -> bool {
+    let mut session = lex_with_ids(input, IdProvider::default(), SourceLocationFactory::internal(input));
+    let mut depth = 0;
+    while !session.is_end_of_stream() {
+        match session.token {
+            Token::KeywordProgram
+            | Token::KeywordClass
+            | Token::KeywordInterface
+            | Token::KeywordVar
+            | Token::KeywordVarInput
+            | Token::KeywordVarOutput
+            | Token::KeywordVarInOut
+            | Token::KeywordVarTemp
+            | Token::KeywordVarGlobal
+            | Token::KeywordVarExternal
+            | Token::KeywordVarConfig
+            | Token::KeywordMethod
+            | Token::KeywordPropertyGet
+            | Token::KeywordPropertySet
+            | Token::KeywordFunction
+            | Token::KeywordFunctionBlock
+            | Token::KeywordType
+            | Token::KeywordStruct
+            | Token::KeywordActions
+            | Token::KeywordAction
+            | Token::KeywordIf
+            | Token::KeywordFor
+            | Token::KeywordWhile
+            | Token::KeywordRepeat
+            | Token::KeywordCase => depth += 1,
+            Token::KeywordEndProgram
+            | Token::KeywordEndClass
+            | Token::KeywordEndInterface
+            | Token::KeywordEndVar
+            | Token::KeywordEndMethod
+            | Token::KeywordEndProperty
+            | Token::KeywordEndFunction
+            | Token::KeywordEndFunctionBlock
+            | Token::KeywordEndType
+            | Token::KeywordEndStruct
+            | Token::KeywordEndActions
+            | Token::KeywordEndAction
+            | Token::KeywordEndIf
+            | Token::KeywordEndFor
+            | Token::KeywordEndWhile
+            | Token::KeywordEndRepeat
+            | Token::KeywordEndCase => depth -= 1,
+            _ => {}
+        }
+        session.advance();
+    }
+    depth <= 0
+}
+
+/// Reads inputs from `input` until it ends or `:quit` is entered, printing values and prompts to `out`.
+/// Diagnostics are reported through the session's diagnostician.
+pub fn run<R: BufRead, W: Write>(repl: &mut Repl, mut input: R, out: &mut W) -> Result<(), Diagnostic> {
+    writeln!(out, "Type :help for help, :quit to exit")?;
+    let mut buffer = String::new();
+    loop {
+        // a continuation prompt is shown while a block is still open
+        write!(out, "{}", if buffer.is_empty() { "> " } else { "| " })?;
+        out.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        if buffer.is_empty() {
+            match line.trim() {
+                "" => continue,
+                ":quit" | ":q" => return Ok(()),
+                ":help" => {
+                    writeln!(out, "{HELP}")?;
+                    continue;
+                }
+                ":decls" => {
+                    writeln!(out, "{}", repl.get_declarations())?;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        buffer.push_str(&line);
+        if !is_complete(&buffer) {
+            continue;
+        }
+        match repl.eval(&buffer) {
+            Ok(Some(value)) => writeln!(out, "{value}")?,
+            Ok(None) => {}
+            Err(err) => {
+                repl.pipeline.diagnostician.handle(&[err]);
+            }
+        }
+        buffer.clear();
+    }
+}
@@ -3,9 +3,15 @@ use crate::{
     CompileOptions,
 };
 
+use std::{io::Write, time::Duration};
+
 use log::trace;
-use plc::codegen::{CodegenContext, GeneratedModule};
-use plc_diagnostics::diagnostician::Diagnostician;
+use plc::{
+    codegen::{find_symbol_address, CodegenContext, GeneratedModule},
+    index::{Index, PouIndexEntry},
+    typesystem::{DataTypeInformation, LONG_TIME_TYPE, TIME_TYPE},
+};
+use plc_diagnostics::{diagnostician::Diagnostician, diagnostics::Diagnostic};
 use plc_index::GlobalContext;
 use project::project::Project;
 use source_code::Compilable;
@@ -96,3 +102,291 @@ pub fn compile_and_run_no_params<U, S: Compilable>(source: S) -> U {
     trace!("{}", module.persist_to_string());
     module.run_no_param::<U>("main")
 }
+
+/// Options to execute a `PROGRAM` cyclically through the JIT, see `plc run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOptions {
+    /// The name of the `PROGRAM` to execute
+    pub program: String,
+    /// How many times the program is called
+    pub cycles: u64,
+    /// The time the simulated clock advances after every cycle
+    pub cycle_time: Duration,
+    /// Program variables (e.g. `counter` or `fb.ET`) printed after every cycle
+    pub watch: Vec<String>,
+}
+
+/// Symbol exported by the stdlib's `mock_time` feature to reset the simulated clock
+const MOCK_TIME_SET: &str = "__mock_time_set_ns";
+/// Symbol exported by the stdlib's `mock_time` feature to advance the simulated clock
+const MOCK_TIME_ADVANCE: &str = "__mock_time_advance_ns";
+
+type MockTimeFunction = unsafe extern "C" fn(u64);
+
+/// Function blocks of the stdlib reading the clock advanced by [`MOCK_TIME_ADVANCE`]
+const TIMERS: &[&str] =
+    &["TP", "TP_TIME", "TP_LTIME", "TON", "TON_TIME", "TON_LTIME", "TOF", "TOF_TIME", "TOF_LTIME"];
+
+///
+/// Executes the program described by `options` inside the given module.
+/// The module's constructors are run once, then the program is called `options.cycles` times.
+/// After every cycle the watched variables are written to `out` and the simulated clock is
+/// advanced by the cycle time. A program instantiating a timer cannot be run without the simulated
+/// clock, other programs are called back to back.
+///
+pub fn run_program<W: Write>(
+    module: &GeneratedModule,
+    index: &Index,
+    options: &RunOptions,
+    out: &mut W,
+) -> Result<(), Diagnostic> {
+    let Some(pou) = index.find_pou(&options.program).filter(|it| it.is_program()) else {
+        return Err(Diagnostic::new(format!("Cannot run `{}`: no such PROGRAM", options.program))
+            .with_error_code("E149"));
+    };
+    let program = pou.get_name();
+    let watched = options
+        .watch
+        .iter()
+        .map(|it| WatchedVariable::resolve(module, index, program, it))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mock_time = find_symbol_address(MOCK_TIME_SET).zip(find_symbol_address(MOCK_TIME_ADVANCE)).map(
+        |(set, advance)| unsafe {
+            (
+                std::mem::transmute::<usize, MockTimeFunction>(set),
+                std::mem::transmute::<usize, MockTimeFunction>(advance),
+            )
+        },
+    );
+    if mock_time.is_none() {
+        check_without_clock(index, program)?;
+    }
+    let cycle_time = u64::try_from(options.cycle_time.as_nanos()).unwrap_or(u64::MAX);
+
+    if let Some((set_time, _)) = mock_time {
+        unsafe { set_time(0) };
+    }
+    module.run_static_constructors();
+    let instance = index
+        .find_pou(program)
+        .and_then(|it| match it {
+            PouIndexEntry::Program { instance_variable, .. } => {
+                module.get_global_address(instance_variable.get_qualified_name())
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            Diagnostic::new(format!("Cannot find the instance of PROGRAM `{program}`"))
+                .with_error_code("E149")
+        })?;
+
+    for cycle in 1..=options.cycles {
+        module.call_with_instance(program, instance)?;
+
+        if !watched.is_empty() {
+            let values = watched
+                .iter()
+                .map(|it| format!("{} = {}", it.name, it.read(instance)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(out, "cycle {cycle}: {values}")?;
+        }
+
+        if let Some((_, advance_time)) = mock_time {
+            unsafe { advance_time(cycle_time) };
+        }
+    }
+
+    Ok(())
+}
+
+/// Reports the first timer instance, which would read the real time instead of the simulated clock
+pub(crate) fn check_without_clock(index: &Index, program: &str) -> Result<(), Diagnostic> {
+    let timer = index
+        .find_instances()
+        .map(|(_, it)| it)
+        .find(|it| TIMERS.iter().any(|timer| timer.eq_ignore_ascii_case(it.get_type_name())));
+    let Some(timer) = timer else { return Ok(()) };
+    Err(Diagnostic::new(format!(
+        "Cannot run `{program}`: the timer `{}` needs the simulated clock `{MOCK_TIME_ADVANCE}`, link a stdlib built with the `mock_time` feature",
+        timer.get_qualified_name()
+    ))
+    .with_error_code("E149"))
+}
+
+/// A variable inside a program instance whose value is printed after every cycle
+struct WatchedVariable {
+    name: String,
+    /// Offsets to follow from the program instance, every offset but the last one points to
+    /// a pointer that has to be dereferenced (e.g. `VAR_IN_OUT` or `RETAIN` members)
+    path: Vec<u64>,
+    kind: ValueKind,
+}
+
+/// How a value is read from memory and printed
+pub(crate) enum ValueKind {
+    Bool,
+    Signed(u32),
+    Unsigned(u32),
+    Float(u32),
+    Time,
+    String { length: usize },
+}
+
+impl ValueKind {
+    /// Returns how values of the given type are printed, or why they cannot be printed
+    pub(crate) fn of(info: &DataTypeInformation, index: &Index) -> Result<ValueKind, String> {
+        let kind = match index.get_intrinsic_type_information(info) {
+            info if info.is_bool() => ValueKind::Bool,
+            DataTypeInformation::Integer { name, size: 64, .. }
+                if name == TIME_TYPE || name == LONG_TIME_TYPE =>
+            {
+                ValueKind::Time
+            }
+            DataTypeInformation::Integer { signed: true, size, .. } => ValueKind::Signed(*size),
+            DataTypeInformation::Integer { signed: false, size, .. } => ValueKind::Unsigned(*size),
+            DataTypeInformation::Float { size, .. } => ValueKind::Float(*size),
+            info @ DataTypeInformation::String { size, .. } if info.is_string_utf8() => {
+                let length = size.as_int_value(index)?;
+                ValueKind::String { length: length as usize }
+            }
+            DataTypeInformation::Enum { referenced_type, .. } => {
+                match index.find_effective_type_info(referenced_type) {
+                    Some(DataTypeInformation::Integer { signed: true, size, .. }) => ValueKind::Signed(*size),
+                    Some(DataTypeInformation::Integer { size, .. }) => ValueKind::Unsigned(*size),
+                    _ => ValueKind::Signed(32),
+                }
+            }
+            info => return Err(format!("values of type `{}` cannot be printed", info.get_name())),
+        };
+        Ok(kind)
+    }
+
+    /// Reads and formats the value stored at `address`
+    ///
+    /// # Safety
+    /// `address` has to point to an initialized value of the type this kind was created for
+    pub(crate) unsafe fn read(&self, address: usize) -> String {
+        match self {
+            ValueKind::Bool => if *(address as *const u8) != 0 { "TRUE" } else { "FALSE" }.to_string(),
+            ValueKind::Signed(8) => (*(address as *const i8)).to_string(),
+            ValueKind::Signed(16) => (*(address as *const i16)).to_string(),
+            ValueKind::Signed(32) => (*(address as *const i32)).to_string(),
+            ValueKind::Signed(_) => (*(address as *const i64)).to_string(),
+            ValueKind::Unsigned(8) => (*(address as *const u8)).to_string(),
+            ValueKind::Unsigned(16) => (*(address as *const u16)).to_string(),
+            ValueKind::Unsigned(32) => (*(address as *const u32)).to_string(),
+            ValueKind::Unsigned(_) => (*(address as *const u64)).to_string(),
+            ValueKind::Float(32) => (*(address as *const f32)).to_string(),
+            ValueKind::Float(_) => (*(address as *const f64)).to_string(),
+            ValueKind::Time => format_time(*(address as *const i64)),
+            ValueKind::String { length } => {
+                let bytes = std::slice::from_raw_parts(address as *const u8, *length);
+                let end = bytes.iter().position(|it| *it == 0).unwrap_or(*length);
+                format!("'{}'", String::from_utf8_lossy(&bytes[..end]))
+            }
+        }
+    }
+}
+
+impl WatchedVariable {
+    fn resolve(
+        module: &GeneratedModule,
+        index: &Index,
+        program: &str,
+        name: &str,
+    ) -> Result<WatchedVariable, Diagnostic> {
+        let error = |reason: &str| {
+            Diagnostic::new(format!("Cannot watch `{name}` in PROGRAM `{program}`: {reason}"))
+                .with_error_code("E174")
+        };
+
+        let mut container = program.to_string();
+        let mut path = vec![0];
+        let mut type_info: Option<&DataTypeInformation> = None;
+        for segment in name.split('.') {
+            if type_info.is_some() {
+                match type_info {
+                    Some(DataTypeInformation::Struct { name, .. }) => container = name.clone(),
+                    _ => return Err(error(&format!("`{segment}` is not a member of a struct or POU"))),
+                }
+            }
+
+            let mut member = index.find_local_member(&container, segment);
+            // members of a base class are reached through the `__<base>` member added by the inheritance lowering
+            while member.is_none() {
+                let Some(base) = index.find_pou(&container).and_then(|it| it.get_super_class()) else {
+                    return Err(error(&format!("`{segment}` is not a member of `{container}`")));
+                };
+                let base_member = index
+                    .find_local_member(&container, &format!("__{base}"))
+                    .ok_or_else(|| error(&format!("cannot resolve the base of `{container}`")))?;
+                *path.last_mut().expect("path is never empty") += module
+                    .get_member_offset(&container, base_member.get_location_in_parent())
+                    .ok_or_else(|| error(&format!("unknown layout of `{container}`")))?;
+                container = base.to_string();
+                member = index.find_local_member(&container, segment);
+            }
+            let member = member.expect("resolved above");
+
+            *path.last_mut().expect("path is never empty") += module
+                .get_member_offset(&container, member.get_location_in_parent())
+                .ok_or_else(|| error(&format!("unknown layout of `{container}`")))?;
+
+            let mut info =
+                index.get_effective_type_or_void_by_name(member.get_type_name()).get_type_information();
+            if let DataTypeInformation::Pointer { inner_type_name, auto_deref: Some(_), .. } = info {
+                path.push(0);
+                info = index.get_effective_type_or_void_by_name(inner_type_name).get_type_information();
+            }
+            type_info = Some(info);
+        }
+
+        let kind = match type_info {
+            Some(info) => ValueKind::of(info, index).map_err(|reason| error(&reason))?,
+            None => return Err(error("no variable given")),
+        };
+
+        Ok(WatchedVariable { name: name.to_string(), path, kind })
+    }
+
+    /// Reads the current value from the given program instance
+    fn read(&self, instance: usize) -> String {
+        let (last, pointers) = self.path.split_last().expect("path is never empty");
+        let mut address = instance;
+        // SAFETY: the offsets were computed from the module's data layout and the pointers
+        // followed were initialized by the module's constructors
+        unsafe {
+            for offset in pointers {
+                address = *((address + *offset as usize) as *const usize);
+            }
+            self.kind.read(address + *last as usize)
+        }
+    }
+}
+
+/// Formats a TIME value given in nanoseconds as an IEC literal, e.g. `T#1s500ms`
+fn format_time(nanos: i64) -> String {
+    if nanos == 0 {
+        return "T#0s".to_string();
+    }
+    let sign = if nanos < 0 { "-" } else { "" };
+    let mut rest = nanos.unsigned_abs();
+    let mut result = format!("T#{sign}");
+    for (unit, factor) in [
+        ("d", 86_400_000_000_000),
+        ("h", 3_600_000_000_000),
+        ("m", 60_000_000_000),
+        ("s", 1_000_000_000),
+        ("ms", 1_000_000),
+        ("us", 1_000),
+        ("ns", 1),
+    ] {
+        if rest >= factor {
+            result.push_str(&format!("{}{unit}", rest / factor));
+            rest %= factor;
+        }
+    }
+    result
+}
@@ -19,6 +19,8 @@ mod debug_paths;
 mod external_files;
 mod header_generator;
 mod multi_files;
+mod repl;
+mod run;
 
 #[derive(Serialize, Deserialize)]
 #[serde(bound(deserialize = "'de: 'static"))]
@@ -993,6 +993,253 @@ fn case_15_non_constant_sizes_report_a_diagnostic() {
     );
 }
 
+// ------------------ //
+// -- Test Case 16 -- //
+// ------------------ //
+
+#[test]
+fn case_16_rust_bindings_mirror_the_user_types() {
+    let source_code = SourceCode::new(
+        "
+    TYPE Color : (red, green := 5, blue);
+    END_TYPE
+
+    TYPE Point :
+    STRUCT
+        x : DINT;
+        y : DINT;
+        visible : BOOL;
+        label : STRING[10];
+        history : ARRAY[0..1, 0..2] OF INT;
+    END_STRUCT
+    END_TYPE
+
+    FUNCTION add : DINT
+    VAR_INPUT
+        a : DINT;
+        b : REF_TO DINT;
+    END_VAR
+    END_FUNCTION
+    ",
+        "rust_user_types.pli",
+    );
+
+    let generated_headers = generate_all_header_contents(source_code, GenerateLanguage::Rust);
+    assert_eq!(generated_headers.len(), 1);
+    assert_eq!(generated_headers[0].get_path(), "rust_user_types.rs");
+
+    let contents = generated_headers[0].get_contents();
+    assert!(contents.contains("#[repr(i32)]"), "{contents}");
+    assert!(
+        contents.contains("pub enum Color {\n    red = 0,\n    green = 5,\n    blue = 6,\n}"),
+        "{contents}"
+    );
+    assert!(contents.contains("#[repr(C)]\npub struct Point {"), "{contents}");
+    assert!(contents.contains("    pub visible: bool,\n    pub label: [u8; 11],\n"), "{contents}");
+    assert!(contents.contains("    pub history: [[i16; 3]; 2],\n"), "{contents}");
+    // 4 + 4 + 1 + 11 bytes followed by 12 bytes aligned to 2
+    assert!(contents.contains("const _: () = assert!(core::mem::size_of::<Point>() == 32);"), "{contents}");
+    assert!(contents.contains("pub fn add(a: i32, b: *mut i32) -> i32;"), "{contents}");
+}
+
+// ------------------ //
+// -- Test Case 17 -- //
+// ------------------ //
+
+#[test]
+fn case_17_rust_bindings_declare_the_bodies_of_stateful_pous() {
+    let source_code = SourceCode::new(
+        "
+    FUNCTION_BLOCK Counter
+    VAR_INPUT
+        step : INT;
+    END_VAR
+    VAR_OUTPUT
+        count : DINT;
+    END_VAR
+    VAR_TEMP
+        scratch : LREAL;
+    END_VAR
+        METHOD reset : BOOL
+        VAR_INPUT
+            value : LREAL;
+        END_VAR
+        END_METHOD
+    END_FUNCTION_BLOCK
+
+    PROGRAM mainProg
+    VAR
+        counter : Counter;
+        message : WSTRING[4];
+    END_VAR
+    END_PROGRAM
+    ",
+        "rust_stateful_pous.pli",
+    );
+
+    let generated_headers = generate_all_header_contents(source_code, GenerateLanguage::Rust);
+    let contents = generated_headers[0].get_contents();
+
+    assert!(contents.contains("pub struct Counter_type {"), "{contents}");
+    assert!(contents.contains("    pub step: i16,\n    pub count: i32,\n}"), "{contents}");
+    assert!(!contents.contains("scratch"), "{contents}");
+    assert!(
+        contents.contains(
+            "pub struct mainProg_type {\n    pub counter: Counter_type,\n    pub message: [u16; 5],\n}"
+        ),
+        "{contents}"
+    );
+    assert!(contents.contains("pub static mut mainProg_instance: mainProg_type;"), "{contents}");
+    assert!(contents.contains("pub fn Counter(instance: *mut Counter_type);"), "{contents}");
+    assert!(contents.contains("pub fn mainProg(instance: *mut mainProg_type);"), "{contents}");
+    assert!(
+        contents.contains(
+            "#[link_name = \"Counter.reset\"]\n    pub fn Counter__reset(instance: *mut Counter_type, value: f64) -> bool;"
+        ),
+        "{contents}"
+    );
+}
+
+// ------------------ //
+// -- Test Case 18 -- //
+// ------------------ //
+
+#[test]
+fn case_18_python_bindings_declare_structures_and_prototypes() {
+    let source_code = SourceCode::new(
+        "
+    TYPE Color : (red, green := 5, blue);
+    END_TYPE
+
+    TYPE Point :
+    STRUCT
+        x : DINT;
+        history : ARRAY[0..1, 0..2] OF INT;
+    END_STRUCT
+    END_TYPE
+
+    FUNCTION add : DINT
+    VAR_INPUT
+        a : DINT;
+        b : REF_TO DINT;
+    END_VAR
+    END_FUNCTION
+
+    FUNCTION_BLOCK Counter
+    VAR_INPUT
+        step : INT;
+    END_VAR
+        METHOD reset : BOOL
+        VAR_INPUT
+            value : LREAL;
+        END_VAR
+        END_METHOD
+    END_FUNCTION_BLOCK
+    ",
+        "python_bindings.pli",
+    );
+
+    let generated_headers = generate_all_header_contents(source_code, GenerateLanguage::Python);
+    assert_eq!(generated_headers.len(), 1);
+    assert_eq!(generated_headers[0].get_path(), "python_bindings.py");
+
+    let contents = generated_headers[0].get_contents();
+    assert!(contents.contains("import ctypes"), "{contents}");
+    assert!(contents.contains("class Point(ctypes.Structure):\n    pass\n"), "{contents}");
+    assert!(
+        contents.contains("Color = ctypes.c_int32\nColor_red = 0\nColor_green = 5\nColor_blue = 6\n"),
+        "{contents}"
+    );
+    assert!(
+        contents.contains(
+            "Point._fields_ = [\n    (\"x\", ctypes.c_int32),\n    (\"history\", ctypes.c_int16 * 3 * 2),\n]"
+        ),
+        "{contents}"
+    );
+    assert!(contents.contains("assert ctypes.sizeof(Point) == 16"), "{contents}");
+    assert!(
+        contents.contains(
+            "(\"add\", \"add\", [ctypes.c_int32, ctypes.POINTER(ctypes.c_int32)], ctypes.c_int32),"
+        ),
+        "{contents}"
+    );
+    assert!(
+        contents.contains("(\"Counter\", \"Counter\", [ctypes.POINTER(Counter_type)], None),"),
+        "{contents}"
+    );
+    assert!(
+        contents.contains(
+            "(\"Counter__reset\", \"Counter.reset\", [ctypes.POINTER(Counter_type), ctypes.c_double], ctypes.c_bool),"
+        ),
+        "{contents}"
+    );
+    assert!(contents.contains("def load(path):"), "{contents}");
+}
+
+// ------------------ //
+// -- Test Case 19 -- //
+// ------------------ //
+
+#[test]
+fn case_19_cpp_header_declares_classes_with_member_functions() {
+    let source_code = SourceCode::new(
+        "
+    FUNCTION_BLOCK Motor
+    VAR_INPUT
+        enabled : BOOL;
+    END_VAR
+        METHOD start : BOOL
+        VAR_INPUT
+            speed : INT;
+        END_VAR
+        END_METHOD
+
+        PROPERTY rpm : INT
+            GET
+                rpm := 0;
+            END_GET
+        END_PROPERTY
+    END_FUNCTION_BLOCK
+
+    FUNCTION_BLOCK Servo EXTENDS Motor
+    VAR
+        position : SINT;
+    END_VAR
+    END_FUNCTION_BLOCK
+    ",
+        "cpp_classes.pli",
+    );
+
+    let generated_headers = generate_all_header_contents(source_code, GenerateLanguage::Cpp);
+    assert_eq!(generated_headers.len(), 1);
+    assert_eq!(generated_headers[0].get_path(), "cpp_classes.hpp");
+
+    let contents = generated_headers[0].get_contents();
+    assert!(contents.contains("extern \"C\" {"), "{contents}");
+    assert!(contents.contains("struct Motor_type {\n"), "{contents}");
+    assert!(
+        contents.contains(
+            "struct Servo_type : Motor_type {\n    alignas(alignof(Motor_type)) std::int8_t position;\n"
+        ),
+        "{contents}"
+    );
+    assert!(contents.contains("void operator()() { ::Motor(this); }"), "{contents}");
+    assert!(contents.contains("void operator()() { ::Servo(this); }"), "{contents}");
+    assert!(
+        contents.contains(
+            "bool Motor__start(Motor_type* instance, std::int16_t speed) PLC_SYMBOL(\"Motor.start\");"
+        ),
+        "{contents}"
+    );
+    assert!(
+        contents.contains("bool start(std::int16_t speed) { return ::Motor__start(this, speed); }"),
+        "{contents}"
+    );
+    assert!(contents.contains("std::int16_t get_rpm() { return ::Motor____get_rpm(this); }"), "{contents}");
+    assert!(contents.contains("static_assert(sizeof(Servo_type) == "), "{contents}");
+    assert!(!contents.contains("__Motor"), "{contents}");
+}
+
 // -------------------------------- //
 // -- Re-usable pipeline methods -- //
 // -------------------------------- //
@@ -1033,6 +1280,33 @@ fn get_all_generated_header_contents(test_name: &str) -> Box<dyn GeneratedHeader
 ///
 /// The template data is generated by invoking the pipeline
 fn prepare_all_generated_header_contents(source_code: SourceCode) -> Vec<Box<dyn GeneratedHeader>> {
+    prepare_all_generated_header_contents_for_language(source_code, GenerateLanguage::C)
+}
+
+/// Returns the generated headers for the given language based on the given source code.
+///
+/// ---
+///
+/// Unlike [prepare_all_generated_header_contents] the templating engine has already been run for the returned headers.
+fn generate_all_header_contents(
+    source_code: SourceCode,
+    language: GenerateLanguage,
+) -> Vec<Box<dyn GeneratedHeader>> {
+    let mut generated_headers = prepare_all_generated_header_contents_for_language(source_code, language);
+    for generated_header in &mut generated_headers {
+        generated_header.generate_headers().expect("Header generation failed!");
+    }
+
+    generated_headers
+}
+
+/// Returns the generated header template for the given language based on the given source code.
+fn prepare_all_generated_header_contents_for_language(
+    source_code: SourceCode,
+    language: GenerateLanguage,
+) -> Vec<Box<dyn GeneratedHeader>> {
+    let generate_header_options =
+        GenerateHeaderOptions { language, ..get_default_generated_header_options() };
     let mut compilation_units: Vec<&CompilationUnit> = Vec::new();
 
     // Fetch parsed project
@@ -1068,7 +1342,7 @@ fn prepare_all_generated_header_contents(source_code: SourceCode) -> Vec<Box<dyn
 
     for unit in compilation_units {
         let generated_header = prepare_template_data_for_header_generation(
-            &get_default_generated_header_options(),
+            &generate_header_options,
             unit,
             &annotated_project_wrapper.annotated_project.index,
         )
@@ -0,0 +1,149 @@
+use plc::codegen::CodegenContext;
+use plc_diagnostics::{diagnostician::Diagnostician, reporter::DiagnosticReporter};
+use plc_index::GlobalContext;
+use project::project::Project;
+
+use crate::{
+    pipelines::BuildPipeline,
+    repl::{is_complete, run, Repl},
+    CompileOptions,
+};
+
+fn repl(context: &CodegenContext) -> Repl<'_> {
+    let pipeline = BuildPipeline {
+        context: GlobalContext::new(),
+        project: Project::new("repl".to_string()),
+        diagnostician: Diagnostician::buffered(),
+        compile_parameters: None,
+        linker: plc::linker::LinkerType::Internal,
+        mutable_participants: vec![],
+        participants: vec![],
+        module_name: None,
+    };
+    Repl::new(context, pipeline, CompileOptions::default(), vec![], vec![]).unwrap()
+}
+
+#[test]
+fn expressions_are_printed_with_their_type() {
+    let context = CodegenContext::create();
+    let mut repl = repl(&context);
+
+    assert_eq!(repl.eval("1 + 2").unwrap().as_deref(), Some("3 : DINT"));
+    assert_eq!(repl.eval("TRUE AND FALSE;").unwrap().as_deref(), Some("FALSE : BOOL"));
+    assert_eq!(repl.eval("2.5 * 2.0").unwrap().as_deref(), Some("5 : REAL"));
+    assert_eq!(repl.eval("T#1s + T#500ms").unwrap().as_deref(), Some("T#1s500ms : TIME"));
+    assert_eq!(repl.eval("'hello'").unwrap().as_deref(), Some("'hello' : STRING[5]"));
+}
+
+#[test]
+fn session_variables_keep_their_values() {
+    let context = CodegenContext::create();
+    let mut repl = repl(&context);
+
+    assert_eq!(repl.eval("VAR x : INT := 40; END_VAR").unwrap(), None);
+    assert_eq!(repl.eval("x := x + 1;").unwrap(), None);
+    assert_eq!(repl.eval("x := x + 1;").unwrap(), None);
+    assert_eq!(repl.eval("x").unwrap().as_deref(), Some("42 : INT"));
+}
+
+#[test]
+fn declared_pous_can_be_called() {
+    let context = CodegenContext::create();
+    let mut repl = repl(&context);
+
+    repl.eval(
+        "
+        FUNCTION square : DINT
+        VAR_INPUT x : DINT; END_VAR
+            square := x * x;
+        END_FUNCTION
+        ",
+    )
+    .unwrap();
+    repl.eval(
+        "
+        FUNCTION_BLOCK Counter
+        VAR count : DINT; END_VAR
+            count := count + 1;
+        END_FUNCTION_BLOCK
+        ",
+    )
+    .unwrap();
+    repl.eval("VAR c : Counter; END_VAR").unwrap();
+
+    assert_eq!(repl.eval("square(7)").unwrap().as_deref(), Some("49 : DINT"));
+    assert_eq!(repl.eval("c(); c(); c();").unwrap(), None);
+    assert_eq!(repl.eval("c.count").unwrap().as_deref(), Some("3 : DINT"));
+}
+
+#[test]
+fn aggregate_results_are_printed() {
+    let context = CodegenContext::create();
+    let mut repl = repl(&context);
+
+    repl.eval(
+        "
+        FUNCTION greet : STRING[20]
+        VAR_INPUT name : STRING[10]; END_VAR
+            greet := name;
+        END_FUNCTION
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(repl.eval("greet('world')").unwrap().as_deref(), Some("'world' : STRING[20]"));
+}
+
+#[test]
+fn rejected_input_does_not_change_the_session() {
+    let context = CodegenContext::create();
+    let mut repl = repl(&context);
+
+    repl.eval("VAR x : DINT := 1; END_VAR").unwrap();
+    assert!(repl.eval("y := 2;").is_err());
+    assert!(repl.eval("VAR x : BOOL; END_VAR").is_err());
+    assert!(repl.eval("x := ;").is_err());
+
+    assert_eq!(repl.eval("x").unwrap().as_deref(), Some("1 : DINT"));
+    assert_eq!(repl.get_declarations(), "VAR_GLOBAL x : DINT := 1; END_VAR");
+}
+
+#[test]
+fn declarations_are_added_while_the_session_index_is_shared() {
+    let context = CodegenContext::create();
+    let mut repl = repl(&context);
+    let shared = repl.index.clone();
+
+    repl.eval("VAR x : DINT := 1; END_VAR").unwrap();
+    repl.eval("VAR y : DINT := 2; END_VAR").unwrap();
+
+    assert_eq!(repl.eval("x + y").unwrap().as_deref(), Some("3 : DINT"));
+    assert!(shared.find_global_variable("x").is_none());
+}
+
+#[test]
+fn rejected_input_is_reported_through_the_diagnostician() {
+    let context = CodegenContext::create();
+    let mut repl = repl(&context);
+    let mut out = vec![];
+
+    run(&mut repl, "y := 2;\n1 + 2\n".as_bytes(), &mut out).unwrap();
+
+    let report = repl.pipeline.diagnostician.buffer().unwrap();
+    assert!(report.contains("Could not resolve reference to y"));
+    assert!(report.contains("Input rejected due to errors"));
+    assert!(String::from_utf8(out).unwrap().contains("3 : DINT"));
+}
+
+#[test]
+fn input_is_complete_once_all_blocks_are_closed() {
+    assert!(is_complete("x := 1;"));
+    assert!(is_complete("1 + 2"));
+    assert!(!is_complete("IF x THEN\n"));
+    assert!(!is_complete("IF x THEN\n  y := 1;\n"));
+    assert!(is_complete("IF x THEN\n  y := 1;\nEND_IF\n"));
+    assert!(!is_complete("TYPE Point : STRUCT\n x : DINT;\nEND_STRUCT\n"));
+    assert!(is_complete("TYPE Point : STRUCT\n x : DINT;\nEND_STRUCT\nEND_TYPE\n"));
+    assert!(!is_complete("FUNCTION foo : DINT\nVAR_INPUT a : DINT; END_VAR\n"));
+    assert!(is_complete("FUNCTION foo : DINT\nVAR_INPUT a : DINT; END_VAR\nEND_FUNCTION\n"));
+}
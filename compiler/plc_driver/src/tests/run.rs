@@ -0,0 +1,199 @@
+use std::{cell::Cell, time::Duration};
+
+use plc::codegen::CodegenContext;
+use plc_diagnostics::{diagnostician::Diagnostician, diagnostics::Diagnostic};
+use plc_index::GlobalContext;
+use project::project::Project;
+use source_code::SourceCode;
+
+use crate::{
+    pipelines::{AnnotatedProject, ParsedProject},
+    runner::{check_without_clock, run_program, RunOptions},
+    CompileOptions,
+};
+
+fn options(program: &str, cycles: u64, watch: &[&str]) -> RunOptions {
+    RunOptions {
+        program: program.to_string(),
+        cycles,
+        cycle_time: Duration::from_millis(10),
+        watch: watch.iter().map(|it| it.to_string()).collect(),
+    }
+}
+
+fn annotate(src: &str) -> AnnotatedProject {
+    let mut diagnostician = Diagnostician::null_diagnostician();
+    let project = Project::new("TestProject".into()).with_sources(vec![SourceCode::new(src, "main.st")]);
+    let ctxt = GlobalContext::new().with_source(project.get_sources(), None).unwrap();
+    ParsedProject::parse(&ctxt, &project, &mut diagnostician)
+        .unwrap()
+        .index(ctxt.provider())
+        .annotate(ctxt.provider())
+}
+
+fn run(src: &str, options: &RunOptions) -> Result<String, Diagnostic> {
+    let project = annotate(src);
+    let context = CodegenContext::create();
+    let module = project.generate_single_module(&context, &CompileOptions::default(), None).unwrap().unwrap();
+    let mut out = Vec::new();
+    run_program(&module, &project.index, options, &mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn program_is_called_cyclically_and_watched_variables_are_printed() {
+    let src = "
+    TYPE Pair : STRUCT
+        a : INT;
+        b : LREAL;
+    END_STRUCT END_TYPE
+
+    PROGRAM mainProg
+    VAR
+        counter : DINT;
+        toggle : BOOL;
+        pair : Pair;
+    END_VAR
+        counter := counter + 1;
+        toggle := NOT toggle;
+        pair.a := pair.a + 2;
+        pair.b := pair.b + 0.5;
+    END_PROGRAM
+    ";
+
+    let output = run(src, &options("mainProg", 3, &["counter", "toggle", "pair.a", "pair.b"])).unwrap();
+
+    assert_eq!(
+        output,
+        "cycle 1: counter = 1, toggle = TRUE, pair.a = 2, pair.b = 0.5\n\
+         cycle 2: counter = 2, toggle = FALSE, pair.a = 4, pair.b = 1\n\
+         cycle 3: counter = 3, toggle = TRUE, pair.a = 6, pair.b = 1.5\n"
+    );
+}
+
+#[test]
+fn initial_values_are_applied_before_the_first_cycle() {
+    let src = "
+    PROGRAM mainProg
+    VAR
+        x : DINT := 40;
+        s : STRING := 'hello';
+    END_VAR
+        x := x + 2;
+    END_PROGRAM
+    ";
+
+    let output = run(src, &options("mainProg", 1, &["x", "s"])).unwrap();
+
+    assert_eq!(output, "cycle 1: x = 42, s = 'hello'\n");
+}
+
+thread_local! {
+    // every test runs on its own thread, other tests running in parallel do not move this clock
+    static CLOCK_NS: Cell<u64> = const { Cell::new(u64::MAX) };
+}
+
+extern "C" fn set_clock(ns: u64) {
+    CLOCK_NS.with(|it| it.set(ns));
+}
+
+extern "C" fn advance_clock(ns: u64) {
+    CLOCK_NS.with(|it| it.set(it.get() + ns));
+}
+
+extern "C" fn read_clock() -> u64 {
+    CLOCK_NS.with(Cell::get)
+}
+
+#[test]
+fn simulated_clock_is_reset_and_advanced_by_the_cycle_time_after_every_cycle() {
+    plc::codegen::add_symbol("__mock_time_set_ns", set_clock as usize);
+    plc::codegen::add_symbol("__mock_time_advance_ns", advance_clock as usize);
+    plc::codegen::add_symbol("read_test_clock", read_clock as usize);
+    let src = "
+    {external}
+    FUNCTION read_test_clock : ULINT
+    END_FUNCTION
+
+    PROGRAM mainProg
+    VAR
+        now : ULINT;
+    END_VAR
+        now := read_test_clock();
+    END_PROGRAM
+    ";
+
+    let options = RunOptions { cycle_time: Duration::from_millis(5), ..options("mainProg", 3, &["now"]) };
+    let output = run(src, &options).unwrap();
+
+    assert_eq!(output, "cycle 1: now = 0\ncycle 2: now = 5000000\ncycle 3: now = 10000000\n");
+    assert_eq!(read_clock(), 15_000_000);
+}
+
+#[test]
+fn timers_cannot_be_run_without_the_simulated_clock() {
+    let src = "
+    {external}
+    FUNCTION_BLOCK TON
+    VAR_INPUT
+        IN : BOOL;
+        PT : TIME;
+    END_VAR
+    VAR_OUTPUT
+        Q : BOOL;
+        ET : TIME;
+    END_VAR
+    END_FUNCTION_BLOCK
+
+    PROGRAM mainProg
+    VAR
+        delay : TON;
+    END_VAR
+    END_PROGRAM
+    ";
+
+    let err = check_without_clock(&annotate(src).index, "mainProg").unwrap_err();
+
+    assert_eq!(err.get_error_code(), "E149");
+    assert!(err.get_message().contains("`mainProg.delay`"), "{}", err.get_message());
+}
+
+#[test]
+fn programs_without_timers_run_without_the_simulated_clock() {
+    let src = "
+    PROGRAM mainProg
+    VAR
+        x : DINT;
+    END_VAR
+    END_PROGRAM
+    ";
+
+    assert!(check_without_clock(&annotate(src).index, "mainProg").is_ok());
+}
+
+#[test]
+fn running_an_unknown_program_is_an_error() {
+    let src = "
+    FUNCTION foo : DINT
+    END_FUNCTION
+    ";
+
+    let err = run(src, &options("foo", 1, &[])).unwrap_err();
+
+    assert_eq!(err.get_error_code(), "E149");
+}
+
+#[test]
+fn watching_an_unknown_variable_is_an_error() {
+    let src = "
+    PROGRAM mainProg
+    VAR
+        x : DINT;
+    END_VAR
+    END_PROGRAM
+    ";
+
+    let err = run(src, &options("mainProg", 1, &["y"])).unwrap_err();
+
+    assert_eq!(err.get_error_code(), "E174");
+}
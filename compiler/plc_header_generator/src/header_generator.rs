@@ -14,6 +14,9 @@ use crate::{
     header_generator::{
         file_helper::{format_path, FileHelper},
         header_generator_c::GeneratedHeaderForC,
+        header_generator_cpp::GeneratedHeaderForCpp,
+        header_generator_python::GeneratedHeaderForPython,
+        header_generator_rust::GeneratedHeaderForRust,
         symbol_helper::SymbolHelper,
         template_helper::{TemplateData, TemplateHelper, Variable, VariableType},
         type_helper::TypeHelper,
@@ -21,12 +24,19 @@ use crate::{
     GenerateHeaderOptions, GenerateLanguage,
 };
 
+mod declaration_helper;
 pub mod file_helper;
 mod header_generator_c;
+mod header_generator_cpp;
+mod header_generator_python;
+mod header_generator_rust;
 mod symbol_helper;
 pub mod template_helper;
 mod type_helper;
 
+/// The constant value for the string that is appended to types added by the header generation process
+const TYPE_APPEND: &str = "_type";
+
 /// A combined trait containing all of the necessary implementations for generating a header
 pub trait GeneratedHeader: FileHelper + TypeHelper + TemplateHelper + SymbolHelper {
     /// Returns whether or not this generated header is empty
@@ -162,15 +172,25 @@ pub fn combine_generated_headers(
 pub fn get_empty_generated_header_from_options(
     generate_header_options: &GenerateHeaderOptions,
 ) -> Result<Box<dyn GeneratedHeader>, Diagnostic> {
-    let generated_header: Result<Box<dyn GeneratedHeader>, Diagnostic> = match generate_header_options
-        .language
-    {
-        GenerateLanguage::C => {
-            let generated_header = GeneratedHeaderForC::new();
-            Ok(Box::new(generated_header))
-        }
-        language => Err(Diagnostic::new(format!("This language '{:?}' is not yet implemented!", language))),
-    };
+    let generated_header: Result<Box<dyn GeneratedHeader>, Diagnostic> =
+        match generate_header_options.language {
+            GenerateLanguage::C => {
+                let generated_header = GeneratedHeaderForC::new();
+                Ok(Box::new(generated_header))
+            }
+            GenerateLanguage::Rust => {
+                let generated_header = GeneratedHeaderForRust::new();
+                Ok(Box::new(generated_header))
+            }
+            GenerateLanguage::Python => {
+                let generated_header = GeneratedHeaderForPython::new();
+                Ok(Box::new(generated_header))
+            }
+            GenerateLanguage::Cpp => {
+                let generated_header = GeneratedHeaderForCpp::new();
+                Ok(Box::new(generated_header))
+            }
+        };
 
     generated_header
 }
@@ -0,0 +1,553 @@
+use plc::{
+    index::{Index, VariableIndexEntry},
+    typesystem::{
+        get_builtin_types, DataType, DataTypeInformation, StringEncoding, StructSource, VarArgs, DINT_TYPE,
+    },
+};
+use plc_ast::ast::{CompilationUnit, LinkageType, Pou, PouType, PropertyKind};
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::source_location::SourceLocation;
+
+use crate::header_generator::{
+    data_type_is_system_generated,
+    template_helper::{
+        Function, MemberFunction, MemberFunctionKind, TemplateData, UserType, Variable, VariableType,
+    },
+    type_helper::TypeHelper,
+    ExtendedTypeName, TYPE_APPEND,
+};
+
+/// The name of the parameter that receives the instance of a function block, program or class
+const INSTANCE_PARAMETER: &str = "instance";
+
+/// Spells the declarations of a language whose types mirror the memory layout of the compiled code
+pub trait DeclarationHelper: TypeHelper {
+    /// Returns the given name as an identifier of the language, escaping it if it is a keyword
+    fn format_identifier(&self, name: &str) -> String;
+
+    /// Returns the type of a pointer to the given type, or of an untyped pointer if there is none
+    fn format_pointer_type(&self, inner_type: Option<&str>) -> String;
+
+    /// Returns the type of an array with the given length of the given type
+    fn format_array_type(&self, inner_type: &str, length: u32) -> String;
+}
+
+/// Prepares the template data for a language that mirrors the compiled code, returning it together with the
+/// diagnostics that were reported while doing so.
+///
+/// ---
+///
+/// Unlike the "C" header the declarations are derived from the index, so user types, instances and function
+/// signatures match the layout and calling convention of the generated code. Structs and enums carry their size.
+pub fn prepare_mirrored_template_data<H: DeclarationHelper>(
+    helper: &H,
+    compilation_unit: &CompilationUnit,
+    index: &Index,
+) -> (TemplateData, Vec<Diagnostic>) {
+    let mut preparer = DeclarationPreparer {
+        helper,
+        index,
+        builtin_types: get_builtin_types(),
+        template_data: TemplateData::new(),
+        diagnostics: Vec::new(),
+        referenced_structs: Vec::new(),
+        member_functions: Vec::new(),
+    };
+
+    preparer.prepare_user_types(compilation_unit);
+    preparer.prepare_global_variables(compilation_unit);
+    preparer.prepare_functions(compilation_unit);
+    preparer.prepare_referenced_structs();
+    preparer.attach_member_functions();
+
+    (preparer.template_data, preparer.diagnostics)
+}
+
+struct DeclarationPreparer<'h, H: DeclarationHelper> {
+    helper: &'h H,
+    index: &'h Index,
+    builtin_types: Vec<DataType>,
+    template_data: TemplateData,
+    diagnostics: Vec<Diagnostic>,
+    /// The system generated structs (e.g. inline struct declarations) referenced by the header
+    referenced_structs: Vec<String>,
+    /// The functions operating on an instance, by the name of the instance type
+    member_functions: Vec<(String, MemberFunction)>,
+}
+
+impl<H: DeclarationHelper> DeclarationPreparer<'_, H> {
+    /// Populates the [TemplateData] instance with the user types that should be added to the generated header file
+    fn prepare_user_types(&mut self, compilation_unit: &CompilationUnit) {
+        for user_type in &compilation_unit.user_types {
+            if user_type.linkage.is_external_or_included() {
+                continue;
+            }
+
+            // System generated types are only declared if they are referenced
+            let Some(name) = user_type.data_type.get_name().filter(|it| !data_type_is_system_generated(it))
+            else {
+                continue;
+            };
+
+            let Some(data_type) = self.index.find_type(name) else {
+                continue;
+            };
+
+            match data_type.get_type_information() {
+                DataTypeInformation::Struct { .. } => {
+                    self.prepare_struct(data_type, self.helper.format_identifier(name));
+                }
+                DataTypeInformation::Enum { referenced_type, variants, .. } => {
+                    self.prepare_enum(data_type, referenced_type, variants);
+                }
+                _ => {
+                    let data_type = self.get_type_definition(data_type);
+                    self.template_data.user_defined_types.aliases.push(Variable {
+                        data_type,
+                        name: self.helper.format_identifier(name),
+                        variable_type: VariableType::Default,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Populates the [TemplateData] instance with the global variables that should be added to the generated header file
+    fn prepare_global_variables(&mut self, compilation_unit: &CompilationUnit) {
+        for variable_block in &compilation_unit.global_vars {
+            if variable_block.linkage == LinkageType::External {
+                continue;
+            }
+
+            for variable in &variable_block.variables {
+                if data_type_is_system_generated(variable.get_name()) {
+                    continue;
+                }
+
+                let Some(global_variable) = self.index.find_global_variable(variable.get_name()) else {
+                    continue;
+                };
+
+                let data_type = self.get_type(global_variable.get_type_name());
+                self.template_data.global_variables.push(Variable {
+                    data_type,
+                    name: self.helper.format_identifier(variable.get_name()),
+                    variable_type: VariableType::Default,
+                });
+            }
+        }
+    }
+
+    /// Populates the [TemplateData] instance with the functions that should be added to the generated header file
+    ///
+    /// ---
+    ///
+    /// Function blocks, programs and classes also add the struct of their instances.
+    fn prepare_functions(&mut self, compilation_unit: &CompilationUnit) {
+        for pou in &compilation_unit.pous {
+            if matches!(pou.kind, PouType::Init | PouType::ProjectInit) {
+                continue;
+            }
+            if pou.linkage.is_external_or_included() || pou.is_generic() {
+                continue;
+            }
+
+            // The accessors of a property are only declared as members of the instance
+            if let PouType::Method { parent, property: Some((name, kind)), .. } = &pou.kind {
+                self.prepare_property_accessor(pou, parent, name, kind);
+                continue;
+            }
+
+            let method_name = pou.name.rsplit('.').next().unwrap_or_default();
+            if data_type_is_system_generated(method_name) {
+                continue;
+            }
+
+            let Some(data_type) = self.index.find_pou_type(&pou.name) else {
+                continue;
+            };
+
+            match &pou.kind {
+                PouType::Function => {
+                    let parameters = self.get_parameters(&pou.name);
+                    let return_type = self.get_return_type(&pou.name);
+                    self.template_data.functions.push(Function {
+                        return_type,
+                        name: pou.name.to_string(),
+                        parameters,
+                    });
+                }
+                PouType::FunctionBlock | PouType::Program => {
+                    let instance_type = self.prepare_instance_struct(pou, data_type);
+
+                    // Adds the global variable instance for this program
+                    if pou.kind == PouType::Program {
+                        self.template_data.global_variables.push(Variable {
+                            data_type: instance_type,
+                            name: format!("{}_instance", pou.name),
+                            variable_type: VariableType::Default,
+                        });
+                    }
+
+                    let function = Function {
+                        return_type: String::new(),
+                        name: pou.name.to_string(),
+                        parameters: vec![self.get_instance_parameter(&pou.name)],
+                    };
+                    self.add_member_function(&pou.name, String::new(), MemberFunctionKind::Body, &function);
+                    self.template_data.functions.push(function);
+                }
+                PouType::Class => {
+                    self.prepare_instance_struct(pou, data_type);
+                }
+                PouType::Method { parent, .. } => {
+                    let mut parameters = vec![self.get_instance_parameter(parent)];
+                    parameters.append(&mut self.get_parameters(&pou.name));
+                    let return_type = self.get_return_type(&pou.name);
+                    let function = Function { return_type, name: pou.name.to_string(), parameters };
+                    let name = self.helper.format_identifier(method_name);
+                    self.add_member_function(parent, name, MemberFunctionKind::Method, &function);
+                    self.template_data.functions.push(function);
+                }
+                _ => continue,
+            }
+        }
+
+        for implementation in &compilation_unit.implementations {
+            if implementation.pou_type != PouType::Action || implementation.linkage.is_external_or_included()
+            {
+                continue;
+            }
+
+            self.template_data.functions.push(Function {
+                return_type: String::new(),
+                name: implementation.name.to_string(),
+                parameters: vec![self.get_instance_parameter(&implementation.type_name)],
+            });
+        }
+    }
+
+    /// Adds the struct of the instances of a function block, program or class, returning its name
+    fn prepare_instance_struct(&mut self, pou: &Pou, data_type: &DataType) -> String {
+        let instance_type = format!("{}{TYPE_APPEND}", pou.name);
+        self.prepare_struct(data_type, instance_type.clone());
+
+        // The base is stored as the first member of the instance
+        let base = pou.super_class.as_ref().map(|it| format!("{}{TYPE_APPEND}", it.name));
+        if let Some(user_type) =
+            self.template_data.user_defined_types.structs.iter_mut().find(|it| it.name == instance_type)
+        {
+            user_type.base = base;
+        }
+
+        instance_type
+    }
+
+    /// Adds the getter or setter of a property as a member function of the instance
+    fn prepare_property_accessor(&mut self, pou: &Pou, parent: &str, name: &str, kind: &PropertyKind) {
+        if pou.linkage.is_external_or_included() || self.index.find_pou_type(&pou.name).is_none() {
+            return;
+        }
+
+        let mut parameters = vec![self.get_instance_parameter(parent)];
+        parameters.append(&mut self.get_parameters(&pou.name));
+        let return_type = self.get_return_type(&pou.name);
+        let function = Function { return_type, name: pou.name.to_string(), parameters };
+
+        let (name, kind) = match kind {
+            PropertyKind::Get => (format!("get_{name}"), MemberFunctionKind::Getter),
+            PropertyKind::Set => (format!("set_{name}"), MemberFunctionKind::Setter),
+        };
+        self.add_member_function(parent, self.helper.format_identifier(&name), kind, &function);
+    }
+
+    fn add_member_function(
+        &mut self,
+        pou_name: &str,
+        name: String,
+        kind: MemberFunctionKind,
+        function: &Function,
+    ) {
+        self.member_functions.push((
+            format!("{pou_name}{TYPE_APPEND}"),
+            MemberFunction { name, kind, function: function.clone() },
+        ));
+    }
+
+    /// Moves the member functions to the structs of the instances they operate on
+    fn attach_member_functions(&mut self) {
+        for (instance_type, member_function) in std::mem::take(&mut self.member_functions) {
+            if let Some(user_type) =
+                self.template_data.user_defined_types.structs.iter_mut().find(|it| it.name == instance_type)
+            {
+                user_type.member_functions.push(member_function);
+            }
+        }
+    }
+
+    /// Populates the [TemplateData] instance with the system generated structs that are referenced by
+    /// the already prepared declarations
+    fn prepare_referenced_structs(&mut self) {
+        let mut prepared = 0;
+        // Preparing a struct may reference further system generated structs
+        while let Some(name) = self.referenced_structs.get(prepared).cloned() {
+            if let Some(data_type) = self.index.find_type(&name) {
+                self.prepare_struct(data_type, name);
+            }
+            prepared += 1;
+        }
+    }
+
+    /// Adds the struct that mirrors the stored members of the given struct type
+    fn prepare_struct(&mut self, data_type: &DataType, name: String) {
+        let DataTypeInformation::Struct { members, .. } = data_type.get_type_information() else {
+            return;
+        };
+
+        // Temporary variables, the return value and external variables are not part of the instance
+        let mut variables: Vec<Variable> = Vec::new();
+        for member in members.iter().filter(|it| !(it.is_temp() || it.is_return() || it.is_var_external())) {
+            variables.push(Variable {
+                data_type: self.get_type(member.get_type_name()),
+                name: self.helper.format_identifier(member.get_name()),
+                variable_type: VariableType::Default,
+            });
+        }
+
+        let size = self.get_size(data_type);
+        self.template_data.user_defined_types.structs.push(UserType {
+            name,
+            variables,
+            data_type: None,
+            size,
+            base: None,
+            member_functions: Vec::new(),
+        });
+    }
+
+    /// Adds the enum with the values of its variants
+    fn prepare_enum(&mut self, data_type: &DataType, referenced_type: &str, variants: &[VariableIndexEntry]) {
+        let name = self.helper.format_identifier(data_type.get_name());
+
+        let mut variables: Vec<Variable> = Vec::new();
+        for variant in variants {
+            let value = variant
+                .initial_value
+                .as_ref()
+                .and_then(|it| self.index.get_const_expressions().get_constant_int_statement_value(it).ok());
+
+            match value {
+                Some(value) => variables.push(Variable {
+                    data_type: name.clone(),
+                    name: self.helper.format_identifier(variant.get_name()),
+                    variable_type: VariableType::Declaration(value),
+                }),
+                None => self.diagnostics.push(
+                    Diagnostic::new(
+                        "Header generation failed: unable to resolve this expression to a constant integer value",
+                    )
+                    .with_location(&variant.source_location),
+                ),
+            }
+        }
+
+        // The representation of an enum has to be a primitive integer type
+        let representation = self.index.get_effective_type_or_void_by_name(referenced_type);
+        let representation = self.get_type_definition(representation);
+        let size = self.get_size(data_type);
+
+        self.template_data.user_defined_types.enums.push(UserType {
+            name,
+            variables,
+            data_type: Some(representation),
+            size,
+            base: None,
+            member_functions: Vec::new(),
+        });
+    }
+
+    /// Returns the parameters of the function or method with the given name as they are passed by the generated code
+    fn get_parameters(&mut self, pou_name: &str) -> Vec<Variable> {
+        let mut parameters: Vec<Variable> = Vec::new();
+
+        for parameter in self.index.get_available_parameters(pou_name) {
+            let type_name = parameter.get_type_name();
+            let mut data_type = self.get_type(type_name);
+
+            // Aggregate values are passed as a pointer to them
+            if !parameter.get_declaration_type().is_by_ref()
+                && self.index.get_type_information_or_void(type_name).is_aggregate()
+            {
+                data_type = self.helper.format_pointer_type(Some(&data_type));
+            }
+
+            parameters.push(Variable {
+                data_type,
+                name: self.helper.format_identifier(parameter.get_name()),
+                variable_type: VariableType::Default,
+            });
+        }
+
+        let variadic = self.index.get_variadic_member(pou_name);
+        match variadic.and_then(VariableIndexEntry::get_varargs) {
+            Some(VarArgs::Unsized(_)) => parameters.push(Variable {
+                data_type: String::new(),
+                name: String::new(),
+                variable_type: VariableType::Variadic,
+            }),
+            Some(VarArgs::Sized(type_name)) => {
+                let name =
+                    self.helper.format_identifier(variadic.map(|it| it.get_name()).unwrap_or_default());
+
+                // Sized variadics are passed as the number of arguments and a pointer to them
+                let data_type = type_name.as_ref().map(|it| self.get_type(it));
+                parameters.push(Variable {
+                    data_type: self.get_type(DINT_TYPE),
+                    name: format!("{name}_count"),
+                    variable_type: VariableType::Default,
+                });
+                parameters.push(Variable {
+                    data_type: self.helper.format_pointer_type(data_type.as_deref()),
+                    name,
+                    variable_type: VariableType::Default,
+                });
+            }
+            None => {}
+        }
+
+        parameters
+    }
+
+    /// Returns the type returned by the function or method with the given name,
+    /// or an empty string if it does not return a value
+    fn get_return_type(&mut self, pou_name: &str) -> String {
+        match self.index.find_return_type(pou_name) {
+            Some(data_type) if !data_type.get_type_information().is_void() => {
+                self.get_type(data_type.get_name())
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Returns the parameter through which the body of a stateful pou receives its instance
+    fn get_instance_parameter(&self, pou_name: &str) -> Variable {
+        Variable {
+            data_type: self.helper.format_pointer_type(Some(&format!("{pou_name}{TYPE_APPEND}"))),
+            name: String::from(INSTANCE_PARAMETER),
+            variable_type: VariableType::Default,
+        }
+    }
+
+    /// Returns the type a value of the given type is declared with.
+    ///
+    /// ---
+    ///
+    /// User declared types are referred to by their name, all other types are spelled out.
+    fn get_type(&mut self, type_name: &str) -> String {
+        let Some(data_type) = self.index.find_type(type_name) else {
+            return self.get_void_type();
+        };
+
+        let name = data_type.get_name();
+        match data_type.get_type_information() {
+            DataTypeInformation::Struct { source: StructSource::Pou(_), .. } => {
+                format!("{name}{TYPE_APPEND}")
+            }
+            DataTypeInformation::Struct { .. } => {
+                if data_type_is_system_generated(name) && !self.referenced_structs.iter().any(|it| it == name)
+                {
+                    self.referenced_structs.push(name.to_string());
+                }
+
+                self.helper.format_identifier(name)
+            }
+            _ if !data_type.location.is_internal() && !data_type_is_system_generated(name) => {
+                self.helper.format_identifier(name)
+            }
+            _ => self.get_type_definition(data_type),
+        }
+    }
+
+    /// Returns the type that spells out the definition of the given type
+    fn get_type_definition(&mut self, data_type: &DataType) -> String {
+        match data_type.get_type_information() {
+            DataTypeInformation::String { size, encoding, .. } => {
+                let char_type = self.helper.get_type_name_for_string(&(*encoding == StringEncoding::Utf16));
+                match size.as_int_value(self.index) {
+                    Ok(length) => self.helper.format_array_type(&char_type, length as u32),
+                    Err(_) => {
+                        self.report_unresolved_size(data_type.get_name(), &data_type.location);
+                        String::new()
+                    }
+                }
+            }
+            DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+                let lengths =
+                    dimensions.iter().map(|it| it.get_length(self.index)).collect::<Result<Vec<_>, _>>();
+                let Ok(lengths) = lengths else {
+                    self.report_unresolved_size(data_type.get_name(), &data_type.location);
+                    return String::new();
+                };
+
+                // Multi-dimensional arrays are nested arrays with the first dimension on the outside
+                let inner_type = self.get_type(inner_type_name);
+                lengths.iter().rev().fold(inner_type, |inner_type, length| {
+                    self.helper.format_array_type(&inner_type, *length)
+                })
+            }
+            DataTypeInformation::Pointer { inner_type_name, is_function, .. } => {
+                let inner_is_void = self.index.get_type_information_or_void(inner_type_name).is_void();
+                if *is_function || inner_is_void {
+                    self.helper.format_pointer_type(None)
+                } else {
+                    let inner_type = self.get_type(inner_type_name);
+                    self.helper.format_pointer_type(Some(&inner_type))
+                }
+            }
+            DataTypeInformation::Enum { referenced_type, .. }
+            | DataTypeInformation::Alias { referenced_type, .. }
+            | DataTypeInformation::SubRange { referenced_type, .. } => self.get_type(referenced_type),
+            // A reference to an interface is a pointer to the instance and one to its table
+            DataTypeInformation::Interface { .. } => {
+                self.helper.format_array_type(&self.helper.format_pointer_type(None), 2)
+            }
+            DataTypeInformation::Integer { .. }
+            | DataTypeInformation::Float { .. }
+            | DataTypeInformation::Void => {
+                let extended_type_name = ExtendedTypeName {
+                    type_name: data_type.get_name().to_string(),
+                    is_variadic: false,
+                    is_sized_variadic: false,
+                };
+
+                self.helper.get_type_name_for_type(&extended_type_name, &self.builtin_types).get_type_name()
+            }
+            DataTypeInformation::Struct { .. } | DataTypeInformation::Generic { .. } => self.get_void_type(),
+        }
+    }
+
+    /// Returns the type used for untyped memory
+    fn get_void_type(&self) -> String {
+        self.helper.get_type_name_for_type(&ExtendedTypeName::new(), &self.builtin_types).get_type_name()
+    }
+
+    /// Returns the size of the given type as laid out by the generated code
+    fn get_size(&mut self, data_type: &DataType) -> Option<u32> {
+        match self.index.get_type_layout().get_size(data_type.get_type_information(), self.index) {
+            Ok(size) => Some(size.value()),
+            Err(_) => {
+                self.report_unresolved_size(data_type.get_name(), &data_type.location);
+                None
+            }
+        }
+    }
+
+    fn report_unresolved_size(&mut self, type_name: &str, location: &SourceLocation) {
+        self.diagnostics.push(
+            Diagnostic::new(format!(
+                "Header generation failed: unable to determine the size of '{type_name}'"
+            ))
+            .with_location(location),
+        );
+    }
+}
@@ -7,7 +7,68 @@ use regex::Regex;
 
 use crate::GenerateHeaderOptions;
 
+/// Implements [FileHelper] for a header that keeps its [HeaderFileInformation] in a `file_information` field,
+/// writing files with the given extension
+macro_rules! impl_file_helper {
+    ($header:ty, $file_extension:literal) => {
+        impl $crate::header_generator::file_helper::FileHelper for $header {
+            fn get_directory(&self) -> &str {
+                &self.file_information.directory
+            }
+
+            fn set_directory(&mut self, directory: &str) {
+                self.file_information.directory = directory.to_string();
+            }
+
+            fn get_path(&self) -> &str {
+                &self.file_information.path
+            }
+
+            fn set_path(&mut self, path: &str) {
+                self.file_information.path = format!("{path}.{}", $file_extension);
+            }
+
+            fn get_file_name(&self) -> &str {
+                &self.file_information.name
+            }
+
+            fn set_file_name(&mut self, file_name: &str) {
+                self.file_information.name =
+                    $crate::header_generator::file_helper::format_file_name(file_name);
+            }
+
+            fn get_formatted_path(&self) -> &str {
+                &self.file_information.formatted_path
+            }
+
+            fn set_formatted_path(&mut self, formatted_path: &str) {
+                self.file_information.formatted_path =
+                    $crate::header_generator::file_helper::format_file_name(formatted_path);
+            }
+
+            fn determine_header_file_information(
+                &mut self,
+                generate_header_options: &$crate::GenerateHeaderOptions,
+                compilation_unit: &plc_ast::ast::CompilationUnit,
+            ) -> bool {
+                let (file_information, determined_successfully) =
+                    $crate::header_generator::file_helper::get_header_file_information(
+                        generate_header_options,
+                        compilation_unit,
+                        $file_extension,
+                    );
+                self.file_information = file_information;
+
+                determined_successfully
+            }
+        }
+    };
+}
+
 mod file_helper_c;
+mod file_helper_cpp;
+mod file_helper_python;
+mod file_helper_rust;
 
 use once_cell::sync::Lazy;
 
@@ -0,0 +1,3 @@
+use crate::header_generator::header_generator_cpp::GeneratedHeaderForCpp;
+
+impl_file_helper!(GeneratedHeaderForCpp, "hpp");
@@ -0,0 +1,3 @@
+use crate::header_generator::header_generator_python::GeneratedHeaderForPython;
+
+impl_file_helper!(GeneratedHeaderForPython, "py");
@@ -0,0 +1,3 @@
+use crate::header_generator::header_generator_rust::GeneratedHeaderForRust;
+
+impl_file_helper!(GeneratedHeaderForRust, "rs");
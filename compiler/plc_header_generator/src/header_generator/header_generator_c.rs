@@ -21,12 +21,9 @@ use crate::header_generator::{
         Function, TemplateData, TemplateHelper, TemplateType, UserType, Variable, VariableType,
     },
     type_helper::{TypeAttribute, TypeHelper},
-    ExtendedTypeName, GeneratedHeader,
+    ExtendedTypeName, GeneratedHeader, TYPE_APPEND,
 };
 
-/// The constant value for the string that is appended to types added by the header generation process
-const TYPE_APPEND: &str = "_type";
-
 pub struct GeneratedHeaderForC {
     pub file_information: HeaderFileInformation,
     contents: String,
@@ -369,6 +366,9 @@ impl GeneratedHeaderForC {
                     name: name.clone().unwrap_or_default(),
                     variables: formatted_variables,
                     data_type: None,
+                    size: None,
+                    base: None,
+                    member_functions: Vec::new(),
                 });
             }
             ast::DataType::EnumType { name, elements, numeric_type } => {
@@ -390,6 +390,9 @@ impl GeneratedHeaderForC {
                     name,
                     variables: enum_declerations,
                     data_type: Some(type_information.get_type_name()),
+                    size: None,
+                    base: None,
+                    member_functions: Vec::new(),
                 });
             }
             ast::DataType::ArrayType { name, bounds, referenced_type, .. } => {
@@ -525,12 +528,18 @@ impl GeneratedHeaderForC {
                 variables: self
                     .modify_function_block_variables_for_inheritance(&input_variables, super_class),
                 data_type: None,
+                size: None,
+                base: None,
+                member_functions: Vec::new(),
             });
         } else {
             self.template_data.user_defined_types.structs.push(UserType {
                 name: data_type.to_string(),
                 variables: input_variables,
                 data_type: None,
+                size: None,
+                base: None,
+                member_functions: Vec::new(),
             });
         }
 
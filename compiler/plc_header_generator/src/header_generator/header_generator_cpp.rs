@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+
+use plc::index::Index;
+use plc_ast::ast::CompilationUnit;
+use plc_diagnostics::diagnostics::Diagnostic;
+use tera::{from_value, to_value, Context, Tera};
+
+use crate::header_generator::{
+    declaration_helper::{prepare_mirrored_template_data, DeclarationHelper},
+    file_helper::HeaderFileInformation,
+    sanitize_method_name,
+    symbol_helper::SymbolHelper,
+    template_helper::{
+        Function, MemberFunction, MemberFunctionKind, TemplateData, TemplateHelper, TemplateType, UserType,
+        VariableType,
+    },
+    GeneratedHeader,
+};
+
+pub struct GeneratedHeaderForCpp {
+    pub file_information: HeaderFileInformation,
+    contents: String,
+    pub template_data: TemplateData,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Default for GeneratedHeaderForCpp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeneratedHeader for GeneratedHeaderForCpp {
+    fn is_empty(&self) -> bool {
+        (self.file_information.directory.is_empty()
+            && self.file_information.path.is_empty()
+            && self.contents.is_empty())
+            || self.template_data.is_empty()
+    }
+
+    fn get_contents(&self) -> &str {
+        &self.contents
+    }
+
+    fn prepare_template_data(&mut self, compilation_unit: &CompilationUnit, index: &Index) {
+        let (template_data, diagnostics) = prepare_mirrored_template_data(self, compilation_unit, index);
+
+        self.template_data = template_data;
+        self.diagnostics = diagnostics;
+    }
+
+    fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn generate_headers(&mut self) -> Result<(), Diagnostic> {
+        // Configure tera
+        let mut tera = Tera::default();
+        let mut context = Context::new();
+
+        let template = self.get_template(TemplateType::Header);
+        tera.add_raw_template(&template.name, &template.content)
+            .expect("Unable to add the 'header' template to tera!");
+        tera.register_function(
+            "format_function_declaration",
+            format_function_declaration(self.get_variadic_symbol()),
+        );
+        tera.register_function("format_member_function", format_member_function());
+
+        // Classes are defined after the classes they extend or contain
+        let mut user_defined_types = self.template_data.user_defined_types.clone();
+        user_defined_types.structs = order_structs_by_dependency(&user_defined_types.structs);
+        for user_type in &mut user_defined_types.structs {
+            inherit_base(user_type);
+
+            // Variadic arguments cannot be forwarded
+            user_type.member_functions.retain(|member_function| {
+                !member_function
+                    .function
+                    .parameters
+                    .iter()
+                    .any(|it| matches!(it.variable_type, VariableType::Variadic))
+            });
+        }
+
+        // The accessors of properties are only called through the members of their class
+        let mut functions = self.template_data.functions.clone();
+        functions.extend(
+            user_defined_types
+                .structs
+                .iter()
+                .flat_map(|it| &it.member_functions)
+                .filter(|it| matches!(it.kind, MemberFunctionKind::Getter | MemberFunctionKind::Setter))
+                .map(|it| it.function.clone()),
+        );
+
+        context.insert("global_variables", &self.template_data.global_variables);
+        context.insert("user_defined_types", &user_defined_types);
+        context.insert("functions", &functions);
+        context.insert("file_name_caps", &self.file_information.formatted_path);
+
+        // Set the outputs
+        self.contents = tera.render(&template.name, &context).unwrap();
+
+        Ok(())
+    }
+}
+
+impl DeclarationHelper for GeneratedHeaderForCpp {
+    fn format_identifier(&self, name: &str) -> String {
+        format_identifier(name)
+    }
+
+    fn format_pointer_type(&self, inner_type: Option<&str>) -> String {
+        let inner_type = inner_type.unwrap_or(CPP_VOID);
+        format!("{inner_type}{}", self.get_reference_symbol())
+    }
+
+    fn format_array_type(&self, inner_type: &str, length: u32) -> String {
+        format!("std::array<{inner_type}, {length}>")
+    }
+}
+
+impl GeneratedHeaderForCpp {
+    pub const fn new() -> Self {
+        GeneratedHeaderForCpp {
+            file_information: HeaderFileInformation::new(),
+            template_data: TemplateData::new(),
+            contents: String::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
+/// Returns the given name as a c++ identifier, escaping it if it is a keyword
+fn format_identifier(name: &str) -> String {
+    if CPP_KEYWORDS.contains(&name) {
+        return format!("{name}_");
+    }
+
+    name.to_string()
+}
+
+/// Replaces the member holding the base of a function block or class with the base class it extends.
+///
+/// ---
+///
+/// The compiled code stores the base as the first member, so the base class is at the same offset. Unlike a
+/// member, a base class may share its tail padding with the members of the derived class, which is prevented
+/// by aligning the first member of the derived class like its base.
+fn inherit_base(user_type: &mut UserType) {
+    let Some(base) = &user_type.base else {
+        return;
+    };
+
+    match user_type.variables.first() {
+        Some(variable) if &variable.data_type == base => {
+            user_type.variables.remove(0);
+        }
+        // The base is kept as a member if it is not stored as expected
+        _ => user_type.base = None,
+    }
+}
+
+/// Returns the structs in an order in which every struct is defined after the structs it extends or contains
+fn order_structs_by_dependency(structs: &[UserType]) -> Vec<UserType> {
+    let mut remaining = structs.to_vec();
+    let mut ordered: Vec<UserType> = Vec::new();
+
+    while !remaining.is_empty() {
+        let depends_on_remaining = |user_type: &UserType| {
+            remaining.iter().filter(|other| other.name != user_type.name).any(|other| {
+                user_type.base.as_ref() == Some(&other.name)
+                    || user_type.variables.iter().any(|it| contains_by_value(&it.data_type, &other.name))
+            })
+        };
+
+        match remaining.iter().position(|user_type| !depends_on_remaining(user_type)) {
+            Some(position) => ordered.push(remaining.remove(position)),
+            // Cyclic structs cannot be ordered, keep them as they are declared
+            None => ordered.append(&mut remaining),
+        }
+    }
+
+    ordered
+}
+
+/// Returns whether the given type contains a value of the type with the given name, rather than a pointer to it
+fn contains_by_value(data_type: &str, name: &str) -> bool {
+    data_type.match_indices(name).any(|(index, _)| {
+        let is_identifier = |it: char| it.is_alphanumeric() || it == '_';
+        let before = data_type[..index].chars().next_back();
+        let mut after = data_type[index + name.len()..].chars().skip_while(|it| it.is_whitespace());
+        !before.is_some_and(is_identifier)
+            && !matches!(after.next(), Some(it) if is_identifier(it) || it == '*')
+    })
+}
+
+/// Formats a function for its declaration within an `extern "C"` block.
+///
+/// Methods, actions and property accessors are declared with a sanitized name and bound to their qualified name.
+///
+/// ---
+///
+/// This function is used by the templating engine [tera](https://keats.github.io/tera/).
+fn format_function_declaration(variadic_symbol: String) -> impl tera::Function {
+    Box::new(move |args: &HashMap<String, serde_json::Value>| -> tera::Result<serde_json::Value> {
+        match args.get("function") {
+            Some(value) => match from_value::<Function>(value.clone()) {
+                Ok(function) => {
+                    let name = sanitize_method_name(&function.name);
+                    let symbol = if name != function.name {
+                        format!(" PLC_SYMBOL(\"{}\")", function.name)
+                    } else {
+                        String::new()
+                    };
+
+                    let parameters = function
+                        .parameters
+                        .iter()
+                        .map(|parameter| match parameter.variable_type {
+                            VariableType::Variadic => variadic_symbol.to_string(),
+                            _ => format!("{} {}", parameter.data_type, parameter.name),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    let return_type =
+                        if function.return_type.is_empty() { CPP_VOID } else { &function.return_type };
+
+                    Ok(to_value(format!("{return_type} {name}({parameters}){symbol};")).unwrap())
+                }
+                Err(_) => Err("Unable to format function for declaration!".into()),
+            },
+            None => Err("Unable to format function for declaration!".into()),
+        }
+    })
+}
+
+/// Formats a member function of a class, which forwards to the function it represents with the instance.
+///
+/// i.e. `bool reset(double value) { return ::Counter__reset(this, value); }`
+///
+/// ---
+///
+/// This function is used by the templating engine [tera](https://keats.github.io/tera/).
+fn format_member_function() -> impl tera::Function {
+    Box::new(move |args: &HashMap<String, serde_json::Value>| -> tera::Result<serde_json::Value> {
+        match args.get("member") {
+            Some(value) => match from_value::<MemberFunction>(value.clone()) {
+                Ok(member_function) => {
+                    let function = &member_function.function;
+                    let name = match member_function.kind {
+                        MemberFunctionKind::Body => CPP_CALL_OPERATOR,
+                        _ => &member_function.name,
+                    };
+
+                    // The first parameter receives the instance
+                    let parameters = function.parameters.iter().skip(1);
+                    let declared_parameters = parameters
+                        .clone()
+                        .map(|parameter| format!("{} {}", parameter.data_type, parameter.name))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let arguments = std::iter::once(CPP_THIS)
+                        .chain(parameters.map(|parameter| parameter.name.as_str()))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    let (return_type, return_keyword) = if function.return_type.is_empty() {
+                        (CPP_VOID, "")
+                    } else {
+                        (function.return_type.as_str(), "return ")
+                    };
+
+                    Ok(to_value(format!(
+                        "{return_type} {name}({declared_parameters}) {{ {return_keyword}::{}({arguments}); }}",
+                        sanitize_method_name(&function.name)
+                    ))
+                    .unwrap())
+                }
+                Err(_) => Err("Unable to format member function!".into()),
+            },
+            None => Err("Unable to format member function!".into()),
+        }
+    })
+}
+
+// --------------------- //
+// -- "C++" Constants -- //
+
+/// The constant value for the "c++" type: void
+const CPP_VOID: &str = "void";
+
+/// The constant value for the "c++" pointer to the instance of a member function
+const CPP_THIS: &str = "this";
+
+/// The constant value for the "c++" name of the member that calls an instance
+const CPP_CALL_OPERATOR: &str = "operator()";
+
+/// The keywords that need to be escaped when used as an identifier
+const CPP_KEYWORDS: &[&str] = &[
+    "alignas",
+    "alignof",
+    "and",
+    "and_eq",
+    "asm",
+    "auto",
+    "bitand",
+    "bitor",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "char8_t",
+    "char16_t",
+    "char32_t",
+    "class",
+    "compl",
+    "concept",
+    "const",
+    "consteval",
+    "constexpr",
+    "constinit",
+    "const_cast",
+    "continue",
+    "co_await",
+    "co_return",
+    "co_yield",
+    "decltype",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "dynamic_cast",
+    "else",
+    "enum",
+    "explicit",
+    "export",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "mutable",
+    "namespace",
+    "new",
+    "noexcept",
+    "not",
+    "not_eq",
+    "nullptr",
+    "operator",
+    "or",
+    "or_eq",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "reinterpret_cast",
+    "requires",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "static_assert",
+    "static_cast",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "thread_local",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typeid",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "wchar_t",
+    "while",
+    "xor",
+    "xor_eq",
+];
+
+// --------------------- //
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use plc::index::Index;
+use plc_ast::ast::CompilationUnit;
+use plc_diagnostics::diagnostics::Diagnostic;
+use tera::{from_value, to_value, Context, Tera};
+
+use crate::header_generator::{
+    declaration_helper::{prepare_mirrored_template_data, DeclarationHelper},
+    file_helper::HeaderFileInformation,
+    sanitize_method_name,
+    symbol_helper::SymbolHelper,
+    template_helper::{Function, TemplateData, TemplateHelper, TemplateType, Variable, VariableType},
+    GeneratedHeader,
+};
+
+pub struct GeneratedHeaderForPython {
+    pub file_information: HeaderFileInformation,
+    contents: String,
+    pub template_data: TemplateData,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Default for GeneratedHeaderForPython {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeneratedHeader for GeneratedHeaderForPython {
+    fn is_empty(&self) -> bool {
+        (self.file_information.directory.is_empty()
+            && self.file_information.path.is_empty()
+            && self.contents.is_empty())
+            || self.template_data.is_empty()
+    }
+
+    fn get_contents(&self) -> &str {
+        &self.contents
+    }
+
+    fn prepare_template_data(&mut self, compilation_unit: &CompilationUnit, index: &Index) {
+        let (template_data, diagnostics) = prepare_mirrored_template_data(self, compilation_unit, index);
+
+        self.template_data = template_data;
+        self.diagnostics = diagnostics;
+    }
+
+    fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn generate_headers(&mut self) -> Result<(), Diagnostic> {
+        // Configure tera
+        let mut tera = Tera::default();
+        let mut context = Context::new();
+
+        let template = self.get_template(TemplateType::Header);
+        tera.add_raw_template(&template.name, &template.content)
+            .expect("Unable to add the 'header' template to tera!");
+        tera.register_function(
+            "format_function_declaration",
+            format_function_declaration(self.get_variadic_symbol()),
+        );
+
+        // Unlike the classes of structs, which are declared before their fields, aliases have to be assigned in
+        // the order they depend on each other
+        let mut user_defined_types = self.template_data.user_defined_types.clone();
+        user_defined_types.aliases = order_aliases_by_dependency(&user_defined_types.aliases);
+
+        context.insert("global_variables", &self.template_data.global_variables);
+        context.insert("user_defined_types", &user_defined_types);
+        context.insert("functions", &self.template_data.functions);
+
+        // Set the outputs
+        self.contents = tera.render(&template.name, &context).unwrap();
+
+        Ok(())
+    }
+}
+
+impl DeclarationHelper for GeneratedHeaderForPython {
+    fn format_identifier(&self, name: &str) -> String {
+        format_identifier(name)
+    }
+
+    fn format_pointer_type(&self, inner_type: Option<&str>) -> String {
+        match inner_type {
+            Some(inner_type) => format!("{}({inner_type})", self.get_reference_symbol()),
+            None => String::from(PYTHON_VOID_POINTER),
+        }
+    }
+
+    fn format_array_type(&self, inner_type: &str, length: u32) -> String {
+        format!("{inner_type} * {length}")
+    }
+}
+
+impl GeneratedHeaderForPython {
+    pub const fn new() -> Self {
+        GeneratedHeaderForPython {
+            file_information: HeaderFileInformation::new(),
+            template_data: TemplateData::new(),
+            contents: String::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
+/// Returns the given name as a python identifier, escaping it if it is a keyword
+fn format_identifier(name: &str) -> String {
+    if PYTHON_KEYWORDS.contains(&name) {
+        return format!("{name}_");
+    }
+
+    name.to_string()
+}
+
+/// Returns the aliases in an order in which every alias is assigned after the aliases its type refers to
+fn order_aliases_by_dependency(aliases: &[Variable]) -> Vec<Variable> {
+    let mut remaining = aliases.to_vec();
+    let mut ordered: Vec<Variable> = Vec::new();
+
+    while !remaining.is_empty() {
+        let refers_to_remaining = |alias: &Variable| {
+            alias
+                .data_type
+                .split(|it: char| !(it.is_alphanumeric() || it == '_'))
+                .any(|name| remaining.iter().any(|other| other.name == name && other.name != alias.name))
+        };
+
+        match remaining.iter().position(|alias| !refers_to_remaining(alias)) {
+            Some(position) => ordered.push(remaining.remove(position)),
+            // Cyclic aliases cannot be ordered, keep them as they are declared
+            None => ordered.append(&mut remaining),
+        }
+    }
+
+    ordered
+}
+
+/// Formats a function as an entry of the functions the loader declares.
+///
+/// i.e. `("Counter__reset", "Counter.reset", [ctypes.POINTER(Counter_type)], ctypes.c_bool),`
+///
+/// ---
+///
+/// This function is used by the templating engine [tera](https://keats.github.io/tera/).
+fn format_function_declaration(variadic_symbol: String) -> impl tera::Function {
+    Box::new(move |args: &HashMap<String, serde_json::Value>| -> tera::Result<serde_json::Value> {
+        match args.get("function") {
+            Some(value) => match from_value::<Function>(value.clone()) {
+                Ok(function) => {
+                    let name = format_identifier(&sanitize_method_name(&function.name));
+
+                    // Additional arguments of a variadic function are passed without being declared
+                    let argument_types = function
+                        .parameters
+                        .iter()
+                        .filter(|parameter| !matches!(parameter.variable_type, VariableType::Variadic))
+                        .map(|parameter| parameter.data_type.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let is_variadic = function
+                        .parameters
+                        .iter()
+                        .any(|parameter| matches!(parameter.variable_type, VariableType::Variadic));
+
+                    let return_type =
+                        if function.return_type.is_empty() { PYTHON_NONE } else { &function.return_type };
+
+                    let comment = if is_variadic { format!("  # {variadic_symbol}") } else { String::new() };
+
+                    Ok(to_value(format!(
+                        "(\"{name}\", \"{}\", [{argument_types}], {return_type}),{comment}",
+                        function.name
+                    ))
+                    .unwrap())
+                }
+                Err(_) => Err("Unable to format function for declaration!".into()),
+            },
+            None => Err("Unable to format function for declaration!".into()),
+        }
+    })
+}
+
+// ------------------------ //
+// -- "Python" Constants -- //
+
+/// The constant value for the "python" type of an untyped pointer
+const PYTHON_VOID_POINTER: &str = "ctypes.c_void_p";
+
+/// The constant value for the "python" result of functions that do not return a value
+const PYTHON_NONE: &str = "None";
+
+/// The keywords that need to be escaped when used as an identifier
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def",
+    "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is",
+    "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+// ------------------------ //
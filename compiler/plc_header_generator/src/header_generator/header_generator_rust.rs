@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use plc::index::Index;
+use plc_ast::ast::CompilationUnit;
+use plc_diagnostics::diagnostics::Diagnostic;
+use tera::{from_value, to_value, Context, Tera};
+
+use crate::header_generator::{
+    declaration_helper::{prepare_mirrored_template_data, DeclarationHelper},
+    file_helper::HeaderFileInformation,
+    sanitize_method_name,
+    symbol_helper::SymbolHelper,
+    template_helper::{Function, TemplateData, TemplateHelper, TemplateType, VariableType},
+    type_helper::TypeHelper,
+    ExtendedTypeName, GeneratedHeader,
+};
+
+pub struct GeneratedHeaderForRust {
+    pub file_information: HeaderFileInformation,
+    contents: String,
+    pub template_data: TemplateData,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Default for GeneratedHeaderForRust {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeneratedHeader for GeneratedHeaderForRust {
+    fn is_empty(&self) -> bool {
+        (self.file_information.directory.is_empty()
+            && self.file_information.path.is_empty()
+            && self.contents.is_empty())
+            || self.template_data.is_empty()
+    }
+
+    fn get_contents(&self) -> &str {
+        &self.contents
+    }
+
+    fn prepare_template_data(&mut self, compilation_unit: &CompilationUnit, index: &Index) {
+        let (template_data, diagnostics) = prepare_mirrored_template_data(self, compilation_unit, index);
+
+        self.template_data = template_data;
+        self.diagnostics = diagnostics;
+    }
+
+    fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn generate_headers(&mut self) -> Result<(), Diagnostic> {
+        // Configure tera
+        let mut tera = Tera::default();
+        let mut context = Context::new();
+
+        let template = self.get_template(TemplateType::Header);
+        tera.add_raw_template(&template.name, &template.content)
+            .expect("Unable to add the 'header' template to tera!");
+        tera.register_function(
+            "format_function_declaration",
+            format_function_declaration(self.get_variadic_symbol()),
+        );
+
+        context.insert("global_variables", &self.template_data.global_variables);
+        context.insert("user_defined_types", &self.template_data.user_defined_types);
+        context.insert("functions", &self.template_data.functions);
+
+        // Set the outputs
+        self.contents = tera.render(&template.name, &context).unwrap();
+
+        Ok(())
+    }
+}
+
+impl DeclarationHelper for GeneratedHeaderForRust {
+    fn format_identifier(&self, name: &str) -> String {
+        format_identifier(name)
+    }
+
+    fn format_pointer_type(&self, inner_type: Option<&str>) -> String {
+        let inner_type = match inner_type {
+            Some(inner_type) => inner_type.to_string(),
+            None => self.get_type_name_for_type(&ExtendedTypeName::new(), &[]).get_type_name(),
+        };
+
+        format!("{}{inner_type}", self.get_reference_symbol())
+    }
+
+    fn format_array_type(&self, inner_type: &str, length: u32) -> String {
+        format!("[{inner_type}; {length}]")
+    }
+}
+
+impl GeneratedHeaderForRust {
+    pub const fn new() -> Self {
+        GeneratedHeaderForRust {
+            file_information: HeaderFileInformation::new(),
+            template_data: TemplateData::new(),
+            contents: String::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
+/// Returns the given name as a rust identifier, escaping it if it is a keyword
+fn format_identifier(name: &str) -> String {
+    match name {
+        // These keywords cannot be used as raw identifiers
+        "crate" | "self" | "Self" | "super" => format!("{name}_"),
+        _ if RUST_KEYWORDS.contains(&name) => format!("r#{name}"),
+        _ => name.to_string(),
+    }
+}
+
+/// Formats a function for its declaration within an `extern "C"` block.
+///
+/// Methods and actions are declared with a sanitized name and link to their qualified name.
+///
+/// ---
+///
+/// This function is used by the templating engine [tera](https://keats.github.io/tera/).
+fn format_function_declaration(variadic_symbol: String) -> impl tera::Function {
+    Box::new(move |args: &HashMap<String, serde_json::Value>| -> tera::Result<serde_json::Value> {
+        match args.get("function") {
+            Some(value) => match from_value::<Function>(value.clone()) {
+                Ok(function) => {
+                    let name = sanitize_method_name(&function.name);
+                    let link_name = if name != function.name {
+                        format!("#[link_name = \"{}\"]\n    ", function.name)
+                    } else {
+                        String::new()
+                    };
+
+                    let parameters = function
+                        .parameters
+                        .iter()
+                        .map(|parameter| match parameter.variable_type {
+                            VariableType::Variadic => variadic_symbol.to_string(),
+                            _ => format!("{}: {}", parameter.name, parameter.data_type),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    let return_type = if function.return_type.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" -> {}", function.return_type)
+                    };
+
+                    Ok(to_value(format!(
+                        "{link_name}pub fn {}({parameters}){return_type};",
+                        format_identifier(&name)
+                    ))
+                    .unwrap())
+                }
+                Err(_) => Err("Unable to format function for declaration!".into()),
+            },
+            None => Err("Unable to format function for declaration!".into()),
+        }
+    })
+}
+
+// ---------------------- //
+// -- "Rust" Constants -- //
+
+/// The keywords that need to be escaped when used as an identifier
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else",
+    "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro",
+    "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait",
+    "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+// ---------------------- //
@@ -1,4 +1,7 @@
 mod symbol_helper_c;
+mod symbol_helper_cpp;
+mod symbol_helper_python;
+mod symbol_helper_rust;
 
 pub trait SymbolHelper {
     /// Returns the reference symbol for the defined language
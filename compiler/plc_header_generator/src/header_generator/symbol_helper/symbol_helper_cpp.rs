@@ -0,0 +1,22 @@
+use crate::header_generator::{header_generator_cpp::GeneratedHeaderForCpp, symbol_helper::SymbolHelper};
+
+impl SymbolHelper for GeneratedHeaderForCpp {
+    fn get_reference_symbol(&self) -> String {
+        String::from(CPP_REFERENCE_SYMBOL)
+    }
+
+    fn get_variadic_symbol(&self) -> String {
+        String::from(CPP_VARIADIC_SYMBOL)
+    }
+}
+
+// --------------------- //
+// -- "C++" Constants -- //
+
+/// The constant value for the "c++" reference symbol
+const CPP_REFERENCE_SYMBOL: &str = "*";
+
+/// The constant value for the "c++" variadic symbol
+const CPP_VARIADIC_SYMBOL: &str = "...";
+
+// --------------------- //
@@ -0,0 +1,24 @@
+use crate::header_generator::{
+    header_generator_python::GeneratedHeaderForPython, symbol_helper::SymbolHelper,
+};
+
+impl SymbolHelper for GeneratedHeaderForPython {
+    fn get_reference_symbol(&self) -> String {
+        String::from(PYTHON_REFERENCE_SYMBOL)
+    }
+
+    fn get_variadic_symbol(&self) -> String {
+        String::from(PYTHON_VARIADIC_SYMBOL)
+    }
+}
+
+// ------------------------ //
+// -- "Python" Constants -- //
+
+/// The constant value for the "python" reference symbol
+const PYTHON_REFERENCE_SYMBOL: &str = "ctypes.POINTER";
+
+/// The constant value for the "python" variadic symbol
+const PYTHON_VARIADIC_SYMBOL: &str = "...";
+
+// ------------------------ //
@@ -0,0 +1,22 @@
+use crate::header_generator::{header_generator_rust::GeneratedHeaderForRust, symbol_helper::SymbolHelper};
+
+impl SymbolHelper for GeneratedHeaderForRust {
+    fn get_reference_symbol(&self) -> String {
+        String::from(RUST_REFERENCE_SYMBOL)
+    }
+
+    fn get_variadic_symbol(&self) -> String {
+        String::from(RUST_VARIADIC_SYMBOL)
+    }
+}
+
+// ---------------------- //
+// -- "Rust" Constants -- //
+
+/// The constant value for the "rust" reference symbol
+const RUST_REFERENCE_SYMBOL: &str = "*mut ";
+
+/// The constant value for the "rust" variadic symbol
+const RUST_VARIADIC_SYMBOL: &str = "...";
+
+// ---------------------- //
@@ -1,6 +1,60 @@
 use serde::{Deserialize, Serialize};
 
+/// Implements [TemplateHelper] for a header that keeps its [TemplateData] in a `template_data` field,
+/// rendering the given header template
+macro_rules! impl_template_helper {
+    ($header:ty, $template_content:expr, $template_name:literal) => {
+        impl $crate::header_generator::template_helper::TemplateHelper for $header {
+            fn get_template_data(&self) -> &$crate::header_generator::template_helper::TemplateData {
+                &self.template_data
+            }
+
+            fn set_template_data(
+                &mut self,
+                template_data: $crate::header_generator::template_helper::TemplateData,
+            ) {
+                self.template_data = template_data;
+            }
+
+            fn get_mutable_template_data_user_defined_types(
+                &mut self,
+            ) -> &mut $crate::header_generator::template_helper::UserDefinedTypes {
+                &mut self.template_data.user_defined_types
+            }
+
+            fn get_mutable_template_data_global_variables(
+                &mut self,
+            ) -> &mut Vec<$crate::header_generator::template_helper::Variable> {
+                &mut self.template_data.global_variables
+            }
+
+            fn get_mutable_template_data_functions(
+                &mut self,
+            ) -> &mut Vec<$crate::header_generator::template_helper::Function> {
+                &mut self.template_data.functions
+            }
+
+            fn get_template(
+                &self,
+                template_type: $crate::header_generator::template_helper::TemplateType,
+            ) -> $crate::header_generator::template_helper::Template {
+                match template_type {
+                    $crate::header_generator::template_helper::TemplateType::Header => {
+                        $crate::header_generator::template_helper::Template {
+                            content: $template_content.to_string(),
+                            name: String::from($template_name),
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
 mod template_helper_c;
+mod template_helper_cpp;
+mod template_helper_python;
+mod template_helper_rust;
 
 pub trait TemplateHelper {
     /// Returns the template data of the header file, which is an object representation of the data that will be written to the header template
@@ -89,6 +143,15 @@ pub struct UserType {
     pub name: String,
     pub variables: Vec<Variable>,
     pub data_type: Option<String>,
+    /// The size in bytes of the type as laid out in memory, if the language asserts it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+    /// The instance type of the function block or class this type extends, its first variable holds the base
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// The functions operating on an instance of this type, if the language declares them as its members
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub member_functions: Vec<MemberFunction>,
 }
 
 /// A representation of a variable used by the template data
@@ -116,6 +179,28 @@ pub enum VariableType {
     MultidimensionalArray(Vec<i128>),
 }
 
+/// A representation of a function operating on an instance used by the template data
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MemberFunction {
+    /// The name of the member
+    pub name: String,
+    pub kind: MemberFunctionKind,
+    /// The function the member forwards to, its first parameter is the instance
+    pub function: Function,
+}
+
+/// The kind of a member function used by the template data
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MemberFunctionKind {
+    /// The body of a function block or program
+    Body,
+    Method,
+    /// The getter of a property
+    Getter,
+    /// The setter of a property
+    Setter,
+}
+
 /// A representation of a function used by the template data
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Function {
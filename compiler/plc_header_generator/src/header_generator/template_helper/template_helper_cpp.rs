@@ -0,0 +1,7 @@
+use crate::header_generator::header_generator_cpp::GeneratedHeaderForCpp;
+
+impl_template_helper!(
+    GeneratedHeaderForCpp,
+    include_str!("templates/cpp/header_template.hpp"),
+    "header_template.hpp"
+);
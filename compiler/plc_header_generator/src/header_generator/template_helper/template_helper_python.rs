@@ -0,0 +1,7 @@
+use crate::header_generator::header_generator_python::GeneratedHeaderForPython;
+
+impl_template_helper!(
+    GeneratedHeaderForPython,
+    include_str!("templates/python/header_template.py"),
+    "header_template.py"
+);
@@ -0,0 +1,7 @@
+use crate::header_generator::header_generator_rust::GeneratedHeaderForRust;
+
+impl_template_helper!(
+    GeneratedHeaderForRust,
+    include_str!("templates/rust/header_template.rs"),
+    "header_template.rs"
+);
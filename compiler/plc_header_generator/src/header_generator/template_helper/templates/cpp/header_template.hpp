@@ -0,0 +1,87 @@
+// ---------------------------------------------------- //
+// This file is auto-generated                          //
+// Manual changes made to this file will be overwritten //
+// ---------------------------------------------------- //
+
+#ifndef {{ file_name_caps }}
+#define {{ file_name_caps }}
+
+#include <array>
+#include <cstdint>
+
+// Binds a declaration to the symbol of its qualified name, e.g. `Motor.start`
+#define PLC_SYMBOL_STRING(prefix) #prefix
+#define PLC_SYMBOL_PREFIX(prefix) PLC_SYMBOL_STRING(prefix)
+#define PLC_SYMBOL(name) __asm__(PLC_SYMBOL_PREFIX(__USER_LABEL_PREFIX__) name)
+{% raw %}
+{% endraw %}
+
+{#- Forward Declarations -#}
+{% for struct in user_defined_types.structs -%}
+struct {{ struct.name }};
+{% if loop.last == true -%}
+{% raw %}
+{% endraw %}
+{%- endif -%}
+{%- endfor %}
+
+{#- Enums -#}
+{% for enum in user_defined_types.enums -%}
+enum class {{ enum.name }} : {{ enum.data_type }} {
+{% for variable in enum.variables -%}
+{% raw %}    {% endraw %}{{ variable.name }} = {{ variable.variable_type.Declaration }},
+{% endfor -%}
+};
+{% if enum.size is defined -%}
+static_assert(sizeof({{ enum.name }}) == {{ enum.size }}, "{{ enum.name }} does not match the compiled layout");
+{% endif -%}
+{% raw %}
+{% endraw %}
+{%- endfor %}
+
+{#- Aliases -#}
+{% for alias in user_defined_types.aliases -%}
+using {{ alias.name }} = {{ alias.data_type }};
+{% if loop.last == true -%}
+{% raw %}
+{% endraw %}
+{%- endif -%}
+{%- endfor %}
+
+{#- Global Variables and Functions -#}
+{% if global_variables or functions -%}
+extern "C" {
+{% for global_variable in global_variables -%}
+{% raw %}    {% endraw %}extern {{ global_variable.data_type }} {{ global_variable.name }};
+{% endfor -%}
+{% for function in functions -%}
+{% raw %}    {% endraw %}{{ format_function_declaration(function=function) }}
+{% endfor -%}
+}
+{% raw %}
+{% endraw %}
+{%- endif -%}
+
+{#- Classes -#}
+{% for struct in user_defined_types.structs -%}
+{% set member_functions = struct.member_functions | default(value=[]) -%}
+struct {{ struct.name }}{% if struct.base %} : {{ struct.base }}{% endif %} {
+{% for variable in struct.variables -%}
+{% raw %}    {% endraw %}{% if struct.base and loop.first %}alignas(alignof({{ struct.base }})) {% endif %}{{ variable.data_type }} {{ variable.name }};
+{% endfor -%}
+{% if member_functions and struct.variables -%}
+{% raw %}
+{% endraw %}
+{%- endif -%}
+{% for member_function in member_functions -%}
+{% raw %}    {% endraw %}{{ format_member_function(member=member_function) }}
+{% endfor -%}
+};
+{% if struct.size is defined and struct.size > 0 -%}
+static_assert(sizeof({{ struct.name }}) == {{ struct.size }}, "{{ struct.name }} does not match the compiled layout");
+{% endif -%}
+{% raw %}
+{% endraw %}
+{%- endfor -%}
+
+#endif /* !{{ file_name_caps }} */
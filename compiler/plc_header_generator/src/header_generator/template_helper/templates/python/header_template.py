@@ -0,0 +1,82 @@
+# ---------------------------------------------------- #
+# This file is auto-generated                          #
+# Manual changes made to this file will be overwritten #
+# ---------------------------------------------------- #
+
+import ctypes
+{% raw %}
+{% endraw %}
+
+{#- Struct Classes -#}
+{% for struct in user_defined_types.structs -%}
+class {{ struct.name }}(ctypes.Structure):
+    pass
+{% raw %}
+{% endraw %}
+{%- endfor %}
+
+{#- Enums -#}
+{% for enum in user_defined_types.enums -%}
+{{ enum.name }} = {{ enum.data_type }}
+{% for variable in enum.variables -%}
+{{ enum.name }}_{{ variable.name }} = {{ variable.variable_type.Declaration }}
+{% endfor -%}
+{% raw %}
+{% endraw %}
+{%- endfor %}
+
+{#- Aliases -#}
+{% for alias in user_defined_types.aliases -%}
+{{ alias.name }} = {{ alias.data_type }}
+{% if loop.last == true -%}
+{% raw %}
+{% endraw %}
+{%- endif -%}
+{%- endfor %}
+
+{#- Struct Fields -#}
+{% for struct in user_defined_types.structs -%}
+{{ struct.name }}._fields_ = [
+{% for variable in struct.variables -%}
+{% raw %}    {% endraw %}("{{ variable.name }}", {{ variable.data_type }}),
+{% endfor -%}
+]
+{% if struct.size is defined -%}
+assert ctypes.sizeof({{ struct.name }}) == {{ struct.size }}
+{% endif -%}
+{% raw %}
+{% endraw %}
+{%- endfor %}
+
+{#- Functions -#}
+# (name, symbol, argtypes, restype)
+FUNCTIONS = [
+{% for function in functions -%}
+{% raw %}    {% endraw %}{{ format_function_declaration(function=function) }}
+{% endfor -%}
+]
+
+{#- Global Variables -#}
+{% raw %}
+{% endraw %}
+GLOBAL_VARIABLES = {
+{% for global_variable in global_variables -%}
+{% raw %}    {% endraw %}"{{ global_variable.name }}": {{ global_variable.data_type }},
+{% endfor -%}
+}
+
+
+def load(path):
+    """Loads the compiled library at the given path and declares the prototypes of its functions."""
+    library = ctypes.CDLL(path)
+    for name, symbol, argtypes, restype in FUNCTIONS:
+        function = library[symbol]
+        function.argtypes = argtypes
+        function.restype = restype
+        setattr(library, name, function)
+    return library
+
+
+def global_variable(library, name):
+    """Returns the global variable with the given name of a loaded library."""
+    return GLOBAL_VARIABLES[name].in_dll(library, name)
@@ -0,0 +1,60 @@
+// ---------------------------------------------------- //
+// This file is auto-generated                          //
+// Manual changes made to this file will be overwritten //
+// ---------------------------------------------------- //
+
+#![allow(non_camel_case_types, non_snake_case, non_upper_case_globals, dead_code)]
+{% raw %}
+{% endraw %}
+
+{#- Aliases -#}
+{% for alias in user_defined_types.aliases -%}
+pub type {{ alias.name }} = {{ alias.data_type }};
+{% if loop.last == true -%}
+{% raw %}
+{% endraw %}
+{%- endif -%}
+{%- endfor %}
+
+{#- Enums -#}
+{% for enum in user_defined_types.enums -%}
+#[repr({{ enum.data_type }})]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum {{ enum.name }} {
+{% for variable in enum.variables -%}
+{% raw %}    {% endraw %}{{ variable.name }} = {{ variable.variable_type.Declaration }},
+{% endfor -%}
+}
+{% if enum.size is defined -%}
+const _: () = assert!(core::mem::size_of::<{{ enum.name }}>() == {{ enum.size }});
+{% endif -%}
+{% raw %}
+{% endraw %}
+{%- endfor %}
+
+{#- Structs -#}
+{% for struct in user_defined_types.structs -%}
+#[repr(C)]
+pub struct {{ struct.name }} {
+{% for variable in struct.variables -%}
+{% raw %}    {% endraw %}pub {{ variable.name }}: {{ variable.data_type }},
+{% endfor -%}
+}
+{% if struct.size is defined -%}
+const _: () = assert!(core::mem::size_of::<{{ struct.name }}>() == {{ struct.size }});
+{% endif -%}
+{% raw %}
+{% endraw %}
+{%- endfor %}
+
+{#- Global Variables and Functions -#}
+{% if global_variables or functions -%}
+extern "C" {
+{% for global_variable in global_variables -%}
+{% raw %}    {% endraw %}pub static mut {{ global_variable.name }}: {{ global_variable.data_type }};
+{% endfor -%}
+{% for function in functions -%}
+{% raw %}    {% endraw %}{{ format_function_declaration(function=function) }}
+{% endfor -%}
+}
+{% endif -%}
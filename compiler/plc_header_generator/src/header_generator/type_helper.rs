@@ -1,7 +1,11 @@
 use crate::header_generator::ExtendedTypeName;
-use plc::typesystem::{DataType, TypeSize};
+use plc::typesystem::{DataType, DataTypeInformation, StringEncoding, TypeSize, BOOL_TYPE, REAL_SIZE};
+use plc_ast::ast::TypeNature;
 
 mod type_helper_c;
+mod type_helper_cpp;
+mod type_helper_python;
+mod type_helper_rust;
 
 pub struct TypeInformation {
     pub name: String,
@@ -53,6 +57,96 @@ pub trait TypeHelper {
     fn get_type_name_for_string(&self, is_wide: &bool) -> String;
 }
 
+/// The names a language gives to the builtin types, used by [get_type_information] to spell a type
+pub struct TypeNames {
+    pub void: &'static str,
+    pub bool: &'static str,
+    /// Dates and durations are stored as signed 64 bit values
+    pub time: &'static str,
+    pub float: &'static str,
+    pub double: &'static str,
+    pub char: &'static str,
+    pub wide_char: &'static str,
+    /// Returns the name of the integer type with the given signedness and size in bits
+    pub integer: fn(signed: bool, size: u32) -> String,
+}
+
+impl TypeNames {
+    /// Returns the name of the character type of a (wide) string
+    pub fn get_string_type_name(&self, is_wide: bool) -> String {
+        String::from(if is_wide { self.wide_char } else { self.char })
+    }
+}
+
+/// Determines the type information of the given type, spelled with the given type names of a language.
+pub fn get_type_information(
+    names: &TypeNames,
+    extended_type_name: &ExtendedTypeName,
+    builtin_types: &[DataType],
+) -> TypeInformation {
+    let attribute = |is_user_generated: bool, array_size_option: Option<i128>| {
+        determine_type_attribute(
+            extended_type_name.is_variadic,
+            extended_type_name.is_sized_variadic,
+            is_user_generated,
+            array_size_option,
+        )
+    };
+
+    if extended_type_name.type_name.is_empty() {
+        return TypeInformation { name: String::from(names.void), attribute: attribute(false, None) };
+    }
+
+    let Some(builtin_type) =
+        builtin_types.iter().find(|builtin_type| builtin_type.name == extended_type_name.type_name)
+    else {
+        // This is a user-generated type
+        return TypeInformation {
+            name: extended_type_name.type_name.to_string(),
+            attribute: attribute(true, None),
+        };
+    };
+
+    match &builtin_type.information {
+        DataTypeInformation::Integer { signed, size, .. } => {
+            // Booleans have their own type
+            if extended_type_name.type_name == BOOL_TYPE {
+                return TypeInformation { name: String::from(names.bool), attribute: attribute(false, None) };
+            }
+
+            if builtin_type.nature == TypeNature::Date || builtin_type.nature == TypeNature::Duration {
+                return TypeInformation { name: String::from(names.time), attribute: attribute(false, None) };
+            }
+
+            TypeInformation { name: (names.integer)(*signed, *size), attribute: attribute(false, None) }
+        }
+        DataTypeInformation::Float { size, .. } => {
+            let name = if *size == REAL_SIZE { names.float } else { names.double };
+            TypeInformation { name: String::from(name), attribute: attribute(false, None) }
+        }
+        DataTypeInformation::Alias { referenced_type, .. } => {
+            let referenced_data_type_info = ExtendedTypeName {
+                type_name: referenced_type.to_string(),
+                is_variadic: false,
+                is_sized_variadic: false,
+            };
+
+            get_type_information(names, &referenced_data_type_info, builtin_types)
+        }
+        DataTypeInformation::String { encoding, size, .. } => TypeInformation {
+            name: names.get_string_type_name(*encoding == StringEncoding::Utf16),
+            attribute: attribute(false, Some(extract_string_size(size))),
+        },
+        DataTypeInformation::Void => {
+            TypeInformation { name: String::from(names.void), attribute: attribute(false, None) }
+        }
+        _ => {
+            log::debug!("{} this type is not yet supported!", extended_type_name.type_name);
+            TypeInformation::new()
+        }
+    }
+}
+
 fn determine_type_attribute(
     is_variadic: bool,
     is_sized_variadic: bool,
@@ -0,0 +1,37 @@
+use crate::header_generator::{
+    header_generator_cpp::GeneratedHeaderForCpp,
+    type_helper::{get_type_information, TypeHelper, TypeInformation, TypeNames},
+    ExtendedTypeName,
+};
+use plc::typesystem::DataType;
+
+impl TypeHelper for GeneratedHeaderForCpp {
+    fn get_type_name_for_type(
+        &self,
+        extended_type_name: &ExtendedTypeName,
+        builtin_types: &[DataType],
+    ) -> TypeInformation {
+        get_type_information(&CPP_TYPE_NAMES, extended_type_name, builtin_types)
+    }
+
+    fn get_type_name_for_string(&self, is_wide: &bool) -> String {
+        CPP_TYPE_NAMES.get_string_type_name(*is_wide)
+    }
+}
+
+// --------------------- //
+// -- "C++" Constants -- //
+
+/// The names of the "c++" types
+const CPP_TYPE_NAMES: TypeNames = TypeNames {
+    void: "void",
+    bool: "bool",
+    time: "std::int64_t",
+    float: "float",
+    double: "double",
+    char: "char",
+    wide_char: "char16_t",
+    integer: |signed, size| format!("std::{}int{size}_t", if signed { "" } else { "u" }),
+};
+
+// --------------------- //
@@ -0,0 +1,38 @@
+use crate::header_generator::{
+    header_generator_python::GeneratedHeaderForPython,
+    type_helper::{get_type_information, TypeHelper, TypeInformation, TypeNames},
+    ExtendedTypeName,
+};
+use plc::typesystem::DataType;
+
+impl TypeHelper for GeneratedHeaderForPython {
+    fn get_type_name_for_type(
+        &self,
+        extended_type_name: &ExtendedTypeName,
+        builtin_types: &[DataType],
+    ) -> TypeInformation {
+        get_type_information(&PYTHON_TYPE_NAMES, extended_type_name, builtin_types)
+    }
+
+    fn get_type_name_for_string(&self, is_wide: &bool) -> String {
+        PYTHON_TYPE_NAMES.get_string_type_name(*is_wide)
+    }
+}
+
+// ------------------------ //
+// -- "Python" Constants -- //
+
+/// The names of the "python" types, a wide character is not a `ctypes.c_wchar` as that is not 16 bit on every
+/// platform
+const PYTHON_TYPE_NAMES: TypeNames = TypeNames {
+    void: "None",
+    bool: "ctypes.c_bool",
+    time: "ctypes.c_int64",
+    float: "ctypes.c_float",
+    double: "ctypes.c_double",
+    char: "ctypes.c_char",
+    wide_char: "ctypes.c_uint16",
+    integer: |signed, size| format!("ctypes.c_{}int{size}", if signed { "" } else { "u" }),
+};
+
+// ------------------------ //
@@ -0,0 +1,37 @@
+use crate::header_generator::{
+    header_generator_rust::GeneratedHeaderForRust,
+    type_helper::{get_type_information, TypeHelper, TypeInformation, TypeNames},
+    ExtendedTypeName,
+};
+use plc::typesystem::DataType;
+
+impl TypeHelper for GeneratedHeaderForRust {
+    fn get_type_name_for_type(
+        &self,
+        extended_type_name: &ExtendedTypeName,
+        builtin_types: &[DataType],
+    ) -> TypeInformation {
+        get_type_information(&RUST_TYPE_NAMES, extended_type_name, builtin_types)
+    }
+
+    fn get_type_name_for_string(&self, is_wide: &bool) -> String {
+        RUST_TYPE_NAMES.get_string_type_name(*is_wide)
+    }
+}
+
+// ---------------------- //
+// -- "Rust" Constants -- //
+
+/// The names of the "rust" types
+const RUST_TYPE_NAMES: TypeNames = TypeNames {
+    void: "core::ffi::c_void",
+    bool: "bool",
+    time: "i64",
+    float: "f32",
+    double: "f64",
+    char: "u8",
+    wide_char: "u16",
+    integer: |signed, size| format!("{}{size}", if signed { "i" } else { "u" }),
+};
+
+// ---------------------- //
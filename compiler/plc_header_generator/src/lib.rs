@@ -8,6 +8,8 @@ pub enum GenerateLanguage {
     #[default]
     C,
     Rust,
+    Python,
+    Cpp,
 }
 
 #[derive(Debug)]
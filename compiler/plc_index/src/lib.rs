@@ -28,6 +28,7 @@ pub struct GlobalContext {
     error_fmt: ErrorFormat,
     // TODO: Move to a dedicated CompilerOptions struct — this is a compile flag, not global context.
     generate_external_constructors: bool,
+    generate_retain_persistence: bool,
 }
 
 // XXX: Temporary
@@ -90,6 +91,15 @@ impl GlobalContext {
         self.generate_external_constructors
     }
 
+    pub fn generate_retain_persistence(mut self) -> Self {
+        self.generate_retain_persistence = true;
+        self
+    }
+
+    pub fn should_generate_retain_persistence(&self) -> bool {
+        self.generate_retain_persistence
+    }
+
     /// Returns some [`SourceCode`] based on the given key
     pub fn get(&self, key: &str) -> Option<&SourceCode> {
         self.sources.get(key)
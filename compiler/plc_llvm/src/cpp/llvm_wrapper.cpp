@@ -17,4 +17,11 @@ extern "C" {
         TargetOptions* options = &targetMachine->Options;
         options->UseInitArray = useInitArray ? 1 : 0;
     }
+
+    // Set the file the stack frame size of each generated function is written to
+    void setStackUsageOutput(LLVMTargetMachineRef tm, const char* path) {
+        auto* targetMachine = unwrap(tm);
+        TargetOptions* options = &targetMachine->Options;
+        options->StackUsageOutput = path;
+    }
 }
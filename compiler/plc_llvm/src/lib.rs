@@ -1,5 +1,7 @@
 //! This crate provides Rust bindings for LLVM Target Machine functionalities.
 
+use std::ffi::CString;
+
 use inkwell::llvm_sys::prelude::LLVMBool;
 use inkwell::targets::TargetMachine;
 
@@ -10,11 +12,13 @@ mod ffi {
     #[link(name = "llvm_wrapper")]
     unsafe extern "C" {
         pub fn setUseInitArray(tm: LLVMTargetMachineRef, use_init_array: LLVMBool);
+        pub fn setStackUsageOutput(tm: LLVMTargetMachineRef, path: *const std::ffi::c_char);
     }
 }
 
 pub trait TargetMachineExt {
     fn use_init_array(&mut self, use_init_array: bool);
+    fn set_stack_usage_output(&mut self, path: &str);
 }
 
 impl TargetMachineExt for TargetMachine {
@@ -30,4 +34,17 @@ impl TargetMachineExt for TargetMachine {
             ffi::setUseInitArray(tm, if use_init_array { 1 } else { 0 } as LLVMBool);
         }
     }
+
+    /// Set the file the stack usage of every function is written to when emitting code.
+    ///
+    /// Each line holds the location and name of a function, the size of its stack frame
+    /// in bytes and whether the frame is `static` or `dynamic`, the same format as GCC's
+    /// `-fstack-usage`. The file is appended to.
+    fn set_stack_usage_output(&mut self, path: &str) {
+        let tm = self.as_mut_ptr();
+        let path = CString::new(path).expect("Path must not contain a nul byte");
+        unsafe {
+            ffi::setStackUsageOutput(tm, path.as_ptr());
+        }
+    }
 }
@@ -118,6 +118,11 @@
 //!
 //! Compiler-generated names such as `__ran_once_N` and `__is_incrementing_N` are unique per desugared loop.
 //! They are ordinary AST allocations used only to encode loop semantics explicitly for later stages.
+//!
+//! ## Original loops
+//!
+//! The lowered `WHILE TRUE` loop of a `WHILE` or `FOR` loop keeps the original loop header (without its body)
+//! as its [`MetaData`], so the validation can still check the condition and bounds the user wrote.
 
 use std::{
     rc::Rc,
@@ -125,8 +130,8 @@ use std::{
 };
 
 use plc_ast::{
-    ast::{AstFactory, AstNode, CompilationUnit, Operator},
-    control_statements::{ForLoopStatement, LoopStatement},
+    ast::{AstFactory, AstNode, AstStatement, CompilationUnit, MetaData, Operator},
+    control_statements::{AstControlStatement, ForLoopStatement, LoopStatement},
     mut_visitor::{AstVisitorMut, WalkerMut},
     provider::IdProvider,
 };
@@ -138,6 +143,9 @@ pub struct LoopDesugarer {
 
 struct WhileDesugarer {
     ids: IdProvider,
+
+    /// The header of the last desugared loop
+    original: Option<MetaData>,
 }
 
 struct RepeatDesugarer {
@@ -164,7 +172,7 @@ impl LoopDesugarer {
     pub fn desugar(&self, units: &mut [CompilationUnit]) {
         let counter = Rc::new(AtomicUsize::new(0));
 
-        let mut whiled = WhileDesugarer { ids: self.ids.clone() };
+        let mut whiled = WhileDesugarer { ids: self.ids.clone(), original: None };
         let mut repeatd =
             RepeatDesugarer { ids: self.ids.clone(), counter: counter.clone(), replacement: None };
         let mut ford = ForDesugarer { ids: self.ids.clone(), counter, replacement: None };
@@ -178,10 +186,34 @@ impl LoopDesugarer {
 }
 
 impl AstVisitorMut for WhileDesugarer {
+    fn visit_control_statement(&mut self, node: &mut AstNode) {
+        let AstStatement::ControlStatement(stmt) = &mut node.stmt else {
+            unreachable!("Must be a control statement")
+        };
+
+        match stmt {
+            AstControlStatement::WhileLoop(stmt) => self.visit_while_loop_statement(stmt),
+            _ => stmt.walk(self),
+        }
+
+        if let Some(original) = self.original.take() {
+            node.metadata = Some(original);
+        }
+    }
+
     fn visit_while_loop_statement(&mut self, stmt: &mut LoopStatement) {
         // First, visit the body itself to desugar nested loops (if any)
         self.visit_statement_list(&mut stmt.body);
 
+        // Keep the original header for the validation
+        let header = LoopStatement {
+            condition: stmt.condition.clone(),
+            body: Vec::new(),
+            end_location: stmt.end_location.clone(),
+        };
+        let location = stmt.condition.get_location();
+        self.original = Some(AstFactory::create_while_statement(header, location, self.ids.next_id()).into());
+
         // Preserve the original condition location so stepping can still land on the user-written
         // `WHILE <cond>` header, but make the canonical loop scaffolding internal.
         let prev_cond_loc = stmt.condition.location.clone();
@@ -293,6 +325,18 @@ impl AstVisitorMut for ForDesugarer {
         // First, visit the body itself to desugar nested loops (if any)
         self.visit_statement_list(&mut stmt.body);
 
+        // Keep the original header for the validation
+        let header = ForLoopStatement {
+            counter: stmt.counter.clone(),
+            start: stmt.start.clone(),
+            end: stmt.end.clone(),
+            by_step: stmt.by_step.clone(),
+            body: Vec::new(),
+            end_location: stmt.end_location.clone(),
+        };
+        let location = stmt.counter.get_location().span(&stmt.end.get_location());
+        let original = AstFactory::create_for_loop(header, location, self.ids.next_id());
+
         // Take the for loop components and original body.
         let counter = *std::mem::take(&mut stmt.counter);
         let start = *std::mem::take(&mut stmt.start);
@@ -384,7 +428,7 @@ impl AstVisitorMut for ForDesugarer {
             is_incrementing_alloca,
             AstFactory::create_assignment(counter, start, self.ids.next_id()),
             is_incrementing_assignment,
-            helper::create_while_true_loop(&mut self.ids, body),
+            helper::create_while_true_loop(&mut self.ids, body).with_metadata(original.into()),
         ]);
     }
 }
@@ -1358,4 +1402,47 @@ mod tests {
             ");
         }
     }
+
+    #[test]
+    fn desugared_loops_keep_their_original_header() {
+        use plc_ast::{ast::AstStatement, control_statements::AstControlStatement};
+
+        let source = r#"
+            FUNCTION main
+                VAR
+                    i : DINT;
+                END_VAR
+
+                WHILE i < 10 DO
+                    i := i + 1;
+                END_WHILE
+                FOR i := 0 TO 10 BY 2 DO
+                END_FOR
+            END_FUNCTION
+        "#;
+
+        let (_, project) = parse_and_annotate("unit-test", vec![source.into()]).unwrap();
+        let unit = project.units[0].get_unit();
+        let statements = &unit.implementations.iter().find(|it| &it.name == "main").unwrap().statements;
+        let originals =
+            statements.iter().filter_map(|it| it.metadata.as_ref()).map(|it| it.get_inner().get_stmt());
+
+        let headers = originals
+            .map(|it| match it {
+                AstStatement::ControlStatement(AstControlStatement::WhileLoop(stmt)) => {
+                    format!("WHILE {}", stmt.condition.as_string())
+                }
+                AstStatement::ControlStatement(AstControlStatement::ForLoop(stmt)) => format!(
+                    "FOR {} := {} TO {} BY {}",
+                    stmt.counter.as_string(),
+                    stmt.start.as_string(),
+                    stmt.end.as_string(),
+                    stmt.by_step.as_ref().unwrap().as_string()
+                ),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(headers, ["WHILE i < 10", "FOR i := 0 TO 10 BY 2"]);
+    }
 }
@@ -365,6 +365,7 @@ impl AstVisitorMut for ReferenceToReturnLowerer {
                     kind: plc_ast::ast::VariableBlockType::Input(ArgumentProperty::ByVal),
                     constant: false,
                     retain: false,
+                    persistent: false,
                     linkage: plc_ast::ast::LinkageType::Internal,
                     location,
                     access: AccessModifier::Public,
@@ -443,6 +444,7 @@ impl AstVisitorMut for ReferenceToReturnLowerer {
                         kind: plc_ast::ast::VariableBlockType::Temp,
                         constant: false,
                         retain: false,
+                        persistent: false,
                         linkage: plc_ast::ast::LinkageType::Internal,
                         location: return_type_for_call_location.clone(),
                         access: AccessModifier::Private,
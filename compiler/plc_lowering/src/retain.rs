@@ -35,30 +35,70 @@
 //! entire FB instance receives the `.retain` section treatment when it is instantiated
 //! at the global or program level, handled by the transitive `should_retain()` check
 //! during code generation.
+//!
+//! ## Persistent variables
+//! Variables declared as `PERSISTENT` survive a download and a reset origin and are placed into
+//! the `.persistent` section, `RETAIN PERSISTENT` variables survive a power cycle as well and are
+//! placed into the `.retain_persistent` section. They follow the same three cases: program
+//! variables are moved to a global block with the same modifiers, and a variable whose type
+//! transitively contains persistent members is moved to a block of the section of its members.
+//! An FB instance mixing members of different sections cannot be placed without changing how
+//! long some of them are kept, which the validation reports with `E172`.
+//!
+//! ## Saving and restoring
+//! Since the layout of the `.retain` section changes with every firmware update, the values of all
+//! retain variables can be saved into a buffer with `__retain_save(buf)` and restored into an
+//! updated firmware with `__retain_restore(buf, length)`. Both are generated with
+//! `--generate-retain-persistence`, see [`persistence`].
 
+use plc::index::RetainSection;
 use plc_ast::{
     ast::{
-        AccessModifier, AstFactory, AutoDerefType, CompilationUnit, DataType, DataTypeDeclaration, Variable,
+        AccessModifier, AstFactory, AutoDerefType, CompilationUnit, DataType, DataTypeDeclaration,
+        LinkageType, Variable,
     },
     mut_visitor::{AstVisitorMut, WalkerMut},
     provider::IdProvider,
 };
+use plc_diagnostics::diagnostics::Diagnostic;
 use plc_source::source_location::SourceLocation;
+use plc_util::convention::retain_variable_name;
+
+use persistence::{generate_persistence, RetainRoot};
+
+mod persistence;
 
 pub struct RetainParticipant {
     pub ids: IdProvider,
+    /// generate `__retain_save` and `__retain_restore`, see [`persistence`]
+    pub generate_persistence: bool,
+    /// names of retain values whose hashes collide
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl RetainParticipant {
-    pub fn new(ids: IdProvider) -> Self {
-        Self { ids }
+    pub fn new(ids: IdProvider, generate_persistence: bool) -> Self {
+        Self { ids, generate_persistence, diagnostics: vec![] }
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
     }
 
-    pub fn lower_retain(&mut self, units: &mut [CompilationUnit], index: plc::index::Index) {
-        let mut lowerer = RetainLowerer { ids: self.ids.clone(), index, context: Context::default() };
-        for unit in units {
+    pub fn lower_retain(&mut self, units: &mut Vec<CompilationUnit>, index: plc::index::Index) {
+        let mut lowerer =
+            RetainLowerer { ids: self.ids.clone(), index, context: Context::default(), roots: vec![] };
+        for unit in units.iter_mut() {
             lowerer.visit_compilation_unit(unit);
         }
+        if !self.generate_persistence {
+            return;
+        }
+        match generate_persistence(&lowerer.roots, &lowerer.index, self.ids.clone()) {
+            Ok(Some(unit)) => units.push(unit),
+            Ok(None) => {}
+            Err(diagnostics) => self.diagnostics.extend(diagnostics),
+        }
     }
 }
 
@@ -66,39 +106,28 @@ struct RetainLowerer {
     ids: IdProvider,
     index: plc::index::Index,
     context: Context,
+    /// the variables placed into the `.retain` section, their values are saved and restored
+    roots: Vec<RetainRoot>,
 }
 
 #[derive(Debug, Default)]
 struct Context {
     container_name: Option<String>,
     in_program: bool,
-    retain_variables: Vec<Variable>,
+    /// the variables moved to a global block of the given section
+    retain_variables: Vec<(RetainSection, Variable)>,
 }
 
 impl AstVisitorMut for RetainLowerer {
     fn visit_compilation_unit(&mut self, unit: &mut CompilationUnit) {
         unit.walk(self);
         // After visiting the compilation unit, add all retain variables to the global vars
-        if !self.context.retain_variables.is_empty() {
-            // Find an existing retain global variable block or create a new one if it doesn't exist
-            unit.global_vars
-                .iter_mut()
-                .find(|block| block.retain)
-                .map(|block| {
-                    block.variables.append(&mut self.context.retain_variables);
-                })
-                .unwrap_or_else(|| {
-                    let retain_block = plc_ast::ast::VariableBlock {
-                        variables: self.context.retain_variables.drain(..).collect(),
-                        kind: plc_ast::ast::VariableBlockType::Global,
-                        constant: false,
-                        retain: true,
-                        linkage: plc_ast::ast::LinkageType::Internal,
-                        location: SourceLocation::internal(),
-                        access: AccessModifier::Public,
-                    };
-                    unit.global_vars.push(retain_block);
-                });
+        let mut retain_variables = std::mem::take(&mut self.context.retain_variables);
+        for section in [RetainSection::Retain, RetainSection::Persistent, RetainSection::RetainPersistent] {
+            let (variables, rest): (Vec<_>, Vec<_>) =
+                retain_variables.into_iter().partition(|(it, _)| *it == section);
+            retain_variables = rest;
+            append_to_retain_block(unit, variables.into_iter().map(|(_, it)| it).collect(), section);
         }
     }
     fn visit_pou(&mut self, pou: &mut plc_ast::ast::Pou) {
@@ -111,9 +140,10 @@ impl AstVisitorMut for RetainLowerer {
 
     fn visit_variable_block(&mut self, block: &mut plc_ast::ast::VariableBlock) {
         let variables = std::mem::take(&mut block.variables);
-        //If the block is retain but we are in a program, mark the block as non-retain
-        if block.retain && self.context.in_program {
+        //If the block is retain or persistent but we are in a program, mark the block as non-retain
+        if (block.retain || block.persistent) && self.context.in_program {
             block.retain = false;
+            block.persistent = false;
         }
         for variable in variables {
             let Some(variable_index) =
@@ -123,19 +153,37 @@ impl AstVisitorMut for RetainLowerer {
                 continue;
             };
 
-            if !variable_index.should_retain(&self.index) {
+            let Some(section) = variable_index.get_retain_section(&self.index) else {
                 block.variables.push(variable);
                 continue;
+            };
+
+            let is_global = matches!(block.kind, plc_ast::ast::VariableBlockType::Global);
+            if (self.context.in_program || is_global) && block.linkage == LinkageType::Internal {
+                let container = self.context.container_name.as_deref().filter(|_| self.context.in_program);
+                self.roots.push(RetainRoot {
+                    path: container.map_or_else(
+                        || variable.get_name().to_string(),
+                        |it| format!("{it}.{}", variable.get_name()),
+                    ),
+                    symbol: container.map_or_else(
+                        || variable.get_name().to_string(),
+                        |it| retain_variable_name(it, variable.get_name()),
+                    ),
+                    type_name: variable_index.get_type_name().to_string(),
+                    location: variable.location.clone(),
+                });
             }
 
             if self.context.in_program {
                 let (old_variable, new_var) = self.replace_with_retain_variable(variable);
-                self.context.retain_variables.push(new_var);
+                self.context.retain_variables.push((section, new_var));
                 block.variables.push(old_variable);
-            } else if matches!(block.kind, plc_ast::ast::VariableBlockType::Global) && !block.retain {
-                // Global variable in a non-retain block whose type transitively contains retain
-                // members (e.g. an FB with VAR RETAIN). Move it to a retain block.
-                self.context.retain_variables.push(variable);
+            } else if is_global && RetainSection::new(block.retain, block.persistent) != Some(section) {
+                // Global variable in a block of another section whose type transitively contains
+                // retain or persistent members (e.g. an FB with VAR RETAIN). Move it to a block
+                // of the section of its members.
+                self.context.retain_variables.push((section, variable));
             } else {
                 // FB retain variables stay in-place within the FB's struct. The entire FB instance
                 // gets placed in the .retain section when instantiated at the global/program level,
@@ -146,13 +194,38 @@ impl AstVisitorMut for RetainLowerer {
     }
 }
 
+/// Appends the variables to the first global block of the given section, creating the block if it
+/// does not exist
+fn append_to_retain_block(unit: &mut CompilationUnit, mut variables: Vec<Variable>, section: RetainSection) {
+    if variables.is_empty() {
+        return;
+    }
+    if let Some(block) = unit
+        .global_vars
+        .iter_mut()
+        .find(|block| RetainSection::new(block.retain, block.persistent) == Some(section))
+    {
+        block.variables.append(&mut variables);
+        return;
+    }
+    unit.global_vars.push(plc_ast::ast::VariableBlock {
+        variables,
+        kind: plc_ast::ast::VariableBlockType::Global,
+        constant: false,
+        retain: section.is_retain(),
+        persistent: section.is_persistent(),
+        linkage: plc_ast::ast::LinkageType::Internal,
+        location: SourceLocation::internal(),
+        access: AccessModifier::Public,
+    });
+}
+
 impl RetainLowerer {
     /// Replaces a retain variable in a program with a global retain variable and replaces the original variable with an auto reference to the global variable
     fn replace_with_retain_variable(&mut self, mut variable: Variable) -> (Variable, Variable) {
-        let new_name = format!(
-            "__{}_{}__retain",
+        let new_name = retain_variable_name(
             self.context.container_name.as_deref().unwrap_or_default(),
-            variable.get_name()
+            variable.get_name(),
         );
         // Create a global variable called __<pou_name>_<var_name> and move the initializer and datatype to the global variable
         let new_var = Variable {
@@ -185,9 +258,12 @@ impl RetainLowerer {
 #[cfg(test)]
 mod tests {
     use plc_ast::ast::{CompilationUnit, DataTypeDeclaration, Variable, VariableBlock};
-    use plc_driver::parse_and_annotate;
+    use plc_diagnostics::diagnostician::Diagnostician;
+    use plc_driver::{parse_and_annotate, pipelines::BuildPipeline};
     use plc_source::SourceCode;
 
+    use super::persistence::hash;
+
     /// Finds the first global variable block with `retain: true` in a compilation unit.
     fn find_retain_block(unit: &CompilationUnit) -> &VariableBlock {
         unit.global_vars.iter().find(|b| b.retain).expect("expected a global retain block")
@@ -212,6 +288,11 @@ mod tests {
         find_variable_in_blocks(&pou.variable_blocks, var_name)
     }
 
+    /// Returns the values of all string literals in the debug output of an AST
+    fn string_literals(debug: &str) -> Vec<String> {
+        debug.split("value: \"").skip(1).map(|it| it[..it.find('"').unwrap()].to_string()).collect()
+    }
+
     /// Returns the referenced type name from a DataTypeDeclaration, if it is a Reference variant.
     fn referenced_type_name(decl: &DataTypeDeclaration) -> &str {
         match decl {
@@ -346,4 +427,121 @@ mod tests {
             .collect();
         assert_eq!(non_retain_globals, vec!["x"], "only x should remain in the non-retain global block");
     }
+
+    #[test]
+    fn persistent_variables_are_moved_to_a_persistent_block() {
+        let source: SourceCode = r#"
+        FUNCTION_BLOCK FB
+        VAR PERSISTENT
+            b: INT;
+        END_VAR
+        END_FUNCTION_BLOCK
+        VAR_GLOBAL RETAIN
+            implicit_persistent: FB;
+            y : INT;
+        END_VAR
+        PROGRAM Test
+        VAR PERSISTENT
+            p: INT;
+        END_VAR
+        VAR RETAIN PERSISTENT
+            x: INT;
+        END_VAR
+        VAR RETAIN
+            z: INT;
+        END_VAR
+        END_PROGRAM
+        "#
+        .into();
+
+        let (_, project) =
+            parse_and_annotate("test", vec![source]).expect("Failed to parse compilation unit");
+        let unit = project.units[0].get_unit();
+
+        let names = |retain: bool, persistent: bool| {
+            unit.global_vars
+                .iter()
+                .filter(|b| b.retain == retain && b.persistent == persistent)
+                .flat_map(|b| &b.variables)
+                .map(|v| v.get_name())
+                .collect::<Vec<_>>()
+        };
+        // an FB instance containing only persistent members is persistent, even if declared as retain
+        assert_eq!(names(false, true), vec!["implicit_persistent", "__Test_p__retain"]);
+        assert_eq!(names(true, true), vec!["__Test_x__retain"]);
+        assert_eq!(names(true, false), vec!["y", "__Test_z__retain"]);
+
+        let test_pou = unit.pous.iter().find(|p| p.name == "Test").unwrap();
+        assert!(test_pou.variable_blocks.iter().all(|b| !b.retain && !b.persistent));
+    }
+
+    #[test]
+    fn retain_values_are_saved_and_restored_by_their_qualified_name() {
+        let source: SourceCode = r#"
+        FUNCTION_BLOCK FB
+        VAR RETAIN
+            a : INT;
+        END_VAR
+        VAR_TEMP
+            t : INT;
+        END_VAR
+        END_FUNCTION_BLOCK
+        VAR_GLOBAL RETAIN
+            total : LREAL;
+            target : REF_TO INT;
+        END_VAR
+        VAR_GLOBAL
+            implicit_retain : FB;
+            x : INT;
+        END_VAR
+        TYPE Point : STRUCT
+            x : INT;
+        END_STRUCT
+        END_TYPE
+        PROGRAM Test
+        VAR RETAIN
+            values : ARRAY[0..1] OF DINT;
+            name : STRING[10];
+            points : ARRAY[0..1] OF Point;
+        END_VAR
+        END_PROGRAM
+        "#
+        .into();
+
+        let mut pipeline = BuildPipeline::from_sources("test", vec![source], Diagnostician::buffered())
+            .expect("Failed to create the pipeline");
+        pipeline.context = pipeline.context.generate_retain_persistence();
+        pipeline.register_default_mut_participants();
+        let project = pipeline.parse_and_annotate().expect("Failed to parse compilation unit");
+        let unit = project
+            .units
+            .iter()
+            .map(|it| it.get_unit())
+            .find(|it| it.pous.iter().any(|pou| pou.name == "__retain_save"))
+            .expect("expected a unit saving the retain variables");
+        for name in ["__retain_restore", "__retain_size"] {
+            assert!(unit.pous.iter().any(|pou| pou.name == name), "expected {name}");
+        }
+
+        // every elementary value and every array of them is an entry of the schema, the elements
+        // of arrays of structs are entries of their own and pointers are not saved
+        let paths = find_variable_in_blocks(&unit.global_vars, "__retain_schema_paths");
+        let paths = string_literals(&format!("{:?}", paths.initializer));
+        let mut expected = vec![
+            "total",
+            "implicit_retain.a",
+            "Test.values",
+            "Test.name",
+            "Test.points[0].x",
+            "Test.points[1].x",
+        ];
+        // the entries are sorted by the hashes of their names
+        expected.sort_by_key(|it| hash(it));
+        assert_eq!(paths, expected);
+
+        let types = find_variable_in_blocks(&unit.global_vars, "__retain_schema_types");
+        let types = string_literals(&format!("{:?}", types.initializer));
+        assert!(types.iter().any(|it| it == "STRING[10]"), "{types:?}");
+        assert!(types.iter().any(|it| it == "ARRAY[0..1] OF DINT"), "{types:?}");
+    }
 }
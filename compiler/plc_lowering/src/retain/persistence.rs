@@ -0,0 +1,876 @@
+//! # Saving and restoring retain variables
+//!
+//! The `.retain` section keeps its values across a power cycle, but its layout changes with every
+//! firmware update that adds, removes or reorders retain variables. To keep the values across an
+//! update, a runtime saves them with `__retain_save(buf)` before the update and restores them into
+//! the new firmware with `__retain_restore(buf)`.
+//!
+//! Every elementary value of a retain variable, i.e. every member of a struct or function block
+//! instance, is an entry of the schema embedded in the binary (`__retain_schema`,
+//! `__retain_schema_paths`, `__retain_schema_types` and `__retain_schema_hash`). An array of
+//! elementary values is a single entry, the elements of an array of structs are entries of their
+//! own. An entry is identified by the hash of its qualified name, e.g. `Main.counter` or
+//! `motors[2].speed`, and describes the type, size, number of elements and offset of its value in
+//! the saved data. The entries are sorted by the hashes of their names.
+//!
+//! The buffer written by `__retain_save` starts with a header and the schema of the firmware that
+//! saved it, followed by the values and the names of the entries:
+//!
+//! ```text
+//! __RETAIN_HEADER | __RETAIN_ENTRY * count | values | names
+//! ```
+//!
+//! `__retain_restore(buf, length)` binary searches every entry of its own schema by the hash of its
+//! name in the saved schema and compares the saved name, so values survive reordering and additions.
+//! A value whose type changed is converted if every value of the old type can be represented by the
+//! new one (e.g. `INT` to `DINT`, `REAL` to `LREAL`, a longer `STRING` or a one-dimensional array with
+//! more elements). The outcome of every entry is written to `__retain_restore_status`. A buffer which
+//! is shorter than its header claims, or whose entries refer to data outside of it, restores nothing.
+//!
+//! Two names with the same hash could not be told apart by the saved schema, the collision is reported
+//! with `E175` when the schema is generated.
+
+use std::fmt::Write;
+
+use plc::{
+    index::Index,
+    lexer, parser,
+    typesystem::{DataTypeInformation, StringEncoding},
+};
+use plc_ast::{
+    ast::{
+        AstNode, CompilationUnit, Implementation, LinkageType, Pou, UserTypeDeclaration, Variable,
+        VariableBlock,
+    },
+    mut_visitor::{AstVisitorMut, WalkerMut},
+    provider::IdProvider,
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::source_location::{SourceLocation, SourceLocationFactory};
+
+/// Identifies a buffer written by `__retain_save`
+const MAGIC: u32 = 0x5254_4E31;
+
+/// A variable placed into the `.retain` section
+#[derive(Debug)]
+pub(super) struct RetainRoot {
+    /// the qualified name the values of the variable are matched by
+    pub path: String,
+    /// the global variable holding the values
+    pub symbol: String,
+    pub type_name: String,
+    pub location: SourceLocation,
+}
+
+/// How a value is converted when its type changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// only restored into the same type, e.g. enums
+    Raw = 0,
+    Bool = 1,
+    Signed = 2,
+    Unsigned = 3,
+    Float = 4,
+    String = 5,
+    WString = 6,
+}
+
+/// An elementary value of a retain variable, or an array of them
+#[derive(Debug)]
+struct Entry {
+    path: String,
+    /// the expression the value is accessed with
+    reference: String,
+    /// the type shown in the schema, e.g. `INT` or `ARRAY[0..9] OF INT`
+    type_name: String,
+    /// the type of a single element, which decides how the value is converted
+    element_type: String,
+    kind: Kind,
+    /// the size of a single element
+    size: u32,
+    /// the number of elements, 1 unless the value is an array
+    count: u32,
+    /// the lengths of the dimensions of a multi-dimensional array, empty otherwise
+    shape: String,
+    offset: u32,
+    /// the offset of the name in the saved names
+    path_offset: u32,
+    /// the declaration of the retain variable the value belongs to
+    location: SourceLocation,
+}
+
+/// Generates the unit holding the schema of all retain variables and the functions saving and
+/// restoring them, `None` if there are no retain variables. Names whose hashes collide are reported
+/// instead.
+pub(super) fn generate_persistence(
+    roots: &[RetainRoot],
+    index: &Index,
+    ids: IdProvider,
+) -> Result<Option<CompilationUnit>, Vec<Diagnostic>> {
+    let mut entries = vec![];
+    for root in roots {
+        collect_entries(
+            index,
+            &root.type_name,
+            root.path.clone(),
+            root.symbol.clone(),
+            &root.location,
+            &mut entries,
+        );
+    }
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    // the saved schema is binary searched by the hashes of the names
+    entries.sort_by_key(|it| hash(&it.path));
+    let collisions = find_collisions(&entries);
+    if !collisions.is_empty() {
+        return Err(collisions);
+    }
+
+    let size = assign_offsets(&mut entries);
+    let mut names = 0;
+    for entry in &mut entries {
+        entry.path_offset = names;
+        names += entry.path.len() as u32;
+    }
+
+    let src = generate_source(&entries, size, names);
+    let mut unit = parser::parse(
+        lexer::lex_with_ids(&src, ids, SourceLocationFactory::internal(&src)),
+        LinkageType::Internal,
+        "<retain>",
+    )
+    .0;
+    // the generated code accesses private members of function blocks, which is only allowed for internal code
+    InternalLocations.visit_compilation_unit(&mut unit);
+    Ok(Some(unit))
+}
+
+/// Reports the entries whose names have the same hash, the entries have to be sorted by it
+fn find_collisions(entries: &[Entry]) -> Vec<Diagnostic> {
+    entries
+        .windows(2)
+        .filter(|it| hash(&it[0].path) == hash(&it[1].path))
+        .map(|it| {
+            Diagnostic::new(format!(
+                "Retain values `{}` and `{}` have the same hash, they cannot be saved and restored",
+                it[0].path, it[1].path
+            ))
+            .with_error_code("E175")
+            .with_location(&it[1].location)
+            .with_secondary_location(&it[0].location)
+        })
+        .collect()
+}
+
+/// Assigns the offsets of the values in the saved data and returns its size. Values are stored at
+/// their natural alignment, so they can be converted in place.
+fn assign_offsets(entries: &mut [Entry]) -> u32 {
+    let mut size: u32 = 0;
+    for entry in entries {
+        let alignment = match entry.kind {
+            Kind::String => 1,
+            Kind::WString => 2,
+            _ if entry.size.is_power_of_two() => entry.size.min(8),
+            _ => 1,
+        };
+        entry.offset = size.next_multiple_of(alignment);
+        size = entry.offset + entry.size * entry.count;
+    }
+    size
+}
+
+struct InternalLocations;
+
+impl AstVisitorMut for InternalLocations {
+    fn visit(&mut self, node: &mut AstNode) {
+        node.location = SourceLocation::internal();
+        node.walk(self)
+    }
+
+    fn visit_pou(&mut self, pou: &mut Pou) {
+        pou.location = SourceLocation::internal();
+        pou.name_location = SourceLocation::internal();
+        pou.walk(self)
+    }
+
+    fn visit_implementation(&mut self, implementation: &mut Implementation) {
+        implementation.location = SourceLocation::internal();
+        implementation.name_location = SourceLocation::internal();
+        implementation.end_location = SourceLocation::internal();
+        implementation.walk(self)
+    }
+
+    fn visit_variable_block(&mut self, block: &mut VariableBlock) {
+        block.location = SourceLocation::internal();
+        block.walk(self)
+    }
+
+    fn visit_variable(&mut self, variable: &mut Variable) {
+        variable.location = SourceLocation::internal();
+        variable.walk(self)
+    }
+
+    fn visit_user_type_declaration(&mut self, user_type: &mut UserTypeDeclaration) {
+        user_type.location = SourceLocation::internal();
+        user_type.walk(self)
+    }
+}
+
+fn collect_entries(
+    index: &Index,
+    type_name: &str,
+    path: String,
+    reference: String,
+    location: &SourceLocation,
+    entries: &mut Vec<Entry>,
+) {
+    if let Some((kind, element_type, size)) = get_elementary_type(index, type_name) {
+        let entry = Entry {
+            path,
+            reference,
+            type_name: element_type.clone(),
+            element_type,
+            kind,
+            size,
+            count: 1,
+            shape: String::new(),
+            offset: 0,
+            path_offset: 0,
+            location: location.clone(),
+        };
+        entries.push(entry);
+        return;
+    }
+    let Some(data_type) = index.find_effective_type_by_name(type_name) else { return };
+    match data_type.get_type_information() {
+        DataTypeInformation::Struct { members, .. } => {
+            // the members of the base are accessed like the ones of the derived function block
+            if let Some(base) = index.find_pou(data_type.get_name()).and_then(|it| it.get_super_class()) {
+                collect_entries(index, base, path.clone(), reference.clone(), location, entries);
+            }
+            let members = members.iter().filter(|it| {
+                !(it.is_temp() || it.is_return() || it.is_var_external() || it.get_name().starts_with("__"))
+            });
+            for member in members {
+                collect_entries(
+                    index,
+                    member.get_type_name(),
+                    format!("{path}.{}", member.get_name()),
+                    format!("{reference}.{}", member.get_name()),
+                    location,
+                    entries,
+                );
+            }
+        }
+        DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+            let Ok(ranges) =
+                dimensions.iter().map(|it| it.get_range_inclusive(index)).collect::<Result<Vec<_>, _>>()
+            else {
+                return;
+            };
+            let lengths =
+                ranges.iter().map(|it| (it.end() - it.start() + 1).max(0) as u32).collect::<Vec<_>>();
+
+            // an array of elementary values is saved as a whole and converted element by element
+            if let Some((kind, element_type, size)) = get_elementary_type(index, inner_type_name) {
+                let bounds =
+                    ranges.iter().map(|it| format!("{}..{}", it.start(), it.end())).collect::<Vec<_>>();
+                let shape = if lengths.len() > 1 {
+                    lengths.iter().map(ToString::to_string).collect::<Vec<_>>().join(",")
+                } else {
+                    String::new()
+                };
+                entries.push(Entry {
+                    path,
+                    reference,
+                    type_name: format!("ARRAY[{}] OF {element_type}", bounds.join(",")),
+                    element_type,
+                    kind,
+                    size,
+                    count: lengths.iter().product(),
+                    shape,
+                    offset: 0,
+                    path_offset: 0,
+                    location: location.clone(),
+                });
+                return;
+            }
+
+            // the elements are stored row by row, i.e. the last index changes fastest
+            let mut elements = vec![vec![]];
+            for range in ranges {
+                elements = elements
+                    .into_iter()
+                    .flat_map(|indices: Vec<i64>| {
+                        range.clone().map(move |it| {
+                            let mut indices = indices.clone();
+                            indices.push(it);
+                            indices
+                        })
+                    })
+                    .collect();
+            }
+            for indices in elements {
+                let indices = indices.iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
+                collect_entries(
+                    index,
+                    inner_type_name,
+                    format!("{path}[{indices}]"),
+                    format!("{reference}[{indices}]"),
+                    location,
+                    entries,
+                );
+            }
+        }
+        // pointers refer to the data of the previous firmware
+        _ => {}
+    }
+}
+
+/// Returns the kind, the name shown in the schema and the size of an elementary type, `None` for
+/// structs, arrays, pointers and other types
+fn get_elementary_type(index: &Index, type_name: &str) -> Option<(Kind, String, u32)> {
+    let data_type = index.find_effective_type_by_name(type_name)?;
+    let information = data_type.get_type_information();
+    let size = information.get_size(index).ok()?.value();
+    let name = data_type.get_name().to_string();
+    let elementary = match information {
+        DataTypeInformation::SubRange { referenced_type, .. } => {
+            return get_elementary_type(index, referenced_type)
+        }
+        DataTypeInformation::Integer { semantic_size: Some(1), .. } => (Kind::Bool, "BOOL".to_string(), size),
+        DataTypeInformation::Integer { signed: true, .. } => (Kind::Signed, name, size),
+        DataTypeInformation::Integer { signed: false, .. } => (Kind::Unsigned, name, size),
+        DataTypeInformation::Float { .. } => (Kind::Float, name, size),
+        DataTypeInformation::Enum { .. } => (Kind::Raw, name, size),
+        DataTypeInformation::String { encoding: StringEncoding::Utf8, .. } => {
+            (Kind::String, format!("STRING[{}]", size - 1), size)
+        }
+        DataTypeInformation::String { encoding: StringEncoding::Utf16, .. } => {
+            (Kind::WString, format!("WSTRING[{}]", size / 2 - 1), size)
+        }
+        _ => return None,
+    };
+    Some(elementary)
+}
+
+/// Returns the 64 bit FNV-1a hash of the given text, ignoring the case
+pub(super) fn hash(text: &str) -> u64 {
+    text.to_lowercase()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3))
+}
+
+fn generate_source(entries: &[Entry], size: u32, names: u32) -> String {
+    let last = entries.len() - 1;
+    let schema_hash = hash(
+        &entries
+            .iter()
+            .map(|it| format!("{}:{}:{}", it.path, it.type_name, it.offset))
+            .collect::<Vec<_>>()
+            .join(";"),
+    );
+    let schema = entries
+        .iter()
+        .map(|it| {
+            format!(
+                "(name_hash := 16#{:X}, type_hash := 16#{:X}, shape_hash := 16#{:X}, kind := {}, size := {}, count := {}, offset := {}, path_offset := {}, path_length := {})",
+                hash(&it.path),
+                hash(&it.element_type),
+                hash(&it.shape),
+                it.kind as u32,
+                it.size,
+                it.count,
+                it.offset,
+                it.path_offset,
+                it.path.len()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n        ");
+    let strings = |values: Vec<&str>| {
+        let length = values.iter().map(|it| it.len()).max().unwrap_or_default().max(1);
+        let values = values.iter().map(|it| format!("'{it}'")).collect::<Vec<_>>().join(",\n        ");
+        (length, values)
+    };
+    let (path_length, paths) = strings(entries.iter().map(|it| it.path.as_str()).collect());
+    let (type_length, types) = strings(entries.iter().map(|it| it.type_name.as_str()).collect());
+
+    let mut save = String::new();
+    let mut restore = String::new();
+    for (position, entry) in entries.iter().enumerate() {
+        let _ = writeln!(
+            save,
+            "    __retain_copy(ADR({}), data + {}, {});",
+            entry.reference,
+            entry.offset,
+            entry.size * entry.count
+        );
+        let _ = writeln!(
+            restore,
+            "    __retain_restore_status[{position}] := __retain_restore_entry(saved, header^.count, data, names, {position}, ADR({}));",
+            entry.reference
+        );
+    }
+
+    format!(
+        "
+TYPE __RETAIN_HEADER : STRUCT
+    magic : DWORD;
+    count : UDINT;
+    hash : LWORD;
+    size : UDINT;
+    names : UDINT;
+END_STRUCT
+END_TYPE
+
+// kind: 0 raw, 1 BOOL, 2 signed integer, 3 unsigned integer, 4 floating point, 5 STRING, 6 WSTRING
+// size is the size of a single element, count the number of elements of an array, path_offset and
+// path_length locate the name of the entry in the saved names
+TYPE __RETAIN_ENTRY : STRUCT
+    name_hash : ULINT;
+    type_hash : ULINT;
+    shape_hash : ULINT;
+    kind : UDINT;
+    size : UDINT;
+    count : UDINT;
+    offset : UDINT;
+    path_offset : UDINT;
+    path_length : UDINT;
+END_STRUCT
+END_TYPE
+
+VAR_GLOBAL CONSTANT
+    __retain_schema_hash : LWORD := 16#{schema_hash:X};
+    __retain_schema : ARRAY[0..{last}] OF __RETAIN_ENTRY := [
+        {schema}
+    ];
+    __retain_schema_paths : ARRAY[0..{last}] OF STRING[{path_length}] := [
+        {paths}
+    ];
+    __retain_schema_types : ARRAY[0..{last}] OF STRING[{type_length}] := [
+        {types}
+    ];
+END_VAR
+
+// status: 0 restored, 1 converted, 2 not saved, 3 incompatible type
+VAR_GLOBAL
+    __retain_restore_status : ARRAY[0..{last}] OF DINT;
+END_VAR
+
+FUNCTION __retain_copy
+VAR_INPUT
+    source : POINTER TO BYTE;
+    destination : POINTER TO BYTE;
+    size : UDINT;
+END_VAR
+VAR
+    i : UDINT;
+END_VAR
+    FOR i := 1 TO size DO
+        destination^ := source^;
+        source := source + 1;
+        destination := destination + 1;
+    END_FOR
+END_FUNCTION
+
+FUNCTION __retain_convert : BOOL
+VAR_INPUT
+    source : POINTER TO BYTE;
+    source_kind : UDINT;
+    source_size : UDINT;
+    destination : POINTER TO BYTE;
+    kind : UDINT;
+    size : UDINT;
+END_VAR
+VAR
+    signed_8 : POINTER TO SINT;
+    signed_16 : POINTER TO INT;
+    signed_32 : POINTER TO DINT;
+    signed_64 : POINTER TO LINT;
+    unsigned_8 : POINTER TO USINT;
+    unsigned_16 : POINTER TO UINT;
+    unsigned_32 : POINTER TO UDINT;
+    real_32 : POINTER TO REAL;
+    to_real : POINTER TO REAL;
+    to_lreal : POINTER TO LREAL;
+    integer : LINT;
+    probe : UINT := 1;
+    first_byte : POINTER TO BYTE;
+END_VAR
+    __retain_convert := FALSE;
+    // every value of the saved type has to be representable by the new one
+    IF NOT ((kind = source_kind AND kind >= 2 AND size >= source_size)
+        OR (source_kind = 3 AND kind = 2 AND size > source_size)
+        OR (source_kind = 1 AND (kind = 2 OR kind = 3))
+        OR ((source_kind = 2 OR source_kind = 3) AND kind = 4 AND size > source_size)) THEN
+        RETURN;
+    END_IF
+    __retain_convert := TRUE;
+
+    // strings keep their characters and terminator, values of the same size their representation
+    IF kind = 5 OR kind = 6 OR (kind = source_kind AND size = source_size) THEN
+        __retain_copy(source, destination, source_size);
+        RETURN;
+    END_IF
+
+    signed_8 := source;
+    signed_16 := source;
+    signed_32 := source;
+    signed_64 := source;
+    unsigned_8 := source;
+    unsigned_16 := source;
+    unsigned_32 := source;
+    real_32 := source;
+    to_real := destination;
+    to_lreal := destination;
+
+    // integers converted to REAL have at most 16 bits, a REAL is only converted to LREAL
+    IF kind = 4 AND size = 4 THEN
+        IF source_kind = 2 AND source_size = 1 THEN
+            to_real^ := signed_8^;
+        ELSIF source_kind = 2 THEN
+            to_real^ := signed_16^;
+        ELSIF source_size = 1 THEN
+            to_real^ := unsigned_8^;
+        ELSE
+            to_real^ := unsigned_16^;
+        END_IF
+        RETURN;
+    ELSIF kind = 4 AND source_kind = 4 THEN
+        to_lreal^ := real_32^;
+        RETURN;
+    END_IF
+
+    IF source_kind = 2 THEN
+        CASE source_size OF
+            1: integer := signed_8^;
+            2: integer := signed_16^;
+            4: integer := signed_32^;
+        ELSE
+            integer := signed_64^;
+        END_CASE
+    ELSE
+        // an unsigned integer of 64 bits is only restored into the same type
+        CASE source_size OF
+            1: integer := unsigned_8^;
+            2: integer := unsigned_16^;
+        ELSE
+            integer := unsigned_32^;
+        END_CASE
+    END_IF
+
+    IF kind = 4 THEN
+        to_lreal^ := integer;
+        RETURN;
+    END_IF
+
+    // the integer is extended to 64 bits, its low bytes hold the value of the new type
+    first_byte := ADR(probe);
+    IF first_byte^ = 1 THEN
+        __retain_copy(ADR(integer), destination, size);
+    ELSE
+        __retain_copy(ADR(integer) + 8 - size, destination, size);
+    END_IF
+END_FUNCTION
+
+// the saved names are compared ignoring their case, like their hashes
+FUNCTION __retain_same_name : BOOL
+VAR_INPUT
+    name : POINTER TO BYTE;
+    length : UDINT;
+    position : UDINT;
+END_VAR
+VAR
+    path : STRING[{path_length}];
+    saved_character : POINTER TO USINT;
+    character : POINTER TO USINT;
+    a : USINT;
+    b : USINT;
+    i : UDINT;
+END_VAR
+    __retain_same_name := FALSE;
+    IF length <> __retain_schema[position].path_length THEN
+        RETURN;
+    END_IF
+    path := __retain_schema_paths[position];
+    saved_character := name;
+    character := ADR(path);
+    WHILE i < length DO
+        a := saved_character^;
+        b := character^;
+        IF a >= 65 AND a <= 90 THEN
+            a := a + 32;
+        END_IF
+        IF b >= 65 AND b <= 90 THEN
+            b := b + 32;
+        END_IF
+        IF a <> b THEN
+            RETURN;
+        END_IF
+        saved_character := saved_character + 1;
+        character := character + 1;
+        i := i + 1;
+    END_WHILE
+    __retain_same_name := TRUE;
+END_FUNCTION
+
+FUNCTION __retain_restore_entry : DINT
+VAR_INPUT
+    saved : POINTER TO __RETAIN_ENTRY;
+    count : UDINT;
+    data : POINTER TO BYTE;
+    names : POINTER TO BYTE;
+    position : UDINT;
+    destination : POINTER TO BYTE;
+END_VAR
+VAR
+    entry : POINTER TO __RETAIN_ENTRY;
+    low : UDINT;
+    high : UDINT;
+    middle : UDINT;
+    i : UDINT;
+END_VAR
+    // the saved schema is sorted by the hashes of the names
+    high := count;
+    WHILE low < high DO
+        middle := (low + high) / 2;
+        entry := saved + middle;
+        IF entry^.name_hash < __retain_schema[position].name_hash THEN
+            low := middle + 1;
+        ELSE
+            high := middle;
+        END_IF
+    END_WHILE
+    IF low = count THEN
+        __retain_restore_entry := 2;
+        RETURN;
+    END_IF
+    entry := saved + low;
+
+    // a name with the same hash is a different value
+    IF entry^.name_hash <> __retain_schema[position].name_hash
+        OR NOT __retain_same_name(names + entry^.path_offset, entry^.path_length, position) THEN
+        __retain_restore_entry := 2;
+    ELSIF entry^.shape_hash <> __retain_schema[position].shape_hash
+        OR entry^.count > __retain_schema[position].count THEN
+        // the saved elements do not fit into the array
+        __retain_restore_entry := 3;
+    ELSIF entry^.type_hash = __retain_schema[position].type_hash AND entry^.size = __retain_schema[position].size THEN
+        __retain_copy(data + entry^.offset, destination, entry^.size * entry^.count);
+        IF entry^.count = __retain_schema[position].count THEN
+            __retain_restore_entry := 0;
+        ELSE
+            __retain_restore_entry := 1;
+        END_IF
+    ELSE
+        WHILE i < entry^.count DO
+            IF NOT __retain_convert(
+                data + entry^.offset + i * entry^.size,
+                entry^.kind,
+                entry^.size,
+                destination + i * __retain_schema[position].size,
+                __retain_schema[position].kind,
+                __retain_schema[position].size
+            ) THEN
+                __retain_restore_entry := 3;
+                RETURN;
+            END_IF
+            i := i + 1;
+        END_WHILE
+        __retain_restore_entry := 1;
+    END_IF
+END_FUNCTION
+
+FUNCTION __retain_size : ULINT
+VAR
+    header : __RETAIN_HEADER;
+    entry : __RETAIN_ENTRY;
+END_VAR
+    __retain_size := SIZEOF(header) + {count} * SIZEOF(entry) + {size} + {names};
+END_FUNCTION
+
+FUNCTION __retain_save : ULINT
+VAR_INPUT
+    buf : POINTER TO BYTE;
+END_VAR
+VAR
+    header : POINTER TO __RETAIN_HEADER;
+    entry : POINTER TO __RETAIN_ENTRY;
+    data : POINTER TO BYTE;
+    names : POINTER TO BYTE;
+    name : STRING[{path_length}];
+    i : DINT;
+END_VAR
+    header := buf;
+    header^.magic := 16#{MAGIC:X};
+    header^.count := {count};
+    header^.hash := __retain_schema_hash;
+    header^.size := {size};
+    header^.names := {names};
+    entry := buf + SIZEOF(header^);
+    data := entry + {count};
+    names := data + {size};
+    FOR i := 0 TO {last} DO
+        entry^ := __retain_schema[i];
+        entry := entry + 1;
+        name := __retain_schema_paths[i];
+        __retain_copy(ADR(name), names + __retain_schema[i].path_offset, __retain_schema[i].path_length);
+    END_FOR
+{save}
+    __retain_save := __retain_size();
+END_FUNCTION
+
+FUNCTION __retain_restore : UDINT
+VAR_INPUT
+    buf : POINTER TO BYTE;
+    length : ULINT;
+END_VAR
+VAR
+    header : POINTER TO __RETAIN_HEADER;
+    saved : POINTER TO __RETAIN_ENTRY;
+    entry : POINTER TO __RETAIN_ENTRY;
+    data : POINTER TO BYTE;
+    names : POINTER TO BYTE;
+    required : ULINT;
+    previous : ULINT;
+    i : DINT;
+    j : UDINT;
+    low : UDINT;
+    high : UDINT;
+    middle : UDINT;
+    unmatched : UDINT;
+END_VAR
+    // every value keeps its initial value unless the buffer is valid
+    FOR i := 0 TO {last} DO
+        __retain_restore_status[i] := 2;
+    END_FOR
+    __retain_restore := 0;
+    header := buf;
+    IF length < SIZEOF(header^) THEN
+        RETURN;
+    END_IF
+    IF header^.magic <> 16#{MAGIC:X} THEN
+        RETURN;
+    END_IF
+    required := header^.count;
+    required := SIZEOF(header^) + required * SIZEOF(entry^) + header^.size + header^.names;
+    IF required > length THEN
+        RETURN;
+    END_IF
+    saved := buf + SIZEOF(header^);
+    data := saved + header^.count;
+    names := data + header^.size;
+
+    // the saved entries have to be sorted by the hashes of their names and refer to the saved data
+    WHILE j < header^.count DO
+        entry := saved + j;
+        required := entry^.size;
+        required := required * entry^.count + entry^.offset;
+        IF (j > 0 AND entry^.name_hash <= previous) OR required > header^.size THEN
+            RETURN;
+        END_IF
+        required := entry^.path_offset;
+        required := required + entry^.path_length;
+        IF required > header^.names THEN
+            RETURN;
+        END_IF
+        previous := entry^.name_hash;
+        j := j + 1;
+    END_WHILE
+
+{restore}
+    // the saved values which were not restored, the own schema is sorted by the hashes of the names too
+    j := 0;
+    WHILE j < header^.count DO
+        entry := saved + j;
+        low := 0;
+        high := {count};
+        WHILE low < high DO
+            middle := (low + high) / 2;
+            IF __retain_schema[middle].name_hash < entry^.name_hash THEN
+                low := middle + 1;
+            ELSE
+                high := middle;
+            END_IF
+        END_WHILE
+        IF low = {count} THEN
+            unmatched := unmatched + 1;
+        ELSIF __retain_schema[low].name_hash <> entry^.name_hash OR __retain_restore_status[low] > 1 THEN
+            unmatched := unmatched + 1;
+        END_IF
+        j := j + 1;
+    END_WHILE
+    __retain_restore := unmatched;
+END_FUNCTION
+",
+        count = entries.len(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_source::source_location::SourceLocation;
+
+    use super::{assign_offsets, find_collisions, hash, Entry, Kind};
+
+    fn entry(kind: Kind, size: u32, count: u32) -> Entry {
+        Entry {
+            path: String::new(),
+            reference: String::new(),
+            type_name: String::new(),
+            element_type: String::new(),
+            kind,
+            size,
+            count,
+            shape: String::new(),
+            offset: 0,
+            path_offset: 0,
+            location: SourceLocation::internal(),
+        }
+    }
+
+    #[test]
+    fn names_are_hashed_ignoring_their_case() {
+        assert_eq!(hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(hash("Main.Counter"), hash("main.counter"));
+        assert_ne!(hash("main.counter"), hash("main.count"));
+    }
+
+    #[test]
+    fn values_are_stored_at_their_natural_alignment() {
+        let mut entries = vec![
+            entry(Kind::Bool, 1, 1),
+            entry(Kind::Signed, 4, 1),
+            entry(Kind::String, 11, 1),
+            entry(Kind::Float, 8, 1),
+            entry(Kind::WString, 22, 1),
+            entry(Kind::Unsigned, 2, 3),
+            entry(Kind::Raw, 3, 1),
+        ];
+        let size = assign_offsets(&mut entries);
+        let offsets = entries.iter().map(|it| it.offset).collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0, 4, 8, 24, 32, 54, 60]);
+        assert_eq!(size, 63);
+    }
+
+    #[test]
+    fn names_with_the_same_hash_are_reported() {
+        let mut entries = vec![entry(Kind::Signed, 4, 1), entry(Kind::Signed, 4, 1), entry(Kind::Bool, 1, 1)];
+        entries[0].path = "Main.Counter".into();
+        entries[1].path = "main.counter".into();
+        entries[2].path = "main.enabled".into();
+        entries.sort_by_key(|it| hash(&it.path));
+
+        let collisions = find_collisions(&entries);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].get_error_code(), "E175");
+        assert!(collisions[0].get_message().contains("`Main.Counter` and `main.counter`"));
+
+        entries.retain(|it| it.path != "Main.Counter");
+        assert!(find_collisions(&entries).is_empty());
+    }
+}
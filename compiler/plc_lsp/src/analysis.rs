@@ -0,0 +1,636 @@
+//! Answers the queries of the language server from an [`AnnotatedProject`].
+//!
+//! The project is parsed leniently (syntax errors do not abort the analysis) and annotated without
+//! any lowering participants, so every node of the AST still points to the location it was parsed from.
+
+use lsp_types::{CompletionItem, CompletionItemKind};
+use plc::{
+    index::{Index, PouIndexEntry, VariableIndexEntry, VariableType},
+    lexer,
+    parser::parse,
+    resolver::{AnnotationMap, StatementAnnotation},
+    typesystem::{DataType, DataTypeInformation},
+};
+use plc_ast::{
+    ast::{
+        AstId, AstNode, AstStatement, CompilationUnit, DataTypeDeclaration, LinkageType, ReferenceAccess,
+        ReferenceExpr,
+    },
+    provider::IdProvider,
+    visitor::{AstVisitor, Walker},
+};
+use plc_diagnostics::{diagnostician::Diagnostician, reporter::ResolvedDiagnostics};
+use plc_driver::pipelines::{AnnotatedProject, ParsedProject};
+use plc_index::GlobalContext;
+use plc_source::{
+    source_location::{SourceLocation, SourceLocationFactory},
+    SourceCode, SourceContainer, SourceType,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{diagnostics::DiagnosticCollector, position::LineIndex};
+
+/// Something a position in the source can refer to. Names are stored lowercase since
+/// identifiers are case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    /// A variable, member or enum variant by its qualified name (e.g. `plc_prg.x`)
+    Variable(String),
+    /// A program, function, function block, class, method or action
+    Pou(String),
+    /// A user defined or builtin data type
+    Type(String),
+}
+
+impl Symbol {
+    fn variable(qualified_name: &str) -> Self {
+        Symbol::Variable(qualified_name.to_lowercase())
+    }
+
+    fn pou(name: &str) -> Self {
+        Symbol::Pou(name.to_lowercase())
+    }
+
+    /// POUs are also types (e.g. `fb : MyFb`), they are treated as POUs so they can be navigated to
+    fn data_type(name: &str, index: &Index) -> Self {
+        if index.find_pou(name).is_some() {
+            Symbol::pou(name)
+        } else {
+            Symbol::Type(name.to_lowercase())
+        }
+    }
+
+    fn from_annotation(annotation: &StatementAnnotation, index: &Index) -> Option<Self> {
+        match annotation {
+            StatementAnnotation::Variable { qualified_name, .. } => Some(Symbol::variable(qualified_name)),
+            StatementAnnotation::Function { qualified_name, .. }
+            | StatementAnnotation::FunctionPointer { qualified_name, .. }
+            | StatementAnnotation::Program { qualified_name } => Some(Symbol::pou(qualified_name)),
+            StatementAnnotation::Type { type_name } => Some(Symbol::data_type(type_name, index)),
+            _ => None,
+        }
+    }
+}
+
+/// The result of a hover request
+#[derive(Debug, PartialEq)]
+pub struct HoverInfo {
+    /// Markdown describing the element under the cursor
+    pub contents: String,
+    /// The location of the element under the cursor
+    pub location: SourceLocation,
+}
+
+/// A place in the source that may refer to a symbol
+enum Occurrence {
+    /// An expression, its meaning is looked up in the annotations
+    Node(AstId),
+    /// A type referenced by a declaration (e.g. `x : MyStruct`)
+    TypeReference(String),
+}
+
+#[derive(Default)]
+struct OccurrenceCollector {
+    occurrences: Vec<(Occurrence, SourceLocation)>,
+}
+
+impl AstVisitor for OccurrenceCollector {
+    fn visit(&mut self, node: &AstNode) {
+        // for `a.b` only the `b` refers to what the whole expression resolves to
+        let location = match node.get_stmt() {
+            AstStatement::ReferenceExpr(ReferenceExpr {
+                access: ReferenceAccess::Member(member), ..
+            }) => member.get_location(),
+            _ => node.get_location(),
+        };
+        self.occurrences.push((Occurrence::Node(node.get_id()), location));
+        node.walk(self)
+    }
+
+    fn visit_data_type_declaration(&mut self, data_type_declaration: &DataTypeDeclaration) {
+        if let DataTypeDeclaration::Reference { referenced_type, location } = data_type_declaration {
+            self.occurrences.push((Occurrence::TypeReference(referenced_type.clone()), location.clone()));
+        }
+        data_type_declaration.walk(self)
+    }
+}
+
+pub struct Analysis {
+    project: AnnotatedProject,
+    files: FxHashMap<String, LineIndex>,
+    diagnostics: Vec<(String, ResolvedDiagnostics)>,
+}
+
+impl Analysis {
+    /// Parses, indexes, annotates and validates the given sources and includes
+    pub fn new(sources: &[SourceCode], includes: &[SourceCode]) -> Self {
+        let collector = DiagnosticCollector::default();
+        let mut diagnostician = Diagnostician::default().with_reporter(Box::new(collector.clone()));
+        let mut context = GlobalContext::new();
+
+        let mut units = vec![];
+        let files = sources
+            .iter()
+            .map(|it| (it, LinkageType::Internal))
+            .chain(includes.iter().map(|it| (it, LinkageType::Include)));
+        for (source, linkage) in files {
+            if let Err(diagnostic) = context.insert(source, None) {
+                diagnostician.handle(&[diagnostic]);
+            }
+            units.extend(parse_source(source, linkage, context.provider(), &mut diagnostician));
+        }
+
+        let project = ParsedProject::from_units(units).index(context.provider()).annotate(context.provider());
+        // the result only tells whether there were errors, they were all reported to the collector
+        let _ = project.validate(&context, &mut diagnostician);
+
+        let files = sources
+            .iter()
+            .chain(includes)
+            .map(|it| (it.get_location_str().to_string(), LineIndex::new(&it.source)))
+            .collect();
+
+        Analysis { project, files, diagnostics: collector.take() }
+    }
+
+    /// The diagnostics of the project together with the file they belong to
+    pub fn get_diagnostics(&self) -> &[(String, ResolvedDiagnostics)] {
+        &self.diagnostics
+    }
+
+    /// All files that were part of the analysis
+    pub fn get_files(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    pub fn get_line_index(&self, file: &str) -> Option<&LineIndex> {
+        self.files.get(file)
+    }
+
+    /// Returns the symbol at the given offset and the location of the name referring to it
+    pub fn find_symbol(&self, file: &str, offset: usize) -> Option<(Symbol, SourceLocation)> {
+        let index = &self.project.index;
+        let references = self
+            .get_occurrences(file)
+            .filter_map(|(occurrence, location)| self.get_symbol(occurrence, index).map(|it| (it, location)));
+        let declarations = self.get_declarations().map(|(symbol, location)| (symbol, location.clone()));
+
+        declarations
+            .chain(references)
+            .filter(|(_, location)| contains(location, file, offset))
+            .min_by_key(|(_, location)| location.to_range().map(|it| it.len()).unwrap_or(usize::MAX))
+    }
+
+    /// Describes the element at the given offset
+    pub fn hover(&self, file: &str, offset: usize) -> Option<HoverInfo> {
+        let annotations = &self.project.annotations;
+
+        // expressions are described by their annotation, which also covers literals and operators
+        let expression = self
+            .get_occurrences(file)
+            .filter(|(_, location)| contains(location, file, offset))
+            .filter_map(|(occurrence, location)| match occurrence {
+                Occurrence::Node(id) => annotations
+                    .get_with_id(id)
+                    .and_then(|it| self.describe_annotation(it))
+                    .map(|contents| HoverInfo { contents, location }),
+                Occurrence::TypeReference(_) => None,
+            })
+            .min_by_key(|it| it.location.to_range().map(|it| it.len()).unwrap_or(usize::MAX));
+
+        let declaration = self.find_symbol(file, offset).and_then(|(symbol, location)| {
+            self.describe_symbol(&symbol).map(|contents| HoverInfo { contents, location })
+        });
+
+        // prefer whichever is more specific
+        match (expression, declaration) {
+            (Some(expression), Some(declaration)) => {
+                let len = |it: &HoverInfo| it.location.to_range().map(|it| it.len()).unwrap_or(usize::MAX);
+                if len(&declaration) < len(&expression) {
+                    Some(declaration)
+                } else {
+                    Some(expression)
+                }
+            }
+            (expression, declaration) => expression.or(declaration),
+        }
+    }
+
+    /// Returns the location where the symbol at the given offset is declared
+    pub fn find_definition(&self, file: &str, offset: usize) -> Option<SourceLocation> {
+        let (symbol, _) = self.find_symbol(file, offset)?;
+        self.get_declaration(&symbol).cloned()
+    }
+
+    /// Returns every location referring to the symbol at the given offset
+    pub fn find_references(
+        &self,
+        file: &str,
+        offset: usize,
+        include_declaration: bool,
+    ) -> Vec<SourceLocation> {
+        let Some((symbol, _)) = self.find_symbol(file, offset) else {
+            return vec![];
+        };
+        let index = &self.project.index;
+
+        let declaration = self.get_declaration(&symbol).filter(|_| include_declaration).cloned();
+        let references =
+            self.project.units.iter().flat_map(|unit| collect_occurrences(unit.get_unit())).filter_map(
+                |(occurrence, location)| {
+                    (self.get_symbol(occurrence, index).as_ref() == Some(&symbol)).then_some(location)
+                },
+            );
+
+        let mut seen = FxHashSet::default();
+        declaration
+            .into_iter()
+            .chain(references)
+            .filter(|it| seen.insert((it.get_file_name(), it.to_range().map(|it| (it.start, it.end)))))
+            .collect()
+    }
+
+    /// Returns the completion candidates at the given offset. After a `.` the members of the
+    /// qualifier are offered, otherwise everything visible from the surrounding POU.
+    pub fn get_completions(&self, file: &str, offset: usize) -> Vec<CompletionItem> {
+        let Some(text) = self.files.get(file).map(LineIndex::text) else {
+            return vec![];
+        };
+        let offset = offset.min(text.len());
+        let start = text[..offset].trim_end_matches(is_identifier_char).len();
+        let scope = self.get_scope(file, offset);
+
+        let items = if let Some(qualifier) = text[..start].strip_suffix('.') {
+            let qualifier =
+                &qualifier[qualifier.trim_end_matches(|c| is_identifier_char(c) || c == '.').len()..];
+            let segments = qualifier.split('.').collect::<Vec<_>>();
+            if segments.iter().any(|it| it.is_empty()) {
+                return vec![];
+            }
+            self.get_member_completions(scope.as_deref(), &segments)
+        } else {
+            self.get_scope_completions(scope.as_deref())
+        };
+
+        // overridden methods and program instances would otherwise show up twice
+        let mut seen: FxHashMap<String, Vec<Option<CompletionItemKind>>> = FxHashMap::default();
+        items
+            .into_iter()
+            .filter(|it| {
+                let kinds = seen.entry(it.label.to_lowercase()).or_default();
+                let is_new = !kinds.contains(&it.kind);
+                kinds.push(it.kind);
+                is_new
+            })
+            .collect()
+    }
+
+    fn get_member_completions(&self, scope: Option<&str>, segments: &[&str]) -> Vec<CompletionItem> {
+        let index = &self.project.index;
+        let container = index
+            .find_variable(scope, segments)
+            .map(|it| it.get_type_name())
+            // `MyProgram.` or `MyEnum.`
+            .or_else(|| segments.first().filter(|_| segments.len() == 1).copied());
+
+        container.map(|it| self.get_members(it)).unwrap_or_default()
+    }
+
+    fn get_scope_completions(&self, scope: Option<&str>) -> Vec<CompletionItem> {
+        let index = &self.project.index;
+        let mut items = vec![];
+
+        // the POU itself, followed by the POU an action or method belongs to
+        let mut current = scope;
+        while let Some(pou) = current.and_then(|it| index.find_pou(it)) {
+            items.extend(self.get_members(pou.get_name()));
+            current = pou.get_parent_pou_name();
+        }
+
+        items.extend(index.get_globals().values().filter(|it| is_visible(it.get_name())).map(variable_item));
+        items.extend(
+            index
+                .get_pous()
+                .values()
+                .filter(|it| !it.is_method() && !it.is_action() && !index.is_init_function(it.get_name()))
+                .filter(|it| is_visible(it.get_name()))
+                .map(pou_item),
+        );
+        items.extend(index.get_types().values().filter(|it| is_visible(it.get_name())).map(type_item));
+        items
+    }
+
+    /// Returns the members of the given type, for POUs this includes inherited members, methods and actions
+    fn get_members(&self, type_name: &str) -> Vec<CompletionItem> {
+        let index = &self.project.index;
+        if index.find_pou(type_name).is_some() {
+            let mut items = vec![];
+            let mut seen = FxHashSet::default();
+            let mut current = Some(type_name);
+            while let Some(pou) = current.filter(|it| seen.insert(it.to_lowercase())) {
+                items.extend(
+                    index
+                        .get_pou_members(pou)
+                        .iter()
+                        .filter(|it| is_visible(it.get_name()) && !it.is_return())
+                        .map(variable_item),
+                );
+                items.extend(
+                    index
+                        .get_pous()
+                        .values()
+                        .filter(|it| it.is_method() || it.is_action())
+                        .filter(|it| it.get_parent_pou_name().is_some_and(|it| it.eq_ignore_ascii_case(pou)))
+                        .map(pou_item),
+                );
+                current = index.find_pou(pou).and_then(PouIndexEntry::get_super_class);
+            }
+            return items;
+        }
+
+        match index.find_effective_type_info(type_name) {
+            Some(DataTypeInformation::Struct { members, .. }) => {
+                members.iter().filter(|it| is_visible(it.get_name())).map(variable_item).collect()
+            }
+            Some(DataTypeInformation::Enum { variants, .. }) => variants.iter().map(variable_item).collect(),
+            Some(DataTypeInformation::Pointer { inner_type_name, auto_deref: Some(_), .. }) => {
+                self.get_members(inner_type_name)
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Returns the name of the innermost POU or implementation surrounding the given offset
+    fn get_scope(&self, file: &str, offset: usize) -> Option<String> {
+        let unit = self.get_unit(file)?;
+        let pous = unit.pous.iter().map(|it| (it.name.as_str(), &it.location));
+        let implementations = unit.implementations.iter().map(|it| (it.name.as_str(), &it.location));
+        pous.chain(implementations)
+            .filter(|(_, location)| contains(location, file, offset))
+            .min_by_key(|(_, location)| location.to_range().map(|it| it.len()).unwrap_or(usize::MAX))
+            .map(|(name, _)| name.to_string())
+    }
+
+    fn get_unit(&self, file: &str) -> Option<&CompilationUnit> {
+        self.project.units.iter().map(|it| it.get_unit()).find(|it| it.file.get_name() == Some(file))
+    }
+
+    fn get_occurrences(&self, file: &str) -> impl Iterator<Item = (Occurrence, SourceLocation)> {
+        self.get_unit(file).map(collect_occurrences).unwrap_or_default().into_iter()
+    }
+
+    fn get_symbol(&self, occurrence: Occurrence, index: &Index) -> Option<Symbol> {
+        match occurrence {
+            Occurrence::Node(id) => {
+                self.project.annotations.get_with_id(id).and_then(|it| Symbol::from_annotation(it, index))
+            }
+            Occurrence::TypeReference(name) => Some(Symbol::data_type(&name, index)),
+        }
+    }
+
+    /// Every declared POU, type, variable, member and enum variant with the location of its name
+    fn get_declarations(&self) -> impl Iterator<Item = (Symbol, &SourceLocation)> {
+        let index = &self.project.index;
+        let pous = index
+            .get_pous()
+            .values()
+            .filter(|it| is_visible(it.get_name()))
+            .map(|it| (Symbol::pou(it.get_name()), it.get_location()));
+        let types = index
+            .get_types()
+            .values()
+            .filter(|it| is_visible(it.get_name()))
+            .map(|it| (Symbol::Type(it.get_name().to_lowercase()), &it.location));
+        let members =
+            index.get_types().values().chain(index.get_pou_types().values()).flat_map(|it| {
+                match &it.information {
+                    DataTypeInformation::Struct { members, .. } => members.as_slice(),
+                    DataTypeInformation::Enum { variants, .. } => variants.as_slice(),
+                    _ => &[],
+                }
+            });
+        let variables = index
+            .get_globals()
+            .values()
+            .chain(members)
+            .filter(|it| is_visible(it.get_name()))
+            .map(|it| (Symbol::variable(it.get_qualified_name()), &it.source_location));
+
+        pous.chain(types).chain(variables)
+    }
+
+    fn get_declaration(&self, symbol: &Symbol) -> Option<&SourceLocation> {
+        self.get_declarations().find(|(it, _)| it == symbol).map(|(_, location)| location)
+    }
+
+    fn describe_annotation(&self, annotation: &StatementAnnotation) -> Option<String> {
+        let index = &self.project.index;
+        match annotation {
+            StatementAnnotation::Variable {
+                resulting_type, qualified_name, constant, argument_type, ..
+            } => {
+                let declaration = if index.is_enum_variant(qualified_name) {
+                    format!("{qualified_name} : {resulting_type}")
+                } else {
+                    let block = get_block_name(argument_type.get_inner());
+                    let constant = if *constant { " CONSTANT" } else { "" };
+                    format!("{block}{constant} {qualified_name} : {resulting_type}").trim_start().to_string()
+                };
+                Some(code_block(&declaration))
+            }
+            StatementAnnotation::Function { qualified_name, .. }
+            | StatementAnnotation::FunctionPointer { qualified_name, .. }
+            | StatementAnnotation::Program { qualified_name } => {
+                self.describe_symbol(&Symbol::pou(qualified_name))
+            }
+            StatementAnnotation::Type { type_name } => {
+                self.describe_symbol(&Symbol::data_type(type_name, index))
+            }
+            StatementAnnotation::Value { resulting_type }
+            | StatementAnnotation::Argument { resulting_type, .. } => Some(code_block(resulting_type)),
+            _ => None,
+        }
+    }
+
+    fn describe_symbol(&self, symbol: &Symbol) -> Option<String> {
+        let index = &self.project.index;
+        match symbol {
+            Symbol::Variable(qualified_name) => {
+                let variable = index
+                    .find_fully_qualified_variable(qualified_name)
+                    .or_else(|| index.find_enum_variant_by_qualified_name(qualified_name))?;
+                Some(code_block(&describe_variable(variable, index)))
+            }
+            Symbol::Pou(name) => index.find_pou(name).map(|it| code_block(&describe_pou(it, index))),
+            Symbol::Type(name) => index.find_type(name).map(|it| code_block(&describe_type(it))),
+        }
+    }
+}
+
+/// Parses the given source without aborting on syntax errors, they are reported to the diagnostician
+fn parse_source(
+    source: &SourceCode,
+    linkage: LinkageType,
+    id_provider: IdProvider,
+    diagnostician: &mut Diagnostician,
+) -> Option<CompilationUnit> {
+    match source.get_type() {
+        SourceType::Text => {
+            let (unit, errors) = parse(
+                lexer::lex_with_ids(&source.source, id_provider, SourceLocationFactory::for_source(source)),
+                linkage,
+                source.get_location_str(),
+            );
+            diagnostician.register_file(source.get_location_str().to_string(), source.source.clone());
+            diagnostician.handle(&errors);
+            Some(unit)
+        }
+        SourceType::Xml => plc_cfc::parse_file(source, linkage, id_provider, diagnostician).ok(),
+        SourceType::Unknown => None,
+    }
+}
+
+fn collect_occurrences(unit: &CompilationUnit) -> Vec<(Occurrence, SourceLocation)> {
+    let mut collector = OccurrenceCollector::default();
+    collector.visit_compilation_unit(unit);
+    collector.occurrences
+}
+
+fn contains(location: &SourceLocation, file: &str, offset: usize) -> bool {
+    location.get_file_name() == Some(file)
+        && location.to_range().is_some_and(|it| it.start <= offset && offset <= it.end)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Compiler generated elements are prefixed with `__` and not shown to the user
+fn is_visible(name: &str) -> bool {
+    !name.starts_with("__")
+}
+
+fn code_block(content: &str) -> String {
+    format!("```st\n{content}\n```")
+}
+
+fn get_block_name(variable_type: VariableType) -> &'static str {
+    match variable_type {
+        VariableType::Local => "VAR",
+        VariableType::Temp => "VAR_TEMP",
+        VariableType::Input => "VAR_INPUT",
+        VariableType::Output => "VAR_OUTPUT",
+        VariableType::InOut => "VAR_IN_OUT",
+        VariableType::Global => "VAR_GLOBAL",
+        VariableType::External => "VAR_EXTERNAL",
+        VariableType::Property => "PROPERTY",
+        VariableType::Return => "",
+    }
+}
+
+fn describe_variable(variable: &VariableIndexEntry, index: &Index) -> String {
+    let qualified_name = variable.get_qualified_name();
+    let data_type = variable.get_type_name();
+    if index.is_enum_variant(qualified_name) {
+        return format!("{qualified_name} : {data_type}");
+    }
+    let block = get_block_name(variable.get_declaration_type().get_inner());
+    let constant = if variable.is_constant() { " CONSTANT" } else { "" };
+    format!("{block}{constant} {qualified_name} : {data_type}").trim_start().to_string()
+}
+
+fn describe_pou(pou: &PouIndexEntry, index: &Index) -> String {
+    let kind = match pou {
+        PouIndexEntry::Program { .. } => "PROGRAM",
+        PouIndexEntry::FunctionBlock { .. } => "FUNCTION_BLOCK",
+        PouIndexEntry::Function { .. } => "FUNCTION",
+        PouIndexEntry::Class { .. } => "CLASS",
+        PouIndexEntry::Method { .. } => "METHOD",
+        PouIndexEntry::Action { .. } => "ACTION",
+    };
+    let mut signature = format!("{kind} {}", pou.get_name());
+    if let Some(return_type) = pou.get_return_type().filter(|it| !it.eq_ignore_ascii_case("VOID")) {
+        signature.push_str(&format!(" : {return_type}"));
+    }
+    if let Some(super_class) = pou.get_super_class() {
+        signature.push_str(&format!(" EXTENDS {super_class}"));
+    }
+    let interfaces = pou.get_interfaces();
+    if !interfaces.is_empty() {
+        signature.push_str(&format!(" IMPLEMENTS {}", interfaces.join(", ")));
+    }
+    for parameter in index.get_available_parameters(pou.get_name()) {
+        let block = get_block_name(parameter.get_declaration_type().get_inner());
+        signature.push_str(&format!(
+            "\n    {block} {} : {}",
+            parameter.get_name(),
+            parameter.get_type_name()
+        ));
+    }
+    signature
+}
+
+fn describe_type(data_type: &DataType) -> String {
+    let name = data_type.get_name();
+    match &data_type.information {
+        DataTypeInformation::Struct { members, .. } => {
+            let members = members
+                .iter()
+                .map(|it| format!("\n    {} : {};", it.get_name(), it.get_type_name()))
+                .collect::<String>();
+            format!("TYPE {name} : STRUCT{members}\nEND_STRUCT")
+        }
+        DataTypeInformation::Enum { variants, .. } => {
+            let variants = variants.iter().map(|it| it.get_name()).collect::<Vec<_>>().join(", ");
+            format!("TYPE {name} : ({variants})")
+        }
+        DataTypeInformation::Array { inner_type_name, .. } => {
+            format!("TYPE {name} : ARRAY OF {inner_type_name}")
+        }
+        DataTypeInformation::Pointer { inner_type_name, .. } => {
+            format!("TYPE {name} : REF_TO {inner_type_name}")
+        }
+        DataTypeInformation::Alias { referenced_type, .. }
+        | DataTypeInformation::SubRange { referenced_type, .. } => format!("TYPE {name} : {referenced_type}"),
+        _ => name.to_string(),
+    }
+}
+
+fn variable_item(variable: &VariableIndexEntry) -> CompletionItem {
+    let kind = match variable.get_declaration_type().get_inner() {
+        VariableType::Global => CompletionItemKind::VARIABLE,
+        _ if variable.is_constant() => CompletionItemKind::CONSTANT,
+        _ => CompletionItemKind::FIELD,
+    };
+    CompletionItem {
+        label: variable.get_name().to_string(),
+        kind: Some(kind),
+        detail: Some(variable.get_type_name().to_string()),
+        ..Default::default()
+    }
+}
+
+fn pou_item(pou: &PouIndexEntry) -> CompletionItem {
+    let kind = match pou {
+        PouIndexEntry::Program { .. } => CompletionItemKind::MODULE,
+        PouIndexEntry::FunctionBlock { .. } | PouIndexEntry::Class { .. } => CompletionItemKind::CLASS,
+        PouIndexEntry::Function { .. } => CompletionItemKind::FUNCTION,
+        PouIndexEntry::Method { .. } | PouIndexEntry::Action { .. } => CompletionItemKind::METHOD,
+    };
+    CompletionItem {
+        label: pou.get_call_name().to_string(),
+        kind: Some(kind),
+        detail: pou.get_return_type().map(str::to_string),
+        ..Default::default()
+    }
+}
+
+fn type_item(data_type: &DataType) -> CompletionItem {
+    let kind = match data_type.information {
+        DataTypeInformation::Struct { .. } => CompletionItemKind::STRUCT,
+        DataTypeInformation::Enum { .. } => CompletionItemKind::ENUM,
+        DataTypeInformation::Interface { .. } => CompletionItemKind::INTERFACE,
+        _ => CompletionItemKind::TYPE_PARAMETER,
+    };
+    CompletionItem { label: data_type.get_name().to_string(), kind: Some(kind), ..Default::default() }
+}
@@ -0,0 +1,45 @@
+use std::{cell::RefCell, rc::Rc};
+
+use plc_diagnostics::{
+    diagnostics::Severity,
+    reporter::{DiagnosticReporter, ResolvedDiagnostics},
+};
+
+/// A reporter that keeps the reported diagnostics so they can be published to the client
+/// instead of printing them. Clones share the same storage.
+#[derive(Default, Clone)]
+pub struct DiagnosticCollector {
+    inner: Rc<RefCell<Collected>>,
+}
+
+#[derive(Default)]
+struct Collected {
+    files: Vec<String>,
+    diagnostics: Vec<ResolvedDiagnostics>,
+}
+
+impl DiagnosticCollector {
+    /// Returns the collected diagnostics together with the file they were reported for,
+    /// diagnostics without a file (e.g. internal ones) or with severity `Ignore` are dropped
+    pub fn take(&self) -> Vec<(String, ResolvedDiagnostics)> {
+        let mut inner = self.inner.borrow_mut();
+        let diagnostics = std::mem::take(&mut inner.diagnostics);
+        diagnostics
+            .into_iter()
+            .filter(|it| it.severity != Severity::Ignore)
+            .filter_map(|it| inner.files.get(it.main_location.file_handle).cloned().map(|file| (file, it)))
+            .collect()
+    }
+}
+
+impl DiagnosticReporter for DiagnosticCollector {
+    fn report(&mut self, diagnostics: &[ResolvedDiagnostics]) {
+        self.inner.borrow_mut().diagnostics.extend_from_slice(diagnostics);
+    }
+
+    fn register(&mut self, path: String, _src: String) -> usize {
+        let mut inner = self.inner.borrow_mut();
+        inner.files.push(path);
+        inner.files.len() - 1
+    }
+}
@@ -0,0 +1,27 @@
+//! A language server for Structured Text.
+//!
+//! The server speaks the language server protocol over stdio. It analyses the project described by
+//! the `plc.json` in the workspace root together with the documents opened in the editor and offers
+//! diagnostics, hover information, go-to-definition, find-references and completion.
+
+use lsp_server::Connection;
+
+pub mod analysis;
+pub mod diagnostics;
+pub mod position;
+mod server;
+pub mod workspace;
+
+pub use server::{capabilities, serve};
+
+#[cfg(test)]
+mod tests;
+
+/// Runs the language server on stdin/stdout until the client asks it to exit
+pub fn run_stdio() -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,5 @@
+fn main() -> anyhow::Result<()> {
+    // stdout is reserved for the protocol, logs go to stderr
+    env_logger::init();
+    plc_lsp::run_stdio()
+}
@@ -0,0 +1,77 @@
+use lsp_types::Position;
+
+/// Converts between byte offsets used by the compiler and the line/character positions used by
+/// the language server protocol. Characters are counted in UTF-16 code units as the protocol demands.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    text: String,
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0).chain(text.match_indices('\n').map(|(it, _)| it + 1)).collect();
+        LineIndex { text: text.to_string(), line_starts }
+    }
+
+    /// Returns the position of the given byte offset
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|it| *it <= offset) - 1;
+        let start = self.line_starts[line];
+        let character = self.text.get(start..offset).map(|it| it.encode_utf16().count()).unwrap_or_default();
+        Position::new(line as u32, character as u32)
+    }
+
+    /// Returns the byte offset of the given position, positions past the end of a line are clamped to it
+    pub fn offset(&self, position: Position) -> usize {
+        let Some(start) = self.line_starts.get(position.line as usize).copied() else {
+            return self.text.len();
+        };
+        let end = self.line_starts.get(position.line as usize + 1).copied().unwrap_or(self.text.len());
+        let mut remaining = position.character as usize;
+        for (offset, c) in self.text[start..end].char_indices() {
+            if remaining == 0 || c == '\n' {
+                return start + offset;
+            }
+            remaining = remaining.saturating_sub(c.len_utf16());
+        }
+        end
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::Position;
+
+    use super::LineIndex;
+
+    #[test]
+    fn offsets_and_positions_roundtrip() {
+        let index = LineIndex::new("PROGRAM a\n  x := 1;\nEND_PROGRAM");
+        assert_eq!(index.position(0), Position::new(0, 0));
+        assert_eq!(index.position(12), Position::new(1, 2));
+        assert_eq!(index.position(20), Position::new(2, 0));
+        assert_eq!(index.offset(Position::new(1, 2)), 12);
+        assert_eq!(index.offset(Position::new(2, 3)), 23);
+    }
+
+    #[test]
+    fn characters_are_counted_in_utf16_code_units() {
+        let index = LineIndex::new("s := 'ä😀'; x");
+        // 'ä' is 2 bytes but 1 code unit, '😀' is 4 bytes but 2 code units
+        assert_eq!(index.position(15), Position::new(0, 12));
+        assert_eq!(index.offset(Position::new(0, 12)), 15);
+    }
+
+    #[test]
+    fn positions_past_the_end_of_a_line_are_clamped() {
+        let index = LineIndex::new("ab\ncd");
+        assert_eq!(index.offset(Position::new(0, 10)), 2);
+        assert_eq!(index.offset(Position::new(5, 0)), 5);
+    }
+}
@@ -0,0 +1,286 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+use anyhow::anyhow;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, References, Request as _},
+    CompletionOptions, CompletionParams, CompletionResponse, Diagnostic, DiagnosticSeverity,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability,
+    InitializeParams, Location, MarkupContent, MarkupKind, NumberOrString, OneOf, PublishDiagnosticsParams,
+    Range, ReferenceParams, SaveOptions, ServerCapabilities, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions, TextDocumentSyncSaveOptions,
+    Url,
+};
+use plc_diagnostics::diagnostics::Severity;
+use plc_source::source_location::SourceLocation;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{analysis::Analysis, workspace::Workspace};
+
+/// The features announced to the client
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::FULL),
+            save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions { include_text: Some(false) })),
+            ..Default::default()
+        })),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Runs the initialization handshake and serves requests on the given connection until the client
+/// shuts the server down
+pub fn serve(connection: &Connection) -> anyhow::Result<()> {
+    let params = connection.initialize(serde_json::to_value(capabilities())?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+
+    #[allow(deprecated)]
+    let root = params
+        .workspace_folders
+        .and_then(|it| it.into_iter().next())
+        .map(|it| it.uri)
+        .or(params.root_uri)
+        .and_then(|it| it.to_file_path().ok());
+    log::info!("Starting language server in {root:?}");
+
+    Server::new(Workspace::new(root)).run(connection)
+}
+
+struct Server {
+    workspace: Workspace,
+    /// The current analysis, `None` if a document changed since it was created
+    analysis: Option<Analysis>,
+    /// Files diagnostics were published for, they need to be cleared once they are fixed
+    published: FxHashSet<String>,
+}
+
+impl Server {
+    fn new(workspace: Workspace) -> Self {
+        Server { workspace, analysis: None, published: FxHashSet::default() }
+    }
+
+    fn run(&mut self, connection: &Connection) -> anyhow::Result<()> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(request);
+                    connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => match self.handle_notification(notification) {
+                    Ok(notifications) => {
+                        for notification in notifications {
+                            connection.sender.send(Message::Notification(notification))?;
+                        }
+                    }
+                    Err(err) => log::error!("{err}"),
+                },
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => self.dispatch::<HoverRequest>(request, Server::hover),
+            GotoDefinition::METHOD => self.dispatch::<GotoDefinition>(request, Server::definition),
+            References::METHOD => self.dispatch::<References>(request, Server::references),
+            Completion::METHOD => self.dispatch::<Completion>(request, Server::completion),
+            method => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("Unsupported request {method}"),
+                )
+            }
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err(err) => Response::new_err(id, ErrorCode::InternalError as i32, err.to_string()),
+        }
+    }
+
+    fn dispatch<R: lsp_types::request::Request>(
+        &mut self,
+        request: Request,
+        handler: fn(&mut Server, R::Params) -> anyhow::Result<R::Result>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let (_, params) = request.extract::<R::Params>(R::METHOD)?;
+        Ok(serde_json::to_value(handler(self, params)?)?)
+    }
+
+    /// Updates the workspace, returns the diagnostics to publish if a document was opened or saved
+    fn handle_notification(&mut self, notification: Notification) -> anyhow::Result<Vec<Notification>> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)?;
+                self.workspace.open(&to_path(&params.text_document.uri)?, params.text_document.text);
+                self.analysis = None;
+                self.publish_diagnostics()
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidChangeTextDocumentParams>(DidChangeTextDocument::METHOD)?;
+                // we only announce full synchronization, the last change holds the whole document
+                if let Some(change) = params.content_changes.into_iter().next_back() {
+                    self.workspace.open(&to_path(&params.text_document.uri)?, change.text);
+                    self.analysis = None;
+                }
+                Ok(vec![])
+            }
+            DidSaveTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidSaveTextDocumentParams>(DidSaveTextDocument::METHOD)?;
+                if let Some(text) = params.text {
+                    self.workspace.open(&to_path(&params.text_document.uri)?, text);
+                }
+                self.analysis = None;
+                self.publish_diagnostics()
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)?;
+                self.workspace.close(&to_path(&params.text_document.uri)?);
+                self.analysis = None;
+                Ok(vec![])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Returns the current analysis, re-analysing the workspace if something changed
+    fn get_analysis(&mut self) -> anyhow::Result<&Analysis> {
+        if self.analysis.is_none() {
+            // the compiler is not used to seeing half-written code, a crash must not take the server down
+            let analysis = catch_unwind(AssertUnwindSafe(|| self.workspace.analyse()))
+                .map_err(|_| anyhow!("The analysis of the workspace failed"))?;
+            self.analysis = Some(analysis);
+        }
+        Ok(self.analysis.as_ref().expect("analysis was just created"))
+    }
+
+    fn publish_diagnostics(&mut self) -> anyhow::Result<Vec<Notification>> {
+        let analysis = self.get_analysis()?;
+
+        let mut diagnostics: FxHashMap<String, Vec<Diagnostic>> =
+            analysis.get_files().map(|it| (it.to_string(), vec![])).collect();
+        for (file, diagnostic) in analysis.get_diagnostics() {
+            let range = analysis
+                .get_line_index(file)
+                .zip(diagnostic.main_location.span.to_range())
+                .map(|(lines, range)| Range::new(lines.position(range.start), lines.position(range.end)))
+                .unwrap_or_default();
+            let severity = match diagnostic.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Info | Severity::Ignore => DiagnosticSeverity::INFORMATION,
+            };
+            diagnostics.entry(file.clone()).or_default().push(Diagnostic {
+                range,
+                severity: Some(severity),
+                code: Some(diagnostic.code.clone()).filter(|it| !it.is_empty()).map(NumberOrString::String),
+                source: Some("plc".to_string()),
+                message: diagnostic.message.clone(),
+                ..Default::default()
+            });
+        }
+
+        // files that are no longer part of the analysis get their diagnostics cleared
+        for file in self.published.drain() {
+            diagnostics.entry(file).or_default();
+        }
+
+        let mut notifications = vec![];
+        for (file, diagnostics) in diagnostics {
+            let Ok(uri) = Url::from_file_path(&file) else { continue };
+            if !diagnostics.is_empty() {
+                self.published.insert(file);
+            }
+            let params = PublishDiagnosticsParams { uri, diagnostics, version: None };
+            notifications.push(Notification::new(PublishDiagnostics::METHOD.to_string(), params));
+        }
+        Ok(notifications)
+    }
+
+    fn hover(&mut self, params: HoverParams) -> anyhow::Result<Option<Hover>> {
+        let (file, offset) = self.resolve_position(&params.text_document_position_params)?;
+        let analysis = self.get_analysis()?;
+        Ok(analysis.hover(&file, offset).map(|it| Hover {
+            contents: HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value: it.contents }),
+            range: to_location(analysis, &it.location).map(|it| it.range),
+        }))
+    }
+
+    fn definition(&mut self, params: GotoDefinitionParams) -> anyhow::Result<Option<GotoDefinitionResponse>> {
+        let (file, offset) = self.resolve_position(&params.text_document_position_params)?;
+        let analysis = self.get_analysis()?;
+        Ok(analysis
+            .find_definition(&file, offset)
+            .and_then(|it| to_location(analysis, &it))
+            .map(GotoDefinitionResponse::Scalar))
+    }
+
+    fn references(&mut self, params: ReferenceParams) -> anyhow::Result<Option<Vec<Location>>> {
+        let (file, offset) = self.resolve_position(&params.text_document_position)?;
+        let analysis = self.get_analysis()?;
+        let references = analysis
+            .find_references(&file, offset, params.context.include_declaration)
+            .iter()
+            .filter_map(|it| to_location(analysis, it))
+            .collect();
+        Ok(Some(references))
+    }
+
+    fn completion(&mut self, params: CompletionParams) -> anyhow::Result<Option<CompletionResponse>> {
+        let (file, offset) = self.resolve_position(&params.text_document_position)?;
+        let analysis = self.get_analysis()?;
+        Ok(Some(CompletionResponse::Array(analysis.get_completions(&file, offset))))
+    }
+
+    /// Returns the file name used by the analysis and the byte offset for the given position
+    fn resolve_position(&mut self, params: &TextDocumentPositionParams) -> anyhow::Result<(String, usize)> {
+        let path = to_path(&params.text_document.uri)?;
+        let path = path.canonicalize().unwrap_or(path);
+        let file = path.to_string_lossy().to_string();
+        let analysis = self.get_analysis()?;
+        let offset = analysis
+            .get_line_index(&file)
+            .map(|it| it.offset(params.position))
+            .ok_or_else(|| anyhow!("{file} is not part of the workspace"))?;
+        Ok((file, offset))
+    }
+}
+
+fn to_path(uri: &Url) -> anyhow::Result<PathBuf> {
+    uri.to_file_path().map_err(|_| anyhow!("{uri} is not a file"))
+}
+
+fn to_location(analysis: &Analysis, location: &SourceLocation) -> Option<Location> {
+    let file = location.get_file_name()?;
+    let lines = analysis.get_line_index(file)?;
+    let range = location.to_range()?;
+    let uri = Url::from_file_path(file).ok()?;
+    Some(Location::new(uri, Range::new(lines.position(range.start), lines.position(range.end))))
+}
@@ -0,0 +1,196 @@
+use plc_source::{source_location::SourceLocation, SourceCode};
+use pretty_assertions::assert_eq;
+
+use crate::analysis::Analysis;
+
+const FILE: &str = "main.st";
+
+fn analyse(src: &str) -> Analysis {
+    Analysis::new(&[SourceCode::new(src, FILE)], &[])
+}
+
+/// Returns the offset of the `nth` occurrence of `needle` in `src`
+fn offset_of(src: &str, needle: &str, nth: usize) -> usize {
+    src.match_indices(needle).nth(nth).map(|(it, _)| it).expect("needle not found")
+}
+
+/// Returns the source text a location points to
+fn text_of<'a>(src: &'a str, location: &SourceLocation) -> &'a str {
+    &src[location.to_range().unwrap()]
+}
+
+const PROJECT: &str = r#"
+TYPE Point : STRUCT
+    x : INT;
+    y : INT;
+END_STRUCT
+END_TYPE
+
+VAR_GLOBAL
+    origin : Point;
+END_VAR
+
+FUNCTION_BLOCK Counter
+VAR_INPUT
+    step : DINT;
+END_VAR
+VAR
+    count : DINT;
+END_VAR
+    count := count + step;
+END_FUNCTION_BLOCK
+
+FUNCTION double : DINT
+VAR_INPUT
+    value : DINT;
+END_VAR
+    double := value * 2;
+END_FUNCTION
+
+PROGRAM main
+VAR
+    counter : Counter;
+    result : DINT;
+END_VAR
+    counter(step := 1);
+    result := double(counter.count);
+    origin.x := 5;
+END_PROGRAM
+"#;
+
+#[test]
+fn syntax_and_validation_errors_are_collected_without_aborting() {
+    let src = r#"
+PROGRAM main
+VAR
+    x : INT;
+END_VAR
+    x := ;
+    y := 1;
+END_PROGRAM
+"#;
+    let analysis = analyse(src);
+
+    let diagnostics = analysis.get_diagnostics();
+    assert!(diagnostics.iter().all(|(file, _)| file == FILE));
+    assert!(diagnostics.iter().any(|(_, it)| it.code == "E007"), "{diagnostics:#?}");
+    // validation still ran, the unresolved reference is reported as well
+    assert!(diagnostics.iter().any(|(_, it)| it.message.contains("Could not resolve reference to y")));
+}
+
+#[test]
+fn hover_shows_the_resolved_type_of_a_variable() {
+    let analysis = analyse(PROJECT);
+
+    let hover = analysis.hover(FILE, offset_of(PROJECT, "step;", 0) + 1).unwrap();
+    assert_eq!(hover.contents, "```st\nVAR_INPUT Counter.step : DINT\n```");
+    assert_eq!(text_of(PROJECT, &hover.location), "step");
+
+    let hover = analysis.hover(FILE, offset_of(PROJECT, "count)", 0)).unwrap();
+    assert_eq!(hover.contents, "```st\nVAR Counter.count : DINT\n```");
+    assert_eq!(text_of(PROJECT, &hover.location), "count");
+}
+
+#[test]
+fn hover_shows_the_signature_of_a_function() {
+    let analysis = analyse(PROJECT);
+
+    let hover = analysis.hover(FILE, offset_of(PROJECT, "double(", 0)).unwrap();
+    assert_eq!(hover.contents, "```st\nFUNCTION double : DINT\n    VAR_INPUT value : DINT\n```");
+}
+
+#[test]
+fn hover_shows_the_type_of_literals() {
+    let analysis = analyse(PROJECT);
+
+    let hover = analysis.hover(FILE, offset_of(PROJECT, "2;", 0)).unwrap();
+    assert_eq!(hover.contents, "```st\nDINT\n```");
+}
+
+#[test]
+fn definition_of_a_call_is_the_name_of_the_pou() {
+    let analysis = analyse(PROJECT);
+
+    let definition = analysis.find_definition(FILE, offset_of(PROJECT, "double(", 0) + 2).unwrap();
+    assert_eq!(
+        definition.to_range(),
+        Some(offset_of(PROJECT, "double", 0)..offset_of(PROJECT, "double", 0) + 6)
+    );
+}
+
+#[test]
+fn definition_of_a_member_access_is_the_member_declaration() {
+    let analysis = analyse(PROJECT);
+
+    let definition = analysis.find_definition(FILE, offset_of(PROJECT, "x := 5", 0)).unwrap();
+    let declaration = offset_of(PROJECT, "x : INT", 0);
+    assert_eq!(definition.to_range(), Some(declaration..declaration + 1));
+}
+
+#[test]
+fn definition_of_a_type_reference_is_the_type_declaration() {
+    let analysis = analyse(PROJECT);
+
+    let definition = analysis.find_definition(FILE, offset_of(PROJECT, "Counter;", 0)).unwrap();
+    let declaration = offset_of(PROJECT, "Counter", 0);
+    assert_eq!(definition.to_range(), Some(declaration..declaration + 7));
+}
+
+#[test]
+fn references_are_found_across_pous() {
+    let analysis = analyse(PROJECT);
+
+    let references = analysis.find_references(FILE, offset_of(PROJECT, "count :", 0), true);
+    let references = references.iter().map(|it| it.to_range().unwrap().start).collect::<Vec<_>>();
+    assert_eq!(
+        references,
+        vec![
+            offset_of(PROJECT, "count", 0),
+            offset_of(PROJECT, "count", 1),
+            offset_of(PROJECT, "count", 2),
+            offset_of(PROJECT, "count)", 0),
+        ]
+    );
+
+    let references = analysis.find_references(FILE, offset_of(PROJECT, "count :", 0), false);
+    assert_eq!(references.len(), 3);
+}
+
+#[test]
+fn completion_after_a_dot_offers_the_members() {
+    let src = r#"
+FUNCTION_BLOCK Counter
+VAR_INPUT
+    step : DINT;
+END_VAR
+VAR
+    count : DINT;
+END_VAR
+END_FUNCTION_BLOCK
+
+PROGRAM main
+VAR
+    counter : Counter;
+END_VAR
+    counter.
+END_PROGRAM
+"#;
+    let analysis = analyse(src);
+
+    let completions = analysis.get_completions(FILE, offset_of(src, "counter.", 0) + 8);
+    let labels = completions.iter().map(|it| it.label.as_str()).collect::<Vec<_>>();
+    assert_eq!(labels, vec!["step", "count"]);
+}
+
+#[test]
+fn completion_offers_locals_globals_and_pous() {
+    let analysis = analyse(PROJECT);
+
+    let completions = analysis.get_completions(FILE, offset_of(PROJECT, "origin.x", 0));
+    let labels = completions.iter().map(|it| it.label.as_str()).collect::<Vec<_>>();
+    for expected in ["counter", "result", "origin", "Counter", "double", "main", "Point", "DINT"] {
+        assert!(labels.contains(&expected), "{expected} missing in {labels:?}");
+    }
+    // members of other POUs are not visible
+    assert!(!labels.contains(&"step"));
+}
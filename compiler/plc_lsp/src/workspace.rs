@@ -0,0 +1,92 @@
+use std::path::{Path, PathBuf};
+
+use plc_project::project::{LibraryInformation, Project};
+use plc_source::{SourceCode, SourceContainer};
+use rustc_hash::FxHashMap;
+
+use crate::analysis::Analysis;
+
+/// The files the language server works on: the sources of the `plc.json` in the workspace root
+/// (if any) overlaid with the documents currently opened in the editor.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    root: Option<PathBuf>,
+    documents: FxHashMap<PathBuf, String>,
+}
+
+impl Workspace {
+    pub fn new(root: Option<PathBuf>) -> Self {
+        Workspace { root: root.map(|it| normalize(&it)), documents: FxHashMap::default() }
+    }
+
+    /// Registers the editor's version of a document, it takes precedence over the file on disk
+    pub fn open(&mut self, path: &Path, text: String) {
+        self.documents.insert(normalize(path), text);
+    }
+
+    pub fn close(&mut self, path: &Path) {
+        self.documents.remove(&normalize(path));
+    }
+
+    /// Returns the `plc.json` in the workspace root if there is one
+    pub fn get_config(&self) -> Option<PathBuf> {
+        self.root.as_ref().map(|it| it.join("plc.json")).filter(|it| it.is_file())
+    }
+
+    pub fn analyse(&self) -> Analysis {
+        let (sources, includes) = self.load();
+        Analysis::new(&sources, &includes)
+    }
+
+    /// Returns the sources and includes to analyse
+    fn load(&self) -> (Vec<SourceCode>, Vec<SourceCode>) {
+        let mut sources = vec![];
+        let mut includes = vec![];
+
+        if let Some(config) = self.get_config() {
+            match Project::<PathBuf>::from_config(&config) {
+                Ok(project) => {
+                    sources.extend(project.get_sources().iter().filter_map(|it| self.read(it)));
+                    includes.extend(
+                        project
+                            .get_includes()
+                            .iter()
+                            .chain(project.get_libraries().iter().flat_map(LibraryInformation::get_includes))
+                            .filter_map(|it| self.read(it)),
+                    );
+                }
+                Err(err) => log::warn!("Could not load {}: {err}", config.display()),
+            }
+        }
+
+        // open documents that are not part of the project are analysed alongside it
+        let mut documents = self
+            .documents
+            .iter()
+            .filter(|(path, _)| {
+                !sources
+                    .iter()
+                    .chain(&includes)
+                    .any(|it: &SourceCode| it.path.as_deref() == Some(path.as_path()))
+            })
+            .map(|(path, text)| SourceCode::new(text.clone(), path.clone()))
+            .collect::<Vec<_>>();
+        documents.sort_by(|a, b| a.path.cmp(&b.path));
+        sources.extend(documents);
+
+        (sources, includes)
+    }
+
+    fn read(&self, path: &Path) -> Option<SourceCode> {
+        let path = normalize(path);
+        if let Some(text) = self.documents.get(&path) {
+            return Some(SourceCode::new(text.clone(), path));
+        }
+        path.load_source(None).map_err(|err| log::warn!("Could not read {}: {err}", path.display())).ok()
+    }
+}
+
+/// Paths coming from the client and from the project configuration are compared in their canonical form
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
@@ -11,6 +11,7 @@ use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 
+use plc::formatter::FormatOptions;
 use plc::output::FormatOption;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -63,6 +64,10 @@ pub struct ProjectConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(alias = "format-version")]
     pub format_version: Option<String>,
+    /// Options used by `plc fmt`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatter: Option<FormatOptions>,
 }
 
 impl ProjectConfig {
@@ -136,6 +141,7 @@ mod tests {
 
     use crate::build_config::default_targets;
     use insta::assert_snapshot;
+    use plc::formatter::{CompoundKeywords, FormatOptions, KeywordCase};
     use plc::output::FormatOption;
 
     use super::LibraryConfig;
@@ -314,6 +320,7 @@ mod tests {
             package_commands: vec![],
             version: None,
             format_version: None,
+            formatter: None,
         };
         let proj = ProjectConfig::try_parse(SIMPLE_PROGRAM.into()).unwrap();
 
@@ -438,4 +445,29 @@ mod tests {
             Err(err) => panic!("expected ProjectConfig to be OK, got \n {err}"),
         };
     }
+
+    #[test]
+    fn formatter_options_are_read_from_the_config() {
+        const FORMATTER_OPTIONS: &str = r#"
+        {
+            "name": "MyProject",
+            "files" : [ "file.st" ],
+            "compile_type" : "Shared",
+            "formatter": {
+                "indent_width": 2,
+                "compound_keywords": "joined"
+            }
+        }
+        "#;
+
+        let cfg = ProjectConfig::try_parse(FORMATTER_OPTIONS.into()).unwrap();
+        assert_eq!(
+            cfg.formatter,
+            Some(FormatOptions {
+                indent_width: 2,
+                keyword_case: KeywordCase::Upper,
+                compound_keywords: CompoundKeywords::Joined,
+            })
+        );
+    }
 }
@@ -12,7 +12,7 @@ use crate::{
     object::Object,
 };
 
-use plc::output::FormatOption;
+use plc::{formatter::FormatOptions, output::FormatOption};
 use source_code::{SourceContainer, SourceType};
 
 #[derive(Debug, Clone, Copy)]
@@ -84,6 +84,8 @@ pub struct Project<T: SourceContainer> {
     format: FormatOption,
     /// Output Name
     output: Option<String>,
+    /// Options used when formatting the sources
+    format_options: FormatOptions,
 }
 
 impl<T: SourceContainer> LibraryInformation<T> {
@@ -192,6 +194,7 @@ impl Project<PathBuf> {
             libraries,
             format: project_config.compile_type,
             output: project_config.output,
+            format_options: project_config.formatter.unwrap_or_default(),
             includes: vec![],
             objects: vec![],
             library_paths: vec![],
@@ -231,6 +234,7 @@ impl<S: SourceContainer> Project<S> {
             library_paths: vec![],
             format: FormatOption::default(),
             output: None,
+            format_options: FormatOptions::default(),
         }
     }
 
@@ -319,6 +323,10 @@ impl<S: SourceContainer> Project<S> {
         self.format
     }
 
+    pub fn get_format_options(&self) -> &FormatOptions {
+        &self.format_options
+    }
+
     /// Returns the validation schema used for this project
     pub fn get_validation_schema(&self) -> impl AsRef<str> {
         include_str!("../schema/plc-json.schema")
@@ -1,4 +1,5 @@
 use std::{
+    cell::RefCell,
     fmt::{Debug, Display, Formatter},
     ops::Range,
     path::{Path, PathBuf},
@@ -189,7 +190,7 @@ impl CodeSpan {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug, Serialize)]
 pub enum FileMarker {
     File(&'static str),
     #[default]
@@ -197,6 +198,44 @@ pub enum FileMarker {
     Internal(&'static str),
 }
 
+/// The name of internally generated code, see [`SourceLocation::internal`]
+const INTERNAL: &str = "<internal>";
+
+thread_local! {
+    /// The names deserialized markers may refer to, see [`FileMarker::deserialize_with_names`]
+    static FILE_NAMES: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The deserialized form of a `FileMarker`, its name is resolved to one of the known names
+#[derive(Deserialize)]
+enum OwnedFileMarker {
+    File(String),
+    Undefined,
+    Internal(String),
+}
+
+// Since the name is resolved to a known one, neither a marker nor the AST nodes and index entries
+// containing one need a `'de: 'static` bound on their `Deserialize` implementation. They can be
+// deserialized from owned input (e.g. `serde_json::from_value`), which is what the build cache reads
+// its entries with.
+impl<'de> Deserialize<'de> for FileMarker {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let resolve = |name: String| {
+            if name == INTERNAL {
+                return Ok(INTERNAL);
+            }
+            FILE_NAMES
+                .with(|it| it.borrow().iter().copied().find(|it| *it == name))
+                .ok_or_else(|| serde::de::Error::custom(format!("Unknown file `{name}`")))
+        };
+        match OwnedFileMarker::deserialize(deserializer)? {
+            OwnedFileMarker::File(name) => resolve(name).map(FileMarker::File),
+            OwnedFileMarker::Undefined => Ok(FileMarker::Undefined),
+            OwnedFileMarker::Internal(name) => resolve(name).map(FileMarker::Internal),
+        }
+    }
+}
+
 impl From<&'static str> for FileMarker {
     fn from(value: &'static str) -> Self {
         Self::File(value)
@@ -234,10 +273,19 @@ impl FileMarker {
     pub fn is_internal(&self) -> bool {
         matches!(self, Self::Internal(_))
     }
+
+    /// Runs `deserialize`, resolving the names of the markers it deserializes to the given ones. A marker
+    /// naming any other file fails to deserialize, so names are never allocated for the rest of the
+    /// process.
+    pub fn deserialize_with_names<T>(names: &[&'static str], deserialize: impl FnOnce() -> T) -> T {
+        let previous = FILE_NAMES.with(|it| it.replace(names.to_vec()));
+        let result = deserialize();
+        FILE_NAMES.with(|it| it.replace(previous));
+        result
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct SourceLocation {
     span: CodeSpan,
     /// the name of the file if available. if there is no file available
@@ -351,6 +399,28 @@ impl SourceLocation {
         SourceLocation { span, file: self.file }
     }
 
+    /// returns an empty location at the start of this location, e.g. to insert text in front of it.
+    /// Locations which do not point into a text are returned as they are
+    pub fn get_start(&self) -> SourceLocation {
+        match &self.span {
+            CodeSpan::Range(range) => {
+                SourceLocation { span: CodeSpan::Range(range.start..range.start), file: self.file }
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// returns an empty location at the end of this location, e.g. to insert text after it.
+    /// Locations which do not point into a text are returned as they are
+    pub fn get_end(&self) -> SourceLocation {
+        match &self.span {
+            CodeSpan::Range(range) => {
+                SourceLocation { span: CodeSpan::Range(range.end..range.end), file: self.file }
+            }
+            _ => self.clone(),
+        }
+    }
+
     /// converts this SourceRange into a Range
     pub fn to_range(&self) -> Option<Range<usize>> {
         self.span.to_range()
@@ -377,7 +447,7 @@ impl SourceLocation {
     /// creates a SymbolLocation with undefined source_range used for
     /// symbols that are created by the compiler on-the-fly.
     pub fn internal() -> Self {
-        SourceLocation { span: CodeSpan::None, file: FileMarker::Internal("<internal>") }
+        SourceLocation { span: CodeSpan::None, file: FileMarker::Internal(INTERNAL) }
     }
 
     /// creates an internal SymbolLocation but specifies the CompilationUnit/File it was encountered in.
@@ -463,7 +533,7 @@ mod tests {
     use crate::source_location::{NewLines, TextLocation};
     use insta::assert_debug_snapshot;
 
-    use super::{CodeSpan, SourceLocation};
+    use super::{CodeSpan, FileMarker, SourceLocation};
 
     #[test]
     fn new_lines_test_empty_string() {
@@ -572,4 +642,32 @@ mod tests {
         let loc2 = SourceLocation { file: None.into(), span: CodeSpan::None };
         assert_debug_snapshot!(loc1.span(&loc2));
     }
+
+    #[test]
+    fn file_markers_can_be_deserialized_from_owned_input() {
+        let name = "src/main.st";
+        let file: FileMarker = name.into();
+        let json = serde_json::to_string(&file).unwrap();
+
+        let deserialized = FileMarker::deserialize_with_names(&["src/other.st", name], || {
+            serde_json::from_str::<FileMarker>(&String::from(json.as_str())).unwrap()
+        });
+        assert_eq!(deserialized, file);
+        // the name is resolved to the given one instead of being allocated
+        assert!(std::ptr::eq(deserialized.get_name().unwrap(), name));
+
+        let internal = serde_json::to_string(&SourceLocation::internal()).unwrap();
+        assert!(serde_json::from_str::<SourceLocation>(&internal).unwrap().is_internal());
+    }
+
+    #[test]
+    fn file_markers_of_unknown_files_cannot_be_deserialized() {
+        let json = serde_json::to_string(&FileMarker::from("src/main.st")).unwrap();
+
+        assert!(serde_json::from_str::<FileMarker>(&json).is_err());
+        let result = FileMarker::deserialize_with_names(&["src/other.st"], || {
+            serde_json::from_str::<FileMarker>(&json)
+        });
+        assert!(result.is_err());
+    }
 }
@@ -12,6 +12,12 @@ pub fn internal_type_name<T: AsRef<str> + Display>(prefix: T, original_type_name
     format!("__{prefix}{original_type_name}")
 }
 
+/// Returns the name of the global variable holding a retain variable of a program in the form of
+/// `__<pou_name>_<variable_name>__retain`.
+pub fn retain_variable_name<T: AsRef<str> + Display>(pou_name: T, variable_name: T) -> String {
+    format!("__{pou_name}_{variable_name}__retain")
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -23,4 +29,9 @@ mod tests {
     fn internal_type_name() {
         assert_eq!(super::internal_type_name("POINTER_TO_", "foo"), "__POINTER_TO_foo");
     }
+
+    #[test]
+    fn retain_variable_name() {
+        assert_eq!(super::retain_variable_name("main", "x"), "__main_x__retain");
+    }
 }
@@ -0,0 +1,2152 @@
+//! External declarations of the functions and types of a C header, written by `plc generate bindings` so
+//! C libraries can be called from ST without declaring their interfaces by hand.
+//!
+//! The header is read as it is, the preprocessor is not run. Conditional directives are evaluated with the
+//! macros defined by the header itself, macros defined elsewhere (e.g. by the C compiler) are undefined.
+//! Object-like macros defining an integer are declared as constants, all other directives are ignored. The
+//! declarations are mapped as follows:
+//!
+//! | C                                         | ST                                                    |
+//! |-------------------------------------------|-------------------------------------------------------|
+//! | function                                  | `{external}` `FUNCTION`                               |
+//! | `...`                                     | `args : ...` (`DataType::VarArgs`)                    |
+//! | `const char *` parameter                  | `VAR_INPUT {ref}` parameter of type `STRING`          |
+//! | `struct`                                  | `STRUCT`                                              |
+//! | anonymous `struct` of a member            | `STRUCT` named after the struct and the member, e.g. `outer_position` |
+//! | `enum`                                    | enum with the values of its variants                  |
+//! | `typedef` of an elementary type or pointer | alias                                                |
+//! | `T name[N]`                               | `ARRAY[0..N-1] OF T`, `STRING[N-1]` for `char`        |
+//! | `T *`                                     | `REF_TO T`, `REF_TO BYTE` for `void *`, function pointers and incomplete types |
+//! | anonymous `enum`, integer macro           | `VAR_GLOBAL CONSTANT`                                 |
+//!
+//! Integer types are mapped for LP64 targets, i.e. `long` is 64 bit wide. Declarations which would not
+//! have the ABI of their C counterpart, like unions, bit fields, packed structs or structs passed by
+//! value, are left out and reported.
+
+use std::{fmt::Write, ops::Range};
+
+use logos::Logos;
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::{source_location::SourceLocationFactory, SourceCode, SourceContainer};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{builtins, lexer::Token, typesystem::get_builtin_types};
+
+/// The ST type of pointers whose target cannot be declared
+const UNTYPED_POINTER: &str = "REF_TO BYTE";
+
+/// The name of the parameter taking the variadic arguments
+const VARIADIC_PARAMETER: &str = "args";
+
+/// The ST types of the typedefs of the C standard library
+const STANDARD_TYPEDEFS: &[(&str, &str)] = &[
+    ("int8_t", "SINT"),
+    ("uint8_t", "USINT"),
+    ("int16_t", "INT"),
+    ("uint16_t", "UINT"),
+    ("int32_t", "DINT"),
+    ("uint32_t", "UDINT"),
+    ("int64_t", "LINT"),
+    ("uint64_t", "ULINT"),
+    ("intptr_t", "LINT"),
+    ("uintptr_t", "ULINT"),
+    ("ptrdiff_t", "LINT"),
+    ("size_t", "ULINT"),
+    ("ssize_t", "LINT"),
+    ("wchar_t", "DINT"),
+    ("char16_t", "WCHAR"),
+    ("char32_t", "UDINT"),
+];
+
+/// Words which do not change the type or the ABI of a declaration
+const IGNORED_WORDS: &[&str] = &[
+    "inline",
+    "__inline",
+    "__inline__",
+    "_Noreturn",
+    "__extension__",
+    "register",
+    "auto",
+    "volatile",
+    "__volatile__",
+    "restrict",
+    "__restrict",
+    "__restrict__",
+    "_Nonnull",
+    "_Nullable",
+    "__ptr32",
+    "__ptr64",
+    "__cdecl",
+];
+
+/// Words which attach an attribute to a declaration, followed by its arguments in parentheses
+const ATTRIBUTE_WORDS: &[&str] =
+    &["__attribute__", "__attribute", "__declspec", "__asm__", "__asm", "asm", "_Alignas"];
+
+/// Words of the elementary types
+const ELEMENTARY_WORDS: &[&str] = &[
+    "void",
+    "char",
+    "short",
+    "int",
+    "long",
+    "float",
+    "double",
+    "signed",
+    "__signed__",
+    "unsigned",
+    "_Bool",
+    "bool",
+    "_Complex",
+];
+
+/// Returns the external declarations of the functions and types of the given C header, and a diagnostic
+/// for every declaration which cannot be declared in ST
+pub fn generate_bindings(header: &SourceCode) -> (String, Vec<Diagnostic>) {
+    let mut parser = Parser::new(header);
+    while parser.position < parser.tokens.len() {
+        let start = parser.position;
+        if let Err(range) = parser.parse_external_declaration() {
+            parser.report(
+                range,
+                "Cannot read this declaration, it is either not valid C or not supported by the bindings generator",
+            );
+            parser.recover(start);
+        }
+    }
+    parser.finish(header.get_location_str())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Identifier,
+    Number,
+    Literal,
+    Punctuation,
+}
+
+#[derive(Debug, Clone)]
+struct CToken {
+    kind: TokenKind,
+    range: Range<usize>,
+}
+
+/// The tokens of a header and what its preprocessor directives declare
+#[derive(Default)]
+struct Tokens {
+    tokens: Vec<CToken>,
+    /// The object-like macros as name and range of their value
+    macros: Vec<(String, Range<usize>)>,
+    /// The offsets after which the packing of structs changes, `None` restores the default
+    packing: Vec<(usize, Option<String>)>,
+}
+
+/// The state of the preprocessor directives read so far
+#[derive(Default)]
+struct Directives {
+    packing_stack: Vec<Option<String>>,
+    /// The open conditional directives, the innermost last
+    conditionals: Vec<Conditional>,
+    /// The names of all macros defined so far, including function-like macros
+    defined: FxHashSet<String>,
+}
+
+/// An open `#if`, `#ifdef` or `#ifndef`
+struct Conditional {
+    /// Whether the lines around the conditional are read
+    is_parent_active: bool,
+    /// Whether the lines of the current branch are read
+    is_active: bool,
+    /// Whether one of the branches so far was read, so the following ones are skipped
+    was_taken: bool,
+}
+
+impl Directives {
+    /// Whether the current line is read, i.e. it is not in a skipped branch of a conditional
+    fn is_active(&self) -> bool {
+        self.conditionals.last().is_none_or(|it| it.is_active)
+    }
+
+    /// Whether the condition of the given conditional directive holds
+    fn holds(
+        &self,
+        directive: &str,
+        condition: &str,
+        source: &str,
+        macros: &[(String, Range<usize>)],
+    ) -> bool {
+        // a condition may continue on the next lines
+        let condition = condition.replace('\\', " ");
+        match directive {
+            "ifdef" | "elifdef" => self.defined.contains(condition.trim()),
+            "ifndef" | "elifndef" => !self.defined.contains(condition.trim()),
+            _ => evaluate_condition(&condition, &evaluate_macros(source, macros), &self.defined),
+        }
+    }
+}
+
+/// Splits the source into tokens, skipping comments and reading the preprocessor directives
+fn tokenize(source: &str) -> Tokens {
+    let bytes = source.as_bytes();
+    let mut tokens = Tokens::default();
+    let mut directives = Directives::default();
+    let mut position = 0;
+    // only whitespace since the start of the line, so a `#` starts a directive
+    let mut line_start = true;
+    while position < bytes.len() {
+        let start = position;
+        let current = bytes[position];
+        let next = bytes.get(position + 1).copied();
+        let kind = match current {
+            b'\n' => {
+                line_start = true;
+                position += 1;
+                continue;
+            }
+            _ if current.is_ascii_whitespace() => {
+                position += 1;
+                continue;
+            }
+            b'/' if next == Some(b'/') => {
+                position = source[position..].find('\n').map_or(bytes.len(), |it| position + it);
+                continue;
+            }
+            b'/' if next == Some(b'*') => {
+                position = source[position + 2..].find("*/").map_or(bytes.len(), |it| position + it + 4);
+                continue;
+            }
+            b'#' if line_start => {
+                // a directive continues on the next line if the line ends with a backslash
+                let mut end = position;
+                while let Some(offset) = source[end..].find('\n') {
+                    end += offset;
+                    if !source[..end].trim_end_matches('\r').ends_with('\\') {
+                        break;
+                    }
+                    end += 1;
+                }
+                if !source[end..].contains('\n') {
+                    end = bytes.len();
+                }
+                read_directive(source, position + 1..end, &mut tokens, &mut directives);
+                position = end;
+                continue;
+            }
+            _ if current.is_ascii_alphabetic() || current == b'_' => {
+                while position < bytes.len()
+                    && (bytes[position].is_ascii_alphanumeric() || bytes[position] == b'_')
+                {
+                    position += 1;
+                }
+                TokenKind::Identifier
+            }
+            _ if current.is_ascii_digit()
+                || (current == b'.' && next.is_some_and(|it| it.is_ascii_digit())) =>
+            {
+                position += 1;
+                while position < bytes.len() {
+                    let it = bytes[position];
+                    let is_exponent_sign = matches!(it, b'+' | b'-')
+                        && matches!(bytes[position - 1], b'e' | b'E' | b'p' | b'P')
+                        && !source[start..position].starts_with("0x");
+                    if !(it.is_ascii_alphanumeric() || it == b'_' || it == b'.' || is_exponent_sign) {
+                        break;
+                    }
+                    position += 1;
+                }
+                TokenKind::Number
+            }
+            b'"' | b'\'' => {
+                position += 1;
+                while position < bytes.len() && bytes[position] != current && bytes[position] != b'\n' {
+                    position += if bytes[position] == b'\\' { 2 } else { 1 };
+                }
+                position = (position + 1).min(bytes.len());
+                TokenKind::Literal
+            }
+            _ if source[position..].starts_with("...") => {
+                position += 3;
+                TokenKind::Punctuation
+            }
+            _ => {
+                position += source[position..].chars().next().map_or(1, char::len_utf8);
+                TokenKind::Punctuation
+            }
+        };
+        line_start = false;
+        if directives.is_active() {
+            tokens.tokens.push(CToken { kind, range: start..position });
+        }
+    }
+    tokens
+}
+
+/// Reads the directive in the given range, i.e. the text following the `#`
+fn read_directive(source: &str, range: Range<usize>, tokens: &mut Tokens, directives: &mut Directives) {
+    let text = &source[range.clone()];
+    let directive = text.trim_start();
+    let offset = range.start + text.len() - directive.len();
+    let name =
+        &directive[..directive.find(|it: char| !it.is_ascii_alphanumeric()).unwrap_or(directive.len())];
+    let arguments = &directive[name.len()..];
+    match name {
+        "if" | "ifdef" | "ifndef" => {
+            let is_parent_active = directives.is_active();
+            let is_active = is_parent_active && directives.holds(name, arguments, source, &tokens.macros);
+            directives.conditionals.push(Conditional { is_parent_active, is_active, was_taken: is_active });
+        }
+        "elif" | "elifdef" | "elifndef" => {
+            let Some(&Conditional { is_parent_active, was_taken, .. }) = directives.conditionals.last()
+            else {
+                return;
+            };
+            let is_active =
+                is_parent_active && !was_taken && directives.holds(name, arguments, source, &tokens.macros);
+            if let Some(conditional) = directives.conditionals.last_mut() {
+                conditional.is_active = is_active;
+                conditional.was_taken |= is_active;
+            }
+        }
+        "else" => {
+            if let Some(conditional) = directives.conditionals.last_mut() {
+                conditional.is_active = conditional.is_parent_active && !conditional.was_taken;
+                conditional.was_taken = true;
+            }
+        }
+        "endif" => {
+            directives.conditionals.pop();
+        }
+        _ if !directives.is_active() => {}
+        "define" => {
+            let name_offset = offset + name.len() + arguments.len() - arguments.trim_start().len();
+            let name_length = source[name_offset..range.end]
+                .find(|it: char| !(it.is_ascii_alphanumeric() || it == '_'))
+                .unwrap_or(range.end - name_offset);
+            let name = &source[name_offset..name_offset + name_length];
+            if name.is_empty() {
+                return;
+            }
+            directives.defined.insert(name.to_string());
+            tokens.macros.retain(|(it, _)| it != name);
+            // function-like macros are not declarations
+            if !source[name_offset + name_length..range.end].starts_with('(') {
+                tokens.macros.push((name.to_string(), name_offset + name_length..range.end));
+            }
+        }
+        "undef" => {
+            let name = arguments.trim();
+            directives.defined.remove(name);
+            tokens.macros.retain(|(it, _)| it != name);
+        }
+        "pragma" => {
+            let Some(arguments) = arguments.trim_start().strip_prefix("pack") else { return };
+            let arguments = arguments.trim().trim_start_matches('(').trim_end_matches(')');
+            let mut arguments = arguments.split(',').map(str::trim).filter(|it| !it.is_empty());
+            let current = tokens.packing.last().and_then(|(_, it)| it.clone());
+            let packing = match arguments.next() {
+                Some("push") => {
+                    directives.packing_stack.push(current.clone());
+                    arguments.next().map(str::to_string).or(current)
+                }
+                Some("pop") => directives.packing_stack.pop().flatten(),
+                Some(value) => Some(value.to_string()),
+                None => None,
+            };
+            tokens.packing.push((range.end, packing));
+        }
+        _ => {}
+    }
+}
+
+/// A type of the header
+#[derive(Debug, Clone)]
+enum CType {
+    /// An elementary type or an alias declared in ST
+    Named(String),
+    /// A `char`, which is mapped to a string where possible
+    Char {
+        is_const: bool,
+    },
+    Void,
+    /// A struct, union or enum referred to by its tag, e.g. `struct point`
+    Tagged(String),
+    /// A struct declared in ST
+    Struct(String),
+    /// An enum declared in ST
+    Enum(String),
+    /// A type which is not declared in the header, only usable behind a pointer
+    Opaque(String),
+    Pointer(Box<CType>),
+    /// An array and its length, if it has one
+    Array(Box<CType>, Option<i128>),
+    Function(Box<FunctionType>),
+    /// A struct or enum defined by the declaration being read, which is named by the declaration
+    Definition(Box<Definition>),
+    /// A type which cannot be declared in ST, and why
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone)]
+struct FunctionType {
+    return_type: CType,
+    parameters: Vec<Parameter>,
+    is_variadic: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Parameter {
+    name: Option<String>,
+    data_type: CType,
+    range: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Member {
+    name: String,
+    data_type: CType,
+    range: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum Definition {
+    Struct {
+        /// `struct` or `union`
+        keyword: String,
+        tag: Option<String>,
+        members: Vec<Member>,
+        /// Why the struct cannot be declared in ST, and where
+        problem: Option<(String, Range<usize>)>,
+        range: Range<usize>,
+    },
+    Enum {
+        tag: Option<String>,
+        variants: Vec<(String, i128)>,
+        problem: Option<(String, Range<usize>)>,
+        range: Range<usize>,
+    },
+}
+
+/// A declaration of the generated file
+enum Item {
+    Constant { name: String, value: i128 },
+    Struct { name: String, members: Vec<Member> },
+    Enum { name: String, variants: Vec<(String, i128)> },
+    Alias { name: String, data_type: CType, range: Range<usize> },
+    Function { name: String, function: FunctionType, range: Range<usize> },
+}
+
+struct Specifiers {
+    is_typedef: bool,
+    is_static: bool,
+    data_type: CType,
+}
+
+/// The name of a declarator and its location, if it has one, and its type
+type Declarator = (Option<(String, Range<usize>)>, CType);
+
+enum Suffix {
+    Array(Result<Option<i128>, String>),
+    Function(FunctionType),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ParameterKind {
+    Input,
+    Reference,
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<CToken>,
+    position: usize,
+    factory: SourceLocationFactory,
+    packing: Vec<(usize, Option<String>)>,
+    /// Macros which expand to nothing or to an attribute, and are skipped like one
+    ignored_macros: FxHashSet<String>,
+    /// The values of integer macros and enum variants
+    constants: FxHashMap<String, i128>,
+    /// The integer macros, which are declared if their name is still free once the header is read
+    macros: Vec<(String, i128)>,
+    /// The types declared by the header by their C name, e.g. `struct point` or the name of a typedef
+    types: FxHashMap<String, CType>,
+    /// The declared ST names in upper case, since ST is not case-sensitive
+    names: FxHashSet<String>,
+    builtin_types: FxHashSet<String>,
+    /// The structs which cannot be declared in ST, by their name in upper case
+    rejected: FxHashSet<String>,
+    items: Vec<Item>,
+    /// The depth of the open `extern "C" {` blocks
+    linkage_depth: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(header: &'a SourceCode) -> Self {
+        let source = header.source.as_str();
+        let Tokens { tokens, macros, packing } = tokenize(source);
+        let mut parser = Parser {
+            source,
+            tokens,
+            position: 0,
+            factory: SourceLocationFactory::for_source(header),
+            packing,
+            ignored_macros: FxHashSet::default(),
+            constants: FxHashMap::default(),
+            macros: vec![],
+            types: FxHashMap::default(),
+            names: FxHashSet::default(),
+            builtin_types: get_builtin_types().iter().map(|it| it.get_name().to_uppercase()).collect(),
+            rejected: FxHashSet::default(),
+            items: vec![],
+            linkage_depth: 0,
+            diagnostics: vec![],
+        };
+        parser.constants = evaluate_macros(source, &macros);
+        for (name, range) in macros {
+            let value = source[range].trim();
+            let value_tokens = tokenize(value).tokens;
+            // a macro expanding to nothing, an attribute or other ignored macros is ignored too
+            if value_tokens.iter().all(|it| parser.ignored_macros.contains(&value[it.range.clone()]))
+                || ATTRIBUTE_WORDS
+                    .iter()
+                    .any(|it| value.starts_with(it) && value[it.len()..].starts_with(['(', ' ']))
+            {
+                parser.ignored_macros.insert(name);
+            } else if let Some(&value) = parser.constants.get(&name) {
+                parser.macros.push((name, value));
+            }
+        }
+        parser
+    }
+
+    fn text(&self, offset: usize) -> &'a str {
+        self.tokens.get(self.position + offset).map_or("", |it| &self.source[it.range.clone()])
+    }
+
+    fn kind(&self, offset: usize) -> Option<TokenKind> {
+        self.tokens.get(self.position + offset).map(|it| it.kind)
+    }
+
+    /// The range of the current token, or an empty range at the end of the header
+    fn range(&self) -> Range<usize> {
+        self.tokens.get(self.position).map_or(self.source.len()..self.source.len(), |it| it.range.clone())
+    }
+
+    /// The range from the given start to the end of the previous token
+    fn range_from(&self, start: &Range<usize>) -> Range<usize> {
+        let end = self.tokens.get(self.position.wrapping_sub(1)).map_or(start.end, |it| it.range.end);
+        start.start..end.max(start.end)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        let matches = self.text(0) == text && self.position < self.tokens.len();
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), Range<usize>> {
+        if self.eat(text) {
+            Ok(())
+        } else {
+            Err(self.range())
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<(String, Range<usize>), Range<usize>> {
+        if self.kind(0) != Some(TokenKind::Identifier) {
+            return Err(self.range());
+        }
+        let identifier = (self.text(0).to_string(), self.range());
+        self.position += 1;
+        Ok(identifier)
+    }
+
+    fn report(&mut self, range: Range<usize>, message: impl Into<String>) {
+        self.diagnostics.push(
+            Diagnostic::new(message).with_error_code("E170").with_location(self.factory.create_range(range)),
+        );
+    }
+
+    /// Skips the current token and everything up to its closing bracket
+    fn skip_balanced(&mut self) -> Result<(), Range<usize>> {
+        let start = self.range();
+        let mut depth = 0;
+        while self.position < self.tokens.len() {
+            match self.text(0) {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                _ => {}
+            }
+            self.position += 1;
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+        Err(start)
+    }
+
+    /// Returns the tokens up to the given closing token, which is skipped
+    fn take_until(&mut self, ends: &[&str]) -> Result<Range<usize>, Range<usize>> {
+        let start = self.position;
+        let mut depth = 0;
+        while self.position < self.tokens.len() {
+            let text = self.text(0);
+            if depth == 0 && ends.contains(&text) {
+                return Ok(start..self.position);
+            }
+            match text {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                _ => {}
+            }
+            self.position += 1;
+        }
+        Err(self.range())
+    }
+
+    /// Skips the tokens of the declaration which could not be read
+    fn recover(&mut self, start: usize) {
+        self.position = start;
+        let mut depth = 0;
+        while self.position < self.tokens.len() {
+            let text = self.text(0);
+            self.position += 1;
+            match text {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" => depth -= 1,
+                "}" => {
+                    depth -= 1;
+                    if depth <= 0 {
+                        self.eat(";");
+                        return;
+                    }
+                }
+                ";" if depth <= 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips attributes, returns whether one of them changes the layout of a struct
+    fn skip_attributes(&mut self) -> Result<bool, Range<usize>> {
+        let mut changes_layout = false;
+        loop {
+            let text = self.text(0);
+            if ATTRIBUTE_WORDS.contains(&text) && self.text(1) == "(" {
+                self.position += 1;
+                let start = self.position;
+                self.skip_balanced()?;
+                changes_layout |= text == "_Alignas"
+                    || self.tokens[start..self.position].iter().any(|it| {
+                        matches!(
+                            &self.source[it.range.clone()],
+                            "packed" | "__packed__" | "aligned" | "__aligned__"
+                        )
+                    });
+            } else if self.ignored_macros.contains(text) {
+                self.position += 1;
+            } else {
+                return Ok(changes_layout);
+            }
+        }
+    }
+
+    /// Skips the qualifiers of a pointer
+    fn skip_qualifiers(&mut self) -> Result<(), Range<usize>> {
+        loop {
+            self.skip_attributes()?;
+            if matches!(self.text(0), "const" | "__const") || IGNORED_WORDS.contains(&self.text(0)) {
+                self.position += 1;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_external_declaration(&mut self) -> Result<(), Range<usize>> {
+        if self.eat(";") {
+            return Ok(());
+        }
+        if self.text(0) == "extern" && self.kind(1) == Some(TokenKind::Literal) {
+            self.position += 2;
+            if self.eat("{") {
+                self.linkage_depth += 1;
+            }
+            return Ok(());
+        }
+        if self.linkage_depth > 0 && self.eat("}") {
+            self.linkage_depth -= 1;
+            return Ok(());
+        }
+        self.parse_declaration()
+    }
+
+    fn parse_declaration(&mut self) -> Result<(), Range<usize>> {
+        let specifiers = self.parse_specifiers()?;
+        let mut base = specifiers.data_type;
+        if let CType::Definition(definition) = base {
+            // `typedef struct { ... } name;` names the struct after the typedef
+            let typedef_name = (specifiers.is_typedef
+                && self.kind(0) == Some(TokenKind::Identifier)
+                && matches!(self.text(1), ";" | ","))
+            .then(|| self.text(0).to_string());
+            base = self.declare(*definition, typedef_name.clone());
+            if let Some(name) = typedef_name {
+                self.types.insert(name, base.clone());
+                self.position += 1;
+                if !self.eat(",") {
+                    return self.expect(";");
+                }
+            }
+        }
+        if self.eat(";") {
+            return Ok(());
+        }
+
+        loop {
+            let (name, data_type) = self.parse_declarator(base.clone())?;
+            let Some((name, range)) = name else { return Err(self.range()) };
+            self.skip_attributes()?;
+            match data_type {
+                _ if specifiers.is_typedef => self.declare_typedef(name, data_type, range),
+                CType::Function(function) => {
+                    let is_definition = self.text(0) == "{";
+                    if is_definition {
+                        self.skip_balanced()?;
+                    }
+                    if specifiers.is_static {
+                        self.report(
+                            range,
+                            format!("Cannot declare '{name}' in ST, static functions are not exported by the library"),
+                        );
+                    } else {
+                        self.declare_function(name, *function, range);
+                    }
+                    if is_definition {
+                        return Ok(());
+                    }
+                }
+                _ => {
+                    self.report(
+                        range,
+                        format!("Cannot declare '{name}' in ST, only the functions and types of a header are declared"),
+                    );
+                    if self.eat("=") {
+                        self.take_until(&[",", ";"])?;
+                    }
+                }
+            }
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect(";")
+    }
+
+    fn parse_specifiers(&mut self) -> Result<Specifiers, Range<usize>> {
+        let start = self.range();
+        let mut specifiers = Specifiers { is_typedef: false, is_static: false, data_type: CType::Void };
+        let mut words: Vec<&str> = vec![];
+        let mut data_type = None;
+        let mut is_const = false;
+        loop {
+            self.skip_attributes()?;
+            if self.kind(0) != Some(TokenKind::Identifier) {
+                break;
+            }
+            match self.text(0) {
+                "typedef" => specifiers.is_typedef = true,
+                "static" => specifiers.is_static = true,
+                "extern" => {}
+                "const" | "__const" => is_const = true,
+                it if IGNORED_WORDS.contains(&it) => {}
+                "struct" | "union" if data_type.is_none() => {
+                    data_type = Some(self.parse_struct()?);
+                    continue;
+                }
+                "enum" if data_type.is_none() => {
+                    data_type = Some(self.parse_enum()?);
+                    continue;
+                }
+                it if ELEMENTARY_WORDS.contains(&it) => words.push(it),
+                // the first other identifier is the name of a type, the next one the name of the declaration
+                it if words.is_empty() && data_type.is_none() => data_type = Some(self.lookup_type(it)),
+                _ => break,
+            }
+            self.position += 1;
+        }
+
+        specifiers.data_type = match (data_type, words.is_empty()) {
+            (Some(data_type), true) => data_type,
+            (None, false) => elementary_type(&words, is_const),
+            _ => return Err(start),
+        };
+        Ok(specifiers)
+    }
+
+    /// Returns the type with the given name, or an opaque type if the header does not declare it
+    fn lookup_type(&self, name: &str) -> CType {
+        if let Some(data_type) = self.types.get(name) {
+            return data_type.clone();
+        }
+        if let Some((_, st_type)) = STANDARD_TYPEDEFS.iter().find(|(it, _)| *it == name) {
+            return CType::Named(st_type.to_string());
+        }
+        CType::Opaque(name.to_string())
+    }
+
+    /// Follows a reference to a struct or enum by its tag
+    fn resolve(&self, data_type: &CType) -> CType {
+        match data_type {
+            CType::Tagged(key) => self.types.get(key).cloned().unwrap_or_else(|| {
+                CType::Opaque(key.split_once(' ').map_or(key.as_str(), |(_, tag)| tag).to_string())
+            }),
+            _ => data_type.clone(),
+        }
+    }
+
+    fn parse_struct(&mut self) -> Result<CType, Range<usize>> {
+        let start = self.range();
+        let keyword = self.text(0);
+        self.position += 1;
+        let mut changes_layout = self.skip_attributes()?;
+        let tag = if self.kind(0) == Some(TokenKind::Identifier) {
+            Some(self.expect_identifier()?.0)
+        } else {
+            None
+        };
+        if self.text(0) != "{" {
+            let Some(tag) = tag else { return Err(self.range()) };
+            return Ok(CType::Tagged(format!("{keyword} {tag}")));
+        }
+
+        self.position += 1;
+        let mut members = vec![];
+        let mut problem = None;
+        while !self.eat("}") {
+            let member_start = self.range();
+            let specifiers = self.parse_specifiers()?;
+            let base = match specifiers.data_type {
+                // anonymous structs are declared with this struct, once the names of both are known
+                CType::Definition(definition)
+                    if matches!(definition.as_ref(), Definition::Struct { tag: None, .. }) =>
+                {
+                    CType::Definition(definition)
+                }
+                CType::Definition(definition) => self.declare(*definition, None),
+                data_type => data_type,
+            };
+            if self.eat(";") {
+                problem.get_or_insert((
+                    "anonymous structs and unions cannot be declared in ST".to_string(),
+                    self.range_from(&member_start),
+                ));
+                continue;
+            }
+            loop {
+                let (name, data_type) = self.parse_declarator(base.clone())?;
+                let Some((name, range)) = name else { return Err(self.range()) };
+                if self.eat(":") {
+                    self.take_until(&[",", ";"])?;
+                    problem.get_or_insert(("bit fields cannot be declared in ST".to_string(), range.clone()));
+                }
+                self.skip_attributes()?;
+                members.push(Member { name, data_type, range });
+                if !self.eat(",") {
+                    break;
+                }
+            }
+            self.expect(";")?;
+        }
+        changes_layout |= self.skip_attributes()?;
+
+        let range = self.range_from(&start);
+        if keyword == "union" {
+            problem = Some(("unions cannot be declared in ST".to_string(), range.clone()));
+        } else if changes_layout || self.packing_at(start.start).is_some() {
+            problem = Some((
+                "the layout of packed or aligned structs cannot be declared in ST".to_string(),
+                range.clone(),
+            ));
+        } else if members.is_empty() {
+            problem = Some(("structs without members cannot be declared in ST".to_string(), range.clone()));
+        }
+        Ok(CType::Definition(Box::new(Definition::Struct {
+            keyword: keyword.to_string(),
+            tag,
+            members,
+            problem,
+            range,
+        })))
+    }
+
+    /// The packing of the structs declared at the given offset, `None` if they are not packed
+    fn packing_at(&self, offset: usize) -> Option<&str> {
+        self.packing.iter().rev().find(|(it, _)| *it <= offset).and_then(|(_, packing)| packing.as_deref())
+    }
+
+    fn parse_enum(&mut self) -> Result<CType, Range<usize>> {
+        let start = self.range();
+        self.position += 1;
+        self.skip_attributes()?;
+        let tag = if self.kind(0) == Some(TokenKind::Identifier) {
+            Some(self.expect_identifier()?.0)
+        } else {
+            None
+        };
+        if self.text(0) != "{" {
+            let Some(tag) = tag else { return Err(self.range()) };
+            // an enum declared by another header still has the size of an int
+            return Ok(self
+                .types
+                .get(&format!("enum {tag}"))
+                .cloned()
+                .unwrap_or(CType::Named("DINT".into())));
+        }
+
+        self.position += 1;
+        let mut variants = vec![];
+        let mut problem = None;
+        let mut value: i128 = -1;
+        while !self.eat("}") {
+            let (name, range) = self.expect_identifier()?;
+            self.skip_attributes()?;
+            value = if self.eat("=") {
+                let tokens = self.take_until(&[",", "}"])?;
+                match evaluate(self.source, &self.tokens[tokens], &self.constants) {
+                    Some(value) => value,
+                    None => {
+                        problem.get_or_insert((
+                            format!("the value of '{name}' is not a constant"),
+                            range.clone(),
+                        ));
+                        value + 1
+                    }
+                }
+            } else {
+                value + 1
+            };
+            if i32::try_from(value).is_err() {
+                problem.get_or_insert((format!("the value of '{name}' exceeds the range of DINT"), range));
+            }
+            self.constants.insert(name.clone(), value);
+            variants.push((name, value));
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        let range = self.range_from(&start);
+        Ok(CType::Definition(Box::new(Definition::Enum { tag, variants, problem, range })))
+    }
+
+    fn parse_declarator(&mut self, mut data_type: CType) -> Result<Declarator, Range<usize>> {
+        self.skip_qualifiers()?;
+        while self.eat("*") {
+            data_type = CType::Pointer(Box::new(data_type));
+            self.skip_qualifiers()?;
+        }
+
+        let mut name = None;
+        let mut nested = None;
+        if self.kind(0) == Some(TokenKind::Identifier) {
+            name = Some(self.expect_identifier()?);
+        } else if self.text(0) == "(" && self.is_nested_declarator() {
+            nested = Some(self.position + 1);
+            self.skip_balanced()?;
+        }
+
+        let mut suffixes = vec![];
+        loop {
+            match self.text(0) {
+                "[" => suffixes.push(Suffix::Array(self.parse_array_length()?)),
+                "(" => suffixes.push(Suffix::Function(self.parse_parameters()?)),
+                _ => break,
+            }
+        }
+        for suffix in suffixes.into_iter().rev() {
+            data_type = match suffix {
+                Suffix::Array(Ok(length)) => CType::Array(Box::new(data_type), length),
+                Suffix::Array(Err(reason)) => CType::Unsupported(reason),
+                Suffix::Function(mut function) => {
+                    function.return_type = data_type;
+                    CType::Function(Box::new(function))
+                }
+            };
+        }
+
+        // the nested declarator applies to the type including the suffixes, e.g. `int (*name)[4]`
+        if let Some(start) = nested {
+            let end = self.position;
+            self.position = start;
+            let declarator = self.parse_declarator(data_type)?;
+            self.expect(")")?;
+            self.position = end;
+            return Ok(declarator);
+        }
+        Ok((name, data_type))
+    }
+
+    /// Whether the current parenthesis encloses a declarator rather than the parameters of a function
+    fn is_nested_declarator(&self) -> bool {
+        let next = self.text(1);
+        matches!(next, "*" | "(" | "^")
+            || (self.kind(1) == Some(TokenKind::Identifier)
+                && !ELEMENTARY_WORDS.contains(&next)
+                && !matches!(next, "const" | "struct" | "union" | "enum")
+                && !self.types.contains_key(next)
+                && !STANDARD_TYPEDEFS.iter().any(|(it, _)| *it == next))
+    }
+
+    fn parse_array_length(&mut self) -> Result<Result<Option<i128>, String>, Range<usize>> {
+        self.expect("[")?;
+        let tokens = self.take_until(&["]"])?;
+        self.expect("]")?;
+        // qualifiers of array parameters, e.g. `int values[static 4]`
+        let tokens = self.tokens[tokens]
+            .iter()
+            .filter(|it| {
+                !matches!(&self.source[it.range.clone()], "static" | "const" | "restrict" | "volatile")
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if tokens.is_empty() {
+            return Ok(Ok(None));
+        }
+        let text = &self.source[tokens[0].range.start..tokens[tokens.len() - 1].range.end];
+        Ok(match evaluate(self.source, &tokens, &self.constants) {
+            Some(length) if length > 0 => Ok(Some(length)),
+            _ => Err(format!("the length '{text}' of the array is not a positive constant")),
+        })
+    }
+
+    fn parse_parameters(&mut self) -> Result<FunctionType, Range<usize>> {
+        self.expect("(")?;
+        let mut function = FunctionType { return_type: CType::Void, parameters: vec![], is_variadic: false };
+        if self.eat(")")
+            || (self.text(0) == "void" && self.text(1) == ")" && self.eat("void") && self.eat(")"))
+        {
+            return Ok(function);
+        }
+        loop {
+            if self.eat("...") {
+                function.is_variadic = true;
+                self.expect(")")?;
+                return Ok(function);
+            }
+            let start = self.range();
+            let specifiers = self.parse_specifiers()?;
+            let base = match specifiers.data_type {
+                // anonymous structs are declared with this struct, once the names of both are known
+                CType::Definition(definition)
+                    if matches!(definition.as_ref(), Definition::Struct { tag: None, .. }) =>
+                {
+                    CType::Definition(definition)
+                }
+                CType::Definition(definition) => self.declare(*definition, None),
+                data_type => data_type,
+            };
+            let (name, data_type) = self.parse_declarator(base)?;
+            self.skip_attributes()?;
+            let range =
+                name.as_ref().map(|(_, range)| range.clone()).unwrap_or_else(|| self.range_from(&start));
+            function.parameters.push(Parameter { name: name.map(|(name, _)| name), data_type, range });
+            if self.eat(")") {
+                return Ok(function);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    /// Declares the struct or enum under the given name or its tag, returns the type to refer to it
+    fn declare(&mut self, definition: Definition, typedef_name: Option<String>) -> CType {
+        match definition {
+            Definition::Struct { keyword, tag, members, problem, range } => {
+                let name = typedef_name.or_else(|| tag.clone());
+                let data_type = match (name, problem) {
+                    (_, Some((reason, range))) => {
+                        let name = tag.as_deref().unwrap_or(&keyword);
+                        self.reject(name, reason, range)
+                    }
+                    (None, None) => {
+                        self.reject(&keyword, "anonymous structs cannot be declared in ST".into(), range)
+                    }
+                    (Some(name), None) => match self.declare_name(&name) {
+                        Ok(st_name) => {
+                            let mut declared = FxHashMap::default();
+                            let members = members
+                                .into_iter()
+                                .map(|mut member| {
+                                    let name = format!("{st_name}_{}", member.name);
+                                    member.data_type =
+                                        self.declare_anonymous(member.data_type, &name, &mut declared);
+                                    member
+                                })
+                                .collect();
+                            self.items.push(Item::Struct { name: st_name.clone(), members });
+                            CType::Struct(st_name)
+                        }
+                        Err(reason) => self.reject(&name, reason, range),
+                    },
+                };
+                if let Some(tag) = tag {
+                    self.types.insert(format!("{keyword} {tag}"), data_type.clone());
+                }
+                data_type
+            }
+            Definition::Enum { tag, variants, problem, range } => {
+                let name = typedef_name.or_else(|| tag.clone());
+                let data_type = match (name, problem) {
+                    (_, Some((reason, range))) => {
+                        let name = tag.as_deref().unwrap_or("enum");
+                        self.reject(name, reason, range)
+                    }
+                    // the variants of an anonymous enum are constants
+                    (None, None) => {
+                        for (name, value) in variants {
+                            match self.declare_name(&name) {
+                                Ok(name) => self.items.push(Item::Constant { name, value }),
+                                Err(reason) => self.report(
+                                    range.clone(),
+                                    format!("Cannot declare '{name}' in ST, {reason}"),
+                                ),
+                            }
+                        }
+                        CType::Named("DINT".into())
+                    }
+                    (Some(name), None) => match self.declare_name(&name) {
+                        Ok(st_name) => {
+                            let variants =
+                                variants.into_iter().map(|(name, value)| (escape(&name), value)).collect();
+                            self.items.push(Item::Enum { name: st_name.clone(), variants });
+                            CType::Enum(st_name)
+                        }
+                        Err(reason) => self.reject(&name, reason, range),
+                    },
+                };
+                if let Some(tag) = tag {
+                    self.types.insert(format!("enum {tag}"), data_type.clone());
+                }
+                data_type
+            }
+        }
+    }
+
+    /// Declares the anonymous struct of a member with the given name, `declared` holds the structs
+    /// declared so far by their offset, since the declarators of a member share its struct
+    fn declare_anonymous(
+        &mut self,
+        data_type: CType,
+        name: &str,
+        declared: &mut FxHashMap<usize, CType>,
+    ) -> CType {
+        match data_type {
+            CType::Definition(definition) => {
+                let (Definition::Struct { range, .. } | Definition::Enum { range, .. }) = definition.as_ref();
+                let offset = range.start;
+                if let Some(data_type) = declared.get(&offset) {
+                    return data_type.clone();
+                }
+                let data_type = self.declare(*definition, Some(name.to_string()));
+                declared.insert(offset, data_type.clone());
+                data_type
+            }
+            CType::Pointer(inner) => CType::Pointer(Box::new(self.declare_anonymous(*inner, name, declared))),
+            CType::Array(inner, length) => {
+                CType::Array(Box::new(self.declare_anonymous(*inner, name, declared)), length)
+            }
+            data_type => data_type,
+        }
+    }
+
+    /// Reports why the named type cannot be declared, returns the type to refer to it
+    fn reject(&mut self, name: &str, reason: String, range: Range<usize>) -> CType {
+        self.report(range, format!("Cannot declare '{name}' in ST, {reason}"));
+        CType::Unsupported(format!("'{name}' cannot be declared in ST"))
+    }
+
+    fn declare_typedef(&mut self, name: String, data_type: CType, range: Range<usize>) {
+        let data_type = self.resolve(&data_type);
+        // the typedef of a struct or enum refers to the declared type, other types are used as they are
+        if !matches!(data_type, CType::Named(_) | CType::Pointer(_)) {
+            self.types.insert(name, data_type);
+            return;
+        }
+        // e.g. `typedef unsigned char BYTE;` uses the elementary type instead
+        if self.builtin_types.contains(&name.to_uppercase()) {
+            self.types.insert(name, data_type);
+            return;
+        }
+        match self.declare_name(&name) {
+            Ok(st_name) => {
+                self.items.push(Item::Alias { name: st_name.clone(), data_type, range });
+                self.types.insert(name, CType::Named(st_name));
+            }
+            Err(reason) => {
+                self.report(range, format!("Cannot declare '{name}' in ST, {reason}"));
+                self.types.insert(name, data_type);
+            }
+        }
+    }
+
+    fn declare_function(&mut self, name: String, function: FunctionType, range: Range<usize>) {
+        // the name of a function is its symbol, so it cannot be changed
+        let reason = if escape(&name) != name {
+            Some(format!("'{name}' is a keyword in ST"))
+        } else if builtins::get_builtin(&name).is_some() {
+            Some(format!("'{name}' is a built-in function of ST"))
+        } else {
+            self.declare_name(&name).err()
+        };
+        match reason {
+            Some(reason) => self.report(range, format!("Cannot declare '{name}' in ST, {reason}")),
+            None => self.items.push(Item::Function { name, function, range }),
+        }
+    }
+
+    /// Reserves the ST name of a declaration, returns why it cannot be used otherwise
+    fn declare_name(&mut self, name: &str) -> Result<String, String> {
+        let st_name = escape(name);
+        let key = st_name.to_uppercase();
+        if self.builtin_types.contains(&key) {
+            return Err(format!("'{name}' is a built-in type of ST"));
+        }
+        if !self.names.insert(key) {
+            return Err(format!(
+                "'{name}' clashes with another declaration, names are not case-sensitive in ST"
+            ));
+        }
+        Ok(st_name)
+    }
+
+    /// Returns the ST type of a member or typedef, or why it cannot be declared
+    fn to_st_type(
+        &self,
+        data_type: &CType,
+        range: &Range<usize>,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> Result<String, String> {
+        match self.resolve(data_type) {
+            CType::Named(name) | CType::Enum(name) => Ok(name),
+            CType::Struct(name) if self.rejected.contains(&name.to_uppercase()) => {
+                Err(format!("'{name}' cannot be declared in ST"))
+            }
+            CType::Struct(name) => Ok(name),
+            CType::Char { .. } => Ok("CHAR".into()),
+            CType::Void => Err("'void' is not the type of a value".into()),
+            CType::Opaque(name) => Err(format!("the type '{name}' is not declared in the header")),
+            CType::Unsupported(reason) => Err(reason),
+            CType::Function(_) | CType::Definition(_) | CType::Tagged(_) => {
+                Err("a function is not the type of a value".into())
+            }
+            CType::Pointer(inner) => Ok(self.pointer_to_st_type(&inner, range, warnings)),
+            CType::Array(inner, length) => {
+                let mut dimensions = vec![length];
+                let mut element = self.resolve(&inner);
+                while let CType::Array(inner, length) = &element {
+                    if matches!(self.resolve(inner), CType::Char { .. }) {
+                        break;
+                    }
+                    dimensions.push(*length);
+                    element = self.resolve(inner);
+                }
+                let element = match &element {
+                    CType::Array(_, Some(length)) if *length > 1 => format!("STRING[{}]", length - 1),
+                    _ => self.to_st_type(&element, range, warnings)?,
+                };
+                // a character array is a string including its terminator
+                if let ([Some(length)], "CHAR") = (dimensions.as_slice(), element.as_str()) {
+                    if *length > 1 {
+                        return Ok(format!("STRING[{}]", length - 1));
+                    }
+                }
+                let dimensions = dimensions
+                    .iter()
+                    .map(|it| it.map(|length| format!("0..{}", length - 1)))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or("arrays without a length cannot be declared in ST")?;
+                Ok(format!("ARRAY[{}] OF {element}", dimensions.join(", ")))
+            }
+        }
+    }
+
+    fn pointer_to_st_type(
+        &self,
+        inner: &CType,
+        range: &Range<usize>,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> String {
+        if let CType::Function(_) = self.resolve(inner) {
+            warnings.push(
+                Diagnostic::new("Function pointers are declared as untyped pointers, ST cannot call them")
+                    .with_error_code("E170")
+                    .with_location(self.factory.create_range(range.clone())),
+            );
+            return UNTYPED_POINTER.into();
+        }
+        self.to_st_type(inner, range, warnings)
+            .map(|it| format!("REF_TO {it}"))
+            .unwrap_or(UNTYPED_POINTER.into())
+    }
+
+    /// Returns how the parameter is passed and its ST type, or why it cannot be declared
+    fn parameter_to_st_type(
+        &self,
+        parameter: &Parameter,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> Result<(ParameterKind, String), String> {
+        let data_type = match self.resolve(&parameter.data_type) {
+            // arrays and functions are passed as a pointer
+            CType::Array(inner, _) => CType::Pointer(inner),
+            CType::Function(function) => CType::Pointer(Box::new(CType::Function(function))),
+            data_type => data_type,
+        };
+        match data_type {
+            CType::Pointer(inner) if matches!(self.resolve(&inner), CType::Char { is_const: true }) => {
+                Ok((ParameterKind::Reference, "STRING".into()))
+            }
+            CType::Struct(name) => {
+                Err(format!("the struct '{name}' is passed by value, ST passes structs by reference"))
+            }
+            data_type => Ok((ParameterKind::Input, self.to_st_type(&data_type, &parameter.range, warnings)?)),
+        }
+    }
+
+    fn return_to_st_type(
+        &self,
+        return_type: &CType,
+        range: &Range<usize>,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> Result<Option<String>, String> {
+        match self.resolve(return_type) {
+            CType::Void => Ok(None),
+            CType::Struct(name) => {
+                Err(format!("the struct '{name}' is returned by value, ST returns structs through a pointer"))
+            }
+            data_type => self.to_st_type(&data_type, range, warnings).map(Some),
+        }
+    }
+
+    /// Writes the declarations which could be mapped to ST
+    fn finish(mut self, file_name: &str) -> (String, Vec<Diagnostic>) {
+        // a struct containing a struct which cannot be declared by value cannot be declared either, so
+        // these are found first, to declare pointers to them as untyped pointers
+        let items = std::mem::take(&mut self.items);
+        for item in &items {
+            let Item::Struct { name, members } = item else { continue };
+            for member in members {
+                if let Err(reason) = self.to_st_type(&member.data_type, &member.range, &mut vec![]) {
+                    self.report(member.range.clone(), format!("Cannot declare '{name}' in ST, {reason}"));
+                    self.rejected.insert(name.to_uppercase());
+                    break;
+                }
+            }
+        }
+
+        let mut constants = String::new();
+        let mut types = String::new();
+        let mut functions = String::new();
+        let mut warnings = vec![];
+        for item in &items {
+            match item {
+                Item::Constant { name, value } => write_constant(&mut constants, name, *value),
+                Item::Struct { name, .. } if self.rejected.contains(&name.to_uppercase()) => {}
+                Item::Struct { name, members } => {
+                    let _ = writeln!(types, "TYPE {name} :\nSTRUCT");
+                    for member in members {
+                        let data_type = self.to_st_type(&member.data_type, &member.range, &mut warnings);
+                        let _ = writeln!(
+                            types,
+                            "    {} : {};",
+                            escape(&member.name),
+                            data_type.unwrap_or_default()
+                        );
+                    }
+                    let _ = writeln!(types, "END_STRUCT\nEND_TYPE\n");
+                }
+                Item::Enum { name, variants } => {
+                    let variants = variants
+                        .iter()
+                        .map(|(name, value)| format!("    {name} := {value}"))
+                        .collect::<Vec<_>>();
+                    let _ = writeln!(types, "TYPE {name} : (\n{}\n);\nEND_TYPE\n", variants.join(",\n"));
+                }
+                Item::Alias { name, data_type, range } => {
+                    match self.to_st_type(data_type, range, &mut warnings) {
+                        Ok(data_type) => {
+                            let _ = writeln!(types, "TYPE {name} : {data_type}; END_TYPE\n");
+                        }
+                        Err(reason) => {
+                            self.report(range.clone(), format!("Cannot declare '{name}' in ST, {reason}"))
+                        }
+                    }
+                }
+                Item::Function { name, function, range } => {
+                    match self.write_function(name, function, range, &mut warnings) {
+                        Ok(declaration) => functions.push_str(&declaration),
+                        Err(reason) => {
+                            self.report(range.clone(), format!("Cannot declare '{name}' in ST, {reason}"))
+                        }
+                    }
+                }
+            }
+        }
+        // macros are only declared if their names are not used by a declaration
+        for (name, value) in std::mem::take(&mut self.macros) {
+            if !name.starts_with('_') && escape(&name) == name && self.names.insert(name.to_uppercase()) {
+                write_constant(&mut constants, &name, value);
+            }
+        }
+        self.diagnostics.extend(warnings);
+
+        let mut bindings = format!("// Generated by `plc generate bindings` from {file_name}\n\n");
+        if !constants.is_empty() {
+            let _ = writeln!(bindings, "VAR_GLOBAL CONSTANT\n{constants}END_VAR\n");
+        }
+        bindings.push_str(&types);
+        bindings.push_str(&functions);
+        (bindings.trim_end().to_string() + "\n", self.diagnostics)
+    }
+
+    fn write_function(
+        &self,
+        name: &str,
+        function: &FunctionType,
+        range: &Range<usize>,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> Result<String, String> {
+        let mut declaration = String::from("{external}\n");
+        match self.return_to_st_type(&function.return_type, range, warnings)? {
+            Some(return_type) => {
+                let _ = writeln!(declaration, "FUNCTION {name} : {return_type}");
+            }
+            None => {
+                let _ = writeln!(declaration, "FUNCTION {name}");
+            }
+        }
+
+        // consecutive parameters passed the same way share a block, to keep their order
+        let mut blocks: Vec<(ParameterKind, Vec<String>)> = vec![];
+        let mut names = FxHashSet::from_iter([name.to_uppercase()]);
+        for (index, parameter) in function.parameters.iter().enumerate() {
+            let (kind, data_type) = self.parameter_to_st_type(parameter, warnings).map_err(|reason| {
+                let parameter =
+                    parameter.name.as_deref().map_or(format!("{}", index + 1), |it| format!("'{it}'"));
+                format!("the parameter {parameter} cannot be declared, {reason}")
+            })?;
+            let mut parameter_name =
+                escape(parameter.name.as_deref().unwrap_or(&format!("param{}", index + 1)));
+            // the return value of a function is a variable named after it
+            while !names.insert(parameter_name.to_uppercase()) {
+                parameter_name.push('_');
+            }
+            let line = format!("    {parameter_name} : {data_type};");
+            match blocks.last_mut() {
+                Some((last, lines)) if *last == kind => lines.push(line),
+                _ => blocks.push((kind, vec![line])),
+            }
+        }
+        if function.is_variadic {
+            let mut parameter_name = VARIADIC_PARAMETER.to_string();
+            while !names.insert(parameter_name.to_uppercase()) {
+                parameter_name.push('_');
+            }
+            let line = format!("    {parameter_name} : ...;");
+            match blocks.last_mut() {
+                Some((ParameterKind::Input, lines)) => lines.push(line),
+                _ => blocks.push((ParameterKind::Input, vec![line])),
+            }
+        }
+
+        for (kind, lines) in blocks {
+            let block = if kind == ParameterKind::Reference { "VAR_INPUT {ref}" } else { "VAR_INPUT" };
+            let _ = writeln!(declaration, "{block}\n{}\nEND_VAR", lines.join("\n"));
+        }
+        declaration.push_str("END_FUNCTION\n\n");
+        Ok(declaration)
+    }
+}
+
+/// Returns the type of the given elementary type words, e.g. `unsigned long int`
+fn elementary_type(words: &[&str], is_const: bool) -> CType {
+    let contains = |word: &str| words.contains(&word);
+    let is_unsigned = contains("unsigned");
+    let named =
+        |signed: &str, unsigned: &str| CType::Named(if is_unsigned { unsigned } else { signed }.to_string());
+    if contains("void") {
+        CType::Void
+    } else if contains("_Bool") || contains("bool") {
+        CType::Named("BOOL".into())
+    } else if contains("_Complex") {
+        CType::Unsupported("complex numbers have no equivalent in ST".into())
+    } else if contains("char") {
+        if is_unsigned || contains("signed") || contains("__signed__") {
+            named("SINT", "USINT")
+        } else {
+            CType::Char { is_const }
+        }
+    } else if contains("float") {
+        CType::Named("REAL".into())
+    } else if contains("double") && contains("long") {
+        CType::Unsupported("'long double' has no equivalent in ST".into())
+    } else if contains("double") {
+        CType::Named("LREAL".into())
+    } else if contains("short") {
+        named("INT", "UINT")
+    } else if contains("long") {
+        // LP64, `long` is as wide as `long long`
+        named("LINT", "ULINT")
+    } else {
+        named("DINT", "UDINT")
+    }
+}
+
+/// Returns the name as an ST identifier, names which are keywords in ST get a trailing underscore
+fn escape(name: &str) -> String {
+    let mut lexer = Token::lexer(name);
+    match (lexer.next(), lexer.next()) {
+        (Some(Token::Identifier), None) => name.to_string(),
+        _ => format!("{name}_"),
+    }
+}
+
+fn write_constant(constants: &mut String, name: &str, value: i128) {
+    let data_type = if i32::try_from(value).is_ok() {
+        "DINT"
+    } else if i64::try_from(value).is_ok() {
+        "LINT"
+    } else {
+        return;
+    };
+    let _ = writeln!(constants, "    {name} : {data_type} := {value};");
+}
+
+/// Returns the values of the given macros which define an integer, like in C a macro may use the macros
+/// defined after it
+fn evaluate_macros(source: &str, macros: &[(String, Range<usize>)]) -> FxHashMap<String, i128> {
+    let mut constants = FxHashMap::default();
+    let mut pending = macros.iter().collect::<Vec<_>>();
+    loop {
+        let count = pending.len();
+        pending.retain(|(name, range)| {
+            let value = source[range.clone()].trim();
+            let Some(value) = evaluate(value, &tokenize(value).tokens, &constants) else { return true };
+            constants.insert(name.clone(), value);
+            false
+        });
+        if pending.len() == count {
+            return constants;
+        }
+    }
+}
+
+/// Evaluates an integer constant expression, `None` if the tokens are not one
+fn evaluate(source: &str, tokens: &[CToken], constants: &FxHashMap<String, i128>) -> Option<i128> {
+    let mut expression = ConstantExpression { source, tokens, position: 0, constants, defined: None };
+    let value = expression.binary(0)?;
+    (expression.position == tokens.len()).then_some(value)
+}
+
+/// Evaluates the condition of an `#if` or `#elif`, a condition which cannot be evaluated does not hold
+fn evaluate_condition(
+    condition: &str,
+    constants: &FxHashMap<String, i128>,
+    defined: &FxHashSet<String>,
+) -> bool {
+    let tokens = tokenize(condition).tokens;
+    let mut expression = ConstantExpression {
+        source: condition,
+        tokens: &tokens,
+        position: 0,
+        constants,
+        defined: Some(defined),
+    };
+    let value = expression.binary(0);
+    expression.position == tokens.len() && value.is_some_and(|it| it != 0)
+}
+
+struct ConstantExpression<'a> {
+    source: &'a str,
+    tokens: &'a [CToken],
+    position: usize,
+    constants: &'a FxHashMap<String, i128>,
+    /// The names of the defined macros if the condition of a directive is evaluated, which may use
+    /// `defined` and treats unknown names as 0
+    defined: Option<&'a FxHashSet<String>>,
+}
+
+impl ConstantExpression<'_> {
+    fn text(&self, offset: usize) -> &str {
+        self.tokens.get(self.position + offset).map_or("", |it| &self.source[it.range.clone()])
+    }
+
+    /// Returns the binary operator at the current position and its precedence
+    fn operator(&self) -> Option<(&'static str, u8)> {
+        let adjacent = self
+            .tokens
+            .get(self.position + 1)
+            .zip(self.tokens.get(self.position))
+            .is_some_and(|(next, current)| next.range.start == current.range.end);
+        let operator = match (self.text(0), self.text(1)) {
+            ("<", "<") if adjacent => "<<",
+            (">", ">") if adjacent => ">>",
+            ("|", "|") if adjacent => "||",
+            ("&", "&") if adjacent => "&&",
+            ("=", "=") if adjacent => "==",
+            ("!", "=") if adjacent => "!=",
+            ("<", "=") if adjacent => "<=",
+            (">", "=") if adjacent => ">=",
+            ("|", _) => "|",
+            ("^", _) => "^",
+            ("&", _) => "&",
+            ("<", _) => "<",
+            (">", _) => ">",
+            ("+", _) => "+",
+            ("-", _) => "-",
+            ("*", _) => "*",
+            ("/", _) => "/",
+            ("%", _) => "%",
+            _ => return None,
+        };
+        let precedence = match operator {
+            "||" => 1,
+            "&&" => 2,
+            "|" => 3,
+            "^" => 4,
+            "&" => 5,
+            "==" | "!=" => 6,
+            "<" | ">" | "<=" | ">=" => 7,
+            "<<" | ">>" => 8,
+            "+" | "-" => 9,
+            _ => 10,
+        };
+        Some((operator, precedence))
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Option<i128> {
+        let mut left = self.unary()?;
+        while let Some((operator, precedence)) = self.operator().filter(|(_, it)| *it >= min_precedence) {
+            self.position += operator.len();
+            let right = self.binary(precedence + 1)?;
+            left = match operator {
+                "||" => i128::from(left != 0 || right != 0),
+                "&&" => i128::from(left != 0 && right != 0),
+                "==" => i128::from(left == right),
+                "!=" => i128::from(left != right),
+                "<" => i128::from(left < right),
+                ">" => i128::from(left > right),
+                "<=" => i128::from(left <= right),
+                ">=" => i128::from(left >= right),
+                "<<" => left.checked_shl(u32::try_from(right).ok()?)?,
+                ">>" => left.checked_shr(u32::try_from(right).ok()?)?,
+                "|" => left | right,
+                "^" => left ^ right,
+                "&" => left & right,
+                "+" => left.checked_add(right)?,
+                "-" => left.checked_sub(right)?,
+                "*" => left.checked_mul(right)?,
+                "/" => left.checked_div(right)?,
+                _ => left.checked_rem(right)?,
+            };
+        }
+        Some(left)
+    }
+
+    fn unary(&mut self) -> Option<i128> {
+        let text = self.text(0).to_string();
+        let kind = self.tokens.get(self.position)?.kind;
+        self.position += 1;
+        match (kind, text.as_str()) {
+            (TokenKind::Punctuation, "-") => self.unary()?.checked_neg(),
+            (TokenKind::Punctuation, "+") => self.unary(),
+            (TokenKind::Punctuation, "~") => Some(!self.unary()?),
+            (TokenKind::Punctuation, "!") => Some(i128::from(self.unary()? == 0)),
+            (TokenKind::Punctuation, "(") => {
+                let value = self.binary(0)?;
+                (self.text(0) == ")").then(|| self.position += 1)?;
+                Some(value)
+            }
+            (TokenKind::Number, _) => parse_integer(&text),
+            (TokenKind::Identifier, "defined") if self.defined.is_some() => {
+                let has_parentheses = self.text(0) == "(";
+                self.position += usize::from(has_parentheses);
+                let is_defined = self.defined?.contains(self.text(0));
+                self.position += 1;
+                if has_parentheses {
+                    (self.text(0) == ")").then(|| self.position += 1)?;
+                }
+                Some(i128::from(is_defined))
+            }
+            (TokenKind::Identifier, _) => self.constants.get(&text).copied().or(self.defined.map(|_| 0)),
+            // a character literal without escape sequences, e.g. `'A'`
+            (TokenKind::Literal, _) if text.len() == 3 && text.starts_with('\'') => {
+                Some(text.as_bytes()[1].into())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parses a decimal, hexadecimal, octal or binary integer literal, ignoring its suffix
+fn parse_integer(text: &str) -> Option<i128> {
+    let text = text.trim_end_matches(['u', 'U', 'l', 'L']);
+    let (digits, radix) = if let Some(digits) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        (digits, 16)
+    } else if let Some(digits) = text.strip_prefix("0b").or_else(|| text.strip_prefix("0B")) {
+        (digits, 2)
+    } else if text.len() > 1 && text.starts_with('0') {
+        (&text[1..], 8)
+    } else {
+        (text, 10)
+    };
+    i128::from_str_radix(digits, radix).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_source::SourceCode;
+
+    use super::generate_bindings;
+    use crate::test_utils::tests::parse;
+
+    fn bindings(header: &str) -> (String, Vec<String>) {
+        let (bindings, diagnostics) = generate_bindings(&SourceCode::new(header, "lib.h"));
+        (bindings, diagnostics.iter().map(|it| it.get_message().to_string()).collect())
+    }
+
+    #[test]
+    fn functions_are_declared_as_external_functions() {
+        let (bindings, diagnostics) = bindings(
+            r#"
+            #include <stdint.h>
+            #ifdef __cplusplus
+            extern "C" {
+            #endif
+            int sum(int a, int b);
+            void log_message(const char *format, ...);
+            size_t count(const int32_t values[], size_t n);
+            static inline int twice(int x) { return x * 2; }
+            #ifdef __cplusplus
+            }
+            #endif
+            "#,
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec!["Cannot declare 'twice' in ST, static functions are not exported by the library"]
+        );
+        insta::assert_snapshot!(bindings, @r"
+        // Generated by `plc generate bindings` from lib.h
+
+        {external}
+        FUNCTION sum : DINT
+        VAR_INPUT
+            a : DINT;
+            b : DINT;
+        END_VAR
+        END_FUNCTION
+
+        {external}
+        FUNCTION log_message
+        VAR_INPUT {ref}
+            format : STRING;
+        END_VAR
+        VAR_INPUT
+            args : ...;
+        END_VAR
+        END_FUNCTION
+
+        {external}
+        FUNCTION count : ULINT
+        VAR_INPUT
+            values : REF_TO DINT;
+            n : ULINT;
+        END_VAR
+        END_FUNCTION
+        ");
+    }
+
+    #[test]
+    fn types_and_constants_are_declared() {
+        let (bindings, diagnostics) = bindings(
+            "
+            #define MAX_NAME 32
+            #define FLAGS (1 << 3) | 0x10
+            typedef struct point { int32_t x, y; double weight; } Point;
+            struct sensor {
+                char name[MAX_NAME];
+                Point position;
+                struct sensor *next;
+                float samples[4][8];
+                void (*callback)(int);
+            };
+            typedef enum { RED, GREEN = 5, BLUE } Color;
+            enum { LIMIT = 10 };
+            typedef uint32_t handle_t;
+            typedef struct handle_s *opaque_t;
+            ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec!["Function pointers are declared as untyped pointers, ST cannot call them"]
+        );
+        insta::assert_snapshot!(bindings, @r"
+        // Generated by `plc generate bindings` from lib.h
+
+        VAR_GLOBAL CONSTANT
+            LIMIT : DINT := 10;
+            MAX_NAME : DINT := 32;
+            FLAGS : DINT := 24;
+        END_VAR
+
+        TYPE Point :
+        STRUCT
+            x : DINT;
+            y : DINT;
+            weight : LREAL;
+        END_STRUCT
+        END_TYPE
+
+        TYPE sensor :
+        STRUCT
+            name : STRING[31];
+            position : Point;
+            next : REF_TO sensor;
+            samples : ARRAY[0..3, 0..7] OF REAL;
+            callback : REF_TO BYTE;
+        END_STRUCT
+        END_TYPE
+
+        TYPE Color : (
+            RED := 0,
+            GREEN := 5,
+            BLUE := 6
+        );
+        END_TYPE
+
+        TYPE handle_t : UDINT; END_TYPE
+
+        TYPE opaque_t : REF_TO BYTE; END_TYPE
+        ");
+    }
+
+    #[test]
+    fn declarations_with_a_different_abi_are_reported() {
+        let (bindings, diagnostics) = bindings(
+            "
+            union value { int i; float f; };
+            struct bits { int a : 3; };
+            #pragma pack(push, 1)
+            struct packed { char a; int b; };
+            #pragma pack(pop)
+            struct holder { union value v; };
+            struct point { int x; };
+            struct point make_point(int x);
+            double length(struct point p);
+            long double precise(void);
+            int counter;
+            ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                "Cannot declare 'value' in ST, unions cannot be declared in ST",
+                "Cannot declare 'bits' in ST, bit fields cannot be declared in ST",
+                "Cannot declare 'packed' in ST, the layout of packed or aligned structs cannot be declared in ST",
+                "Cannot declare 'counter' in ST, only the functions and types of a header are declared",
+                "Cannot declare 'holder' in ST, 'value' cannot be declared in ST",
+                "Cannot declare 'make_point' in ST, the struct 'point' is returned by value, ST returns structs through a pointer",
+                "Cannot declare 'length' in ST, the parameter 'p' cannot be declared, the struct 'point' is passed by value, ST passes structs by reference",
+                "Cannot declare 'precise' in ST, 'long double' has no equivalent in ST",
+            ]
+        );
+        assert!(bindings.contains("TYPE point :"));
+        assert!(!bindings.contains("FUNCTION"));
+    }
+
+    #[test]
+    fn names_are_checked_against_st() {
+        let (bindings, diagnostics) = bindings(
+            "
+            struct config { int type; };
+            void add(int x);
+            void scale(int x);
+            int SCALE(int x);
+            void type(int to);
+            typedef unsigned char BYTE;
+            typedef int INT;
+            ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                "Cannot declare 'add' in ST, 'add' is a built-in function of ST",
+                "Cannot declare 'SCALE' in ST, 'SCALE' clashes with another declaration, names are not case-sensitive in ST",
+                "Cannot declare 'type' in ST, 'type' is a keyword in ST",
+            ]
+        );
+        assert!(bindings.contains("    type_ : DINT;"));
+        assert!(bindings.contains("    x : DINT;"));
+        assert!(!bindings.contains("TYPE BYTE"));
+    }
+
+    #[test]
+    fn invalid_declarations_are_skipped() {
+        let (bindings, diagnostics) = bindings(
+            "
+            template<typename T> void broken(T x);
+            int after_error(int x);
+            ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec!["Cannot read this declaration, it is either not valid C or not supported by the bindings generator"]
+        );
+        assert!(bindings.contains("FUNCTION after_error : DINT"));
+    }
+
+    #[test]
+    fn only_the_branches_of_conditionals_which_hold_are_read() {
+        let (bindings, diagnostics) = bindings(
+            "
+            #define VERSION 3
+            #define USE_FLOAT
+            #ifndef LIB_H
+            #define LIB_H
+            #if defined(USE_FLOAT) && VERSION >= 2
+            #  ifdef _WIN32
+            float scale(float x, int flags);
+            #  elif VERSION == 3
+            float scale(float x);
+            #  else
+            double scale(double x);
+            #  endif
+            #elif !defined USE_INT
+            long scale(long x);
+            #else
+            int scale(int x);
+            #endif
+            #if 0
+            int disabled(void);
+            #endif
+            #endif
+            ",
+        );
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        insta::assert_snapshot!(bindings, @r"
+        // Generated by `plc generate bindings` from lib.h
+
+        VAR_GLOBAL CONSTANT
+            VERSION : DINT := 3;
+        END_VAR
+
+        {external}
+        FUNCTION scale : REAL
+        VAR_INPUT
+            x : REAL;
+        END_VAR
+        END_FUNCTION
+        ");
+    }
+
+    #[test]
+    fn macros_can_use_other_macros() {
+        let (bindings, diagnostics) = bindings(
+            "
+            #define AREA (WIDTH * HEIGHT)
+            #define WIDTH 4
+            #define HEIGHT (WIDTH + 2)
+            #define MASK ~(1 << WIDTH)
+            #define EXPORT __attribute__((visibility(\"default\")))
+            #define API EXPORT
+            #define NAME_LENGTH AREA
+            #undef WIDTH
+            #define WIDTH 5
+            API int get_area(void);
+            typedef struct { char name[NAME_LENGTH]; } Label;
+            ",
+        );
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        insta::assert_snapshot!(bindings, @r"
+        // Generated by `plc generate bindings` from lib.h
+
+        VAR_GLOBAL CONSTANT
+            AREA : DINT := 35;
+            HEIGHT : DINT := 7;
+            MASK : DINT := -33;
+            NAME_LENGTH : DINT := 35;
+            WIDTH : DINT := 5;
+        END_VAR
+
+        TYPE Label :
+        STRUCT
+            name : STRING[34];
+        END_STRUCT
+        END_TYPE
+
+        {external}
+        FUNCTION get_area : DINT
+        END_FUNCTION
+        ");
+    }
+
+    #[test]
+    fn pointers_to_pointers_and_const_pointers_are_declared() {
+        let (bindings, diagnostics) = bindings(
+            "
+            int **matrix(const char **names, int *const values, const int *const *rows);
+            typedef const char *const *string_list;
+            struct node { struct node **children; void *const data; const double *const weight; };
+            ",
+        );
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        insta::assert_snapshot!(bindings, @r"
+        // Generated by `plc generate bindings` from lib.h
+
+        TYPE string_list : REF_TO REF_TO CHAR; END_TYPE
+
+        TYPE node :
+        STRUCT
+            children : REF_TO REF_TO node;
+            data : REF_TO BYTE;
+            weight : REF_TO LREAL;
+        END_STRUCT
+        END_TYPE
+
+        {external}
+        FUNCTION matrix : REF_TO REF_TO DINT
+        VAR_INPUT
+            names : REF_TO REF_TO CHAR;
+            values : REF_TO DINT;
+            rows : REF_TO REF_TO DINT;
+        END_VAR
+        END_FUNCTION
+        ");
+    }
+
+    #[test]
+    fn anonymous_nested_structs_are_declared() {
+        let (bindings, diagnostics) = bindings(
+            "
+            struct outer {
+                struct { int x, y; } position;
+                struct inner { float a; struct { char c; } deepest; } first, *second;
+                enum { IDLE, BUSY } state;
+            };
+            ",
+        );
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        insta::assert_snapshot!(bindings, @r"
+        // Generated by `plc generate bindings` from lib.h
+
+        VAR_GLOBAL CONSTANT
+            IDLE : DINT := 0;
+            BUSY : DINT := 1;
+        END_VAR
+
+        TYPE inner_deepest :
+        STRUCT
+            c : CHAR;
+        END_STRUCT
+        END_TYPE
+
+        TYPE inner :
+        STRUCT
+            a : REAL;
+            deepest : inner_deepest;
+        END_STRUCT
+        END_TYPE
+
+        TYPE outer_position :
+        STRUCT
+            x : DINT;
+            y : DINT;
+        END_STRUCT
+        END_TYPE
+
+        TYPE outer :
+        STRUCT
+            position : outer_position;
+            first : inner;
+            second : REF_TO inner;
+            state : DINT;
+        END_STRUCT
+        END_TYPE
+        ");
+    }
+
+    #[test]
+    fn integer_types_are_mapped_by_their_width() {
+        let (bindings, diagnostics) = bindings(
+            "
+            typedef long l_t;
+            typedef long int li_t;
+            typedef unsigned long ul_t;
+            typedef long unsigned int lui_t;
+            typedef long long ll_t;
+            typedef unsigned long long ull_t;
+            typedef signed long long int sll_t;
+            typedef short s_t;
+            typedef unsigned short int us_t;
+            typedef signed char sc_t;
+            typedef unsigned char uc_t;
+            typedef unsigned u_t;
+            ",
+        );
+
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        insta::assert_snapshot!(bindings, @r"
+        // Generated by `plc generate bindings` from lib.h
+
+        TYPE l_t : LINT; END_TYPE
+
+        TYPE li_t : LINT; END_TYPE
+
+        TYPE ul_t : ULINT; END_TYPE
+
+        TYPE lui_t : ULINT; END_TYPE
+
+        TYPE ll_t : LINT; END_TYPE
+
+        TYPE ull_t : ULINT; END_TYPE
+
+        TYPE sll_t : LINT; END_TYPE
+
+        TYPE s_t : INT; END_TYPE
+
+        TYPE us_t : UINT; END_TYPE
+
+        TYPE sc_t : SINT; END_TYPE
+
+        TYPE uc_t : USINT; END_TYPE
+
+        TYPE u_t : UDINT; END_TYPE
+        ");
+    }
+
+    #[test]
+    fn generated_bindings_can_be_parsed() {
+        let (bindings, _) = bindings(
+            "
+            #define SIZE 4
+            typedef struct { int values[SIZE]; char name[8]; } Data;
+            typedef enum { OFF, ON } State;
+            State update(Data *data, const char *reason, ...);
+            ",
+        );
+
+        let (_, diagnostics) = parse(&bindings);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+}
@@ -0,0 +1,720 @@
+//! Call graph of the annotated project and reachability of POUs and types.
+//!
+//! The graph is built from the lowered and annotated AST, so calls which are dispatched at runtime
+//! already went through [`crate::lowering::polymorphism`] and show up as calls through a function
+//! pointer of a virtual table (`__vtable_A#(ptr^.__vtable^).foo^(ptr^)`) or an interface table
+//! (`__itable_I#(ref.table^).foo^(ref.data^)`). Such calls are resolved back into edges to the
+//! called method and every method which may be called in its place:
+//! - for virtual tables, the overriding methods of all POUs extending the method's POU
+//! - for interface tables, the methods of all POUs implementing the interface
+//!
+//! The same edges are used to find POUs and types that can never be used at runtime, i.e. that are
+//! not reachable from any `PROGRAM`, any `{external}` declaration or the initializer of a global.
+
+use std::fmt::Write;
+
+use plc_ast::{
+    ast::{
+        AstNode, CallStatement, CompilationUnit, Implementation, LinkageType, Pou, PouType, ReferenceExpr,
+        UserTypeDeclaration,
+    },
+    visitor::{AstVisitor, Walker},
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::source_location::SourceLocation;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+
+use crate::{
+    index::{FxIndexSet, Index, PouIndexEntry},
+    resolver::{AnnotationMap, StatementAnnotation},
+    typesystem::DataTypeInformation,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CallGraph {
+    pub nodes: Vec<CallGraphNode>,
+    pub edges: Vec<CallGraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CallGraphNode {
+    pub name: String,
+    /// the kind of the POU, e.g. `Program`, `FunctionBlock` or `Method`
+    pub kind: String,
+    pub external: bool,
+    pub file: Option<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CallGraphEdge {
+    pub caller: String,
+    pub callee: String,
+    pub kind: CallKind,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum CallKind {
+    /// a call of a function or program
+    Call,
+    /// a call of a function block instance
+    Instance,
+    /// a call of a method
+    Method,
+    /// a method (or function block body) which may be called instead of the called one because it
+    /// overrides it in a derived POU
+    Dispatch,
+    /// a method implementing the called interface method
+    Interface,
+    /// a call of an action
+    Action,
+    /// the POU is referenced without being called, e.g. `ADR(foo)`
+    Reference,
+}
+
+impl CallKind {
+    fn get_label(&self) -> &'static str {
+        match self {
+            CallKind::Call => "call",
+            CallKind::Instance => "instance",
+            CallKind::Method => "method",
+            CallKind::Dispatch => "dispatch",
+            CallKind::Interface => "interface",
+            CallKind::Action => "action",
+            CallKind::Reference => "reference",
+        }
+    }
+}
+
+/// A declaration that is not reachable from any entry point of the project
+#[derive(Debug, Clone, Copy)]
+pub enum Unreachable<'a> {
+    Pou(&'a Pou),
+    Action(&'a Implementation),
+    Type(&'a UserTypeDeclaration),
+}
+
+/// Builds the call graph of all POUs declared in the given units
+pub fn collect_call_graph<'a>(
+    units: impl IntoIterator<Item = &'a CompilationUnit>,
+    index: &Index,
+    annotations: &impl AnnotationMap,
+) -> CallGraph {
+    collect(&units.into_iter().collect::<Vec<_>>(), index, annotations).graph
+}
+
+/// Returns the functions, function blocks, classes, methods, actions and types declared in the given
+/// units, which are neither called nor used by anything reachable from a `PROGRAM`, an `{external}`
+/// declaration or the initializer of a global variable
+pub fn find_unreachable<'a>(
+    units: &[&'a CompilationUnit],
+    index: &Index,
+    annotations: &impl AnnotationMap,
+) -> Vec<Unreachable<'a>> {
+    let Collected { graph, mut uses, roots } = collect(units, index, annotations);
+    for edge in &graph.edges {
+        uses.entry(edge.caller.to_lowercase()).or_default().insert(edge.callee.to_lowercase());
+    }
+
+    let mut reachable = FxHashSet::default();
+    let mut worklist = roots.into_iter().collect::<Vec<_>>();
+    while let Some(name) = worklist.pop() {
+        if !reachable.insert(name.clone()) {
+            continue;
+        }
+        worklist.extend(uses.get(&name).into_iter().flatten().cloned());
+        worklist.extend(get_implicit_uses(index, &name).into_iter().map(|it| it.to_lowercase()));
+    }
+
+    let is_unreachable = |name: &str, location: &SourceLocation, linkage: LinkageType| {
+        linkage == LinkageType::Internal
+            && !location.is_internal()
+            && !name.starts_with("__")
+            && !reachable.contains(&name.to_lowercase())
+    };
+
+    let mut result = Vec::new();
+    for unit in units {
+        for pou in &unit.pous {
+            let is_candidate = match &pou.kind {
+                PouType::Function | PouType::FunctionBlock | PouType::Class => pou.generics.is_empty(),
+                // property accessors are reported as part of their property
+                PouType::Method { property, .. } => property.is_none(),
+                _ => false,
+            };
+            if is_candidate && is_unreachable(&pou.name, &pou.name_location, pou.linkage) {
+                result.push(Unreachable::Pou(pou));
+            }
+        }
+        for action in unit.implementations.iter().filter(|it| it.pou_type == PouType::Action) {
+            if is_unreachable(&action.name, &action.name_location, action.linkage) {
+                result.push(Unreachable::Action(action));
+            }
+        }
+        for user_type in &unit.user_types {
+            let Some(name) = user_type.data_type.get_name() else { continue };
+            if is_unreachable(name, &user_type.location, user_type.linkage) {
+                result.push(Unreachable::Type(user_type));
+            }
+        }
+    }
+
+    result
+}
+
+pub fn serialize_call_graph(graph: &CallGraph, format: CallGraphFormat) -> Result<String, Diagnostic> {
+    match format {
+        CallGraphFormat::Dot => Ok(to_dot(graph)),
+        CallGraphFormat::Json => serde_json::to_string_pretty(graph).map_err(|it| {
+            Diagnostic::new(it.to_string()).with_internal_error(it.into()).with_error_code("E002")
+        }),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallGraphFormat {
+    Dot,
+    Json,
+}
+
+impl CallGraphFormat {
+    /// Returns the format matching the extension of the given file name
+    pub fn from_file_name(name: &str) -> Option<CallGraphFormat> {
+        match name.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("dot" | "gv") => Some(CallGraphFormat::Dot),
+            Some("json") => Some(CallGraphFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+fn to_dot(graph: &CallGraph) -> String {
+    let mut dot = String::from("digraph callgraph {\n");
+    for node in &graph.nodes {
+        let shape = match node.kind.as_str() {
+            "Program" => "box",
+            "FunctionBlock" | "Class" => "component",
+            "Method" | "Action" => "oval",
+            _ => "ellipse",
+        };
+        let style = if node.external { ", style=dashed" } else { "" };
+        let _ = writeln!(dot, "    \"{}\" [shape={shape}{style}];", node.name);
+    }
+    for edge in &graph.edges {
+        let style = match edge.kind {
+            CallKind::Dispatch | CallKind::Interface => ", style=dashed",
+            CallKind::Reference => ", style=dotted",
+            _ => "",
+        };
+        let _ = writeln!(
+            dot,
+            "    \"{}\" -> \"{}\" [label=\"{}\"{style}];",
+            edge.caller,
+            edge.callee,
+            edge.kind.get_label()
+        );
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+struct Collected {
+    graph: CallGraph,
+    /// the (lowercase) names of types and POUs each POU uses, besides the ones it calls
+    uses: FxHashMap<String, FxIndexSet<String>>,
+    /// the (lowercase) names of POUs and types which are used by an entry point of the project
+    roots: FxIndexSet<String>,
+}
+
+fn collect(units: &[&CompilationUnit], index: &Index, annotations: &impl AnnotationMap) -> Collected {
+    let mut nodes = Vec::new();
+    let mut roots = FxIndexSet::default();
+    for unit in units {
+        for pou in unit.pous.iter().filter(|it| !it.location.is_internal()) {
+            if pou.kind == PouType::Program || pou.linkage.is_external_or_included() {
+                roots.insert(pou.name.to_lowercase());
+            }
+            nodes.push(CallGraphNode {
+                name: pou.name.clone(),
+                kind: pou.kind.to_string(),
+                external: pou.linkage.is_external_or_included(),
+                file: pou.name_location.get_file_name().map(str::to_string),
+                line: pou.name_location.get_line_plus_one(),
+            });
+        }
+        for action in unit.implementations.iter().filter(|it| it.pou_type == PouType::Action) {
+            if action.linkage.is_external_or_included() {
+                roots.insert(action.name.to_lowercase());
+            }
+            nodes.push(CallGraphNode {
+                name: action.name.clone(),
+                kind: action.pou_type.to_string(),
+                external: action.linkage.is_external_or_included(),
+                file: action.name_location.get_file_name().map(str::to_string),
+                line: action.name_location.get_line_plus_one(),
+            });
+        }
+    }
+
+    // globals and their initializers are used by the project itself
+    for global in index.get_globals().values().filter(|it| !it.source_location.is_internal()) {
+        roots.insert(global.get_type_name().to_lowercase());
+    }
+
+    let known = nodes.iter().map(|it| it.name.to_lowercase()).collect::<FxHashSet<_>>();
+    let mut collector = CallCollector {
+        index,
+        annotations,
+        known: &known,
+        caller: None,
+        in_operator: false,
+        edges: FxIndexSet::default(),
+        uses: FxHashMap::default(),
+        roots,
+    };
+
+    for unit in units {
+        for block in unit.global_vars.iter().filter(|it| !it.location.is_internal()) {
+            for initializer in block.variables.iter().filter_map(|it| it.initializer.as_ref()) {
+                collector.visit(initializer);
+            }
+        }
+        for pou in unit.pous.iter().filter(|it| !it.location.is_internal()) {
+            collector.caller = Some(pou.name.clone());
+            for block in &pou.variable_blocks {
+                for initializer in block.variables.iter().filter_map(|it| it.initializer.as_ref()) {
+                    collector.visit(initializer);
+                }
+            }
+        }
+        for implementation in unit.implementations.iter().filter(|it| !it.location.is_internal()) {
+            collector.caller = Some(implementation.name.clone());
+            collector.visit_statement_list(&implementation.statements);
+        }
+    }
+
+    let CallCollector { edges, uses, roots, .. } = collector;
+    Collected { graph: CallGraph { nodes, edges: edges.into_iter().collect() }, uses, roots }
+}
+
+/// Returns the POUs and types the given POU or type uses by its declaration alone
+fn get_implicit_uses<'i>(index: &'i Index, name: &str) -> Vec<&'i str> {
+    let mut result = Vec::new();
+    if let Some(pou) = index.find_pou(name) {
+        result.extend(index.get_pou_members(pou.get_name()).iter().map(|it| it.get_type_name()));
+        result.extend(pou.get_return_type());
+        result.extend(pou.get_super_class());
+        result.extend(pou.get_parent_pou_name());
+        if pou.is_function_block() || pou.is_class() {
+            // the user-defined constructor is called by the generated initializers
+            result.extend(index.find_method(pou.get_name(), "FB_INIT").map(PouIndexEntry::get_name));
+        }
+    }
+
+    if let Some(data_type) = index.find_type(name) {
+        match data_type.get_type_information() {
+            DataTypeInformation::Struct { members, .. } => {
+                result.extend(members.iter().map(|it| it.get_type_name()))
+            }
+            DataTypeInformation::Array { inner_type_name, .. }
+            | DataTypeInformation::Pointer { inner_type_name, .. } => result.push(inner_type_name),
+            DataTypeInformation::Enum { referenced_type, .. }
+            | DataTypeInformation::SubRange { referenced_type, .. }
+            | DataTypeInformation::Alias { referenced_type, .. } => result.push(referenced_type),
+            _ => {}
+        }
+    }
+
+    result
+}
+
+struct CallCollector<'a, T: AnnotationMap> {
+    index: &'a Index,
+    annotations: &'a T,
+    /// the (lowercase) names of all nodes of the graph
+    known: &'a FxHashSet<String>,
+    /// the POU whose statements are visited, `None` for global initializers
+    caller: Option<String>,
+    /// whether the operator of a call is visited, its qualifiers are not recorded as references
+    in_operator: bool,
+    edges: FxIndexSet<CallGraphEdge>,
+    uses: FxHashMap<String, FxIndexSet<String>>,
+    roots: FxIndexSet<String>,
+}
+
+impl<T: AnnotationMap> CallCollector<'_, T> {
+    fn add_edge(&mut self, callee: &str, kind: CallKind) {
+        if !self.known.contains(&callee.to_lowercase()) {
+            return;
+        }
+        match &self.caller {
+            Some(caller) => {
+                self.edges.insert(CallGraphEdge { caller: caller.clone(), callee: callee.to_string(), kind });
+            }
+            None => {
+                self.roots.insert(callee.to_lowercase());
+            }
+        }
+    }
+
+    fn add_use(&mut self, name: &str) {
+        let name = name.to_lowercase();
+        match &self.caller {
+            Some(caller) => {
+                self.uses.entry(caller.to_lowercase()).or_default().insert(name);
+            }
+            None => {
+                self.roots.insert(name);
+            }
+        }
+    }
+
+    /// Adds the edges for a call through the given operator
+    fn add_call(&mut self, operator: &AstNode) {
+        let index = self.index;
+        match self.annotations.get(operator) {
+            Some(StatementAnnotation::Function { qualified_name, call_name, .. }) => {
+                for name in std::iter::once(qualified_name).chain(call_name) {
+                    match index.find_pou(name) {
+                        Some(pou) if pou.is_method() => self.add_method_call(pou, false),
+                        _ => self.add_edge(name, CallKind::Call),
+                    }
+                }
+            }
+            Some(StatementAnnotation::Program { qualified_name }) => {
+                let is_action = index.find_pou(qualified_name).is_some_and(PouIndexEntry::is_action);
+                self.add_edge(qualified_name, if is_action { CallKind::Action } else { CallKind::Call });
+            }
+            Some(StatementAnnotation::Variable { resulting_type, .. })
+                if index.find_pou(resulting_type).is_some_and(PouIndexEntry::is_function_block) =>
+            {
+                self.add_edge(resulting_type, CallKind::Instance);
+            }
+            // calls through a virtual or interface table
+            Some(StatementAnnotation::FunctionPointer { qualified_name, .. }) => {
+                if let Some(pou) = index.find_pou(qualified_name) {
+                    self.add_method_call(pou, true);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds the edges for a call of the given method (or function block body). Methods of interfaces
+    /// are resolved to their implementations, dispatched calls also reach overriding methods.
+    fn add_method_call(&mut self, pou: &PouIndexEntry, dispatched: bool) {
+        let index = self.index;
+        if let Some(interface) = pou.get_parent_pou_name().and_then(|it| index.find_interface(it)) {
+            for implementation in get_interface_implementations(index, interface.get_name(), pou) {
+                self.add_edge(implementation, CallKind::Interface);
+            }
+            return;
+        }
+
+        self.add_edge(pou.get_name(), if pou.is_method() { CallKind::Method } else { CallKind::Instance });
+        if dispatched {
+            for implementation in get_overriding_implementations(index, pou) {
+                self.add_edge(implementation, CallKind::Dispatch);
+            }
+        }
+    }
+}
+
+impl<T: AnnotationMap> AstVisitor for CallCollector<'_, T> {
+    fn visit(&mut self, node: &AstNode) {
+        if let Some(data_type) = self.annotations.get_type(node, self.index) {
+            self.add_use(&data_type.name);
+        }
+        if let Some(StatementAnnotation::Type { type_name }) = self.annotations.get(node) {
+            self.add_use(type_name);
+        }
+        node.walk(self)
+    }
+
+    fn visit_call_statement(&mut self, stmt: &CallStatement, _node: &AstNode) {
+        self.add_call(&stmt.operator);
+
+        let outer = std::mem::replace(&mut self.in_operator, true);
+        self.visit(&stmt.operator);
+        self.in_operator = false;
+        if let Some(parameters) = &stmt.parameters {
+            self.visit(parameters);
+        }
+        self.in_operator = outer;
+    }
+
+    fn visit_reference_expr(&mut self, stmt: &ReferenceExpr, node: &AstNode) {
+        if !self.in_operator {
+            match self.annotations.get(node) {
+                Some(StatementAnnotation::Function { qualified_name, .. })
+                | Some(StatementAnnotation::Program { qualified_name })
+                | Some(StatementAnnotation::FunctionPointer { qualified_name, .. }) => {
+                    let qualified_name = qualified_name.clone();
+                    self.add_edge(&qualified_name, CallKind::Reference);
+                }
+                _ => {}
+            }
+        }
+        stmt.walk(self)
+    }
+}
+
+/// Returns the POUs extending `base` (directly or indirectly)
+fn get_derived_pous<'i>(index: &'i Index, base: &str) -> impl Iterator<Item = &'i PouIndexEntry> + 'i {
+    let base = base.to_string();
+    index.get_pous().values().filter(move |it| {
+        (it.is_function_block() || it.is_class())
+            && !it.get_name().eq_ignore_ascii_case(&base)
+            && !index.get_inheritance_chain(it.get_name(), &base).is_empty()
+    })
+}
+
+/// Returns the methods (or function block bodies) overriding the given one in derived POUs
+fn get_overriding_implementations<'i>(index: &'i Index, pou: &'i PouIndexEntry) -> Vec<&'i str> {
+    let result: FxIndexSet<&str> = match pou.get_parent_pou_name() {
+        Some(parent) if pou.is_method() => get_derived_pous(index, parent)
+            .filter_map(|it| index.find_method(it.get_name(), pou.get_call_name()))
+            .map(PouIndexEntry::get_name)
+            .collect(),
+        _ => get_derived_pous(index, pou.get_name()).map(PouIndexEntry::get_name).collect(),
+    };
+    result.into_iter().filter(|it| !it.eq_ignore_ascii_case(pou.get_name())).collect()
+}
+
+/// Returns the methods implementing the given interface method in all POUs implementing the interface
+fn get_interface_implementations<'i>(
+    index: &'i Index,
+    interface: &str,
+    method: &'i PouIndexEntry,
+) -> Vec<&'i str> {
+    let implements = |pou: &PouIndexEntry| {
+        let mut current = Some(pou);
+        let mut seen = FxHashSet::default();
+        while let Some(pou) = current.filter(|it| seen.insert(it.get_name())) {
+            let implemented = pou
+                .get_interfaces()
+                .into_iter()
+                .filter_map(|it| index.find_interface(it))
+                .flat_map(|it| it.get_interface_hierarchy(index))
+                .any(|it| it.get_name().eq_ignore_ascii_case(interface));
+            if implemented {
+                return true;
+            }
+            current = pou.get_super_class().and_then(|it| index.find_pou(it));
+        }
+        false
+    };
+
+    let result: FxIndexSet<&str> = index
+        .get_pous()
+        .values()
+        .filter(|it| (it.is_function_block() || it.is_class()) && implements(it))
+        .filter_map(|it| index.find_method(it.get_name(), method.get_call_name()))
+        .map(PouIndexEntry::get_name)
+        .collect();
+    result.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_ast::provider::IdProvider;
+
+    use super::{
+        collect_call_graph, find_unreachable, serialize_call_graph, CallGraph, CallKind, Unreachable,
+    };
+    use crate::{
+        call_graph::CallGraphFormat,
+        test_utils::tests::{annotate_with_ids, index_with_ids},
+    };
+
+    fn call_graph(src: &str) -> CallGraph {
+        let id_provider = IdProvider::default();
+        let (unit, mut index) = index_with_ids(src, id_provider.clone());
+        let annotations = annotate_with_ids(&unit, &mut index, id_provider);
+        collect_call_graph([&unit], &index, &annotations)
+    }
+
+    fn unreachable(src: &str) -> Vec<String> {
+        let id_provider = IdProvider::default();
+        let (unit, mut index) = index_with_ids(src, id_provider.clone());
+        let annotations = annotate_with_ids(&unit, &mut index, id_provider);
+        find_unreachable(&[&unit], &index, &annotations)
+            .into_iter()
+            .map(|it| match it {
+                Unreachable::Pou(pou) => pou.name.clone(),
+                Unreachable::Action(action) => action.name.clone(),
+                Unreachable::Type(user_type) => {
+                    user_type.data_type.get_name().unwrap_or_default().to_string()
+                }
+            })
+            .collect()
+    }
+
+    fn edges(graph: &CallGraph) -> Vec<(&str, &str, CallKind)> {
+        graph.edges.iter().map(|it| (it.caller.as_str(), it.callee.as_str(), it.kind)).collect()
+    }
+
+    #[test]
+    fn calls_of_functions_programs_instances_and_actions_are_edges() {
+        let graph = call_graph(
+            "
+        FUNCTION foo : INT
+        END_FUNCTION
+
+        FUNCTION_BLOCK fb
+            foo();
+        END_FUNCTION_BLOCK
+
+        PROGRAM helper
+        END_PROGRAM
+        ACTIONS helper
+            ACTION act
+            END_ACTION
+        END_ACTIONS
+
+        PROGRAM main
+        VAR
+            inst : fb;
+        END_VAR
+            inst();
+            helper();
+            helper.act();
+        END_PROGRAM",
+        );
+
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("fb", "foo", CallKind::Call),
+                ("main", "fb", CallKind::Instance),
+                ("main", "helper", CallKind::Call),
+                ("main", "helper.act", CallKind::Action),
+            ]
+        );
+    }
+
+    #[test]
+    fn method_calls_and_references_are_edges() {
+        let graph = call_graph(
+            "
+        FUNCTION callback : INT
+        END_FUNCTION
+
+        FUNCTION_BLOCK fb
+            METHOD run
+            END_METHOD
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            inst : fb;
+            address : LWORD;
+        END_VAR
+            inst.run();
+            address := ADR(callback);
+        END_PROGRAM",
+        );
+
+        assert_eq!(
+            edges(&graph),
+            vec![("main", "fb.run", CallKind::Method), ("main", "callback", CallKind::Reference)]
+        );
+    }
+
+    #[test]
+    fn call_graph_is_serialized_as_dot() {
+        let graph = call_graph(
+            "
+        FUNCTION foo : INT
+        END_FUNCTION
+
+        PROGRAM main
+            foo();
+        END_PROGRAM",
+        );
+
+        insta::assert_snapshot!(serialize_call_graph(&graph, CallGraphFormat::Dot).unwrap(), @r#"
+        digraph callgraph {
+            "foo" [shape=ellipse];
+            "main" [shape=box];
+            "main" -> "foo" [label="call"];
+        }
+        "#);
+    }
+
+    #[test]
+    fn pous_and_types_not_used_by_programs_are_unreachable() {
+        let unreachable = unreachable(
+            "
+        TYPE Used : STRUCT a : INT; END_STRUCT END_TYPE
+        TYPE Unused : STRUCT a : INT; END_STRUCT END_TYPE
+        TYPE Color : (red, green) END_TYPE
+
+        FUNCTION used : INT
+        VAR_INPUT
+            u : Used;
+        END_VAR
+        END_FUNCTION
+
+        FUNCTION unused : INT
+        VAR
+            u : Unused;
+        END_VAR
+        END_FUNCTION
+
+        FUNCTION_BLOCK fb
+            METHOD called
+            END_METHOD
+            METHOD never_called
+            END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK unused_fb
+        END_FUNCTION_BLOCK
+
+        {external}
+        FUNCTION ext : INT
+        END_FUNCTION
+
+        PROGRAM main
+        VAR
+            inst : fb;
+            c : INT;
+        END_VAR
+            used(u := (a := 1));
+            inst.called();
+            c := Color#green;
+        END_PROGRAM",
+        );
+
+        assert_eq!(unreachable, vec!["unused", "fb.never_called", "unused_fb", "Unused"]);
+    }
+
+    #[test]
+    fn interface_implementations_are_reachable() {
+        let unreachable = unreachable(
+            "
+        INTERFACE IShape
+            METHOD area : INT
+            END_METHOD
+        END_INTERFACE
+
+        FUNCTION_BLOCK square IMPLEMENTS IShape
+            METHOD area : INT
+            END_METHOD
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            square_instance : square;
+            shape : IShape;
+        END_VAR
+            shape := square_instance;
+            shape.area();
+        END_PROGRAM",
+        );
+
+        assert_eq!(unreachable, Vec::<String>::new());
+    }
+}
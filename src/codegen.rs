@@ -20,8 +20,10 @@ use self::{
     llvm_index::LlvmTypedIndex,
 };
 use crate::{
+    datalayout::{Bytes, DataLayout},
     output::{FormatOption, RelocationPreference},
     resolver::{AstAnnotations, Dependency, StringLiterals},
+    stack_usage::{parse_stack_usage, FrameSize},
     DebugLevel, OnlineChange, OptimizationLevel, Target,
 };
 
@@ -33,20 +35,21 @@ use inkwell::{
     execution_engine::{ExecutionEngine, JitFunction},
     memory_buffer::MemoryBuffer,
     support::LLVMString,
-    types::BasicType,
+    types::{AnyType, BasicType},
     values::BasicValue,
     AddressSpace,
 };
 use inkwell::{
     module::Module,
     passes::PassBuilderOptions,
-    targets::{CodeModel, FileType, InitializationConfig, RelocMode},
+    targets::{CodeModel, FileType, InitializationConfig, RelocMode, TargetData, TargetMachine},
     types::BasicTypeEnum,
 };
 use plc_ast::ast::{CompilationUnit, LinkageType, PouType};
 use plc_diagnostics::diagnostics::Diagnostic;
 use plc_llvm::TargetMachineExt;
 use plc_source::source_location::{FileMarker, SourceLocation};
+use rustc_hash::FxHashMap;
 
 mod debug;
 pub(crate) mod generators;
@@ -72,6 +75,50 @@ impl Deref for CodegenContext {
     }
 }
 
+/// Loads the shared library at `path` into the current process, making its symbols available to
+/// modules executed through the JIT
+pub fn load_library_permanently(path: &Path) -> Result<(), CodegenError> {
+    inkwell::support::load_library_permanently(path).map_err(|err| {
+        CodegenError::new(
+            format!("Cannot load library {}: {err}", path.display()),
+            SourceLocation::undefined(),
+        )
+    })
+}
+
+/// Returns the address of `symbol` in the current process or in any permanently loaded library
+pub fn find_symbol_address(symbol: &str) -> Option<usize> {
+    inkwell::support::search_for_address_of_symbol(symbol)
+}
+
+/// Makes `address` available as `symbol` to modules executed through the JIT and to
+/// [`find_symbol_address`], taking precedence over the symbols of loaded libraries
+pub fn add_symbol(symbol: &str, address: usize) {
+    let Ok(symbol) = std::ffi::CString::new(symbol) else { return };
+    unsafe { inkwell::llvm_sys::support::LLVMAddSymbol(symbol.as_ptr(), address as *mut std::ffi::c_void) }
+}
+
+/// Returns the sizes and alignments the code generator uses for the primitive types on the given target
+pub fn get_target_data_layout(target: &Target) -> Result<DataLayout, CodegenError> {
+    let machine =
+        GeneratedModule::create_target_machine(target, RelocMode::Default, OptimizationLevel::None)?;
+    let target_data = machine.get_target_data();
+    let context = Context::create();
+    let alignment = |it: &dyn AnyType| Bytes::new(target_data.get_abi_alignment(it));
+    Ok(DataLayout {
+        // booleans are stored as bytes
+        i1: alignment(&context.i8_type()),
+        i8: alignment(&context.i8_type()),
+        i16: alignment(&context.i16_type()),
+        i32: alignment(&context.i32_type()),
+        i64: alignment(&context.i64_type()),
+        f32: alignment(&context.f32_type()),
+        f64: alignment(&context.f64_type()),
+        p64: Bytes::new(target_data.get_pointer_byte_size(None)),
+        ..DataLayout::default()
+    })
+}
+
 /// the codegen struct carries all dependencies required to generate
 /// the IR code for a compilation unit
 pub struct CodeGen<'ink> {
@@ -528,7 +575,8 @@ impl<'ink> GeneratedModule<'ink> {
         }
     }
 
-    fn get_output_file(output_dir: Option<&Path>, output_name: &str, target: &Target) -> PathBuf {
+    /// Returns the path a module with the given output name is persisted to
+    pub fn get_output_file(output_dir: Option<&Path>, output_name: &str, target: &Target) -> PathBuf {
         let output_dir = output_dir.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(""));
         let output = if let Some(name) = target.try_get_name() {
             output_dir.join(name).join(output_name)
@@ -542,16 +590,12 @@ impl<'ink> GeneratedModule<'ink> {
         &self.location
     }
 
-    ///
-    /// Compiles the given source into an object file and saves it in output
-    ///
-    fn persist_to_obj(
-        &self,
-        output: PathBuf,
-        reloc: RelocMode,
+    /// Creates the target machine used to compile the module into an object
+    fn create_target_machine(
         target: &Target,
+        reloc: RelocMode,
         optimization_level: OptimizationLevel,
-    ) -> Result<PathBuf, CodegenError> {
+    ) -> Result<TargetMachine, CodegenError> {
         let initialization_config = &InitializationConfig::default();
         inkwell::targets::Target::initialize_all(initialization_config);
 
@@ -571,6 +615,20 @@ impl<'ink> GeneratedModule<'ink> {
             .ok_or_else(|| CodegenError::new("Cannot create target machine.", SourceLocation::undefined()))?;
 
         machine.use_init_array(true);
+        Ok(machine)
+    }
+
+    ///
+    /// Compiles the given source into an object file and saves it in output
+    ///
+    fn persist_to_obj(
+        &self,
+        output: PathBuf,
+        reloc: RelocMode,
+        target: &Target,
+        optimization_level: OptimizationLevel,
+    ) -> Result<PathBuf, CodegenError> {
+        let machine = Self::create_target_machine(target, reloc, optimization_level)?;
 
         //Make sure all parents exist
         if let Some(parent) = output.parent() {
@@ -583,6 +641,28 @@ impl<'ink> GeneratedModule<'ink> {
         Ok(output)
     }
 
+    /// Compiles a copy of the module into an object in memory and returns the stack frame sizes the
+    /// code generator reported for its functions, see [`crate::stack_usage::parse_stack_usage`]
+    pub fn get_frame_sizes(
+        &self,
+        target: &Target,
+        optimization_level: OptimizationLevel,
+    ) -> Result<FxHashMap<String, FrameSize>, CodegenError> {
+        let mut machine = Self::create_target_machine(target, RelocMode::Default, optimization_level)?;
+        let report = tempfile::NamedTempFile::new()?;
+        machine.set_stack_usage_output(&report.path().to_string_lossy());
+
+        // the passes modify the module, the module itself may still be persisted afterwards
+        let module = self.module.clone();
+        module
+            .run_passes(optimization_level.opt_params(), &machine, PassBuilderOptions::create())
+            .and_then(|_| machine.write_to_memory_buffer(&module, FileType::Object))?;
+
+        // the report is written once the code generator is done with the module
+        let content = std::fs::read_to_string(report.path())?;
+        Ok(parse_stack_usage(&content))
+    }
+
     /// Persists a given LLVM module to a static object and saves the output.
     ///
     /// # Arguments
@@ -704,6 +784,124 @@ impl<'ink> GeneratedModule<'ink> {
         }
     }
 
+    ///
+    /// Runs the static constructors (`llvm.global_ctors`) of the module inside the JIT
+    ///
+    pub fn run_static_constructors(&self) {
+        self.get_execution_engine().run_static_constructors();
+    }
+
+    ///
+    /// Calls the POU given by `name` with a pointer to its instance, e.g. a `PROGRAM` with the
+    /// address of its `<name>_instance` global. Static constructors are not run.
+    ///
+    pub fn call_with_instance(&self, name: &str, instance: usize) -> Result<(), CodegenError> {
+        let engine = self.get_execution_engine();
+        unsafe {
+            let pou: JitFunction<MainFunction<u8, ()>> = engine.get_function(name).map_err(|err| {
+                CodegenError::new(format!("Cannot call {name}: {err}"), SourceLocation::undefined())
+            })?;
+            pou.call(instance as *mut u8);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the address of the global variable given by `name` inside the JIT-compiled module
+    ///
+    pub fn get_global_address(&self, name: &str) -> Option<usize> {
+        self.module.get_global(name)?;
+        let engine = self.get_execution_engine();
+        let name = std::ffi::CString::new(name).ok()?;
+        let address = unsafe {
+            inkwell::llvm_sys::execution_engine::LLVMGetGlobalValueAddress(engine.as_mut_ptr(), name.as_ptr())
+        };
+        (address != 0).then_some(address as usize)
+    }
+
+    ///
+    /// Returns the byte offset of the member at position `index` inside the struct type `type_name`,
+    /// as computed from the module's data layout
+    ///
+    pub fn get_member_offset(&self, type_name: &str, index: u32) -> Option<u64> {
+        let struct_type = self.module.get_struct_type(type_name)?;
+        let target_data = TargetData::create(self.module.get_data_layout().as_str().to_str().ok()?);
+        target_data.offset_of_element(&struct_type, index)
+    }
+
+    ///
+    /// Returns the size in bytes of the struct type `type_name`, as computed from the module's data layout
+    ///
+    pub fn get_struct_size(&self, type_name: &str) -> Option<u64> {
+        let struct_type = self.module.get_struct_type(type_name)?;
+        let target_data = TargetData::create(self.module.get_data_layout().as_str().to_str().ok()?);
+        Some(target_data.get_abi_size(&struct_type))
+    }
+
+    ///
+    /// Calls the parameterless function given by `name` inside the compiled module.
+    /// Static constructors are not run.
+    ///
+    pub fn call(&self, name: &str) -> Result<(), CodegenError> {
+        let engine = self.get_execution_engine();
+        unsafe {
+            let function: JitFunction<MainEmptyFunction<()>> = engine.get_function(name).map_err(|err| {
+                CodegenError::new(format!("Cannot call {name}: {err}"), SourceLocation::undefined())
+            })?;
+            function.call();
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the names of all functions and global variables that are only declared in this module
+    /// and have to be resolved when it is executed. LLVM intrinsics are not included.
+    ///
+    pub fn get_declared_symbols(&self) -> Vec<String> {
+        self.get_symbols(true)
+    }
+
+    ///
+    /// Returns the names of all functions and global variables defined in this module
+    ///
+    pub fn get_defined_symbols(&self) -> Vec<String> {
+        self.get_symbols(false)
+    }
+
+    fn get_symbols(&self, declarations: bool) -> Vec<String> {
+        self.module
+            .get_functions()
+            .map(|it| it.as_global_value())
+            .chain(self.module.get_globals())
+            .filter(|it| it.is_declaration() == declarations)
+            .map(|it| it.get_name().to_string_lossy().to_string())
+            .filter(|it| !it.starts_with("llvm."))
+            .collect()
+    }
+
+    ///
+    /// Returns the address of the function or global variable given by `name` inside the JIT-compiled module
+    ///
+    pub fn get_symbol_address(&self, name: &str) -> Option<usize> {
+        if self.module.get_function(name).is_some() {
+            self.get_execution_engine().get_function_address(name).ok()
+        } else {
+            self.get_global_address(name)
+        }
+    }
+
+    ///
+    /// Resolves the declared function or global variable given by `name` to `address`,
+    /// e.g. a symbol defined by another JIT-compiled module
+    ///
+    pub fn add_symbol_mapping(&self, name: &str, address: usize) {
+        if self.module.get_function(name).is_some() {
+            self.add_global_function_mapping(name, address)
+        } else {
+            self.add_global_variable_mapping(name, address)
+        }
+    }
+
     pub fn add_global_function_mapping(&self, function_name: &str, local_function: usize) {
         let engine = self.get_execution_engine();
         if let Some(function) = self.module.get_function(function_name) {
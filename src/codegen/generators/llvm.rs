@@ -1,7 +1,7 @@
 use crate::codegen::generators::data_type_generator::get_default_for;
 use crate::codegen::llvm_index::LlvmTypedIndex;
 use crate::codegen::CodegenError;
-use crate::index::Index;
+use crate::index::{Index, RetainSection};
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use crate::typesystem::{CHAR_TYPE, WCHAR_TYPE};
 use inkwell::types::{ArrayType, BasicType};
@@ -34,13 +34,11 @@ pub struct Llvm<'a> {
     pub target_triple: String,
 }
 
-static RETAIN_SECTION_NAME: &str = ".retain";
-
 pub trait GlobalValueExt {
     fn make_constant(self) -> Self;
     fn make_private(self) -> Self;
     fn make_external(self) -> Self;
-    fn make_retain(self) -> Self;
+    fn make_retain(self, section: RetainSection) -> Self;
     fn set_initial_value(self, initial_value: Option<BasicValueEnum>, data_type: BasicTypeEnum) -> Self;
 }
 
@@ -71,8 +69,8 @@ impl GlobalValueExt for GlobalValue<'_> {
         self
     }
 
-    fn make_retain(self) -> Self {
-        self.set_section(Some(RETAIN_SECTION_NAME));
+    fn make_retain(self, section: RetainSection) -> Self {
+        self.set_section(Some(section.get_name()));
         self
     }
 }
@@ -176,8 +176,8 @@ impl<'ctx, 'b> VariableGenerator<'ctx, 'b> {
             global_ir_variable = global_ir_variable.make_constant();
         };
 
-        if global_variable.should_retain(self.global_index) {
-            global_ir_variable = global_ir_variable.make_retain();
+        if let Some(section) = global_variable.get_retain_section(self.global_index) {
+            global_ir_variable = global_ir_variable.make_retain(section);
         };
 
         let global_name = if global_variable.get_name().ends_with("instance") {
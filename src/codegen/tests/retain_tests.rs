@@ -186,3 +186,31 @@ fn nested_retain_variables_are_in_the_retain_section() {
     }
     "#);
 }
+
+#[test]
+fn persistent_variables_are_in_persistent_linker_section() {
+    let res = codegen(
+        "
+        VAR_GLOBAL PERSISTENT
+            x : INT;
+        END_VAR
+        VAR_GLOBAL RETAIN
+            y : INT;
+        END_VAR
+        VAR_GLOBAL RETAIN PERSISTENT
+            z : STRING;
+        END_VAR
+        ",
+    );
+
+    filtered_assert_snapshot!(res, @r#"
+    ; ModuleID = '<internal>'
+    source_filename = "<internal>"
+    target datalayout = "[filtered]"
+    target triple = "[filtered]"
+
+    @x = global i16 0, section ".persistent"
+    @y = global i16 0, section ".retain"
+    @z = global [81 x i8] zeroinitializer, section ".retain_persistent"
+    "#);
+}
@@ -1,7 +1,13 @@
 use std::ops::{Add, AddAssign};
 
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    index::{Index, VariableIndexEntry},
+    typesystem::{DataTypeInformation, StringEncoding},
+};
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct DataLayout {
     pub i1: Bytes,
@@ -36,8 +42,101 @@ impl Default for DataLayout {
     }
 }
 
+impl DataLayout {
+    /// Returns the alignment of values of the given type in memory
+    pub fn get_alignment(&self, information: &DataTypeInformation, index: &Index) -> Bytes {
+        match information {
+            DataTypeInformation::Integer { size, .. } => match size {
+                0..=8 => self.i8,
+                9..=16 => self.i16,
+                17..=32 => self.i32,
+                _ => self.i64,
+            },
+            DataTypeInformation::Float { size, .. } if *size <= 32 => self.f32,
+            DataTypeInformation::Float { .. } => self.f64,
+            DataTypeInformation::String { encoding: StringEncoding::Utf8, .. } => self.i8,
+            DataTypeInformation::String { encoding: StringEncoding::Utf16, .. } => self.i16,
+            DataTypeInformation::Pointer { .. } | DataTypeInformation::Interface { .. } => self.p64,
+            DataTypeInformation::Struct { members, .. } => members
+                .iter()
+                .filter(|it| is_stored(it))
+                .map(|it| self.get_alignment(index.get_type_information_or_void(it.get_type_name()), index))
+                .max()
+                .unwrap_or(self.i8),
+            DataTypeInformation::Array { inner_type_name: referenced_type, .. }
+            | DataTypeInformation::Enum { referenced_type, .. }
+            | DataTypeInformation::Alias { referenced_type, .. }
+            | DataTypeInformation::SubRange { referenced_type, .. } => {
+                self.get_alignment(index.get_type_information_or_void(referenced_type), index)
+            }
+            DataTypeInformation::Generic { .. } | DataTypeInformation::Void => self.i8,
+        }
+    }
+
+    /// Returns the size of values of the given type in memory. Unlike [`DataTypeInformation::get_size`]
+    /// it includes the padding the generated code adds between and after the members of structs.
+    pub fn get_size(&self, information: &DataTypeInformation, index: &Index) -> Result<Bytes> {
+        match information {
+            DataTypeInformation::Struct { members, .. } => Ok(self.get_struct_layout(members, index)?.size),
+            DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+                let inner_size = self.get_size(index.get_type_information_or_void(inner_type_name), index)?;
+                let element_count = dimensions
+                    .iter()
+                    .map(|it| it.get_length(index))
+                    .product::<Result<u32, String>>()
+                    .map_err(|err| anyhow::anyhow!(err))?;
+                Ok(Bytes::new(inner_size.value() * element_count))
+            }
+            DataTypeInformation::Enum { referenced_type, .. }
+            | DataTypeInformation::Alias { referenced_type, .. }
+            | DataTypeInformation::SubRange { referenced_type, .. } => {
+                self.get_size(index.get_type_information_or_void(referenced_type), index)
+            }
+            DataTypeInformation::Pointer { .. } => Ok(self.p64),
+            // a reference to an interface is a pointer to the instance and one to its table
+            DataTypeInformation::Interface { .. } => Ok(self.p64 + self.p64),
+            _ => information.get_size(index),
+        }
+    }
+
+    /// Returns the offsets of the members a struct stores and its size, including the padding after
+    /// its last member
+    pub fn get_struct_layout<'a>(
+        &self,
+        members: &'a [VariableIndexEntry],
+        index: &Index,
+    ) -> Result<StructLayout<'a>> {
+        let mut offset = MemoryLocation::new(0);
+        let mut alignment = self.i8;
+        let mut offsets = vec![];
+        for member in members.iter().filter(|it| is_stored(it)) {
+            let information = index.get_type_information_or_void(member.get_type_name());
+            let member_alignment = self.get_alignment(information, index);
+            offset = offset.align_to(member_alignment);
+            offsets.push((member, offset));
+            offset += self.get_size(information, index)?;
+            alignment = alignment.max(member_alignment);
+        }
+        Ok(StructLayout { offsets, size: offset.align_to(alignment).into() })
+    }
+}
+
+/// Returns whether a member is stored in its struct, temporary variables, the return value and
+/// external variables of a POU are not
+fn is_stored(member: &VariableIndexEntry) -> bool {
+    !(member.is_temp() || member.is_return() || member.is_var_external())
+}
+
+/// The memory layout of a struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLayout<'a> {
+    /// the stored members with their offset from the start of the struct
+    pub offsets: Vec<(&'a VariableIndexEntry, MemoryLocation)>,
+    pub size: Bytes,
+}
+
 /// An representation of a Byte unit, used to represent sizes, and alignments
-#[derive(PartialEq, Eq, Copy, Clone, Debug, PartialOrd, Default, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub struct Bytes(u32);
 
 impl Add for Bytes {
@@ -190,4 +289,38 @@ mod tests {
         // The size is 120 bytes and the struct is not aligned
         assert_eq!(struct_type.get_size(&index).unwrap().bits(), 120);
     }
+
+    #[test]
+    fn struct_layout_pads_members_to_their_alignment() {
+        let (_, index) = index(
+            "
+        TYPE MyStruct : STRUCT
+            a : BYTE;
+            b : DWORD;
+            c : WORD;
+            d : LWORD;
+            e : STRING[4];
+        END_STRUCT
+        END_TYPE
+
+        TYPE Outer : STRUCT
+            flag : BOOL;
+            inner : ARRAY[0..1] OF MyStruct;
+        END_STRUCT
+        END_TYPE
+        ",
+        );
+
+        let layout = index.get_type_layout();
+        let members = index.get_container_members("MyStruct");
+        let struct_layout = layout.get_struct_layout(members, &index).unwrap();
+        let offsets = struct_layout.offsets.iter().map(|(_, offset)| offset.value()).collect::<Vec<_>>();
+        assert_eq!(offsets, [0, 4, 8, 16, 24]);
+        // the 5 characters of the string are padded to the alignment of the LWORD
+        assert_eq!(struct_layout.size, Bytes::new(32));
+
+        let outer = index.get_type_information_or_void("Outer");
+        assert_eq!(layout.get_alignment(outer, &index), Bytes::new(8));
+        assert_eq!(layout.get_size(outer, &index).unwrap(), Bytes::new(72));
+    }
 }
@@ -0,0 +1,1022 @@
+//! API documentation of a project, generated from the doc comments of its declarations like
+//! `rustdoc` does for Rust.
+//!
+//! The parser attaches `(** ... *)` and `/// ...` comments to the declaration following them, see
+//! [`CompilationUnit::doc_comments`]. The documented items, i.e. POUs, interfaces and data types
+//! with their variables, methods and properties, are collected from the parsed AST so they describe
+//! the code as it was written. Their types, the values of enum elements and the inheritance and
+//! interface relations are resolved through the [`Index`] afterwards, see [`resolve_documentation`].
+//!
+//! Every item is rendered to a page of its own, linked from an index page listing all items and the
+//! global variables. Types referring to a documented item link to its page.
+
+use std::fmt::Write;
+
+use clap::clap_derive::ArgEnum;
+use itertools::Itertools;
+use plc_ast::ast::{
+    AutoDerefType, CompilationUnit, DataType, DataTypeDeclaration, DocComment, LinkageType, Pou, PouType,
+    PropertyBlock, PropertyKind, Variable, VariableBlock, VariableBlockType,
+};
+use plc_source::source_location::SourceLocation;
+
+use crate::{
+    index::{Index, PouIndexEntry, VariableIndexEntry},
+    lowering::polymorphism::table::interface::helper::{
+        collect_ancestor_interfaces, collect_interfaces_for_pou,
+    },
+    typesystem::{DataTypeInformation, StringEncoding, VOID_TYPE},
+};
+
+/// The documented items of a project
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Documentation {
+    /// the POUs, interfaces and data types, ordered by their kind and name
+    pub items: Vec<DocItem>,
+    pub globals: Vec<DocVariable>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DocKind {
+    Program,
+    FunctionBlock,
+    Class,
+    Function,
+    Interface,
+    Type,
+    Method,
+}
+
+impl DocKind {
+    fn get_keyword(&self) -> &'static str {
+        match self {
+            DocKind::Program => "PROGRAM",
+            DocKind::FunctionBlock => "FUNCTION_BLOCK",
+            DocKind::Class => "CLASS",
+            DocKind::Function => "FUNCTION",
+            DocKind::Interface => "INTERFACE",
+            DocKind::Type => "TYPE",
+            DocKind::Method => "METHOD",
+        }
+    }
+
+    fn get_title(&self) -> &'static str {
+        match self {
+            DocKind::Program => "Programs",
+            DocKind::FunctionBlock => "Function Blocks",
+            DocKind::Class => "Classes",
+            DocKind::Function => "Functions",
+            DocKind::Interface => "Interfaces",
+            DocKind::Type => "Data Types",
+            DocKind::Method => "Methods",
+        }
+    }
+}
+
+/// A POU, interface, data type or method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocItem {
+    /// the qualified name of the item, e.g. `motor.start` for a method
+    pub name: String,
+    pub kind: DocKind,
+    pub doc: Option<String>,
+    /// the declaration of the item, e.g. `FUNCTION_BLOCK motor EXTENDS drive IMPLEMENTS IStartable`
+    pub signature: String,
+    /// the return type of a function or method, or the definition of a data type
+    pub data_type: Option<DocType>,
+    pub variables: Vec<DocVariable>,
+    pub properties: Vec<DocVariable>,
+    pub methods: Vec<DocItem>,
+    /// the elements of an enum with their values
+    pub elements: Vec<(String, Option<i128>)>,
+    /// the related items, e.g. the super class or the implemented interfaces
+    pub relations: Vec<(Relation, Vec<String>)>,
+}
+
+impl DocItem {
+    fn new(name: &str, kind: DocKind, doc: Option<&str>) -> Self {
+        DocItem {
+            name: name.to_string(),
+            kind,
+            doc: doc.map(str::to_string),
+            signature: format!("{} {name}", kind.get_keyword()),
+            data_type: None,
+            variables: vec![],
+            properties: vec![],
+            methods: vec![],
+            elements: vec![],
+            relations: vec![],
+        }
+    }
+
+    fn get_simple_name(&self) -> &str {
+        self.name.rsplit('.').next().unwrap_or(&self.name)
+    }
+
+    fn get_file_name(&self, format: DocFormat) -> String {
+        format!("{}.{}.{}", self.kind.get_keyword().to_lowercase(), self.name, format.get_extension())
+    }
+}
+
+/// A variable, parameter, struct member or property
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocVariable {
+    pub name: String,
+    /// the block declaring the variable, e.g. `VAR_INPUT`, or the accessors of a property
+    pub section: String,
+    pub data_type: DocType,
+    pub doc: Option<String>,
+}
+
+/// A type as it is declared in ST, e.g. `ARRAY[0..9] OF Point`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DocType {
+    pub text: String,
+    /// the documented item the type refers to, e.g. `Point`
+    pub link: Option<String>,
+}
+
+impl DocType {
+    fn new(text: impl Into<String>) -> Self {
+        DocType { text: text.into(), link: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// the super class of a POU or the interfaces an interface extends, including their ancestors
+    Extends,
+    /// the interfaces a POU implements, including the interfaces inherited from its super classes
+    Implements,
+    ExtendedBy,
+    ImplementedBy,
+}
+
+impl Relation {
+    fn get_label(&self) -> &'static str {
+        match self {
+            Relation::Extends => "Extends",
+            Relation::Implements => "Implements",
+            Relation::ExtendedBy => "Extended by",
+            Relation::ImplementedBy => "Implemented by",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+impl DocFormat {
+    fn get_extension(&self) -> &'static str {
+        match self {
+            DocFormat::Markdown => "md",
+            DocFormat::Html => "html",
+        }
+    }
+}
+
+fn is_generated(name: &str, location: &SourceLocation) -> bool {
+    location.is_internal() || name.starts_with("__")
+}
+
+/// Collects the documented items declared in the given units. Included units, e.g. the headers of
+/// libraries, are not documented. The types are the declared ones until they are resolved with
+/// [`resolve_documentation`].
+pub fn collect_documentation<'a>(units: impl IntoIterator<Item = &'a CompilationUnit>) -> Documentation {
+    let units = units.into_iter().filter(|it| it.linkage == LinkageType::Internal).collect::<Vec<_>>();
+    let doc_comments = units.iter().flat_map(|it| &it.doc_comments).collect::<Vec<_>>();
+    let collector = DocCollector { doc_comments };
+
+    let pous = units.iter().flat_map(|it| &it.pous).collect::<Vec<_>>();
+    let mut items = vec![];
+    for pou in pous.iter().filter(|it| !is_generated(&it.name, &it.name_location)) {
+        let kind = match pou.kind {
+            PouType::Program => DocKind::Program,
+            PouType::FunctionBlock => DocKind::FunctionBlock,
+            PouType::Class => DocKind::Class,
+            PouType::Function => DocKind::Function,
+            _ => continue,
+        };
+        let mut item = collector.collect_pou(pou, kind);
+        item.methods = pous
+            .iter()
+            .filter(|it| {
+                matches!(&it.kind, PouType::Method { parent, property: None, .. } if parent == &pou.name)
+            })
+            .map(|it| collector.collect_pou(it, DocKind::Method))
+            .collect();
+        item.properties = collector.collect_properties(&pou.properties);
+        items.push(item);
+    }
+
+    for interface in units.iter().flat_map(|it| &it.interfaces) {
+        let mut item = DocItem::new(
+            &interface.ident.name,
+            DocKind::Interface,
+            collector.get_doc(&interface.ident.location),
+        );
+        item.methods =
+            interface.methods.iter().map(|it| collector.collect_pou(it, DocKind::Method)).collect();
+        item.properties = collector.collect_properties(&interface.properties);
+        items.push(item);
+    }
+
+    for user_type in units.iter().flat_map(|it| &it.user_types) {
+        let Some(name) = user_type.data_type.get_name().filter(|it| !is_generated(it, &user_type.location))
+        else {
+            continue;
+        };
+        let mut item = DocItem::new(name, DocKind::Type, collector.get_doc(&user_type.location));
+        if let DataType::StructType { variables, .. } = &user_type.data_type {
+            item.variables = variables.iter().map(|it| collector.collect_variable(it, "STRUCT")).collect();
+        }
+        items.push(item);
+    }
+    items.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+
+    let globals = units
+        .iter()
+        .flat_map(|it| &it.global_vars)
+        .flat_map(|block| {
+            let section = get_section(block);
+            block.variables.iter().map(move |it| (it, section))
+        })
+        .filter(|(it, _)| !is_generated(&it.name, &it.location))
+        .map(|(it, section)| collector.collect_variable(it, section))
+        .collect();
+
+    Documentation { items, globals }
+}
+
+struct DocCollector<'a> {
+    doc_comments: Vec<&'a DocComment>,
+}
+
+impl DocCollector<'_> {
+    fn get_doc(&self, location: &SourceLocation) -> Option<&str> {
+        self.doc_comments.iter().find(|it| &it.location == location).map(|it| it.text.as_str())
+    }
+
+    fn collect_pou(&self, pou: &Pou, kind: DocKind) -> DocItem {
+        let mut item = DocItem::new(&pou.name, kind, self.get_doc(&pou.name_location));
+        item.data_type = pou.return_type.as_ref().and_then(DataTypeDeclaration::get_name).map(DocType::new);
+        // the locals of functions and methods are an implementation detail
+        let has_instance = matches!(kind, DocKind::Program | DocKind::FunctionBlock | DocKind::Class);
+        item.variables = pou
+            .variable_blocks
+            .iter()
+            .filter(|it| match it.kind {
+                VariableBlockType::Input(_) | VariableBlockType::Output | VariableBlockType::InOut => true,
+                VariableBlockType::Local => has_instance,
+                _ => false,
+            })
+            .flat_map(|block| {
+                let section = get_section(block);
+                block.variables.iter().map(move |it| (it, section))
+            })
+            .filter(|(it, _)| !is_generated(&it.name, &it.location))
+            .map(|(it, section)| self.collect_variable(it, section))
+            .collect();
+        item
+    }
+
+    fn collect_variable(&self, variable: &Variable, section: &str) -> DocVariable {
+        DocVariable {
+            name: variable.name.clone(),
+            section: section.to_string(),
+            data_type: DocType::new(variable.data_type_declaration.get_name().unwrap_or_default()),
+            doc: self.get_doc(&variable.location).map(str::to_string),
+        }
+    }
+
+    fn collect_properties(&self, properties: &[PropertyBlock]) -> Vec<DocVariable> {
+        properties
+            .iter()
+            .map(|property| {
+                let accessors = property
+                    .implementations
+                    .iter()
+                    .map(|it| match it.kind {
+                        PropertyKind::Get => "PROPERTY_GET",
+                        PropertyKind::Set => "PROPERTY_SET",
+                    })
+                    .sorted()
+                    .dedup()
+                    .join(", ");
+                let data_type = property.implementations.first().and_then(|it| it.datatype.get_name());
+                DocVariable {
+                    name: property.ident.name.clone(),
+                    section: accessors,
+                    data_type: DocType::new(data_type.unwrap_or_default()),
+                    doc: self.get_doc(&property.ident.location).map(str::to_string),
+                }
+            })
+            .collect()
+    }
+}
+
+fn get_section(block: &VariableBlock) -> &'static str {
+    match (block.kind, block.constant) {
+        (VariableBlockType::Input(_), _) => "VAR_INPUT",
+        (VariableBlockType::Output, _) => "VAR_OUTPUT",
+        (VariableBlockType::InOut, _) => "VAR_IN_OUT",
+        (VariableBlockType::Global, true) => "VAR_GLOBAL CONSTANT",
+        (VariableBlockType::Global, false) => "VAR_GLOBAL",
+        (_, true) => "VAR CONSTANT",
+        _ => "VAR",
+    }
+}
+
+/// Resolves the types of the documented items through the index, links them to the documented items
+/// they refer to and adds the relations of POUs and interfaces
+pub fn resolve_documentation(documentation: &mut Documentation, index: &Index) {
+    let documented = documentation.items.iter().map(|it| it.name.to_lowercase()).collect::<Vec<_>>();
+    let resolve_type = |type_name: &str| {
+        let target = get_named_type(index, type_name);
+        DocType {
+            text: get_type_text(index, type_name),
+            link: documented.contains(&target.to_lowercase()).then(|| target.to_string()),
+        }
+    };
+    let resolve_variables = |container: &str, variables: &mut Vec<DocVariable>| {
+        for variable in variables {
+            if let Some(entry) = index.find_member(container, &variable.name) {
+                variable.data_type = resolve_type(entry.get_type_name());
+            }
+        }
+    };
+
+    for item in &mut documentation.items {
+        match item.kind {
+            DocKind::Type => {
+                let Some(data_type) = index.find_type(&item.name) else { continue };
+                let definition = get_definition_text(index, &data_type.information);
+                item.signature = format!("TYPE {} : {definition}", data_type.get_name());
+                item.data_type = Some(DocType::new(definition));
+                if let DataTypeInformation::Enum { variants, .. } = &data_type.information {
+                    item.elements =
+                        variants.iter().map(|it| (it.get_name().to_string(), get_value(index, it))).collect();
+                }
+            }
+            DocKind::Interface => {
+                let Some(interface) = index.find_interface(&item.name) else { continue };
+                let extensions = interface.extensions.iter().map(|it| it.name.as_str()).collect::<Vec<_>>();
+                if !extensions.is_empty() {
+                    item.signature.push_str(&format!(" EXTENDS {}", extensions.join(", ")));
+                }
+                let implemented_by = index
+                    .get_pous()
+                    .values()
+                    .filter(|pou| {
+                        collect_interfaces_for_pou(index, pou)
+                            .iter()
+                            .any(|it| it.eq_ignore_ascii_case(interface.get_name()))
+                    })
+                    .map(|it| it.get_name().to_string())
+                    .sorted_by_key(|it| it.to_lowercase())
+                    .collect();
+                let extended_by = index
+                    .get_interfaces()
+                    .values()
+                    .filter(|it| it.extensions.iter().any(|it| it.name.eq_ignore_ascii_case(&item.name)))
+                    .map(|it| it.get_name().to_string())
+                    .sorted_by_key(|it| it.to_lowercase())
+                    .collect();
+                item.relations = vec![
+                    (Relation::Extends, to_strings(collect_ancestor_interfaces(index, interface))),
+                    (Relation::ExtendedBy, extended_by),
+                    (Relation::ImplementedBy, implemented_by),
+                ];
+            }
+            _ => {
+                let Some(pou) = index.find_pou(&item.name) else { continue };
+                item.signature = get_pou_signature(pou, index);
+                let super_class = pou.get_super_class().map(|it| vec![it.to_string()]).unwrap_or_default();
+                let interfaces = collect_interfaces_for_pou(index, pou)
+                    .into_iter()
+                    .sorted_by_key(|it| it.to_lowercase())
+                    .collect::<Vec<_>>();
+                let extended_by = index
+                    .get_pous()
+                    .values()
+                    .filter(|it| it.get_super_class().is_some_and(|it| it.eq_ignore_ascii_case(&item.name)))
+                    .map(|it| it.get_name().to_string())
+                    .sorted_by_key(|it| it.to_lowercase())
+                    .collect();
+                item.relations = vec![
+                    (Relation::Extends, super_class),
+                    (Relation::Implements, to_strings(interfaces)),
+                    (Relation::ExtendedBy, extended_by),
+                ];
+            }
+        }
+        item.relations.retain(|(_, names)| !names.is_empty());
+
+        resolve_variables(&item.name, &mut item.variables);
+        for method in &mut item.methods {
+            if let Some(pou) = index.find_pou(&method.name) {
+                method.signature = get_pou_signature(pou, index);
+                method.data_type = get_return_type(pou).map(&resolve_type);
+            }
+            resolve_variables(&method.name, &mut method.variables);
+        }
+        if item.kind != DocKind::Type {
+            item.data_type = index.find_pou(&item.name).and_then(get_return_type).map(&resolve_type);
+        }
+        for property in &mut item.properties {
+            if !property.data_type.text.is_empty() {
+                property.data_type = resolve_type(&property.data_type.text);
+            }
+        }
+    }
+
+    for variable in &mut documentation.globals {
+        if let Some(entry) = index.find_global_variable(&variable.name) {
+            variable.data_type = resolve_type(entry.get_type_name());
+        }
+    }
+}
+
+fn to_strings(names: impl IntoIterator<Item = impl Into<String>>) -> Vec<String> {
+    names.into_iter().map(Into::into).collect()
+}
+
+fn get_return_type(pou: &PouIndexEntry) -> Option<&str> {
+    pou.get_return_type().filter(|it| !it.eq_ignore_ascii_case(VOID_TYPE))
+}
+
+fn get_value(index: &Index, variant: &VariableIndexEntry) -> Option<i128> {
+    let id = variant.initial_value.as_ref()?;
+    index.get_const_expressions().get_constant_int_statement_value(id).ok()
+}
+
+fn get_pou_signature(pou: &PouIndexEntry, index: &Index) -> String {
+    let kind = match pou {
+        PouIndexEntry::Program { .. } => "PROGRAM",
+        PouIndexEntry::FunctionBlock { .. } => "FUNCTION_BLOCK",
+        PouIndexEntry::Function { .. } => "FUNCTION",
+        PouIndexEntry::Class { .. } => "CLASS",
+        PouIndexEntry::Method { .. } => "METHOD",
+        PouIndexEntry::Action { .. } => "ACTION",
+    };
+    let name = pou.get_name();
+    let mut signature = format!("{kind} {}", name.rsplit('.').next().unwrap_or(name));
+    if let Some(return_type) = get_return_type(pou) {
+        signature.push_str(&format!(" : {}", get_type_text(index, return_type)));
+    }
+    if let Some(super_class) = pou.get_super_class() {
+        signature.push_str(&format!(" EXTENDS {super_class}"));
+    }
+    let interfaces = pou.get_interfaces();
+    if !interfaces.is_empty() {
+        signature.push_str(&format!(" IMPLEMENTS {}", interfaces.join(", ")));
+    }
+    signature
+}
+
+/// Returns the type as it is declared in ST. Types the compiler generated for inline declarations,
+/// e.g. `ARRAY[0..9] OF INT`, are described by their definition.
+pub(crate) fn get_type_text(index: &Index, type_name: &str) -> String {
+    match index.find_type(type_name) {
+        Some(data_type) if type_name.starts_with("__") => get_definition_text(index, &data_type.information),
+        Some(data_type) => data_type.get_name().to_string(),
+        None => type_name.to_string(),
+    }
+}
+
+fn get_definition_text(index: &Index, information: &DataTypeInformation) -> String {
+    let get_int = |size: &crate::typesystem::TypeSize| {
+        size.as_int_value(index).map(|it| it.to_string()).unwrap_or_else(|_| "*".to_string())
+    };
+    match information {
+        DataTypeInformation::Struct { .. } => "STRUCT".to_string(),
+        DataTypeInformation::Enum { variants, .. } => {
+            format!("({})", variants.iter().map(VariableIndexEntry::get_name).join(", "))
+        }
+        DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+            let dimensions = dimensions
+                .iter()
+                .map(|it| format!("{}..{}", get_int(&it.start_offset), get_int(&it.end_offset)))
+                .join(", ");
+            format!("ARRAY[{dimensions}] OF {}", get_type_text(index, inner_type_name))
+        }
+        DataTypeInformation::Pointer { inner_type_name, auto_deref, type_safe, .. } => {
+            let keyword = match auto_deref {
+                Some(AutoDerefType::Reference) => "REFERENCE TO",
+                _ if *type_safe => "REF_TO",
+                _ => "POINTER TO",
+            };
+            format!("{keyword} {}", get_type_text(index, inner_type_name))
+        }
+        DataTypeInformation::String { size, encoding, .. } => {
+            let keyword = match encoding {
+                StringEncoding::Utf8 => "STRING",
+                StringEncoding::Utf16 => "WSTRING",
+            };
+            // the size includes the terminator
+            match size.as_int_value(index) {
+                Ok(size) => format!("{keyword}[{}]", size - 1),
+                Err(_) => keyword.to_string(),
+            }
+        }
+        DataTypeInformation::SubRange { referenced_type, sub_range, .. } => {
+            format!(
+                "{}({}..{})",
+                get_type_text(index, referenced_type),
+                get_int(&sub_range.start),
+                get_int(&sub_range.end)
+            )
+        }
+        DataTypeInformation::Alias { referenced_type, .. } => get_type_text(index, referenced_type),
+        _ => information.get_name().to_string(),
+    }
+}
+
+/// Returns the named type a type refers to, e.g. `Point` for `ARRAY[0..9] OF REF_TO Point`
+fn get_named_type<'a>(index: &'a Index, type_name: &'a str) -> &'a str {
+    if !type_name.starts_with("__") {
+        return index.find_type(type_name).map(|it| it.get_name()).unwrap_or(type_name);
+    }
+    match index.find_type(type_name).map(|it| &it.information) {
+        Some(DataTypeInformation::Array { inner_type_name, .. })
+        | Some(DataTypeInformation::Pointer { inner_type_name, .. }) => {
+            get_named_type(index, inner_type_name)
+        }
+        Some(DataTypeInformation::SubRange { referenced_type, .. })
+        | Some(DataTypeInformation::Alias { referenced_type, .. }) => get_named_type(index, referenced_type),
+        _ => type_name,
+    }
+}
+
+/// Renders the documentation as pages of the given format, returned as pairs of file name and content.
+/// The first page is the index page listing all items.
+pub fn render_documentation(
+    documentation: &Documentation,
+    title: &str,
+    format: DocFormat,
+) -> Vec<(String, String)> {
+    let renderer = Renderer { documentation, format };
+    let mut pages = vec![(format!("index.{}", format.get_extension()), renderer.render_index(title))];
+    for item in &documentation.items {
+        pages.push((item.get_file_name(format), renderer.render_item(item, title)));
+    }
+    pages
+}
+
+/// An inline element of a line or table cell
+enum Inline {
+    Text(String),
+    Code(String),
+    /// a code span linking to the given file
+    Link(String, String),
+}
+
+struct Renderer<'a> {
+    documentation: &'a Documentation,
+    format: DocFormat,
+}
+
+impl Renderer<'_> {
+    fn get_item(&self, name: &str) -> Option<&DocItem> {
+        self.documentation.items.iter().find(|it| it.name.eq_ignore_ascii_case(name))
+    }
+
+    fn to_link(&self, name: &str) -> Inline {
+        match self.get_item(name) {
+            Some(item) => Inline::Link(name.to_string(), item.get_file_name(self.format)),
+            None => Inline::Code(name.to_string()),
+        }
+    }
+
+    fn to_type(&self, data_type: &DocType) -> Inline {
+        match data_type.link.as_deref().and_then(|it| self.get_item(it)) {
+            Some(item) => Inline::Link(data_type.text.clone(), item.get_file_name(self.format)),
+            None => Inline::Code(data_type.text.clone()),
+        }
+    }
+
+    fn render_index(&self, title: &str) -> String {
+        let mut page = Page::new(self.format);
+        page.heading(1, title);
+        for (kind, items) in &self.documentation.items.iter().chunk_by(|it| it.kind) {
+            page.heading(2, kind.get_title());
+            let rows = items
+                .map(|it| vec![vec![self.to_link(&it.name)], vec![Inline::Text(get_summary(&it.doc))]])
+                .collect::<Vec<_>>();
+            page.table(&["Name", "Description"], &rows);
+        }
+        if !self.documentation.globals.is_empty() {
+            page.heading(2, "Global Variables");
+            self.variable_table(&mut page, &self.documentation.globals);
+        }
+        page.finish(title)
+    }
+
+    fn render_item(&self, item: &DocItem, title: &str) -> String {
+        let mut page = Page::new(self.format);
+        let heading = format!("{} {}", item.kind.get_keyword(), item.name);
+        page.heading(1, &heading);
+        page.code_block(&item.signature);
+        if let Some(doc) = &item.doc {
+            page.doc(doc);
+        }
+        for (relation, names) in &item.relations {
+            let mut line = vec![Inline::Text(format!("{}: ", relation.get_label()))];
+            for (i, name) in names.iter().enumerate() {
+                if i > 0 {
+                    line.push(Inline::Text(", ".to_string()));
+                }
+                line.push(self.to_link(name));
+            }
+            page.line(&line);
+        }
+        if !item.elements.is_empty() {
+            page.heading(2, "Elements");
+            let rows = item
+                .elements
+                .iter()
+                .map(|(name, value)| {
+                    vec![
+                        vec![Inline::Code(name.clone())],
+                        vec![Inline::Text(value.map(|it| it.to_string()).unwrap_or_default())],
+                    ]
+                })
+                .collect::<Vec<_>>();
+            page.table(&["Name", "Value"], &rows);
+        }
+        if !item.variables.is_empty() {
+            page.heading(2, if item.kind == DocKind::Type { "Members" } else { "Variables" });
+            self.variable_table(&mut page, &item.variables);
+        }
+        if !item.properties.is_empty() {
+            page.heading(2, "Properties");
+            self.variable_table(&mut page, &item.properties);
+        }
+        if !item.methods.is_empty() {
+            page.heading(2, "Methods");
+            for method in &item.methods {
+                page.heading(3, method.get_simple_name());
+                page.code_block(&method.signature);
+                if let Some(doc) = &method.doc {
+                    page.doc(doc);
+                }
+                if !method.variables.is_empty() {
+                    self.variable_table(&mut page, &method.variables);
+                }
+            }
+        }
+        page.line(&[Inline::Link(title.to_string(), format!("index.{}", self.format.get_extension()))]);
+        page.finish(&heading)
+    }
+
+    fn variable_table(&self, page: &mut Page, variables: &[DocVariable]) {
+        let rows = variables
+            .iter()
+            .map(|it| {
+                vec![
+                    vec![Inline::Code(it.name.clone())],
+                    vec![Inline::Text(it.section.clone())],
+                    vec![self.to_type(&it.data_type)],
+                    vec![Inline::Text(it.doc.clone().unwrap_or_default())],
+                ]
+            })
+            .collect::<Vec<_>>();
+        page.table(&["Name", "Section", "Type", "Description"], &rows);
+    }
+}
+
+/// Returns the first paragraph of a doc comment as a single line
+fn get_summary(doc: &Option<String>) -> String {
+    let doc = doc.as_deref().unwrap_or_default();
+    doc.split("\n\n").next().unwrap_or_default().lines().map(str::trim).join(" ")
+}
+
+/// A page under construction in either format
+struct Page {
+    format: DocFormat,
+    content: String,
+}
+
+impl Page {
+    fn new(format: DocFormat) -> Self {
+        Page { format, content: String::new() }
+    }
+
+    fn heading(&mut self, level: usize, text: &str) {
+        match self.format {
+            DocFormat::Markdown => {
+                let _ = writeln!(self.content, "{} {text}\n", "#".repeat(level));
+            }
+            DocFormat::Html => {
+                let _ = writeln!(self.content, "<h{level}>{}</h{level}>", escape_html(text));
+            }
+        }
+    }
+
+    fn code_block(&mut self, code: &str) {
+        match self.format {
+            DocFormat::Markdown => {
+                let _ = writeln!(self.content, "```iecst\n{code}\n```\n");
+            }
+            DocFormat::Html => {
+                let _ = writeln!(self.content, "<pre><code>{}</code></pre>", escape_html(code));
+            }
+        }
+    }
+
+    /// Writes a doc comment, which is Markdown and copied as it is to Markdown pages. HTML pages
+    /// get its paragraphs with the code spans highlighted.
+    fn doc(&mut self, doc: &str) {
+        match self.format {
+            DocFormat::Markdown => {
+                let _ = writeln!(self.content, "{doc}\n");
+            }
+            DocFormat::Html => {
+                for paragraph in doc.split("\n\n").filter(|it| !it.trim().is_empty()) {
+                    let _ = writeln!(self.content, "<p>{}</p>", to_html(paragraph).replace('\n', "<br>\n"));
+                }
+            }
+        }
+    }
+
+    fn line(&mut self, line: &[Inline]) {
+        let text = self.render_inlines(line);
+        match self.format {
+            DocFormat::Markdown => {
+                let _ = writeln!(self.content, "{text}\n");
+            }
+            DocFormat::Html => {
+                let _ = writeln!(self.content, "<p>{text}</p>");
+            }
+        }
+    }
+
+    fn table(&mut self, header: &[&str], rows: &[Vec<Vec<Inline>>]) {
+        match self.format {
+            DocFormat::Markdown => {
+                let _ = writeln!(self.content, "| {} |", header.join(" | "));
+                let _ = writeln!(self.content, "|{}", "---|".repeat(header.len()));
+                for row in rows {
+                    let cells =
+                        row.iter().map(|it| self.render_inlines(it).replace('\n', "<br>")).join(" | ");
+                    let _ = writeln!(self.content, "| {cells} |");
+                }
+                self.content.push('\n');
+            }
+            DocFormat::Html => {
+                let header = header.iter().map(|it| format!("<th>{it}</th>")).join("");
+                let _ = writeln!(self.content, "<table>\n<tr>{header}</tr>");
+                for row in rows {
+                    let cells = row
+                        .iter()
+                        .map(|it| format!("<td>{}</td>", self.render_inlines(it).replace('\n', "<br>")))
+                        .join("");
+                    let _ = writeln!(self.content, "<tr>{cells}</tr>");
+                }
+                let _ = writeln!(self.content, "</table>");
+            }
+        }
+    }
+
+    fn render_inlines(&self, inlines: &[Inline]) -> String {
+        inlines
+            .iter()
+            .map(|it| match (self.format, it) {
+                (DocFormat::Markdown, Inline::Text(text)) => text.replace('|', "\\|"),
+                (DocFormat::Markdown, Inline::Code(code)) => format!("`{code}`"),
+                (DocFormat::Markdown, Inline::Link(text, file)) => format!("[`{text}`]({file})"),
+                (DocFormat::Html, Inline::Text(text)) => to_html(text),
+                (DocFormat::Html, Inline::Code(code)) => format!("<code>{}</code>", escape_html(code)),
+                (DocFormat::Html, Inline::Link(text, file)) => {
+                    format!("<a href=\"{file}\"><code>{}</code></a>", escape_html(text))
+                }
+            })
+            .collect()
+    }
+
+    fn finish(self, title: &str) -> String {
+        match self.format {
+            DocFormat::Markdown => self.content,
+            DocFormat::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+                escape_html(title),
+                self.content
+            ),
+        }
+    }
+}
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 60em; margin: auto; }
+pre { background: #f4f4f4; padding: 0.5em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.5em; text-align: left; vertical-align: top; }";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Escapes the text and renders its `code` spans
+fn to_html(text: &str) -> String {
+    escape_html(text)
+        .split('`')
+        .enumerate()
+        .map(|(i, it)| if i % 2 == 1 { format!("<code>{it}</code>") } else { it.to_string() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        collect_documentation, render_documentation, resolve_documentation, DocFormat, DocKind, DocType,
+        Documentation, Relation,
+    };
+    use crate::test_utils::tests::index;
+
+    const SOURCE: &str = "
+        (** Can be started *)
+        INTERFACE IStartable
+            /// Starts the device
+            METHOD start : BOOL
+            END_METHOD
+        END_INTERFACE
+
+        INTERFACE IMotor EXTENDS IStartable
+        END_INTERFACE
+
+        /// A drive
+        FUNCTION_BLOCK drive
+        VAR_INPUT
+            /// the set point
+            speed : Speed;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        (**
+         * A motor
+         *
+         * Details of the `motor`.
+         *)
+        FUNCTION_BLOCK motor EXTENDS drive IMPLEMENTS IMotor
+        VAR_OUTPUT
+            /// the last positions
+            positions : ARRAY[0..9] OF Point;
+        END_VAR
+        VAR_TEMP
+            tmp : INT;
+        END_VAR
+            (** Starts the motor *)
+            METHOD start : BOOL
+            END_METHOD
+            /// the current state
+            PROPERTY_GET state : State
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+
+        TYPE
+            /// A point
+            Point : STRUCT
+                /// horizontal
+                x : INT;
+                y : INT;
+            END_STRUCT
+            Speed : INT(0..100);
+            (** The state of a motor *)
+            State : (Idle, Running := 5);
+        END_TYPE
+
+        VAR_GLOBAL
+            /// the main motor
+            mainMotor : motor;
+        END_VAR
+    ";
+
+    fn document() -> Documentation {
+        let (unit, index) = index(SOURCE);
+        let mut documentation = collect_documentation([&unit]);
+        resolve_documentation(&mut documentation, &index);
+        documentation
+    }
+
+    #[test]
+    fn items_are_collected_with_their_doc_comments() {
+        let documentation = document();
+        let items = documentation.items.iter().map(|it| (it.kind, it.name.as_str())).collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            [
+                (DocKind::FunctionBlock, "drive"),
+                (DocKind::FunctionBlock, "motor"),
+                (DocKind::Interface, "IMotor"),
+                (DocKind::Interface, "IStartable"),
+                (DocKind::Type, "Point"),
+                (DocKind::Type, "Speed"),
+                (DocKind::Type, "State"),
+            ]
+        );
+
+        let motor = &documentation.items[1];
+        assert_eq!(motor.doc.as_deref(), Some("A motor\n\nDetails of the `motor`."));
+        assert_eq!(motor.signature, "FUNCTION_BLOCK motor EXTENDS drive IMPLEMENTS IMotor");
+        // temporaries are not part of the documentation
+        assert_eq!(motor.variables.len(), 1);
+        assert_eq!(motor.variables[0].doc.as_deref(), Some("the last positions"));
+        assert_eq!(motor.methods[0].signature, "METHOD start : BOOL");
+        assert_eq!(motor.methods[0].doc.as_deref(), Some("Starts the motor"));
+        assert_eq!(motor.properties[0].section, "PROPERTY_GET");
+        assert_eq!(motor.properties[0].doc.as_deref(), Some("the current state"));
+
+        assert_eq!(documentation.items[3].methods[0].doc.as_deref(), Some("Starts the device"));
+        assert_eq!(documentation.globals[0].doc.as_deref(), Some("the main motor"));
+    }
+
+    #[test]
+    fn types_are_resolved_and_linked_to_documented_items() {
+        let documentation = document();
+        let motor = &documentation.items[1];
+        assert_eq!(
+            motor.variables[0].data_type,
+            DocType { text: "ARRAY[0..9] OF Point".to_string(), link: Some("Point".to_string()) }
+        );
+        assert_eq!(motor.properties[0].data_type.link.as_deref(), Some("State"));
+        assert_eq!(documentation.items[0].variables[0].data_type.link.as_deref(), Some("Speed"));
+        assert_eq!(documentation.globals[0].data_type.link.as_deref(), Some("motor"));
+
+        let speed = &documentation.items[5];
+        assert_eq!(speed.signature, "TYPE Speed : INT(0..100)");
+        let state = &documentation.items[6];
+        assert_eq!(state.elements, [("Idle".to_string(), Some(0)), ("Running".to_string(), Some(5))]);
+        assert_eq!(documentation.items[4].variables[0].doc.as_deref(), Some("horizontal"));
+    }
+
+    #[test]
+    fn inheritance_and_interface_relations_are_resolved() {
+        let documentation = document();
+        let relations =
+            |name: &str| documentation.items.iter().find(|it| it.name == name).unwrap().relations.clone();
+        let names = |names: &[&str]| names.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(relations("drive"), [(Relation::ExtendedBy, names(&["motor"]))]);
+        assert_eq!(
+            relations("motor"),
+            [
+                (Relation::Extends, names(&["drive"])),
+                (Relation::Implements, names(&["IMotor", "IStartable"]))
+            ]
+        );
+        assert_eq!(
+            relations("IStartable"),
+            [(Relation::ExtendedBy, names(&["IMotor"])), (Relation::ImplementedBy, names(&["motor"]))]
+        );
+        assert_eq!(
+            relations("IMotor"),
+            [(Relation::Extends, names(&["IStartable"])), (Relation::ImplementedBy, names(&["motor"]))]
+        );
+    }
+
+    #[test]
+    fn documentation_is_rendered_as_markdown() {
+        let pages = render_documentation(&document(), "Motors", DocFormat::Markdown);
+        let files = pages.iter().map(|(file, _)| file.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            files,
+            [
+                "index.md",
+                "function_block.drive.md",
+                "function_block.motor.md",
+                "interface.IMotor.md",
+                "interface.IStartable.md",
+                "type.Point.md",
+                "type.Speed.md",
+                "type.State.md",
+            ]
+        );
+
+        let index = &pages[0].1;
+        assert!(index.starts_with("# Motors\n\n## Function Blocks\n\n| Name | Description |\n|---|---|\n"));
+        assert!(index.contains("| [`motor`](function_block.motor.md) | A motor |"));
+        assert!(index
+            .contains("| `mainMotor` | VAR_GLOBAL | [`motor`](function_block.motor.md) | the main motor |"));
+
+        let motor = &pages[2].1;
+        assert!(motor.starts_with(
+            "# FUNCTION_BLOCK motor\n\n```iecst\nFUNCTION_BLOCK motor EXTENDS drive IMPLEMENTS IMotor\n```\n\nA motor\n\nDetails of the `motor`.\n\nExtends: [`drive`](function_block.drive.md)\n"
+        ));
+        assert!(motor.contains(
+            "| `positions` | VAR_OUTPUT | [`ARRAY[0..9] OF Point`](type.Point.md) | the last positions |"
+        ));
+        assert!(motor.contains("### start\n\n```iecst\nMETHOD start : BOOL\n```\n\nStarts the motor\n"));
+    }
+
+    #[test]
+    fn documentation_is_rendered_as_html() {
+        let pages = render_documentation(&document(), "Motors", DocFormat::Html);
+        assert_eq!(pages[0].0, "index.html");
+        let motor = &pages.iter().find(|(file, _)| file == "function_block.motor.html").unwrap().1;
+        assert!(motor.starts_with("<!DOCTYPE html>"));
+        assert!(motor.contains("<p>Details of the <code>motor</code>.</p>"));
+        assert!(motor.contains("<td><a href=\"type.Point.html\"><code>ARRAY[0..9] OF Point</code></a></td>"));
+    }
+}
@@ -40,6 +40,10 @@ pub struct ExpressionPath<'idx> {
 }
 
 impl<'idx> ExpressionPath<'idx> {
+    pub fn get_elements(&self) -> &[ExpressionPathElement<'idx>] {
+        &self.names
+    }
+
     pub fn join(&mut self, name: &mut ExpressionPath<'idx>) {
         self.names.append(&mut name.names)
     }
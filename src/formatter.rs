@@ -0,0 +1,96 @@
+//! A source formatter for Structured Text.
+//!
+//! The formatter prints the parsed [`CompilationUnit`] back to Structured Text through the
+//! [`AstSerializer`]: every statement and declaration starts on its own line, nested blocks are
+//! indented and operators are spaced consistently. Comments and pragmas are attached to the
+//! declarations and statements around them by the parser and are emitted next to them.
+//!
+//! Literals are kept as they were written. Every result is parsed again and compared against the
+//! input; if the formatter would change the meaning of the file, it refuses to format it.
+use plc_ast::{
+    ast::{CompilationUnit, LinkageType},
+    provider::IdProvider,
+    ser::AstSerializer,
+};
+use plc_diagnostics::{diagnostician::Diagnostician, diagnostics::Diagnostic};
+use plc_source::{SourceCode, SourceContainer};
+use serde_json::Value;
+
+use crate::parser::parse_file;
+
+pub use plc_ast::ser::{CompoundKeywords, FormatOptions, KeywordCase};
+
+#[cfg(test)]
+mod tests;
+
+/// Formats the given source. Parse errors are reported to the diagnostician, a file that does not
+/// parse cannot be formatted.
+pub fn format(
+    source: &SourceCode,
+    options: &FormatOptions,
+    diagnostician: &mut Diagnostician,
+) -> Result<String, Diagnostic> {
+    let unit =
+        parse_file(source, LinkageType::Internal, IdProvider::default(), diagnostician).map_err(|_| {
+            Diagnostic::new(format!("Cannot format {}, it contains syntax errors", source.get_location_str()))
+        })?;
+
+    let formatted = AstSerializer::format_source(&unit, &source.source, options);
+    verify(source, &unit, &formatted)?;
+    Ok(formatted)
+}
+
+/// Makes sure the formatted text parses into the same declarations, statements and comments as
+/// the source
+fn verify(source: &SourceCode, unit: &CompilationUnit, formatted: &str) -> Result<(), Diagnostic> {
+    let formatted_source = SourceCode { source: formatted.to_string(), path: source.path.clone() };
+    let formatted_unit = parse_file(
+        &formatted_source,
+        LinkageType::Internal,
+        IdProvider::default(),
+        &mut Diagnostician::null_diagnostician(),
+    );
+    let is_equal = formatted_unit.is_ok_and(|formatted_unit| {
+        normalize(unit) == normalize(&formatted_unit) && trivia_texts(unit) == trivia_texts(&formatted_unit)
+    });
+    if is_equal {
+        return Ok(());
+    }
+
+    Err(Diagnostic::new(format!(
+        "Cannot format {}, the formatted code would differ from the original",
+        source.get_location_str()
+    ))
+    .with_error_code("E152"))
+}
+
+/// Serializes the unit without the ids, locations and trivia which change when a file is formatted
+fn normalize(unit: &CompilationUnit) -> Value {
+    fn strip(value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                fields.retain(|key, _| key != "id" && !key.contains("location"));
+                fields.values_mut().for_each(strip);
+            }
+            Value::Array(elements) => {
+                elements.retain(|it| it.pointer("/stmt/EmptyStatement").is_none());
+                elements.iter_mut().for_each(strip);
+            }
+            _ => {}
+        }
+    }
+
+    let mut value = serde_json::to_value(unit).expect("a unit can be serialized");
+    if let Value::Object(fields) = &mut value {
+        fields.remove("trivia");
+    }
+    strip(&mut value);
+    value
+}
+
+fn trivia_texts(unit: &CompilationUnit) -> Vec<&str> {
+    unit.trivia
+        .iter()
+        .map(|it| if it.text.starts_with("//") { it.text.trim_end() } else { it.text.as_str() })
+        .collect()
+}
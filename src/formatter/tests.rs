@@ -0,0 +1,436 @@
+use plc_diagnostics::diagnostician::Diagnostician;
+use plc_source::SourceCode;
+use pretty_assertions::assert_eq;
+
+use crate::formatter::{format, CompoundKeywords, FormatOptions, KeywordCase};
+
+fn format_with(src: &str, options: &FormatOptions) -> String {
+    let formatted =
+        format(&SourceCode::new(src, "main.st"), options, &mut Diagnostician::null_diagnostician())
+            .expect("source can be formatted");
+    // formatting the result again does not change it
+    let again = format(
+        &SourceCode::new(formatted.as_str(), "main.st"),
+        options,
+        &mut Diagnostician::null_diagnostician(),
+    )
+    .expect("formatted source can be formatted");
+    assert_eq!(formatted, again);
+    formatted
+}
+
+fn format_default(src: &str) -> String {
+    format_with(src, &FormatOptions::default())
+}
+
+#[test]
+fn declarations_and_statements_are_put_on_their_own_lines() {
+    let src = "
+program main var x:int; y : DINT:=5; END_VAR
+x:=1; y := x+2*-y;
+end_program";
+
+    assert_eq!(
+        format_default(src),
+        "\
+PROGRAM main
+VAR
+    x : int;
+    y : DINT := 5;
+END_VAR
+    x := 1;
+    y := x + 2 * -y;
+END_PROGRAM
+"
+    );
+}
+
+#[test]
+fn nested_blocks_are_indented() {
+    let src = "
+FUNCTION foo : INT
+VAR_INPUT a : INT; END_VAR
+IF a > 0 THEN foo := a; ELSIF a < 0 THEN foo := -a; ELSE
+FOR a := 0 TO 10 BY 2 DO WHILE a < 5 DO a := a + 1; END_WHILE END_FOR
+REPEAT a := a - 1; UNTIL a = 0 END_REPEAT
+END_IF
+END_FUNCTION";
+
+    assert_eq!(
+        format_default(src),
+        "\
+FUNCTION foo : INT
+VAR_INPUT
+    a : INT;
+END_VAR
+    IF a > 0 THEN
+        foo := a;
+    ELSIF a < 0 THEN
+        foo := -a;
+    ELSE
+        FOR a := 0 TO 10 BY 2 DO
+            WHILE a < 5 DO
+                a := a + 1;
+            END_WHILE
+        END_FOR
+        REPEAT
+            a := a - 1;
+        UNTIL a = 0
+        END_REPEAT
+    END_IF
+END_FUNCTION
+"
+    );
+}
+
+#[test]
+fn case_labels_and_their_statements_are_indented() {
+    let src = "
+PROGRAM main
+VAR x : INT; END_VAR
+CASE x OF
+1, 2: x := 3;
+3..5: x := 4; x := 5;
+ELSE x := 0;
+END_CASE
+END_PROGRAM";
+
+    assert_eq!(
+        format_default(src),
+        "\
+PROGRAM main
+VAR
+    x : INT;
+END_VAR
+    CASE x OF
+        1, 2:
+            x := 3;
+        3..5:
+            x := 4;
+            x := 5;
+        ELSE
+            x := 0;
+    END_CASE
+END_PROGRAM
+"
+    );
+}
+
+#[test]
+fn types_methods_and_actions_are_formatted() {
+    let src = "
+TYPE Point : STRUCT x : INT; y : INT; END_STRUCT END_TYPE
+TYPE Color : (red, green := 5, blue); END_TYPE
+FUNCTION_BLOCK fb
+VAR p : REF_TO Point; values : ARRAY[0..2] OF INT := [1,2,3]; END_VAR
+METHOD reset
+p^.x := 0; values[1] := Color#green;
+END_METHOD
+END_FUNCTION_BLOCK
+ACTIONS fb ACTION clear values[0] := 0; END_ACTION END_ACTIONS";
+
+    assert_eq!(
+        format_default(src),
+        "\
+TYPE Point : STRUCT
+    x : INT;
+    y : INT;
+END_STRUCT
+END_TYPE
+TYPE Color : (red, green := 5, blue);
+END_TYPE
+FUNCTION_BLOCK fb
+VAR
+    p : REF_TO Point;
+    values : ARRAY[0..2] OF INT := [1, 2, 3];
+END_VAR
+METHOD reset
+    p^.x := 0;
+    values[1] := Color#green;
+END_METHOD
+END_FUNCTION_BLOCK
+ACTIONS fb
+ACTION clear
+    values[0] := 0;
+END_ACTION
+END_ACTIONS
+"
+    );
+}
+
+#[test]
+fn comments_and_pragmas_stay_with_their_code() {
+    let src = "
+(* the main program *)
+{external}
+FUNCTION ext : DINT END_FUNCTION
+
+PROGRAM main
+VAR
+x : INT; // the counter
+
+
+    {attribute 'hidden'}
+y : INT;
+END_VAR
+IF x > 0 THEN
+// reset
+x := 0; (* done *)
+END_IF
+END_PROGRAM";
+
+    assert_eq!(
+        format_default(src),
+        "\
+(* the main program *)
+{external}
+FUNCTION ext : DINT
+END_FUNCTION
+
+PROGRAM main
+VAR
+    x : INT; // the counter
+
+    {attribute 'hidden'}
+    y : INT;
+END_VAR
+    IF x > 0 THEN
+        // reset
+        x := 0; (* done *)
+    END_IF
+END_PROGRAM
+"
+    );
+}
+
+#[test]
+fn statements_are_put_on_a_single_line() {
+    let src = "
+PROGRAM main
+VAR x : INT; END_VAR
+x := MAX(1,
+2, // two
+        3);
+x := foo(a := 1, b => x);
+END_PROGRAM";
+
+    assert_eq!(
+        format_default(src),
+        "\
+PROGRAM main
+VAR
+    x : INT;
+END_VAR
+    x := MAX(1, 2, 3); // two
+    x := foo(a := 1, b => x);
+END_PROGRAM
+"
+    );
+}
+
+#[test]
+fn variables_are_declared_one_per_line_and_blank_lines_are_kept() {
+    let src = "
+PROGRAM main
+VAR
+a, b : INT := 1;
+
+
+// no more variables
+END_VAR
+a := 1;
+
+
+
+b := 2;
+END_PROGRAM";
+
+    assert_eq!(
+        format_default(src),
+        "\
+PROGRAM main
+VAR
+    a : INT := 1;
+    b : INT := 1;
+
+    // no more variables
+END_VAR
+    a := 1;
+
+    b := 2;
+END_PROGRAM
+"
+    );
+}
+
+#[test]
+fn interfaces_properties_and_methods_are_formatted() {
+    let src = "
+INTERFACE counter
+METHOD inc : DINT VAR_INPUT step : DINT; END_VAR END_METHOD
+PROPERTY_GET value : DINT END_PROPERTY
+END_INTERFACE
+FUNCTION_BLOCK ABSTRACT base IMPLEMENTS counter
+VAR_OUTPUT PUBLIC current : DINT; END_VAR
+METHOD PUBLIC inc : DINT VAR_INPUT step : DINT; END_VAR current := current + step; inc := current; END_METHOD
+PROPERTY_GET value : DINT value := current; END_PROPERTY
+END_FUNCTION_BLOCK
+FUNCTION_BLOCK child EXTENDS base
+METHOD OVERRIDE inc : DINT VAR_INPUT step : DINT; END_VAR inc := SUPER^.inc(step * 2); END_METHOD
+END_FUNCTION_BLOCK";
+
+    assert_eq!(
+        format_default(src),
+        "\
+INTERFACE counter
+METHOD inc : DINT
+VAR_INPUT
+    step : DINT;
+END_VAR
+END_METHOD
+PROPERTY_GET value : DINT
+END_PROPERTY
+END_INTERFACE
+FUNCTION_BLOCK ABSTRACT base IMPLEMENTS counter
+VAR_OUTPUT PUBLIC
+    current : DINT;
+END_VAR
+METHOD PUBLIC inc : DINT
+VAR_INPUT
+    step : DINT;
+END_VAR
+    current := current + step;
+    inc := current;
+END_METHOD
+PROPERTY_GET value : DINT
+    value := current;
+END_PROPERTY
+END_FUNCTION_BLOCK
+FUNCTION_BLOCK child EXTENDS base
+METHOD OVERRIDE inc : DINT
+VAR_INPUT
+    step : DINT;
+END_VAR
+    inc := SUPER^.inc(step * 2);
+END_METHOD
+END_FUNCTION_BLOCK
+"
+    );
+}
+
+#[test]
+fn types_and_expressions_are_printed_as_declared() {
+    let src = "
+TYPE Level : INT (low := 1, high := 2); END_TYPE
+TYPE Small : INT(0..10) := 5; END_TYPE
+FUNCTION foo<T: ANY_NUM> : T
+VAR_INPUT {ref} in : T; END_VAR
+VAR p : POINTER TO INT; r : REFERENCE TO INT; a AT in : T; s : WSTRING[10];
+m : ARRAY[0..1, 2..3] OF INT := [4(0)]; END_VAR
+VAR CONSTANT c : INT; END_VAR
+r REF= p^; m[0, 2] := 2 ** 3 MOD 2; IF NOT TRUE THEN RETURN; END_IF
+END_FUNCTION";
+
+    assert_eq!(
+        format_default(src),
+        "\
+TYPE Level : INT (low := 1, high := 2);
+END_TYPE
+TYPE Small : INT(0..10) := 5;
+END_TYPE
+FUNCTION foo<T: ANY_NUM> : T
+VAR_INPUT {ref}
+    in : T;
+END_VAR
+VAR
+    p : POINTER TO INT;
+    r : REFERENCE TO INT;
+    a AT in : T;
+    s : WSTRING[10];
+    m : ARRAY[0..1, 2..3] OF INT := [4(0)];
+END_VAR
+VAR CONSTANT
+    c : INT;
+END_VAR
+    r REF= p^;
+    m[0, 2] := 2 ** 3 MOD 2;
+    IF NOT TRUE THEN
+        RETURN;
+    END_IF
+END_FUNCTION
+"
+    );
+}
+
+#[test]
+fn literals_and_addresses_are_kept_verbatim() {
+    let src = "
+PROGRAM main
+VAR
+x AT %IX1.2 : BOOL; t : TIME := t#1s500ms; s : STRING[20] := 'a  b';
+END_VAR
+x := x.%X0 OR INT#16#FF > 1.5E3;
+END_PROGRAM";
+
+    assert_eq!(
+        format_default(src),
+        "\
+PROGRAM main
+VAR
+    x AT %IX1.2 : BOOL;
+    t : TIME := t#1s500ms;
+    s : STRING[20] := 'a  b';
+END_VAR
+    x := x.%X0 OR INT#16#FF > 1.5E3;
+END_PROGRAM
+"
+    );
+}
+
+#[test]
+fn keyword_case_and_compound_keywords_are_configurable() {
+    let src = "
+PROGRAM main
+VAR_INPUT x : INT; END_VAR
+IF x > 0 THEN x := 0; END_IF
+END_PROGRAM";
+
+    let options = FormatOptions {
+        indent_width: 2,
+        keyword_case: KeywordCase::Lower,
+        compound_keywords: CompoundKeywords::Joined,
+    };
+    assert_eq!(
+        format_with(src, &options),
+        "\
+program main
+varinput
+  x : INT;
+endvar
+  if x > 0 then
+    x := 0;
+  endif
+endprogram
+"
+    );
+
+    let src = "program main\nvarinput x : INT; endvar\nendprogram";
+    assert_eq!(
+        format_default(src),
+        "\
+PROGRAM main
+VAR_INPUT
+    x : INT;
+END_VAR
+END_PROGRAM
+"
+    );
+}
+
+#[test]
+fn sources_with_syntax_errors_are_not_formatted() {
+    let src = "PROGRAM main x := ; END_PROGRAM";
+    let result =
+        format(&SourceCode::new(src, "main.st"), &FormatOptions::default(), &mut Diagnostician::buffered());
+    assert!(result.is_err());
+}
@@ -39,7 +39,11 @@ use plc_ast::ast::{mangle_hw_name, AstStatement, DirectAccessType, HardwareAcces
 use plc_diagnostics::diagnostics::Diagnostic;
 use serde::Serialize;
 
-use crate::{expression_path::ExpressionPath, index::Index, ConfigFormat};
+use crate::{
+    expression_path::ExpressionPath,
+    index::{HardwareBinding, Index},
+    ConfigFormat,
+};
 
 /// One row in the emitted map. `name` is the qualified user-facing path;
 /// `mangled_name` is the synthetic global as it appears in DWARF.
@@ -128,15 +132,7 @@ pub fn collect_hw_map(index: &Index) -> Result<HwMap, Diagnostic> {
         if matches!(binding.access, DirectAccessType::Template) {
             continue;
         }
-        let address_ints = binding
-            .entries
-            .iter()
-            .map(|id| index.get_const_expressions().get_constant_int_statement_value(id))
-            .collect::<Result<Vec<i128>, _>>()
-            .map_err(|msg| Diagnostic::new(msg).with_error_code("E002"))?;
-
-        let mangled = mangle_hw_name(binding.direction, &address_ints);
-        let address_literal = render_address(binding.direction, binding.access, &address_ints);
+        let (mangled, address_literal) = resolve_hardware_binding(index, binding)?;
 
         for name in path.expand(index) {
             if seen.insert((name.clone(), mangled.clone())) {
@@ -197,6 +193,23 @@ pub fn collect_hw_map(index: &Index) -> Result<HwMap, Diagnostic> {
     Ok(HwMap { entries })
 }
 
+/// Returns the synthetic global a hardware binding is aliased to and its source-form address
+pub fn resolve_hardware_binding(
+    index: &Index,
+    binding: &HardwareBinding,
+) -> Result<(String, String), Diagnostic> {
+    let address_ints = binding
+        .entries
+        .iter()
+        .map(|id| index.get_const_expressions().get_constant_int_statement_value(id))
+        .collect::<Result<Vec<i128>, _>>()
+        .map_err(|msg| Diagnostic::new(msg).with_error_code("E002"))?;
+
+    let mangled = mangle_hw_name(binding.direction, &address_ints);
+    let address_literal = render_address(binding.direction, binding.access, &address_ints);
+    Ok((mangled, address_literal))
+}
+
 pub fn serialize_hw_map(map: &HwMap, format: ConfigFormat) -> Result<String, Diagnostic> {
     // A serializer failure here is a contract violation between this module and serde —
     // every field is a plain string or a derived enum. Surface it as an internal error.
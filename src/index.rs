@@ -1,6 +1,6 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 
-use std::{collections::VecDeque, hash::BuildHasherDefault};
+use std::{collections::VecDeque, hash::BuildHasherDefault, sync::Arc};
 
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
@@ -43,7 +43,6 @@ pub type FxIndexSet<K> = indexmap::IndexSet<K, BuildHasherDefault<FxHasher>>;
 /// A label represents a possible jump point in the source.
 /// It can be referenced by jump elements in the same unit
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Label {
     pub id: AstId,
     pub name: String,
@@ -60,8 +59,45 @@ impl From<&AstNode> for Label {
     }
 }
 
+/// The linker section holding the values of `RETAIN` and `PERSISTENT` variables
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum RetainSection {
+    /// `RETAIN` values, kept across a power cycle
+    Retain,
+    /// `PERSISTENT` values, kept across a download
+    Persistent,
+    /// `RETAIN PERSISTENT` values, kept across both
+    RetainPersistent,
+}
+
+impl RetainSection {
+    pub fn new(retain: bool, persistent: bool) -> Option<Self> {
+        match (retain, persistent) {
+            (true, false) => Some(RetainSection::Retain),
+            (false, true) => Some(RetainSection::Persistent),
+            (true, true) => Some(RetainSection::RetainPersistent),
+            (false, false) => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            RetainSection::Retain => ".retain",
+            RetainSection::Persistent => ".persistent",
+            RetainSection::RetainPersistent => ".retain_persistent",
+        }
+    }
+
+    pub fn is_retain(&self) -> bool {
+        matches!(self, RetainSection::Retain | RetainSection::RetainPersistent)
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        matches!(self, RetainSection::Persistent | RetainSection::RetainPersistent)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct VariableIndexEntry {
     /// the name of this variable (e.g. 'x' for 'PLC_PRG.x')
     name: String,
@@ -77,6 +113,8 @@ pub struct VariableIndexEntry {
     is_var_external: bool,
     /// Returns true if the variable is in a `RETAIN` block
     is_retain: bool,
+    /// Returns true if the variable is in a `PERSISTENT` block
+    is_persistent: bool,
     /// the variable's datatype
     pub data_type_name: String,
     /// the index of the member-variable in it's container (e.g. struct). defautls to 0 (Single variables)
@@ -117,6 +155,7 @@ impl VariableIndexEntry {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: data_type_name.to_string(),
             location_in_parent,
             linkage: LinkageType::Internal,
@@ -140,6 +179,7 @@ impl VariableIndexEntry {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: data_type_name.to_string(),
             location_in_parent: 0,
             linkage: LinkageType::Internal,
@@ -184,6 +224,11 @@ impl VariableIndexEntry {
         self
     }
 
+    pub fn set_persistent(mut self, is_persistent: bool) -> Self {
+        self.is_persistent = is_persistent;
+        self
+    }
+
     /// Creates a new VariableIndexEntry from the current entry with a new container and type
     /// This is used to create new entries from previously generic entries
     pub fn into_typed(&self, container: &str, new_type: &str) -> Self {
@@ -247,6 +292,16 @@ impl VariableIndexEntry {
         self.is_var_external
     }
 
+    /// Returns true if the variable is declared in a `RETAIN` block
+    pub fn is_retain(&self) -> bool {
+        self.is_retain
+    }
+
+    /// Returns true if the variable is declared in a `PERSISTENT` block
+    pub fn is_persistent(&self) -> bool {
+        self.is_persistent
+    }
+
     pub fn get_declaration_type(&self) -> ArgumentType {
         self.argument_type
     }
@@ -305,18 +360,49 @@ impl VariableIndexEntry {
     }
 
     pub fn should_retain(&self, index: &Index) -> bool {
-        self.should_retain_recursive(index, &mut FxHashSet::default())
+        self.matches_recursive(index, &mut FxHashSet::default(), VariableIndexEntry::is_retain)
+    }
+
+    /// Returns true if the variable is persistent or its datatype contains a persistent variable
+    pub fn should_persist(&self, index: &Index) -> bool {
+        self.matches_recursive(index, &mut FxHashSet::default(), VariableIndexEntry::is_persistent)
+    }
+
+    /// Returns the section the variable is placed into, if it or its datatype contains a `RETAIN` or
+    /// `PERSISTENT` variable. A variable mixing sections is placed into the one keeping all of its values.
+    pub fn get_retain_section(&self, index: &Index) -> Option<RetainSection> {
+        RetainSection::new(self.should_retain(index), self.should_persist(index))
     }
 
-    pub(crate) fn should_retain_recursive(&self, index: &Index, visited: &mut FxHashSet<String>) -> bool {
+    /// Returns the sections of the variable itself and of all `RETAIN` and `PERSISTENT` variables in its
+    /// datatype. A variable with more than one section cannot be placed without changing how long some of
+    /// its values are kept.
+    pub fn get_retain_sections(&self, index: &Index) -> Vec<RetainSection> {
+        let sections: [(RetainSection, fn(&VariableIndexEntry) -> bool); 3] = [
+            (RetainSection::Retain, |it| it.is_retain && !it.is_persistent),
+            (RetainSection::Persistent, |it| !it.is_retain && it.is_persistent),
+            (RetainSection::RetainPersistent, |it| it.is_retain && it.is_persistent),
+        ];
+        sections
+            .into_iter()
+            .filter(|(_, predicate)| self.matches_recursive(index, &mut FxHashSet::default(), *predicate))
+            .map(|(section, _)| section)
+            .collect()
+    }
+
+    pub(crate) fn matches_recursive(
+        &self,
+        index: &Index,
+        visited: &mut FxHashSet<String>,
+        predicate: fn(&VariableIndexEntry) -> bool,
+    ) -> bool {
         let datatype = index.find_effective_type_by_name(self.get_type_name());
-        // is self retain? otherwise does the datatype contain a retain variable (nested)?
-        self.is_retain || datatype.is_some_and(|dt| dt.should_retain(index, visited))
+        // does self match? otherwise does the datatype contain a matching variable (nested)?
+        predicate(self) || datatype.is_some_and(|dt| dt.contains_variable(index, visited, predicate))
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct HardwareBinding {
     /// Specifies if the binding is an In/Out or Memory binding
     pub direction: HardwareAccessType,
@@ -364,6 +450,7 @@ pub struct MemberInfo<'b> {
     is_constant: bool,
     is_var_external: bool,
     is_retain: bool,
+    is_persistent: bool,
     varargs: Option<VarArgs>,
 }
 
@@ -442,7 +529,6 @@ pub enum ImplementationType {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct ImplementationIndexEntry {
     pub(crate) call_name: String,
     pub(crate) type_name: String,
@@ -542,7 +628,6 @@ impl ImplementationType {
 }
 
 #[derive(Eq, PartialEq, Hash, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct InterfaceIndexEntry {
     /// The interface identifier, consisting of its name and name-location
     pub ident: Identifier,
@@ -709,7 +794,6 @@ impl From<&Interface> for InterfaceIndexEntry {
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum PouIndexEntry {
     Program {
         name: String,
@@ -1187,7 +1271,7 @@ impl PouIndexEntry {
         }
     }
 
-    fn is_auto_generated_function(&self) -> bool {
+    pub(crate) fn is_auto_generated_function(&self) -> bool {
         matches!(self, PouIndexEntry::Function { is_generated: true, .. })
     }
 
@@ -1206,13 +1290,16 @@ impl PouIndexEntry {
 /// it is extracted into its seaprate struct so it can be
 /// internally borrowed individually from the other maps
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct TypeIndex {
     /// all types (structs, enums, type, POUs, etc.)
     types: SymbolMap<String, DataType>,
     pou_types: SymbolMap<String, DataType>,
 
     void_type: DataType,
+
+    /// the index this one was created on top of, see [`Index::with_imports`]
+    #[serde(skip)]
+    imports: Option<Arc<Index>>,
 }
 
 impl Default for TypeIndex {
@@ -1228,17 +1315,24 @@ impl Default for TypeIndex {
                 location: SourceLocation::internal(),
                 linkage: LinkageType::Internal,
             },
+            imports: None,
         }
     }
 }
 
 impl TypeIndex {
     pub fn find_type(&self, type_name: &str) -> Option<&DataType> {
-        self.types.get(&type_name.to_lowercase()).or_else(|| self.find_pou_type(type_name))
+        let name = type_name.to_lowercase();
+        self.types
+            .get(&name)
+            .or_else(|| self.pou_types.get(&name))
+            .or_else(|| self.imports.as_deref()?.type_index.find_type(type_name))
     }
 
     pub fn find_pou_type(&self, type_name: &str) -> Option<&DataType> {
-        self.pou_types.get(&type_name.to_lowercase())
+        self.pou_types
+            .get(&type_name.to_lowercase())
+            .or_else(|| self.imports.as_deref()?.type_index.find_pou_type(type_name))
     }
 
     pub fn find_effective_type_by_name(&self, type_name: &str) -> Option<&DataType> {
@@ -1290,7 +1384,6 @@ impl TypeIndex {
 ///
 /// The index contains information about all referencable elements.
 #[derive(Debug, Default, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct Index {
     /// All global variables
     global_variables: SymbolMap<String, VariableIndexEntry>,
@@ -1327,9 +1420,30 @@ pub struct Index {
     labels: FxIndexMap<String, SymbolMap<String, Label>>,
 
     config_variables: Vec<ConfigVariable>,
+
+    /// Declarations of an earlier build this index was created on top of, see [`Index::with_imports`]
+    #[serde(skip)]
+    imports: Option<Arc<Index>>,
 }
 
 impl Index {
+    /// Creates an empty index on top of the given one (e.g. the declarations of a `plc repl` session).
+    /// Lookups fall back to the imported declarations, they are shared and never copied. Iterating the
+    /// maps of this index (e.g. [`Index::get_pous`]) only yields the declarations added to it.
+    pub fn with_imports(imports: Arc<Index>) -> Index {
+        Index {
+            type_index: TypeIndex { imports: Some(imports.clone()), ..TypeIndex::default() },
+            constant_expressions: ConstExpressions::with_imports(imports.clone()),
+            imports: Some(imports),
+            ..Index::default()
+        }
+    }
+
+    /// Returns the index this one was created on top of, see [`Index::with_imports`]
+    pub fn get_imports(&self) -> Option<&Index> {
+        self.imports.as_deref()
+    }
+
     /// imports all entries from the given index into the current index
     ///
     /// imports all global_variables, types and implementations
@@ -1556,6 +1670,7 @@ impl Index {
                     it.first()
                 }
             })
+            .or_else(|| self.imports.as_deref()?.find_qualified_global_variable(context, name))
     }
 
     /// returns the `VariableIndexEntry` of the global variable with the given name
@@ -1565,7 +1680,9 @@ impl Index {
 
     /// returns the `VariableIndexEntry` of the global initializer with the given name
     pub fn find_global_initializer(&self, name: &str) -> Option<&VariableIndexEntry> {
-        self.global_initializers.get(&name.to_lowercase())
+        self.global_initializers
+            .get(&name.to_lowercase())
+            .or_else(|| self.imports.as_deref()?.find_global_initializer(name))
     }
 
     /// return the `VariableIndexEntry` with the qualified name: `container_name`.`variable_name`
@@ -1658,11 +1775,15 @@ impl Index {
 
     /// Returns an interface with the given name or None if it does not exist
     pub fn find_interface(&self, name: &str) -> Option<&InterfaceIndexEntry> {
-        self.interfaces.get(name)
+        self.interfaces.get(name).or_else(|| self.imports.as_deref()?.find_interface(name))
     }
 
     pub fn get_properties_in_pou(&self, pou_name: &str) -> Vec<Identifier> {
-        self.properties.get_all(pou_name).unwrap_or(&vec![]).to_vec()
+        match (self.properties.get_all(pou_name), &self.imports) {
+            (Some(properties), _) => properties.to_vec(),
+            (None, Some(imports)) => imports.get_properties_in_pou(pou_name),
+            (None, None) => vec![],
+        }
     }
 
     /// return the `VariableIndexEntry` associated with the given fully qualified name using `.` as
@@ -1770,10 +1891,7 @@ impl Index {
 
     /// returns all member variables of the given POU (e.g. FUNCTION, PROGRAM, etc.)
     pub fn get_pou_members(&self, container_name: &str) -> &[VariableIndexEntry] {
-        self.get_pou_types()
-            .get(&container_name.to_lowercase())
-            .map(|it| it.get_members())
-            .unwrap_or_else(|| &[])
+        self.type_index.find_pou_type(container_name).map(|it| it.get_members()).unwrap_or_else(|| &[])
     }
 
     pub fn get_variables_for_pou(&self, pou: &PouIndexEntry) -> &[VariableIndexEntry] {
@@ -1785,7 +1903,7 @@ impl Index {
     }
 
     pub fn find_pou_type(&self, pou_name: &str) -> Option<&DataType> {
-        self.get_pou_types().get(&pou_name.to_lowercase())
+        self.type_index.find_pou_type(pou_name)
     }
 
     /// Returns the parameter (INPUT, OUTPUT or IN_OUT) for the given POU by its location, if it exists.
@@ -1948,7 +2066,11 @@ impl Index {
     /// expect a built-in type
     /// This only returns types, not POUs as it is meant for builtins only
     pub fn get_type_or_panic(&self, type_name: &str) -> &DataType {
-        self.get_types().get(&type_name.to_lowercase()).unwrap_or_else(|| panic!("{type_name} not found"))
+        match (self.get_types().get(&type_name.to_lowercase()), &self.imports) {
+            (Some(data_type), _) => data_type,
+            (None, Some(imports)) => imports.get_type_or_panic(type_name),
+            (None, None) => panic!("{type_name} not found"),
+        }
     }
 
     pub fn get_initial_value(&self, id: &Option<ConstId>) -> Option<&AstNode> {
@@ -1988,7 +2110,7 @@ impl Index {
     }
 
     pub fn find_return_variable(&self, pou_name: &str) -> Option<&VariableIndexEntry> {
-        self.get_pou_types().get(&pou_name.to_lowercase()).and_then(|it| it.find_return_variable())
+        self.type_index.find_pou_type(pou_name).and_then(|it| it.find_return_variable())
     }
 
     pub fn find_return_type(&self, pou_name: &str) -> Option<&DataType> {
@@ -2076,7 +2198,7 @@ impl Index {
     }
 
     pub fn find_pou(&self, pou_name: &str) -> Option<&PouIndexEntry> {
-        self.pous.get(&pou_name.to_lowercase())
+        self.pous.get(&pou_name.to_lowercase()).or_else(|| self.imports.as_deref()?.find_pou(pou_name))
     }
 
     pub fn is_init_function(&self, pou_name: &str) -> bool {
@@ -2104,7 +2226,9 @@ impl Index {
     }
 
     pub fn find_implementation_by_name(&self, call_name: &str) -> Option<&ImplementationIndexEntry> {
-        self.implementations.get(&call_name.to_lowercase())
+        self.implementations
+            .get(&call_name.to_lowercase())
+            .or_else(|| self.imports.as_deref()?.find_implementation_by_name(call_name))
     }
 
     pub fn find_pou_implementation(&self, pou_name: &str) -> Option<&ImplementationIndexEntry> {
@@ -2149,6 +2273,7 @@ impl Index {
         .set_varargs(member_info.varargs)
         .set_var_external(member_info.is_var_external)
         .set_retain(member_info.is_retain)
+        .set_persistent(member_info.is_persistent)
     }
 
     pub fn register_enum_variant(
@@ -2435,10 +2560,8 @@ impl Index {
     ) -> Vec<&'b PouIndexEntry> {
         if let Some(pou) = self.find_pou(container) {
             let mut res = self
-                .get_pous()
-                .values()
-                .filter(|it| it.is_method())
-                .filter(|it| it.get_parent_pou_name().is_some_and(|it| it == container))
+                .get_declared_methods(container)
+                .into_iter()
                 .filter(|it| !current_methods.iter().any(|m| m.get_call_name() == it.get_call_name()))
                 .collect::<Vec<_>>();
             res.extend(current_methods);
@@ -2490,13 +2613,7 @@ impl Index {
                 collected = self.get_methods_recursive_in_fixed_order(super_class, collected, seen);
             }
 
-            let methods = self
-                .get_pous()
-                .values()
-                .filter(|pou| pou.is_method())
-                .filter(|pou| pou.get_parent_pou_name().is_some_and(|opt| opt == container));
-
-            for method in methods {
+            for method in self.get_declared_methods(container) {
                 let name = method.get_name().split_once('.').unwrap().1;
                 collected.insert(name, method);
             }
@@ -2504,6 +2621,19 @@ impl Index {
 
         collected
     }
+
+    /// Returns the methods declared in the given container, without the ones of its super "classes"
+    fn get_declared_methods(&self, container: &str) -> Vec<&PouIndexEntry> {
+        let mut methods =
+            self.imports.as_deref().map(|it| it.get_declared_methods(container)).unwrap_or_default();
+        methods.extend(
+            self.pous
+                .values()
+                .filter(|pou| pou.is_method())
+                .filter(|pou| pou.get_parent_pou_name().is_some_and(|it| it == container)),
+        );
+        methods
+    }
 }
 
 /// Returns true for the hardware-address-mangled synthetic globals emitted by
@@ -2517,7 +2647,7 @@ impl Index {
 /// to dedup at import time. The match is against lowercase prefixes because
 /// `Index::register_global_variable` lowercases keys on insert (see
 /// `src/index.rs:2166`), so the input name is already canonicalised.
-fn is_synthetic_hw_global(name: &str) -> bool {
+pub(crate) fn is_synthetic_hw_global(name: &str) -> bool {
     name.starts_with("__pi_") || name.starts_with("__m_") || name.starts_with("__g_")
 }
 
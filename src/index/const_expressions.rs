@@ -1,5 +1,7 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 
+use std::sync::Arc;
+
 use generational_arena::{Arena, Iter};
 use plc_ast::{
     ast::{AstNode, AstStatement},
@@ -9,13 +11,14 @@ use plc_ast::{
 use plc_source::source_location::SourceLocation;
 use serde::{Deserialize, Serialize};
 
+use super::Index;
+
 pub type ConstId = generational_arena::Index;
 
 /// wrapper around ConstExpression stored in the arena
 /// changing expr allows to change the referenced const-expression
 /// without aquiring a new ID in the arena
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 struct ConstWrapper {
     /// the constant expression
     expr: ConstExpression,
@@ -33,7 +36,6 @@ impl ConstWrapper {
 /// whether this expression was already (potentially) resolved or not, or if a
 /// resolving failed.
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum ConstExpression {
     Unresolved {
         statement: AstNode,
@@ -97,7 +99,6 @@ impl ConstExpression {
 /// Initializers which rely on code-execution/allocated memory addresses and are
 /// therefore not resolvable before the codegen stage
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct InitData {
     pub initializer: Box<Option<AstNode>>,
     pub target_type_name: Option<String>,
@@ -122,7 +123,6 @@ impl InitData {
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum UnresolvableKind {
     /// Indicates that the const expression was not resolvable for any reason not listed in [`UnresolvableKind`].
     Misc(String),
@@ -156,14 +156,50 @@ impl UnresolvableKind {
 }
 
 #[derive(Default, Debug, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct ConstExpressions {
     expressions: Arena<ConstWrapper>,
+    /// the index these expressions were created on top of, see [`Index::with_imports`]
+    #[serde(skip)]
+    imports: Option<Arc<Index>>,
+    /// the number of ids reserved for the imported expressions, the ids of this arena start after them
+    #[serde(skip)]
+    offset: usize,
 }
 
 impl ConstExpressions {
     pub fn new() -> ConstExpressions {
-        ConstExpressions { expressions: Arena::new() }
+        ConstExpressions { expressions: Arena::new(), imports: None, offset: 0 }
+    }
+
+    /// creates empty const-expressions on top of the expressions of the given index. their ids stay
+    /// valid and are looked up in the imported index
+    pub fn with_imports(imports: Arc<Index>) -> ConstExpressions {
+        let imported = imports.get_const_expressions();
+        let offset = imported.offset + imported.expressions.capacity();
+        ConstExpressions { expressions: Arena::new(), imports: Some(imports), offset }
+    }
+
+    /// returns the arena's index of the given `id` or None if it belongs to an imported expression
+    fn to_arena_index(&self, id: &ConstId) -> Option<generational_arena::Index> {
+        let (slot, generation) = id.into_raw_parts();
+        slot.checked_sub(self.offset).map(|slot| generational_arena::Index::from_raw_parts(slot, generation))
+    }
+
+    fn to_id(offset: usize, index: generational_arena::Index) -> ConstId {
+        let (slot, generation) = index.into_raw_parts();
+        ConstId::from_raw_parts(slot + offset, generation)
+    }
+
+    fn get(&self, id: &ConstId) -> Option<&ConstWrapper> {
+        match self.to_arena_index(id) {
+            Some(index) => self.expressions.get(index),
+            None => self.imports.as_deref()?.get_const_expressions().get(id),
+        }
+    }
+
+    /// imported expressions cannot be changed, they were already resolved
+    fn get_mut(&mut self, id: &ConstId) -> Option<&mut ConstWrapper> {
+        self.to_arena_index(id).and_then(|index| self.expressions.get_mut(index))
     }
 
     /// adds the const expression `statement`
@@ -177,36 +213,36 @@ impl ConstExpressions {
         scope: Option<String>,
         lhs: Option<String>,
     ) -> ConstId {
-        self.expressions.insert(ConstWrapper {
+        let index = self.expressions.insert(ConstWrapper {
             expr: ConstExpression::Unresolved { statement, scope, lhs },
             target_type_name,
-        })
+        });
+        Self::to_id(self.offset, index)
     }
 
     /// returns the expression associated with the given `id` together with an optional
     /// `qualifier` that represents the expressions scope  (e.g. the host's POU-name)
     pub fn find_expression(&self, id: &ConstId) -> (Option<&AstNode>, Option<&str>) {
-        self.expressions
-            .get(*id)
+        self.get(id)
             .filter(|it| !it.expr.is_default())
             .map(|it| (Some(it.expr.get_statement()), it.expr.get_qualifier()))
             .unwrap_or((None, None))
     }
 
     pub fn find_expression_target_type(&self, id: &ConstId) -> Option<&str> {
-        self.expressions.get(*id).map(|it| it.target_type_name.as_str())
+        self.get(id).map(|it| it.target_type_name.as_str())
     }
 
     /// similar to `find_expression` but it does not return the `AstStatement` directly.
     /// it returns a ConstExpression wrapper that indicates whether this expression
     /// was successfully resolved yet or not
     pub fn find_const_expression(&self, id: &ConstId) -> Option<&ConstExpression> {
-        self.expressions.get(*id).map(|it| &it.expr)
+        self.get(id).map(|it| &it.expr)
     }
 
     /// clones the expression in the ConstExpressions and returns all of its elements
     pub fn clone(&self, id: &ConstId) -> Option<(AstNode, String, Option<String>, Option<String>)> {
-        self.expressions.get(*id).map(|it| match &it.expr {
+        self.get(id).map(|it| match &it.expr {
             ConstExpression::Unresolved { statement, scope, lhs: target } => {
                 (statement.clone(), it.target_type_name.clone(), scope.clone(), target.clone())
             }
@@ -219,10 +255,8 @@ impl ConstExpressions {
     /// marks the const-expression represented by the given `id` as resolvend and stores the the
     /// given `new_statement` as it's resolved value.
     pub fn mark_resolved(&mut self, id: &ConstId, new_statement: AstNode) -> Result<(), String> {
-        let wrapper = self
-            .expressions
-            .get_mut(*id)
-            .ok_or_else(|| format!("Cannot find constant expression with id: {id:?}"))?;
+        let wrapper =
+            self.get_mut(id).ok_or_else(|| format!("Cannot find constant expression with id: {id:?}"))?;
 
         wrapper.expr = ConstExpression::Resolved(new_statement);
         Ok(())
@@ -231,10 +265,8 @@ impl ConstExpressions {
     /// marks the const-expression represented by the given `id` as unresolvable with a given
     /// `reason`.
     pub fn mark_unresolvable(&mut self, id: &ConstId, reason: UnresolvableKind) -> Result<(), String> {
-        let wrapper = self
-            .expressions
-            .get_mut(*id)
-            .ok_or_else(|| format!("Cannot find constant expression with id: {id:?}"))?;
+        let wrapper =
+            self.get_mut(id).ok_or_else(|| format!("Cannot find constant expression with id: {id:?}"))?;
 
         wrapper.expr = ConstExpression::Unresolvable {
             statement: wrapper.get_statement().clone(),
@@ -313,18 +345,19 @@ impl<'a> IntoIterator for &'a ConstExpressions {
     type IntoIter = IntoStatementIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        IntoStatementIter { inner: self.expressions.iter() }
+        IntoStatementIter { inner: self.expressions.iter(), offset: self.offset }
     }
 }
 
 pub struct IntoStatementIter<'a> {
     inner: Iter<'a, ConstWrapper>,
+    offset: usize,
 }
 
 impl<'a> Iterator for IntoStatementIter<'a> {
     type Item = (ConstId, &'a AstNode);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(idx, expr)| (idx, expr.get_statement()))
+        self.inner.next().map(|(idx, expr)| (ConstExpressions::to_id(self.offset, idx), expr.get_statement()))
     }
 }
@@ -6,6 +6,7 @@ pub struct VarGlobalIndexer<'i> {
     constant: bool,
     linkage: LinkageType,
     retain: bool,
+    persistent: bool,
     index: &'i mut Index,
 }
 
@@ -13,10 +14,11 @@ impl VarGlobalIndexer<'_> {
     pub fn new(
         constant: bool,
         retain: bool,
+        persistent: bool,
         linkage: LinkageType,
         index: &mut Index,
     ) -> VarGlobalIndexer<'_> {
-        VarGlobalIndexer { constant, retain, linkage, index }
+        VarGlobalIndexer { constant, retain, persistent, linkage, index }
     }
 
     pub fn visit_variable(&mut self, var: &plc_ast::ast::Variable) {
@@ -36,6 +38,7 @@ impl VarGlobalIndexer<'_> {
         .set_initial_value(initializer)
         .set_constant(self.constant)
         .set_retain(self.retain)
+        .set_persistent(self.persistent)
         .set_linkage(self.linkage)
         .set_hardware_binding(
             var.address.as_ref().and_then(|it| HardwareBinding::from_statement(self.index, it, None)),
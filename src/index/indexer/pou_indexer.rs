@@ -40,6 +40,7 @@ impl<'i> PouIndexer<'i> {
                     is_constant: false,     //return variables are not constants
                     is_var_external: false, // see above
                     is_retain: false,       // return variables cannot be in retain blocks
+                    is_persistent: false,
                     binding: None,
                     varargs: None,
                 },
@@ -255,6 +256,7 @@ impl<'i> PouIndexer<'i> {
                         is_constant: block.constant,
                         is_var_external: matches!(block.kind, VariableBlockType::External),
                         is_retain: block.retain,
+                        is_persistent: block.persistent,
                         binding,
                         varargs,
                     },
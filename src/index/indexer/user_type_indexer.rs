@@ -505,6 +505,7 @@ impl UserTypeIndexer<'_, '_> {
                         is_constant: false, //struct members are not constants (although they can be part of a struct that is in a constant block)
                         is_var_external: false, // see above
                         is_retain: false, // struct members cannot be retain (although they can be part of a struct that is in a retain block)
+                        is_persistent: false,
                         binding,
                         varargs: None,
                     },
@@ -8,9 +8,11 @@ use plc_ast::provider::IdProvider;
 use plc_source::source_location::{SourceLocation, SourceLocationFactory};
 use pretty_assertions::assert_eq;
 use rustc_hash::FxHashMap;
+use std::sync::Arc;
 
-use crate::index::{ArgumentType, PouIndexEntry, VariableIndexEntry};
+use crate::index::{indexer, ArgumentType, Index, PouIndexEntry, RetainSection, VariableIndexEntry};
 use crate::parser::tests::literal_int;
+use crate::resolver::const_evaluator::evaluate_constants;
 use crate::test_utils::tests::{annotate_with_ids, index, index_with_ids, parse_and_preprocess};
 use crate::typesystem::{InternalType, StructSource, TypeSize, INT_TYPE, VOID_TYPE};
 use crate::{index::VariableType, typesystem::DataTypeInformation};
@@ -1373,6 +1375,7 @@ fn a_program_pou_is_indexed() {
                 is_constant: false,
                 is_var_external: false,
                 is_retain: false,
+                is_persistent: false,
                 data_type_name: "myProgram".into(),
                 location_in_parent: 0,
                 linkage: LinkageType::Internal,
@@ -1650,6 +1653,7 @@ fn internal_vla_struct_type_is_indexed_correctly() {
                     is_constant: false,
                     is_var_external: false,
                     is_retain: false,
+                    is_persistent: false,
                     data_type_name: "__ptr_to___foo_arr_vla_1_int".to_string(),
                     location_in_parent: 0,
                     linkage: LinkageType::Internal,
@@ -1665,6 +1669,7 @@ fn internal_vla_struct_type_is_indexed_correctly() {
                     is_constant: false,
                     is_var_external: false,
                     is_retain: false,
+                    is_persistent: false,
                     data_type_name: "__bounds___foo_arr_vla_1_int".to_string(),
                     location_in_parent: 1,
                     linkage: LinkageType::Internal,
@@ -1735,6 +1740,7 @@ fn aliased_hardware_access_variable_has_implicit_initial_value_declaration() {
         is_constant: false,
         is_var_external: false,
         is_retain: false,
+        is_persistent: false,
         data_type_name: "__global_foo",
         location_in_parent: 0,
         linkage: Internal,
@@ -1803,6 +1809,7 @@ fn aliased_hardware_access_variable_creates_global_var_for_address() {
         is_constant: false,
         is_var_external: false,
         is_retain: false,
+        is_persistent: false,
         data_type_name: "BOOL",
         location_in_parent: 0,
         linkage: Internal,
@@ -1941,6 +1948,7 @@ fn address_used_in_2_aliases_only_created_once() {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: "BOOL",
             location_in_parent: 0,
             linkage: Internal,
@@ -1985,6 +1993,7 @@ fn aliased_variable_with_in_or_out_directions_create_the_same_variable() {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: "BOOL",
             location_in_parent: 0,
             linkage: Internal,
@@ -2011,6 +2020,7 @@ fn aliased_variable_with_in_or_out_directions_create_the_same_variable() {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: "WORD",
             location_in_parent: 0,
             linkage: Internal,
@@ -2053,6 +2063,7 @@ fn if_two_aliased_var_of_different_types_use_the_same_address_the_first_wins() {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: "BOOL",
             location_in_parent: 0,
             linkage: Internal,
@@ -2092,6 +2103,7 @@ fn var_config_hardware_address_creates_global_variable() {
         is_constant: false,
         is_var_external: false,
         is_retain: false,
+        is_persistent: false,
         data_type_name: "BOOL",
         location_in_parent: 0,
         linkage: Internal,
@@ -2741,6 +2753,82 @@ fn var_nested_retain_in_program() {
     assert!(arr.should_retain(&index));
 }
 
+#[test]
+fn persistent_variables_are_not_retain() {
+    let (_, index) = index(
+        r#"
+        VAR_GLOBAL RETAIN
+            x : INT;
+        END_VAR
+        VAR_GLOBAL PERSISTENT
+            y : INT;
+        END_VAR
+        VAR_GLOBAL RETAIN PERSISTENT
+            z : INT;
+        END_VAR
+        "#,
+    );
+
+    let x = index.find_global_variable("x").unwrap();
+    assert!(x.is_retain && !x.is_persistent);
+
+    let y = index.find_global_variable("y").unwrap();
+    assert!(!y.is_retain && y.is_persistent);
+
+    let z = index.find_global_variable("z").unwrap();
+    assert!(z.is_retain && z.is_persistent);
+
+    assert_eq!(x.get_retain_section(&index), Some(RetainSection::Retain));
+    assert_eq!(y.get_retain_section(&index), Some(RetainSection::Persistent));
+    assert_eq!(z.get_retain_section(&index), Some(RetainSection::RetainPersistent));
+}
+
+#[test]
+fn var_nested_persistent_in_program() {
+    let (_, index) = index(
+        r#"
+        FUNCTION_BLOCK nested
+            VAR RETAIN
+            a : INT;
+            END_VAR
+            VAR PERSISTENT
+            b : INT;
+            END_VAR
+        END_FUNCTION_BLOCK
+        FUNCTION_BLOCK nested2
+            VAR RETAIN
+            a : INT;
+            END_VAR
+        END_FUNCTION_BLOCK
+        TYPE deepNested : STRUCT
+            fb: nested;
+        END_STRUCT
+        END_TYPE
+
+        PROGRAM P
+            VAR
+                fb: nested;
+                fb2: nested2;
+                str: deepNested;
+                arr: ARRAY[0..10] OF nested;
+            END_VAR
+        END_PROGRAM
+        "#,
+    );
+
+    // an instance with retain and persistent members cannot be placed into a single section
+    let fb = index.find_local_member("P", "fb").unwrap();
+    assert!(fb.should_retain(&index) && fb.should_persist(&index));
+    assert_eq!(fb.get_retain_sections(&index), [RetainSection::Retain, RetainSection::Persistent]);
+    let fb2 = index.find_local_member("P", "fb2").unwrap();
+    assert!(fb2.should_retain(&index) && !fb2.should_persist(&index));
+    assert_eq!(fb2.get_retain_sections(&index), [RetainSection::Retain]);
+    let str = index.find_local_member("P", "str").unwrap();
+    assert!(str.should_persist(&index));
+    let arr = index.find_local_member("P", "arr").unwrap();
+    assert!(arr.should_persist(&index));
+}
+
 #[test]
 fn var_nested_retain_in_global() {
     let (_, index) = index(
@@ -2782,3 +2870,56 @@ fn var_nested_retain_in_global() {
     let arr = index.find_global_variable("arr").unwrap();
     assert!(arr.should_retain(&index));
 }
+
+#[test]
+fn index_on_top_of_imports_resolves_through_them() {
+    let id_provider = IdProvider::default();
+    let (_, imports) = index_with_ids(
+        r#"
+        VAR_GLOBAL CONSTANT
+            size : INT := 5;
+        END_VAR
+        TYPE point : STRUCT x, y : INT; END_STRUCT END_TYPE
+        FUNCTION foo : INT
+        END_FUNCTION
+    "#,
+        id_provider.clone(),
+    );
+    let (imports, _) = evaluate_constants(imports);
+    let mut imports = Arc::new(imports);
+
+    let (unit, _) = index_with_ids(
+        r#"
+        VAR_GLOBAL CONSTANT
+            twice : INT := size * 2;
+        END_VAR
+        FUNCTION bar : point
+        END_FUNCTION
+    "#,
+        id_provider,
+    );
+    let mut index = Index::with_imports(imports.clone());
+    index.import(indexer::index(&unit));
+    let (index, _) = evaluate_constants(index);
+
+    // lookups fall back to the imported declarations
+    assert!(index.find_pou("foo").is_some());
+    assert!(index.find_type("point").is_some());
+    assert_eq!(index.get_return_type_or_void("bar").get_name(), "point");
+    assert_eq!(index.get_effective_type_or_void_by_name("INT").get_name(), INT_TYPE);
+    // iterating only yields the declarations added on top of the imports
+    assert_eq!(index.get_pous().keys().collect::<Vec<_>>(), ["bar"]);
+    assert_eq!(index.get_globals().keys().collect::<Vec<_>>(), ["twice"]);
+
+    // constants keep their ids and new ones are resolved against the imported ones
+    let constants = index.get_const_expressions();
+    let size = index.find_global_variable("size").and_then(|it| it.initial_value).unwrap();
+    let twice = index.find_global_variable("twice").and_then(|it| it.initial_value).unwrap();
+    assert_ne!(size, twice);
+    assert_eq!(constants.get_constant_int_statement_value(&size), Ok(5));
+    assert_eq!(constants.get_constant_int_statement_value(&twice), Ok(10));
+
+    // the imported index is shared, not copied
+    drop(index);
+    assert!(Arc::get_mut(&mut imports).is_some());
+}
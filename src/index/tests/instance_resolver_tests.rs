@@ -256,6 +256,7 @@ fn aliased_structs_are_retrieved() {
                 is_constant: false,
                 is_var_external: false,
                 is_retain: false,
+                is_persistent: false,
                 data_type_name: "strAlias",
                 location_in_parent: 0,
                 linkage: Internal,
@@ -287,6 +288,7 @@ fn aliased_structs_are_retrieved() {
                 is_constant: false,
                 is_var_external: false,
                 is_retain: false,
+                is_persistent: false,
                 data_type_name: "MainProg",
                 location_in_parent: 0,
                 linkage: Internal,
@@ -321,6 +323,7 @@ fn aliased_structs_are_retrieved() {
                 is_constant: false,
                 is_var_external: false,
                 is_retain: false,
+                is_persistent: false,
                 data_type_name: "strAlias",
                 location_in_parent: 0,
                 linkage: Internal,
@@ -373,6 +376,7 @@ fn enum_variables_are_not_recursed() {
                 is_constant: false,
                 is_var_external: false,
                 is_retain: false,
+                is_persistent: false,
                 data_type_name: "enumType",
                 location_in_parent: 0,
                 linkage: Internal,
@@ -75,6 +75,7 @@ fn interface_with_single_method() {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: "INT",
             location_in_parent: 0,
             linkage: Internal,
@@ -97,6 +98,7 @@ fn interface_with_single_method() {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: "INT",
             location_in_parent: 1,
             linkage: Internal,
@@ -119,6 +121,7 @@ fn interface_with_single_method() {
             is_constant: false,
             is_var_external: false,
             is_retain: false,
+            is_persistent: false,
             data_type_name: "INT",
             location_in_parent: 2,
             linkage: Internal,
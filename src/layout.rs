@@ -0,0 +1,470 @@
+//! Memory layout of the types and the global data of a project, requested with `--emit layout=PATH`.
+//!
+//! The offsets are computed like the code generator lays out its types (see [`crate::datalayout`]),
+//! with the sizes and alignments of the primitive types taken from the target the project is compiled
+//! for. Every struct, function block, program, class and array type is listed with the offset, size
+//! and alignment of its members and the padding the code generator inserts between and after them.
+//!
+//! ```text
+//! FUNCTION_BLOCK Motor : size 16, alignment 8
+//!   offset  size  align  member
+//!        0     8      8  __vtable : POINTER TO __VOID
+//!        8     1      1  enabled : BOOL
+//!        9     7         <padding>
+//! ```
+
+use std::fmt::Write;
+
+use plc_ast::ast::{LinkageType, PouType};
+use plc_diagnostics::diagnostics::Diagnostic;
+use serde::Serialize;
+
+use crate::{
+    datalayout::DataLayout,
+    index::{Index, VariableIndexEntry},
+    typesystem::{DataType, DataTypeInformation, StructSource},
+    Target,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LayoutReport {
+    /// the target triple the layout was computed for
+    pub target: String,
+    pub types: Vec<TypeLayout>,
+    /// the size of all global variables and program instances which are not retained, in bytes
+    pub global_size: u32,
+    /// the size of all global variables and program instances placed into the retain and persistent
+    /// sections, in bytes
+    pub retain_size: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeLayout {
+    pub name: String,
+    pub kind: TypeKind,
+    pub size: u32,
+    pub alignment: u32,
+    /// the stored members of a struct, function block, program or class
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<MemberLayout>,
+    /// the gaps between and after the members which do not hold any value
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub padding: Vec<Padding>,
+    /// the elements of an array
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elements: Option<ElementLayout>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum TypeKind {
+    Struct,
+    FunctionBlock,
+    Program,
+    Class,
+    Array,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemberLayout {
+    pub name: String,
+    pub type_name: String,
+    /// the offset of the member from the start of its container in bytes
+    pub offset: u32,
+    pub size: u32,
+    pub alignment: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct Padding {
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ElementLayout {
+    pub type_name: String,
+    pub count: u32,
+    /// the size of a single element in bytes, which is also the distance between two elements
+    pub size: u32,
+}
+
+/// Collects the layout of all types and the size of the global data on the given target
+pub fn collect_layout(index: &Index, target: &Target) -> Result<LayoutReport, Diagnostic> {
+    let layout = crate::codegen::get_target_data_layout(target)?;
+    let triple = target.get_target_triple();
+    collect_layout_with(index, &layout, &triple.as_str().to_string_lossy())
+}
+
+fn collect_layout_with(index: &Index, layout: &DataLayout, target: &str) -> Result<LayoutReport, Diagnostic> {
+    let mut types = vec![];
+    for data_type in index.get_types().values().chain(index.get_pou_types().values()) {
+        if data_type.linkage == LinkageType::BuiltIn || data_type.get_type_information().is_generic(index) {
+            continue;
+        }
+        if let Some(type_layout) = get_type_layout(index, layout, data_type)? {
+            types.push(type_layout);
+        }
+    }
+
+    let (mut global_size, mut retain_size) = (0, 0);
+    for variable in index.get_globals().values().chain(index.get_program_instances()) {
+        if variable.is_constant() || variable.get_linkage() == LinkageType::External {
+            continue;
+        }
+        let size = get_size(index, layout, variable.get_type_name())?;
+        if variable.get_retain_section(index).is_some() {
+            retain_size += size;
+        } else {
+            global_size += size;
+        }
+    }
+
+    Ok(LayoutReport { target: target.to_string(), types, global_size, retain_size })
+}
+
+/// Returns the layout of the given type, `None` if it is neither a struct, a stateful POU nor an array
+fn get_type_layout(
+    index: &Index,
+    layout: &DataLayout,
+    data_type: &DataType,
+) -> Result<Option<TypeLayout>, Diagnostic> {
+    let information = data_type.get_type_information();
+    let kind = match information {
+        DataTypeInformation::Struct { source, .. } => match source {
+            StructSource::OriginalDeclaration => TypeKind::Struct,
+            StructSource::Pou(PouType::FunctionBlock) => TypeKind::FunctionBlock,
+            StructSource::Pou(PouType::Program) => TypeKind::Program,
+            StructSource::Pou(PouType::Class) => TypeKind::Class,
+            // the variables of functions and methods live on the stack
+            _ => return Ok(None),
+        },
+        DataTypeInformation::Array { .. } => TypeKind::Array,
+        _ => return Ok(None),
+    };
+
+    // arrays of variable length have no layout of their own
+    let Ok(size) = layout.get_size(information, index).map(|it| it.value()) else {
+        return Ok(None);
+    };
+    let alignment = layout.get_alignment(information, index).value();
+    let mut type_layout = TypeLayout {
+        name: data_type.get_name().to_string(),
+        kind,
+        size,
+        alignment,
+        members: vec![],
+        padding: vec![],
+        elements: None,
+    };
+
+    match information {
+        DataTypeInformation::Struct { members, .. } => {
+            let struct_layout = layout.get_struct_layout(members, index).map_err(to_diagnostic)?;
+            let mut end = 0;
+            for (member, offset) in struct_layout.offsets {
+                let member_layout = get_member_layout(index, layout, member, offset.value())?;
+                if member_layout.offset > end {
+                    type_layout.padding.push(Padding { offset: end, size: member_layout.offset - end });
+                }
+                end = member_layout.offset + member_layout.size;
+                type_layout.members.push(member_layout);
+            }
+            if size > end {
+                type_layout.padding.push(Padding { offset: end, size: size - end });
+            }
+        }
+        DataTypeInformation::Array { inner_type_name, .. } => {
+            let element_size = get_size(index, layout, inner_type_name)?;
+            type_layout.elements = Some(ElementLayout {
+                type_name: crate::doc::get_type_text(index, inner_type_name),
+                count: size.checked_div(element_size).unwrap_or_default(),
+                size: element_size,
+            });
+        }
+        _ => unreachable!("only structs and arrays are laid out"),
+    }
+
+    Ok(Some(type_layout))
+}
+
+fn get_member_layout(
+    index: &Index,
+    layout: &DataLayout,
+    member: &VariableIndexEntry,
+    offset: u32,
+) -> Result<MemberLayout, Diagnostic> {
+    let information = index.get_type_information_or_void(member.get_type_name());
+    Ok(MemberLayout {
+        name: member.get_name().to_string(),
+        type_name: crate::doc::get_type_text(index, member.get_type_name()),
+        offset,
+        size: layout.get_size(information, index).map_err(to_diagnostic)?.value(),
+        alignment: layout.get_alignment(information, index).value(),
+    })
+}
+
+fn get_size(index: &Index, layout: &DataLayout, type_name: &str) -> Result<u32, Diagnostic> {
+    let information = index.get_type_information_or_void(type_name);
+    layout.get_size(information, index).map(|it| it.value()).map_err(to_diagnostic)
+}
+
+fn to_diagnostic(error: anyhow::Error) -> Diagnostic {
+    Diagnostic::new(error.to_string()).with_error_code("E002")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutFormat {
+    Text,
+    Json,
+}
+
+impl LayoutFormat {
+    /// Returns the format matching the extension of the given file name, text for anything but `.json`
+    pub fn from_file_name(name: &str) -> LayoutFormat {
+        match name.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("json") => LayoutFormat::Json,
+            _ => LayoutFormat::Text,
+        }
+    }
+}
+
+pub fn serialize_layout(report: &LayoutReport, format: LayoutFormat) -> Result<String, Diagnostic> {
+    match format {
+        LayoutFormat::Text => Ok(to_text(report)),
+        LayoutFormat::Json => serde_json::to_string_pretty(report).map_err(|it| {
+            Diagnostic::new(it.to_string()).with_internal_error(it.into()).with_error_code("E002")
+        }),
+    }
+}
+
+fn to_text(report: &LayoutReport) -> String {
+    let mut text = format!("Memory layout for target {}\n", report.target);
+    for type_layout in &report.types {
+        let keyword = match type_layout.kind {
+            TypeKind::Struct => "STRUCT",
+            TypeKind::FunctionBlock => "FUNCTION_BLOCK",
+            TypeKind::Program => "PROGRAM",
+            TypeKind::Class => "CLASS",
+            TypeKind::Array => "ARRAY",
+        };
+        let _ = writeln!(
+            text,
+            "\n{keyword} {} : size {}, alignment {}",
+            type_layout.name, type_layout.size, type_layout.alignment
+        );
+
+        if let Some(elements) = &type_layout.elements {
+            let _ = writeln!(
+                text,
+                "  {} elements of {}, {} bytes each",
+                elements.count, elements.type_name, elements.size
+            );
+            continue;
+        }
+
+        // members and padding holes are listed by their offset
+        let mut rows = type_layout
+            .members
+            .iter()
+            .map(|it| {
+                (it.offset, it.size, it.alignment.to_string(), format!("{} : {}", it.name, it.type_name))
+            })
+            .chain(
+                type_layout.padding.iter().map(|it| (it.offset, it.size, String::new(), "<padding>".into())),
+            )
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|(offset, ..)| *offset);
+
+        let _ = writeln!(text, "  offset  size  align  member");
+        for (offset, size, alignment, member) in rows {
+            let _ = writeln!(text, "  {offset:>6}  {size:>4}  {alignment:>5}  {member}");
+        }
+    }
+
+    let _ = writeln!(text, "\nGlobal data : {} bytes", report.global_size);
+    let _ = writeln!(text, "Retain data : {} bytes", report.retain_size);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_layout_with, serialize_layout, LayoutFormat, Padding, TypeKind};
+    use crate::{
+        datalayout::{Bytes, DataLayout},
+        test_utils::tests::index,
+    };
+
+    #[test]
+    fn struct_members_are_listed_with_their_padding() {
+        let (_, index) = index(
+            "
+            TYPE Point : STRUCT
+                flag : BOOL;
+                x : LREAL;
+                id : INT;
+            END_STRUCT
+            END_TYPE
+
+            FUNCTION_BLOCK Motor
+            VAR_INPUT
+                speeds : ARRAY[0..2] OF INT;
+            END_VAR
+            VAR_TEMP
+                scratch : DINT;
+            END_VAR
+            END_FUNCTION_BLOCK
+            ",
+        );
+
+        let report = collect_layout_with(&index, index.get_type_layout(), "x86_64-pc-linux-gnu").unwrap();
+        let point = report.types.iter().find(|it| it.name == "Point").unwrap();
+        assert_eq!((point.kind, point.size, point.alignment), (TypeKind::Struct, 24, 8));
+        let offsets =
+            point.members.iter().map(|it| (it.name.as_str(), it.offset, it.size)).collect::<Vec<_>>();
+        assert_eq!(offsets, [("flag", 0, 1), ("x", 8, 8), ("id", 16, 2)]);
+        assert_eq!(point.padding, [Padding { offset: 1, size: 7 }, Padding { offset: 18, size: 6 }]);
+
+        // temporary variables are not stored in the instance
+        let motor = report.types.iter().find(|it| it.name == "Motor").unwrap();
+        assert_eq!(motor.kind, TypeKind::FunctionBlock);
+        assert!(motor.members.iter().all(|it| it.name != "scratch"));
+
+        let speeds = report.types.iter().find(|it| it.kind == TypeKind::Array).unwrap();
+        let elements = speeds.elements.as_ref().unwrap();
+        assert_eq!((speeds.size, elements.count, elements.size), (6, 3, 2));
+
+        let text = serialize_layout(&report, LayoutFormat::Text).unwrap();
+        assert!(text.contains("STRUCT Point : size 24, alignment 8\n"), "{text}");
+        assert!(text.contains("       1     7         <padding>\n"), "{text}");
+        assert!(text.contains("       8     8      8  x : LREAL\n"), "{text}");
+    }
+
+    #[test]
+    fn global_and_retain_data_are_summed_up() {
+        let (_, index) = index(
+            "
+            VAR_GLOBAL
+                a : DINT;
+                b : LREAL;
+            END_VAR
+            VAR_GLOBAL CONSTANT
+                c : DINT := 1;
+            END_VAR
+            VAR_GLOBAL RETAIN
+                d : ARRAY[0..3] OF INT;
+            END_VAR
+
+            PROGRAM main
+            VAR
+                x : BOOL;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+
+        let report = collect_layout_with(&index, index.get_type_layout(), "x86_64-pc-linux-gnu").unwrap();
+        assert_eq!((report.global_size, report.retain_size), (13, 8));
+
+        let text = serialize_layout(&report, LayoutFormat::Text).unwrap();
+        assert!(text.ends_with("Global data : 13 bytes\nRetain data : 8 bytes\n"), "{text}");
+    }
+
+    #[test]
+    fn nested_instances_and_arrays_of_structs_are_laid_out_in_place() {
+        let (_, index) = index(
+            "
+            TYPE Sample : STRUCT
+                valid : BOOL;
+                value : LREAL;
+            END_STRUCT
+            END_TYPE
+
+            FUNCTION_BLOCK Counter
+            VAR_INPUT
+                reset : BOOL;
+            END_VAR
+            VAR
+                count : DINT;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            FUNCTION_BLOCK Logger
+            VAR
+                active : BOOL;
+                counter : Counter;
+                history : ARRAY[0..2] OF Sample;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            PROGRAM recorder
+            VAR
+                log : Logger;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+
+        let report = collect_layout_with(&index, index.get_type_layout(), "x86_64-pc-linux-gnu").unwrap();
+        let counter = report.types.iter().find(|it| it.name == "Counter").unwrap();
+        assert_eq!((counter.size, counter.alignment), (8, 4));
+
+        // the instance of the nested function block and the array are stored within the logger, aligned
+        // like their most strictly aligned member
+        let logger = report.types.iter().find(|it| it.name == "Logger").unwrap();
+        assert_eq!((logger.kind, logger.size, logger.alignment), (TypeKind::FunctionBlock, 64, 8));
+        let offsets =
+            logger.members.iter().map(|it| (it.name.as_str(), it.offset, it.size)).collect::<Vec<_>>();
+        assert_eq!(offsets, [("active", 0, 1), ("counter", 4, 8), ("history", 16, 48)]);
+        assert_eq!(logger.padding, [Padding { offset: 1, size: 3 }, Padding { offset: 12, size: 4 }]);
+
+        // the elements of an array of structs include the padding of the struct
+        let history = report
+            .types
+            .iter()
+            .filter_map(|it| it.elements.as_ref().map(|elements| (it, elements)))
+            .find(|(_, elements)| elements.type_name == "Sample")
+            .unwrap();
+        assert_eq!((history.0.size, history.1.count, history.1.size), (48, 3, 16));
+
+        let recorder = report.types.iter().find(|it| it.name == "recorder").unwrap();
+        assert_eq!((recorder.kind, recorder.size, recorder.alignment), (TypeKind::Program, 64, 8));
+        assert_eq!(report.global_size, 64);
+    }
+
+    #[test]
+    fn padding_follows_the_alignment_of_the_target() {
+        let (_, index) = index(
+            "
+            TYPE Sample : STRUCT
+                flag : BOOL;
+                value : LREAL;
+                next : REF_TO Sample;
+            END_STRUCT
+            END_TYPE
+            ",
+        );
+        let layout_of = |layout: &DataLayout| {
+            let report = collect_layout_with(&index, layout, "test").unwrap();
+            let sample = report.types.into_iter().find(|it| it.name == "Sample").unwrap();
+            let offsets = sample.members.iter().map(|it| (it.offset, it.size)).collect::<Vec<_>>();
+            (sample.size, sample.alignment, offsets, sample.padding)
+        };
+
+        assert_eq!(
+            layout_of(index.get_type_layout()),
+            (24, 8, vec![(0, 1), (8, 8), (16, 8)], vec![Padding { offset: 1, size: 7 }])
+        );
+
+        // 32 bit targets align 64 bit values to 4 bytes and use 4 byte pointers
+        let layout = DataLayout {
+            i64: Bytes::new(4),
+            f64: Bytes::new(4),
+            p64: Bytes::new(4),
+            ..DataLayout::default()
+        };
+        assert_eq!(
+            layout_of(&layout),
+            (16, 4, vec![(0, 1), (4, 8), (12, 4)], vec![Padding { offset: 1, size: 3 }])
+        );
+    }
+}
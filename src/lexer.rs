@@ -1,10 +1,11 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use core::ops::Range;
 use logos::{Filter, Lexer, Logos};
-use plc_ast::ast::{AstId, DirectAccessType, HardwareAccessType};
+use plc_ast::ast::{AstId, Attribute, DirectAccessType, DocComment, HardwareAccessType, Trivia};
 use plc_ast::provider::IdProvider;
 use plc_diagnostics::diagnostics::Diagnostic;
 use plc_source::source_location::{SourceLocation, SourceLocationFactory};
+use rustc_hash::FxHashMap;
 pub use tokens::{Token, TokenClass};
 
 #[cfg(test)]
@@ -24,6 +25,17 @@ pub struct ParseSession<'a> {
     pub id_provider: IdProvider,
     pub source_range_factory: SourceLocationFactory,
     pub scope: Option<String>,
+    /// the ranges of the doc comments in front of the current token
+    doc_comment: Vec<Range<usize>>,
+    /// the doc comments attached to the declarations parsed so far
+    doc_comments: Vec<DocComment>,
+    /// the names and values of the `{attribute ...}` pragmas in front of the current token
+    attribute: Vec<(String, Option<String>)>,
+    /// the attributes attached to the declarations parsed so far
+    attributes: Vec<Attribute>,
+    /// the end offsets of the declarations whose location does not span their closing keyword (e.g.
+    /// the location of a `VAR` block only covers the `VAR` keyword), keyed by their start offset
+    declaration_ends: FxHashMap<usize, usize>,
 }
 
 #[macro_export]
@@ -57,6 +69,11 @@ impl<'a> ParseSession<'a> {
             id_provider,
             scope: None,
             source_range_factory,
+            doc_comment: vec![],
+            doc_comments: vec![],
+            attribute: vec![],
+            attributes: vec![],
+            declaration_ends: FxHashMap::default(),
         };
         lexer.advance();
         lexer
@@ -103,15 +120,99 @@ impl<'a> ParseSession<'a> {
         self.token == Token::End || self.token == Token::Error
     }
 
+    /// Returns the ranges of the comments and unknown pragmas skipped so far, trivia that
+    /// was already taken is not returned again
+    pub fn take_trivia(&mut self) -> Vec<Range<usize>> {
+        std::mem::take(&mut self.lexer.extras)
+    }
+
     pub fn slice_region(&self, range: Range<usize>) -> &str {
         &self.lexer.source()[range]
     }
 
+    /// Returns the text of the doc comments in front of the current token. The doc comments of a
+    /// declaration may also precede an `{external}` or `{constant}` pragma in front of it.
+    pub fn get_doc_comment(&self) -> Option<String> {
+        if self.doc_comment.is_empty() {
+            return None;
+        }
+        let text = self
+            .doc_comment
+            .iter()
+            .map(|range| Trivia::get_doc_text(self.slice_region(range.clone())))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(text)
+    }
+
+    /// Attaches the doc comment to the declaration whose name is at the given location
+    pub fn attach_doc_comment(&mut self, doc: Option<String>, location: &SourceLocation) {
+        if let Some(text) = doc {
+            self.doc_comments.push(DocComment { text, location: location.clone() });
+        }
+    }
+
+    /// Returns the doc comments attached so far
+    pub fn take_doc_comments(&mut self) -> Vec<DocComment> {
+        std::mem::take(&mut self.doc_comments)
+    }
+
+    /// Returns the names and values of the `{attribute ...}` pragmas in front of the current token
+    pub fn get_attributes(&self) -> Vec<(String, Option<String>)> {
+        self.attribute.clone()
+    }
+
+    /// Attaches the attributes to the declaration whose name is at the given location
+    pub fn attach_attributes(
+        &mut self,
+        attributes: Vec<(String, Option<String>)>,
+        location: &SourceLocation,
+    ) {
+        self.attributes.extend(attributes.into_iter().map(|(name, value)| Attribute {
+            name,
+            value,
+            location: location.clone(),
+        }));
+    }
+
+    /// Returns the attributes attached so far
+    pub fn take_attributes(&mut self) -> Vec<Attribute> {
+        std::mem::take(&mut self.attributes)
+    }
+
+    /// Marks the last consumed token as the end of the declaration starting at the given offset
+    pub fn mark_declaration_end(&mut self, start: usize) {
+        self.declaration_ends.insert(start, self.last_range.end);
+    }
+
+    /// Returns the declaration ends marked so far
+    pub fn take_declaration_ends(&mut self) -> FxHashMap<usize, usize> {
+        std::mem::take(&mut self.declaration_ends)
+    }
+
     pub fn advance(&mut self) {
         self.last_range = self.range();
+        let trivia_count = self.lexer.extras.len();
         self.last_token = std::mem::replace(&mut self.token, self.lexer.next().unwrap_or(Token::End));
         self.parse_progress += 1;
 
+        let source = self.lexer.source();
+        let trivia = self.lexer.extras.get(trivia_count..).unwrap_or_default();
+        let doc_comment = trivia
+            .iter()
+            .filter(|it| Trivia::is_doc_comment(&source[(*it).clone()]))
+            .cloned()
+            .collect::<Vec<_>>();
+        let attribute =
+            trivia.iter().filter_map(|it| Attribute::parse(&source[it.clone()])).collect::<Vec<_>>();
+        if !matches!(self.last_token, Token::PropertyExternal | Token::PropertyConstant) {
+            self.doc_comment = doc_comment;
+            self.attribute = attribute;
+        } else {
+            self.doc_comment.extend(doc_comment);
+            self.attribute.extend(attribute);
+        }
+
         match self.token {
             Token::KeywordVarInput
             | Token::KeywordVarOutput
@@ -226,12 +327,41 @@ impl<'a> ParseSession<'a> {
                     .last()
                     .expect("parse-recovery has no closing-keyword to recover from."); //illegal state! invalid use of parser-recovery?
                 let expected_tokens = format!("{closing:?}");
-                self.accept_diagnostic(Diagnostic::missing_token(expected_tokens.as_str(), self.location()));
+                let mut diagnostic = Diagnostic::missing_token(expected_tokens.as_str(), self.location());
+                if let Some(keyword) = closing.first().and_then(get_closing_keyword) {
+                    // insert the keyword in front of the current token, keeping the token's indentation
+                    let start = self.range().start;
+                    let line_start = self.lexer.source()[..start].rfind('\n').map(|it| it + 1).unwrap_or(0);
+                    let indentation = &self.lexer.source()[line_start..start];
+                    let replacement = if indentation.trim().is_empty() {
+                        format!("{keyword}\n{indentation}")
+                    } else {
+                        format!("{keyword} ")
+                    };
+                    diagnostic = diagnostic.with_suggested_edit(
+                        format!("insert `{keyword}`"),
+                        self.location().get_start(),
+                        replacement,
+                    );
+                }
+                self.accept_diagnostic(diagnostic);
             }
         }
     }
 }
 
+/// returns the keyword closing the body of a control statement, `None` for any other token
+fn get_closing_keyword(token: &Token) -> Option<&'static str> {
+    match token {
+        Token::KeywordEndIf => Some("END_IF"),
+        Token::KeywordEndFor => Some("END_FOR"),
+        Token::KeywordEndWhile => Some("END_WHILE"),
+        Token::KeywordEndRepeat => Some("END_REPEAT"),
+        Token::KeywordEndCase => Some("END_CASE"),
+        _ => None,
+    }
+}
+
 fn parse_pragma(lexer: &mut Lexer<Token>) -> Filter<()> {
     let remainder = lexer.remainder();
     let chars = remainder.chars();
@@ -240,6 +370,7 @@ fn parse_pragma(lexer: &mut Lexer<Token>) -> Filter<()> {
         traversed += c.len_utf8();
         if c == '}' {
             lexer.bump(traversed);
+            lexer.extras.push(lexer.span());
             return Filter::Skip;
         }
     }
@@ -269,12 +400,18 @@ fn parse_comments(lexer: &mut Lexer<Token>) -> Filter<()> {
         if unclosed == 0 {
             lexer.bump(traversed);
             //This is a well formed comment, treat it as whitespace
+            lexer.extras.push(lexer.span());
             return Filter::Skip;
         }
     }
     Filter::Emit(())
 }
 
+fn parse_line_comment(lexer: &mut Lexer<Token>) -> Filter<()> {
+    lexer.extras.push(lexer.span());
+    Filter::Skip
+}
+
 fn get_closing_tag(open_tag: &str) -> (char, char) {
     match open_tag {
         "(*" => ('(', ')'),
@@ -806,7 +806,7 @@ fn lowercase_keywords_accepted() {
     let mut result = lex(r###"
         program class end_class endclass var_input varinput var_output
         varoutput var abstract final method constant retain non_retain
-        nonretain var_temp vartemp end_method endmethod
+        nonretain persistent var_temp vartemp end_method endmethod
         public private internal protected override
         var_global varglobal var_in_out varinout end_var endvar
         end_program endprogram end_function endfunction end_function_block endfunctionblock
@@ -1,14 +1,17 @@
+use std::ops::Range;
+
 use logos::Logos;
 
 use plc_ast::ast::{DirectAccessType, HardwareAccessType};
 
 #[derive(Debug, PartialEq, Eq, Logos, Clone, Copy)]
+#[logos(extras = Vec<Range<usize>>)]
 pub enum Token {
     #[error]
     #[regex(r"\(\*", |lex| super::parse_comments(lex))]
     #[regex(r"/\*", |lex| super::parse_comments(lex))]
     #[regex(r"\{", |lex| super::parse_pragma(lex))]
-    #[regex(r"//.*", logos::skip)]
+    #[regex(r"//.*", super::parse_line_comment)]
     #[regex(r"(?m)\r", logos::skip)]
     Error,
 
@@ -104,6 +107,9 @@ pub enum Token {
     #[token("NONRETAIN", ignore(case))]
     KeywordNonRetain,
 
+    #[token("PERSISTENT", ignore(case))]
+    KeywordPersistent,
+
     #[token("VAR_TEMP", ignore(case))]
     #[token("VARTEMP", ignore(case))]
     KeywordVarTemp,
@@ -469,6 +475,7 @@ impl Token {
             Token::KeywordConstant
             | Token::KeywordRetain
             | Token::KeywordNonRetain
+            | Token::KeywordPersistent
             | Token::KeywordAbstract
             | Token::KeywordFinal
             | Token::KeywordOverride
@@ -30,27 +30,40 @@ use inkwell::targets::{self, TargetMachine, TargetTriple};
 #[cfg(test)]
 use validation::Validator;
 
+pub mod bindings;
 pub mod builtins;
+pub mod call_graph;
 pub mod codegen;
 mod datalayout;
+pub mod doc;
 pub mod expression_path;
+pub mod formatter;
 pub mod hardware_binding;
 pub mod hw_map;
 pub mod index;
+pub mod layout;
 pub mod lexer;
 pub mod linker;
 pub mod lowering;
+pub mod metrics;
+pub mod online_change;
+pub mod opcua;
 pub mod output;
 pub mod parser;
 pub mod resolver;
+pub mod stack_usage;
+pub mod symbols;
 mod test_utils;
 
 pub mod typesystem;
 pub mod validation;
+pub mod xref;
 extern crate shell_words;
 
 pub const DEFAULT_DWARF_VERSION: usize = 5;
 pub const DEFAULT_GOT_LAYOUT_FILE: &str = "online_change_got.json";
+pub const DEFAULT_DATA_LAYOUT_FILE: &str = "online_change_layout.json";
+pub const DEFAULT_MIGRATION_FILE: &str = "online_change_migration.json";
 
 #[derive(Default, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Target {
@@ -154,6 +167,8 @@ pub enum ErrorFormat {
     #[default]
     Rich,
     Clang,
+    Json,
+    Sarif,
     None,
 }
 
@@ -162,7 +177,7 @@ impl From<ErrorFormat> for IndexErrorFormat {
         match value {
             ErrorFormat::Rich => IndexErrorFormat::Rich,
             ErrorFormat::Clang => IndexErrorFormat::Clang,
-            ErrorFormat::None => IndexErrorFormat::Null,
+            ErrorFormat::Json | ErrorFormat::Sarif | ErrorFormat::None => IndexErrorFormat::Null,
         }
     }
 }
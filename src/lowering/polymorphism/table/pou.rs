@@ -155,6 +155,7 @@ impl VirtualTableGenerator {
                 access: AccessModifier::Protected,
                 constant: false,
                 retain: false,
+                persistent: false,
                 location: location.clone(),
             },
         );
@@ -296,6 +296,7 @@ pub fn lower_to_pou(
                     access: AccessModifier::Public,
                     constant: false,
                     retain: false,
+                    persistent: false,
                     variables: vec![Variable {
                         name: name.to_string(),
                         data_type_declaration: datatype.clone(),
@@ -319,6 +320,7 @@ pub fn lower_to_pou(
                     access: AccessModifier::Public,
                     constant: false,
                     retain: false,
+                    persistent: false,
                     variables: vec![Variable {
                         name: name.to_string(),
                         data_type_declaration: datatype,
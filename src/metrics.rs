@@ -0,0 +1,459 @@
+//! Code metrics of every POU, e.g. to track the complexity of a library between releases.
+//!
+//! Most metrics describe the code as it was written, so they are taken from the parsed AST before
+//! any lowering. The fan-in and fan-out are added from the [call graph](crate::call_graph) of the
+//! annotated project and the instance size from the data layout of the generated module.
+
+use std::fmt::Write;
+
+use clap::clap_derive::ArgEnum;
+use plc_ast::{
+    ast::{AstNode, AstStatement, CompilationUnit, Implementation, LinkageType, PouType, VariableBlockType},
+    control_statements::{AstControlStatement, ReturnStatement},
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::source_location::SourceLocation;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+
+use crate::call_graph::{CallGraph, CallKind};
+
+/// The metrics of a single POU
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PouMetrics {
+    pub name: String,
+    /// the kind of the POU, e.g. `Program`, `FunctionBlock` or `Method`
+    pub kind: String,
+    pub file: Option<String>,
+    pub line: usize,
+    /// the lines of the body holding statements, blank lines and comments are not counted
+    pub lines_of_code: usize,
+    /// the statements of the body, including the statements nested in control statements
+    pub statements: usize,
+    /// the number of independent paths through the body, one plus the number of decisions
+    pub cyclomatic_complexity: usize,
+    /// the deepest nesting of control statements
+    pub nesting_depth: usize,
+    /// the number of `VAR_INPUT`, `VAR_OUTPUT` and `VAR_IN_OUT` variables
+    pub parameters: usize,
+    /// the number of POUs calling this POU
+    pub fan_in: usize,
+    /// the number of POUs called by this POU
+    pub fan_out: usize,
+    /// the size of the instance data in bytes, `None` for POUs without an instance
+    pub instance_size: Option<u64>,
+    /// whether the POU has instance data, i.e. it is a program, function block or class
+    #[serde(skip)]
+    pub has_instance: bool,
+}
+
+/// Collects the metrics of all POUs implemented in the given units. The fan-in, fan-out and instance
+/// size are not known yet, see [`add_call_graph`].
+pub fn collect_metrics<'a>(units: impl IntoIterator<Item = &'a CompilationUnit>) -> Vec<PouMetrics> {
+    units
+        .into_iter()
+        .flat_map(|unit| {
+            unit.implementations
+                .iter()
+                .filter(|it| it.linkage == LinkageType::Internal && !it.location.is_internal())
+                .map(|implementation| {
+                    let pou = unit.pous.iter().find(|it| it.name.eq_ignore_ascii_case(&implementation.name));
+                    let parameters = pou
+                        .iter()
+                        .flat_map(|it| &it.variable_blocks)
+                        .filter(|it| {
+                            matches!(
+                                it.kind,
+                                VariableBlockType::Input(_)
+                                    | VariableBlockType::Output
+                                    | VariableBlockType::InOut
+                            )
+                        })
+                        .flat_map(|it| &it.variables)
+                        .filter(|it| !it.location.is_internal())
+                        .count();
+                    collect_pou_metrics(implementation, parameters)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn collect_pou_metrics(implementation: &Implementation, parameters: usize) -> PouMetrics {
+    let mut body = BodyMetrics::default();
+    body.visit_statements(&implementation.statements, 0);
+
+    PouMetrics {
+        name: implementation.name.clone(),
+        kind: implementation.pou_type.to_string(),
+        file: implementation.name_location.get_file_name().map(str::to_string),
+        line: implementation.name_location.get_line_plus_one(),
+        lines_of_code: body.lines.len(),
+        statements: body.statements,
+        cyclomatic_complexity: body.decisions + 1,
+        nesting_depth: body.nesting_depth,
+        parameters,
+        fan_in: 0,
+        fan_out: 0,
+        instance_size: None,
+        has_instance: matches!(
+            implementation.pou_type,
+            PouType::Program | PouType::FunctionBlock | PouType::Class
+        ),
+    }
+}
+
+/// Adds the fan-in and fan-out of every POU from the given call graph. References to a POU which do
+/// not call it (e.g. `ADR(foo)`) are not counted.
+pub fn add_call_graph(metrics: &mut [PouMetrics], graph: &CallGraph) {
+    let mut callers: FxHashMap<String, FxHashSet<String>> = FxHashMap::default();
+    let mut callees: FxHashMap<String, FxHashSet<String>> = FxHashMap::default();
+    for edge in graph.edges.iter().filter(|it| it.kind != CallKind::Reference) {
+        let (caller, callee) = (edge.caller.to_lowercase(), edge.callee.to_lowercase());
+        callers.entry(callee.clone()).or_default().insert(caller.clone());
+        callees.entry(caller).or_default().insert(callee);
+    }
+
+    for metric in metrics {
+        let name = metric.name.to_lowercase();
+        metric.fan_in = callers.get(&name).map_or(0, FxHashSet::len);
+        metric.fan_out = callees.get(&name).map_or(0, FxHashSet::len);
+    }
+}
+
+/// The metrics collected while walking the statements of a body
+#[derive(Default)]
+struct BodyMetrics {
+    lines: FxHashSet<usize>,
+    statements: usize,
+    decisions: usize,
+    nesting_depth: usize,
+}
+
+impl BodyMetrics {
+    fn visit_statements(&mut self, statements: &[AstNode], depth: usize) {
+        for statement in statements.iter().filter(|it| !it.is_empty_statement()) {
+            self.statements += 1;
+            self.visit_statement(statement, depth);
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &AstNode, depth: usize) {
+        let AstStatement::ControlStatement(control) = statement.get_stmt() else {
+            // conditional returns of CFC bodies
+            if let AstStatement::ReturnStatement(ReturnStatement { condition: Some(_) }) =
+                statement.get_stmt()
+            {
+                self.decisions += 1;
+            }
+            self.add_lines(&statement.location);
+            return;
+        };
+
+        // only the lines of a control statement's keywords and conditions are counted, the lines
+        // in between belong to its bodies
+        self.nesting_depth = self.nesting_depth.max(depth + 1);
+        self.add_line(&statement.location);
+        match control {
+            AstControlStatement::If(stmt) => {
+                for block in &stmt.blocks {
+                    self.decisions += 1;
+                    self.add_lines(&block.condition.location);
+                    self.visit_statements(&block.body, depth + 1);
+                }
+                self.visit_statements(&stmt.else_block, depth + 1);
+                self.add_line(&stmt.end_location);
+            }
+            AstControlStatement::ForLoop(stmt) => {
+                self.decisions += 1;
+                for node in stmt.get_conditionals() {
+                    self.add_lines(&node.location);
+                }
+                self.visit_statements(&stmt.body, depth + 1);
+                self.add_line(&stmt.end_location);
+            }
+            AstControlStatement::WhileLoop(stmt) | AstControlStatement::RepeatLoop(stmt) => {
+                self.decisions += 1;
+                self.add_lines(&stmt.condition.location);
+                self.visit_statements(&stmt.body, depth + 1);
+                self.add_line(&stmt.end_location);
+            }
+            AstControlStatement::Case(stmt) => {
+                self.add_lines(&stmt.selector.location);
+                for block in &stmt.case_blocks {
+                    self.decisions += 1;
+                    self.add_lines(&block.condition.location);
+                    self.visit_statements(&block.body, depth + 1);
+                }
+                self.visit_statements(&stmt.else_block, depth + 1);
+                self.add_line(&stmt.end_location);
+            }
+        }
+    }
+
+    /// Adds the first line of the given location
+    fn add_line(&mut self, location: &SourceLocation) {
+        if !location.is_internal() && !location.is_undefined() {
+            self.lines.insert(location.get_line());
+        }
+    }
+
+    /// Adds all lines of the given location
+    fn add_lines(&mut self, location: &SourceLocation) {
+        if !location.is_internal() && !location.is_undefined() {
+            self.lines.extend(location.get_line()..=location.get_line_end());
+        }
+    }
+}
+
+pub fn serialize_metrics(metrics: &[PouMetrics], format: MetricsFormat) -> Result<String, Diagnostic> {
+    match format {
+        MetricsFormat::Text => Ok(to_table(metrics)),
+        MetricsFormat::Json => serde_json::to_string_pretty(metrics).map_err(|it| {
+            Diagnostic::new(it.to_string()).with_internal_error(it.into()).with_error_code("E002")
+        }),
+        MetricsFormat::Csv => Ok(to_csv(metrics)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum MetricsFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+/// Returns the columns of a POU's row, the instance size is empty for POUs without an instance
+fn to_row(metric: &PouMetrics) -> [String; 10] {
+    [
+        metric.name.clone(),
+        metric.kind.clone(),
+        metric.lines_of_code.to_string(),
+        metric.statements.to_string(),
+        metric.cyclomatic_complexity.to_string(),
+        metric.nesting_depth.to_string(),
+        metric.parameters.to_string(),
+        metric.fan_in.to_string(),
+        metric.fan_out.to_string(),
+        metric.instance_size.map(|it| it.to_string()).unwrap_or_default(),
+    ]
+}
+
+fn to_table(metrics: &[PouMetrics]) -> String {
+    let rows = metrics.iter().map(to_row).collect::<Vec<_>>();
+
+    let header = [
+        "POU",
+        "Kind",
+        "LOC",
+        "Statements",
+        "Complexity",
+        "Nesting",
+        "Parameters",
+        "Fan-in",
+        "Fan-out",
+        "Instance (bytes)",
+    ];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.len());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(header.map(str::to_string)).chain(rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(column, width)| format!("{column:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        let _ = writeln!(table, "{}", line.trim_end());
+    }
+    table
+}
+
+fn to_csv(metrics: &[PouMetrics]) -> String {
+    let mut csv = String::from(
+        "name,kind,file,line,lines_of_code,statements,cyclomatic_complexity,nesting_depth,parameters,fan_in,fan_out,instance_size\n",
+    );
+    for metric in metrics {
+        let [name, kind, columns @ ..] = to_row(metric);
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{}",
+            escape_csv(&name),
+            kind,
+            escape_csv(metric.file.as_deref().unwrap_or_default()),
+            metric.line,
+            columns.join(",")
+        );
+    }
+    csv
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_ast::provider::IdProvider;
+
+    use super::{add_call_graph, collect_metrics, serialize_metrics, MetricsFormat, PouMetrics};
+    use crate::{
+        call_graph::collect_call_graph,
+        test_utils::tests::{annotate_with_ids, index_with_ids, parse},
+    };
+
+    fn get<'a>(metrics: &'a [PouMetrics], name: &str) -> &'a PouMetrics {
+        metrics.iter().find(|it| it.name == name).unwrap()
+    }
+
+    #[test]
+    fn body_metrics_are_collected() {
+        let (unit, _) = parse(
+            "
+            FUNCTION clamp : INT
+            VAR_INPUT
+                value, low, high : INT;
+            END_VAR
+            VAR_OUTPUT
+                clamped : BOOL;
+            END_VAR
+                // comments and blank lines are not counted
+
+                clamp := value;
+                IF value < low THEN
+                    clamp := low;
+                ELSIF value > high THEN
+                    FOR value := 0 TO 10 DO
+                        WHILE value > 3 DO
+                            value := value - 1;
+                        END_WHILE
+                    END_FOR
+                END_IF
+                CASE value OF
+                    1: clamped := TRUE;
+                    2, 3: clamped := FALSE;
+                ELSE
+                    ;
+                END_CASE
+            END_FUNCTION
+
+            PROGRAM mainProg
+            END_PROGRAM
+            ",
+        );
+
+        let metrics = collect_metrics([&unit]);
+        let clamp = get(&metrics, "clamp");
+        assert_eq!(clamp.kind, "Function");
+        assert_eq!(clamp.parameters, 4);
+        // clamp, IF, clamp, FOR, WHILE, value, CASE, clamped, clamped
+        assert_eq!(clamp.statements, 9);
+        // the `ELSE` of the CASE and its empty statement hold no code
+        assert_eq!(clamp.lines_of_code, 14);
+        // IF, ELSIF, FOR, WHILE and two case labels
+        assert_eq!(clamp.cyclomatic_complexity, 7);
+        assert_eq!(clamp.nesting_depth, 3);
+        assert!(!clamp.has_instance);
+
+        let program = get(&metrics, "mainProg");
+        assert_eq!(
+            (program.statements, program.lines_of_code, program.cyclomatic_complexity, program.nesting_depth),
+            (0, 0, 1, 0)
+        );
+        assert!(program.has_instance);
+    }
+
+    #[test]
+    fn fan_in_and_fan_out_are_taken_from_the_call_graph() {
+        let src = "
+            FUNCTION helper : INT
+            END_FUNCTION
+
+            FUNCTION_BLOCK fb
+            METHOD m
+                helper();
+            END_METHOD
+                helper();
+                helper();
+            END_FUNCTION_BLOCK
+
+            PROGRAM mainProg
+            VAR inst : fb; END_VAR
+                inst();
+                inst.m();
+                helper();
+            END_PROGRAM
+            ";
+        let (unit, _) = parse(src);
+        let mut metrics = collect_metrics([&unit]);
+
+        let id_provider = IdProvider::default();
+        let (unit, mut index) = index_with_ids(src, id_provider.clone());
+        let annotations = annotate_with_ids(&unit, &mut index, id_provider);
+        add_call_graph(&mut metrics, &collect_call_graph([&unit], &index, &annotations));
+
+        let fan = |name| {
+            let metric = get(&metrics, name);
+            (metric.fan_in, metric.fan_out)
+        };
+        assert_eq!(fan("helper"), (3, 0));
+        assert_eq!(fan("fb"), (1, 1));
+        assert_eq!(fan("fb.m"), (1, 1));
+        assert_eq!(fan("mainProg"), (0, 3));
+    }
+
+    #[test]
+    fn metrics_are_serialized_as_text_json_and_csv() {
+        let (unit, _) = parse(
+            "
+            FUNCTION_BLOCK fb
+            VAR_INPUT x : INT; END_VAR
+                x := 1;
+            END_FUNCTION_BLOCK
+            ",
+        );
+        let mut metrics = collect_metrics([&unit]);
+        metrics[0].instance_size = Some(2);
+
+        let text = serialize_metrics(&metrics, MetricsFormat::Text).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next().unwrap().split("  ").filter(|it| !it.is_empty()).map(str::trim).collect::<Vec<_>>(),
+            [
+                "POU",
+                "Kind",
+                "LOC",
+                "Statements",
+                "Complexity",
+                "Nesting",
+                "Parameters",
+                "Fan-in",
+                "Fan-out",
+                "Instance (bytes)"
+            ]
+        );
+        assert_eq!(
+            lines.next().unwrap().split_whitespace().collect::<Vec<_>>(),
+            ["fb", "FunctionBlock", "1", "1", "1", "0", "1", "0", "0", "2"]
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&serialize_metrics(&metrics, MetricsFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[0]["name"], "fb");
+        assert_eq!(json[0]["instance_size"], 2);
+        assert!(json[0].get("has_instance").is_none());
+
+        let csv = serialize_metrics(&metrics, MetricsFormat::Csv).unwrap();
+        assert_eq!(csv.lines().nth(1).unwrap(), "fb,FunctionBlock,,2,1,1,1,0,1,0,0,2");
+    }
+}
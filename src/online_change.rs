@@ -0,0 +1,729 @@
+//! Migration of the instance data for online changes.
+//!
+//! With `--online-change` the build writes a data layout file next to the GOT layout, describing the
+//! type, symbol and section of every global variable and program instance together with the layout
+//! of all types they contain. `plc online-change diff OLD NEW` compares the data layout files of two
+//! builds variable by variable, down to the elementary values, and classifies every variable as
+//! - `Unchanged`: same type, same symbol and offset
+//! - `Moved`: same type, stored at another offset or in another symbol
+//! - `Converted`: the type changed, but the old value can be converted into the new type without
+//!   losing information (e.g. `INT` to `DINT`, `REAL` to `LREAL` or a longer `STRING`)
+//! - `Removed` / `Added`: the variable only exists in the old / the new build
+//!
+//! From the classification a migration descriptor is derived, a list of copies a runtime applies to
+//! move the instance data of the running binary into the data of the binary replacing it, after the
+//! latter was initialized. Added variables keep their initial value. Pointers and references are not
+//! migrated since they refer to the data of the old binary, they keep the value the initialization of
+//! the new binary assigns. Any other change of a type cannot be migrated and is refused.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use plc_ast::ast::LinkageType;
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_util::convention::retain_variable_name;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    datalayout::DataLayout,
+    index::{Index, PouIndexEntry},
+    typesystem::{DataTypeInformation, StringEncoding},
+    Target,
+};
+
+/// The layout of all instance data of a build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataLayoutFile {
+    /// the target triple the layout was computed for
+    pub target: String,
+    pub instances: Vec<InstanceDescription>,
+    /// the types of the instances and of everything they contain, by their name
+    pub types: BTreeMap<String, TypeDescription>,
+}
+
+/// A global variable or program instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceDescription {
+    /// the qualified name the variables of the instance are matched by
+    pub name: String,
+    /// the symbol holding the instance
+    pub symbol: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub section: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum TypeDescription {
+    Bool,
+    Signed {
+        size: u32,
+    },
+    Unsigned {
+        size: u32,
+    },
+    Float {
+        size: u32,
+    },
+    Enum {
+        size: u32,
+    },
+    /// a string of `length` characters including the terminator
+    String {
+        character_size: u32,
+        length: u32,
+    },
+    Pointer {
+        size: u32,
+    },
+    Struct {
+        size: u32,
+        members: Vec<MemberDescription>,
+    },
+    /// an array with the inclusive bounds of each dimension, the last index changes fastest
+    Array {
+        element_type: String,
+        element_size: u32,
+        dimensions: Vec<(i64, i64)>,
+    },
+}
+
+impl TypeDescription {
+    /// Returns the size of a value of this type in bytes
+    pub fn get_size(&self) -> u32 {
+        match self {
+            TypeDescription::Bool => 1,
+            TypeDescription::Signed { size }
+            | TypeDescription::Unsigned { size }
+            | TypeDescription::Float { size }
+            | TypeDescription::Enum { size }
+            | TypeDescription::Pointer { size }
+            | TypeDescription::Struct { size, .. } => *size,
+            TypeDescription::String { character_size, length } => character_size * length,
+            TypeDescription::Array { element_size, dimensions, .. } => {
+                element_size
+                    * dimensions.iter().map(|(start, end)| (end - start + 1).max(0) as u32).product::<u32>()
+            }
+        }
+    }
+
+    /// Returns whether a value of this elementary type can be converted into the given type without
+    /// losing information
+    fn can_convert_to(&self, other: &TypeDescription) -> bool {
+        match (self, other) {
+            (TypeDescription::Signed { size: from }, TypeDescription::Signed { size: to })
+            | (TypeDescription::Unsigned { size: from }, TypeDescription::Unsigned { size: to })
+            | (TypeDescription::Float { size: from }, TypeDescription::Float { size: to }) => to >= from,
+            (TypeDescription::Unsigned { size: from }, TypeDescription::Signed { size: to }) => to > from,
+            (TypeDescription::Bool, TypeDescription::Signed { .. } | TypeDescription::Unsigned { .. }) => {
+                true
+            }
+            // the mantissa of a REAL holds 24 bits, the one of an LREAL 53 bits
+            (
+                TypeDescription::Signed { size: from } | TypeDescription::Unsigned { size: from },
+                TypeDescription::Float { size: to },
+            ) => to > from,
+            (
+                TypeDescription::String { character_size: from_size, length: from_length },
+                TypeDescription::String { character_size: to_size, length: to_length },
+            ) => from_size == to_size && to_length >= from_length,
+            _ => false,
+        }
+    }
+}
+
+/// A stored member of a struct, function block, program or class
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberDescription {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub offset: u32,
+}
+
+/// Collects the data layout file of the instances on the given target
+pub fn collect_data_layout(index: &Index, target: &Target) -> Result<DataLayoutFile, Diagnostic> {
+    let layout = crate::codegen::get_target_data_layout(target)?;
+    let triple = target.get_target_triple();
+    collect_data_layout_with(index, &layout, &triple.as_str().to_string_lossy())
+}
+
+fn collect_data_layout_with(
+    index: &Index,
+    layout: &DataLayout,
+    target: &str,
+) -> Result<DataLayoutFile, Diagnostic> {
+    // the retain variables of programs are moved into global variables, they keep the name of the program variable
+    let mut names = FxHashMap::default();
+    let mut instances = vec![];
+    for pou in index.get_pous().values() {
+        let PouIndexEntry::Program { name, instance_variable, .. } = pou else { continue };
+        for member in index.get_container_members(name) {
+            names.insert(
+                retain_variable_name(name.as_str(), member.get_name()).to_lowercase(),
+                format!("{name}.{}", member.get_name()),
+            );
+        }
+        instances.push((name.to_string(), instance_variable.as_ref()));
+    }
+    instances.splice(
+        0..0,
+        index.get_globals().values().map(|it| {
+            let name = names
+                .get(&it.get_name().to_lowercase())
+                .cloned()
+                .unwrap_or_else(|| it.get_name().to_string());
+            (name, it)
+        }),
+    );
+
+    let mut collector = TypeCollector { index, layout, types: BTreeMap::new() };
+    let mut descriptions = vec![];
+    for (name, variable) in instances {
+        if variable.is_constant() || variable.get_linkage() == LinkageType::External {
+            continue;
+        }
+        let Some(type_name) = collector.describe(variable.get_type_name())? else { continue };
+        descriptions.push(InstanceDescription {
+            name,
+            symbol: variable.get_name().to_string(),
+            type_name,
+            section: variable.get_retain_section(index).map(|it| it.get_name().to_string()),
+        });
+    }
+
+    Ok(DataLayoutFile { target: target.to_string(), instances: descriptions, types: collector.types })
+}
+
+struct TypeCollector<'a> {
+    index: &'a Index,
+    layout: &'a DataLayout,
+    types: BTreeMap<String, TypeDescription>,
+}
+
+impl TypeCollector<'_> {
+    /// Adds the description of the given type and the types it contains, returning the name it is
+    /// described by. Types without a value, e.g. generics, are not described.
+    fn describe(&mut self, type_name: &str) -> Result<Option<String>, Diagnostic> {
+        let index = self.index;
+        let Some(data_type) = index.find_effective_type_by_name(type_name) else { return Ok(None) };
+        let information = data_type.get_type_information();
+        if let DataTypeInformation::SubRange { referenced_type, .. } = information {
+            return self.describe(referenced_type);
+        }
+
+        // structs are named by their declaration, other types by their definition, e.g. `ARRAY[0..1] OF INT`
+        let name = match information {
+            DataTypeInformation::Struct { .. } => data_type.get_name().to_string(),
+            _ => crate::doc::get_type_text(index, data_type.get_name()),
+        };
+        if self.types.contains_key(&name) {
+            return Ok(Some(name));
+        }
+
+        let size = self.layout.get_size(information, index).map_err(to_diagnostic)?.value();
+        let description = match information {
+            DataTypeInformation::Integer { semantic_size: Some(1), .. } => TypeDescription::Bool,
+            DataTypeInformation::Integer { signed: true, .. } => TypeDescription::Signed { size },
+            DataTypeInformation::Integer { signed: false, .. } => TypeDescription::Unsigned { size },
+            DataTypeInformation::Float { .. } => TypeDescription::Float { size },
+            DataTypeInformation::Enum { .. } => TypeDescription::Enum { size },
+            DataTypeInformation::String { encoding, .. } => {
+                let character_size = match encoding {
+                    StringEncoding::Utf8 => 1,
+                    StringEncoding::Utf16 => 2,
+                };
+                TypeDescription::String { character_size, length: size / character_size }
+            }
+            DataTypeInformation::Pointer { .. } | DataTypeInformation::Interface { .. } => {
+                TypeDescription::Pointer { size }
+            }
+            DataTypeInformation::Struct { members, .. } => {
+                let struct_layout = self.layout.get_struct_layout(members, index).map_err(to_diagnostic)?;
+                let mut descriptions = vec![];
+                for (member, offset) in struct_layout.offsets {
+                    let Some(type_name) = self.describe(member.get_type_name())? else { continue };
+                    descriptions.push(MemberDescription {
+                        name: member.get_name().to_string(),
+                        type_name,
+                        offset: offset.value(),
+                    });
+                }
+                TypeDescription::Struct { size, members: descriptions }
+            }
+            DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+                let Some(element_type) = self.describe(inner_type_name)? else { return Ok(None) };
+                let dimensions = dimensions
+                    .iter()
+                    .map(|it| it.get_range_inclusive(index).map(|it| (*it.start(), *it.end())))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|it| Diagnostic::new(it).with_error_code("E002"))?;
+                let element_size = self.types[&element_type].get_size();
+                TypeDescription::Array { element_type, element_size, dimensions }
+            }
+            DataTypeInformation::SubRange { .. }
+            | DataTypeInformation::Alias { .. }
+            | DataTypeInformation::Generic { .. }
+            | DataTypeInformation::Void => return Ok(None),
+        };
+        self.types.insert(name.clone(), description);
+        Ok(Some(name))
+    }
+}
+
+fn to_diagnostic(error: anyhow::Error) -> Diagnostic {
+    Diagnostic::new(error.to_string()).with_error_code("E002")
+}
+
+/// The position of a value in the instance data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DataLocation {
+    pub symbol: String,
+    /// the offset from the start of the symbol in bytes
+    pub offset: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum ChangeKind {
+    Unchanged,
+    Moved,
+    Converted,
+    Removed,
+    Added,
+}
+
+/// The change of an elementary variable, an element of an array or a member of a struct
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VariableChange {
+    pub name: String,
+    pub kind: ChangeKind,
+    pub old_type: Option<String>,
+    pub new_type: Option<String>,
+}
+
+/// A copy of a value from the old instance data into the new one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationStep {
+    pub from: DataLocation,
+    pub to: DataLocation,
+    /// the number of bytes read from the old instance data
+    pub size: u32,
+    /// the types the value is converted between, `None` if it is copied as it is
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<(TypeDescription, TypeDescription)>,
+}
+
+/// The copies a runtime applies to move the instance data of the old binary into the new one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationDescriptor {
+    pub target: String,
+    pub steps: Vec<MigrationStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLayoutDiff {
+    pub changes: Vec<VariableChange>,
+    pub migration: MigrationDescriptor,
+}
+
+/// An elementary value of an instance
+struct Leaf<'a> {
+    name: String,
+    type_name: &'a str,
+    description: &'a TypeDescription,
+    location: DataLocation,
+}
+
+/// Compares the data layouts of two builds and derives the migration of the instance data, fails if
+/// the instance data cannot be migrated
+pub fn diff_data_layouts(old: &DataLayoutFile, new: &DataLayoutFile) -> Result<DataLayoutDiff, Diagnostic> {
+    if !old.target.eq_ignore_ascii_case(&new.target) {
+        return Err(Diagnostic::new(format!(
+            "Cannot migrate the instance data from target {} to target {}",
+            old.target, new.target
+        ))
+        .with_error_code("E171"));
+    }
+
+    let old_leaves = get_leaves(old);
+    let mut new_leaves =
+        get_leaves(new).into_iter().map(|it| (it.name.to_lowercase(), it)).collect::<FxHashMap<_, _>>();
+
+    let mut changes = vec![];
+    let mut steps: Vec<MigrationStep> = vec![];
+    let mut errors = vec![];
+    for old_leaf in old_leaves {
+        let Some(new_leaf) = new_leaves.remove(&old_leaf.name.to_lowercase()) else {
+            changes.push(VariableChange {
+                name: old_leaf.name,
+                kind: ChangeKind::Removed,
+                old_type: Some(old_leaf.type_name.to_string()),
+                new_type: None,
+            });
+            continue;
+        };
+
+        let (kind, conversion) = if old_leaf.description == new_leaf.description {
+            let kind = if !old_leaf.type_name.eq_ignore_ascii_case(new_leaf.type_name) {
+                ChangeKind::Converted
+            } else if old_leaf.location != new_leaf.location {
+                ChangeKind::Moved
+            } else {
+                ChangeKind::Unchanged
+            };
+            (kind, None)
+        } else if old_leaf.description.can_convert_to(new_leaf.description) {
+            (ChangeKind::Converted, Some((old_leaf.description.clone(), new_leaf.description.clone())))
+        } else {
+            errors.push(
+                Diagnostic::new(format!(
+                "Cannot migrate '{}' from {} to {}, the value cannot be converted without losing information",
+                old_leaf.name, old_leaf.type_name, new_leaf.type_name
+            ))
+                .with_error_code("E171"),
+            );
+            continue;
+        };
+
+        add_step(
+            &mut steps,
+            MigrationStep {
+                from: old_leaf.location,
+                to: new_leaf.location,
+                size: old_leaf.description.get_size(),
+                conversion,
+            },
+        );
+        changes.push(VariableChange {
+            name: new_leaf.name,
+            kind,
+            old_type: Some(old_leaf.type_name.to_string()),
+            new_type: Some(new_leaf.type_name.to_string()),
+        });
+    }
+
+    match errors.len() {
+        0 => {}
+        1 => return Err(errors.remove(0)),
+        count => {
+            return Err(Diagnostic::new(format!(
+                "Cannot migrate the instance data, {count} variables changed their type incompatibly"
+            ))
+            .with_error_code("E171")
+            .with_sub_diagnostics(errors))
+        }
+    }
+
+    // the added variables are reported in the order they are declared
+    let mut added = new_leaves.into_values().collect::<Vec<_>>();
+    added.sort_by(|a, b| {
+        (&a.location.symbol, a.location.offset).cmp(&(&b.location.symbol, b.location.offset))
+    });
+    changes.extend(added.into_iter().map(|it| VariableChange {
+        name: it.name,
+        kind: ChangeKind::Added,
+        old_type: None,
+        new_type: Some(it.type_name.to_string()),
+    }));
+
+    Ok(DataLayoutDiff { changes, migration: MigrationDescriptor { target: new.target.clone(), steps } })
+}
+
+/// Adds a step to the migration, merging copies of adjacent values into a single copy
+fn add_step(steps: &mut Vec<MigrationStep>, step: MigrationStep) {
+    if let Some(last) = steps.last_mut() {
+        if last.conversion.is_none()
+            && step.conversion.is_none()
+            && last.from.symbol == step.from.symbol
+            && last.to.symbol == step.to.symbol
+            && last.from.offset + last.size == step.from.offset
+            && last.to.offset + last.size == step.to.offset
+        {
+            last.size += step.size;
+            return;
+        }
+    }
+    steps.push(step);
+}
+
+/// Returns the elementary values of all instances of the given layout
+fn get_leaves(file: &DataLayoutFile) -> Vec<Leaf<'_>> {
+    let mut leaves = vec![];
+    for instance in &file.instances {
+        let location = DataLocation { symbol: instance.symbol.clone(), offset: 0 };
+        collect_leaves(file, &instance.type_name, instance.name.clone(), location, &mut leaves);
+    }
+    leaves
+}
+
+fn collect_leaves<'a>(
+    file: &'a DataLayoutFile,
+    type_name: &'a str,
+    name: String,
+    location: DataLocation,
+    leaves: &mut Vec<Leaf<'a>>,
+) {
+    let Some(description) = file.types.get(type_name) else { return };
+    match description {
+        // pointers refer to the data of the old binary
+        TypeDescription::Pointer { .. } => {}
+        TypeDescription::Struct { members, .. } => {
+            for member in members {
+                // the members of the base are matched like the ones of the derived function block
+                let is_base = member.name.eq_ignore_ascii_case(&format!("__{}", member.type_name));
+                let name = if is_base { name.clone() } else { format!("{name}.{}", member.name) };
+                let location =
+                    DataLocation { symbol: location.symbol.clone(), offset: location.offset + member.offset };
+                collect_leaves(file, &member.type_name, name, location, leaves);
+            }
+        }
+        TypeDescription::Array { element_type, element_size, dimensions } => {
+            // the elements are stored row by row, i.e. the last index changes fastest
+            let mut elements = vec![vec![]];
+            for (start, end) in dimensions {
+                elements = elements
+                    .into_iter()
+                    .flat_map(|indices: Vec<i64>| {
+                        (*start..=*end).map(move |it| {
+                            let mut indices = indices.clone();
+                            indices.push(it);
+                            indices
+                        })
+                    })
+                    .collect();
+            }
+            for (position, indices) in elements.into_iter().enumerate() {
+                let indices = indices.iter().map(ToString::to_string).collect::<Vec<_>>();
+                let location = DataLocation {
+                    symbol: location.symbol.clone(),
+                    offset: location.offset + position as u32 * element_size,
+                };
+                collect_leaves(
+                    file,
+                    element_type,
+                    format!("{name}[{}]", indices.join(",")),
+                    location,
+                    leaves,
+                );
+            }
+        }
+        _ => leaves.push(Leaf { name, type_name, description, location }),
+    }
+}
+
+/// Returns the changes as a text table, unchanged variables are only counted
+pub fn changes_to_text(changes: &[VariableChange]) -> String {
+    let mut text = String::new();
+    for change in changes.iter().filter(|it| it.kind != ChangeKind::Unchanged) {
+        let types = match (&change.old_type, &change.new_type) {
+            (Some(old), Some(new)) if change.kind == ChangeKind::Converted => format!("{old} -> {new}"),
+            (Some(it), _) | (None, Some(it)) => it.to_string(),
+            (None, None) => String::new(),
+        };
+        let _ = writeln!(text, "{:<10} {} : {types}", format!("{:?}", change.kind), change.name);
+    }
+    let unchanged = changes.iter().filter(|it| it.kind == ChangeKind::Unchanged).count();
+    let _ = writeln!(text, "{unchanged} variables unchanged");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changes_to_text, collect_data_layout_with, diff_data_layouts, ChangeKind, DataLayoutFile};
+    use crate::test_utils::tests::index;
+
+    fn layout(src: &str) -> DataLayoutFile {
+        let (_, index) = index(src);
+        collect_data_layout_with(&index, index.get_type_layout(), "x86_64-pc-linux-gnu").unwrap()
+    }
+
+    fn kind_of(diff: &super::DataLayoutDiff, name: &str) -> ChangeKind {
+        diff.changes.iter().find(|it| it.name == name).unwrap_or_else(|| panic!("{name} not found")).kind
+    }
+
+    #[test]
+    fn variables_are_classified_by_their_change() {
+        let old = layout(
+            "
+            PROGRAM main
+            VAR
+                a : INT;
+                b : INT;
+                c : INT;
+                d : BOOL;
+                values : ARRAY[0..1] OF DINT;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+        let new = layout(
+            "
+            PROGRAM main
+            VAR
+                a : INT;
+                b : INT;
+                c : DINT;
+                e : LREAL;
+                values : ARRAY[0..2] OF DINT;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+
+        let diff = diff_data_layouts(&old, &new).unwrap();
+        assert_eq!(kind_of(&diff, "main.a"), ChangeKind::Unchanged);
+        assert_eq!(kind_of(&diff, "main.c"), ChangeKind::Converted);
+        assert_eq!(kind_of(&diff, "main.d"), ChangeKind::Removed);
+        assert_eq!(kind_of(&diff, "main.e"), ChangeKind::Added);
+        assert_eq!(kind_of(&diff, "main.values[1]"), ChangeKind::Moved);
+        assert_eq!(kind_of(&diff, "main.values[2]"), ChangeKind::Added);
+
+        // adjacent values which are copied as they are, are copied at once
+        let steps =
+            diff.migration.steps.iter().map(|it| (it.from.offset, it.to.offset, it.size)).collect::<Vec<_>>();
+        assert_eq!(steps, [(0, 0, 4), (4, 4, 2), (8, 16, 8)]);
+        assert!(diff.migration.steps[1].conversion.is_some());
+
+        let text = changes_to_text(&diff.changes);
+        assert!(text.contains("Converted  main.c : INT -> DINT\n"), "{text}");
+        assert!(text.contains("Added      main.e : LREAL\n"), "{text}");
+        assert!(text.ends_with("2 variables unchanged\n"), "{text}");
+    }
+
+    #[test]
+    fn narrowing_a_type_is_refused() {
+        let old = layout("VAR_GLOBAL x : DINT; s : STRING[20]; END_VAR");
+        let new = layout("VAR_GLOBAL x : INT; s : STRING[10]; END_VAR");
+
+        let error = diff_data_layouts(&old, &new).unwrap_err();
+        assert_eq!(error.get_error_code(), "E171");
+        assert_eq!(error.get_sub_diagnostics().len(), 2);
+    }
+
+    #[test]
+    fn members_of_structs_are_matched_by_their_name() {
+        let old = layout(
+            "
+            TYPE Point : STRUCT
+                x : INT;
+                y : INT;
+            END_STRUCT
+            END_TYPE
+
+            VAR_GLOBAL
+                position : Point;
+                target : REF_TO Point;
+            END_VAR
+            ",
+        );
+        let new = layout(
+            "
+            TYPE Point : STRUCT
+                y : INT;
+                x : INT;
+                z : INT;
+            END_STRUCT
+            END_TYPE
+
+            VAR_GLOBAL
+                position : Point;
+                target : REF_TO Point;
+            END_VAR
+            ",
+        );
+
+        let diff = diff_data_layouts(&old, &new).unwrap();
+        assert_eq!(kind_of(&diff, "position.x"), ChangeKind::Moved);
+        assert_eq!(kind_of(&diff, "position.y"), ChangeKind::Moved);
+        assert_eq!(kind_of(&diff, "position.z"), ChangeKind::Added);
+        // pointers are not migrated
+        assert!(diff.changes.iter().all(|it| it.name != "target"));
+    }
+
+    #[test]
+    fn members_of_nested_instances_and_arrays_of_structs_are_migrated() {
+        let old = layout(
+            "
+            TYPE Recipe : STRUCT
+                amount : INT;
+                unit : INT;
+            END_STRUCT
+            END_TYPE
+
+            FUNCTION_BLOCK Heater
+            VAR
+                temperature : INT;
+                power : REAL;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            FUNCTION_BLOCK Mixer
+            VAR
+                heater : Heater;
+                recipes : ARRAY[0..1] OF Recipe;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            PROGRAM line
+            VAR
+                mixer : Mixer;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+        // widens `amount` and `temperature`, removes `unit` and renames `power` to `output`
+        let new = layout(
+            "
+            TYPE Recipe : STRUCT
+                amount : DINT;
+            END_STRUCT
+            END_TYPE
+
+            FUNCTION_BLOCK Heater
+            VAR
+                temperature : DINT;
+                output : REAL;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            FUNCTION_BLOCK Mixer
+            VAR
+                heater : Heater;
+                recipes : ARRAY[0..1] OF Recipe;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            PROGRAM line
+            VAR
+                mixer : Mixer;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+
+        let diff = diff_data_layouts(&old, &new).unwrap();
+        assert_eq!(kind_of(&diff, "line.mixer.heater.temperature"), ChangeKind::Converted);
+        assert_eq!(kind_of(&diff, "line.mixer.recipes[0].amount"), ChangeKind::Converted);
+        assert_eq!(kind_of(&diff, "line.mixer.recipes[1].amount"), ChangeKind::Converted);
+        assert_eq!(kind_of(&diff, "line.mixer.recipes[0].unit"), ChangeKind::Removed);
+        assert_eq!(kind_of(&diff, "line.mixer.recipes[1].unit"), ChangeKind::Removed);
+        // members are matched by their name, the value of a renamed member is not migrated
+        assert_eq!(kind_of(&diff, "line.mixer.heater.power"), ChangeKind::Removed);
+        assert_eq!(kind_of(&diff, "line.mixer.heater.output"), ChangeKind::Added);
+
+        // the widened values are converted one by one, reading the size of the old type
+        let steps = diff
+            .migration
+            .steps
+            .iter()
+            .map(|it| (it.from.offset, it.to.offset, it.size, it.conversion.is_some()))
+            .collect::<Vec<_>>();
+        assert_eq!(steps, [(0, 0, 2, true), (8, 8, 2, true), (12, 12, 2, true)]);
+
+        let text = changes_to_text(&diff.changes);
+        assert!(text.contains("Converted  line.mixer.recipes[1].amount : INT -> DINT\n"), "{text}");
+        assert!(text.contains("Removed    line.mixer.heater.power : REAL\n"), "{text}");
+        assert!(text.ends_with("0 variables unchanged\n"), "{text}");
+    }
+}
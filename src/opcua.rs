@@ -0,0 +1,609 @@
+//! OPC UA information model of a project, written as a NodeSet2 XML file which OPC UA servers import
+//! to expose the data of the running program.
+//!
+//! The model maps the declarations of the project as follows:
+//!
+//! | Declaration                        | Node                                              |
+//! |------------------------------------|---------------------------------------------------|
+//! | `STRUCT`                           | `UADataType`, a subtype of `Structure`            |
+//! | enum                               | `UADataType`, a subtype of `Enumeration`          |
+//! | `FUNCTION_BLOCK`                   | `UAObjectType`, a subtype of its base or `BaseObjectType` |
+//! | program or function block instance | `UAObject`                                        |
+//! | any other variable                 | `UAVariable`, arrays have a value rank            |
+//!
+//! Global variables and program instances are organized by the `Objects` folder, the members of an
+//! instance are its components. Pointers, interfaces and variables generated by the compiler are not
+//! part of the model.
+//!
+//! # Node ids
+//!
+//! All nodes of the project are in namespace 1 and use string ids, so they stay the same across builds
+//! as long as the declarations are not renamed: instances use their qualified path (`ns=1;s=main.motor.speed`),
+//! types their name prefixed with `type:` (`ns=1;s=type:Motor`), which cannot clash with a path.
+
+use std::fmt::Write;
+
+use plc_ast::ast::PouType;
+use plc_diagnostics::diagnostics::Diagnostic;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expression_path::ExpressionPathElement,
+    index::{Index, PouIndexEntry, VariableIndexEntry, VariableType},
+    symbols::escape_xml,
+    typesystem::{DataType, DataTypeInformation, StructSource},
+};
+
+/// The node id of the `Objects` folder
+const OBJECTS_FOLDER: &str = "i=85";
+/// The node id of `BaseObjectType`
+const BASE_OBJECT_TYPE: &str = "i=58";
+/// The node id of `BaseDataVariableType`
+const BASE_DATA_VARIABLE_TYPE: &str = "i=63";
+/// The node id of the `Mandatory` modelling rule
+const MANDATORY: &str = "i=78";
+/// The node id of `Structure`
+const STRUCTURE: &str = "i=22";
+/// The node id of `Enumeration`
+const ENUMERATION: &str = "i=29";
+
+/// The aliases of the built-in data types and reference types used by the generated nodes
+const ALIASES: &[(&str, &str)] = &[
+    ("Boolean", "i=1"),
+    ("SByte", "i=2"),
+    ("Byte", "i=3"),
+    ("Int16", "i=4"),
+    ("UInt16", "i=5"),
+    ("Int32", "i=6"),
+    ("UInt32", "i=7"),
+    ("Int64", "i=8"),
+    ("UInt64", "i=9"),
+    ("Float", "i=10"),
+    ("Double", "i=11"),
+    ("String", "i=12"),
+    ("Organizes", "i=35"),
+    ("HasModellingRule", "i=37"),
+    ("HasTypeDefinition", "i=40"),
+    ("HasSubtype", "i=45"),
+    ("HasComponent", "i=47"),
+];
+
+/// Options of the generated model, read from the file given with `--opcua-config`
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpcUaOptions {
+    /// The uri of the namespace of the project's nodes
+    pub namespace_uri: String,
+    /// Exports all variables read-only, otherwise only constants are
+    pub read_only: bool,
+}
+
+/// Generates the NodeSet2 XML of the types, global variables and program instances in the index
+pub fn generate_node_set(index: &Index, options: &OpcUaOptions) -> Result<String, Diagnostic> {
+    let mut writer = NodeSetWriter { index, options, xml: String::new() };
+    writer.write_header();
+
+    let mut data_types = index
+        .get_types()
+        .values()
+        .filter(|it| is_exported_type(it))
+        .filter(|it| {
+            matches!(
+                it.get_type_information(),
+                DataTypeInformation::Struct { source: StructSource::OriginalDeclaration, .. }
+                    | DataTypeInformation::Enum { .. }
+            )
+        })
+        .collect::<Vec<_>>();
+    data_types.sort_by_key(|it| it.get_name().to_lowercase());
+    for data_type in data_types {
+        writer.write_data_type(data_type)?;
+    }
+
+    let mut function_blocks = index
+        .get_pous()
+        .values()
+        .filter(|it| matches!(it, PouIndexEntry::FunctionBlock { .. }) && !it.get_location().is_internal())
+        .collect::<Vec<_>>();
+    function_blocks.sort_by_key(|it| it.get_name().to_lowercase());
+    for function_block in function_blocks {
+        writer.write_object_type(function_block)?;
+    }
+
+    // the qualified paths of the objects written so far, only their members are part of the model
+    let mut objects = FxHashSet::default();
+    for (path, variable) in index.find_instances() {
+        let mut names = vec![];
+        let mut is_generated = false;
+        for element in path.get_elements() {
+            match element {
+                ExpressionPathElement::Name(name) if name.starts_with("__") && names.is_empty() => break,
+                // the members of a base class are members of the derived one
+                ExpressionPathElement::Name(name) if name.starts_with("__") => is_generated = true,
+                ExpressionPathElement::Name(name) => {
+                    names.push(*name);
+                    is_generated = false;
+                }
+                // array elements are part of the value of the array
+                ExpressionPathElement::ArrayDimensions(_) | ExpressionPathElement::ArrayAccess(_) => {
+                    names.clear();
+                    break;
+                }
+            }
+        }
+        let Some((name, parents)) = names.split_last().filter(|_| !is_generated) else { continue };
+        let parent = if parents.is_empty() {
+            OBJECTS_FOLDER.to_string()
+        } else if objects.contains(&parents.join(".")) {
+            get_instance_id(&parents.join("."))
+        } else {
+            continue;
+        };
+        let qualified_name = names.join(".");
+        if writer.write_instance(&get_instance_id(&qualified_name), name, &parent, variable)? {
+            objects.insert(qualified_name);
+        }
+    }
+
+    writer.xml.push_str("</UANodeSet>\n");
+    Ok(writer.xml)
+}
+
+struct NodeSetWriter<'a> {
+    index: &'a Index,
+    options: &'a OpcUaOptions,
+    xml: String,
+}
+
+impl NodeSetWriter<'_> {
+    fn write_header(&mut self) {
+        self.xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        self.xml.push_str("<UANodeSet xmlns=\"http://opcfoundation.org/UA/2011/03/UANodeSet.xsd\">\n");
+        let _ = writeln!(
+            self.xml,
+            "  <NamespaceUris>\n    <Uri>{}</Uri>\n  </NamespaceUris>",
+            escape_xml(&self.options.namespace_uri)
+        );
+        self.xml.push_str("  <Aliases>\n");
+        for (alias, node_id) in ALIASES {
+            let _ = writeln!(self.xml, "    <Alias Alias=\"{alias}\">{node_id}</Alias>");
+        }
+        self.xml.push_str("  </Aliases>\n");
+    }
+
+    fn write_data_type(&mut self, data_type: &DataType) -> Result<(), Diagnostic> {
+        let name = data_type.get_name();
+        let mut fields = vec![];
+        let super_type = match data_type.get_type_information() {
+            DataTypeInformation::Enum { variants, .. } => {
+                for variant in variants {
+                    let value = variant
+                        .initial_value
+                        .as_ref()
+                        .and_then(|it| {
+                            self.index.get_const_expressions().get_constant_int_statement_value(it).ok()
+                        })
+                        .ok_or_else(|| {
+                            Diagnostic::new(format!(
+                                "Cannot evaluate the value of {}",
+                                variant.get_qualified_name()
+                            ))
+                            .with_error_code("E002")
+                        })?;
+                    fields.push(format!(
+                        "<Field Name=\"{}\" Value=\"{value}\" />",
+                        escape_xml(variant.get_name())
+                    ));
+                }
+                ENUMERATION
+            }
+            _ => {
+                for member in data_type.get_members() {
+                    // members of a type the model cannot express are left out, like variables of such a type
+                    let Some(Value { data_type, dimensions }) = self.get_value(member.get_type_name()) else {
+                        continue;
+                    };
+                    fields.push(format!(
+                        "<Field Name=\"{}\" DataType=\"{}\"{} />",
+                        escape_xml(member.get_name()),
+                        escape_xml(&data_type),
+                        get_array_attributes(&dimensions)
+                    ));
+                }
+                STRUCTURE
+            }
+        };
+
+        let _ = writeln!(
+            self.xml,
+            "  <UADataType NodeId=\"{}\" BrowseName=\"1:{}\">",
+            get_type_id(name),
+            escape_xml(name)
+        );
+        self.write_display_name(name);
+        self.write_references(&[("HasSubtype", super_type, false)]);
+        let _ = writeln!(self.xml, "    <Definition Name=\"1:{}\">", escape_xml(name));
+        for field in fields {
+            let _ = writeln!(self.xml, "      {field}");
+        }
+        self.xml.push_str("    </Definition>\n  </UADataType>\n");
+        Ok(())
+    }
+
+    fn write_object_type(&mut self, function_block: &PouIndexEntry) -> Result<(), Diagnostic> {
+        let name = function_block.get_name();
+        let type_id = get_type_id(name);
+        let super_type = function_block.get_super_class().map(get_type_id);
+        let _ =
+            writeln!(self.xml, "  <UAObjectType NodeId=\"{type_id}\" BrowseName=\"1:{}\">", escape_xml(name));
+        self.write_display_name(name);
+        self.write_references(&[("HasSubtype", super_type.as_deref().unwrap_or(BASE_OBJECT_TYPE), false)]);
+        self.xml.push_str("  </UAObjectType>\n");
+
+        // the members are declared by instances of the type, the inherited ones by the base type
+        for member in self.index.get_container_members(name) {
+            if is_exported_member(member) {
+                let member_id = format!("{type_id}.{}", member.get_name());
+                self.write_node(&member_id, member.get_name(), &type_id, member, Some(MANDATORY))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the node of an instance, returns true if it is an object with members
+    fn write_instance(
+        &mut self,
+        node_id: &str,
+        name: &str,
+        parent: &str,
+        variable: &VariableIndexEntry,
+    ) -> Result<bool, Diagnostic> {
+        if parent != OBJECTS_FOLDER && !is_exported_member(variable) {
+            return Ok(false);
+        }
+        self.write_node(node_id, name, parent, variable, None)
+    }
+
+    /// Writes an object or variable node, returns true if it is an object
+    fn write_node(
+        &mut self,
+        node_id: &str,
+        name: &str,
+        parent: &str,
+        variable: &VariableIndexEntry,
+        modelling_rule: Option<&str>,
+    ) -> Result<bool, Diagnostic> {
+        let parent_reference = if parent == OBJECTS_FOLDER { "Organizes" } else { "HasComponent" };
+        let type_name = variable.get_type_name();
+        let object_type = match self.index.find_effective_type_info(type_name) {
+            Some(DataTypeInformation::Struct { source: StructSource::Pou(PouType::Program), .. }) => {
+                Some(BASE_OBJECT_TYPE.to_string())
+            }
+            Some(DataTypeInformation::Struct {
+                source: StructSource::Pou(PouType::FunctionBlock),
+                name,
+                ..
+            }) => Some(get_type_id(name)),
+            _ => None,
+        };
+
+        if let Some(object_type) = object_type {
+            let _ = writeln!(
+                self.xml,
+                "  <UAObject NodeId=\"{}\" BrowseName=\"1:{}\" ParentNodeId=\"{}\">",
+                escape_xml(node_id),
+                escape_xml(name),
+                escape_xml(parent)
+            );
+            self.write_display_name(name);
+            let mut references =
+                vec![(parent_reference, parent, false), ("HasTypeDefinition", &object_type, true)];
+            references.extend(modelling_rule.map(|it| ("HasModellingRule", it, true)));
+            self.write_references(&references);
+            self.xml.push_str("  </UAObject>\n");
+            return Ok(true);
+        }
+
+        let Some(Value { data_type, dimensions }) = self.get_value(type_name) else { return Ok(false) };
+        let access_level = if variable.is_constant() || self.options.read_only { 1 } else { 3 };
+        let _ = writeln!(
+            self.xml,
+            "  <UAVariable NodeId=\"{}\" BrowseName=\"1:{}\" ParentNodeId=\"{}\" DataType=\"{}\"{} AccessLevel=\"{access_level}\">",
+            escape_xml(node_id),
+            escape_xml(name),
+            escape_xml(parent),
+            escape_xml(&data_type),
+            get_array_attributes(&dimensions)
+        );
+        self.write_display_name(name);
+        let mut references =
+            vec![(parent_reference, parent, false), ("HasTypeDefinition", BASE_DATA_VARIABLE_TYPE, true)];
+        references.extend(modelling_rule.map(|it| ("HasModellingRule", it, true)));
+        self.write_references(&references);
+        self.xml.push_str("  </UAVariable>\n");
+        Ok(false)
+    }
+
+    fn write_display_name(&mut self, name: &str) {
+        let _ = writeln!(self.xml, "    <DisplayName>{}</DisplayName>", escape_xml(name));
+    }
+
+    fn write_references(&mut self, references: &[(&str, &str, bool)]) {
+        self.xml.push_str("    <References>\n");
+        for (reference_type, target, is_forward) in references {
+            let direction = if *is_forward { "" } else { " IsForward=\"false\"" };
+            let _ = writeln!(
+                self.xml,
+                "      <Reference ReferenceType=\"{reference_type}\"{direction}>{}</Reference>",
+                escape_xml(target)
+            );
+        }
+        self.xml.push_str("    </References>\n");
+    }
+
+    /// Returns the OPC UA data type of values of the given type, `None` if the model cannot express it
+    fn get_value(&self, type_name: &str) -> Option<Value> {
+        let data_type = self.index.find_effective_type_by_name(type_name)?;
+        let information = data_type.get_type_information();
+        let builtin = |name: &str| Some(Value { data_type: name.to_string(), dimensions: vec![] });
+        match information {
+            _ if information.is_bool() => builtin("Boolean"),
+            DataTypeInformation::Integer { signed, size, .. } => builtin(match (signed, size) {
+                (true, 0..=8) => "SByte",
+                (false, 0..=8) => "Byte",
+                (true, 9..=16) => "Int16",
+                (false, 9..=16) => "UInt16",
+                (true, 17..=32) => "Int32",
+                (false, 17..=32) => "UInt32",
+                (true, _) => "Int64",
+                (false, _) => "UInt64",
+            }),
+            DataTypeInformation::Float { size, .. } if *size <= 32 => builtin("Float"),
+            DataTypeInformation::Float { .. } => builtin("Double"),
+            DataTypeInformation::String { .. } => builtin("String"),
+            DataTypeInformation::Enum { name, referenced_type, .. } => {
+                if is_exported_type(data_type) {
+                    Some(Value { data_type: get_type_id(name), dimensions: vec![] })
+                } else {
+                    // inline enums are not declared as data types, their values are integers
+                    self.get_value(referenced_type)
+                }
+            }
+            DataTypeInformation::Struct { name, source: StructSource::OriginalDeclaration, .. }
+                if is_exported_type(data_type) =>
+            {
+                Some(Value { data_type: get_type_id(name), dimensions: vec![] })
+            }
+            DataTypeInformation::Array { inner_type_name, dimensions, .. } => {
+                let mut value = self.get_value(inner_type_name)?;
+                let mut lengths =
+                    dimensions.iter().map(|it| it.get_length(self.index).ok()).collect::<Option<Vec<_>>>()?;
+                // nested arrays become additional dimensions
+                lengths.append(&mut value.dimensions);
+                value.dimensions = lengths;
+                Some(value)
+            }
+            DataTypeInformation::Alias { referenced_type, .. }
+            | DataTypeInformation::SubRange { referenced_type, .. } => self.get_value(referenced_type),
+            _ => None,
+        }
+    }
+}
+
+/// The OPC UA data type of a value and the lengths of its array dimensions
+struct Value {
+    data_type: String,
+    dimensions: Vec<u32>,
+}
+
+fn get_array_attributes(dimensions: &[u32]) -> String {
+    if dimensions.is_empty() {
+        return String::new();
+    }
+    let lengths = dimensions.iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
+    format!(" ValueRank=\"{}\" ArrayDimensions=\"{lengths}\"", dimensions.len())
+}
+
+fn get_instance_id(qualified_name: &str) -> String {
+    format!("ns=1;s={qualified_name}")
+}
+
+fn get_type_id(name: &str) -> String {
+    format!("ns=1;s=type:{name}")
+}
+
+/// Returns whether a type is declared in the project, types generated for inline declarations are not
+fn is_exported_type(data_type: &DataType) -> bool {
+    !data_type.get_name().starts_with("__") && !data_type.location.is_internal()
+}
+
+/// Returns whether a member of an instance is part of the model, only the variables stored in the
+/// instance and declared by the user are
+fn is_exported_member(member: &VariableIndexEntry) -> bool {
+    matches!(
+        member.get_variable_type(),
+        VariableType::Input | VariableType::Output | VariableType::InOut | VariableType::Local
+    ) && !member.get_name().starts_with("__")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_node_set, OpcUaOptions};
+    use crate::test_utils::tests::index;
+
+    fn node_set(src: &str) -> String {
+        let (_, index) = index(src);
+        let options = OpcUaOptions { namespace_uri: "urn:test".into(), ..Default::default() };
+        generate_node_set(&index, &options).unwrap()
+    }
+
+    #[test]
+    fn types_are_declared_as_data_types_and_object_types() {
+        let node_set = node_set(
+            "
+            TYPE Color : (red, green := 5) END_TYPE
+            TYPE Point : STRUCT
+                x : REAL;
+                samples : ARRAY[0..9] OF INT;
+            END_STRUCT
+            END_TYPE
+
+            FUNCTION_BLOCK Motor
+            VAR_INPUT
+                speed : INT;
+            END_VAR
+            VAR_TEMP
+                tmp : INT;
+            END_VAR
+            END_FUNCTION_BLOCK
+            ",
+        );
+
+        assert!(node_set.contains("<Uri>urn:test</Uri>"));
+        insta::assert_snapshot!(node_set.split("</Aliases>\n").nth(1).unwrap(), @r#"
+          <UADataType NodeId="ns=1;s=type:Color" BrowseName="1:Color">
+            <DisplayName>Color</DisplayName>
+            <References>
+              <Reference ReferenceType="HasSubtype" IsForward="false">i=29</Reference>
+            </References>
+            <Definition Name="1:Color">
+              <Field Name="red" Value="0" />
+              <Field Name="green" Value="5" />
+            </Definition>
+          </UADataType>
+          <UADataType NodeId="ns=1;s=type:Point" BrowseName="1:Point">
+            <DisplayName>Point</DisplayName>
+            <References>
+              <Reference ReferenceType="HasSubtype" IsForward="false">i=22</Reference>
+            </References>
+            <Definition Name="1:Point">
+              <Field Name="x" DataType="Float" />
+              <Field Name="samples" DataType="Int16" ValueRank="1" ArrayDimensions="10" />
+            </Definition>
+          </UADataType>
+          <UAObjectType NodeId="ns=1;s=type:Motor" BrowseName="1:Motor">
+            <DisplayName>Motor</DisplayName>
+            <References>
+              <Reference ReferenceType="HasSubtype" IsForward="false">i=58</Reference>
+            </References>
+          </UAObjectType>
+          <UAVariable NodeId="ns=1;s=type:Motor.speed" BrowseName="1:speed" ParentNodeId="ns=1;s=type:Motor" DataType="Int16" AccessLevel="3">
+            <DisplayName>speed</DisplayName>
+            <References>
+              <Reference ReferenceType="HasComponent" IsForward="false">ns=1;s=type:Motor</Reference>
+              <Reference ReferenceType="HasTypeDefinition">i=63</Reference>
+              <Reference ReferenceType="HasModellingRule">i=78</Reference>
+            </References>
+          </UAVariable>
+        </UANodeSet>
+        "#);
+    }
+
+    #[test]
+    fn instances_are_declared_as_objects_and_variables() {
+        let node_set = node_set(
+            "
+            TYPE Point : STRUCT
+                x, y : DINT;
+            END_STRUCT
+            END_TYPE
+
+            FUNCTION_BLOCK Motor
+            VAR_INPUT
+                speed : INT;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            VAR_GLOBAL CONSTANT
+                limit : INT := 10;
+            END_VAR
+
+            PROGRAM main
+            VAR
+                drive : Motor;
+                position : Point;
+                ptr : REF_TO INT;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+
+        let instances = node_set.split("</UAObjectType>\n").nth(1).unwrap();
+        let headers = instances.lines().filter(|it| it.starts_with("  <UA")).collect::<Vec<_>>();
+        assert_eq!(
+            headers,
+            [
+                // the declaration of the member of the object type
+                r#"  <UAVariable NodeId="ns=1;s=type:Motor.speed" BrowseName="1:speed" ParentNodeId="ns=1;s=type:Motor" DataType="Int16" AccessLevel="3">"#,
+                r#"  <UAVariable NodeId="ns=1;s=limit" BrowseName="1:limit" ParentNodeId="i=85" DataType="Int16" AccessLevel="1">"#,
+                r#"  <UAObject NodeId="ns=1;s=main" BrowseName="1:main" ParentNodeId="i=85">"#,
+                r#"  <UAObject NodeId="ns=1;s=main.drive" BrowseName="1:drive" ParentNodeId="ns=1;s=main">"#,
+                r#"  <UAVariable NodeId="ns=1;s=main.drive.speed" BrowseName="1:speed" ParentNodeId="ns=1;s=main.drive" DataType="Int16" AccessLevel="3">"#,
+                r#"  <UAVariable NodeId="ns=1;s=main.position" BrowseName="1:position" ParentNodeId="ns=1;s=main" DataType="ns=1;s=type:Point" AccessLevel="3">"#,
+            ]
+        );
+        assert!(instances
+            .contains(r#"<Reference ReferenceType="HasTypeDefinition">ns=1;s=type:Motor</Reference>"#));
+        assert!(
+            instances.contains(r#"<Reference ReferenceType="Organizes" IsForward="false">i=85</Reference>"#)
+        );
+    }
+
+    #[test]
+    fn nested_function_blocks_are_objects_and_arrays_of_structs_are_variables() {
+        let node_set = node_set(
+            "
+            TYPE Reading : STRUCT
+                value : REAL;
+                quality : WORD;
+            END_STRUCT
+            END_TYPE
+
+            FUNCTION_BLOCK Valve
+            VAR_INPUT
+                flow : Reading;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            FUNCTION_BLOCK Station
+            VAR
+                valve : Valve;
+                history : ARRAY[1..3] OF Reading;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            PROGRAM plant
+            VAR
+                station : Station;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+
+        let headers = node_set
+            .lines()
+            .filter(|it| it.starts_with("  <UA"))
+            .filter(|it| it.contains("NodeId=\"ns=1;s=type:Station.") || it.contains("NodeId=\"ns=1;s=plant"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            headers,
+            [
+                // the members of the object type, the nested function block is an object of its type
+                r#"  <UAObject NodeId="ns=1;s=type:Station.valve" BrowseName="1:valve" ParentNodeId="ns=1;s=type:Station">"#,
+                r#"  <UAVariable NodeId="ns=1;s=type:Station.history" BrowseName="1:history" ParentNodeId="ns=1;s=type:Station" DataType="ns=1;s=type:Reading" ValueRank="1" ArrayDimensions="3" AccessLevel="3">"#,
+                r#"  <UAObject NodeId="ns=1;s=plant" BrowseName="1:plant" ParentNodeId="i=85">"#,
+                r#"  <UAObject NodeId="ns=1;s=plant.station" BrowseName="1:station" ParentNodeId="ns=1;s=plant">"#,
+                r#"  <UAObject NodeId="ns=1;s=plant.station.valve" BrowseName="1:valve" ParentNodeId="ns=1;s=plant.station">"#,
+                r#"  <UAVariable NodeId="ns=1;s=plant.station.valve.flow" BrowseName="1:flow" ParentNodeId="ns=1;s=plant.station.valve" DataType="ns=1;s=type:Reading" AccessLevel="3">"#,
+                // the elements of the array are part of its value
+                r#"  <UAVariable NodeId="ns=1;s=plant.station.history" BrowseName="1:history" ParentNodeId="ns=1;s=plant.station" DataType="ns=1;s=type:Reading" ValueRank="1" ArrayDimensions="3" AccessLevel="3">"#,
+            ]
+        );
+        assert!(node_set
+            .contains(r#"<Reference ReferenceType="HasTypeDefinition">ns=1;s=type:Valve</Reference>"#));
+        // the members of a struct are fields of its data type, not nodes of their own
+        assert!(!node_set.contains("ns=1;s=plant.station.valve.flow.value"));
+        assert!(!node_set.contains("ns=1;s=plant.station.history[1]"));
+    }
+}
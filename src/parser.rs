@@ -7,7 +7,7 @@ use plc_ast::{
         AccessModifier, ArgumentProperty, AstFactory, AstNode, AstStatement, AutoDerefType, CompilationUnit,
         ConfigVariable, DataType, DataTypeDeclaration, DeclarationKind, DirectAccessType, GenericBinding,
         HardwareAccessType, Identifier, Implementation, Interface, LinkageType, PolymorphismMode, Pou,
-        PouType, PropertyBlock, PropertyImplementation, PropertyKind, ReferenceAccess, ReferenceExpr,
+        PouType, PropertyBlock, PropertyImplementation, PropertyKind, ReferenceAccess, ReferenceExpr, Trivia,
         TypeNature, UserTypeDeclaration, Variable, VariableBlock, VariableBlockType,
     },
     provider::IdProvider,
@@ -40,6 +40,7 @@ use self::{
 
 mod control_parser;
 pub mod expressions_parser;
+mod trivia;
 
 #[cfg(test)]
 pub mod tests;
@@ -51,12 +52,27 @@ pub fn parse_file(
     id_provider: IdProvider,
     diagnostician: &mut Diagnostician,
 ) -> Result<CompilationUnit, Diagnostic> {
+    let parsed = parse_source(source, linkage, id_provider);
+    report_parse_diagnostics(source, parsed, diagnostician)
+}
+
+/// Parses the given source without reporting anything, the parse diagnostics are returned alongside the unit
+pub fn parse_source(source: &SourceCode, linkage: LinkageType, id_provider: IdProvider) -> ParsedAst {
     let location_factory = SourceLocationFactory::for_source(source);
-    let (unit, errors) = parse(
+    parse(
         lexer::lex_with_ids(&source.source, id_provider, location_factory),
         linkage,
         source.get_location_str(),
-    );
+    )
+}
+
+/// Registers the parsed source with the diagnostician and reports the diagnostics of parsing it.
+/// Returns the unit unless one of the diagnostics is a critical error
+pub fn report_parse_diagnostics(
+    source: &SourceCode,
+    (unit, errors): ParsedAst,
+    diagnostician: &mut Diagnostician,
+) -> Result<CompilationUnit, Diagnostic> {
     //Register the source file with the diagnostician
     //TODO: We should reduce the clone here
     diagnostician.register_file(source.get_location_str().to_string(), source.source.clone()); // TODO: Remove clone here, generally passing the GlobalContext instead of the actual source here or in the handle method should be sufficient
@@ -135,7 +151,14 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType, file_name: &'static str)
                     unit.user_types.push(utype);
                 }
             }
-            KeywordEndActions | End => return (unit, lexer.diagnostics),
+            KeywordEndActions | End => {
+                let trivia = take_trivia(&mut lexer);
+                let declaration_ends = lexer.take_declaration_ends();
+                unit.trivia = trivia::attach(&unit, trivia, &declaration_ends, lexer.get_src());
+                unit.doc_comments = lexer.take_doc_comments();
+                unit.attributes = lexer.take_attributes();
+                return (unit, lexer.diagnostics);
+            }
             _ => {
                 lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
                     "StartKeyword",
@@ -149,6 +172,17 @@ pub fn parse(mut lexer: ParseSession, lnk: LinkageType, file_name: &'static str)
     }
     //the match in the loop will always return
 }
+/// Collects the comments and pragmas the lexer skipped
+fn take_trivia(lexer: &mut ParseSession) -> Vec<Trivia> {
+    lexer
+        .take_trivia()
+        .into_iter()
+        .map(|range| {
+            Trivia::new(lexer.slice_region(range.clone()), lexer.source_range_factory.create_range(range))
+        })
+        .collect()
+}
+
 fn parse_actions(
     lexer: &mut ParseSession,
     linkage: LinkageType,
@@ -185,6 +219,7 @@ fn parse_actions(
 /// Parses an interface and its methods / properties
 fn parse_interface(lexer: &mut ParseSession) -> (Interface, Vec<Implementation>) {
     let location_start = lexer.range().start;
+    let doc = lexer.get_doc_comment();
     lexer.try_consume_or_report(KeywordInterface);
 
     let (name, location_name) = if is_name_slot_candidate(lexer) {
@@ -200,6 +235,7 @@ fn parse_interface(lexer: &mut ParseSession) -> (Interface, Vec<Implementation>)
         // unrecoverable error though
         (String::new(), SourceLocation::undefined())
     };
+    lexer.attach_doc_comment(doc, &location_name);
 
     let mut extensions = Vec::new();
     let mut methods = Vec::new();
@@ -253,6 +289,7 @@ fn parse_interface(lexer: &mut ParseSession) -> (Interface, Vec<Implementation>)
     }
 
     lexer.try_consume_or_report(KeywordEndInterface);
+    lexer.mark_declaration_end(location_start);
     let location_end = lexer.range().start;
 
     (
@@ -292,6 +329,8 @@ fn parse_pou(
     }
 
     let start = lexer.range().start;
+    let doc = lexer.get_doc_comment();
+    let attributes = lexer.get_attributes();
     lexer.advance(); //Consume ProgramKeyword
     let closing_tokens = vec![
         expected_end_token,
@@ -317,6 +356,8 @@ fn parse_pou(
         };
         let (name, name_location) = expect_name_slot(lexer, pou_slot_label)
             .unwrap_or_else(|| ("".to_string(), SourceLocation::undefined()));
+        lexer.attach_doc_comment(doc, &name_location);
+        lexer.attach_attributes(attributes, &name_location);
 
         let generics = parse_generics(lexer);
 
@@ -486,6 +527,7 @@ fn parse_pou(
         ));
     }
 
+    lexer.mark_declaration_end(start);
     let (mut pous, mut implementations) = result;
     unit.pous.append(&mut pous);
     unit.implementations.append(&mut implementations);
@@ -686,6 +728,7 @@ fn parse_method(
         }
 
         let method_start = lexer.range().start;
+        let doc = lexer.get_doc_comment();
         lexer.advance(); // eat METHOD keyword
 
         let access = Some(parse_access_modifier(lexer));
@@ -693,6 +736,7 @@ fn parse_method(
         let poly_mode = parse_polymorphism_mode(lexer, &pou_kind);
         let overriding = lexer.try_consume(KeywordOverride);
         let (name, name_location) = expect_name_slot(lexer, "a method name")?;
+        lexer.attach_doc_comment(doc, &name_location);
         let generics = parse_generics(lexer);
         let return_type = parse_return_type(lexer);
 
@@ -749,6 +793,7 @@ fn parse_property(
     outer_end_tokens: &[Token],
 ) -> Option<(Identifier, PropertyImplementation)> {
     let kind_location = lexer.location();
+    let doc = lexer.get_doc_comment();
     let kind = match lexer.token {
         KeywordPropertyGet => PropertyKind::Get,
         KeywordPropertySet => PropertyKind::Set,
@@ -767,6 +812,7 @@ fn parse_property(
         recover_property(lexer, outer_end_tokens);
         return None;
     };
+    lexer.attach_doc_comment(doc, &name_location);
 
     if !lexer.try_consume(KeywordColon) {
         lexer.accept_diagnostic(
@@ -979,7 +1025,7 @@ fn parse_action(
     let closing_tokens =
         vec![KeywordEndAction, KeywordEndProgram, KeywordEndFunction, KeywordEndFunctionBlock];
 
-    parse_any_in_region(lexer, closing_tokens.clone(), |lexer| {
+    let implementation = parse_any_in_region(lexer, closing_tokens.clone(), |lexer| {
         let name_or_container = lexer.slice_and_advance();
 
         let (container, name, name_location) = if let Some(container) = container {
@@ -1015,24 +1061,35 @@ fn parse_action(
             ))
         }
         Some(implementation)
-    })
+    });
+    if let Some(range) = implementation.as_ref().and_then(|it| it.name_location.to_range()) {
+        lexer.mark_declaration_end(range.start);
+    }
+    implementation
 }
 
 // TYPE ... END_TYPE
 fn parse_type(lexer: &mut ParseSession, linkage: LinkageType) -> Vec<UserTypeDeclaration> {
+    // the doc comment in front of `TYPE` documents the first declared type
+    let mut type_doc = lexer.get_doc_comment();
     lexer.advance(); // consume the TYPE
 
     parse_any_in_region(lexer, vec![KeywordEndType], |lexer| {
         let mut declarations = vec![];
         while !lexer.closes_open_region(&lexer.token) {
+            let doc = lexer.get_doc_comment().or(type_doc.take());
             let Some((name, name_location)) = expect_name_slot(lexer, "a type name") else {
                 // unrecoverable: skip the offending token to avoid an infinite loop
                 lexer.advance();
                 continue;
             };
+            lexer.attach_doc_comment(doc, &name_location);
             lexer.try_consume_or_report(KeywordColon);
 
             let result = parse_full_data_type_definition(lexer, Some(name));
+            if let Some(range) = name_location.to_range() {
+                lexer.mark_declaration_end(range.start);
+            }
 
             if let Some((DataTypeDeclaration::Definition { data_type, .. }, initializer)) = result {
                 declarations.push(UserTypeDeclaration {
@@ -1613,7 +1670,12 @@ fn parse_variable_block(lexer: &mut ParseSession, linkage: LinkageType) -> Varia
     let variable_block_type = parse_variable_block_type(lexer);
 
     let constant = try_consume_var_modifier(lexer, KeywordConstant);
-    let retain = try_consume_var_modifier(lexer, KeywordRetain);
+    // `RETAIN PERSISTENT` may also be written as `PERSISTENT RETAIN`
+    let mut retain = try_consume_var_modifier(lexer, KeywordRetain);
+    let persistent = try_consume_var_modifier(lexer, KeywordPersistent);
+    if persistent && !retain {
+        retain = try_consume_var_modifier(lexer, KeywordRetain);
+    }
     try_consume_var_modifier(lexer, KeywordNonRetain);
 
     let access = parse_access_modifier(lexer);
@@ -1626,6 +1688,9 @@ fn parse_variable_block(lexer: &mut ParseSession, linkage: LinkageType) -> Varia
     };
     let mut variables =
         parse_any_in_region(lexer, vec![KeywordEndVar], |lexer| parse_variable_list(lexer, slot_label));
+    if let Some(range) = location.to_range() {
+        lexer.mark_declaration_end(range.start);
+    }
 
     if constant && !matches!(variable_block_type, VariableBlockType::External) {
         // sneak in the DefaultValue-Statements if no initializers were defined
@@ -1634,7 +1699,16 @@ fn parse_variable_block(lexer: &mut ParseSession, linkage: LinkageType) -> Varia
         });
     }
 
-    VariableBlock { access, constant, retain, variables, kind: variable_block_type, linkage, location }
+    VariableBlock {
+        access,
+        constant,
+        retain,
+        persistent,
+        variables,
+        kind: variable_block_type,
+        linkage,
+        location,
+    }
 }
 
 /// Consumes a var-block modifier, but only if the following token is not a
@@ -1754,6 +1828,10 @@ fn parse_aliasing(lexer: &mut ParseSession, names: &(String, Range<usize>)) -> O
 }
 
 fn parse_variable_line(lexer: &mut ParseSession, slot_label: &'static str) -> Vec<Variable> {
+    // the doc comment and attributes in front of the line apply to all of its variables
+    let doc = lexer.get_doc_comment();
+    let attributes = lexer.get_attributes();
+
     // read in a comma separated list of variable names
     let mut var_names: Vec<(String, Range<usize>)> = vec![];
     while is_name_slot_candidate(lexer) {
@@ -1833,10 +1911,13 @@ fn parse_variable_line(lexer: &mut ParseSession, slot_label: &'static str) -> Ve
 
     if let Some((data_type, initializer)) = parse_definition_opt {
         for (name, range) in var_names {
+            let location = lexer.source_range_factory.create_range(range);
+            lexer.attach_doc_comment(doc.clone(), &location);
+            lexer.attach_attributes(attributes.clone(), &location);
             variables.push(Variable {
                 name,
                 data_type_declaration: data_type.clone(),
-                location: lexer.source_range_factory.create_range(range),
+                location,
                 initializer: initializer.clone(),
                 address: address.clone(),
             });
@@ -6,7 +6,7 @@ use insta::assert_debug_snapshot;
 use plc_ast::{
     ast::{
         Assignment, AstNode, AstStatement, BinaryExpression, CallStatement, LinkageType, ReferenceAccess,
-        ReferenceExpr, UnaryExpression,
+        ReferenceExpr, Trivia, TriviaKind, UnaryExpression,
     },
     control_statements::{AstControlStatement, CaseStatement, ForLoopStatement, IfStatement, LoopStatement},
 };
@@ -481,3 +481,194 @@ fn ids_are_assigned_to_case_statements() {
         _ => panic!("invalid statement"),
     }
 }
+
+fn trivia_texts(trivia: &[Trivia]) -> Vec<&str> {
+    trivia.iter().map(|it| it.text.as_str()).collect()
+}
+
+#[test]
+fn comments_and_unknown_pragmas_are_kept_as_trivia() {
+    let src = r#"
+    (* block (* nested *) comment *)
+    PROGRAM main // line comment
+    {attribute 'hidden'}
+    VAR
+        x : INT; /* another block */
+    END_VAR
+    END_PROGRAM
+    "#;
+    let (unit, diagnostics) = parse(src);
+    assert!(diagnostics.is_empty());
+
+    let trivia = unit
+        .trivia
+        .iter()
+        .map(|it| (it.kind, it.text.as_str(), &src[it.location.to_range().unwrap()]))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        trivia,
+        vec![
+            (
+                TriviaKind::BlockComment,
+                "(* block (* nested *) comment *)",
+                "(* block (* nested *) comment *)"
+            ),
+            (TriviaKind::LineComment, "// line comment", "// line comment"),
+            (TriviaKind::Pragma, "{attribute 'hidden'}", "{attribute 'hidden'}"),
+            (TriviaKind::BlockComment, "/* another block */", "/* another block */"),
+        ]
+    );
+
+    let block = &unit.pous[0].variable_blocks[0];
+    assert_eq!(
+        trivia_texts(unit.trivia.get_leading(&unit.pous[0].location)),
+        vec!["(* block (* nested *) comment *)"]
+    );
+    assert_eq!(
+        trivia_texts(unit.trivia.get_leading(&block.location)),
+        vec!["// line comment", "{attribute 'hidden'}"]
+    );
+    assert_eq!(
+        trivia_texts(unit.trivia.get_trailing(&block.variables[0].location)),
+        vec!["/* another block */"]
+    );
+}
+
+#[test]
+fn trivia_is_attached_to_statements_and_closing_keywords() {
+    let src = r#"
+    PROGRAM main
+    VAR
+        x : INT;
+        // no more variables
+    END_VAR
+        // reset
+        x := 0; (* done *)
+        IF x = 0 THEN
+            x := MAX(1, (* two *) 2);
+            // nothing else
+        END_IF // end of if
+    END_PROGRAM
+    // end of file
+    "#;
+    let (unit, diagnostics) = parse(src);
+    assert!(diagnostics.is_empty());
+
+    let block = &unit.pous[0].variable_blocks[0];
+    assert_eq!(trivia_texts(unit.trivia.get_closing(&block.location)), vec!["// no more variables"]);
+
+    let statements = &unit.implementations[0].statements;
+    assert_eq!(trivia_texts(unit.trivia.get_leading(&statements[0].get_location())), vec!["// reset"]);
+    assert_eq!(trivia_texts(unit.trivia.get_trailing(&statements[0].get_location())), vec!["(* done *)"]);
+    assert_eq!(trivia_texts(unit.trivia.get_closing(&statements[1].get_location())), vec!["// nothing else"]);
+    assert_eq!(trivia_texts(unit.trivia.get_trailing(&statements[1].get_location())), vec!["// end of if"]);
+    let AstStatement::ControlStatement(AstControlStatement::If(if_statement)) = statements[1].get_stmt()
+    else {
+        panic!("expected an if statement")
+    };
+    // a comment within a statement follows it
+    let call = &if_statement.blocks[0].body[0];
+    assert_eq!(trivia_texts(unit.trivia.get_trailing(&call.get_location())), vec!["(* two *)"]);
+    assert_eq!(trivia_texts(&unit.trivia.dangling), vec!["// end of file"]);
+}
+
+#[test]
+fn doc_comments_are_attached_to_declarations() {
+    let src = r#"
+    (** Adds two numbers *)
+    {external}
+    FUNCTION add : DINT
+    VAR_INPUT
+        /// the first summand
+        /// may be negative
+        a, b : DINT;
+        c : DINT; // not documented
+    END_VAR
+    END_FUNCTION
+
+    /// A counter
+    FUNCTION_BLOCK counter
+        (**
+         * Increments the counter
+         *
+         * by one
+         *)
+        METHOD inc
+        END_METHOD
+        /// the current value
+        PROPERTY_GET value : DINT
+        END_PROPERTY
+    END_FUNCTION_BLOCK
+
+    (****************)
+    TYPE
+        (** a point *) Point : STRUCT
+            /// horizontal
+            x : INT;
+        END_STRUCT
+    END_TYPE
+    "#;
+    let (unit, diagnostics) = parse(src);
+    assert!(diagnostics.is_empty());
+
+    let docs = unit
+        .doc_comments
+        .iter()
+        .map(|it| (&src[it.location.to_range().unwrap()], it.text.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        docs,
+        vec![
+            ("add", "Adds two numbers"),
+            ("a", "the first summand\nmay be negative"),
+            ("b", "the first summand\nmay be negative"),
+            ("counter", "A counter"),
+            ("inc", "Increments the counter\n\nby one"),
+            ("value", "the current value"),
+            ("Point", "a point"),
+            ("x", "horizontal"),
+        ]
+    );
+    assert_eq!(unit.get_doc_comment(&unit.pous[0].name_location), Some("Adds two numbers"));
+    assert_eq!(unit.trivia.iter().next().map(|it| it.kind), Some(TriviaKind::DocComment));
+    assert!(unit.trivia.iter().any(|it| it.kind == TriviaKind::BlockComment && it.text.starts_with("(***")));
+}
+
+#[test]
+fn attributes_are_attached_to_declarations() {
+    let src = r#"
+    {attribute 'symbol' := 'read'}
+    PROGRAM main
+    VAR
+        {attribute 'symbol' := 'none'}
+        a, b : DINT;
+        c : DINT;
+    END_VAR
+    END_PROGRAM
+
+    VAR_GLOBAL
+        {attribute 'hide'} /// documented
+        g : INT;
+    END_VAR
+    "#;
+    let (unit, diagnostics) = parse(src);
+    assert!(diagnostics.is_empty());
+
+    let attributes = unit
+        .attributes
+        .iter()
+        .map(|it| (&src[it.location.to_range().unwrap()], it.name.as_str(), it.value.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        attributes,
+        vec![
+            ("main", "symbol", Some("read")),
+            ("a", "symbol", Some("none")),
+            ("b", "symbol", Some("none")),
+            ("g", "hide", None),
+        ]
+    );
+    assert_eq!(unit.get_attribute(&unit.pous[0].name_location, "SYMBOL"), Some(Some("read")));
+    assert_eq!(unit.get_attribute(&unit.pous[0].name_location, "hide"), None);
+    assert_eq!(unit.get_doc_comment(&unit.global_vars[0].variables[0].location), Some("documented"));
+}
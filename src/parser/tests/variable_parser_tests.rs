@@ -369,6 +369,14 @@ fn var_config_test() {
             "test.st",
         ),
         linkage: Internal,
+        trivia: AttachedTrivia {
+            leading: {},
+            trailing: {},
+            closing: {},
+            dangling: [],
+        },
+        doc_comments: [],
+        attributes: [],
     }
     "#);
 }
@@ -495,6 +503,14 @@ fn var_external() {
             "test.st",
         ),
         linkage: Internal,
+        trivia: AttachedTrivia {
+            leading: {},
+            trailing: {},
+            closing: {},
+            dangling: [],
+        },
+        doc_comments: [],
+        attributes: [],
     }
     "#);
 }
@@ -609,6 +625,14 @@ fn var_external_constant() {
             "test.st",
         ),
         linkage: Internal,
+        trivia: AttachedTrivia {
+            leading: {},
+            trailing: {},
+            closing: {},
+            dangling: [],
+        },
+        doc_comments: [],
+        attributes: [],
     }
     "#);
 }
@@ -744,6 +768,50 @@ fn retain_block_in_program() {
     assert!(var_block.retain);
 }
 
+#[test]
+fn persistent_and_retain_are_independent_modifiers() {
+    let src = r#"
+    VAR_GLOBAL PERSISTENT
+        x : INT;
+    END_VAR
+    VAR_GLOBAL RETAIN PERSISTENT
+        y : INT;
+    END_VAR
+    VAR_GLOBAL CONSTANT PERSISTENT RETAIN
+        z : INT;
+    END_VAR
+    VAR_GLOBAL RETAIN
+        w : INT;
+    END_VAR
+    "#;
+
+    let (result, diagnostics) = parse(src);
+
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    let blocks = &result.global_vars;
+    assert!(!blocks[0].retain && blocks[0].persistent);
+    assert!(blocks[1..3].iter().all(|it| it.retain && it.persistent));
+    assert!(blocks[2].constant);
+    assert!(blocks[3].retain && !blocks[3].persistent);
+}
+
+#[test]
+fn persistent_block_in_program() {
+    let src = r#"
+    PROGRAM main
+    VAR PERSISTENT
+        x : INT;
+    END_VAR
+    END_PROGRAM
+    "#;
+
+    let (result, _) = parse(src);
+
+    let var_block = &result.pous[0].variable_blocks[0];
+    assert!(!var_block.retain && var_block.persistent);
+    assert_eq!(var_block.variables[0].name, "x");
+}
+
 #[test]
 fn retain_block_in_function_block() {
     let src = r#"
@@ -0,0 +1,171 @@
+//! Attaches the comments and pragmas skipped by the lexer to the declarations and statements
+//! around them, see [`AttachedTrivia`].
+use plc_ast::{
+    ast::{
+        AstNode, AstStatement, AttachedTrivia, CompilationUnit, DataType, DataTypeDeclaration, Pou, PouType,
+        PropertyBlock, Trivia, Variable, VariableBlock,
+    },
+    control_statements::AstControlStatement,
+};
+use plc_source::source_location::SourceLocation;
+use rustc_hash::FxHashMap;
+
+/// A declaration or statement trivia can be attached to
+struct Node {
+    start: usize,
+    end: usize,
+    /// Blocks (POUs, `VAR` blocks, control statements, ...) contain other nodes and end with a
+    /// closing keyword
+    is_block: bool,
+}
+
+/// Attaches every trivia to a node of the unit:
+/// - trivia behind a node on the same line trails that node
+/// - other trivia leads the next node, unless that node is outside of the innermost block the
+///   trivia is in, then it is placed in front of the block's closing keyword
+/// - trivia behind the last node of the unit dangles
+pub(crate) fn attach(
+    unit: &CompilationUnit,
+    trivia: Vec<Trivia>,
+    declaration_ends: &FxHashMap<usize, usize>,
+    src: &str,
+) -> AttachedTrivia {
+    let mut collector = NodeCollector { declaration_ends, nodes: vec![] };
+    collector.collect_unit(unit);
+    let nodes = collector.nodes;
+
+    let mut result = AttachedTrivia::default();
+    for trivia in trivia {
+        let Some(range) = trivia.location.to_range() else {
+            result.dangling.push(trivia);
+            continue;
+        };
+
+        let previous = nodes
+            .iter()
+            .filter(|it| it.end <= range.start && !src[it.end..range.start].contains('\n'))
+            .max_by_key(|it| it.end);
+        if let Some(previous) = previous {
+            result.trailing.entry(previous.start).or_default().push(trivia);
+            continue;
+        }
+
+        let block = nodes
+            .iter()
+            .filter(|it| it.start <= range.start && range.end <= it.end)
+            .max_by_key(|it| it.start);
+        let next = nodes.iter().filter(|it| it.start >= range.end).min_by_key(|it| it.start);
+        match (next, block) {
+            (Some(next), block) if block.is_none_or(|it| next.start < it.end) => {
+                result.leading.entry(next.start).or_default().push(trivia)
+            }
+            (_, Some(block)) if block.is_block => result.closing.entry(block.start).or_default().push(trivia),
+            // a comment within a statement, e.g. between two arguments, follows the statement
+            (_, Some(statement)) => result.trailing.entry(statement.start).or_default().push(trivia),
+            (_, None) => result.dangling.push(trivia),
+        }
+    }
+    result
+}
+
+struct NodeCollector<'a> {
+    declaration_ends: &'a FxHashMap<usize, usize>,
+    nodes: Vec<Node>,
+}
+
+impl NodeCollector<'_> {
+    fn add(&mut self, location: &SourceLocation, is_block: bool) {
+        if let Some(range) = location.to_range() {
+            let end = self.declaration_ends.get(&range.start).copied().unwrap_or(range.end);
+            self.nodes.push(Node { start: range.start, end, is_block });
+        }
+    }
+
+    fn collect_unit(&mut self, unit: &CompilationUnit) {
+        unit.global_vars.iter().for_each(|it| self.collect_variable_block(it));
+        unit.var_config.iter().for_each(|it| self.add(&it.location, false));
+        for user_type in &unit.user_types {
+            self.add(&user_type.location, matches!(user_type.data_type, DataType::StructType { .. }));
+            self.collect_data_type(&user_type.data_type);
+        }
+        for interface in &unit.interfaces {
+            self.add(&interface.location, true);
+            interface.methods.iter().for_each(|it| self.collect_pou(it));
+            interface.properties.iter().for_each(|it| self.collect_property(it));
+        }
+        unit.pous.iter().for_each(|it| self.collect_pou(it));
+        for implementation in &unit.implementations {
+            if implementation.pou_type == PouType::Action {
+                self.add(&implementation.name_location, true);
+            }
+            self.collect_statements(&implementation.statements);
+        }
+    }
+
+    fn collect_pou(&mut self, pou: &Pou) {
+        self.add(&pou.location, true);
+        pou.variable_blocks.iter().for_each(|it| self.collect_variable_block(it));
+        pou.properties.iter().for_each(|it| self.collect_property(it));
+    }
+
+    fn collect_property(&mut self, property: &PropertyBlock) {
+        for implementation in &property.implementations {
+            self.add(&implementation.location.span(&implementation.end_location), true);
+            implementation.variable_blocks.iter().for_each(|it| self.collect_variable_block(it));
+            self.collect_statements(&implementation.body);
+        }
+    }
+
+    fn collect_variable_block(&mut self, block: &VariableBlock) {
+        self.add(&block.location, true);
+        block.variables.iter().for_each(|it| self.collect_variable(it));
+    }
+
+    fn collect_variable(&mut self, variable: &Variable) {
+        self.add(&variable.location, false);
+        if let DataTypeDeclaration::Definition { data_type, .. } = &variable.data_type_declaration {
+            self.collect_data_type(data_type);
+        }
+    }
+
+    fn collect_data_type(&mut self, data_type: &DataType) {
+        match data_type {
+            DataType::StructType { variables, .. } => {
+                variables.iter().for_each(|it| self.collect_variable(it))
+            }
+            DataType::ArrayType { referenced_type, .. } | DataType::PointerType { referenced_type, .. } => {
+                if let DataTypeDeclaration::Definition { data_type, .. } = referenced_type.as_ref() {
+                    self.collect_data_type(data_type);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_statements(&mut self, statements: &[AstNode]) {
+        for statement in statements.iter().filter(|it| !it.is_empty_statement()) {
+            let AstStatement::ControlStatement(control) = statement.get_stmt() else {
+                self.add(&statement.get_location(), false);
+                continue;
+            };
+            self.add(&statement.get_location(), true);
+            match control {
+                AstControlStatement::If(stmt) => {
+                    stmt.blocks.iter().for_each(|it| self.collect_statements(&it.body));
+                    self.collect_statements(&stmt.else_block);
+                }
+                AstControlStatement::ForLoop(stmt) => self.collect_statements(&stmt.body),
+                AstControlStatement::WhileLoop(stmt) | AstControlStatement::RepeatLoop(stmt) => {
+                    self.collect_statements(&stmt.body)
+                }
+                AstControlStatement::Case(stmt) => {
+                    for block in &stmt.case_blocks {
+                        self.add(&block.condition.get_location(), false);
+                        self.collect_statements(&block.body);
+                    }
+                    self.collect_statements(&stmt.else_block);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,452 @@
+//! Static analysis of the worst-case stack usage of each `PROGRAM`.
+//!
+//! The code generator reports the size of the stack frame of every generated function (see
+//! [`crate::codegen::GeneratedModule::get_frame_sizes`]). Adding up the frames along the deepest path
+//! of the [call graph](crate::call_graph) starting at a program gives the stack the program needs at
+//! most. Calls dispatched at runtime count with the largest method which may be called in their place.
+//! A recursive call makes the stack usage unbounded, programs reaching one are flagged instead.
+
+use std::fmt::Write;
+
+use plc_ast::ast::{CompilationUnit, PouType};
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::source_location::SourceLocation;
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+
+use crate::{
+    call_graph::{collect_call_graph, CallKind},
+    index::{FxIndexSet, Index},
+    resolver::AnnotationMap,
+};
+
+/// The stack frame of a generated function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FrameSize {
+    /// the size of the frame in bytes
+    pub size: u64,
+    /// whether the function allocates additional stack of a size only known at runtime
+    pub dynamic: bool,
+}
+
+/// Parses the stack usage reported by the code generator, one function per line in the format of
+/// GCC's `-fstack-usage`: `file:line:function<TAB>size<TAB>static|dynamic`.
+/// The frames are returned by the lowercase name of their function.
+pub fn parse_stack_usage(content: &str) -> FxHashMap<String, FrameSize> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+            let function = columns.next()?.rsplit(':').next()?;
+            let size = columns.next()?.trim().parse().ok()?;
+            let dynamic = columns.next().is_some_and(|it| it.trim() != "static");
+            Some((function.to_lowercase(), FrameSize { size, dynamic }))
+        })
+        .collect()
+}
+
+/// The stack usage of all programs of a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StackUsage {
+    pub programs: Vec<ProgramStackUsage>,
+    pub frames: Vec<PouFrame>,
+}
+
+/// The worst-case stack usage of a program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProgramStackUsage {
+    pub name: String,
+    #[serde(skip)]
+    pub location: SourceLocation,
+    /// the stack used by the deepest call path in bytes, `None` if it is unbounded because of recursion
+    pub stack_size: Option<u64>,
+    /// the POUs of the deepest call path, starting with the program
+    pub path: Vec<String>,
+    /// the POUs of a recursive call reachable from the program, ending with the POU called again
+    pub recursion: Vec<String>,
+    /// POUs reachable from the program whose frame size is not known, e.g. `{external}` functions.
+    /// They are not part of the stack size.
+    pub unknown: Vec<String>,
+}
+
+/// The stack frame of a POU
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PouFrame {
+    pub name: String,
+    /// the name of the generated function
+    pub symbol: String,
+    pub size: Option<u64>,
+    pub dynamic: bool,
+}
+
+/// The calls between the POUs of a project, reduced to what is needed to analyze their stack usage
+#[derive(Debug, Clone)]
+pub struct StackGraph {
+    programs: Vec<(String, SourceLocation)>,
+    /// the POUs and the symbols of their generated functions
+    pous: Vec<(String, String)>,
+    /// the POUs each (lowercase) POU may call
+    calls: FxHashMap<String, FxIndexSet<String>>,
+}
+
+impl StackGraph {
+    /// Builds the graph of the POUs declared in the given units
+    pub fn new<'a>(
+        units: impl IntoIterator<Item = &'a CompilationUnit>,
+        index: &Index,
+        annotations: &impl AnnotationMap,
+    ) -> StackGraph {
+        let units = units.into_iter().collect::<Vec<_>>();
+        let programs = units
+            .iter()
+            .flat_map(|it| &it.pous)
+            .filter(|it| {
+                it.kind == PouType::Program
+                    && !it.location.is_internal()
+                    && !it.linkage.is_external_or_included()
+            })
+            .map(|it| (it.name.clone(), it.name_location.clone()))
+            .collect();
+
+        let graph = collect_call_graph(units, index, annotations);
+        let pous = graph
+            .nodes
+            .iter()
+            .map(|node| {
+                let symbol = index
+                    .find_implementation_by_name(&node.name)
+                    .map(|it| it.get_call_name_for_ir())
+                    .unwrap_or_else(|| node.name.clone());
+                (node.name.clone(), symbol)
+            })
+            .collect();
+        let mut calls: FxHashMap<String, FxIndexSet<String>> = FxHashMap::default();
+        for edge in graph.edges.into_iter().filter(|it| it.kind != CallKind::Reference) {
+            calls.entry(edge.caller.to_lowercase()).or_default().insert(edge.callee);
+        }
+
+        StackGraph { programs, pous, calls }
+    }
+
+    /// Computes the stack usage of every program from the given frame sizes, see [`parse_stack_usage`]
+    pub fn analyze(&self, frames: &FxHashMap<String, FrameSize>) -> StackUsage {
+        let symbols =
+            self.pous.iter().map(|(name, symbol)| (name.to_lowercase(), symbol.to_lowercase())).collect();
+        let mut analysis =
+            Analysis { graph: self, frames, symbols, done: FxHashMap::default(), stack: vec![] };
+
+        let programs = self
+            .programs
+            .iter()
+            .map(|(name, location)| {
+                let (stack_size, path, recursion) = match analysis.get_depth(name) {
+                    Depth::Bounded { size, path } => (Some(size), path, vec![]),
+                    Depth::Recursive(cycle) => (None, vec![], cycle),
+                };
+                let unknown = analysis
+                    .get_reachable(name)
+                    .into_iter()
+                    .filter(|it| analysis.get_frame(it).is_none())
+                    .collect();
+                ProgramStackUsage {
+                    name: name.clone(),
+                    location: location.clone(),
+                    stack_size,
+                    path,
+                    recursion,
+                    unknown,
+                }
+            })
+            .collect();
+
+        let frames = self
+            .pous
+            .iter()
+            .map(|(name, symbol)| {
+                let frame = frames.get(&symbol.to_lowercase());
+                PouFrame {
+                    name: name.clone(),
+                    symbol: symbol.clone(),
+                    size: frame.map(|it| it.size),
+                    dynamic: frame.is_some_and(|it| it.dynamic),
+                }
+            })
+            .collect();
+
+        StackUsage { programs, frames }
+    }
+}
+
+impl StackUsage {
+    /// Returns an error for every program that may use more than `limit` bytes of stack or whose stack
+    /// usage is unbounded
+    pub fn check_limit(&self, limit: u64) -> Vec<Diagnostic> {
+        self.programs
+            .iter()
+            .filter_map(|program| match program.stack_size {
+                Some(size) if size > limit => Some(
+                    Diagnostic::new(format!(
+                        "Program `{}` may use up to {size} bytes of stack, exceeding the limit of {limit} bytes (call path: {})",
+                        program.name,
+                        program.path.join(" -> ")
+                    ))
+                    .with_error_code("E154")
+                    .with_location(&program.location),
+                ),
+                Some(_) => None,
+                None => Some(
+                    Diagnostic::new(format!(
+                        "Stack usage of program `{}` is unbounded because of the recursive call {}, it cannot stay within the limit of {limit} bytes",
+                        program.name,
+                        program.recursion.join(" -> ")
+                    ))
+                    .with_error_code("E154")
+                    .with_location(&program.location),
+                ),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Depth {
+    Bounded { size: u64, path: Vec<String> },
+    Recursive(Vec<String>),
+}
+
+struct Analysis<'a> {
+    graph: &'a StackGraph,
+    frames: &'a FxHashMap<String, FrameSize>,
+    /// the lowercase symbols of the generated functions by the lowercase name of their POU
+    symbols: FxHashMap<String, String>,
+    done: FxHashMap<String, Depth>,
+    /// the POUs of the call path currently visited
+    stack: Vec<String>,
+}
+
+impl Analysis<'_> {
+    fn get_frame(&self, name: &str) -> Option<&FrameSize> {
+        self.symbols.get(&name.to_lowercase()).and_then(|it| self.frames.get(it))
+    }
+
+    fn get_callees(&self, name: &str) -> impl Iterator<Item = &String> + '_ {
+        self.graph.calls.get(&name.to_lowercase()).into_iter().flatten()
+    }
+
+    /// Returns the stack used by the deepest call path starting at the given POU
+    fn get_depth(&mut self, name: &str) -> Depth {
+        let key = name.to_lowercase();
+        if let Some(depth) = self.done.get(&key) {
+            return depth.clone();
+        }
+        if let Some(start) = self.stack.iter().position(|it| it.eq_ignore_ascii_case(name)) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(name.to_string());
+            return Depth::Recursive(cycle);
+        }
+
+        let frame = self.get_frame(name).map(|it| it.size).unwrap_or_default();
+        let callees = self.get_callees(name).cloned().collect::<Vec<_>>();
+        self.stack.push(name.to_string());
+        let mut result = Depth::Bounded { size: frame, path: vec![name.to_string()] };
+        for callee in callees {
+            match self.get_depth(&callee) {
+                Depth::Recursive(cycle) => {
+                    result = Depth::Recursive(cycle);
+                    break;
+                }
+                Depth::Bounded { size, path } => {
+                    if let Depth::Bounded { size: deepest, path: deepest_path } = &mut result {
+                        if frame + size > *deepest {
+                            *deepest = frame + size;
+                            *deepest_path = std::iter::once(name.to_string()).chain(path).collect();
+                        }
+                    }
+                }
+            }
+        }
+        self.stack.pop();
+
+        self.done.insert(key, result.clone());
+        result
+    }
+
+    /// Returns the given POU and all POUs it may call directly or indirectly
+    fn get_reachable(&self, name: &str) -> Vec<String> {
+        let mut reachable = FxIndexSet::default();
+        let mut pending = vec![name.to_string()];
+        while let Some(current) = pending.pop() {
+            if reachable.insert(current.to_lowercase()) {
+                pending.extend(self.get_callees(&current).cloned());
+            }
+        }
+        // report the POUs with their declared names
+        self.graph
+            .pous
+            .iter()
+            .map(|(name, _)| name)
+            .filter(|it| reachable.contains(&it.to_lowercase()))
+            .cloned()
+            .collect()
+    }
+}
+
+pub fn serialize_stack_usage(usage: &StackUsage, format: StackUsageFormat) -> Result<String, Diagnostic> {
+    match format {
+        StackUsageFormat::Table => Ok(to_table(usage)),
+        StackUsageFormat::Json => serde_json::to_string_pretty(usage).map_err(|it| {
+            Diagnostic::new(it.to_string()).with_internal_error(it.into()).with_error_code("E002")
+        }),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackUsageFormat {
+    Table,
+    Json,
+}
+
+impl StackUsageFormat {
+    /// Returns the format matching the extension of the given file name, a text table for anything
+    /// but `.json`
+    pub fn from_file_name(name: &str) -> StackUsageFormat {
+        match name.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("json") => StackUsageFormat::Json,
+            _ => StackUsageFormat::Table,
+        }
+    }
+}
+
+fn to_table(usage: &StackUsage) -> String {
+    let rows = usage
+        .programs
+        .iter()
+        .map(|program| {
+            let (size, path) = match program.stack_size {
+                Some(size) => (size.to_string(), program.path.join(" -> ")),
+                None => ("unbounded".to_string(), format!("recursion: {}", program.recursion.join(" -> "))),
+            };
+            [program.name.clone(), size, path, program.unknown.join(", ")]
+        })
+        .collect::<Vec<_>>();
+
+    let header = ["Program", "Stack (bytes)", "Deepest call path", "Unknown frames"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.len());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(header.map(str::to_string)).chain(rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(column, width)| format!("{column:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        let _ = writeln!(table, "{}", line.trim_end());
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_ast::provider::IdProvider;
+    use rustc_hash::FxHashMap;
+
+    use super::{parse_stack_usage, FrameSize, StackGraph, StackUsage};
+    use crate::test_utils::tests::{annotate_with_ids, index_with_ids};
+
+    fn analyze(src: &str, frames: &[(&str, u64)]) -> StackUsage {
+        let id_provider = IdProvider::default();
+        let (unit, mut index) = index_with_ids(src, id_provider.clone());
+        let annotations = annotate_with_ids(&unit, &mut index, id_provider);
+        let frames = frames
+            .iter()
+            .map(|(name, size)| (name.to_lowercase(), FrameSize { size: *size, dynamic: false }))
+            .collect::<FxHashMap<_, _>>();
+        StackGraph::new([&unit], &index, &annotations).analyze(&frames)
+    }
+
+    #[test]
+    fn stack_usage_report_is_parsed() {
+        let frames = parse_stack_usage(
+            "/tmp/main.st:3:mainProg\t48\tstatic\nmain.st:fb__m\t16\tdynamic\ninvalid line\n",
+        );
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames["mainprog"], FrameSize { size: 48, dynamic: false });
+        assert_eq!(frames["fb__m"], FrameSize { size: 16, dynamic: true });
+    }
+
+    #[test]
+    fn deepest_call_path_of_a_program_is_found() {
+        let usage = analyze(
+            "
+            FUNCTION small : INT
+            END_FUNCTION
+
+            FUNCTION large : INT
+                small();
+            END_FUNCTION
+
+            FUNCTION_BLOCK fb
+            METHOD m : INT
+                large();
+            END_METHOD
+                small();
+            END_FUNCTION_BLOCK
+
+            PROGRAM mainProg
+            VAR inst : fb; END_VAR
+                inst();
+                inst.m();
+            END_PROGRAM
+            ",
+            &[("small", 100), ("large", 40), ("fb", 8), ("fb__m", 16), ("mainProg", 32)],
+        );
+
+        let program = &usage.programs[0];
+        assert_eq!(program.name, "mainProg");
+        // mainProg -> fb.m -> large -> small is deeper than mainProg -> fb -> small
+        assert_eq!(program.stack_size, Some(32 + 16 + 40 + 100));
+        assert_eq!(program.path, ["mainProg", "fb.m", "large", "small"]);
+        assert!(program.recursion.is_empty());
+        assert!(program.unknown.is_empty());
+        assert!(usage.check_limit(188).is_empty());
+        assert_eq!(usage.check_limit(187)[0].get_error_code(), "E154");
+    }
+
+    #[test]
+    fn recursion_makes_stack_usage_unbounded() {
+        let usage = analyze(
+            "
+            FUNCTION ext : INT
+            END_FUNCTION
+
+            FUNCTION fact : DINT
+            VAR_INPUT n : DINT; END_VAR
+                IF n > 1 THEN
+                    fact := n * fact(n - 1);
+                END_IF
+            END_FUNCTION
+
+            PROGRAM mainProg
+                fact(5);
+                ext();
+            END_PROGRAM
+            ",
+            &[("fact", 32), ("mainProg", 16)],
+        );
+
+        let program = &usage.programs[0];
+        assert_eq!(program.stack_size, None);
+        assert_eq!(program.recursion, ["fact", "fact"]);
+        assert_eq!(program.unknown, ["ext"]);
+        let diagnostics = usage.check_limit(u64::MAX);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].get_message().contains("unbounded"));
+    }
+}
@@ -0,0 +1,585 @@
+//! Symbol table of every variable an HMI or SCADA system can access at runtime.
+//!
+//! Unlike the [hardware map](crate::hw_map), which only covers hardware-bound variables, the symbol
+//! table lists every global variable and every member of a program instance, down to the members
+//! of nested structs, function block instances and array elements. Every entry names the global
+//! symbol holding the variable and the byte offset and size of the variable within it, computed
+//! with the [`DataLayout`](crate::datalayout::DataLayout) of the index.
+//!
+//! # Wire format
+//!
+//! ```json
+//! {
+//!   "symbols": [
+//!     {
+//!       "name": "main.motor.speed",
+//!       "type": "INT",
+//!       "symbol": "main_instance",
+//!       "section": null,
+//!       "offset": 8,
+//!       "size": 2,
+//!       "direction": "Input",
+//!       "access": "ReadWrite",
+//!       "constant": false,
+//!       "retain": false,
+//!       "persistent": false
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! The XML format carries the same fields as attributes of a `<Symbol>` element, the `section`
+//! attribute is left out if the symbol is in the default section.
+//!
+//! # Access
+//!
+//! Variables are readable and writable unless they are constant. The `{attribute 'symbol' := ...}`
+//! pragma in front of a variable or a POU restricts the access of the variable or of all variables
+//! declared in the POU: `'read'` exports them read-only, `'readwrite'` exports them writable again
+//! and `'none'` excludes them together with their members. Variables generated by the compiler are
+//! never exported, the members inherited from a base class are listed as members of the derived one.
+
+use std::fmt::Write;
+
+use plc_ast::ast::{AutoDerefType, CompilationUnit};
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::source_location::SourceLocation;
+use plc_util::convention::retain_variable_name;
+use serde::Serialize;
+
+use crate::{
+    expression_path::{ExpressionPath, ExpressionPathElement},
+    index::{Index, PouIndexEntry, VariableIndexEntry, VariableType},
+    typesystem::DataTypeInformation,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolTable {
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Symbol {
+    /// the qualified path of the variable, e.g. `main.motor.speed` or `positions[2].x`
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// the global symbol holding the variable
+    pub symbol: String,
+    /// the linker section of the symbol, `None` for the default section
+    pub section: Option<String>,
+    /// the offset of the variable from the start of the symbol in bytes
+    pub offset: u32,
+    pub size: u32,
+    pub direction: SymbolDirection,
+    pub access: SymbolAccess,
+    pub constant: bool,
+    pub retain: bool,
+    pub persistent: bool,
+}
+
+/// The block a variable is declared in
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum SymbolDirection {
+    Input,
+    Output,
+    InOut,
+    Local,
+    Global,
+}
+
+impl From<VariableType> for SymbolDirection {
+    fn from(variable_type: VariableType) -> Self {
+        match variable_type {
+            VariableType::Input => Self::Input,
+            VariableType::Output => Self::Output,
+            VariableType::InOut => Self::InOut,
+            VariableType::Global | VariableType::External => Self::Global,
+            VariableType::Local | VariableType::Temp | VariableType::Return | VariableType::Property => {
+                Self::Local
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum SymbolAccess {
+    Read,
+    ReadWrite,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SymbolsFormat {
+    Json,
+    Xml,
+}
+
+impl SymbolsFormat {
+    /// Returns the format of a symbols file by the extension of its name
+    pub fn from_file_name(name: &str) -> Option<SymbolsFormat> {
+        match name.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("json") => Some(SymbolsFormat::Json),
+            Some("xml") => Some(SymbolsFormat::Xml),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the symbols of all global variables and program instances. The `symbol` attributes
+/// restricting the access are taken from the given units.
+pub fn collect_symbols<'a>(
+    index: &Index,
+    units: impl IntoIterator<Item = &'a CompilationUnit>,
+) -> Result<SymbolTable, Diagnostic> {
+    let attributes = units
+        .into_iter()
+        .flat_map(|it| &it.attributes)
+        .filter(|it| it.name.eq_ignore_ascii_case("symbol"))
+        .map(|it| {
+            let access = match it.value.as_deref().map(str::to_lowercase).as_deref() {
+                Some("none") => None,
+                Some("read") => Some(SymbolAccess::Read),
+                Some("readwrite") => Some(SymbolAccess::ReadWrite),
+                _ => {
+                    return Err(Diagnostic::new(format!(
+                        "Invalid value {:?} of attribute 'symbol', expected 'none', 'read' or 'readwrite'",
+                        it.value.as_deref().unwrap_or_default()
+                    ))
+                    .with_error_code("E169")
+                    .with_location(&it.location))
+                }
+            };
+            Ok((&it.location, access))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let collector = SymbolCollector { index, attributes };
+    let mut symbols = vec![];
+    for (path, _) in index.find_instances() {
+        symbols.extend(collector.collect(&path)?);
+    }
+    Ok(SymbolTable { symbols })
+}
+
+struct SymbolCollector<'a> {
+    index: &'a Index,
+    attributes: Vec<(&'a SourceLocation, Option<SymbolAccess>)>,
+}
+
+impl SymbolCollector<'_> {
+    /// Returns the access given by the `symbol` attribute of the declaration at the given location,
+    /// `Some(None)` if the declaration is not exported
+    fn get_access(&self, location: &SourceLocation) -> Option<Option<SymbolAccess>> {
+        self.attributes.iter().find(|(it, _)| *it == location).map(|(_, access)| *access)
+    }
+
+    /// Returns the symbols of the instance at the given path, one per array element if the path
+    /// leads through arrays
+    fn collect(&self, path: &ExpressionPath) -> Result<Vec<Symbol>, Diagnostic> {
+        let index = self.index;
+        let layout = index.get_type_layout();
+        let mut elements = path.get_elements().iter();
+        let Some(ExpressionPathElement::Name(root)) = elements.next() else { return Ok(vec![]) };
+        let Some(mut variable) = find_root(index, root).filter(|_| !root.starts_with("__")) else {
+            return Ok(vec![]);
+        };
+        let Some(mut access) =
+            self.get_access(&variable.source_location).unwrap_or(Some(SymbolAccess::ReadWrite))
+        else {
+            return Ok(vec![]);
+        };
+        let mut symbol = variable.get_name().to_string();
+        let mut section = get_section(index, variable);
+        let mut retain = variable.is_retain();
+        let mut persistent = variable.is_persistent();
+        let mut type_name = variable.get_type_name();
+        let mut names = vec![(root.to_string(), 0)];
+
+        for element in elements {
+            match element {
+                ExpressionPathElement::Name(name) => {
+                    let container = type_name;
+                    let Some(DataTypeInformation::Struct { members, .. }) =
+                        index.find_effective_type_info(container)
+                    else {
+                        return Ok(vec![]);
+                    };
+                    let struct_layout = layout.get_struct_layout(members, index).map_err(to_diagnostic)?;
+                    // temporary variables are not stored in the instance
+                    let Some((member, offset)) = struct_layout
+                        .offsets
+                        .into_iter()
+                        .find(|(it, _)| it.get_name().eq_ignore_ascii_case(name))
+                    else {
+                        return Ok(vec![]);
+                    };
+                    let is_base = name.eq_ignore_ascii_case(&format!("__{}", member.get_type_name()));
+                    if name.starts_with("__") && !is_base {
+                        return Ok(vec![]);
+                    }
+                    let Some(member_access) = self
+                        .get_access(&member.source_location)
+                        .or_else(|| {
+                            index.find_pou(container).and_then(|it| self.get_access(it.get_location()))
+                        })
+                        .unwrap_or(Some(access))
+                    else {
+                        return Ok(vec![]);
+                    };
+                    access = member_access;
+
+                    if let Some(global) = find_retain_global(index, container, member) {
+                        // the value of a retain variable of a program is moved to a global variable
+                        symbol = global.get_name().to_string();
+                        section = get_section(index, global);
+                        retain = global.is_retain();
+                        persistent = global.is_persistent();
+                        type_name = global.get_type_name();
+                        for (path, offset) in &mut names {
+                            path.push('.');
+                            path.push_str(member.get_name());
+                            *offset = 0;
+                        }
+                    } else {
+                        type_name = member.get_type_name();
+                        for (path, member_offset) in &mut names {
+                            // the members of the base class are accessed like the ones of the derived class
+                            if !is_base {
+                                path.push('.');
+                                path.push_str(member.get_name());
+                            }
+                            *member_offset += offset.value();
+                        }
+                    }
+                    retain |= member.is_retain();
+                    persistent |= member.is_persistent();
+                    variable = member;
+                }
+                ExpressionPathElement::ArrayDimensions(dimensions) => {
+                    let Some(DataTypeInformation::Array { inner_type_name, .. }) =
+                        index.find_effective_type_info(type_name)
+                    else {
+                        return Ok(vec![]);
+                    };
+                    let inner_type = index.get_type_information_or_void(inner_type_name);
+                    let element_size = layout.get_size(inner_type, index).map_err(to_diagnostic)?.value();
+                    let ranges = dimensions
+                        .iter()
+                        .map(|it| it.get_range_inclusive(index))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|it| Diagnostic::new(it).with_error_code("E002"))?;
+                    // the elements are stored row by row, i.e. the last index changes fastest
+                    let mut elements = vec![(vec![], 0)];
+                    for range in ranges {
+                        let length = (range.end() - range.start() + 1).max(0) as u32;
+                        elements = elements
+                            .into_iter()
+                            .flat_map(|(indices, position): (Vec<i64>, u32)| {
+                                range.clone().enumerate().map(move |(i, it)| {
+                                    let mut indices = indices.clone();
+                                    indices.push(it);
+                                    (indices, position * length + i as u32)
+                                })
+                            })
+                            .collect();
+                    }
+                    names = names
+                        .into_iter()
+                        .flat_map(|(path, offset)| {
+                            elements.iter().map(move |(indices, position)| {
+                                let indices = indices.iter().map(ToString::to_string).collect::<Vec<_>>();
+                                (format!("{path}[{}]", indices.join(",")), offset + position * element_size)
+                            })
+                        })
+                        .collect();
+                    type_name = inner_type_name;
+                }
+                ExpressionPathElement::ArrayAccess(_) => return Ok(vec![]),
+            }
+        }
+
+        let information = index.get_type_information_or_void(type_name);
+        let size = layout.get_size(information, index).map_err(to_diagnostic)?.value();
+        let type_text = crate::doc::get_type_text(index, type_name);
+        let constant = variable.is_constant();
+        let access = if constant { SymbolAccess::Read } else { access };
+        Ok(names
+            .into_iter()
+            .map(|(name, offset)| Symbol {
+                name,
+                type_name: type_text.clone(),
+                symbol: symbol.clone(),
+                section: section.map(str::to_string),
+                offset,
+                size,
+                direction: variable.get_variable_type().into(),
+                access,
+                constant,
+                retain,
+                persistent,
+            })
+            .collect())
+    }
+}
+
+/// Returns the section the code generator places the given global variable or program instance in
+fn get_section(index: &Index, variable: &VariableIndexEntry) -> Option<&'static str> {
+    variable.get_retain_section(index).map(|it| it.get_name())
+}
+
+/// Returns the global variable or program instance with the given name
+fn find_root<'idx>(index: &'idx Index, name: &str) -> Option<&'idx VariableIndexEntry> {
+    index.find_global_variable(name).or_else(|| match index.find_pou(name) {
+        Some(PouIndexEntry::Program { instance_variable, .. }) => Some(instance_variable.as_ref()),
+        _ => None,
+    })
+}
+
+/// Returns the global variable holding the value of a retain variable of a program, the member in
+/// the program instance only points to it
+fn find_retain_global<'idx>(
+    index: &'idx Index,
+    container: &str,
+    member: &VariableIndexEntry,
+) -> Option<&'idx VariableIndexEntry> {
+    let information = index.find_effective_type_info(member.get_type_name())?;
+    if !matches!(information, DataTypeInformation::Pointer { auto_deref: Some(AutoDerefType::Alias), .. }) {
+        return None;
+    }
+    index.find_global_variable(&retain_variable_name(container, member.get_name()))
+}
+
+fn to_diagnostic(error: anyhow::Error) -> Diagnostic {
+    Diagnostic::new(error.to_string()).with_error_code("E002")
+}
+
+pub fn serialize_symbols(table: &SymbolTable, format: SymbolsFormat) -> Result<String, Diagnostic> {
+    match format {
+        SymbolsFormat::Json => serde_json::to_string_pretty(table).map_err(|e| {
+            Diagnostic::new(e.to_string()).with_error_code("E002").with_internal_error(e.into())
+        }),
+        SymbolsFormat::Xml => {
+            let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Symbols>\n");
+            for symbol in &table.symbols {
+                let _ = write!(
+                    xml,
+                    "  <Symbol name=\"{}\" type=\"{}\" symbol=\"{}\"",
+                    escape_xml(&symbol.name),
+                    escape_xml(&symbol.type_name),
+                    escape_xml(&symbol.symbol)
+                );
+                if let Some(section) = &symbol.section {
+                    let _ = write!(xml, " section=\"{}\"", escape_xml(section));
+                }
+                let _ = writeln!(
+                    xml,
+                    " offset=\"{}\" size=\"{}\" direction=\"{:?}\" access=\"{:?}\" constant=\"{}\" retain=\"{}\" persistent=\"{}\" />",
+                    symbol.offset,
+                    symbol.size,
+                    symbol.direction,
+                    symbol.access,
+                    symbol.constant,
+                    symbol.retain,
+                    symbol.persistent
+                );
+            }
+            xml.push_str("</Symbols>\n");
+            Ok(xml)
+        }
+    }
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_symbols, serialize_symbols, Symbol, SymbolAccess, SymbolDirection, SymbolsFormat};
+    use crate::test_utils::tests::index;
+
+    fn symbols(src: &str) -> Vec<Symbol> {
+        let (unit, index) = index(src);
+        collect_symbols(&index, [&unit]).unwrap().symbols
+    }
+
+    fn get<'a>(symbols: &'a [Symbol], name: &str) -> &'a Symbol {
+        symbols.iter().find(|it| it.name == name).unwrap_or_else(|| panic!("{name} not found"))
+    }
+
+    #[test]
+    fn members_of_program_instances_have_offsets_into_the_instance() {
+        let symbols = symbols(
+            "
+            TYPE Point : STRUCT
+                x : INT;
+                y : DINT;
+            END_STRUCT
+            END_TYPE
+
+            PROGRAM main
+            VAR_INPUT
+                enable : BOOL;
+            END_VAR
+            VAR
+                position : Point;
+            END_VAR
+            VAR_TEMP
+                tmp : INT;
+            END_VAR
+            END_PROGRAM
+            ",
+        );
+
+        let names = symbols.iter().map(|it| it.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["main", "main.enable", "main.position", "main.position.x", "main.position.y"]);
+
+        let enable = get(&symbols, "main.enable");
+        assert_eq!((enable.symbol.as_str(), enable.offset, enable.size), ("main_instance", 0, 1));
+        assert_eq!(enable.direction, SymbolDirection::Input);
+        assert_eq!(enable.type_name, "BOOL");
+        let position = get(&symbols, "main.position");
+        assert_eq!((position.offset, position.size, position.type_name.as_str()), (4, 8, "Point"));
+        assert_eq!(position.direction, SymbolDirection::Local);
+        assert_eq!(get(&symbols, "main.position.y").offset, 8);
+        assert_eq!(get(&symbols, "main").size, 12);
+    }
+
+    #[test]
+    fn array_elements_are_listed_row_by_row() {
+        let symbols = symbols(
+            "
+            FUNCTION_BLOCK fb
+            VAR
+                id : DINT;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            VAR_GLOBAL
+                items : ARRAY[1..2, 0..1] OF fb;
+            END_VAR
+            ",
+        );
+
+        let items = get(&symbols, "items");
+        assert_eq!((items.symbol.as_str(), items.size), ("items", 16));
+        assert_eq!(items.type_name, "ARRAY[1..2, 0..1] OF fb");
+        let offsets = symbols.iter().skip(1).map(|it| (it.name.as_str(), it.offset)).collect::<Vec<_>>();
+        assert_eq!(
+            offsets,
+            [("items[1,0].id", 0), ("items[1,1].id", 4), ("items[2,0].id", 8), ("items[2,1].id", 12)]
+        );
+    }
+
+    #[test]
+    fn symbol_attributes_restrict_the_access() {
+        let symbols = symbols(
+            "
+            {attribute 'symbol' := 'read'}
+            PROGRAM main
+            VAR
+                visible : INT;
+                {attribute 'symbol' := 'readwrite'}
+                writable : INT;
+                {attribute 'symbol' := 'none'}
+                hidden : ARRAY[0..1] OF INT;
+            END_VAR
+            END_PROGRAM
+
+            VAR_GLOBAL CONSTANT
+                limit : INT := 10;
+            END_VAR
+            VAR_GLOBAL RETAIN
+                counter : DINT;
+            END_VAR
+            ",
+        );
+
+        assert_eq!(get(&symbols, "main.visible").access, SymbolAccess::Read);
+        assert_eq!(get(&symbols, "main.writable").access, SymbolAccess::ReadWrite);
+        assert!(!symbols.iter().any(|it| it.name.starts_with("main.hidden")));
+
+        let limit = get(&symbols, "limit");
+        assert!(limit.constant);
+        assert_eq!(limit.access, SymbolAccess::Read);
+        let counter = get(&symbols, "counter");
+        assert!(counter.retain);
+        assert_eq!(counter.section.as_deref(), Some(".retain"));
+    }
+
+    #[test]
+    fn persistent_variables_are_in_the_persistent_section() {
+        let symbols = symbols(
+            "
+            FUNCTION_BLOCK fb
+            VAR
+                a : INT;
+            END_VAR
+            VAR PERSISTENT
+                b : INT;
+            END_VAR
+            END_FUNCTION_BLOCK
+
+            VAR_GLOBAL PERSISTENT
+                counter : DINT;
+            END_VAR
+            VAR_GLOBAL RETAIN PERSISTENT
+                total : DINT;
+            END_VAR
+            VAR_GLOBAL
+                instance : fb;
+            END_VAR
+            ",
+        );
+
+        let counter = get(&symbols, "counter");
+        assert!(!counter.retain && counter.persistent);
+        assert_eq!(counter.section.as_deref(), Some(".persistent"));
+        let total = get(&symbols, "total");
+        assert!(total.retain && total.persistent);
+        assert_eq!(total.section.as_deref(), Some(".retain_persistent"));
+
+        // an instance containing persistent members is placed into the persistent section as a whole
+        let a = get(&symbols, "instance.a");
+        assert!(!a.retain && !a.persistent);
+        assert_eq!(a.section.as_deref(), Some(".persistent"));
+        let b = get(&symbols, "instance.b");
+        assert!(!b.retain && b.persistent);
+    }
+
+    #[test]
+    fn invalid_symbol_attribute_is_reported() {
+        let (unit, index) = index(
+            "
+            VAR_GLOBAL
+                {attribute 'symbol' := 'write'}
+                x : INT;
+            END_VAR
+            ",
+        );
+        let diagnostic = collect_symbols(&index, [&unit]).unwrap_err();
+        assert_eq!(diagnostic.get_error_code(), "E169");
+    }
+
+    #[test]
+    fn symbols_are_serialized_as_xml() {
+        let (unit, index) = index(
+            "
+            VAR_GLOBAL RETAIN
+                flags : ARRAY[0..1] OF BOOL;
+            END_VAR
+            ",
+        );
+        let table = collect_symbols(&index, [&unit]).unwrap();
+        assert_eq!(
+            serialize_symbols(&table, SymbolsFormat::Xml).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<Symbols>
+  <Symbol name=\"flags\" type=\"ARRAY[0..1] OF BOOL\" symbol=\"flags\" section=\".retain\" offset=\"0\" size=\"2\" direction=\"Global\" access=\"ReadWrite\" constant=\"false\" retain=\"true\" persistent=\"false\" />
+</Symbols>
+"
+        );
+        let json = serialize_symbols(&table, SymbolsFormat::Json).unwrap();
+        assert!(json.contains("\"type\": \"ARRAY[0..1] OF BOOL\""));
+    }
+}
@@ -14,7 +14,9 @@ pub mod tests {
         provider::IdProvider,
     };
     use plc_diagnostics::{
-        diagnostician::Diagnostician, diagnostics::Diagnostic, reporter::DiagnosticReporter,
+        diagnostician::Diagnostician,
+        diagnostics::{lints::LintSettings, Diagnostic},
+        reporter::DiagnosticReporter,
     };
     use plc_index::GlobalContext;
 
@@ -228,6 +230,64 @@ pub mod tests {
         diagnostics
     }
 
+    /// Parses, indexes and annotates the source for the validation helpers below, lowering its loops like in
+    /// the pipeline if `desugar_loops` is set
+    fn annotate_for_validation(
+        src: &str,
+        desugar_loops: bool,
+    ) -> (GlobalContext, CompilationUnit, Index, AnnotationMapImpl) {
+        let src = SourceCode::from(src);
+
+        let mut ctxt = GlobalContext::new();
+        ctxt.insert(&src, None).unwrap();
+
+        let (mut unit, index, _) = do_index(src, ctxt.provider());
+        if desugar_loops {
+            LoopDesugarer::new(ctxt.provider()).desugar(std::slice::from_mut(&mut unit));
+        }
+        let (mut index, ..) = evaluate_constants(index);
+        let (mut annotations, ..) = TypeAnnotator::visit_unit(&index, &unit, ctxt.provider());
+        index.import(std::mem::take(&mut annotations.new_index));
+
+        (ctxt, unit, index, annotations)
+    }
+
+    /// Runs only the lint rules with the given settings on the source
+    pub fn parse_and_lint(src: &str, settings: LintSettings) -> Vec<Diagnostic> {
+        let (ctxt, unit, index, annotations) = annotate_for_validation(src, false);
+
+        let mut validator = Validator::new(&ctxt).with_lint_settings(settings);
+        validator.perform_lint_validation(&annotations, &index, &[&unit]);
+        validator.diagnostics()
+    }
+
+    /// Runs only the reachability validation on the source
+    pub fn parse_and_validate_reachability(src: &str) -> Vec<Diagnostic> {
+        let (ctxt, unit, index, annotations) = annotate_for_validation(src, false);
+
+        let mut validator = Validator::new(&ctxt);
+        validator.perform_reachability_validation(&annotations, &index, &[&unit]);
+        validator.diagnostics()
+    }
+
+    /// Runs only the definite assignment analysis on the source, with its loops lowered like in the pipeline
+    pub fn parse_and_validate_definite_assignment(src: &str) -> Vec<Diagnostic> {
+        let (ctxt, unit, index, annotations) = annotate_for_validation(src, true);
+
+        let mut validator = Validator::new(&ctxt);
+        validator.perform_definite_assignment_validation(&annotations, &index, &[&unit]);
+        validator.diagnostics()
+    }
+
+    /// Validates the source like [`parse_and_validate`], but with its loops lowered like in the pipeline
+    pub fn parse_and_validate_lowered_loops(src: &str) -> Vec<Diagnostic> {
+        let (ctxt, unit, index, annotations) = annotate_for_validation(src, true);
+
+        let mut validator = Validator::new(&ctxt);
+        validator.visit_unit(&annotations, &index, &unit);
+        validator.diagnostics()
+    }
+
     pub fn codegen_without_unwrap(src: &str) -> Result<String, String> {
         codegen_debug_without_unwrap(src, DebugLevel::None)
     }
@@ -69,6 +69,7 @@ fn programs_state_is_stored_in_a_struct() {
                         is_constant: false,
                         is_var_external: false,
                         is_retain: false,
+                        is_persistent: false,
                         data_type_name: "INT",
                         location_in_parent: 0,
                         linkage: Internal,
@@ -91,6 +92,7 @@ fn programs_state_is_stored_in_a_struct() {
                         is_constant: false,
                         is_var_external: false,
                         is_retain: false,
+                        is_persistent: false,
                         data_type_name: "__auto_pointer_to_INT",
                         location_in_parent: 1,
                         linkage: Internal,
@@ -113,6 +115,7 @@ fn programs_state_is_stored_in_a_struct() {
                         is_constant: false,
                         is_var_external: false,
                         is_retain: false,
+                        is_persistent: false,
                         data_type_name: "INT",
                         location_in_parent: 2,
                         linkage: Internal,
@@ -135,6 +138,7 @@ fn programs_state_is_stored_in_a_struct() {
                         is_constant: false,
                         is_var_external: false,
                         is_retain: false,
+                        is_persistent: false,
                         data_type_name: "INT",
                         location_in_parent: 3,
                         linkage: Internal,
@@ -157,6 +161,7 @@ fn programs_state_is_stored_in_a_struct() {
                         is_constant: false,
                         is_var_external: false,
                         is_retain: false,
+                        is_persistent: false,
                         data_type_name: "INT",
                         location_in_parent: 4,
                         linkage: Internal,
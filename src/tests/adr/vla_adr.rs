@@ -45,6 +45,7 @@ fn representation() {
                     is_constant: false,
                     is_var_external: false,
                     is_retain: false,
+                    is_persistent: false,
                     data_type_name: "__ptr_to___foo_arr_vla_1_dint",
                     location_in_parent: 0,
                     linkage: Internal,
@@ -64,6 +65,7 @@ fn representation() {
                     is_constant: false,
                     is_var_external: false,
                     is_retain: false,
+                    is_persistent: false,
                     data_type_name: "__bounds___foo_arr_vla_1_dint",
                     location_in_parent: 1,
                     linkage: Internal,
@@ -275,6 +277,7 @@ fn pass() {
                 is_constant: false,
                 is_var_external: false,
                 is_retain: false,
+                is_persistent: false,
                 data_type_name: "__ptr_to___foo_arr_vla_1_dint",
                 location_in_parent: 0,
                 linkage: Internal,
@@ -294,6 +297,7 @@ fn pass() {
                 is_constant: false,
                 is_var_external: false,
                 is_retain: false,
+                is_persistent: false,
                 data_type_name: "__bounds___foo_arr_vla_1_dint",
                 location_in_parent: 1,
                 linkage: Internal,
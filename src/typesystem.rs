@@ -99,7 +99,6 @@ pub const __VLA_TYPE: &str = "__VLA";
 mod tests;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub struct DataType {
     pub name: String,
     /// the initial value defined on the TYPE-declaration
@@ -307,9 +306,15 @@ impl DataType {
         }
     }
 
-    pub(crate) fn should_retain(&self, index: &Index, visited: &mut FxHashSet<String>) -> bool {
-        // A datatype should be retained if one of its members is retain or if it is transitively containing a retain variable
-        self.get_type_information().should_retain(index, visited)
+    /// Returns true if one of the members of this datatype matches the predicate, e.g. is retain,
+    /// or if it transitively contains such a variable
+    pub(crate) fn contains_variable(
+        &self,
+        index: &Index,
+        visited: &mut FxHashSet<String>,
+        predicate: fn(&VariableIndexEntry) -> bool,
+    ) -> bool {
+        self.get_type_information().contains_variable(index, visited, predicate)
     }
 }
 
@@ -429,7 +434,6 @@ pub enum InternalType {
 type TypeId = String;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum DataTypeInformation {
     Struct {
         name: TypeId,
@@ -924,19 +928,24 @@ impl DataTypeInformation {
         }
     }
 
-    fn should_retain(&self, index: &Index, visited: &mut FxHashSet<String>) -> bool {
+    fn contains_variable(
+        &self,
+        index: &Index,
+        visited: &mut FxHashSet<String>,
+        predicate: fn(&VariableIndexEntry) -> bool,
+    ) -> bool {
         if !visited.insert(self.get_name().to_string()) {
             return false;
         }
         // A datatype should be retained if one of its members is retain or if it is transitively containing a retain variable
         let res = match self {
             DataTypeInformation::Struct { members, .. } => {
-                members.iter().any(|member| member.should_retain_recursive(index, visited))
+                members.iter().any(|member| member.matches_recursive(index, visited, predicate))
             }
             DataTypeInformation::Array { inner_type_name, .. }
             | DataTypeInformation::Alias { referenced_type: inner_type_name, .. } => {
                 let inner_type_info = index.get_type_information_or_void(inner_type_name);
-                inner_type_info.should_retain(index, visited)
+                inner_type_info.contains_variable(index, visited, predicate)
             }
             _ => false,
         };
@@ -1,6 +1,6 @@
-use plc_ast::ast::{AstNode, CompilationUnit, DirectAccessType};
+use plc_ast::ast::{AstNode, CompilationUnit, DirectAccessType, PouType};
 use plc_derive::Validators;
-use plc_diagnostics::diagnostics::Diagnostic;
+use plc_diagnostics::diagnostics::{lints::LintSettings, Diagnostic};
 use plc_index::GlobalContext;
 use plc_source::source_location::SourceLocation;
 use property::visit_property;
@@ -8,6 +8,7 @@ use rustc_hash::FxHashMap;
 use variable::visit_config_variable;
 
 use crate::{
+    call_graph::{find_unreachable, Unreachable},
     expression_path::ExpressionPath,
     index::{
         const_expressions::{ConstExpression, UnresolvableKind},
@@ -26,7 +27,9 @@ use self::{
 };
 
 mod array;
+mod definite_assignment;
 mod global;
+mod lint;
 mod pou;
 mod property;
 mod recursive;
@@ -106,6 +109,7 @@ pub struct Validator<'a> {
     diagnostics: Vec<Diagnostic>,
     global_validator: GlobalValidator,
     recursive_validator: RecursiveValidator,
+    lint_settings: LintSettings,
 }
 
 impl Validators for Validator<'_> {
@@ -132,9 +136,15 @@ impl<'a> Validator<'a> {
             diagnostics: Vec::new(),
             global_validator: GlobalValidator::new(),
             recursive_validator: RecursiveValidator::new(),
+            lint_settings: LintSettings::default(),
         }
     }
 
+    pub fn with_lint_settings(mut self, lint_settings: LintSettings) -> Self {
+        self.lint_settings = lint_settings;
+        self
+    }
+
     pub fn get_type_name_or_slice(&self, dt: &DataType) -> String {
         if dt.is_internal() {
             return dt.get_type_information().get_inner_name().to_string();
@@ -171,6 +181,64 @@ impl<'a> Validator<'a> {
         }
     }
 
+    /// Reports POUs and types that are not reachable from any entry point of the project. Needs all
+    /// units of the project, since any of them may use a declaration of another one.
+    pub fn perform_reachability_validation<T: AnnotationMap>(
+        &mut self,
+        annotations: &T,
+        index: &Index,
+        units: &[&CompilationUnit],
+    ) {
+        for unreachable in find_unreachable(units, index, annotations) {
+            let (kind, name, location) = match unreachable {
+                Unreachable::Pou(pou) => {
+                    let kind = match pou.kind {
+                        PouType::FunctionBlock => "Function block",
+                        PouType::Class => "Class",
+                        PouType::Method { .. } => "Method",
+                        _ => "Function",
+                    };
+                    (kind, pou.name.as_str(), &pou.name_location)
+                }
+                Unreachable::Action(action) => ("Action", action.name.as_str(), &action.name_location),
+                Unreachable::Type(user_type) => {
+                    ("Type", user_type.data_type.get_name().unwrap_or_default(), &user_type.location)
+                }
+            };
+            self.push_diagnostic(
+                Diagnostic::new(format!(
+                    "{kind} `{name}` is unreachable from any program or external entry point"
+                ))
+                .with_error_code("E153")
+                .with_location(location),
+            );
+        }
+    }
+
+    /// Runs the lint rules on the given units, see [`lint`]. Like reachability, the usage of a
+    /// variable depends on all units of the project.
+    pub fn perform_lint_validation<T: AnnotationMap>(
+        &mut self,
+        annotations: &T,
+        index: &Index,
+        units: &[&CompilationUnit],
+    ) {
+        let settings = std::mem::take(&mut self.lint_settings);
+        lint::visit_units(self, &settings, annotations, index, units);
+        self.lint_settings = settings;
+    }
+
+    /// Reports unassigned return values, outputs and temporaries, see [`definite_assignment`]. An output
+    /// may be written from any unit of the project.
+    pub fn perform_definite_assignment_validation<T: AnnotationMap>(
+        &mut self,
+        annotations: &T,
+        index: &Index,
+        units: &[&CompilationUnit],
+    ) {
+        definite_assignment::visit_units(self, annotations, index, units);
+    }
+
     pub fn visit_unit<T: AnnotationMap>(&mut self, annotations: &T, index: &Index, unit: &CompilationUnit) {
         let context =
             ValidationContext { annotations, index, qualifier: None, is_call: false, is_cast: false };
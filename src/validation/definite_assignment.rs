@@ -0,0 +1,668 @@
+//! Definite assignment analysis of the bodies of a project.
+//!
+//! The statements of a body are turned into a control flow graph which is walked by a forward data-flow
+//! analysis, tracking which variables are assigned on every path reaching a statement. The bodies are
+//! analyzed after lowering, so loops are usually the canonical `WHILE TRUE` loops left behind by
+//! `plc_lowering::loops`, with `EXIT`, `CONTINUE`, `RETURN` and the jumps of CFC bodies as the only other
+//! edges. The guard of a lowered `FOR` or `REPEAT` loop is skipped by the first iteration, so the body of
+//! a `REPEAT` loop is known to run at least once. The analysis reports:
+//! - `E160` return values which are not assigned on every path through a function or method
+//! - `E161` outputs which are never written, neither by their POU nor by anyone else
+//! - `E162` temporary variables which may be read before they are assigned
+//!
+//! Bodies without any statements, e.g. methods implementing an interface which are not implemented yet, and
+//! classes declaring parameters, which are rejected with `E019`, are not analyzed.
+//!
+//! A variable counts as assigned when it, one of its members or one of its elements is written, when it
+//! is passed to an output or in-out parameter or when its address is taken.
+
+use plc_ast::{
+    ast::{
+        flatten_expression_list, Assignment, AstNode, AstStatement, CallStatement, CompilationUnit,
+        Implementation, LinkageType, Operator, Pou, PouType, ReferenceAccess, ReferenceExpr, UnaryExpression,
+        VariableBlockType,
+    },
+    control_statements::{AstControlStatement, ForLoopStatement, ReturnStatement},
+    literals::AstLiteral,
+    visitor::AstVisitor,
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::source_location::SourceLocation;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    index::{Index, VariableIndexEntry, VariableType},
+    resolver::{AnnotationMap, StatementAnnotation},
+};
+
+use super::{pou::is_class_with_parameters, Validator, Validators};
+
+pub(super) fn visit_units<T: AnnotationMap>(
+    validator: &mut Validator,
+    annotations: &T,
+    index: &Index,
+    units: &[&CompilationUnit],
+) {
+    let implementations = units
+        .iter()
+        .flat_map(|it| &it.implementations)
+        .filter(|it| !it.linkage.is_external_or_included())
+        .map(|it| (it.name.to_lowercase(), it))
+        .collect::<FxHashMap<_, _>>();
+
+    // an output may be written by any body of the project, e.g. `instance.out := TRUE;`
+    let mut writes = AccessVisitor::new(annotations, index, &[]);
+    for implementation in implementations.values() {
+        writes.visit_statement_list(&implementation.statements);
+    }
+    let written = writes.written;
+
+    let pous = units.iter().flat_map(|it| &it.pous).filter(|pou| {
+        pou.linkage == LinkageType::Internal
+            && pou.generics.is_empty()
+            && !is_generated(&pou.name, &pou.location)
+            && !matches!(
+                pou.kind,
+                PouType::Method { property: Some(_), .. } | PouType::Init | PouType::ProjectInit
+            )
+    });
+    for pou in pous {
+        let Some(implementation) = implementations.get(&pou.name.to_lowercase()) else {
+            continue;
+        };
+        // stubs, e.g. methods implementing an interface, are not implemented yet and the parameters of
+        // classes are already rejected
+        if implementation.statements.iter().all(AstNode::is_empty_statement) || is_class_with_parameters(pou)
+        {
+            continue;
+        }
+        validate_outputs(validator, pou, &written);
+        validate_body(validator, annotations, index, pou, implementation, &written);
+    }
+}
+
+fn is_generated(name: &str, location: &SourceLocation) -> bool {
+    location.is_internal() || name.starts_with("__")
+}
+
+fn get_qualified_name(pou: &Pou, variable: &str) -> String {
+    format!("{}.{variable}", pou.name).to_lowercase()
+}
+
+fn validate_outputs(validator: &mut Validator, pou: &Pou, written: &FxHashSet<String>) {
+    let outputs = pou
+        .variable_blocks
+        .iter()
+        .filter(|it| it.kind == VariableBlockType::Output)
+        .flat_map(|it| &it.variables)
+        .filter(|it| !is_generated(&it.name, &it.location) && it.initializer.is_none());
+    for output in outputs {
+        if !written.contains(&get_qualified_name(pou, &output.name)) {
+            validator.push_diagnostic(
+                Diagnostic::new(format!("Output `{}` of `{}` is never written", output.name, pou.name))
+                    .with_error_code("E161")
+                    .with_location(&output.location),
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrackedKind {
+    Return,
+    Temp,
+}
+
+/// A variable whose assignment is tracked through a body
+#[derive(Debug)]
+struct Tracked<'a> {
+    name: &'a str,
+    /// the lowercase qualified name of the variable
+    qualified_name: String,
+    kind: TrackedKind,
+}
+
+fn validate_body<T: AnnotationMap>(
+    validator: &mut Validator,
+    annotations: &T,
+    index: &Index,
+    pou: &Pou,
+    implementation: &Implementation,
+    written: &FxHashSet<String>,
+) {
+    let mut tracked = Vec::new();
+    let has_return =
+        pou.return_type.is_some() && matches!(pou.kind, PouType::Function | PouType::Method { .. });
+    if has_return {
+        let qualified_name = get_qualified_name(pou, pou.get_return_name());
+        tracked.push(Tracked { name: pou.get_return_name(), qualified_name, kind: TrackedKind::Return });
+    }
+    // temporaries of a struct or function block type are initialized by their type
+    let temps = pou
+        .variable_blocks
+        .iter()
+        .filter(|it| it.kind == VariableBlockType::Temp)
+        .flat_map(|it| &it.variables)
+        .filter(|it| !is_generated(&it.name, &it.location) && it.initializer.is_none())
+        .filter(|it| {
+            it.data_type_declaration
+                .get_name()
+                .and_then(|name| index.find_effective_type_info(name))
+                .is_some_and(|info| !info.is_struct())
+        });
+    for temp in temps {
+        let qualified_name = get_qualified_name(pou, &temp.name);
+        tracked.push(Tracked { name: &temp.name, qualified_name, kind: TrackedKind::Temp });
+    }
+    if tracked.is_empty() {
+        return;
+    }
+
+    let graph = ControlFlowGraph::new(annotations, &implementation.statements);
+    let analysis = Analysis { annotations, index, tracked: &tracked, graph: &graph };
+    let states = analysis.solve();
+
+    // report the reads of temporaries, every read reached by an unassigned temporary is reported once
+    let mut reads = Vec::new();
+    for (node, state) in graph.nodes.iter().zip(&states) {
+        let Some(state) = state else {
+            continue;
+        };
+        let mut visitor = analysis.transfer(node, state.clone());
+        reads.append(&mut visitor.unassigned_reads);
+    }
+    let mut reported = FxHashSet::default();
+    for (variable, location) in reads {
+        if reported.insert(location.clone()) {
+            validator.push_diagnostic(
+                Diagnostic::new(format!(
+                    "Temporary variable `{}` may be read before it is assigned",
+                    tracked[variable].name
+                ))
+                .with_error_code("E162")
+                .with_location(&location),
+            );
+        }
+    }
+
+    // the state at the end of the body is the state of every path returning from it
+    let unassigned = states[graph.end].as_ref().is_some_and(|it| !it[0]);
+    if has_return && unassigned {
+        let message = if written.contains(&tracked[0].qualified_name) {
+            format!("Return value of `{}` is not assigned on every path", pou.name)
+        } else {
+            format!("Return value of `{}` is never assigned", pou.name)
+        };
+        validator.push_diagnostic(
+            Diagnostic::new(message).with_error_code("E160").with_location(&pou.name_location),
+        );
+    }
+}
+
+/// A step of the control flow graph
+#[derive(Debug)]
+enum Step<'a> {
+    /// evaluates an expression or a simple statement
+    Eval(&'a AstNode),
+    /// assigns the given reference, e.g. the counter of a `FOR` loop
+    Define(&'a AstNode),
+    /// a point where paths join without any effect, e.g. a label or the end of the body
+    Nop,
+}
+
+#[derive(Debug)]
+struct Node<'a> {
+    step: Step<'a>,
+    successors: Vec<usize>,
+}
+
+#[derive(Debug, Default)]
+struct LoopExits {
+    exits: Vec<usize>,
+    continues: Vec<usize>,
+}
+
+/// The control flow graph of a body, starting at its first node and ending at `end`
+struct ControlFlowGraph<'a> {
+    nodes: Vec<Node<'a>>,
+    end: usize,
+}
+
+struct GraphBuilder<'a, T: AnnotationMap> {
+    annotations: &'a T,
+    nodes: Vec<Node<'a>>,
+    /// the nodes continuing with the next node, empty if the next node is unreachable
+    open: Vec<usize>,
+    loops: Vec<LoopExits>,
+    returns: Vec<usize>,
+    /// the node of every label, keyed by its lowercase name
+    labels: FxHashMap<String, usize>,
+    /// the conditional jumps and the lowercase names of the labels they jump to
+    jumps: Vec<(usize, String)>,
+}
+
+impl<'a> ControlFlowGraph<'a> {
+    fn new<T: AnnotationMap>(annotations: &'a T, statements: &'a [AstNode]) -> Self {
+        let mut builder = GraphBuilder {
+            annotations,
+            nodes: Vec::new(),
+            open: Vec::new(),
+            loops: Vec::new(),
+            returns: Vec::new(),
+            labels: FxHashMap::default(),
+            jumps: Vec::new(),
+        };
+        builder.add(Step::Nop);
+        builder.lower_statements(statements);
+        builder.open.append(&mut builder.returns);
+        let end = builder.add(Step::Nop);
+
+        for (jump, label) in std::mem::take(&mut builder.jumps) {
+            if let Some(&target) = builder.labels.get(&label) {
+                builder.nodes[jump].successors.push(target);
+            }
+        }
+
+        ControlFlowGraph { nodes: builder.nodes, end }
+    }
+}
+
+impl<'a, T: AnnotationMap> GraphBuilder<'a, T> {
+    /// Adds a node continuing all open paths and returns its id
+    fn add(&mut self, step: Step<'a>) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(Node { step, successors: Vec::new() });
+        let open = std::mem::take(&mut self.open);
+        self.link(open, id);
+        self.open = vec![id];
+        id
+    }
+
+    fn link(&mut self, from: Vec<usize>, to: usize) {
+        for node in from {
+            self.nodes[node].successors.push(to);
+        }
+    }
+
+    fn lower_statements(&mut self, statements: &'a [AstNode]) {
+        for statement in statements {
+            self.lower_statement(statement);
+        }
+    }
+
+    fn lower_statement(&mut self, statement: &'a AstNode) {
+        match statement.get_stmt() {
+            AstStatement::ControlStatement(AstControlStatement::If(stmt)) => {
+                let mut ends = Vec::new();
+                for block in &stmt.blocks {
+                    self.add(Step::Eval(&block.condition));
+                    // a constant condition only takes one of the branches, e.g. the guard of a lowered loop
+                    let constant = get_constant_condition(&block.condition);
+                    let otherwise = if constant == Some(true) { Vec::new() } else { self.open.clone() };
+                    if constant == Some(false) {
+                        self.open.clear();
+                    }
+                    self.lower_statements(&block.body);
+                    ends.append(&mut self.open);
+                    self.open = otherwise;
+                }
+                self.lower_statements(&stmt.else_block);
+                self.open.append(&mut ends);
+            }
+            AstStatement::ControlStatement(AstControlStatement::Case(stmt)) => {
+                self.add(Step::Eval(&stmt.selector));
+                let selector = self.open.clone();
+                let mut ends = Vec::new();
+                for block in &stmt.case_blocks {
+                    self.open = selector.clone();
+                    self.lower_statements(&block.body);
+                    ends.append(&mut self.open);
+                }
+                self.open = selector;
+                self.lower_statements(&stmt.else_block);
+                self.open.append(&mut ends);
+            }
+            AstStatement::ControlStatement(AstControlStatement::WhileLoop(stmt)) => {
+                let head = self.add(Step::Eval(&stmt.condition));
+                let constant = get_constant_condition(&stmt.condition);
+                if constant == Some(false) {
+                    self.open.clear();
+                }
+                let exits = self.lower_loop_body(&stmt.body, head);
+                self.open = exits;
+                // the canonical `WHILE TRUE` loop is only left through `EXIT`
+                if constant != Some(true) {
+                    self.open.push(head);
+                }
+            }
+            AstStatement::ControlStatement(AstControlStatement::RepeatLoop(stmt)) => {
+                let head = self.add(Step::Nop);
+                self.loops.push(LoopExits::default());
+                self.lower_statements(&stmt.body);
+                let LoopExits { mut exits, mut continues } = self.loops.pop().unwrap_or_default();
+                self.open.append(&mut continues);
+                let condition = self.add(Step::Eval(&stmt.condition));
+                self.nodes[condition].successors.push(head);
+                self.open.append(&mut exits);
+            }
+            AstStatement::ControlStatement(AstControlStatement::ForLoop(ForLoopStatement {
+                counter,
+                start,
+                end,
+                by_step,
+                body,
+                ..
+            })) => {
+                self.add(Step::Eval(start));
+                self.add(Step::Define(counter));
+                let head = self.add(Step::Eval(end));
+                if let Some(step) = by_step {
+                    self.add(Step::Eval(step));
+                }
+                let check = self.open.clone();
+                let mut exits = self.lower_loop_body(body, head);
+                self.open = check;
+                self.open.append(&mut exits);
+            }
+            // statements replaced by lowering are grouped in a list
+            AstStatement::ExpressionList(statements) => self.lower_statements(statements),
+            AstStatement::ExitStatement(_) => {
+                if let Some(current) = self.loops.last_mut() {
+                    current.exits.append(&mut self.open);
+                }
+            }
+            AstStatement::ContinueStatement(_) => {
+                if let Some(current) = self.loops.last_mut() {
+                    current.continues.append(&mut self.open);
+                }
+            }
+            AstStatement::ReturnStatement(ReturnStatement { condition: None }) => {
+                self.returns.append(&mut self.open);
+            }
+            AstStatement::ReturnStatement(ReturnStatement { condition: Some(condition) }) => {
+                let id = self.add(Step::Eval(condition));
+                self.returns.push(id);
+            }
+            AstStatement::JumpStatement(stmt) => {
+                let id = self.add(Step::Eval(&stmt.condition));
+                let label = match self.annotations.get(statement) {
+                    Some(StatementAnnotation::Label { name }) => Some(name.as_str()),
+                    _ => stmt.target.get_flat_reference_name(),
+                };
+                if let Some(label) = label {
+                    self.jumps.push((id, label.to_lowercase()));
+                }
+            }
+            AstStatement::LabelStatement(stmt) => {
+                let id = self.add(Step::Nop);
+                self.labels.insert(stmt.name.to_lowercase(), id);
+            }
+            _ => {
+                self.add(Step::Eval(statement));
+            }
+        }
+    }
+
+    /// Lowers the body of a loop continuing at `head` and returns the nodes leaving the loop via `EXIT`
+    fn lower_loop_body(&mut self, body: &'a [AstNode], head: usize) -> Vec<usize> {
+        self.loops.push(LoopExits::default());
+        let guarded =
+            body.split_first().and_then(|(first, rest)| get_later_iterations(first).zip(Some(rest)));
+        let (next_iteration, body) = match guarded {
+            // the first iteration of a lowered `FOR` or `REPEAT` loop skips its guard, while all other
+            // iterations continue with it
+            Some((guard, rest)) => {
+                let first_iteration = std::mem::take(&mut self.open);
+                let next_iteration = self.add(Step::Nop);
+                self.lower_statements(guard);
+                self.open.extend(first_iteration);
+                (next_iteration, rest)
+            }
+            None => (head, body),
+        };
+        self.lower_statements(body);
+        let LoopExits { exits, mut continues } = self.loops.pop().unwrap_or_default();
+        let mut back_edges = std::mem::take(&mut self.open);
+        back_edges.append(&mut continues);
+        self.link(back_edges, next_iteration);
+        exits
+    }
+}
+
+/// Returns the statements a lowered `FOR` or `REPEAT` loop runs from its second iteration on, i.e. the
+/// body of its leading `IF __ran_once_N THEN ... END_IF` guard (see `plc_lowering::loops`)
+fn get_later_iterations(statement: &AstNode) -> Option<&[AstNode]> {
+    let AstStatement::ControlStatement(AstControlStatement::If(stmt)) = statement.get_stmt() else {
+        return None;
+    };
+    match stmt.blocks.as_slice() {
+        [block]
+            if stmt.else_block.is_empty()
+                && block
+                    .condition
+                    .get_flat_reference_name()
+                    .is_some_and(|it| it.starts_with("__ran_once_")) =>
+        {
+            Some(&block.body)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the value of a condition made of boolean literals, e.g. `NOT TRUE` in a lowered `WHILE TRUE` loop
+fn get_constant_condition(condition: &AstNode) -> Option<bool> {
+    match condition.get_stmt() {
+        AstStatement::Literal(AstLiteral::Bool(value)) => Some(*value),
+        AstStatement::UnaryExpression(UnaryExpression { operator: Operator::Not, value }) => {
+            get_constant_condition(value).map(|it| !it)
+        }
+        AstStatement::ParenExpression(inner) => get_constant_condition(inner),
+        _ => None,
+    }
+}
+
+struct Analysis<'a, T: AnnotationMap> {
+    annotations: &'a T,
+    index: &'a Index,
+    tracked: &'a [Tracked<'a>],
+    graph: &'a ControlFlowGraph<'a>,
+}
+
+impl<'a, T: AnnotationMap> Analysis<'a, T> {
+    /// Returns the tracked variables assigned on every path reaching a node, `None` for unreachable nodes
+    fn solve(&self) -> Vec<Option<Vec<bool>>> {
+        let mut states = vec![None; self.graph.nodes.len()];
+        states[0] = Some(vec![false; self.tracked.len()]);
+        let mut pending = vec![0];
+        while let Some(node) = pending.pop() {
+            let Some(state) = states[node].clone() else {
+                continue;
+            };
+            let assigned = self.transfer(&self.graph.nodes[node], state).assigned;
+            for &successor in &self.graph.nodes[node].successors {
+                let joined = match &states[successor] {
+                    Some(current) => current.iter().zip(&assigned).map(|(a, b)| *a && *b).collect(),
+                    None => assigned.clone(),
+                };
+                if states[successor].as_ref() != Some(&joined) {
+                    states[successor] = Some(joined);
+                    pending.push(successor);
+                }
+            }
+        }
+        states
+    }
+
+    fn transfer(&self, node: &Node, assigned: Vec<bool>) -> AccessVisitor<'a, T> {
+        let mut visitor = AccessVisitor::new(self.annotations, self.index, self.tracked);
+        visitor.assigned = assigned;
+        match node.step {
+            Step::Eval(statement) => visitor.visit(statement),
+            Step::Define(reference) => visitor.visit_with(reference, Access::Write),
+            Step::Nop => {}
+        }
+        visitor
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+}
+
+/// Collects the variables assigned by the visited statements and the reads of unassigned temporaries
+struct AccessVisitor<'a, T: AnnotationMap> {
+    annotations: &'a T,
+    index: &'a Index,
+    tracked: &'a [Tracked<'a>],
+    access: Access,
+    /// whether each tracked variable is assigned
+    assigned: Vec<bool>,
+    /// the tracked temporaries read while being unassigned and the location of the read
+    unassigned_reads: Vec<(usize, SourceLocation)>,
+    /// the lowercase qualified names of all written variables
+    written: FxHashSet<String>,
+}
+
+impl<'a, T: AnnotationMap> AccessVisitor<'a, T> {
+    fn new(annotations: &'a T, index: &'a Index, tracked: &'a [Tracked<'a>]) -> Self {
+        AccessVisitor {
+            annotations,
+            index,
+            tracked,
+            access: Access::Read,
+            assigned: vec![false; tracked.len()],
+            unassigned_reads: Vec::new(),
+            written: FxHashSet::default(),
+        }
+    }
+
+    fn visit_with(&mut self, node: &AstNode, access: Access) {
+        let outer = std::mem::replace(&mut self.access, access);
+        self.visit(node);
+        self.access = outer;
+    }
+
+    fn record(&mut self, qualified_name: &str, location: &SourceLocation) {
+        let name = qualified_name.to_lowercase();
+        let tracked = self.tracked.iter().position(|it| it.qualified_name == name);
+        match self.access {
+            Access::Write => {
+                if let Some(variable) = tracked {
+                    self.assigned[variable] = true;
+                }
+                self.written.insert(name);
+            }
+            Access::Read => {
+                let Some(variable) = tracked.filter(|it| self.tracked[*it].kind == TrackedKind::Temp) else {
+                    return;
+                };
+                if !self.assigned[variable] {
+                    if !location.is_internal() {
+                        self.unassigned_reads.push((variable, location.clone()));
+                    }
+                    // only the first read of a path is reported
+                    self.assigned[variable] = true;
+                }
+            }
+        }
+    }
+
+    /// Returns the kind of the parameter the given call argument is passed to
+    fn get_parameter_type(&self, argument: &AstNode) -> Option<VariableType> {
+        let Some(StatementAnnotation::Argument { position, pou, .. }) = self.annotations.get_hint(argument)
+        else {
+            return None;
+        };
+        self.index.get_declared_parameter(pou, *position as u32).map(VariableIndexEntry::get_variable_type)
+    }
+}
+
+impl<T: AnnotationMap> AstVisitor for AccessVisitor<'_, T> {
+    fn visit_assignment(&mut self, stmt: &Assignment, _node: &AstNode) {
+        self.visit_with(&stmt.right, Access::Read);
+        self.visit_with(&stmt.left, Access::Write);
+    }
+
+    fn visit_output_assignment(&mut self, stmt: &Assignment, _node: &AstNode) {
+        self.visit_with(&stmt.left, Access::Read);
+        self.visit_with(&stmt.right, Access::Write);
+    }
+
+    fn visit_ref_assignment(&mut self, stmt: &Assignment, _node: &AstNode) {
+        self.visit_with(&stmt.right, Access::Write);
+        self.visit_with(&stmt.left, Access::Write);
+    }
+
+    fn visit_for_loop_statement(&mut self, stmt: &ForLoopStatement, _node: &AstNode) {
+        self.visit_with(&stmt.start, Access::Read);
+        self.visit_with(&stmt.counter, Access::Write);
+        self.visit_with(&stmt.end, Access::Read);
+        stmt.by_step.iter().for_each(|it| self.visit_with(it, Access::Read));
+        self.visit_statement_list(&stmt.body);
+    }
+
+    fn visit_call_statement(&mut self, stmt: &CallStatement, _node: &AstNode) {
+        self.visit_with(&stmt.operator, Access::Read);
+        let takes_address = matches!(
+            self.annotations.get(&stmt.operator),
+            Some(StatementAnnotation::Function { qualified_name, .. })
+                if qualified_name.eq_ignore_ascii_case("ADR") || qualified_name.eq_ignore_ascii_case("REF")
+        );
+
+        for argument in stmt.parameters.as_deref().map(flatten_expression_list).unwrap_or_default() {
+            // arguments passed by reference may be assigned by the callee
+            let by_ref =
+                matches!(self.get_parameter_type(argument), Some(VariableType::Output | VariableType::InOut));
+            let access = if takes_address || by_ref { Access::Write } else { Access::Read };
+            match argument.get_stmt() {
+                AstStatement::Assignment(Assignment { left, right }) => {
+                    self.visit_with(right, access);
+                    self.visit_with(left, Access::Write);
+                }
+                AstStatement::OutputAssignment(Assignment { left, right }) => {
+                    self.visit_with(left, Access::Read);
+                    self.visit_with(right, Access::Write);
+                }
+                _ => self.visit_with(argument, access),
+            }
+        }
+    }
+
+    fn visit_reference_expr(&mut self, stmt: &ReferenceExpr, node: &AstNode) {
+        match &stmt.access {
+            // writing a member or an element also writes the variable it belongs to
+            ReferenceAccess::Member(_) | ReferenceAccess::Global(_) => {
+                if let Some(base) = &stmt.base {
+                    self.visit(base);
+                }
+            }
+            ReferenceAccess::Index(index) => {
+                self.visit_with(index, Access::Read);
+                if let Some(base) = &stmt.base {
+                    self.visit(base);
+                }
+            }
+            // writing through a pointer does not write the pointer
+            ReferenceAccess::Deref => {
+                if let Some(base) = &stmt.base {
+                    self.visit_with(base, Access::Read);
+                }
+            }
+            ReferenceAccess::Address => {
+                if let Some(base) = &stmt.base {
+                    self.visit_with(base, Access::Write);
+                }
+            }
+            ReferenceAccess::Cast(target) => {
+                stmt.base.iter().for_each(|it| self.visit(it));
+                self.visit_with(target, Access::Read);
+            }
+        }
+
+        if let Some(StatementAnnotation::Variable { qualified_name, .. }) = self.annotations.get(node) {
+            self.record(qualified_name, &node.location);
+        }
+    }
+}
@@ -4,7 +4,7 @@ use plc_source::source_location::SourceLocation;
 use rustc_hash::FxHashMap;
 
 use crate::{
-    index::{symbol::SymbolMap, Index, PouIndexEntry},
+    index::{is_synthetic_hw_global, symbol::SymbolMap, Index, PouIndexEntry},
     typesystem::{DataTypeInformation, StructSource},
 };
 
@@ -75,6 +75,9 @@ impl GlobalValidator {
 
         // all POUs
         self.validate_unique_pous(index);
+
+        // declarations of an earlier build
+        self.validate_unique_imports(index);
     }
 
     /// validates following uniqueness-clusters:
@@ -210,6 +213,37 @@ impl GlobalValidator {
         }
     }
 
+    /// validates that an index created on top of other declarations (see [`Index::with_imports`])
+    /// does not declare any of the imported POUs, types or globals again
+    fn validate_unique_imports(&mut self, index: &Index) {
+        let Some(imports) = index.get_imports() else {
+            return;
+        };
+
+        let pous = index
+            .get_pous()
+            .values()
+            .filter(|it| !it.is_auto_generated_function() && !it.is_generic())
+            .filter_map(|it| {
+                Some((it.get_name(), it.get_location(), imports.find_pou(it.get_name())?.get_location()))
+            });
+        let types = index.get_types().values().filter(|it| !it.is_internal()).filter_map(|it| {
+            Some((it.get_name(), &it.location, &imports.find_type(it.get_name())?.location))
+        });
+        let globals = index
+            .get_globals()
+            .values()
+            .filter(|it| !is_synthetic_hw_global(&it.get_name().to_lowercase()))
+            .filter_map(|it| {
+                let imported = imports.find_global_variable(it.get_name())?;
+                Some((it.get_name(), &it.source_location, &imported.source_location))
+            });
+
+        for (name, location, imported) in pous.chain(types).chain(globals).collect::<Vec<_>>() {
+            self.report_name_conflict(name, &[location, imported], None);
+        }
+    }
+
     fn check_uniqueness_of_cluster<'a, T>(&mut self, cluster: T, additional_text: Option<&str>)
     where
         T: Iterator<Item = (&'a str, &'a SourceLocation)>,
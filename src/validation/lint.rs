@@ -0,0 +1,522 @@
+//! Lint rules, reporting code that is valid but does not follow the conventions of a project.
+//!
+//! The lints are disabled by default and enabled by giving their codes a severity in the error
+//! configuration, their settings are described in [`plc_diagnostics::diagnostics::lints`]:
+//! - `E155` names not matching the pattern configured for their kind of declaration
+//! - `E156` variables that are never used
+//! - `E157` parameters that are never used
+//! - `E158` variables shadowing a global variable or a member of the POU declaring a method
+//! - `E159` magic numbers, i.e. numeric literals in a body which are not allowed explicitly
+//! - `E168` identifiers in a body written in a different case than their declaration
+//!
+//! Only declarations of the project itself are checked, `{external}` and included declarations as well
+//! as anything generated by the compiler are skipped.
+
+use std::{collections::BTreeMap, ops::Range};
+
+use plc_ast::{
+    ast::{
+        AstNode, AstStatement, CallStatement, CompilationUnit, DataType, LinkageType, Pou, PouType,
+        ReferenceAccess, ReferenceExpr, Variable, VariableBlock, VariableBlockType,
+    },
+    literals::AstLiteral,
+    visitor::{AstVisitor, Walker},
+};
+use plc_diagnostics::diagnostics::{
+    lints::{LintSettings, NamingKind, NamingPattern},
+    Diagnostic,
+};
+use plc_source::source_location::SourceLocation;
+use rustc_hash::FxHashSet;
+
+use crate::{
+    index::Index,
+    resolver::{AnnotationMap, StatementAnnotation},
+};
+
+use super::{Validator, Validators};
+
+pub(super) fn visit_units<T: AnnotationMap>(
+    validator: &mut Validator,
+    settings: &LintSettings,
+    annotations: &T,
+    index: &Index,
+    units: &[&CompilationUnit],
+) {
+    validate_naming(validator, settings, index, units);
+    validate_usage(validator, annotations, index, units);
+    validate_shadowing(validator, index, units);
+    validate_magic_numbers(validator, settings, units);
+    validate_identifier_case(validator, annotations, index, units);
+}
+
+fn is_generated(name: &str, location: &SourceLocation) -> bool {
+    location.is_internal() || name.starts_with("__")
+}
+
+/// Returns the POUs declared by the project itself, without the methods generated for properties
+fn get_user_pous<'a>(units: &'a [&CompilationUnit]) -> impl Iterator<Item = &'a Pou> {
+    units.iter().flat_map(|it| &it.pous).filter(|pou| {
+        pou.linkage == LinkageType::Internal
+            && !is_generated(&pou.name, &pou.location)
+            && !matches!(pou.kind, PouType::Method { property: Some(_), .. })
+    })
+}
+
+fn get_user_variables(block: &VariableBlock) -> impl Iterator<Item = &Variable> {
+    block.variables.iter().filter(|it| !is_generated(&it.name, &it.location))
+}
+
+/// Returns the name of a method or action without the name of the POU declaring it
+fn get_simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+struct NamingRules<'a> {
+    patterns: &'a BTreeMap<NamingKind, NamingPattern>,
+}
+
+impl NamingRules<'_> {
+    /// Reports the name if it does not match the pattern of the first of the given kinds a pattern is
+    /// configured for
+    fn check(&self, validator: &mut Validator, kinds: &[NamingKind], name: &str, location: &SourceLocation) {
+        let Some((kind, pattern)) =
+            kinds.iter().find_map(|kind| self.patterns.get(kind).map(|it| (kind, it)))
+        else {
+            return;
+        };
+        if !pattern.is_match(name) {
+            validator.push_diagnostic(
+                Diagnostic::new(format!(
+                    "Name `{name}` does not match the naming pattern `{}` configured for `{kind}`",
+                    pattern.as_str()
+                ))
+                .with_error_code("E155")
+                .with_location(location),
+            );
+        }
+    }
+
+    fn check_variables(&self, validator: &mut Validator, index: &Index, block: &VariableBlock) {
+        let block_kind = match block.kind {
+            VariableBlockType::Local => NamingKind::Local,
+            VariableBlockType::Temp => NamingKind::Temp,
+            VariableBlockType::Input(_) => NamingKind::Input,
+            VariableBlockType::Output => NamingKind::Output,
+            VariableBlockType::InOut => NamingKind::InOut,
+            VariableBlockType::Global => NamingKind::Global,
+            VariableBlockType::External => return,
+        };
+        for variable in get_user_variables(block) {
+            let is_instance = variable
+                .data_type_declaration
+                .get_name()
+                .and_then(|it| index.find_pou(it))
+                .is_some_and(|it| it.is_function_block());
+            let kinds = [
+                is_instance.then_some(NamingKind::FunctionBlockInstance),
+                block.constant.then_some(NamingKind::Constant),
+                Some(block_kind),
+            ];
+            self.check(
+                validator,
+                &kinds.into_iter().flatten().collect::<Vec<_>>(),
+                &variable.name,
+                &variable.location,
+            );
+        }
+    }
+}
+
+fn validate_naming(
+    validator: &mut Validator,
+    settings: &LintSettings,
+    index: &Index,
+    units: &[&CompilationUnit],
+) {
+    if settings.naming.is_empty() {
+        return;
+    }
+    let rules = NamingRules { patterns: &settings.naming };
+
+    for pou in get_user_pous(units) {
+        let kind = match pou.kind {
+            PouType::Program => NamingKind::Program,
+            PouType::Function => NamingKind::Function,
+            PouType::FunctionBlock => NamingKind::FunctionBlock,
+            PouType::Class => NamingKind::Class,
+            PouType::Method { .. } => NamingKind::Method,
+            _ => continue,
+        };
+        rules.check(validator, &[kind], get_simple_name(&pou.name), &pou.name_location);
+        for block in &pou.variable_blocks {
+            rules.check_variables(validator, index, block);
+        }
+    }
+
+    for unit in units {
+        let actions = unit.implementations.iter().filter(|it| {
+            it.pou_type == PouType::Action
+                && it.linkage == LinkageType::Internal
+                && !it.location.is_internal()
+        });
+        for action in actions {
+            rules.check(
+                validator,
+                &[NamingKind::Action],
+                get_simple_name(&action.name),
+                &action.name_location,
+            );
+        }
+
+        for interface in unit.interfaces.iter().filter(|it| !it.location.is_internal()) {
+            rules.check(
+                validator,
+                &[NamingKind::Interface],
+                &interface.ident.name,
+                &interface.ident.location,
+            );
+            for method in &interface.methods {
+                rules.check(
+                    validator,
+                    &[NamingKind::Method],
+                    get_simple_name(&method.name),
+                    &method.name_location,
+                );
+            }
+        }
+
+        let user_types = unit.user_types.iter().filter(|it| {
+            it.linkage == LinkageType::Internal && it.scope.is_none() && !it.location.is_internal()
+        });
+        for user_type in user_types {
+            let kind = match &user_type.data_type {
+                DataType::StructType { .. } => NamingKind::Struct,
+                DataType::EnumType { .. } => NamingKind::Enum,
+                _ => continue,
+            };
+            if let Some(name) = user_type.data_type.get_name() {
+                rules.check(validator, &[kind], name, &user_type.location);
+            }
+        }
+
+        for block in unit.global_vars.iter().filter(|it| it.linkage == LinkageType::Internal) {
+            rules.check_variables(validator, index, block);
+        }
+    }
+}
+
+/// Collects the variables referenced by the visited statements
+struct UsageCollector<'a, T: AnnotationMap> {
+    annotations: &'a T,
+    /// the lowercase qualified names of the referenced variables
+    used: FxHashSet<String>,
+    /// the lowercase names of references that are not resolved, e.g. in the range of an array type
+    mentioned: FxHashSet<String>,
+}
+
+impl<T: AnnotationMap> AstVisitor for UsageCollector<'_, T> {
+    fn visit_reference_expr(&mut self, stmt: &ReferenceExpr, node: &AstNode) {
+        match self.annotations.get(node) {
+            Some(StatementAnnotation::Variable { qualified_name, .. }) => {
+                self.used.insert(qualified_name.to_lowercase());
+            }
+            None => {
+                if let ReferenceAccess::Member(member) = &stmt.access {
+                    if let Some(name) = member.get_flat_reference_name() {
+                        self.mentioned.insert(name.to_lowercase());
+                    }
+                }
+            }
+            _ => {}
+        }
+        stmt.walk(self)
+    }
+
+    fn visit_call_statement(&mut self, stmt: &CallStatement, _node: &AstNode) {
+        self.visit(&stmt.operator);
+        let Some(parameters) = &stmt.parameters else {
+            return;
+        };
+        // the parameters named by the caller are not used by it
+        for parameter in plc_ast::ast::flatten_expression_list(parameters) {
+            match parameter.get_stmt() {
+                AstStatement::Assignment(assignment)
+                | AstStatement::OutputAssignment(assignment)
+                | AstStatement::RefAssignment(assignment) => self.visit(&assignment.right),
+                _ => self.visit(parameter),
+            }
+        }
+    }
+}
+
+fn validate_usage<T: AnnotationMap>(
+    validator: &mut Validator,
+    annotations: &T,
+    index: &Index,
+    units: &[&CompilationUnit],
+) {
+    let mut collector =
+        UsageCollector { annotations, used: FxHashSet::default(), mentioned: FxHashSet::default() };
+    for unit in units {
+        for implementation in unit.implementations.iter().filter(|it| !it.location.is_internal()) {
+            collector.visit_statement_list(&implementation.statements);
+        }
+        for block in unit.pous.iter().flat_map(|it| &it.variable_blocks).chain(&unit.global_vars) {
+            collector.visit_variable_block(block);
+        }
+        for user_type in &unit.user_types {
+            collector.visit_user_type_declaration(user_type);
+        }
+    }
+
+    for pou in get_user_pous(units) {
+        let inherits_signature = is_inherited_method(index, pou);
+        for block in &pou.variable_blocks {
+            let is_parameter = match block.kind {
+                VariableBlockType::Local | VariableBlockType::Temp => false,
+                VariableBlockType::Input(_) | VariableBlockType::Output | VariableBlockType::InOut => true,
+                VariableBlockType::Global | VariableBlockType::External => continue,
+            };
+            // the parameters of a method are given by the method it overrides or implements
+            if is_parameter && inherits_signature {
+                continue;
+            }
+            for variable in get_user_variables(block) {
+                // a variable mapped to an address is used by the hardware, the function name is its return value
+                if variable.address.is_some() || variable.name.eq_ignore_ascii_case(&pou.name) {
+                    continue;
+                }
+                let qualified_name = format!("{}.{}", pou.name, variable.name).to_lowercase();
+                if collector.used.contains(&qualified_name)
+                    || collector.mentioned.contains(&variable.name.to_lowercase())
+                {
+                    continue;
+                }
+                let (message, code) = if is_parameter {
+                    (format!("Parameter `{}` of `{}` is never used", variable.name, pou.name), "E157")
+                } else {
+                    (format!("Variable `{}` is never used", variable.name), "E156")
+                };
+                validator.push_diagnostic(
+                    Diagnostic::new(message).with_error_code(code).with_location(&variable.location),
+                );
+            }
+        }
+    }
+}
+
+/// Returns whether the given POU is a method overriding a method of a base class or implementing a
+/// method of an interface
+fn is_inherited_method(index: &Index, pou: &Pou) -> bool {
+    let PouType::Method { parent, .. } = &pou.kind else {
+        return false;
+    };
+    let method = get_simple_name(&pou.name);
+
+    let mut seen = FxHashSet::default();
+    let mut pending = vec![parent.as_str()];
+    while let Some(name) = pending.pop() {
+        let Some(entry) = index.find_pou(name) else {
+            continue;
+        };
+        if !seen.insert(name.to_lowercase()) {
+            continue;
+        }
+        if !name.eq_ignore_ascii_case(parent) && index.find_pou(&format!("{name}.{method}")).is_some() {
+            return true;
+        }
+        let implements =
+            entry.get_interfaces().into_iter().filter_map(|it| index.find_interface(it)).any(|interface| {
+                interface
+                    .get_methods(index)
+                    .iter()
+                    .any(|it| get_simple_name(it.get_name()).eq_ignore_ascii_case(method))
+            });
+        if implements {
+            return true;
+        }
+        pending.extend(entry.get_super_class());
+    }
+    false
+}
+
+fn validate_shadowing(validator: &mut Validator, index: &Index, units: &[&CompilationUnit]) {
+    for pou in get_user_pous(units) {
+        let owner = match &pou.kind {
+            PouType::Method { parent, .. } => Some(parent.as_str()),
+            _ => None,
+        };
+        let variables = pou
+            .variable_blocks
+            .iter()
+            .filter(|it| !matches!(it.kind, VariableBlockType::External | VariableBlockType::Global))
+            .flat_map(get_user_variables);
+        for variable in variables {
+            // program instances are globals too, but their name is reported as a duplicate already
+            let global = index
+                .find_global_variable(&variable.name)
+                .filter(|it| !it.source_location.is_internal() && index.find_pou(it.get_name()).is_none());
+            if let Some(global) = global {
+                validator.push_diagnostic(
+                    Diagnostic::new(format!(
+                        "Variable `{}` shadows the global variable `{}`",
+                        variable.name,
+                        global.get_name()
+                    ))
+                    .with_error_code("E158")
+                    .with_location(&variable.location)
+                    .with_secondary_location(&global.source_location),
+                );
+                continue;
+            }
+
+            let member = owner
+                .and_then(|owner| index.find_member(owner, &variable.name))
+                .filter(|it| !it.source_location.is_internal());
+            if let (Some(owner), Some(member)) = (owner, member) {
+                validator.push_diagnostic(
+                    Diagnostic::new(format!(
+                        "Variable `{}` of method `{}` shadows the member `{}` of `{owner}`",
+                        variable.name,
+                        get_simple_name(&pou.name),
+                        member.get_name()
+                    ))
+                    .with_error_code("E158")
+                    .with_location(&variable.location)
+                    .with_secondary_location(&member.source_location),
+                );
+            }
+        }
+    }
+}
+
+/// Collects the numeric literals of the visited statements which are not allowed explicitly
+struct MagicNumberCollector<'a> {
+    allowed: &'a [f64],
+    numbers: Vec<(String, SourceLocation)>,
+}
+
+impl AstVisitor for MagicNumberCollector<'_> {
+    fn visit_literal(&mut self, stmt: &AstLiteral, node: &AstNode) {
+        let number = match stmt {
+            AstLiteral::Integer(value) => Some((*value as f64, value.to_string())),
+            AstLiteral::Real(value) => value.parse::<f64>().ok().map(|it| (it, value.clone())),
+            _ => None,
+        };
+        if let Some((value, text)) = number {
+            if !node.get_location().is_internal() && !self.allowed.contains(&value) {
+                self.numbers.push((text, node.get_location()));
+            }
+        }
+        stmt.walk(self)
+    }
+}
+
+fn validate_magic_numbers(validator: &mut Validator, settings: &LintSettings, units: &[&CompilationUnit]) {
+    for unit in units {
+        // lowering moves the initializers of some variables into the body, they are not reported
+        let declarations = unit
+            .pous
+            .iter()
+            .flat_map(|it| &it.variable_blocks)
+            .filter_map(|it| it.location.to_range())
+            .collect::<Vec<Range<usize>>>();
+        let is_declaration = |location: &SourceLocation| {
+            location.to_range().is_some_and(|range| {
+                declarations.iter().any(|it| it.start <= range.start && range.end <= it.end)
+            })
+        };
+
+        let implementations = unit
+            .implementations
+            .iter()
+            .filter(|it| it.linkage == LinkageType::Internal && !it.location.is_internal());
+        for implementation in implementations {
+            let mut collector = MagicNumberCollector { allowed: &settings.allowed_numbers, numbers: vec![] };
+            collector.visit_statement_list(&implementation.statements);
+            for (number, location) in collector.numbers.into_iter().filter(|(_, it)| !is_declaration(it)) {
+                validator.push_diagnostic(
+                    Diagnostic::new(format!(
+                        "Magic number `{number}` in `{}`, consider declaring a named constant",
+                        implementation.name
+                    ))
+                    .with_error_code("E159")
+                    .with_location(&location),
+                );
+            }
+        }
+    }
+}
+
+/// Collects the identifiers of the visited statements which are written in a different case than the
+/// variable or POU they refer to
+struct IdentifierCaseCollector<'a, T: AnnotationMap> {
+    annotations: &'a T,
+    index: &'a Index,
+    /// the written and the declared name of the identifiers
+    identifiers: Vec<(String, &'a str, SourceLocation)>,
+}
+
+impl<'a, T: AnnotationMap> IdentifierCaseCollector<'a, T> {
+    fn get_declared_name(&self, node: &AstNode) -> Option<&'a str> {
+        match self.annotations.get(node)? {
+            StatementAnnotation::Variable { qualified_name, .. } => {
+                self.index.find_fully_qualified_variable(qualified_name).map(|it| it.get_name())
+            }
+            StatementAnnotation::Function { qualified_name, .. }
+            | StatementAnnotation::Program { qualified_name } => {
+                self.index.find_pou(qualified_name).map(|it| get_simple_name(it.get_name()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T: AnnotationMap> AstVisitor for IdentifierCaseCollector<'_, T> {
+    fn visit_reference_expr(&mut self, stmt: &ReferenceExpr, node: &AstNode) {
+        if let ReferenceAccess::Member(member) = &stmt.access {
+            if let AstStatement::Identifier(name) = member.get_stmt() {
+                let declared = self.get_declared_name(node);
+                if let Some(declared) = declared.filter(|it| *it != name && it.eq_ignore_ascii_case(name)) {
+                    if !is_generated(name, &member.get_location()) {
+                        self.identifiers.push((name.clone(), declared, member.get_location()));
+                    }
+                }
+            }
+        }
+        stmt.walk(self)
+    }
+}
+
+fn validate_identifier_case<T: AnnotationMap>(
+    validator: &mut Validator,
+    annotations: &T,
+    index: &Index,
+    units: &[&CompilationUnit],
+) {
+    let mut collector = IdentifierCaseCollector { annotations, index, identifiers: vec![] };
+    for unit in units {
+        let implementations = unit
+            .implementations
+            .iter()
+            .filter(|it| it.linkage == LinkageType::Internal && !it.location.is_internal());
+        for implementation in implementations {
+            collector.visit_statement_list(&implementation.statements);
+        }
+    }
+
+    // lowering may copy a reference, e.g. the counter of a FOR loop, every location is reported once
+    let mut reported = FxHashSet::default();
+    for (name, declared, location) in collector.identifiers {
+        if !reported.insert(location.to_range()) {
+            continue;
+        }
+        validator.push_diagnostic(
+            Diagnostic::new(format!("Identifier `{name}` is declared as `{declared}`"))
+                .with_error_code("E168")
+                .with_location(&location)
+                .with_suggested_edit(format!("replace with `{declared}`"), &location, declared),
+        );
+    }
+}
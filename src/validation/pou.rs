@@ -5,7 +5,7 @@ use plc_source::source_location::SourceLocation;
 use signature_validation::validate_method_signature;
 
 use super::{
-    property, statement::visit_statement, variable::visit_variable_block, ValidationContext, Validator,
+    property, statement::visit_statement_list, variable::visit_variable_block, ValidationContext, Validator,
     Validators,
 };
 use crate::resolver::{AnnotationMap, StatementAnnotation};
@@ -241,9 +241,11 @@ pub fn visit_implementation<T: AnnotationMap>(
                 }
             }
         }
-        implementation.statements.iter().for_each(|s| {
-            visit_statement(validator, s, &context.with_qualifier(implementation.name.as_str()))
-        });
+        visit_statement_list(
+            validator,
+            &implementation.statements,
+            &context.with_qualifier(implementation.name.as_str()),
+        );
     }
 }
 
@@ -264,12 +266,21 @@ fn validate_pou(validator: &mut Validator, pou: &Pou) {
     }
 }
 
+/// Returns true if the given POU is a class declaring parameters, which is rejected with `E019`
+pub(super) fn is_class_with_parameters(pou: &Pou) -> bool {
+    pou.kind == PouType::Class
+        && pou.variable_blocks.iter().any(|it| {
+            matches!(
+                it.kind,
+                VariableBlockType::InOut | VariableBlockType::Input(_) | VariableBlockType::Output
+            )
+        })
+}
+
 fn validate_class(validator: &mut Validator, pou: &Pou) {
     // var in/out/inout blocks are not allowed inside of class declaration
     // TODO: This should be on each block
-    if pou.variable_blocks.iter().any(|it| {
-        matches!(it.kind, VariableBlockType::InOut | VariableBlockType::Input(_) | VariableBlockType::Output)
-    }) {
+    if is_class_with_parameters(pou) {
         validator.push_diagnostic(
             Diagnostic::new("A class cannot contain `VAR_INPUT`, `VAR_IN_OUT`, or `VAR_OUTPUT` blocks")
                 .with_error_code("E019")
@@ -8,10 +8,10 @@ use plc_ast::{
         flatten_expression_list, AstNode, AstStatement, BinaryExpression, CallStatement, DirectAccess,
         DirectAccessType, JumpStatement, Operator, ReferenceAccess, TypeNature, UnaryExpression,
     },
-    control_statements::{AstControlStatement, ConditionalBlock},
+    control_statements::{AstControlStatement, ConditionalBlock, ReturnStatement},
     literals::{Array, AstLiteral, StringValue},
 };
-use plc_diagnostics::diagnostics::Diagnostic;
+use plc_diagnostics::diagnostics::{Diagnostic, SuggestedEdit};
 use plc_source::source_location::SourceLocation;
 
 use super::{array::validate_array_assignment, ValidationContext, Validator, Validators};
@@ -149,7 +149,12 @@ pub fn visit_statement<T: AnnotationMap>(
         AstStatement::CallStatement(data) => {
             validate_call(validator, &data.operator, data.parameters.as_deref(), &context.set_is_call());
         }
-        AstStatement::ControlStatement(kind) => validate_control_statement(validator, kind, context),
+        AstStatement::ControlStatement(kind) => {
+            validate_control_statement(validator, kind, &statement.location, context);
+            if let Some(metadata) = &statement.metadata {
+                validate_desugared_loop(validator, context, metadata.get_inner());
+            }
+        }
         AstStatement::CaseCondition(condition) => {
             // if we get here, then a `CaseCondition` is used outside a `CaseStatement`
             // `CaseCondition` are used as a marker for `CaseStatements` and are not passed as such to the `CaseStatement.case_blocks`
@@ -509,31 +514,62 @@ where
     visit_statement(validator, condition, context);
 }
 
+/// Validates a list of statements which are executed one after another, e.g. the body of a POU or a loop
+pub fn visit_statement_list<T: AnnotationMap>(
+    validator: &mut Validator,
+    statements: &[AstNode],
+    context: &ValidationContext<T>,
+) {
+    for statement in statements {
+        visit_statement(validator, statement, context);
+    }
+
+    validate_unreachable_statements(validator, statements);
+}
+
 fn validate_control_statement<T: AnnotationMap>(
     validator: &mut Validator,
     control_statement: &AstControlStatement,
+    location: &SourceLocation,
     context: &ValidationContext<T>,
 ) {
     match control_statement {
         AstControlStatement::If(stmt) => {
             for block in &stmt.blocks {
                 validate_condition(validator, context, &block.condition);
-                block.body.iter().for_each(|s| visit_statement(validator, s, context));
+                // internal IFs are created by lowering, e.g. the `IF NOT cond THEN EXIT` of a WHILE loop
+                if !location.is_internal() {
+                    validate_constant_condition(validator, context, &block.condition);
+                }
+                visit_statement_list(validator, &block.body, context);
             }
 
-            stmt.else_block.iter().for_each(|e| visit_statement(validator, e, context));
+            visit_statement_list(validator, &stmt.else_block, context);
         }
         AstControlStatement::ForLoop(stmt) => {
             validate_for_loop(validator, context, stmt);
+            validate_for_loop_executes(
+                validator,
+                context,
+                &stmt.counter,
+                &stmt.start,
+                &stmt.end,
+                stmt.by_step.as_deref(),
+            );
             visit_all_statements!(validator, context, &stmt.counter, &stmt.start, &stmt.end);
             if let Some(by_step) = &stmt.by_step {
                 visit_statement(validator, by_step, context);
             }
-            stmt.body.iter().for_each(|s| visit_statement(validator, s, context));
+            visit_statement_list(validator, &stmt.body, context);
+        }
+        AstControlStatement::WhileLoop(stmt) => {
+            validate_condition(validator, context, &stmt.condition);
+            validate_constant_condition(validator, context, &stmt.condition);
+            visit_statement_list(validator, &stmt.body, context);
         }
-        AstControlStatement::WhileLoop(stmt) | AstControlStatement::RepeatLoop(stmt) => {
+        AstControlStatement::RepeatLoop(stmt) => {
             validate_condition(validator, context, &stmt.condition);
-            stmt.body.iter().for_each(|s| visit_statement(validator, s, context));
+            visit_statement_list(validator, &stmt.body, context);
         }
         AstControlStatement::Case(stmt) => {
             validate_case_statement(validator, &stmt.selector, &stmt.case_blocks, &stmt.else_block, context);
@@ -541,6 +577,81 @@ fn validate_control_statement<T: AnnotationMap>(
     }
 }
 
+/// Checks the header of the loop a `WHILE TRUE` loop was desugared from, which the desugarer keeps as
+/// the metadata of the lowered loop
+fn validate_desugared_loop<T: AnnotationMap>(
+    validator: &mut Validator,
+    context: &ValidationContext<T>,
+    original: &AstNode,
+) {
+    match original.get_stmt() {
+        AstStatement::ControlStatement(AstControlStatement::ForLoop(stmt)) => validate_for_loop_executes(
+            validator,
+            context,
+            &stmt.counter,
+            &stmt.start,
+            &stmt.end,
+            stmt.by_step.as_deref(),
+        ),
+        AstStatement::ControlStatement(AstControlStatement::WhileLoop(stmt)) => {
+            validate_constant_condition(validator, context, &stmt.condition)
+        }
+        _ => (),
+    }
+}
+
+/// Warns about conditions which always evaluate to the same value, e.g. `IF 1 > 2 THEN`. Plain `TRUE`
+/// and `FALSE` literals are not reported since they are written on purpose (e.g. `WHILE TRUE DO`).
+fn validate_constant_condition<T: AnnotationMap>(
+    validator: &mut Validator,
+    context: &ValidationContext<T>,
+    condition: &AstNode,
+) {
+    if condition.get_location().is_internal()
+        || matches!(condition.get_node_peeled().get_stmt(), AstStatement::Literal(AstLiteral::Bool(_)))
+    {
+        return;
+    }
+
+    if let Ok(Some(AstNode { stmt: AstStatement::Literal(AstLiteral::Bool(value)), .. })) =
+        const_evaluator::evaluate(condition, context.qualifier, context.index, None)
+    {
+        let message = format!("Condition is always {}", if value { "TRUE" } else { "FALSE" });
+        validator.push_diagnostic(Diagnostic::new(message).with_error_code("E164").with_location(condition));
+    }
+}
+
+/// Warns about statements following a `RETURN`, `EXIT` or `CONTINUE` in the same statement list,
+/// which can only be reached again through a label
+fn validate_unreachable_statements(validator: &mut Validator, statements: &[AstNode]) {
+    let mut statements = statements.iter().peekable();
+    while let Some(statement) = statements.next() {
+        let keyword = match statement.get_stmt() {
+            AstStatement::ReturnStatement(ReturnStatement { condition: None }) => "RETURN",
+            AstStatement::ExitStatement(_) => "EXIT",
+            AstStatement::ContinueStatement(_) => "CONTINUE",
+            _ => continue,
+        };
+
+        let mut unreachable = vec![];
+        while let Some(next) =
+            statements.next_if(|it| !matches!(it.get_stmt(), AstStatement::LabelStatement(_)))
+        {
+            if !next.get_location().is_internal() && !next.is_empty_statement() {
+                unreachable.push(next);
+            }
+        }
+
+        if let Some((first, last)) = unreachable.first().zip(unreachable.last()) {
+            validator.push_diagnostic(
+                Diagnostic::new(format!("Unreachable code after `{keyword}`"))
+                    .with_error_code("E163")
+                    .with_location(first.get_location().span(&last.get_location())),
+            );
+        }
+    }
+}
+
 /// validates a literal statement with a dedicated type-prefix (e.g. INT#3)
 /// checks whether the type-prefix is valid and if the target is a literal
 fn validate_cast_literal<T: AnnotationMap>(
@@ -666,6 +777,38 @@ fn validate_access_index<T: AnnotationMap>(
     }
 }
 
+/// Returns the name of the callable POU which is spelled most similar to the given name, `None` if no
+/// name differs in at most two characters (and a third of the name's length)
+fn get_similar_pou_name<'idx>(index: &'idx Index, name: &str) -> Option<&'idx str> {
+    let name = name.to_lowercase();
+    index
+        .get_pous()
+        .values()
+        .filter(|it| it.is_function() || it.is_program())
+        .map(|it| it.get_name())
+        // generated and generic implementations are not called by name
+        .filter(|it| !it.contains("__") && !it.contains('.'))
+        .map(|it| (it, get_edit_distance(&name, &it.to_lowercase())))
+        .filter(|(_, distance)| *distance <= 2 && *distance * 3 <= name.len())
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(it, _)| it)
+}
+
+/// Returns the number of characters to insert, remove or replace to turn `from` into `to`
+fn get_edit_distance(from: &str, to: &str) -> usize {
+    let to = to.chars().collect::<Vec<_>>();
+    let mut previous = (0..=to.len()).collect::<Vec<_>>();
+    for (i, a) in from.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in to.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[to.len()]
+}
+
 fn validate_reference<T: AnnotationMap>(
     validator: &mut Validator,
     statement: &AstNode,
@@ -721,7 +864,14 @@ fn validate_reference<T: AnnotationMap>(
             return;
         }
 
-        validator.push_diagnostic(Diagnostic::unresolved_reference(ref_name, location));
+        let mut diagnostic = Diagnostic::unresolved_reference(ref_name, location);
+        // was this meant to call a function or program with a similar name?
+        if context.is_call() && base.is_none() {
+            if let Some(name) = get_similar_pou_name(context.index, ref_name) {
+                diagnostic = diagnostic.with_suggested_edit(format!("replace with `{name}`"), location, name);
+            }
+        }
+        validator.push_diagnostic(diagnostic);
 
         // was this meant as a direct access?
         // TODO: find a way to solve this without re-resolving this name
@@ -2272,6 +2422,8 @@ fn validate_case_statement<T: AnnotationMap>(
     visit_statement(validator, selector, context);
 
     let mut cases = FxHashSet::default();
+    // the values covered by the labels so far, `None` once a label cannot be evaluated
+    let mut covered = Some(Vec::new());
     case_blocks.iter().for_each(|b| {
         let condition = b.condition.as_ref();
 
@@ -2284,6 +2436,7 @@ fn validate_case_statement<T: AnnotationMap>(
 
         // validate for duplicate conditions
         // first try to evaluate the conditions value
+        let mut is_duplicate = false;
         const_evaluator::evaluate(condition, context.qualifier, context.index, None)
             .map_err(|err| {
                 // value evaluation and validation not possible with non constants
@@ -2300,6 +2453,7 @@ fn validate_case_statement<T: AnnotationMap>(
                 // check for duplicates if we got a value
                 if let Some(AstNode { stmt: AstStatement::Literal(AstLiteral::Integer(value)), .. }) = v {
                     if !cases.insert(value) {
+                        is_duplicate = true;
                         validator.push_diagnostic(
                             Diagnostic::new(format!(
                                 "Duplicate condition value: {value}. Occurred more than once!"
@@ -2312,11 +2466,127 @@ fn validate_case_statement<T: AnnotationMap>(
             })
             .ok(); // no need to worry about the result
 
+        // validate for labels which are already covered by earlier labels, duplicates are reported above
+        for label in flatten_expression_list(condition) {
+            let Some(range) = helper::get_case_label_range(label, context) else {
+                covered = None;
+                continue;
+            };
+
+            if let Some(covered) = covered.as_mut() {
+                if !is_duplicate && helper::is_range_covered(covered, range) {
+                    validator.push_diagnostic(
+                        Diagnostic::new(format!(
+                            "Case label `{}` is never reached, its values are covered by earlier labels",
+                            validator.context.slice(&label.location)
+                        ))
+                        .with_error_code("E165")
+                        .with_location(label),
+                    );
+                }
+
+                covered.push(range);
+            }
+        }
+
         visit_statement(validator, condition, context);
-        b.body.iter().for_each(|s| visit_statement(validator, s, context));
+        visit_statement_list(validator, &b.body, context);
     });
 
-    else_block.iter().for_each(|s| visit_statement(validator, s, context));
+    if else_block.is_empty() {
+        if let Some(covered) = covered {
+            validate_case_covers_enum(validator, selector, &covered, context);
+        }
+    }
+
+    visit_statement_list(validator, else_block, context);
+}
+
+/// Warns about a CASE over an enum which neither covers all variants nor has an ELSE block
+fn validate_case_covers_enum<T: AnnotationMap>(
+    validator: &mut Validator,
+    selector: &AstNode,
+    covered: &[(i128, i128)],
+    context: &ValidationContext<T>,
+) {
+    let Some(enum_type) = context
+        .annotations
+        .get_type(selector, context.index)
+        .and_then(|it| context.index.find_effective_type_info(it.get_name()))
+    else {
+        return;
+    };
+
+    let DataTypeInformation::Enum { name, variants, .. } = enum_type else {
+        return;
+    };
+
+    let missing = variants
+        .iter()
+        .filter(|variant| {
+            variant
+                .initial_value
+                .as_ref()
+                .and_then(|id| {
+                    context.index.get_const_expressions().get_constant_int_statement_value(id).ok()
+                })
+                .is_some_and(|value| !helper::is_range_covered(covered, (value, value)))
+        })
+        .map(|variant| format!("`{}`", variant.get_name()))
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        validator.push_diagnostic(
+            Diagnostic::new(format!(
+                "CASE does not cover the variants {} of `{name}` and has no ELSE",
+                missing.join(", ")
+            ))
+            .with_error_code("E167")
+            .with_location(selector),
+        );
+    }
+}
+
+/// Warns about FOR loops which never execute their body because the start value is already past
+/// the end value, e.g. `FOR i := 10 TO 0 DO`
+fn validate_for_loop_executes<T: AnnotationMap>(
+    validator: &mut Validator,
+    context: &ValidationContext<T>,
+    counter: &AstNode,
+    start: &AstNode,
+    end: &AstNode,
+    step: Option<&AstNode>,
+) {
+    let evaluate = |node: &AstNode| {
+        const_evaluator::evaluate(node, context.qualifier, context.index, None)
+            .ok()
+            .flatten()
+            .and_then(|it| it.get_literal_integer_value())
+    };
+
+    let (Some(start_value), Some(end_value)) = (evaluate(start), evaluate(end)) else {
+        return;
+    };
+
+    let Some(step_value) = step.map_or(Some(1), evaluate) else {
+        return;
+    };
+
+    let relation = if step_value > 0 && start_value > end_value {
+        "greater"
+    } else if step_value < 0 && start_value < end_value {
+        "less"
+    } else {
+        return;
+    };
+
+    validator.push_diagnostic(
+        Diagnostic::new(format!(
+            "FOR loop is never executed, its start value {start_value} is {relation} than its end value {end_value}"
+        ))
+        .with_error_code("E166")
+        .with_location(counter.get_location().span(&end.get_location())),
+    );
 }
 
 fn validate_for_loop<T: AnnotationMap>(
@@ -2500,20 +2770,33 @@ fn validate_assignment_type_sizes<T: AnnotationMap>(
                 && ((lhs.is_signed_int() && rhs.is_unsigned_int()) || (lhs.is_int() && rhs.is_float())))
     };
 
+    // the downcast can be made explicit if the conversion function is available, e.g. from the standard library
+    let conversion = format!("TO_{}", lhs.get_name());
+    let conversion =
+        (lhs.is_numerical() && context.index.find_pou(&conversion).is_some()).then_some(conversion);
+
     get_expression_types_and_locations(right, context, lhs.is_signed_int(), false)
         .into_iter()
         .filter(|(dt, _)| !dt.is_aggregate_type() && results_in_truncation(dt))
         .for_each(|(dt, location)| {
             location.into_iter().for_each(|loc| {
-                validator.push_diagnostic(
-                    Diagnostic::new(format!(
-                        "Implicit downcast from '{}' to '{}'.",
-                        validator.get_type_name_or_slice(dt),
-                        validator.get_type_name_or_slice(left)
-                    ))
-                    .with_error_code("E067")
-                    .with_location(loc),
-                );
+                let mut diagnostic = Diagnostic::new(format!(
+                    "Implicit downcast from '{}' to '{}'.",
+                    validator.get_type_name_or_slice(dt),
+                    validator.get_type_name_or_slice(left)
+                ))
+                .with_error_code("E067")
+                .with_location(&loc);
+                if let Some(conversion) = conversion.as_deref() {
+                    diagnostic = diagnostic.with_suggestion(
+                        format!("convert explicitly with `{conversion}`"),
+                        vec![
+                            SuggestedEdit::new(loc.get_start(), format!("{conversion}(")),
+                            SuggestedEdit::new(loc.get_end(), ")"),
+                        ],
+                    );
+                }
+                validator.push_diagnostic(diagnostic);
             })
         });
 }
@@ -2582,10 +2865,10 @@ fn validate_zero_diviser<T: AnnotationMap>(
 pub(crate) mod helper {
     use std::ops::Range;
 
-    use plc_ast::ast::{AstNode, DirectAccessType};
+    use plc_ast::ast::{AstNode, AstStatement, DirectAccessType};
 
     use crate::index::VariableIndexEntry;
-    use crate::resolver::AnnotationMap;
+    use crate::resolver::{const_evaluator, AnnotationMap};
     use crate::validation::ValidationContext;
     use crate::{index::Index, typesystem::DataTypeInformation};
 
@@ -2650,6 +2933,50 @@ pub(crate) mod helper {
         variant_const_values
     }
 
+    /// Returns the values covered by the given case label, e.g. `(1, 5)` for `1..5`
+    pub fn get_case_label_range<T>(label: &AstNode, context: &ValidationContext<T>) -> Option<(i128, i128)>
+    where
+        T: AnnotationMap,
+    {
+        let evaluate = |node: &AstNode| {
+            const_evaluator::evaluate(node, context.qualifier, context.index, None)
+                .ok()
+                .flatten()
+                .and_then(|it| it.get_literal_integer_value())
+        };
+
+        match label.get_stmt() {
+            AstStatement::RangeStatement(data) => Some((evaluate(&data.start)?, evaluate(&data.end)?)),
+            _ => evaluate(label).map(|value| (value, value)),
+        }
+    }
+
+    /// Returns true if every value of `range` is part of one of the `covered` ranges
+    pub fn is_range_covered(covered: &[(i128, i128)], (start, end): (i128, i128)) -> bool {
+        if start > end {
+            return false;
+        }
+
+        let mut covered = covered.to_vec();
+        covered.sort_unstable();
+
+        // the smallest value of `range` not yet known to be covered
+        let mut next = start;
+        for (from, to) in covered {
+            if from > next {
+                break;
+            }
+
+            if to >= end {
+                return true;
+            }
+
+            next = next.max(to + 1);
+        }
+
+        false
+    }
+
     pub fn is_literal_or_const_expr_value_zero<T>(right: &AstNode, context: &ValidationContext<T>) -> bool
     where
         T: AnnotationMap,
@@ -4,10 +4,13 @@ mod assignment_validation_tests;
 mod bitaccess_validation_test;
 mod builtin_validation_tests;
 mod conditional_validation_tests;
+mod control_flow_validation_tests;
+mod definite_assignment_validation_tests;
 mod duplicates_validation_test;
 mod enum_validation_test;
 mod generic_validation_tests;
 mod interface_validation_tests;
+mod lint_validation_tests;
 mod literals_validation_tests;
 mod naming_validation_test;
 mod pointer_validation_tests;
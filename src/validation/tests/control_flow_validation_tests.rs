@@ -0,0 +1,211 @@
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use crate::test_utils::tests::{parse_and_validate, parse_and_validate_lowered_loops};
+
+fn get_messages(diagnostics: &[Diagnostic], code: &str) -> Vec<String> {
+    diagnostics
+        .iter()
+        .filter(|it| it.get_error_code() == code)
+        .map(|it| it.get_message().to_string())
+        .collect()
+}
+
+#[test]
+fn statements_after_return_exit_and_continue_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION foo : INT
+        VAR
+            i : INT;
+        END_VAR
+            WHILE i < 10 DO
+                i := i + 1;
+                IF i = 5 THEN
+                    CONTINUE;
+                    i := i + 1;
+                END_IF
+                IF i = 7 THEN
+                    EXIT;
+                    i := 0;
+                    i := 1;
+                END_IF
+            END_WHILE
+
+            foo := i;
+            RETURN;
+            foo := 0;
+        END_FUNCTION
+
+        FUNCTION bar : INT
+            bar := 1;
+            IF bar > 0 THEN
+                RETURN;
+            END_IF
+            bar := 2;
+        END_FUNCTION
+        ",
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E163"),
+        vec![
+            "Unreachable code after `CONTINUE`",
+            "Unreachable code after `EXIT`",
+            "Unreachable code after `RETURN`",
+        ]
+    );
+}
+
+#[test]
+fn conditions_folding_to_a_constant_are_reported() {
+    let src = "
+        VAR_GLOBAL CONSTANT
+            DEBUG : BOOL := FALSE;
+            LIMIT : DINT := 10;
+        END_VAR
+
+        FUNCTION foo
+        VAR
+            x : DINT;
+        END_VAR
+            IF LIMIT > 5 THEN
+                x := 1;
+            ELSIF DEBUG AND x > 0 THEN
+                x := 2;
+            ELSIF x > LIMIT THEN
+                x := 3;
+            END_IF
+
+            WHILE LIMIT < 5 DO
+                x := 4;
+            END_WHILE
+
+            // literals are written on purpose and not reported
+            WHILE TRUE DO
+                EXIT;
+            END_WHILE
+            IF FALSE THEN
+                x := 5;
+            END_IF
+        END_FUNCTION
+        ";
+
+    let expected = vec!["Condition is always TRUE", "Condition is always FALSE", "Condition is always FALSE"];
+    assert_eq!(get_messages(&parse_and_validate(src), "E164"), expected);
+    assert_eq!(get_messages(&parse_and_validate_lowered_loops(src), "E164"), expected);
+}
+
+#[test]
+fn case_labels_covered_by_earlier_labels_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        VAR_GLOBAL CONSTANT
+            FIVE : DINT := 5;
+        END_VAR
+
+        FUNCTION foo
+        VAR
+            x : DINT;
+        END_VAR
+            CASE x OF
+                1..10:   x := 1;
+                FIVE:    x := 2;
+                11, 3:   x := 3;
+                8..11:   x := 4;
+                0..20:   x := 5;
+                11..13:  x := 6;
+                5:       x := 7;
+            END_CASE
+        END_FUNCTION
+        ",
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E165"),
+        vec![
+            "Case label `FIVE` is never reached, its values are covered by earlier labels",
+            "Case label `3` is never reached, its values are covered by earlier labels",
+            "Case label `8..11` is never reached, its values are covered by earlier labels",
+            "Case label `11..13` is never reached, its values are covered by earlier labels",
+        ]
+    );
+    // a repeated single value is a duplicate and not reported again
+    assert_eq!(
+        get_messages(&diagnostics, "E078"),
+        vec!["Duplicate condition value: 5. Occurred more than once!"]
+    );
+}
+
+#[test]
+fn for_loops_which_never_execute_are_reported() {
+    let src = "
+        VAR_GLOBAL CONSTANT
+            MAX : DINT := 10;
+        END_VAR
+
+        FUNCTION foo
+        VAR
+            i, n : DINT;
+        END_VAR
+            FOR i := MAX TO 0 DO
+            END_FOR
+            FOR i := 0 TO MAX BY -1 DO
+            END_FOR
+
+            // these run at least once or cannot be evaluated
+            FOR i := MAX TO 0 BY -1 DO
+            END_FOR
+            FOR i := 0 TO MAX DO
+            END_FOR
+            FOR i := 5 TO 5 DO
+            END_FOR
+            FOR i := n TO 0 DO
+            END_FOR
+        END_FUNCTION
+        ";
+
+    let expected = vec![
+        "FOR loop is never executed, its start value 10 is greater than its end value 0",
+        "FOR loop is never executed, its start value 0 is less than its end value 10",
+    ];
+    assert_eq!(get_messages(&parse_and_validate(src), "E166"), expected);
+    assert_eq!(get_messages(&parse_and_validate_lowered_loops(src), "E166"), expected);
+}
+
+#[test]
+fn case_over_enum_without_else_must_cover_all_variants() {
+    let diagnostics = parse_and_validate(
+        "
+        TYPE Color : (red, green, blue, yellow); END_TYPE
+
+        FUNCTION foo
+        VAR
+            color : Color;
+            x : DINT;
+        END_VAR
+            CASE color OF
+                red:          x := 1;
+                Color#green:  x := 2;
+            END_CASE
+
+            // covered by ELSE
+            CASE color OF
+                red: x := 1;
+            ELSE
+                x := 2;
+            END_CASE
+
+            // all variants covered
+            CASE color OF
+                red, green:    x := 1;
+                blue..yellow:  x := 2;
+            END_CASE
+        END_FUNCTION
+        ",
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E167"),
+        vec!["CASE does not cover the variants `blue`, `yellow` of `Color` and has no ELSE"]
+    );
+}
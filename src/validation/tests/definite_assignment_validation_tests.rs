@@ -0,0 +1,255 @@
+use plc_diagnostics::diagnostics::Diagnostic;
+
+use crate::test_utils::tests::parse_and_validate_definite_assignment;
+
+fn get_messages(diagnostics: &[Diagnostic], code: &str) -> Vec<String> {
+    diagnostics
+        .iter()
+        .filter(|it| it.get_error_code() == code)
+        .map(|it| it.get_message().to_string())
+        .collect()
+}
+
+#[test]
+fn return_values_not_assigned_on_every_path_are_reported() {
+    let diagnostics = parse_and_validate_definite_assignment(
+        "
+        FUNCTION assigned_in_all_branches : INT
+        VAR_INPUT x : INT; END_VAR
+            IF x > 0 THEN
+                assigned_in_all_branches := 1;
+            ELSE
+                assigned_in_all_branches := 2;
+            END_IF
+        END_FUNCTION
+
+        FUNCTION missing_else : INT
+        VAR_INPUT x : INT; END_VAR
+            IF x > 0 THEN
+                missing_else := 1;
+            END_IF
+        END_FUNCTION
+
+        FUNCTION early_return : INT
+        VAR_INPUT x : INT; END_VAR
+            IF x > 0 THEN
+                RETURN;
+            END_IF
+            early_return := x;
+        END_FUNCTION
+
+        FUNCTION never_assigned : INT
+        VAR_INPUT x : INT; END_VAR
+            x := x + 1;
+        END_FUNCTION
+
+        FUNCTION no_return_value
+        END_FUNCTION
+
+        FUNCTION_BLOCK fb
+        METHOD case_without_else : BOOL
+        VAR_INPUT x : INT; END_VAR
+            CASE x OF
+                1: case_without_else := TRUE;
+                2: case_without_else := FALSE;
+            END_CASE
+        END_METHOD
+        END_FUNCTION_BLOCK
+        ",
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E160"),
+        [
+            "Return value of `missing_else` is not assigned on every path",
+            "Return value of `early_return` is not assigned on every path",
+            "Return value of `never_assigned` is never assigned",
+            "Return value of `fb.case_without_else` is not assigned on every path",
+        ]
+    );
+}
+
+#[test]
+fn stubs_and_classes_with_parameters_are_not_analyzed() {
+    let diagnostics = parse_and_validate_definite_assignment(
+        "
+        INTERFACE counter
+        METHOD next : INT
+        END_METHOD
+        END_INTERFACE
+
+        FUNCTION_BLOCK fb IMPLEMENTS counter
+        METHOD next : INT
+            ;
+        END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK stub
+        VAR_OUTPUT
+            done : BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        CLASS cls
+        VAR_OUTPUT
+            value : INT;
+        END_VAR
+        METHOD get : INT
+            IF value > 0 THEN
+                get := value;
+            END_IF
+        END_METHOD
+        END_CLASS
+        ",
+    );
+
+    // the output of the class is rejected with E019, its methods are analyzed nonetheless
+    assert!(get_messages(&diagnostics, "E161").is_empty());
+    assert_eq!(
+        get_messages(&diagnostics, "E160"),
+        ["Return value of `cls.get` is not assigned on every path"]
+    );
+}
+
+#[test]
+fn loops_are_followed_through_exit_and_continue() {
+    let diagnostics = parse_and_validate_definite_assignment(
+        "
+        FUNCTION assigned_before_exit : INT
+        VAR_INPUT x : INT; END_VAR
+            WHILE TRUE DO
+                IF x > 10 THEN
+                    assigned_before_exit := x;
+                    EXIT;
+                END_IF
+                x := x + 1;
+            END_WHILE
+        END_FUNCTION
+
+        FUNCTION exit_before_assignment : INT
+        VAR_INPUT x : INT; END_VAR
+            WHILE TRUE DO
+                IF x > 10 THEN
+                    EXIT;
+                END_IF
+                exit_before_assignment := x;
+            END_WHILE
+        END_FUNCTION
+
+        FUNCTION repeat_runs_once : INT
+        VAR_INPUT x : INT; END_VAR
+            REPEAT
+                repeat_runs_once := x;
+                x := x + 1;
+            UNTIL x > 10
+            END_REPEAT
+        END_FUNCTION
+
+        FUNCTION for_may_not_run : INT
+        VAR_INPUT x : INT; END_VAR
+        VAR i : INT; END_VAR
+            FOR i := 0 TO x DO
+                for_may_not_run := i;
+            END_FOR
+        END_FUNCTION
+
+        FUNCTION continue_skips_assignment : INT
+        VAR_INPUT x : INT; END_VAR
+            REPEAT
+                x := x + 1;
+                IF x < 5 THEN
+                    CONTINUE;
+                END_IF
+                continue_skips_assignment := x;
+            UNTIL x > 10
+            END_REPEAT
+        END_FUNCTION
+        ",
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E160"),
+        [
+            "Return value of `exit_before_assignment` is not assigned on every path",
+            "Return value of `for_may_not_run` is not assigned on every path",
+            "Return value of `continue_skips_assignment` is not assigned on every path",
+        ]
+    );
+}
+
+#[test]
+fn outputs_never_written_are_reported() {
+    let diagnostics = parse_and_validate_definite_assignment(
+        "
+        FUNCTION_BLOCK motor
+        VAR_INPUT enable : BOOL; END_VAR
+        VAR_OUTPUT
+            running : BOOL;
+            fault : BOOL;
+            ready : BOOL := TRUE;
+            error : BOOL;
+            mirrored : BOOL;
+        END_VAR
+            running := enable;
+        END_FUNCTION_BLOCK
+
+        ACTION motor.reset
+            error := FALSE;
+        END_ACTION
+
+        FUNCTION split
+        VAR_INPUT x : INT; END_VAR
+        VAR_OUTPUT high, low : INT; END_VAR
+            high := x / 256;
+        END_FUNCTION
+
+        PROGRAM main
+        VAR
+            m : motor;
+            h, l : INT;
+        END_VAR
+            m.mirrored := m.running;
+            split(x := 1234, high => h, low => l);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E161"),
+        ["Output `fault` of `motor` is never written", "Output `low` of `split` is never written"]
+    );
+}
+
+#[test]
+fn temporaries_read_before_assignment_are_reported() {
+    let diagnostics = parse_and_validate_definite_assignment(
+        "
+        FUNCTION_BLOCK filter
+        VAR_INPUT value : REAL; END_VAR
+        VAR average : REAL; END_VAR
+        VAR_TEMP
+            delta : REAL;
+            assigned : REAL;
+            initialized : REAL := 1.0;
+            values : ARRAY[0..1] OF REAL;
+            never : REAL;
+        END_VAR
+            assigned := value;
+            values[0] := assigned * initialized;
+            IF value > average THEN
+                delta := value - average;
+            END_IF
+            average := average + delta / 10 + values[1];
+            average := never + never;
+        END_FUNCTION_BLOCK
+        ",
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E162"),
+        [
+            "Temporary variable `delta` may be read before it is assigned",
+            "Temporary variable `never` may be read before it is assigned",
+        ]
+    );
+}
@@ -0,0 +1,594 @@
+use insta::assert_snapshot;
+use plc_diagnostics::{
+    diagnostician::Diagnostician,
+    diagnostics::{
+        diagnostics_registry::ErrorConfiguration,
+        lints::{LintSettings, NamingKind, NamingPattern},
+        Diagnostic,
+    },
+    reporter::DiagnosticReporter,
+};
+
+use crate::test_utils::tests::{parse_and_lint, parse_and_validate_reachability};
+
+fn get_messages(diagnostics: &[Diagnostic], code: &str) -> Vec<String> {
+    diagnostics
+        .iter()
+        .filter(|it| it.get_error_code() == code)
+        .map(|it| it.get_message().to_string())
+        .collect()
+}
+
+/// Reports the unreachable declarations and the lints of the source like `plc` does with the given error
+/// configuration, i.e. with the configured severities and lint settings
+fn report_with_configuration(src: &str, configuration: &str) -> String {
+    let ErrorConfiguration { diagnostics, lints } = serde_json::from_str(configuration).unwrap();
+    let mut found = parse_and_validate_reachability(src);
+    found.extend(parse_and_lint(src, lints));
+
+    let mut diagnostician = Diagnostician::buffered().with_configuration(diagnostics);
+    diagnostician.register_file("<internal>".to_string(), src.to_string());
+    diagnostician.handle(&found);
+    diagnostician.buffer().unwrap()
+}
+
+#[test]
+fn declarations_used_by_a_program_are_reachable() {
+    let diagnostics = parse_and_validate_reachability(
+        "
+        TYPE Point : STRUCT x, y : INT; END_STRUCT END_TYPE
+
+        FUNCTION length : INT
+        VAR_INPUT p : Point; END_VAR
+            length := p.x + p.y;
+        END_FUNCTION
+
+        FUNCTION_BLOCK fb
+        METHOD reset
+        END_METHOD
+        END_FUNCTION_BLOCK
+
+        ACTION fb.clear
+        END_ACTION
+
+        PROGRAM main
+        VAR
+            inst : fb;
+            p : Point;
+        END_VAR
+            inst.reset();
+            inst.clear();
+            length(p);
+        END_PROGRAM
+
+        {external}
+        FUNCTION from_c : INT
+        END_FUNCTION
+        ",
+    );
+
+    assert!(get_messages(&diagnostics, "E153").is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn unreachable_declarations_are_reported_with_their_configured_severity() {
+    let src = "
+        FUNCTION helper : INT
+        END_FUNCTION
+
+        PROGRAM main
+        END_PROGRAM
+        ";
+
+    assert_eq!(report_with_configuration(src, "{}"), "");
+    assert_snapshot!(report_with_configuration(src, r#"{ "warning": ["E153"] }"#), @r"
+    warning[E153]: Function `helper` is unreachable from any program or external entry point
+      ┌─ <internal>:2:18
+      │
+    2 │         FUNCTION helper : INT
+      │                  ^^^^^^ Function `helper` is unreachable from any program or external entry point
+    ");
+}
+
+#[test]
+fn names_matching_their_pattern_are_not_reported() {
+    let settings = LintSettings {
+        naming: [
+            (NamingKind::Program, NamingPattern::new("^PRG_").unwrap()),
+            (NamingKind::Local, NamingPattern::new("^[a-z]").unwrap()),
+            (NamingKind::Constant, NamingPattern::new("^[A-Z_]+$").unwrap()),
+        ]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    };
+    let diagnostics = parse_and_lint(
+        "
+        PROGRAM PRG_main
+        VAR
+            counter : INT;
+        END_VAR
+        VAR CONSTANT
+            MAX_COUNT : INT := 10;
+        END_VAR
+        END_PROGRAM
+        ",
+        settings,
+    );
+
+    assert!(get_messages(&diagnostics, "E155").is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn names_not_matching_their_pattern_are_reported_with_their_configured_severity() {
+    let src = "
+        PROGRAM main
+        END_PROGRAM
+        ";
+
+    assert_eq!(report_with_configuration(src, r#"{ "lints": { "naming": { "program": "^PRG_" } } }"#), "");
+    assert_snapshot!(
+        report_with_configuration(src, r#"{ "error": ["E155"], "lints": { "naming": { "program": "^PRG_" } } }"#),
+        @r"
+    error[E155]: Name `main` does not match the naming pattern `^PRG_` configured for `program`
+      ┌─ <internal>:2:17
+      │
+    2 │         PROGRAM main
+      │                 ^^^^ Name `main` does not match the naming pattern `^PRG_` configured for `program`
+    "
+    );
+}
+
+#[test]
+fn names_not_matching_their_pattern_are_reported() {
+    let settings = LintSettings {
+        naming: [
+            (NamingKind::FunctionBlockInstance, NamingPattern::new("^fb").unwrap()),
+            (NamingKind::Local, NamingPattern::new("^[a-z]").unwrap()),
+            (NamingKind::FunctionBlock, NamingPattern::new("^FB_").unwrap()),
+        ]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    };
+    let diagnostics = parse_and_lint(
+        "
+        FUNCTION_BLOCK FB_Motor
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK Valve
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            fbMotor : FB_Motor;
+            motor : FB_Motor;
+            counter : INT;
+            Speed : INT;
+        END_VAR
+        END_PROGRAM
+        ",
+        settings,
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E155"),
+        [
+            "Name `Valve` does not match the naming pattern `^FB_` configured for `function_block`",
+            "Name `motor` does not match the naming pattern `^fb` configured for `function_block_instance`",
+            "Name `Speed` does not match the naming pattern `^[a-z]` configured for `local`",
+        ]
+    );
+}
+
+#[test]
+fn invalid_naming_patterns_are_rejected_when_reading_the_configuration() {
+    let error =
+        serde_json::de::from_str::<ErrorConfiguration>(r#"{ "lints": { "naming": { "program": "(" } } }"#)
+            .unwrap_err();
+
+    assert!(error.to_string().starts_with("invalid naming pattern `(`"));
+}
+
+#[test]
+fn unused_variables_and_parameters_are_reported() {
+    let diagnostics = parse_and_lint(
+        "
+        VAR_GLOBAL CONSTANT
+            size : INT := 3;
+        END_VAR
+
+        FUNCTION calc : INT
+        VAR_INPUT
+            a : INT;
+            unused_input : INT;
+        END_VAR
+        VAR
+            unused_local : INT;
+            buffer : ARRAY[0..size] OF INT;
+        END_VAR
+            buffer[0] := a;
+            calc := buffer[0];
+        END_FUNCTION
+
+        FUNCTION_BLOCK fb
+        VAR_INPUT
+            set_by_caller : BOOL;
+        END_VAR
+        VAR_OUTPUT
+            q : BOOL;
+        END_VAR
+            q := TRUE;
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            inst : fb;
+            result : BOOL;
+            input AT %IX1.0 : BOOL;
+        END_VAR
+            inst(set_by_caller := TRUE, q => result);
+            calc(a := 1, unused_input := 2);
+        END_PROGRAM
+        ",
+        LintSettings::default(),
+    );
+
+    assert_eq!(get_messages(&diagnostics, "E156"), ["Variable `unused_local` is never used"]);
+    assert_eq!(
+        get_messages(&diagnostics, "E157"),
+        [
+            "Parameter `unused_input` of `calc` is never used",
+            "Parameter `set_by_caller` of `fb` is never used"
+        ]
+    );
+}
+
+#[test]
+fn used_variables_and_parameters_are_not_reported() {
+    let diagnostics = parse_and_lint(
+        "
+        FUNCTION scale : INT
+        VAR_INPUT
+            value : INT;
+        END_VAR
+        VAR_IN_OUT
+            factor : INT;
+        END_VAR
+        VAR
+            result : INT;
+        END_VAR
+        VAR_TEMP
+            size : INT;
+        END_VAR
+            size := 1;
+            result := value * factor * size;
+            scale := result;
+        END_FUNCTION
+
+        FUNCTION_BLOCK fb
+        VAR_OUTPUT
+            q : BOOL;
+        END_VAR
+            q := TRUE;
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR
+            inst : fb;
+            factor : INT;
+        END_VAR
+            inst();
+            scale(1, factor);
+        END_PROGRAM
+        ",
+        LintSettings::default(),
+    );
+
+    assert!(get_messages(&diagnostics, "E156").is_empty(), "{diagnostics:?}");
+    assert!(get_messages(&diagnostics, "E157").is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn unused_variables_and_parameters_are_reported_with_their_configured_severity() {
+    let src = "
+        FUNCTION calc : INT
+        VAR_INPUT
+            unused_input : INT;
+        END_VAR
+        VAR
+            unused_local : INT;
+        END_VAR
+            calc := 0;
+        END_FUNCTION
+
+        PROGRAM main
+            calc(1);
+        END_PROGRAM
+        ";
+
+    assert_eq!(report_with_configuration(src, "{}"), "");
+    assert_snapshot!(
+        report_with_configuration(src, r#"{ "warning": ["E156"], "info": ["E157"] }"#),
+        @r"
+    note[E157]: Parameter `unused_input` of `calc` is never used
+      ┌─ <internal>:4:13
+      │
+    4 │             unused_input : INT;
+      │             ^^^^^^^^^^^^ Parameter `unused_input` of `calc` is never used
+
+    warning[E156]: Variable `unused_local` is never used
+      ┌─ <internal>:7:13
+      │
+    7 │             unused_local : INT;
+      │             ^^^^^^^^^^^^ Variable `unused_local` is never used
+    "
+    );
+}
+
+#[test]
+fn unused_parameters_of_inherited_methods_are_not_reported() {
+    let diagnostics = parse_and_lint(
+        "
+        INTERFACE IShape
+        METHOD area : REAL
+        VAR_INPUT scale : REAL; END_VAR
+        END_METHOD
+        END_INTERFACE
+
+        FUNCTION_BLOCK base IMPLEMENTS IShape
+        METHOD area : REAL
+        VAR_INPUT scale : REAL; END_VAR
+            area := scale;
+        END_METHOD
+        METHOD describe
+        VAR_INPUT verbose : BOOL; END_VAR
+        END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK derived EXTENDS base
+        METHOD describe
+        VAR_INPUT verbose : BOOL; END_VAR
+        END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK square IMPLEMENTS IShape
+        METHOD area : REAL
+        VAR_INPUT scale : REAL; END_VAR
+            area := 4;
+        END_METHOD
+        END_FUNCTION_BLOCK
+        ",
+        LintSettings::default(),
+    );
+
+    // only the declaration in `base` introduces the parameter, the others are given by it
+    assert_eq!(get_messages(&diagnostics, "E157"), ["Parameter `verbose` of `base.describe` is never used"]);
+}
+
+#[test]
+fn variables_shadowing_globals_and_members_are_reported() {
+    let diagnostics = parse_and_lint(
+        "
+        VAR_GLOBAL
+            counter : INT;
+        END_VAR
+
+        FUNCTION_BLOCK fb
+        VAR
+            speed : INT;
+        END_VAR
+        METHOD accelerate
+        VAR
+            speed : INT;
+        END_VAR
+            speed := 1;
+        END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION count : INT
+        VAR
+            counter : INT;
+        END_VAR
+            count := counter;
+        END_FUNCTION
+        ",
+        LintSettings::default(),
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E158"),
+        [
+            "Variable `speed` of method `accelerate` shadows the member `speed` of `fb`",
+            "Variable `counter` shadows the global variable `counter`",
+        ]
+    );
+}
+
+#[test]
+fn variables_with_distinct_names_do_not_shadow() {
+    let diagnostics = parse_and_lint(
+        "
+        VAR_GLOBAL
+            counter : INT;
+        END_VAR
+
+        FUNCTION_BLOCK fb
+        VAR
+            speed : INT;
+        END_VAR
+        METHOD accelerate
+        VAR
+            delta : INT;
+        END_VAR
+            speed := speed + delta + counter;
+        END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK other
+        VAR
+            delta : INT;
+        END_VAR
+        METHOD accelerate
+        VAR
+            speed : INT;
+        END_VAR
+            speed := delta;
+        END_METHOD
+        END_FUNCTION_BLOCK
+        ",
+        LintSettings::default(),
+    );
+
+    assert!(get_messages(&diagnostics, "E158").is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn shadowing_variables_are_reported_with_their_configured_severity() {
+    let src = "
+        VAR_GLOBAL
+            counter : INT;
+        END_VAR
+
+        PROGRAM main
+        VAR
+            counter : INT;
+        END_VAR
+            counter := 0;
+        END_PROGRAM
+        ";
+
+    assert_eq!(report_with_configuration(src, "{}"), "");
+    assert_snapshot!(report_with_configuration(src, r#"{ "error": ["E158"] }"#), @r"
+    error[E158]: Variable `counter` shadows the global variable `counter`
+      ┌─ <internal>:8:13
+      │
+    3 │             counter : INT;
+      │             ------- see also
+      ·
+    8 │             counter : INT;
+      │             ^^^^^^^ Variable `counter` shadows the global variable `counter`
+    ");
+}
+
+#[test]
+fn allowed_numbers_and_numbers_in_declarations_are_not_magic() {
+    let settings = LintSettings { allowed_numbers: vec![0.0, 1.0, 2.0], ..Default::default() };
+    let diagnostics = parse_and_lint(
+        "
+        PROGRAM main
+        VAR
+            level : REAL := 42.5;
+            values : ARRAY[0..9] OF INT := [10(7)];
+        END_VAR
+        VAR CONSTANT
+            max_level : REAL := 250.0;
+        END_VAR
+            level := level / max_level * 2;
+            IF level > 0 THEN
+                values[1] := 0;
+            END_IF
+        END_PROGRAM
+        ",
+        settings,
+    );
+
+    assert!(get_messages(&diagnostics, "E159").is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn magic_numbers_are_reported_with_their_configured_severity() {
+    let src = "
+        PROGRAM main
+        VAR
+            level : REAL;
+        END_VAR
+            level := level * 3;
+        END_PROGRAM
+        ";
+
+    assert_eq!(report_with_configuration(src, "{}"), "");
+    // the configured numbers are allowed
+    assert_eq!(
+        report_with_configuration(src, r#"{ "warning": ["E159"], "lints": { "allowed_numbers": [3] } }"#),
+        ""
+    );
+    assert_snapshot!(report_with_configuration(src, r#"{ "warning": ["E159"] }"#), @r"
+    warning[E159]: Magic number `3` in `main`, consider declaring a named constant
+      ┌─ <internal>:6:30
+      │
+    6 │             level := level * 3;
+      │                              ^ Magic number `3` in `main`, consider declaring a named constant
+    ");
+}
+
+#[test]
+fn magic_numbers_in_bodies_are_reported() {
+    let settings = LintSettings { allowed_numbers: vec![0.0, 1.0, 100.0], ..Default::default() };
+    let diagnostics = parse_and_lint(
+        "
+        PROGRAM main
+        VAR
+            level : REAL := 42.0;
+            percent : REAL;
+        END_VAR
+        VAR CONSTANT
+            max_level : REAL := 250.0;
+        END_VAR
+            percent := level / max_level * 100;
+            IF level > 0 THEN
+                level := level - 0.5;
+            END_IF
+            level := level * 3;
+        END_PROGRAM
+        ",
+        settings,
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E159"),
+        [
+            "Magic number `0.5` in `main`, consider declaring a named constant",
+            "Magic number `3` in `main`, consider declaring a named constant",
+        ]
+    );
+}
+
+#[test]
+fn identifiers_differing_in_case_from_their_declaration_are_reported() {
+    let diagnostics = parse_and_lint(
+        "
+        FUNCTION CalcSum : DINT
+        VAR_INPUT a, b : DINT; END_VAR
+            CalcSum := a + b;
+        END_FUNCTION
+
+        PROGRAM main
+        VAR
+            motorSpeed : DINT;
+        END_VAR
+            MotorSpeed := calcsum(a := motorSpeed, b := 1);
+            motorspeed := MotorSpeed + 1;
+        END_PROGRAM
+        ",
+        LintSettings::default(),
+    );
+
+    assert_eq!(
+        get_messages(&diagnostics, "E168"),
+        [
+            "Identifier `MotorSpeed` is declared as `motorSpeed`",
+            "Identifier `calcsum` is declared as `CalcSum`",
+            "Identifier `motorspeed` is declared as `motorSpeed`",
+            "Identifier `MotorSpeed` is declared as `motorSpeed`",
+        ]
+    );
+    let suggestions = diagnostics
+        .iter()
+        .filter(|it| it.get_error_code() == "E168")
+        .flat_map(|it| it.get_suggestions())
+        .map(|it| (it.message.as_str(), it.edits[0].replacement.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(suggestions[1], ("replace with `CalcSum`", "CalcSum"));
+    assert!(suggestions.iter().all(|(_, replacement)| !replacement.is_empty()));
+}
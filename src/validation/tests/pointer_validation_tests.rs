@@ -376,6 +376,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -391,6 +392,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -406,6 +408,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -421,6 +424,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -436,6 +440,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -451,6 +456,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -466,6 +472,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -481,6 +488,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -496,6 +504,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -511,6 +520,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -526,6 +536,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -541,6 +552,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -556,6 +568,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -571,6 +584,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -586,6 +600,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -601,6 +616,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -616,6 +632,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -631,6 +648,7 @@ fn pointer_to_validates_assignment_when_not_dealing_with_memory_address_in_body(
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
     ]
@@ -771,6 +789,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -786,6 +805,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -801,6 +821,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -816,6 +837,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -831,6 +853,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -846,6 +869,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -861,6 +885,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -876,6 +901,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -891,6 +917,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
         Diagnostic {
@@ -906,6 +933,7 @@ fn pointer_to_validates_assignment_of_non_pointer_sized_integers() {
                 error_code: "E037",
                 sub_diagnostics: [],
                 internal_error: None,
+                suggestions: [],
             },
         },
     ]
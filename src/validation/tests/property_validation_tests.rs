@@ -882,6 +882,12 @@ fn undefined_references_inheritance() {
        │
     24 │             parent_fb.myProp := 5;                  // Error, the `parent` FB does not define a PROPERTY_SET
        │                       ^^^^^^ PROPERTY_SET for property `myProp` is not defined
+
+    warning[E160]: Return value of `main` is never assigned
+       ┌─ <internal>:18:18
+       │
+    18 │         FUNCTION main : DINT
+       │                  ^^^^ Return value of `main` is never assigned
     ");
 }
 
@@ -2143,6 +2143,12 @@ fn if_statement_triggers_error_if_condition_is_not_boolean() {
        │
     10 │             ELSIF   z THEN // Returns an error, because we're dealing with neither integers nor booleans
        │                     ^ Expected a boolean, got `STRING`
+
+    warning[E164]: Condition is always TRUE
+       ┌─ <internal>:16:21
+       │
+    16 │             ELSIF   (0 < 1)         THEN
+       │                     ^^^^^^^ Condition is always TRUE
     ");
 }
 
@@ -2183,6 +2189,12 @@ fn while_loop_triggers_error_if_condition_is_not_boolean() {
        │
     10 │             WHILE z DO END_WHILE // Returns an error, because we're dealing with neither integers nor booleans
        │                   ^ Expected a boolean, got `STRING`
+
+    warning[E164]: Condition is always TRUE
+       ┌─ <internal>:16:19
+       │
+    16 │             WHILE (0 < 1)       DO END_WHILE
+       │                   ^^^^^^^ Condition is always TRUE
     ");
 }
 
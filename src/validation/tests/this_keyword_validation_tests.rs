@@ -417,6 +417,12 @@ fn this_in_unsupported_pous_is_not_allowed() {
        │
     18 │             THIS^;
        │             ^^^^ Invalid use of `THIS`. Usage is only allowed within `FUNCTION_BLOCK` and its `METHOD`s and `ACTION`s.
+
+    warning[E160]: Return value of `baz` is never assigned
+       ┌─ <internal>:17:18
+       │
+    17 │         FUNCTION baz : INT
+       │                  ^^^ Return value of `baz` is never assigned
     ");
 }
 
@@ -446,7 +452,13 @@ fn this_calling_functionblock_body_from_method_is_ok() {
         END_FUNCTION_BLOCK
     "#,
     );
-    assert!(diagnostics.is_empty());
+    assert_snapshot!(diagnostics, @r"
+    warning[E160]: Return value of `fb.foo` is never assigned
+      ┌─ <internal>:3:20
+      │
+    3 │             METHOD foo : INT
+      │                    ^^^ Return value of `fb.foo` is never assigned
+    ");
 }
 
 #[test]
@@ -471,11 +483,17 @@ fn this_member_access_without_deref_is_an_error() {
         END_FUNCTION_BLOCK
     "#,
     );
-    assert_snapshot!(diagnostics, @"
+    assert_snapshot!(diagnostics, @r"
     error[E141]: Cannot access `a` on `POINTER TO fb`; dereference with `^` first
        ┌─ <internal>:12:22
        │
     12 │                 THIS.a.legs();
        │                      ^ Cannot access `a` on `POINTER TO fb`; dereference with `^` first
+
+    warning[E160]: Return value of `fb.m` is never assigned
+       ┌─ <internal>:11:20
+       │
+    11 │             METHOD m : DINT
+       │                    ^ Return value of `fb.m` is never assigned
     ");
 }
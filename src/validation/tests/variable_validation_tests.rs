@@ -2011,3 +2011,47 @@ fn fb_var_temp_visible_inside_fb_body_and_actions() {
 
     assert!(diagnostics.is_empty(), "expected clean diagnostics, got:\n{diagnostics}");
 }
+
+#[test]
+fn instances_mixing_retain_and_persistent_members_are_reported() {
+    let diagnostics = parse_and_validate_buffered(
+        r#"
+        FUNCTION_BLOCK counter
+        VAR RETAIN
+            count : DINT;
+        END_VAR
+        VAR PERSISTENT
+            total : DINT;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK persistent_counter
+        VAR RETAIN PERSISTENT
+            total : DINT;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        VAR_GLOBAL
+            valid : persistent_counter;
+            mixed : counter;
+        END_VAR
+        VAR_GLOBAL RETAIN
+            also_mixed : persistent_counter;
+        END_VAR
+        "#,
+    );
+
+    assert_snapshot!(diagnostics, @r"
+    error[E172]: Variable mixes values of the `.retain` and `.persistent` sections, which cannot be placed into one section
+       ┌─ <internal>:19:13
+       │
+    19 │             mixed : counter;
+       │             ^^^^^ Variable mixes values of the `.retain` and `.persistent` sections, which cannot be placed into one section
+
+    error[E172]: Variable mixes values of the `.retain` and `.retain_persistent` sections, which cannot be placed into one section
+       ┌─ <internal>:22:13
+       │
+    22 │             also_mixed : persistent_counter;
+       │             ^^^^^^^^^^ Variable mixes values of the `.retain` and `.retain_persistent` sections, which cannot be placed into one section
+    ");
+}
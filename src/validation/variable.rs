@@ -125,6 +125,7 @@ pub fn visit_variable_block<T: AnnotationMap>(
     for variable in &block.variables {
         visit_variable(validator, variable, context);
         validate_template_address(validator, pou, variable);
+        validate_retain_sections(validator, pou, variable, context);
 
         if let Some(referenced_type) = variable.data_type_declaration.get_referenced_type() {
             if context.index.get_type_information_or_void(referenced_type).is_vla() {
@@ -160,6 +161,35 @@ fn validate_template_address(validator: &mut Validator, pou: Option<&Pou>, varia
     }
 }
 
+/// A variable is placed into the section of its `RETAIN` and `PERSISTENT` modifiers as a whole, so an
+/// instance must not mix members of different sections
+fn validate_retain_sections<T: AnnotationMap>(
+    validator: &mut Validator,
+    pou: Option<&Pou>,
+    variable: &Variable,
+    context: &ValidationContext<T>,
+) {
+    if pou.is_some_and(|it| matches!(it.kind, PouType::Function | PouType::Method { .. })) {
+        return;
+    }
+    let Some(entry) = context.index.find_variable(pou.map(|it| it.name.as_str()), &[&variable.name]) else {
+        return;
+    };
+    let sections = entry.get_retain_sections(context.index);
+    if sections.len() > 1 {
+        let sections = sections.iter().map(|it| format!("`{}`", it.get_name())).collect::<Vec<_>>();
+        validator.push_diagnostic(
+            // retain variables of programs are moved to generated globals, so the variable is not named
+            Diagnostic::new(format!(
+                "Variable mixes values of the {} sections, which cannot be placed into one section",
+                sections.join(" and ")
+            ))
+            .with_error_code("E172")
+            .with_location(&variable.location),
+        );
+    }
+}
+
 fn validate_variable_block(validator: &mut Validator, block: &VariableBlock) {
     if matches!(block.kind, VariableBlockType::External) {
         validator.push_diagnostic(
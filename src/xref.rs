@@ -0,0 +1,464 @@
+//! Cross-reference report listing where globals, program variables and function block members
+//! are read, written or have their address taken.
+//!
+//! The report is collected from the annotated AST: every reference the resolver annotated with a
+//! [`StatementAnnotation::Variable`] is one access site. The kind of the access is derived from
+//! the context of the reference:
+//! - the left side of `:=` and the target of `=>` are writes
+//! - the left side of `REF=` is a write, its right side has its address taken
+//! - arguments of `ADR`/`REF` have their address taken
+//! - arguments passed to a `VAR_IN_OUT` parameter are reported as `InOut`, since the callee may
+//!   read and write them
+//! - everything else is a read
+//!
+//! Accesses to a hardware-bound variable (`x AT %IX1.2 : BOOL`) are also reported for the
+//! synthetic global the variable is an alias of, naming the alias in `via`.
+//! Code generated by the compiler (e.g. initializers) is not part of the report.
+
+use plc_ast::{
+    ast::{
+        flatten_expression_list, Assignment, AstNode, AstStatement, CallStatement, CompilationUnit,
+        ReferenceAccess, ReferenceExpr,
+    },
+    control_statements::ForLoopStatement,
+    visitor::AstVisitor,
+};
+use plc_diagnostics::diagnostics::Diagnostic;
+use plc_source::source_location::SourceLocation;
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+
+use crate::{
+    hw_map::resolve_hardware_binding,
+    index::{Index, VariableIndexEntry, VariableType},
+    resolver::{AnnotationMap, StatementAnnotation},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CrossReferences {
+    pub variables: Vec<VariableReferences>,
+}
+
+/// All access sites of a single variable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VariableReferences {
+    /// the qualified name of the variable, e.g. `mainProg.counter` or `MyFb.out`
+    pub name: String,
+    pub kind: VariableKind,
+    pub data_type: String,
+    /// the source-form hardware address of hardware-bound variables
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub accesses: Vec<AccessSite>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum VariableKind {
+    Global,
+    ProgramVariable,
+    FunctionBlockMember,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccessSite {
+    pub access: Access,
+    /// the POU (or method/action) the access is located in
+    pub pou: String,
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    /// the alias the variable was accessed through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum Access {
+    Read,
+    Write,
+    AddressOf,
+    InOut,
+}
+
+/// Collects the access sites of every global, program variable and function block member declared in the index
+pub fn collect_cross_references<'a>(
+    units: impl IntoIterator<Item = &'a CompilationUnit>,
+    index: &Index,
+    annotations: &impl AnnotationMap,
+) -> Result<CrossReferences, Diagnostic> {
+    let mut collector = ReferenceCollector {
+        index,
+        annotations,
+        variables: Vec::new(),
+        lookup: FxHashMap::default(),
+        aliases: FxHashMap::default(),
+        pou: String::new(),
+        access: Access::Read,
+    };
+
+    for global in index.get_globals().values() {
+        collector.declare(global, VariableKind::Global)?;
+    }
+    for pou in index.get_pous().values() {
+        let kind = if pou.is_program() {
+            VariableKind::ProgramVariable
+        } else if pou.is_function_block() || pou.is_class() {
+            VariableKind::FunctionBlockMember
+        } else {
+            continue;
+        };
+        for member in index.get_pou_members(pou.get_name()).iter().filter(|it| !it.is_temp()) {
+            collector.declare(member, kind)?;
+        }
+    }
+
+    for unit in units {
+        for implementation in unit.implementations.iter().filter(|it| !is_generated(&it.location)) {
+            collector.pou = implementation.name.clone();
+            collector.visit_statement_list(&implementation.statements);
+        }
+    }
+
+    Ok(CrossReferences { variables: collector.variables })
+}
+
+struct ReferenceCollector<'i, T: AnnotationMap> {
+    index: &'i Index,
+    annotations: &'i T,
+    variables: Vec<VariableReferences>,
+    /// the position of each variable in `variables`, keyed by its lowercase qualified name
+    lookup: FxHashMap<String, usize>,
+    /// the lowercase qualified names of hardware-bound variables and the synthetic global they alias
+    aliases: FxHashMap<String, String>,
+    pou: String,
+    access: Access,
+}
+
+impl<T: AnnotationMap> ReferenceCollector<'_, T> {
+    fn declare(&mut self, variable: &VariableIndexEntry, kind: VariableKind) -> Result<(), Diagnostic> {
+        if is_generated(&variable.source_location) {
+            return Ok(());
+        }
+        let address = match variable.get_hardware_binding().filter(|_| !variable.is_template()) {
+            Some(binding) => {
+                let (mangled, address) = resolve_hardware_binding(self.index, binding)?;
+                self.aliases.insert(variable.get_qualified_name().to_lowercase(), mangled.to_lowercase());
+                Some(address)
+            }
+            None => None,
+        };
+        self.lookup.insert(variable.get_qualified_name().to_lowercase(), self.variables.len());
+        self.variables.push(VariableReferences {
+            name: variable.get_qualified_name().to_string(),
+            kind,
+            data_type: variable.get_type_name().to_string(),
+            address,
+            accesses: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn visit_with(&mut self, node: &AstNode, access: Access) {
+        let outer = std::mem::replace(&mut self.access, access);
+        self.visit(node);
+        self.access = outer;
+    }
+
+    fn record(&mut self, qualified_name: &str, location: &SourceLocation) {
+        if is_generated(location) {
+            return;
+        }
+        let name = qualified_name.to_lowercase();
+        let site = AccessSite {
+            access: self.access,
+            pou: self.pou.clone(),
+            file: location.get_file_name().map(str::to_string),
+            line: location.get_line_plus_one(),
+            column: location.get_column() + 1,
+            via: None,
+        };
+        let Some(&variable) = self.lookup.get(&name) else {
+            return;
+        };
+        if let Some(&global) = self.aliases.get(&name).and_then(|it| self.lookup.get(it)) {
+            let via = Some(self.variables[variable].name.clone());
+            self.variables[global].accesses.push(AccessSite { via, ..site.clone() });
+        }
+        self.variables[variable].accesses.push(site);
+    }
+
+    /// Returns the kind of the parameter the given call argument is passed to
+    fn get_parameter_type(&self, argument: &AstNode) -> Option<VariableType> {
+        let Some(StatementAnnotation::Argument { position, pou, .. }) = self.annotations.get_hint(argument)
+        else {
+            return None;
+        };
+        self.index.get_declared_parameter(pou, *position as u32).map(VariableIndexEntry::get_variable_type)
+    }
+}
+
+impl<T: AnnotationMap> AstVisitor for ReferenceCollector<'_, T> {
+    fn visit_assignment(&mut self, stmt: &Assignment, _node: &AstNode) {
+        self.visit_with(&stmt.left, Access::Write);
+        self.visit_with(&stmt.right, Access::Read);
+    }
+
+    fn visit_output_assignment(&mut self, stmt: &Assignment, _node: &AstNode) {
+        self.visit_with(&stmt.left, Access::Read);
+        self.visit_with(&stmt.right, Access::Write);
+    }
+
+    fn visit_ref_assignment(&mut self, stmt: &Assignment, _node: &AstNode) {
+        self.visit_with(&stmt.left, Access::Write);
+        self.visit_with(&stmt.right, Access::AddressOf);
+    }
+
+    fn visit_for_loop_statement(&mut self, stmt: &ForLoopStatement, _node: &AstNode) {
+        self.visit_with(&stmt.counter, Access::Write);
+        self.visit_with(&stmt.start, Access::Read);
+        self.visit_with(&stmt.end, Access::Read);
+        stmt.by_step.iter().for_each(|it| self.visit_with(it, Access::Read));
+        self.visit_statement_list(&stmt.body);
+    }
+
+    fn visit_call_statement(&mut self, stmt: &CallStatement, _node: &AstNode) {
+        self.visit_with(&stmt.operator, Access::Read);
+        let takes_address = matches!(
+            self.annotations.get(&stmt.operator),
+            Some(StatementAnnotation::Function { qualified_name, .. })
+                if qualified_name.eq_ignore_ascii_case("ADR") || qualified_name.eq_ignore_ascii_case("REF")
+        );
+
+        for argument in stmt.parameters.as_deref().map(flatten_expression_list).unwrap_or_default() {
+            let in_out = self.get_parameter_type(argument) == Some(VariableType::InOut);
+            let access = match () {
+                _ if takes_address => Access::AddressOf,
+                _ if in_out => Access::InOut,
+                _ => Access::Read,
+            };
+            match argument.get_stmt() {
+                AstStatement::Assignment(Assignment { left, right }) => {
+                    self.visit_with(left, Access::Write);
+                    self.visit_with(right, access);
+                }
+                AstStatement::OutputAssignment(Assignment { left, right }) => {
+                    self.visit_with(left, Access::Read);
+                    self.visit_with(right, Access::Write);
+                }
+                _ => self.visit_with(argument, access),
+            }
+        }
+    }
+
+    fn visit_reference_expr(&mut self, stmt: &ReferenceExpr, node: &AstNode) {
+        if let Some(StatementAnnotation::Variable { qualified_name, .. }) = self.annotations.get(node) {
+            self.record(qualified_name, &node.location);
+        }
+
+        match &stmt.access {
+            // the member itself is the accessed variable recorded above, writing a member also writes
+            // into the variable it is a member of
+            ReferenceAccess::Member(_) | ReferenceAccess::Global(_) => {
+                if let Some(base) = &stmt.base {
+                    self.visit(base);
+                }
+            }
+            ReferenceAccess::Index(index) => {
+                if let Some(base) = &stmt.base {
+                    self.visit(base);
+                }
+                self.visit_with(index, Access::Read);
+            }
+            // writing through a pointer does not write the pointer
+            ReferenceAccess::Deref => {
+                if let Some(base) = &stmt.base {
+                    self.visit_with(base, Access::Read);
+                }
+            }
+            ReferenceAccess::Address => {
+                if let Some(base) = &stmt.base {
+                    self.visit_with(base, Access::AddressOf);
+                }
+            }
+            ReferenceAccess::Cast(target) => {
+                stmt.base.iter().for_each(|it| self.visit(it));
+                self.visit_with(target, Access::Read);
+            }
+        }
+    }
+}
+
+/// Returns true for locations of code the compiler generated rather than the user wrote
+fn is_generated(location: &SourceLocation) -> bool {
+    location.is_internal() || location.is_undefined()
+}
+
+#[cfg(test)]
+mod tests {
+    use plc_ast::provider::IdProvider;
+
+    use super::{collect_cross_references, Access, CrossReferences, VariableKind};
+    use crate::test_utils::tests::{annotate_with_ids, index_with_ids};
+
+    fn xref(src: &str) -> CrossReferences {
+        let id_provider = IdProvider::default();
+        let (unit, mut index) = index_with_ids(src, id_provider.clone());
+        let annotations = annotate_with_ids(&unit, &mut index, id_provider);
+        collect_cross_references([&unit], &index, &annotations).unwrap()
+    }
+
+    /// Returns the (access, pou, line) triples recorded for the given variable
+    fn accesses(xref: &CrossReferences, name: &str) -> Vec<(Access, String, usize)> {
+        xref.variables
+            .iter()
+            .find(|it| it.name == name)
+            .unwrap_or_else(|| panic!("{name} is not part of the report"))
+            .accesses
+            .iter()
+            .map(|it| (it.access, it.pou.clone(), it.line))
+            .collect()
+    }
+
+    #[test]
+    fn reads_and_writes_of_globals_are_reported() {
+        let xref = xref(
+            "
+        VAR_GLOBAL
+            g : INT;
+        END_VAR
+        PROGRAM prg
+        VAR
+            x : INT;
+        END_VAR
+            g := x;
+            x := g + 1;
+        END_PROGRAM",
+        );
+
+        let g = xref.variables.iter().find(|it| it.name == "g").unwrap();
+        assert_eq!(g.kind, VariableKind::Global);
+        assert_eq!(g.data_type, "INT");
+        assert_eq!(
+            accesses(&xref, "g"),
+            vec![(Access::Write, "prg".into(), 9), (Access::Read, "prg".into(), 10)]
+        );
+        assert_eq!(
+            accesses(&xref, "prg.x"),
+            vec![(Access::Read, "prg".into(), 9), (Access::Write, "prg".into(), 10)]
+        );
+    }
+
+    #[test]
+    fn function_block_members_are_reported_for_calls_and_qualified_accesses() {
+        let xref = xref(
+            "
+        FUNCTION_BLOCK fb
+        VAR_INPUT
+            i : INT;
+        END_VAR
+        VAR_OUTPUT
+            o : INT;
+        END_VAR
+            o := i;
+        END_FUNCTION_BLOCK
+        PROGRAM prg
+        VAR
+            inst : fb;
+            x : INT;
+        END_VAR
+            inst(i := x, o => x);
+            x := inst.o;
+        END_PROGRAM",
+        );
+
+        assert_eq!(
+            accesses(&xref, "fb.i"),
+            vec![(Access::Read, "fb".into(), 9), (Access::Write, "prg".into(), 16)]
+        );
+        assert_eq!(
+            accesses(&xref, "fb.o"),
+            vec![
+                (Access::Write, "fb".into(), 9),
+                (Access::Read, "prg".into(), 16),
+                (Access::Read, "prg".into(), 17)
+            ]
+        );
+        assert_eq!(
+            accesses(&xref, "prg.x"),
+            vec![
+                (Access::Read, "prg".into(), 16),
+                (Access::Write, "prg".into(), 16),
+                (Access::Write, "prg".into(), 17)
+            ]
+        );
+        let member = xref.variables.iter().find(|it| it.name == "fb.o").unwrap();
+        assert_eq!(member.kind, VariableKind::FunctionBlockMember);
+    }
+
+    #[test]
+    fn in_out_arguments_and_address_of_are_reported() {
+        let xref = xref(
+            "
+        FUNCTION inc : INT
+        VAR_IN_OUT
+            v : INT;
+        END_VAR
+            v := v + 1;
+        END_FUNCTION
+        PROGRAM prg
+        VAR
+            x : INT;
+            p : REF_TO INT;
+        END_VAR
+            inc(x);
+            p := REF(x);
+            p REF= x;
+            p^ := 1;
+        END_PROGRAM",
+        );
+
+        assert_eq!(
+            accesses(&xref, "prg.x"),
+            vec![
+                (Access::InOut, "prg".into(), 13),
+                (Access::AddressOf, "prg".into(), 14),
+                (Access::AddressOf, "prg".into(), 15)
+            ]
+        );
+        assert_eq!(
+            accesses(&xref, "prg.p"),
+            vec![
+                (Access::Write, "prg".into(), 14),
+                (Access::Write, "prg".into(), 15),
+                (Access::Read, "prg".into(), 16)
+            ]
+        );
+    }
+
+    #[test]
+    fn accesses_through_hardware_aliases_are_reported_for_the_bound_address() {
+        let xref = xref(
+            "
+        PROGRAM prg
+        VAR
+            button AT %IX1.2 : BOOL;
+            lamp AT %QX1.3 : BOOL;
+        END_VAR
+            lamp := button;
+        END_PROGRAM",
+        );
+
+        let button = xref.variables.iter().find(|it| it.name == "prg.button").unwrap();
+        assert_eq!(button.address.as_deref(), Some("%IX1.2"));
+        assert_eq!(accesses(&xref, "prg.button"), vec![(Access::Read, "prg".into(), 7)]);
+
+        let input = xref.variables.iter().find(|it| it.name == "__PI_1_2").unwrap();
+        assert_eq!(input.accesses.len(), 1);
+        assert_eq!(input.accesses[0].access, Access::Read);
+        assert_eq!(input.accesses[0].via.as_deref(), Some("prg.button"));
+
+        let output = xref.variables.iter().find(|it| it.name == "__PI_1_3").unwrap();
+        assert_eq!(output.accesses[0].access, Access::Write);
+        assert_eq!(output.accesses[0].via.as_deref(), Some("prg.lamp"));
+    }
+}
@@ -114,6 +114,377 @@ fn hwmap_full_pass_json() {
     assert!(content.contains("\"%IX7.8\""), "expected reconstructed source address in: {content}");
 }
 
+#[test]
+fn symbols_file_lists_members_of_instances() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("symbols.st");
+    fs::write(
+        &source,
+        "
+        FUNCTION_BLOCK base
+        VAR
+            id : DINT;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK motor EXTENDS base
+        VAR_INPUT
+            speed : INT;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        {attribute 'symbol' := 'read'}
+        PROGRAM main
+        VAR
+            drive : motor;
+            {attribute 'symbol' := 'none'}
+            scratch : INT;
+        END_VAR
+        VAR RETAIN
+            cycles : DINT;
+        END_VAR
+        END_PROGRAM",
+    )
+    .unwrap();
+    let symbols_path = dir.path().join("symbols.json");
+    let symbols_arg = format!("--symbols-file={}", symbols_path.display());
+
+    compile(&["plc", &source.to_string_lossy(), "--check", &symbols_arg]).unwrap();
+
+    let content = fs::read_to_string(&symbols_path).unwrap();
+    let symbols: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let symbols = symbols["symbols"].as_array().unwrap();
+    let find = |name: &str| symbols.iter().find(|it| it["name"] == name);
+
+    let speed = find("main.drive.speed").unwrap();
+    assert_eq!(speed["symbol"], "main_instance");
+    assert_eq!(speed["direction"], "Input");
+    assert_eq!(speed["access"], "Read");
+    assert_eq!(speed["size"], 2);
+    assert_eq!(find("main.drive.id").unwrap()["symbol"], "main_instance");
+    assert!(find("main.scratch").is_none());
+    assert!(symbols.iter().all(|it| !it["name"].as_str().unwrap().contains("__")));
+
+    // retained program variables are moved to a global of their own
+    let cycles = find("main.cycles").unwrap();
+    assert_eq!(cycles["symbol"], "__main_cycles__retain");
+    assert_eq!(cycles["section"], ".retain");
+    assert_eq!(cycles["offset"], 0);
+    assert_eq!(cycles["retain"], true);
+}
+
+#[test]
+fn xref_report_lists_accesses_of_variables() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("xref.st");
+    fs::write(
+        &source,
+        "
+        VAR_GLOBAL
+            counter : INT;
+        END_VAR
+        PROGRAM prg
+        VAR
+            start AT %IX1.0 : BOOL;
+        END_VAR
+            IF start THEN
+                counter := counter + 1;
+            END_IF
+        END_PROGRAM",
+    )
+    .unwrap();
+    let xref_path = dir.path().join("xref.json");
+    let xref_arg = format!("xref={}", xref_path.display());
+
+    compile(&["plc", &source.to_string_lossy(), "--check", "--emit", &xref_arg]).unwrap();
+
+    let content = fs::read_to_string(&xref_path).unwrap();
+    let xref: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let variables = xref["variables"].as_array().unwrap();
+    let find = |name: &str| variables.iter().find(|it| it["name"] == name).unwrap();
+
+    let counter = &find("counter")["accesses"];
+    assert_eq!(counter[0]["access"], "Write");
+    assert_eq!(counter[0]["pou"], "prg");
+    assert_eq!(counter[0]["line"], 10);
+    assert_eq!(counter[1]["access"], "Read");
+
+    let start = find("prg.start");
+    assert_eq!(start["address"], "%IX1.0");
+    assert_eq!(start["accesses"][0]["access"], "Read");
+    assert_eq!(find("__PI_1_0")["accesses"][0]["via"], "prg.start");
+}
+
+#[test]
+fn call_graph_is_emitted_and_unreachable_pous_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("calls.st");
+    fs::write(
+        &source,
+        "
+        FUNCTION used : INT
+        END_FUNCTION
+
+        FUNCTION unused : INT
+        END_FUNCTION
+
+        PROGRAM main
+            used();
+        END_PROGRAM",
+    )
+    .unwrap();
+    let source = source.to_string_lossy().to_string();
+    let graph_path = dir.path().join("calls.json");
+    let graph_arg = format!("callgraph={}", graph_path.display());
+
+    // unreachable POUs are not reported by default
+    compile(&["plc", &source, "--check", "--emit", &graph_arg]).unwrap();
+
+    let graph: serde_json::Value = serde_json::from_str(&fs::read_to_string(&graph_path).unwrap()).unwrap();
+    assert_eq!(graph["edges"][0]["caller"], "main");
+    assert_eq!(graph["edges"][0]["callee"], "used");
+    assert_eq!(graph["edges"][0]["kind"], "Call");
+    assert_eq!(graph["edges"].as_array().unwrap().len(), 1);
+
+    let config = dir.path().join("config.json");
+    fs::write(&config, r#"{ "error": ["E153"] }"#).unwrap();
+    let config = config.to_string_lossy().to_string();
+    assert!(compile(&["plc", &source, "--check", "--error-config", &config]).is_err());
+}
+
+#[test]
+fn stack_usage_of_programs_is_reported_and_checked_against_the_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("stack.st");
+    fs::write(
+        &source,
+        "
+        FUNCTION leaf : DINT
+        VAR buffer : ARRAY[0..63] OF DINT; END_VAR
+            leaf := buffer[0];
+        END_FUNCTION
+
+        FUNCTION fact : DINT
+        VAR_INPUT n : DINT; END_VAR
+            IF n > 1 THEN
+                fact := n * fact(n - 1);
+            END_IF
+        END_FUNCTION
+
+        PROGRAM main
+            leaf();
+        END_PROGRAM
+
+        PROGRAM recursive
+            fact(5);
+        END_PROGRAM",
+    )
+    .unwrap();
+    let source = source.to_string_lossy().to_string();
+    let output = dir.path().join("stack.o").to_string_lossy().to_string();
+    let report = dir.path().join("stack.json");
+    let report_arg = report.to_string_lossy().to_string();
+
+    compile(&["plc", &source, "-c", "-o", &output, "--stack-usage", &report_arg]).unwrap();
+
+    let usage: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    let main = &usage["programs"][0];
+    assert_eq!(main["name"], "main");
+    assert_eq!(main["path"], serde_json::json!(["main", "leaf"]));
+    // the local array alone takes 256 bytes
+    assert!(main["stack_size"].as_u64().unwrap() >= 256);
+    let recursive = &usage["programs"][1];
+    assert_eq!(recursive["stack_size"], serde_json::Value::Null);
+    assert_eq!(recursive["recursion"], serde_json::json!(["fact", "fact"]));
+
+    let table = dir.path().join("stack.txt").to_string_lossy().to_string();
+    assert!(compile(&[
+        "plc",
+        &source,
+        "-c",
+        "-o",
+        &output,
+        "--stack-usage",
+        &table,
+        "--stack-limit",
+        "100000"
+    ])
+    .is_err());
+    assert!(fs::read_to_string(&table).unwrap().contains("unbounded"));
+}
+
+#[test]
+fn metrics_of_pous_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("metrics.st");
+    fs::write(
+        &source,
+        "
+        FUNCTION_BLOCK counter
+        VAR_INPUT enable : BOOL; END_VAR
+        VAR count : DINT; limit : LINT; END_VAR
+            IF enable AND count < limit THEN
+                count := count + 1;
+            END_IF
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR c : counter; END_VAR
+            c(enable := TRUE);
+        END_PROGRAM",
+    )
+    .unwrap();
+    let source = source.to_string_lossy().to_string();
+    let report = dir.path().join("metrics.json").to_string_lossy().to_string();
+
+    compile(&["plc", "metrics", &source, "--format", "json", "-o", &report]).unwrap();
+
+    let metrics: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    let counter = &metrics[0];
+    assert_eq!(counter["name"], "counter");
+    assert_eq!(counter["statements"], 2);
+    assert_eq!(counter["cyclomatic_complexity"], 2);
+    assert_eq!(counter["parameters"], 1);
+    assert_eq!(counter["fan_in"], 1);
+    // at least enable, count and limit with padding
+    let counter_size = counter["instance_size"].as_u64().unwrap();
+    assert!(counter_size >= 16);
+    let main = &metrics[1];
+    assert_eq!(main["fan_out"], 1);
+    assert_eq!(main["instance_size"], counter_size);
+
+    let csv = dir.path().join("metrics.csv").to_string_lossy().to_string();
+    compile(&["plc", "metrics", &source, "--format", "csv", "-o", &csv]).unwrap();
+    assert!(fs::read_to_string(&csv).unwrap().starts_with("name,kind,file,line,lines_of_code"));
+}
+
+#[test]
+fn fix_applies_suggestions_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("fix.st");
+    fs::write(
+        &source,
+        "
+FUNCTION foo : DINT
+END_FUNCTION
+
+PROGRAM main
+VAR x : DINT; END_VAR
+    IF x > 0 THEN
+        x := fooo();
+END_PROGRAM
+",
+    )
+    .unwrap();
+    let path = source.to_string_lossy().to_string();
+
+    // the missing END_IF is a parse error, the misspelled call is only reported once it parses
+    compile(&["plc", "fix", &path]).unwrap();
+    compile(&["plc", "fix", &path]).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&source).unwrap(),
+        "
+FUNCTION foo : DINT
+END_FUNCTION
+
+PROGRAM main
+VAR x : DINT; END_VAR
+    IF x > 0 THEN
+        x := foo();
+END_IF
+END_PROGRAM
+"
+    );
+    compile(&["plc", &path, "--check"]).unwrap();
+}
+
+#[test]
+fn doc_writes_a_page_per_documented_item() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("doc.st");
+    fs::write(
+        &source,
+        "
+        /// A counter
+        FUNCTION_BLOCK counter
+        VAR_INPUT
+            (** counts while set *)
+            enable : BOOL;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR c : counter; END_VAR
+        END_PROGRAM",
+    )
+    .unwrap();
+    let source = source.to_string_lossy().to_string();
+    let output = dir.path().join("api");
+
+    compile(&["plc", "doc", &source, "--format", "html", "-o", &output.to_string_lossy()]).unwrap();
+
+    let index = fs::read_to_string(output.join("index.html")).unwrap();
+    assert!(index.contains("<a href=\"function_block.counter.html\"><code>counter</code></a>"));
+    assert!(index.contains("<td>A counter</td>"));
+    let counter = fs::read_to_string(output.join("function_block.counter.html")).unwrap();
+    assert!(counter.contains(
+        "<td><code>enable</code></td><td>VAR_INPUT</td><td><code>BOOL</code></td><td>counts while set</td>"
+    ));
+    let main = fs::read_to_string(output.join("program.main.html")).unwrap();
+    assert!(main.contains("<td><a href=\"function_block.counter.html\"><code>counter</code></a></td>"));
+}
+
+#[test]
+fn generate_opcua_writes_node_set_of_the_project() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("machine.st"),
+        "
+        FUNCTION_BLOCK motor
+        VAR_INPUT
+            speed : INT;
+        END_VAR
+        END_FUNCTION_BLOCK
+
+        PROGRAM main
+        VAR drive : motor; END_VAR
+        END_PROGRAM",
+    )
+    .unwrap();
+    let build_config = dir.path().join("plc.json");
+    fs::write(
+        &build_config,
+        r#"{ "name": "machine", "files": ["machine.st"], "compile_type": "Shared", "output": "machine.so" }"#,
+    )
+    .unwrap();
+    let options = dir.path().join("opcua.toml");
+    fs::write(&options, "namespace_uri = \"urn:example:machine\"").unwrap();
+    let output = dir.path().join("machine.NodeSet2.xml");
+
+    compile(&[
+        "plc",
+        "generate",
+        &build_config.to_string_lossy(),
+        "opcua",
+        "--opcua-config",
+        &options.to_string_lossy(),
+        "-o",
+        &output.to_string_lossy(),
+    ])
+    .unwrap();
+
+    let node_set = fs::read_to_string(&output).unwrap();
+    assert!(node_set.contains("<Uri>urn:example:machine</Uri>"));
+    assert!(node_set.contains(r#"<UAObjectType NodeId="ns=1;s=type:motor" BrowseName="1:motor">"#));
+    assert!(node_set.contains(
+        r#"<UAObject NodeId="ns=1;s=main.drive" BrowseName="1:drive" ParentNodeId="ns=1;s=main">"#
+    ));
+    assert!(node_set.contains(r#"<UAVariable NodeId="ns=1;s=main.drive.speed""#));
+    // the virtual table of the function block is generated by the compiler
+    assert!(!node_set.contains("__vtable"));
+}
+
 #[test]
 fn hardware_conf_full_pass_toml() {
     let file = get_test_file("io.st");
@@ -209,6 +580,39 @@ fn relative_output_with_build_location_lands_in_cwd_for_non_build() {
     );
 }
 
+#[test]
+fn build_cache_reuses_results_of_unchanged_sources() {
+    let func = get_test_file("multi/func.st");
+    let prog = get_test_file("multi/prog.st");
+    let build_dir = tempfile::tempdir().unwrap();
+    let build_dir_str = build_dir.path().to_string_lossy().to_string();
+    let output_dir = tempfile::tempdir().unwrap();
+    let output_file = output_dir.path().join("multi.ll");
+    let output_file_str = output_file.to_string_lossy().to_string();
+    let compile_to_ir = |args: &[&str]| {
+        let mut parameters = vec!["plc", &func, &prog, "--build-location", &build_dir_str];
+        parameters.extend(["--ir", "--fno-ident", "-o", &output_file_str]);
+        parameters.extend(args);
+        compile(&parameters).unwrap();
+        fs::read_to_string(&output_file).unwrap()
+    };
+
+    // the first build fills the cache
+    let uncached = compile_to_ir(&[]);
+    let cache = build_dir.path().join(".cache");
+    for entries in ["units", "index", "objects"] {
+        assert!(contains_file_recursive(&cache.join(entries)), "expected cached {entries}");
+    }
+
+    // the second build reuses it and produces the same result
+    assert_eq!(compile_to_ir(&[]), uncached);
+
+    // the cache is neither read nor written if it is disabled
+    fs::remove_dir_all(&cache).unwrap();
+    assert_eq!(compile_to_ir(&["--no-build-cache"]), uncached);
+    assert!(!cache.exists());
+}
+
 #[test]
 #[cfg_attr(target_os = "windows", ignore = "linker is not available for windows")]
 #[cfg_attr(target_os = "macos", ignore)]
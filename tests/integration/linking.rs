@@ -283,6 +283,8 @@ fn link_with_library_path() {
         got_layout: Default::default(),
         compile_dirs: Default::default(),
         libraries: pipeline.project.get_libraries().to_vec(),
+        cache: None,
+        object_keys: Default::default(),
     };
     pipeline.register_participant(Box::new(codegen_participant));
     pipeline.run().unwrap();